@@ -0,0 +1,187 @@
+use crate::context::Context;
+use crate::segment::Segment;
+
+/// Symbols that carry meaning, with the words a screen reader should read instead
+const SYMBOL_WORDS: &[(&str, &str)] = &[
+    ("❯", "prompt"),
+    ("➜", "prompt"),
+    ("❮", "normal mode"),
+    ("✖", "error"),
+    ("✘", "error"),
+    ("✔", "ok"),
+    ("✓", "ok"),
+    ("⚠", "warning"),
+    ("⇡", "ahead "),
+    ("⇣", "behind "),
+    ("⇕", "diverged "),
+    ("🔒", "read-only"),
+    ("⏳", "pending"),
+    ("…", "and more"),
+];
+
+/// Rewrites the segments of a module for screen readers
+///
+/// Symbols are replaced with words, decorative glyphs and fills are dropped and the output
+/// of the module ends with a punctuation pause instead of a plain space.
+pub fn describe_module(context: &Context, name: &str, segments: Vec<Segment>) -> Vec<Segment> {
+    let mut described: Vec<Segment> = segments
+        .into_iter()
+        .filter_map(|mut segment| match segment {
+            Segment::Text(_) => {
+                let text = describe_text(segment.value());
+                segment.set_value(text);
+                Some(segment)
+            }
+            // Fills only line up the prompt visually
+            Segment::Fill(_) => None,
+            Segment::LineTerm => Some(segment),
+        })
+        .collect();
+
+    // The character module signals the status of the last command only through its color
+    if name == "character" {
+        let status = context.properties.status_code.as_deref().unwrap_or("0");
+        if status != "0" {
+            described.splice(0..0, Segment::from_text(None, "error "));
+        }
+    }
+
+    let Some(last) = described
+        .iter()
+        .rposition(|segment| !segment.value().trim().is_empty())
+    else {
+        // Nothing but decorations, only keep the line breaks
+        described.retain(|segment| matches!(segment, Segment::LineTerm));
+        return described;
+    };
+
+    let pause = if name == "character" { ": " } else { ", " };
+    let text = format!("{}{}", described[last].value().trim_end(), pause);
+    described[last].set_value(text);
+
+    // The pause replaces the spacing after the last word
+    let mut idx = 0;
+    described.retain(|segment| {
+        idx += 1;
+        idx <= last + 1 || !matches!(segment, Segment::Text(_))
+    });
+    described
+}
+
+/// Replaces symbols in `text` with words and strips decorative glyphs
+fn describe_text(text: &str) -> String {
+    let mut described = String::with_capacity(text.len());
+    let mut rest = text;
+    'outer: while let Some(c) = rest.chars().next() {
+        for (symbol, word) in SYMBOL_WORDS {
+            if let Some(after) = rest.strip_prefix(symbol) {
+                if !described.is_empty() && !described.ends_with(' ') {
+                    described.push(' ');
+                }
+                described.push_str(word);
+                rest = after;
+                continue 'outer;
+            }
+        }
+        if !is_decorative(c) {
+            described.push(c);
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+
+    // Removing glyphs leaves runs of spaces behind, e.g. between a symbol and its text
+    let words = described.split_whitespace().collect::<Vec<_>>().join(" ");
+    if words.is_empty() {
+        // Keep plain spacing, e.g. between modules, but not what was left of decorations
+        return if text.trim().is_empty() {
+            text.to_string()
+        } else {
+            words
+        };
+    }
+
+    let leading = if text.starts_with(' ') { " " } else { "" };
+    let trailing = if text.ends_with(' ') { " " } else { "" };
+    format!("{leading}{words}{trailing}")
+}
+
+/// Glyphs that are only decoration: Nerd Font icons, emoji, pictographs and their modifiers
+fn is_decorative(c: char) -> bool {
+    matches!(c,
+        // Private use areas, where Nerd Fonts and Powerline put their icons
+        '\u{E000}'..='\u{F8FF}'
+        | '\u{F0000}'..='\u{10FFFF}'
+        // Miscellaneous symbols, dingbats and box drawing
+        | '\u{2500}'..='\u{27BF}'
+        // Emoji and pictographs
+        | '\u{1F000}'..='\u{1FAFF}'
+        // Variation selectors and joiners that only belong to the glyphs above
+        | '\u{FE00}'..='\u{FE0F}'
+        | '\u{200D}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::default_context;
+    use nu_ansi_term::Color;
+
+    #[test]
+    fn symbols_become_words() {
+        assert_eq!(describe_text("⇡1⇣2 "), "ahead 1 behind 2 ");
+        assert_eq!(describe_text("✘ "), "error ");
+    }
+
+    #[test]
+    fn decorative_glyphs_are_stripped() {
+        assert_eq!(describe_text("🦀 v1.70.0 "), "v1.70.0 ");
+        assert_eq!(describe_text("\u{e0a0} main"), "main");
+        assert_eq!(describe_text("☁️  us-east-1"), "us-east-1");
+        assert_eq!(describe_text("🦀"), "");
+    }
+
+    #[test]
+    fn module_ends_with_pause() {
+        let context = default_context();
+        let segments = [
+            Segment::from_text(None, "via "),
+            Segment::from_text(Some(Color::Red.bold().into()), "🦀 v1.70.0"),
+            Segment::from_text(None, " "),
+        ]
+        .concat();
+
+        let described = describe_module(&context, "rust", segments);
+        let text: String = described.iter().map(Segment::value).collect();
+
+        assert_eq!(text, "via v1.70.0, ");
+        assert_eq!(
+            described[1].style(),
+            Some(Color::Red.bold()),
+            "styles are kept"
+        );
+    }
+
+    #[test]
+    fn decorations_only_module_is_empty() {
+        let context = default_context();
+        let segments = vec![Segment::fill(None, "─"), Segment::LineTerm];
+
+        let described = describe_module(&context, "fill", segments);
+
+        assert_eq!(described.len(), 1);
+        assert!(matches!(described[0], Segment::LineTerm));
+    }
+
+    #[test]
+    fn failed_command_is_spelled_out() {
+        let mut context = default_context();
+        context.properties.status_code = Some("1".to_string());
+        let segments = Segment::from_text(Some(Color::Red.bold().into()), "❯ ");
+
+        let described = describe_module(&context, "character", segments);
+        let text: String = described.iter().map(Segment::value).collect();
+
+        assert_eq!(text, "error prompt: ");
+    }
+}
@@ -0,0 +1,248 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::alloc_stats;
+use crate::context::{Context, Properties, Target};
+use crate::print::{compute_modules, get_prompt};
+use crate::scenario::Scenario;
+
+/// Durations of repeated prompt renders, in milliseconds
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BenchSamples {
+    /// Rendering the whole prompt, from loading the config to the final string
+    pub total: Vec<f64>,
+    /// Computing each module on its own, for every run it was part of the prompt
+    pub modules: IndexMap<String, Vec<f64>>,
+    /// Heap allocations made while rendering the whole prompt, with the `alloc-stats` feature
+    #[serde(default)]
+    pub allocations: Vec<u64>,
+}
+
+pub struct BenchOptions {
+    pub runs: usize,
+    pub scenario: Option<PathBuf>,
+    pub compare: Option<PathBuf>,
+    pub json: bool,
+}
+
+/// Renders the prompt repeatedly and prints the median and 95th percentile durations
+///
+/// With `compare`, the prompt is also rendered with another configuration so the cost of
+/// a change, like enabling a module, can be seen side by side.
+pub fn bench(properties: Properties, options: BenchOptions) -> io::Result<()> {
+    let results = match &options.scenario {
+        Some(scenario) => bench_scenario(scenario, &options)?,
+        None => {
+            let mut results = vec![sample(&properties, None, options.runs)];
+            if let Some(compare) = &options.compare {
+                let config = load_config(compare)?;
+                results.push(sample(&properties, Some(&config), options.runs));
+            }
+            results
+        }
+    };
+
+    if options.json {
+        let json = serde_json::to_string(&results)
+            .map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+        println!("{json}");
+    } else {
+        print_results(&results, &options);
+    }
+    Ok(())
+}
+
+/// Benchmarks in a separate starship process set up for the scenario
+fn bench_scenario(scenario_path: &Path, options: &BenchOptions) -> io::Result<Vec<BenchSamples>> {
+    let scenario = Scenario::load(scenario_path)?.prepare(scenario_path)?;
+    let runs = options.runs.to_string();
+    let compare = match &options.compare {
+        // The scenario runs in its own directory
+        Some(compare) => Some(dunce::canonicalize(compare)?),
+        None => None,
+    };
+
+    let mut args = vec!["bench", "--json", "--runs", runs.as_str()];
+    let compare_arg = compare.as_ref().map(|compare| compare.to_string_lossy());
+    if let Some(compare) = &compare_arg {
+        args.extend(["--compare", compare.as_ref()]);
+    }
+
+    let output = scenario.run(&args)?;
+    serde_json::from_str(&output.stdout).map_err(|error| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("{error}\n{}", output.stderr.trim()),
+        )
+    })
+}
+
+fn load_config(path: &Path) -> io::Result<toml::Table> {
+    let contents = fs::read_to_string(path)?;
+    toml::from_str(&contents).map_err(|error| Error::new(ErrorKind::InvalidData, error))
+}
+
+fn sample(properties: &Properties, config: Option<&toml::Table>, runs: usize) -> BenchSamples {
+    let new_context = || {
+        let context = Context::new(properties.clone(), Target::Main);
+        match config {
+            Some(config) => context.set_config(config.clone()),
+            None => context,
+        }
+    };
+
+    let mut samples = BenchSamples::default();
+    for _ in 0..runs {
+        // Caches like the git repository live in the context, so every run starts from scratch
+        let allocations = alloc_stats::allocations();
+        let start = Instant::now();
+        get_prompt(new_context());
+        samples.total.push(millis(start.elapsed()));
+        if let (Some(before), Some(after)) = (allocations, alloc_stats::allocations()) {
+            samples.allocations.push(after - before);
+        }
+
+        let context = new_context();
+        for module in compute_modules(&context) {
+            samples
+                .modules
+                .entry(module.get_name().clone())
+                .or_default()
+                .push(millis(module.duration));
+        }
+    }
+    samples
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Returns the `p`th percentile of the samples using the nearest-rank method
+fn percentile(samples: &[f64], p: f64) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn print_results(results: &[BenchSamples], options: &BenchOptions) {
+    let Some(baseline) = results.first() else {
+        return;
+    };
+    let other = results.get(1);
+
+    let mut rows = vec![(
+        "total".to_string(),
+        Some(baseline.total.as_slice()),
+        other.map(|other| other.total.as_slice()),
+    )];
+    let mut names: Vec<&String> = baseline.modules.keys().collect();
+    if let Some(other) = other {
+        names.extend(
+            other
+                .modules
+                .keys()
+                .filter(|name| !baseline.modules.contains_key(*name)),
+        );
+    }
+    // Slowest modules first
+    let p95 = |samples: Option<&[f64]>| samples.map_or(0.0, |samples| percentile(samples, 95.0));
+    names.sort_by(|a, b| {
+        let a = p95(baseline.modules.get(*a).map(Vec::as_slice));
+        let b = p95(baseline.modules.get(*b).map(Vec::as_slice));
+        b.total_cmp(&a)
+    });
+    rows.extend(names.into_iter().map(|name| {
+        (
+            name.clone(),
+            baseline.modules.get(name).map(Vec::as_slice),
+            other.and_then(|other| other.modules.get(name).map(Vec::as_slice)),
+        )
+    }));
+
+    let name_width = rows
+        .iter()
+        .map(|(name, _, _)| name.len())
+        .max()
+        .unwrap_or(0);
+    let stats = |samples: Option<&[f64]>| match samples {
+        Some(samples) => format!(
+            "{:>9.2}ms {:>9.2}ms",
+            percentile(samples, 50.0),
+            percentile(samples, 95.0)
+        ),
+        None => format!("{:>11} {:>11}", "-", "-"),
+    };
+
+    println!("\n Rendered the prompt {} times\n", options.runs);
+    match &options.compare {
+        Some(compare) => println!(
+            " {:name_width$} {:>11} {:>11}   {:>11} {:>11}   ({})",
+            "",
+            "P50",
+            "P95",
+            "P50",
+            "P95",
+            compare.display()
+        ),
+        None => println!(" {:name_width$} {:>11} {:>11}", "", "P50", "P95"),
+    }
+    for (name, baseline, other) in rows {
+        if options.compare.is_some() {
+            println!(" {name:name_width$} {}   {}", stats(baseline), stats(other));
+        } else {
+            println!(" {name:name_width$} {}", stats(baseline));
+        }
+    }
+
+    if !baseline.allocations.is_empty() {
+        let median = |allocations: &[u64]| {
+            let allocations: Vec<f64> = allocations.iter().map(|&count| count as f64).collect();
+            percentile(&allocations, 50.0)
+        };
+        print!(
+            "\n Allocations per prompt (P50): {}",
+            median(&baseline.allocations)
+        );
+        match other {
+            Some(other) => println!(" and {}", median(&other.allocations)),
+            None => println!(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest_rank_percentile() {
+        let samples = [5.0, 1.0, 4.0, 2.0, 3.0];
+        assert_eq!(percentile(&samples, 50.0), 3.0);
+        assert_eq!(percentile(&samples, 95.0), 5.0);
+        assert_eq!(percentile(&samples, 0.0), 1.0);
+        assert_eq!(percentile(&[], 50.0), 0.0);
+    }
+
+    #[test]
+    fn samples_roundtrip_as_json() {
+        let samples = BenchSamples {
+            total: vec![1.5, 2.0],
+            modules: IndexMap::from([("directory".to_string(), vec![0.25, 0.5])]),
+            allocations: vec![1200, 1180],
+        };
+
+        let json = serde_json::to_string(&[&samples]).unwrap();
+        let parsed: Vec<BenchSamples> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed, vec![samples]);
+    }
+}
@@ -0,0 +1,364 @@
+use nu_ansi_term::{AnsiString, Color, Style as AnsiStyle};
+use std::path::PathBuf;
+
+use crate::config::parse_style_string;
+use crate::context::Context;
+
+/// How many colors the terminal can show
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorDepth {
+    Monochrome,
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Returns the color depth set with `color_depth`, or detects it from `COLORTERM` and the
+    /// terminfo entry of `TERM`
+    pub fn detect(context: &Context) -> Self {
+        match context.root_config.color_depth.as_str() {
+            "truecolor" => return Self::TrueColor,
+            "256" => return Self::Ansi256,
+            "16" => return Self::Ansi16,
+            "none" => return Self::Monochrome,
+            "" => {}
+            other => log::warn!("Unknown color_depth {other:?}, detecting it instead"),
+        }
+
+        let colorterm = context.get_env("COLORTERM");
+        if matches!(colorterm.as_deref(), Some("truecolor" | "24bit")) {
+            return Self::TrueColor;
+        }
+        // Without a terminfo entry there is nothing to go by, so the colors are left alone
+        context
+            .get_env("TERM")
+            .and_then(|term| terminfo_colors(context, &term))
+            .map_or(Self::TrueColor, Self::from_colors)
+    }
+
+    fn from_colors(colors: i32) -> Self {
+        match colors {
+            colors if colors >= 1 << 24 => Self::TrueColor,
+            colors if colors >= 256 => Self::Ansi256,
+            colors if colors >= 8 => Self::Ansi16,
+            _ => Self::Monochrome,
+        }
+    }
+}
+
+/// Converts the colors of the prompt to the nearest ones the terminal can show
+///
+/// Colors listed in `color_fallbacks` are replaced with the given style instead, whose
+/// attributes, like `bold`, are also what is left of them on monochrome terminals.
+pub fn degrade_strings<'a>(
+    context: &Context,
+    mut strings: Vec<AnsiString<'a>>,
+) -> Vec<AnsiString<'a>> {
+    let depth = ColorDepth::detect(context);
+    if depth == ColorDepth::TrueColor {
+        return strings;
+    }
+
+    let fallbacks: Vec<(Color, AnsiStyle)> = context
+        .root_config
+        .color_fallbacks
+        .iter()
+        .filter_map(|(color, fallback)| {
+            let color = parse_style_string(color, Some(context))?
+                .to_ansi_style(None)
+                .foreground?;
+            let fallback = parse_style_string(fallback, Some(context))?.to_ansi_style(None);
+            Some((color, fallback))
+        })
+        .collect();
+
+    for string in &mut strings {
+        degrade_style(string.style_ref_mut(), depth, &fallbacks);
+    }
+    strings
+}
+
+fn degrade_style(style: &mut AnsiStyle, depth: ColorDepth, fallbacks: &[(Color, AnsiStyle)]) {
+    let fallback = |color: Option<Color>| {
+        let color = color.filter(|&color| !fits(color, depth))?;
+        fallbacks
+            .iter()
+            .find(|(original, _)| *original == color)
+            .map(|(_, fallback)| *fallback)
+    };
+
+    let fg_fallback = fallback(style.foreground);
+    if let Some(fallback) = fg_fallback {
+        style.foreground = fallback.foreground;
+        style.is_bold |= fallback.is_bold;
+        style.is_dimmed |= fallback.is_dimmed;
+        style.is_italic |= fallback.is_italic;
+        style.is_underline |= fallback.is_underline;
+    }
+    if let Some(fallback) = fallback(style.background) {
+        style.background = fallback.foreground;
+    }
+
+    if depth == ColorDepth::Monochrome {
+        // Backgrounds, like those of powerline segments, still stand out in reverse video
+        if style.background.take().is_some() {
+            style.is_reverse = true;
+        }
+        style.foreground = None;
+        return;
+    }
+    style.foreground = style.foreground.map(|color| degrade_color(color, depth));
+    style.background = style.background.map(|color| degrade_color(color, depth));
+}
+
+/// Whether the terminal can show the color as it is
+fn fits(color: Color, depth: ColorDepth) -> bool {
+    match (color, depth) {
+        (_, ColorDepth::TrueColor) => true,
+        (_, ColorDepth::Monochrome) | (Color::Rgb(..), _) => false,
+        (Color::Fixed(n), ColorDepth::Ansi16) => n < 16,
+        _ => true,
+    }
+}
+
+fn degrade_color(color: Color, depth: ColorDepth) -> Color {
+    if fits(color, depth) {
+        return color;
+    }
+    let rgb = to_rgb(color);
+    match depth {
+        ColorDepth::Ansi256 => Color::Fixed(nearest_256(rgb)),
+        _ => ANSI_16
+            .iter()
+            .min_by_key(|(_, basic)| distance(rgb, *basic))
+            .map_or(Color::Default, |(basic, _)| *basic),
+    }
+}
+
+/// The 16 basic colors, with their default values in xterm
+const ANSI_16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Purple, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::White, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightPurple, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::LightGray, (255, 255, 255)),
+];
+
+/// The levels of each channel in the 6x6x6 color cube of the 256 colors
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The RGB value of a color, with the basic colors at their defaults in xterm
+pub fn to_rgb(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Fixed(n @ 0..=15) => ANSI_16[n as usize].1,
+        Color::Fixed(n @ 16..=231) => {
+            let n = n - 16;
+            let level = |i: u8| CUBE_LEVELS[i as usize];
+            (level(n / 36), level(n / 6 % 6), level(n % 6))
+        }
+        Color::Fixed(n) => {
+            let gray = 8 + 10 * (n - 232);
+            (gray, gray, gray)
+        }
+        Color::Magenta => ANSI_16[5].1,
+        Color::LightMagenta => ANSI_16[13].1,
+        Color::Default => (0, 0, 0),
+        color => ANSI_16
+            .iter()
+            .find(|(basic, _)| *basic == color)
+            .map_or((0, 0, 0), |(_, rgb)| *rgb),
+    }
+}
+
+/// Returns the nearest of the 256 colors out of the color cube and the grayscale ramp
+fn nearest_256(rgb: (u8, u8, u8)) -> u8 {
+    let level = |channel: u8| {
+        (0..6u8)
+            .min_by_key(|&i| CUBE_LEVELS[i as usize].abs_diff(channel))
+            .unwrap_or(0)
+    };
+    let cube = (level(rgb.0), level(rgb.1), level(rgb.2));
+    let cube_rgb = (
+        CUBE_LEVELS[cube.0 as usize],
+        CUBE_LEVELS[cube.1 as usize],
+        CUBE_LEVELS[cube.2 as usize],
+    );
+
+    let average = (u16::from(rgb.0) + u16::from(rgb.1) + u16::from(rgb.2)) / 3;
+    let gray_index = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray = 8 + 10 * gray_index;
+
+    if distance(rgb, (gray, gray, gray)) < distance(rgb, cube_rgb) {
+        232 + gray_index
+    } else {
+        16 + 36 * cube.0 + 6 * cube.1 + cube.2
+    }
+}
+
+/// The squared euclidean distance between two colors
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let channel = |a: u8, b: u8| u32::from(a.abs_diff(b)).pow(2);
+    channel(a.0, b.0) + channel(a.1, b.1) + channel(a.2, b.2)
+}
+
+/// Looks up the number of colors in the terminfo entry of `term`
+fn terminfo_colors(context: &Context, term: &str) -> Option<i32> {
+    let first = term.chars().next()?;
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    dirs.extend(context.get_env("TERMINFO").map(PathBuf::from));
+    dirs.extend(context.get_home().map(|home| home.join(".terminfo")));
+    if let Some(list) = context.get_env("TERMINFO_DIRS") {
+        dirs.extend(
+            list.split(':')
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from),
+        );
+    }
+    dirs.extend(
+        ["/etc/terminfo", "/lib/terminfo", "/usr/share/terminfo"]
+            .iter()
+            .map(PathBuf::from),
+    );
+
+    // Entries are grouped by their first letter, which macOS spells as a hex number
+    dirs.iter()
+        .flat_map(|dir| {
+            [
+                dir.join(first.to_string()).join(term),
+                dir.join(format!("{:x}", u32::from(first))).join(term),
+            ]
+        })
+        .find_map(|path| std::fs::read(path).ok())
+        .and_then(|entry| parse_terminfo_colors(&entry))
+}
+
+/// Reads the `colors` capability of a compiled terminfo entry, in the format of term(5)
+fn parse_terminfo_colors(entry: &[u8]) -> Option<i32> {
+    // `colors` is the 14th of the numeric capabilities
+    const COLORS: usize = 13;
+
+    let header = |i: usize| {
+        let bytes = entry.get(2 * i..2 * i + 2)?;
+        Some(i16::from_le_bytes([bytes[0], bytes[1]]))
+    };
+    let number_size = match header(0)? {
+        0o432 => 2,
+        // The extended format of ncurses 6.1 with 32-bit numbers
+        0o1036 => 4,
+        _ => return None,
+    };
+    let names_size = usize::try_from(header(1)?).ok()?;
+    let bools_count = usize::try_from(header(2)?).ok()?;
+    let numbers_count = usize::try_from(header(3)?).ok()?;
+    if numbers_count <= COLORS {
+        return None;
+    }
+
+    // The numbers are aligned to an even offset
+    let numbers_start = (12 + names_size + bools_count + 1) & !1;
+    let start = numbers_start + COLORS * number_size;
+    let value = entry.get(start..start + number_size)?;
+    match *value {
+        [a, b] => Some(i32::from(i16::from_le_bytes([a, b]))),
+        [a, b, c, d] => Some(i32::from_le_bytes([a, b, c, d])),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::default_context;
+
+    fn terminfo_entry(colors: i16) -> Vec<u8> {
+        let names = b"test|a test terminal\0";
+        let mut entry = Vec::new();
+        for header in [0o432, names.len() as i16, 3, 14, 0, 0] {
+            entry.extend(header.to_le_bytes());
+        }
+        entry.extend(names);
+        entry.extend([1, 0, 1]);
+        // Padding to an even offset
+        entry.push(0);
+        for number in 0..14 {
+            let value: i16 = if number == 13 { colors } else { -1 };
+            entry.extend(value.to_le_bytes());
+        }
+        entry
+    }
+
+    #[test]
+    fn terminfo_colors_capability() {
+        assert_eq!(parse_terminfo_colors(&terminfo_entry(256)), Some(256));
+        assert_eq!(parse_terminfo_colors(&terminfo_entry(-1)), Some(-1));
+        assert_eq!(parse_terminfo_colors(b"not terminfo"), None);
+    }
+
+    #[test]
+    fn detect_from_terminfo() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::create_dir_all(dir.path().join("x"))?;
+        std::fs::write(dir.path().join("x/xterm-16"), terminfo_entry(16))?;
+
+        let mut context = default_context();
+        context
+            .env
+            .insert("TERMINFO", dir.path().to_string_lossy().to_string());
+        context.env.insert("TERM", "xterm-16".to_string());
+        assert_eq!(ColorDepth::detect(&context), ColorDepth::Ansi16);
+
+        context.env.insert("COLORTERM", "truecolor".to_string());
+        assert_eq!(ColorDepth::detect(&context), ColorDepth::TrueColor);
+        dir.close()
+    }
+
+    #[test]
+    fn nearest_colors() {
+        assert_eq!(
+            degrade_color(Color::Rgb(255, 135, 0), ColorDepth::Ansi256),
+            Color::Fixed(208)
+        );
+        assert_eq!(
+            degrade_color(Color::Rgb(40, 40, 40), ColorDepth::Ansi256),
+            Color::Fixed(235)
+        );
+        assert_eq!(
+            degrade_color(Color::Rgb(250, 10, 10), ColorDepth::Ansi16),
+            Color::LightRed
+        );
+        assert_eq!(
+            degrade_color(Color::Fixed(28), ColorDepth::Ansi16),
+            Color::Green
+        );
+        assert_eq!(degrade_color(Color::Blue, ColorDepth::Ansi16), Color::Blue);
+    }
+
+    #[test]
+    fn monochrome_styles() {
+        let mut style = Color::Rgb(255, 0, 0).on(Color::Blue);
+        degrade_style(&mut style, ColorDepth::Monochrome, &[]);
+        assert_eq!(style, AnsiStyle::new().reverse());
+
+        let fallbacks = [(Color::Rgb(255, 0, 0), Color::Red.bold())];
+        let mut style = Color::Rgb(255, 0, 0).normal();
+        degrade_style(&mut style, ColorDepth::Monochrome, &fallbacks);
+        assert_eq!(style, AnsiStyle::new().bold());
+
+        let mut style = Color::Rgb(255, 0, 0).underline();
+        degrade_style(&mut style, ColorDepth::Ansi16, &fallbacks);
+        assert_eq!(style, Color::Red.bold().underline());
+    }
+}
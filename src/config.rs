@@ -2,6 +2,7 @@ use crate::configs::Palette;
 use crate::context::Context;
 
 use crate::serde_utils::{ValueDeserializer, ValueRef};
+use crate::shadow;
 use crate::utils;
 use nu_ansi_term::Color;
 use serde::{
@@ -12,6 +13,7 @@ use std::borrow::Cow;
 use std::clone::Clone;
 use std::collections::HashMap;
 use std::ffi::OsString;
+use std::fmt;
 use std::io::ErrorKind;
 
 use toml::Value;
@@ -115,6 +117,33 @@ where
     }
 }
 
+/// Adds the values of `defaults` that are missing from `table`, merging nested tables
+fn merge_defaults(table: &mut toml::Table, defaults: toml::Table) {
+    for (key, default) in defaults {
+        match (table.get_mut(&key), default) {
+            (Some(Value::Table(table)), Value::Table(defaults)) => merge_defaults(table, defaults),
+            (Some(_), _) => {}
+            (None, default) => {
+                table.insert(key, default);
+            }
+        }
+    }
+}
+
+/// Sets the values of `overrides` in `table`, merging nested tables
+fn merge_overrides(table: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (table.get_mut(&key), value) {
+            (Some(Value::Table(table)), Value::Table(overrides)) => {
+                merge_overrides(table, overrides)
+            }
+            (_, value) => {
+                table.insert(key, value);
+            }
+        }
+    }
+}
+
 /// Root config of starship.
 #[derive(Default)]
 pub struct StarshipConfig {
@@ -173,6 +202,37 @@ impl StarshipConfig {
         }
     }
 
+    /// Fills in the symbols of the bundled symbol table selected by `charset`, e.g. plain
+    /// text for terminals without Unicode support, for all options the user hasn't set.
+    pub fn apply_charset(&mut self, charset: &str) {
+        let preset = match charset {
+            "" => return,
+            "ascii" => "plain-text-symbols",
+            "unicode" => "no-nerd-font",
+            "nerd-font" => "nerd-font-symbols",
+            _ => {
+                log::warn!(
+                    "Unknown charset {charset:?}, expected 'ascii', 'unicode' or 'nerd-font'"
+                );
+                return;
+            }
+        };
+        let symbols = std::str::from_utf8(shadow::get_preset_content(preset))
+            .ok()
+            .and_then(|content| toml::from_str(content).ok());
+        match symbols {
+            Some(symbols) => {
+                merge_defaults(self.config.get_or_insert_with(Default::default), symbols)
+            }
+            None => log::error!("Unable to parse the symbols of charset {charset:?}"),
+        }
+    }
+
+    /// Applies the configuration file of the repository on top of the configuration of the user
+    pub fn apply_local_config(&mut self, local: toml::Table) {
+        merge_overrides(self.config.get_or_insert_with(Default::default), local);
+    }
+
     /// Get the subset of the table for a module by its name
     pub fn get_module_config(&self, module_name: &str) -> Option<&Value> {
         let module_config = self.get_config(&[module_name]);
@@ -282,6 +342,9 @@ pub struct Style {
     style: nu_ansi_term::Style,
     bg: Option<PrevColor>,
     fg: Option<PrevColor>,
+    /// Attributes `nu_ansi_term` has no support for
+    overline: bool,
+    double_underline: bool,
 }
 
 impl Style {
@@ -309,6 +372,34 @@ impl Style {
         current
     }
 
+    /// Wraps text in the SGR sequences of the attributes `nu_ansi_term` can't write, turning
+    /// each of them off again at the end of the text, so they don't leak into the next style
+    pub fn wrap_extra_attributes<'t>(&self, text: &'t str) -> Cow<'t, str> {
+        if !self.overline && !self.double_underline {
+            return Cow::Borrowed(text);
+        }
+        let mut wrapped = String::with_capacity(text.len() + 10);
+        if self.overline {
+            wrapped.push_str("\x1b[53m");
+        }
+        if self.double_underline {
+            wrapped.push_str("\x1b[21m");
+        }
+        wrapped.push_str(text);
+        if self.double_underline {
+            // Turning off underlines also ends a single underline of the style itself
+            wrapped.push_str(if self.style.is_underline {
+                "\x1b[24m\x1b[4m"
+            } else {
+                "\x1b[24m"
+            });
+        }
+        if self.overline {
+            wrapped.push_str("\x1b[55m");
+        }
+        Cow::Owned(wrapped)
+    }
+
     fn map_style<F>(&self, f: F) -> Self
     where
         F: FnOnce(&nu_ansi_term::Style) -> nu_ansi_term::Style,
@@ -352,6 +443,73 @@ impl From<nu_ansi_term::Color> for Style {
     }
 }
 
+/// Formats the style as a style string that `parse_style_string` reads back into the same style
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let style = &self.style;
+        let attributes = [
+            (style.is_bold, "bold"),
+            (style.is_dimmed, "dimmed"),
+            (style.is_italic, "italic"),
+            (style.is_underline, "underline"),
+            (style.is_blink, "blink"),
+            (style.is_reverse, "inverted"),
+            (style.is_hidden, "hidden"),
+            (style.is_strikethrough, "strikethrough"),
+            (self.overline, "overline"),
+            (self.double_underline, "double-underline"),
+        ];
+        let mut tokens: Vec<String> = attributes
+            .iter()
+            .filter(|(is_set, _)| *is_set)
+            .map(|(_, name)| (*name).to_string())
+            .collect();
+
+        let colors = [
+            ("fg", self.fg, style.foreground),
+            ("bg", self.bg, style.background),
+        ];
+        for (prefix, prev_color, color) in colors {
+            let color = match prev_color {
+                Some(PrevColor::Fg) => Some("prev_fg".to_string()),
+                Some(PrevColor::Bg) => Some("prev_bg".to_string()),
+                None => color.and_then(color_string),
+            };
+            if let Some(color) = color {
+                tokens.push(format!("{prefix}:{color}"));
+            }
+        }
+        f.write_str(&tokens.join(" "))
+    }
+}
+
+/// The color string `parse_color_string` reads as `color`
+fn color_string(color: Color) -> Option<String> {
+    let name = match color {
+        Color::Black => "black",
+        Color::Red => "red",
+        Color::Green => "green",
+        Color::Yellow => "yellow",
+        Color::Blue => "blue",
+        Color::Purple | Color::Magenta => "purple",
+        Color::Cyan => "cyan",
+        Color::White => "white",
+        Color::DarkGray => "bright-black",
+        Color::LightRed => "bright-red",
+        Color::LightGreen => "bright-green",
+        Color::LightYellow => "bright-yellow",
+        Color::LightBlue => "bright-blue",
+        Color::LightPurple | Color::LightMagenta => "bright-purple",
+        Color::LightCyan => "bright-cyan",
+        Color::LightGray => "bright-white",
+        Color::Fixed(number) => return Some(number.to_string()),
+        Color::Rgb(r, g, b) => return Some(format!("#{r:02x}{g:02x}{b:02x}")),
+        // The terminal's default color is what an unset color is drawn in
+        Color::Default => return None,
+    };
+    Some(name.to_string())
+}
+
 /** Parse a style string which represents an ansi style. Valid tokens in the style
  string include the following:
  - 'fg:<color>'    (specifies that the color read should be a foreground color)
@@ -361,16 +519,32 @@ impl From<nu_ansi_term::Color> for Style {
  - 'italic'
  - 'inverted'
  - 'blink'
+ - 'overline'
+ - 'double-underline'
  - '`prev_fg`'        (specifies the color should be the previous foreground color)
  - '`prev_bg`'        (specifies the color should be the previous background color)
+ - 'on'            (specifies that the next color should be a background color)
  - '<color>'       (see the `parse_color_string` doc for valid color strings)
 */
 pub fn parse_style_string(style_string: &str, context: Option<&Context>) -> Option<Style> {
+    let mut is_on = false;
     style_string
         .split_whitespace()
+        .map(str::to_lowercase)
+        .filter_map(|token| {
+            // `red on blue` colors the background blue
+            if token == "on" {
+                is_on = true;
+                return None;
+            }
+            let is_bg = std::mem::take(&mut is_on);
+            if is_bg && !token.starts_with("fg:") && !token.starts_with("bg:") {
+                Some(format!("bg:{token}"))
+            } else {
+                Some(token)
+            }
+        })
         .try_fold(Style::default(), |style, token| {
-            let token = token.to_lowercase();
-
             // Check for FG/BG identifiers and strip them off if appropriate
             // If col_fg is true, color the foreground. If it's false, color the background.
             let (token, col_fg) = if token.as_str().starts_with("fg:") {
@@ -390,12 +564,20 @@ pub fn parse_style_string(style_string: &str, context: Option<&Context>) -> Opti
                 "blink" => Some(style.map_style(nu_ansi_term::Style::blink)),
                 "hidden" => Some(style.map_style(nu_ansi_term::Style::hidden)),
                 "strikethrough" => Some(style.map_style(nu_ansi_term::Style::strikethrough)),
+                "overline" => Some(Style {
+                    overline: true,
+                    ..style
+                }),
+                "double-underline" => Some(Style {
+                    double_underline: true,
+                    ..style
+                }),
 
-                "prev_fg" if col_fg => Some(style.fg(PrevColor::Fg)),
-                "prev_fg" => Some(style.bg(PrevColor::Fg)),
+                "prev_fg" | "previous-fg" if col_fg => Some(style.fg(PrevColor::Fg)),
+                "prev_fg" | "previous-fg" => Some(style.bg(PrevColor::Fg)),
 
-                "prev_bg" if col_fg => Some(style.fg(PrevColor::Bg)),
-                "prev_bg" => Some(style.bg(PrevColor::Bg)),
+                "prev_bg" | "previous-bg" if col_fg => Some(style.fg(PrevColor::Bg)),
+                "prev_bg" | "previous-bg" => Some(style.bg(PrevColor::Bg)),
 
                 // When the string is supposed to be a color:
                 // Decide if we yield none, reset background or set color.
@@ -1066,6 +1248,115 @@ mod tests {
         assert!(get_palette(&palettes, None).is_none());
     }
 
+    #[test]
+    fn table_get_styles_on_background() {
+        let style = parse_style_string("bold red on blue", None).unwrap();
+        assert_eq!(style.to_ansi_style(None), Color::Red.bold().on(Color::Blue));
+
+        let style = parse_style_string("fg:red on previous-bg", None).unwrap();
+        let prev = Color::Yellow.on(Color::Green);
+        assert_eq!(
+            style.to_ansi_style(Some(&prev)),
+            Color::Red.on(Color::Green)
+        );
+
+        // `on` only applies to the token right after it
+        let style = parse_style_string("on blue green", None).unwrap();
+        assert_eq!(style.to_ansi_style(None), Color::Green.on(Color::Blue));
+    }
+
+    #[test]
+    fn extra_attributes_are_turned_off_after_the_text() {
+        let style = parse_style_string("overline underline double-underline", None).unwrap();
+        assert_eq!(style.to_ansi_style(None), AnsiStyle::new().underline());
+        assert_eq!(
+            style.wrap_extra_attributes("text"),
+            "\x1b[53m\x1b[21mtext\x1b[24m\x1b[4m\x1b[55m"
+        );
+
+        let style = parse_style_string("bold", None).unwrap();
+        assert_eq!(style.wrap_extra_attributes("text"), "text");
+    }
+
+    #[test]
+    fn style_to_string_round_trips() {
+        for style_string in [
+            "",
+            "bold italic fg:red",
+            "underline fg:bright-black bg:#1a2b3c",
+            "fg:prev_bg bg:prev_fg",
+            "inverted fg:214 bg:bright-white",
+            "bold overline double-underline fg:green",
+        ] {
+            let style = parse_style_string(style_string, None).unwrap();
+            assert_eq!(style.to_string(), style_string);
+            assert_eq!(parse_style_string(&style.to_string(), None), Some(style));
+        }
+    }
+
+    #[test]
+    fn charset_fills_in_unset_symbols() {
+        let mut config = StarshipConfig {
+            config: Some(toml::toml! {
+                [aws]
+                style = "bold yellow"
+                [azure]
+                symbol = "☁️ "
+            }),
+        };
+        config.apply_charset("ascii");
+
+        let symbol = |module: &str| {
+            config
+                .get_config(&[module, "symbol"])
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        };
+        assert_eq!(symbol("aws").as_deref(), Some("aws "));
+        assert_eq!(symbol("azure").as_deref(), Some("☁️ "));
+        assert_eq!(
+            config.get_config(&["aws", "style"]).and_then(Value::as_str),
+            Some("bold yellow")
+        );
+    }
+
+    #[test]
+    fn ascii_charset_covers_all_default_symbols() {
+        fn find_non_ascii(path: &str, value: &Value, found: &mut Vec<String>) {
+            match value {
+                Value::String(text) if !text.is_ascii() => found.push(format!("{path} = {text:?}")),
+                Value::Array(values) => values
+                    .iter()
+                    .for_each(|value| find_non_ascii(path, value, found)),
+                Value::Table(table) => {
+                    for (key, value) in table {
+                        // Files are detected by their actual names
+                        if !key.starts_with("detect_") {
+                            find_non_ascii(&format!("{path}.{key}"), value, found);
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let mut config = StarshipConfig::default();
+        config.apply_charset("ascii");
+        let symbols = Value::Table(config.config.unwrap_or_default());
+        let full = Value::try_from(crate::configs::FullConfig::load(&symbols)).unwrap();
+
+        let mut found = Vec::new();
+        for (module, value) in full.as_table().unwrap() {
+            if value.is_table() {
+                find_non_ascii(module, value, &mut found);
+            }
+        }
+        assert!(
+            found.is_empty(),
+            "not covered by `charset = 'ascii'`: {found:#?}"
+        );
+    }
+
     #[test]
     fn read_config_no_config_file_path_provided() {
         assert_eq!(
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct AnnotationsConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+    pub ttl: u64,
+    pub max_annotations: usize,
+    pub allowed_escape_sequences: Vec<&'a str>,
+}
+
+impl<'a> Default for AnnotationsConfig<'a> {
+    fn default() -> Self {
+        AnnotationsConfig {
+            format: "[$symbol$text]($style) ",
+            symbol: "📌 ",
+            style: "bold yellow",
+            disabled: false,
+            priority: None,
+            ttl: 300,
+            max_annotations: 3,
+            allowed_escape_sequences: Vec::default(),
+        }
+    }
+}
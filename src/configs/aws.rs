@@ -39,10 +39,15 @@ pub struct AwsConfig<'a> {
     pub style: &'a str,
     /// Disables the AWS module.
     pub disabled: bool,
+    /// The priority by which the module is shrunk when the prompt is too wide.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     /// Table of region aliases to display in addition to the AWS name.
     pub region_aliases: HashMap<String, &'a str>,
     /// Table of profile aliases to display in addition to the AWS name.
     pub profile_aliases: HashMap<String, &'a str>,
+    /// Path of a TOML file mapping AWS account IDs to a `name` and a `style`.
+    pub account_aliases_file: &'a str,
     /// The symbol displayed when the temporary credentials have expired.
     pub expiration_symbol: &'a str,
     /// If true displays info even if `credentials`, `credential_process` or `sso_start_url` have not been setup.
@@ -56,8 +61,10 @@ impl<'a> Default for AwsConfig<'a> {
             symbol: "☁️  ",
             style: "bold yellow",
             disabled: false,
+            priority: None,
             region_aliases: HashMap::new(),
             profile_aliases: HashMap::new(),
+            account_aliases_file: "",
             expiration_symbol: "X",
             force_display: false,
         }
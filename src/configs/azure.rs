@@ -13,7 +13,10 @@ pub struct AzureConfig<'a> {
     pub symbol: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub subscription_aliases: HashMap<String, &'a str>,
+    pub expiration_symbol: &'a str,
 }
 
 impl<'a> Default for AzureConfig<'a> {
@@ -23,7 +26,9 @@ impl<'a> Default for AzureConfig<'a> {
             symbol: "󰠅 ",
             style: "blue bold",
             disabled: true,
+            priority: None,
             subscription_aliases: HashMap::new(),
+            expiration_symbol: "X",
         }
     }
 }
@@ -16,7 +16,10 @@ pub struct BatteryConfig<'a> {
     #[serde(borrow)]
     pub display: Vec<BatteryDisplayConfig<'a>>,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub format: &'a str,
+    pub backend: &'a str,
 }
 
 impl<'a> Default for BatteryConfig<'a> {
@@ -30,6 +33,8 @@ impl<'a> Default for BatteryConfig<'a> {
             format: "[$symbol$percentage]($style) ",
             display: vec![BatteryDisplayConfig::default()],
             disabled: false,
+            priority: None,
+            backend: "auto",
         }
     }
 }
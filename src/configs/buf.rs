@@ -13,6 +13,8 @@ pub struct BufConfig<'a> {
     pub symbol: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
     pub detect_folders: Vec<&'a str>,
@@ -21,11 +23,12 @@ pub struct BufConfig<'a> {
 impl<'a> Default for BufConfig<'a> {
     fn default() -> Self {
         BufConfig {
-            format: "with [$symbol($version )]($style)",
+            format: "with [$symbol($version )($name )]($style)",
             version_format: "v${raw}",
             symbol: "🐃 ",
             style: "bold blue",
             disabled: false,
+            priority: None,
             detect_extensions: vec![],
             detect_files: vec!["buf.yaml", "buf.gen.yaml", "buf.work.yaml"],
             detect_folders: vec![],
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct BuildStalenessConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub stale_style: &'a str,
+    pub up_to_date_msg: &'a str,
+    pub stale_msg: &'a str,
+    pub timeout: u64,
+    pub cache_ttl: u64,
+    pub trusted_dirs: Vec<&'a str>,
+    pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+}
+
+impl<'a> Default for BuildStalenessConfig<'a> {
+    fn default() -> Self {
+        BuildStalenessConfig {
+            format: "[$symbol$state]($style) ",
+            symbol: "🔨 ",
+            style: "bold green",
+            stale_style: "bold yellow",
+            up_to_date_msg: "up to date",
+            stale_msg: "stale",
+            timeout: 500,
+            cache_ttl: 30,
+            trusted_dirs: vec![],
+            disabled: true,
+            priority: None,
+        }
+    }
+}
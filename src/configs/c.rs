@@ -13,6 +13,8 @@ pub struct CConfig<'a> {
     pub style: &'a str,
     pub symbol: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
     pub detect_folders: Vec<&'a str>,
@@ -22,11 +24,12 @@ pub struct CConfig<'a> {
 impl<'a> Default for CConfig<'a> {
     fn default() -> Self {
         CConfig {
-            format: "via [$symbol($version(-$name) )]($style)",
+            format: "via [$symbol($version(-$name) )($std )]($style)",
             version_format: "v${raw}",
             style: "149 bold",
             symbol: "C ",
             disabled: false,
+            priority: None,
             detect_extensions: vec!["c", "h"],
             detect_files: vec![],
             detect_folders: vec![],
@@ -11,12 +11,17 @@ pub struct CharacterConfig<'a> {
     pub format: &'a str,
     pub success_symbol: &'a str,
     pub error_symbol: &'a str,
+    pub remote_success_symbol: &'a str,
+    pub remote_error_symbol: &'a str,
     #[serde(alias = "vicmd_symbol")]
     pub vimcmd_symbol: &'a str,
     pub vimcmd_visual_symbol: &'a str,
     pub vimcmd_replace_symbol: &'a str,
     pub vimcmd_replace_one_symbol: &'a str,
+    pub pending_symbols: Vec<&'a str>,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
 }
 
 impl<'a> Default for CharacterConfig<'a> {
@@ -25,11 +30,20 @@ impl<'a> Default for CharacterConfig<'a> {
             format: "$symbol ",
             success_symbol: "[❯](bold green)",
             error_symbol: "[❯](bold red)",
+            remote_success_symbol: "",
+            remote_error_symbol: "",
             vimcmd_symbol: "[❮](bold green)",
             vimcmd_visual_symbol: "[❮](bold yellow)",
             vimcmd_replace_symbol: "[❮](bold purple)",
             vimcmd_replace_one_symbol: "[❮](bold purple)",
+            pending_symbols: vec![
+                "[◐](bold yellow)",
+                "[◓](bold yellow)",
+                "[◑](bold yellow)",
+                "[◒](bold yellow)",
+            ],
             disabled: false,
+            priority: None,
         }
     }
 }
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct CiConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+}
+
+impl<'a> Default for CiConfig<'a> {
+    fn default() -> Self {
+        CiConfig {
+            format: "in [$symbol$provider( $job)( \\(attempt $attempt\\))]($style) ",
+            symbol: "⚙️  ",
+            style: "bold purple",
+            disabled: true,
+            priority: None,
+        }
+    }
+}
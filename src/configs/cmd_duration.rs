@@ -13,6 +13,8 @@ pub struct CmdDurationConfig<'a> {
     pub style: &'a str,
     pub show_milliseconds: bool,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub show_notifications: bool,
     pub min_time_to_notify: i64,
 
@@ -28,6 +30,7 @@ impl<'a> Default for CmdDurationConfig<'a> {
             show_milliseconds: false,
             style: "yellow bold",
             disabled: false,
+            priority: None,
             show_notifications: false,
             min_time_to_notify: 45_000,
             notification_timeout: None,
@@ -14,6 +14,8 @@ pub struct CondaConfig<'a> {
     pub style: &'a str,
     pub ignore_base: bool,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
 }
 
 impl<'a> Default for CondaConfig<'a> {
@@ -25,6 +27,7 @@ impl<'a> Default for CondaConfig<'a> {
             style: "green bold",
             ignore_base: true,
             disabled: false,
+            priority: None,
         }
     }
 }
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct CppConfig<'a> {
+    pub format: &'a str,
+    pub version_format: &'a str,
+    pub style: &'a str,
+    pub symbol: &'a str,
+    pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+    pub detect_extensions: Vec<&'a str>,
+    pub detect_files: Vec<&'a str>,
+    pub detect_folders: Vec<&'a str>,
+    pub commands: Vec<Vec<&'a str>>,
+}
+
+impl<'a> Default for CppConfig<'a> {
+    fn default() -> Self {
+        CppConfig {
+            format: "via [$symbol($version(-$name) )($std )]($style)",
+            version_format: "v${raw}",
+            style: "149 bold",
+            symbol: "C++ ",
+            disabled: true,
+            priority: None,
+            detect_extensions: vec!["cpp", "cc", "cxx", "c++", "hpp", "hh", "hxx", "h++", "tcc"],
+            detect_files: vec![],
+            detect_folders: vec![],
+            commands: vec![
+                vec!["c++", "--version"],
+                vec!["g++", "--version"],
+                vec!["clang++", "--version"],
+            ],
+        }
+    }
+}
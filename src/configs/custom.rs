@@ -19,6 +19,8 @@ pub struct CustomConfig<'a> {
     pub description: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     #[serde(alias = "files")]
     pub detect_files: Vec<&'a str>,
     #[serde(alias = "extensions")]
@@ -31,6 +33,7 @@ pub struct CustomConfig<'a> {
     pub use_stdin: Option<bool>,
     pub ignore_timeout: bool,
     pub unsafe_no_escape: bool,
+    pub allowed_escape_sequences: Vec<&'a str>,
 }
 
 impl<'a> Default for CustomConfig<'a> {
@@ -45,6 +48,7 @@ impl<'a> Default for CustomConfig<'a> {
             description: "<custom config>",
             style: "green bold",
             disabled: false,
+            priority: None,
             detect_files: Vec::default(),
             detect_extensions: Vec::default(),
             detect_folders: Vec::default(),
@@ -52,6 +56,7 @@ impl<'a> Default for CustomConfig<'a> {
             use_stdin: None,
             ignore_timeout: false,
             unsafe_no_escape: false,
+            allowed_escape_sequences: Vec::default(),
         }
     }
 }
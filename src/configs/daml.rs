@@ -13,6 +13,8 @@ pub struct DamlConfig<'a> {
     pub version_format: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
     pub detect_folders: Vec<&'a str>,
@@ -26,6 +28,7 @@ impl<'a> Default for DamlConfig<'a> {
             version_format: "v${raw}",
             style: "bold cyan",
             disabled: false,
+            priority: None,
             detect_extensions: vec![],
             detect_files: vec!["daml.yaml"],
             detect_folders: vec![],
@@ -21,11 +21,18 @@ pub struct DirectoryConfig<'a> {
     pub repo_root_style: Option<&'a str>,
     pub before_repo_root_style: Option<&'a str>,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub read_only: &'a str,
     pub read_only_style: &'a str,
+    pub windows_drive: &'a str,
+    pub windows_drive_style: &'a str,
     pub truncation_symbol: &'a str,
     pub home_symbol: &'a str,
     pub use_os_path_sep: bool,
+    pub bookmarks: IndexMap<String, &'a str>,
+    pub bookmark_symbol: &'a str,
+    pub zoxide_bookmarks: usize,
 }
 
 impl<'a> Default for DirectoryConfig<'a> {
@@ -36,17 +43,23 @@ impl<'a> Default for DirectoryConfig<'a> {
             fish_style_pwd_dir_length: 0,
             use_logical_path: true,
             substitutions: IndexMap::new(),
-            format: "[$path]($style)[$read_only]($read_only_style) ",
-            repo_root_format: "[$before_root_path]($before_repo_root_style)[$repo_root]($repo_root_style)[$path]($style)[$read_only]($read_only_style) ",
+            format: "[$path]($style)[$read_only]($read_only_style)[$windows_drive]($windows_drive_style) ",
+            repo_root_format: "[$before_root_path]($before_repo_root_style)[$repo_root]($repo_root_style)[$path]($style)[$read_only]($read_only_style)[$windows_drive]($windows_drive_style) ",
             style: "cyan bold",
             repo_root_style: None,
             before_repo_root_style: None,
             disabled: false,
+            priority: None,
             read_only: "🔒",
             read_only_style: "red",
+            windows_drive: " 🐢",
+            windows_drive_style: "yellow",
             truncation_symbol: "",
             home_symbol: "~",
             use_os_path_sep: true,
+            bookmarks: IndexMap::new(),
+            bookmark_symbol: "@",
+            zoxide_bookmarks: 0,
         }
     }
 }
@@ -11,7 +11,12 @@ pub struct DirenvConfig<'a> {
     pub format: &'a str,
     pub symbol: &'a str,
     pub style: &'a str,
+    pub allowed_style: Option<&'a str>,
+    pub not_allowed_style: Option<&'a str>,
+    pub denied_style: Option<&'a str>,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
     pub detect_folders: Vec<&'a str>,
@@ -20,6 +25,9 @@ pub struct DirenvConfig<'a> {
     pub denied_msg: &'a str,
     pub loaded_msg: &'a str,
     pub unloaded_msg: &'a str,
+    pub stale_msg: &'a str,
+    pub envrc_msg: &'a str,
+    pub dotenv_msg: &'a str,
 }
 
 impl<'a> Default for DirenvConfig<'a> {
@@ -28,7 +36,11 @@ impl<'a> Default for DirenvConfig<'a> {
             format: "[$symbol$loaded/$allowed]($style) ",
             symbol: "direnv ",
             style: "bold bright-yellow",
+            allowed_style: None,
+            not_allowed_style: None,
+            denied_style: None,
             disabled: true,
+            priority: None,
             detect_extensions: vec![],
             detect_files: vec![".envrc"],
             detect_folders: vec![],
@@ -37,6 +49,9 @@ impl<'a> Default for DirenvConfig<'a> {
             denied_msg: "denied",
             loaded_msg: "loaded",
             unloaded_msg: "not loaded",
+            stale_msg: "stale",
+            envrc_msg: ".envrc",
+            dotenv_msg: ".env",
         }
     }
 }
@@ -13,6 +13,8 @@ pub struct DockerContextConfig<'a> {
     pub format: &'a str,
     pub only_with_files: bool,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
     pub detect_folders: Vec<&'a str>,
@@ -26,6 +28,7 @@ impl<'a> Default for DockerContextConfig<'a> {
             format: "via [$symbol$context]($style) ",
             only_with_files: true,
             disabled: false,
+            priority: None,
             detect_extensions: vec![],
             detect_files: vec!["docker-compose.yml", "docker-compose.yaml", "Dockerfile"],
             detect_folders: vec![],
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct DotenvConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub unloaded_style: &'a str,
+    pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+    pub detect_files: Vec<&'a str>,
+    pub sample_size: usize,
+    pub loaded_msg: &'a str,
+    pub unloaded_msg: &'a str,
+}
+
+impl<'a> Default for DotenvConfig<'a> {
+    fn default() -> Self {
+        Self {
+            format: "[$symbol$loaded]($style) ",
+            symbol: ".env ",
+            style: "bold green",
+            unloaded_style: "bold red",
+            disabled: true,
+            priority: None,
+            detect_files: vec![".env", ".env.local"],
+            sample_size: 3,
+            loaded_msg: "loaded",
+            unloaded_msg: "not loaded",
+        }
+    }
+}
@@ -14,6 +14,8 @@ pub struct DotnetConfig<'a> {
     pub style: &'a str,
     pub heuristic: bool,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
     pub detect_folders: Vec<&'a str>,
@@ -28,6 +30,7 @@ impl<'a> Default for DotnetConfig<'a> {
             style: "blue bold",
             heuristic: true,
             disabled: false,
+            priority: None,
             detect_extensions: vec!["csproj", "fsproj", "xproj"],
             detect_files: vec![
                 "global.json",
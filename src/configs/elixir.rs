@@ -12,7 +12,10 @@ pub struct ElixirConfig<'a> {
     pub version_format: &'a str,
     pub symbol: &'a str,
     pub style: &'a str,
+    pub not_capable_style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
     pub detect_folders: Vec<&'a str>,
@@ -21,11 +24,13 @@ pub struct ElixirConfig<'a> {
 impl<'a> Default for ElixirConfig<'a> {
     fn default() -> Self {
         ElixirConfig {
-            format: "via [$symbol($version \\(OTP $otp_version\\) )]($style)",
+            format: "via [$symbol($version \\(OTP $otp_version\\) )($mix_env )]($style)",
             version_format: "v${raw}",
             symbol: "💧 ",
             style: "bold purple",
+            not_capable_style: "bold red",
             disabled: false,
+            priority: None,
             detect_extensions: vec![],
             detect_files: vec!["mix.exs"],
             detect_folders: vec![],
@@ -16,7 +16,10 @@ pub struct EnvVarConfig<'a> {
     pub default: Option<&'a str>,
     pub format: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub description: &'a str,
+    pub allowed_escape_sequences: Vec<&'a str>,
 }
 
 impl<'a> Default for EnvVarConfig<'a> {
@@ -28,7 +31,9 @@ impl<'a> Default for EnvVarConfig<'a> {
             default: None,
             format: "with [$env_value]($style) ",
             disabled: false,
+            priority: None,
             description: "<env_var module>",
+            allowed_escape_sequences: Vec::default(),
         }
     }
 }
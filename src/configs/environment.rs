@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct EnvironmentConfig<'a> {
+    pub format: &'a str,
+    pub banner_format: &'a str,
+    pub banner_symbol: &'a str,
+    pub banner_environments: Vec<&'a str>,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub rules: Vec<EnvironmentRuleConfig<'a>>,
+    pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+}
+
+impl<'a> Default for EnvironmentConfig<'a> {
+    fn default() -> Self {
+        EnvironmentConfig {
+            format: "on [$symbol$environment]($style) ",
+            banner_format: "[ $symbol$environment ]($style)",
+            banner_symbol: "━",
+            banner_environments: vec!["prod"],
+            symbol: "🚦 ",
+            style: "bold yellow",
+            rules: vec![],
+            disabled: true,
+            priority: None,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize, Default)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct EnvironmentRuleConfig<'a> {
+    pub environment: &'a str,
+    pub env_var: Option<&'a str>,
+    pub env_value_pattern: Option<&'a str>,
+    pub kube_context_pattern: Option<&'a str>,
+    pub aws_profile_pattern: Option<&'a str>,
+    pub directory_pattern: Option<&'a str>,
+    pub symbol: Option<&'a str>,
+    pub style: Option<&'a str>,
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct ErrorsConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub separator: &'a str,
+    pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+}
+
+impl<'a> Default for ErrorsConfig<'a> {
+    fn default() -> Self {
+        ErrorsConfig {
+            format: "[$symbol$count]($style) ",
+            symbol: "⚠ ",
+            style: "bold red",
+            separator: ", ",
+            disabled: true,
+            priority: None,
+        }
+    }
+}
@@ -13,6 +13,8 @@ pub struct FennelConfig<'a> {
     pub symbol: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     #[serde(alias = "detect_extentions")] // TODO: remove it after breaking change releases
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
@@ -27,6 +29,7 @@ impl<'a> Default for FennelConfig<'a> {
             symbol: "🧅 ",
             style: "bold green",
             disabled: true,
+            priority: None,
             detect_extensions: vec!["fnl"],
             detect_files: vec![],
             detect_folders: vec![],
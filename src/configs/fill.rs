@@ -9,16 +9,21 @@ use serde::{Deserialize, Serialize};
 #[serde(default)]
 pub struct FillConfig<'a> {
     pub style: &'a str,
+    pub styles: Vec<&'a str>,
     pub symbol: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
 }
 
 impl<'a> Default for FillConfig<'a> {
     fn default() -> Self {
         FillConfig {
             style: "bold black",
+            styles: vec![],
             symbol: ".",
             disabled: false,
+            priority: None,
         }
     }
 }
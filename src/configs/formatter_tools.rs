@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct FormatterToolsConfig<'a> {
+    pub format: &'a str,
+    pub tool_format: &'a str,
+    pub tool_separator: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub mismatch_style: &'a str,
+    pub missing_style: &'a str,
+    pub only_problems: bool,
+    pub ignore: Vec<&'a str>,
+    pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+}
+
+impl<'a> Default for FormatterToolsConfig<'a> {
+    fn default() -> Self {
+        FormatterToolsConfig {
+            format: "with [$symbol]($style)$tools ",
+            tool_format: "[$name( $version)( \\(wants $required\\))]($style)",
+            tool_separator: " ",
+            symbol: "🧹 ",
+            style: "bold green",
+            mismatch_style: "bold yellow",
+            missing_style: "bold red",
+            only_problems: true,
+            ignore: vec![],
+            disabled: true,
+            priority: None,
+        }
+    }
+}
@@ -14,6 +14,8 @@ pub struct FossilBranchConfig<'a> {
     pub truncation_length: i64,
     pub truncation_symbol: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
 }
 
 impl<'a> Default for FossilBranchConfig<'a> {
@@ -25,6 +27,7 @@ impl<'a> Default for FossilBranchConfig<'a> {
             truncation_length: i64::MAX,
             truncation_symbol: "…",
             disabled: true,
+            priority: None,
         }
     }
 }
@@ -13,6 +13,8 @@ pub struct FossilMetricsConfig<'a> {
     pub deleted_style: &'a str,
     pub only_nonzero_diffs: bool,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
 }
 
 impl<'a> Default for FossilMetricsConfig<'a> {
@@ -23,6 +25,7 @@ impl<'a> Default for FossilMetricsConfig<'a> {
             deleted_style: "bold red",
             only_nonzero_diffs: true,
             disabled: true,
+            priority: None,
         }
     }
 }
@@ -13,8 +13,12 @@ pub struct GcloudConfig<'a> {
     pub symbol: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub region_aliases: HashMap<String, &'a str>,
     pub project_aliases: HashMap<String, &'a str>,
+    pub active_aliases: HashMap<String, &'a str>,
+    pub expiration_symbol: &'a str,
     pub detect_env_vars: Vec<&'a str>,
 }
 
@@ -25,8 +29,11 @@ impl<'a> Default for GcloudConfig<'a> {
             symbol: "☁️  ",
             style: "bold blue",
             disabled: false,
+            priority: None,
             region_aliases: HashMap::new(),
             project_aliases: HashMap::new(),
+            active_aliases: HashMap::new(),
+            expiration_symbol: "X",
             detect_env_vars: vec![],
         }
     }
@@ -16,7 +16,15 @@ pub struct GitBranchConfig<'a> {
     pub only_attached: bool,
     pub always_show_remote: bool,
     pub ignore_branches: Vec<&'a str>,
+    pub protected_branches: Vec<&'a str>,
+    pub protected_symbol: &'a str,
+    pub protected_style: &'a str,
+    pub sparse_symbol: &'a str,
+    pub shallow_symbol: &'a str,
+    pub partial_symbol: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
 }
 
 impl<'a> Default for GitBranchConfig<'a> {
@@ -30,7 +38,14 @@ impl<'a> Default for GitBranchConfig<'a> {
             only_attached: false,
             always_show_remote: false,
             ignore_branches: vec![],
+            protected_branches: vec![],
+            protected_symbol: "⚠ ",
+            protected_style: "",
+            sparse_symbol: "sparse ",
+            shallow_symbol: "shallow ",
+            partial_symbol: "partial ",
             disabled: false,
+            priority: None,
         }
     }
 }
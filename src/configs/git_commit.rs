@@ -13,6 +13,8 @@ pub struct GitCommitConfig<'a> {
     pub style: &'a str,
     pub only_detached: bool,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub tag_symbol: &'a str,
     pub tag_disabled: bool,
     pub tag_max_candidates: usize,
@@ -27,6 +29,7 @@ impl<'a> Default for GitCommitConfig<'a> {
             style: "green bold",
             only_detached: true,
             disabled: false,
+            priority: None,
             tag_symbol: " 🏷  ",
             tag_disabled: true,
             tag_max_candidates: 0,
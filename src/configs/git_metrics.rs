@@ -13,6 +13,8 @@ pub struct GitMetricsConfig<'a> {
     pub only_nonzero_diffs: bool,
     pub format: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub ignore_submodules: bool,
 }
 
@@ -24,6 +26,7 @@ impl<'a> Default for GitMetricsConfig<'a> {
             only_nonzero_diffs: true,
             format: "([+$added]($added_style) )([-$deleted]($deleted_style) )",
             disabled: true,
+            priority: None,
             ignore_submodules: false,
         }
     }
@@ -18,6 +18,8 @@ pub struct GitStateConfig<'a> {
     pub style: &'a str,
     pub format: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
 }
 
 impl<'a> Default for GitStateConfig<'a> {
@@ -33,6 +35,7 @@ impl<'a> Default for GitStateConfig<'a> {
             style: "bold yellow",
             format: "\\([$state( $progress_current/$progress_total)]($style)\\) ",
             disabled: false,
+            priority: None,
         }
     }
 }
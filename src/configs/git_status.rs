@@ -22,9 +22,14 @@ pub struct GitStatusConfig<'a> {
     pub staged: &'a str,
     pub untracked: &'a str,
     pub typechanged: &'a str,
+    pub submodules_dirty: &'a str,
+    pub lfs_missing: &'a str,
     pub ignore_submodules: bool,
+    pub scope: &'a str,
     pub disabled: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub windows_starship: Option<&'a str>,
 }
 
@@ -45,8 +50,12 @@ impl<'a> Default for GitStatusConfig<'a> {
             staged: "+",
             untracked: "?",
             typechanged: "",
+            submodules_dirty: "",
+            lfs_missing: "",
             ignore_submodules: false,
+            scope: "repo",
             disabled: false,
+            priority: None,
             windows_starship: None,
         }
     }
@@ -11,8 +11,11 @@ pub struct GradleConfig<'a> {
     pub format: &'a str,
     pub version_format: &'a str,
     pub symbol: &'a str,
+    pub daemon_symbol: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub recursive: bool,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
@@ -25,8 +28,10 @@ impl<'a> Default for GradleConfig<'a> {
             format: "via [$symbol($version )]($style)",
             version_format: "v${raw}",
             symbol: "🅶 ",
+            daemon_symbol: "⚡",
             style: "bold bright-cyan",
             disabled: false,
+            priority: None,
             recursive: false,
             detect_extensions: vec!["gradle", "gradle.kts"],
             detect_files: vec![],
@@ -12,6 +12,8 @@ pub struct GuixShellConfig<'a> {
     pub symbol: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
 }
 
 impl<'a> Default for GuixShellConfig<'a> {
@@ -21,6 +23,7 @@ impl<'a> Default for GuixShellConfig<'a> {
             symbol: "🐃 ",
             style: "yellow bold",
             disabled: false,
+            priority: None,
         }
     }
 }
@@ -13,6 +13,8 @@ pub struct HelmConfig<'a> {
     pub symbol: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
     pub detect_folders: Vec<&'a str>,
@@ -26,6 +28,7 @@ impl<'a> Default for HelmConfig<'a> {
             symbol: "⎈ ",
             style: "bold white",
             disabled: false,
+            priority: None,
             detect_extensions: vec![],
             detect_files: vec!["helmfile.yaml", "Chart.yaml"],
             detect_folders: vec![],
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct HgStatusConfig<'a> {
+    pub format: &'a str,
+    pub style: &'a str,
+    pub ahead: &'a str,
+    pub conflicted: &'a str,
+    pub deleted: &'a str,
+    pub modified: &'a str,
+    pub added: &'a str,
+    pub untracked: &'a str,
+    pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+}
+
+impl<'a> Default for HgStatusConfig<'a> {
+    fn default() -> Self {
+        HgStatusConfig {
+            format: "([\\[$all_status$ahead\\]]($style) )",
+            style: "red bold",
+            ahead: "⇡",
+            conflicted: "=",
+            deleted: "✘",
+            modified: "!",
+            added: "+",
+            untracked: "?",
+            disabled: true,
+            priority: None,
+        }
+    }
+}
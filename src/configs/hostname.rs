@@ -15,7 +15,10 @@ pub struct HostnameConfig<'a> {
     pub detect_env_vars: Vec<&'a str>,
     pub format: &'a str,
     pub style: &'a str,
+    pub remote_style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub aliases: IndexMap<String, &'a str>,
 }
 
@@ -28,7 +31,9 @@ impl<'a> Default for HostnameConfig<'a> {
             detect_env_vars: vec![],
             format: "[$ssh_symbol$hostname]($style) in ",
             style: "green dimmed bold",
+            remote_style: "",
             disabled: false,
+            priority: None,
             aliases: IndexMap::new(),
         }
     }
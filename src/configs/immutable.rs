@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct ImmutableConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub nix_store_msg: &'a str,
+    pub ostree_msg: &'a str,
+    pub overlay_msg: &'a str,
+    pub read_only_msg: &'a str,
+    pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+}
+
+impl<'a> Default for ImmutableConfig<'a> {
+    fn default() -> Self {
+        ImmutableConfig {
+            format: "in [$symbol$kind]($style) ",
+            symbol: "🧊 ",
+            style: "bold red",
+            nix_store_msg: "nix store",
+            ostree_msg: "ostree",
+            overlay_msg: "read-only overlay",
+            read_only_msg: "read-only",
+            disabled: true,
+            priority: None,
+        }
+    }
+}
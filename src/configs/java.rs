@@ -9,6 +9,8 @@ use serde::{Deserialize, Serialize};
 #[serde(default)]
 pub struct JavaConfig<'a> {
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub format: &'a str,
     pub version_format: &'a str,
     pub style: &'a str,
@@ -24,6 +26,7 @@ impl<'a> Default for JavaConfig<'a> {
             format: "via [$symbol($version )]($style)",
             version_format: "v${raw}",
             disabled: false,
+            priority: None,
             style: "red dimmed",
             symbol: "☕ ",
             detect_extensions: vec!["java", "class", "jar", "gradle", "clj", "cljc"],
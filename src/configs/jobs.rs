@@ -15,6 +15,8 @@ pub struct JobsConfig<'a> {
     pub symbol: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
 }
 
 impl<'a> Default for JobsConfig<'a> {
@@ -27,6 +29,7 @@ impl<'a> Default for JobsConfig<'a> {
             symbol: "✦",
             style: "bold blue",
             disabled: false,
+            priority: None,
         }
     }
 }
@@ -12,7 +12,11 @@ pub struct JuliaConfig<'a> {
     pub version_format: &'a str,
     pub symbol: &'a str,
     pub style: &'a str,
+    pub manifest_synced_symbol: &'a str,
+    pub manifest_out_of_sync_symbol: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
     pub detect_folders: Vec<&'a str>,
@@ -25,7 +29,10 @@ impl<'a> Default for JuliaConfig<'a> {
             version_format: "v${raw}",
             symbol: "ஃ ",
             style: "bold purple",
+            manifest_synced_symbol: "✓",
+            manifest_out_of_sync_symbol: "✗",
             disabled: false,
+            priority: None,
             detect_extensions: vec!["jl"],
             detect_files: vec!["Project.toml", "Manifest.toml"],
             detect_folders: vec![],
@@ -14,6 +14,8 @@ pub struct KotlinConfig<'a> {
     pub style: &'a str,
     pub kotlin_binary: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
     pub detect_folders: Vec<&'a str>,
@@ -28,6 +30,7 @@ impl<'a> Default for KotlinConfig<'a> {
             style: "bold blue",
             kotlin_binary: "kotlin",
             disabled: false,
+            priority: None,
             detect_extensions: vec!["kt", "kts"],
             detect_files: vec![],
             detect_folders: vec![],
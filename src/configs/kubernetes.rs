@@ -1,3 +1,4 @@
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -13,6 +14,8 @@ pub struct KubernetesConfig<'a> {
     pub format: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub context_aliases: HashMap<String, &'a str>,
     pub user_aliases: HashMap<String, &'a str>,
     pub detect_extensions: Vec<&'a str>,
@@ -20,6 +23,7 @@ pub struct KubernetesConfig<'a> {
     pub detect_folders: Vec<&'a str>,
     pub detect_env_vars: Vec<&'a str>,
     pub contexts: Vec<KubernetesContextConfig<'a>>,
+    pub groups: IndexMap<String, KubernetesGroupConfig<'a>>,
 }
 
 impl<'a> Default for KubernetesConfig<'a> {
@@ -29,6 +33,7 @@ impl<'a> Default for KubernetesConfig<'a> {
             format: "[$symbol$context( \\($namespace\\))]($style) in ",
             style: "cyan bold",
             disabled: true,
+            priority: None,
             context_aliases: HashMap::new(),
             user_aliases: HashMap::new(),
             detect_extensions: vec![],
@@ -36,6 +41,7 @@ impl<'a> Default for KubernetesConfig<'a> {
             detect_folders: vec![],
             detect_env_vars: vec![],
             contexts: vec![],
+            groups: IndexMap::new(),
         }
     }
 }
@@ -54,4 +60,18 @@ pub struct KubernetesContextConfig<'a> {
     pub style: Option<&'a str>,
     pub context_alias: Option<&'a str>,
     pub user_alias: Option<&'a str>,
+    pub group: Option<&'a str>,
+}
+
+#[derive(Clone, Deserialize, Serialize, Default)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct KubernetesGroupConfig<'a> {
+    pub context_patterns: Vec<&'a str>,
+    pub symbol: Option<&'a str>,
+    pub style: Option<&'a str>,
 }
@@ -8,5 +8,9 @@ use serde::{Deserialize, Serialize};
 )]
 #[serde(default)]
 pub struct LineBreakConfig {
+    pub min_width: usize,
+    pub max_width: usize,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
 }
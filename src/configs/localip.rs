@@ -1,3 +1,4 @@
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Deserialize, Serialize)]
@@ -11,7 +12,14 @@ pub struct LocalipConfig<'a> {
     pub ssh_only: bool,
     pub format: &'a str,
     pub style: &'a str,
+    pub interfaces: Vec<&'a str>,
+    pub prefer: &'a str,
+    pub labels: IndexMap<String, &'a str>,
+    pub interface_format: &'a str,
+    pub interface_separator: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
 }
 
 impl<'a> Default for LocalipConfig<'a> {
@@ -20,7 +28,13 @@ impl<'a> Default for LocalipConfig<'a> {
             ssh_only: true,
             format: "[$localipv4]($style) ",
             style: "yellow bold",
+            interfaces: vec![],
+            prefer: "ipv4",
+            labels: IndexMap::new(),
+            interface_format: "$label:$address",
+            interface_separator: " ",
             disabled: true,
+            priority: None,
         }
     }
 }
@@ -14,6 +14,8 @@ pub struct LuaConfig<'a> {
     pub style: &'a str,
     pub lua_binary: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
     pub detect_folders: Vec<&'a str>,
@@ -28,6 +30,7 @@ impl<'a> Default for LuaConfig<'a> {
             style: "bold blue",
             lua_binary: "lua",
             disabled: false,
+            priority: None,
             detect_extensions: vec!["lua"],
             detect_files: vec![".lua-version"],
             detect_folders: vec!["lua"],
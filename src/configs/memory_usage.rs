@@ -13,6 +13,8 @@ pub struct MemoryConfig<'a> {
     pub style: &'a str,
     pub symbol: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
 }
 
 impl<'a> Default for MemoryConfig<'a> {
@@ -23,6 +25,7 @@ impl<'a> Default for MemoryConfig<'a> {
             style: "white bold dimmed",
             symbol: "🐏 ",
             disabled: true,
+            priority: None,
         }
     }
 }
@@ -14,6 +14,8 @@ pub struct MesonConfig<'a> {
     pub symbol: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
 }
 
 impl<'a> Default for MesonConfig<'a> {
@@ -25,6 +27,7 @@ impl<'a> Default for MesonConfig<'a> {
             symbol: "⬢ ",
             style: "blue bold",
             disabled: false,
+            priority: None,
         }
     }
 }
@@ -1,18 +1,22 @@
 use indexmap::IndexMap;
 use serde::{self, Deserialize, Serialize};
 
+pub mod annotations;
 pub mod aws;
 pub mod azure;
 pub mod battery;
 pub mod buf;
+pub mod build_staleness;
 pub mod bun;
 pub mod c;
 pub mod character;
+pub mod ci;
 pub mod cmake;
 pub mod cmd_duration;
 pub mod cobol;
 pub mod conda;
 pub mod container;
+pub mod cpp;
 pub mod crystal;
 pub mod custom;
 pub mod daml;
@@ -21,13 +25,17 @@ pub mod deno;
 pub mod directory;
 pub mod direnv;
 pub mod docker_context;
+pub mod dotenv;
 pub mod dotnet;
 pub mod elixir;
 pub mod elm;
 pub mod env_var;
+pub mod environment;
 pub mod erlang;
+pub mod errors;
 pub mod fennel;
 pub mod fill;
+pub mod formatter_tools;
 pub mod fossil_branch;
 pub mod fossil_metrics;
 pub mod gcloud;
@@ -44,7 +52,9 @@ pub mod haskell;
 pub mod haxe;
 pub mod helm;
 pub mod hg_branch;
+pub mod hg_status;
 pub mod hostname;
+pub mod immutable;
 pub mod java;
 pub mod jobs;
 pub mod julia;
@@ -69,12 +79,16 @@ pub mod package;
 pub mod perl;
 pub mod php;
 pub mod pijul_channel;
+pub mod pre_commit;
 pub mod pulumi;
 pub mod purescript;
 pub mod python;
+pub mod python_env;
 pub mod quarto;
 pub mod raku;
 pub mod red;
+pub mod repl;
+pub mod repo_remote;
 pub mod rlang;
 pub mod ruby;
 pub mod rust;
@@ -89,7 +103,10 @@ pub mod status;
 pub mod sudo;
 pub mod swift;
 pub mod terraform;
+pub mod tests;
+pub mod ticket;
 pub mod time;
+pub mod todos;
 pub mod typst;
 pub mod username;
 pub mod v;
@@ -115,6 +132,8 @@ pub struct FullConfig<'a> {
     root: StarshipRootConfig,
     // modules
     #[serde(borrow)]
+    annotations: annotations::AnnotationsConfig<'a>,
+    #[serde(borrow)]
     aws: aws::AwsConfig<'a>,
     #[serde(borrow)]
     azure: azure::AzureConfig<'a>,
@@ -123,12 +142,16 @@ pub struct FullConfig<'a> {
     #[serde(borrow)]
     buf: buf::BufConfig<'a>,
     #[serde(borrow)]
+    build_staleness: build_staleness::BuildStalenessConfig<'a>,
+    #[serde(borrow)]
     bun: bun::BunConfig<'a>,
     #[serde(borrow)]
     c: c::CConfig<'a>,
     #[serde(borrow)]
     character: character::CharacterConfig<'a>,
     #[serde(borrow)]
+    ci: ci::CiConfig<'a>,
+    #[serde(borrow)]
     cmake: cmake::CMakeConfig<'a>,
     #[serde(borrow)]
     cmd_duration: cmd_duration::CmdDurationConfig<'a>,
@@ -139,6 +162,8 @@ pub struct FullConfig<'a> {
     #[serde(borrow)]
     container: container::ContainerConfig<'a>,
     #[serde(borrow)]
+    cpp: cpp::CppConfig<'a>,
+    #[serde(borrow)]
     crystal: crystal::CrystalConfig<'a>,
     #[serde(borrow)]
     daml: daml::DamlConfig<'a>,
@@ -153,6 +178,8 @@ pub struct FullConfig<'a> {
     #[serde(borrow)]
     docker_context: docker_context::DockerContextConfig<'a>,
     #[serde(borrow)]
+    dotenv: dotenv::DotenvConfig<'a>,
+    #[serde(borrow)]
     dotnet: dotnet::DotnetConfig<'a>,
     #[serde(borrow)]
     elixir: elixir::ElixirConfig<'a>,
@@ -161,12 +188,18 @@ pub struct FullConfig<'a> {
     #[serde(borrow)]
     env_var: IndexMap<String, env_var::EnvVarConfig<'a>>,
     #[serde(borrow)]
+    environment: environment::EnvironmentConfig<'a>,
+    #[serde(borrow)]
     erlang: erlang::ErlangConfig<'a>,
     #[serde(borrow)]
+    errors: errors::ErrorsConfig<'a>,
+    #[serde(borrow)]
     fennel: fennel::FennelConfig<'a>,
     #[serde(borrow)]
     fill: fill::FillConfig<'a>,
     #[serde(borrow)]
+    formatter_tools: formatter_tools::FormatterToolsConfig<'a>,
+    #[serde(borrow)]
     fossil_branch: fossil_branch::FossilBranchConfig<'a>,
     #[serde(borrow)]
     fossil_metrics: fossil_metrics::FossilMetricsConfig<'a>,
@@ -199,8 +232,12 @@ pub struct FullConfig<'a> {
     #[serde(borrow)]
     hg_branch: hg_branch::HgBranchConfig<'a>,
     #[serde(borrow)]
+    hg_status: hg_status::HgStatusConfig<'a>,
+    #[serde(borrow)]
     hostname: hostname::HostnameConfig<'a>,
     #[serde(borrow)]
+    immutable: immutable::ImmutableConfig<'a>,
+    #[serde(borrow)]
     java: java::JavaConfig<'a>,
     #[serde(borrow)]
     jobs: jobs::JobsConfig<'a>,
@@ -248,18 +285,26 @@ pub struct FullConfig<'a> {
     #[serde(borrow)]
     pijul_channel: pijul_channel::PijulConfig<'a>,
     #[serde(borrow)]
+    pre_commit: pre_commit::PreCommitConfig<'a>,
+    #[serde(borrow)]
     pulumi: pulumi::PulumiConfig<'a>,
     #[serde(borrow)]
     purescript: purescript::PureScriptConfig<'a>,
     #[serde(borrow)]
     python: python::PythonConfig<'a>,
     #[serde(borrow)]
+    python_env: python_env::PythonEnvConfig<'a>,
+    #[serde(borrow)]
     quarto: quarto::QuartoConfig<'a>,
     #[serde(borrow)]
     raku: raku::RakuConfig<'a>,
     #[serde(borrow)]
     red: red::RedConfig<'a>,
     #[serde(borrow)]
+    repl: repl::ReplConfig<'a>,
+    #[serde(borrow)]
+    repo_remote: repo_remote::RepoRemoteConfig<'a>,
+    #[serde(borrow)]
     rlang: rlang::RLangConfig<'a>,
     #[serde(borrow)]
     ruby: ruby::RubyConfig<'a>,
@@ -286,8 +331,14 @@ pub struct FullConfig<'a> {
     #[serde(borrow)]
     terraform: terraform::TerraformConfig<'a>,
     #[serde(borrow)]
+    tests: tests::TestsConfig<'a>,
+    #[serde(borrow)]
+    ticket: ticket::TicketConfig<'a>,
+    #[serde(borrow)]
     time: time::TimeConfig<'a>,
     #[serde(borrow)]
+    todos: todos::TodosConfig<'a>,
+    #[serde(borrow)]
     typst: typst::TypstConfig<'a>,
     #[serde(borrow)]
     username: username::UsernameConfig<'a>,
@@ -15,6 +15,8 @@ pub struct MojoConfig<'a> {
     pub symbol: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
     pub detect_folders: Vec<&'a str>,
@@ -27,6 +29,7 @@ impl<'a> Default for MojoConfig<'a> {
             symbol: "🔥 ",
             style: "bold 208",
             disabled: false,
+            priority: None,
             detect_extensions: vec!["mojo", "🔥"],
             detect_files: vec![],
             detect_folders: vec![],
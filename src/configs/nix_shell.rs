@@ -15,6 +15,8 @@ pub struct NixShellConfig<'a> {
     pub pure_msg: &'a str,
     pub unknown_msg: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub heuristic: bool,
 }
 
@@ -31,6 +33,7 @@ impl<'a> Default for NixShellConfig<'a> {
             pure_msg: "pure",
             unknown_msg: "",
             disabled: false,
+            priority: None,
             heuristic: false,
         }
     }
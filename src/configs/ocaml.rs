@@ -15,6 +15,8 @@ pub struct OCamlConfig<'a> {
     pub symbol: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
     pub detect_folders: Vec<&'a str>,
@@ -30,6 +32,7 @@ impl<'a> Default for OCamlConfig<'a> {
             symbol: "🐫 ",
             style: "bold yellow",
             disabled: false,
+            priority: None,
             detect_extensions: vec!["opam", "ml", "mli", "re", "rei"],
             detect_files: vec!["dune", "dune-project", "jbuild", "jbuild-ignore", ".merlin"],
             detect_folders: vec!["_opam", "esy.lock"],
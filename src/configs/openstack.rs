@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Clone, Deserialize, Serialize)]
 #[cfg_attr(
@@ -12,6 +13,11 @@ pub struct OspConfig<'a> {
     pub symbol: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+    pub cloud_aliases: HashMap<String, &'a str>,
+    pub project_aliases: HashMap<String, &'a str>,
+    pub region_aliases: HashMap<String, &'a str>,
 }
 
 impl<'a> Default for OspConfig<'a> {
@@ -21,6 +27,10 @@ impl<'a> Default for OspConfig<'a> {
             symbol: "☁️  ",
             style: "bold yellow",
             disabled: false,
+            priority: None,
+            cloud_aliases: HashMap::new(),
+            project_aliases: HashMap::new(),
+            region_aliases: HashMap::new(),
         }
     }
 }
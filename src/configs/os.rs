@@ -14,6 +14,8 @@ pub struct OSConfig<'a> {
     pub style: &'a str,
     pub symbols: IndexMap<Type, &'a str>,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
 }
 
 impl<'a> OSConfig<'a> {
@@ -25,7 +27,7 @@ impl<'a> OSConfig<'a> {
 impl<'a> Default for OSConfig<'a> {
     fn default() -> Self {
         OSConfig {
-            format: "[$symbol]($style)",
+            format: "[$symbol($arch_translated )]($style)",
             style: "bold white",
             symbols: indexmap! {
                 Type::AIX => "➿ ",
@@ -88,6 +90,7 @@ impl<'a> Default for OSConfig<'a> {
                 //solaris =>    " ",
             },
             disabled: true,
+            priority: None,
         }
     }
 }
@@ -13,7 +13,11 @@ pub struct PackageConfig<'a> {
     pub style: &'a str,
     pub display_private: bool,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub version_format: &'a str,
+    pub windows_drive: &'a str,
+    pub windows_drive_style: &'a str,
 }
 
 impl<'a> Default for PackageConfig<'a> {
@@ -24,7 +28,10 @@ impl<'a> Default for PackageConfig<'a> {
             style: "208 bold",
             display_private: false,
             disabled: false,
+            priority: None,
             version_format: "v${raw}",
+            windows_drive: " 🐢",
+            windows_drive_style: "yellow",
         }
     }
 }
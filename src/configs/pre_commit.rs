@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct PreCommitConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub installed_msg: &'a str,
+    pub not_installed_msg: &'a str,
+    pub stale_msg: &'a str,
+    pub check_stale: bool,
+    pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+}
+
+impl<'a> Default for PreCommitConfig<'a> {
+    fn default() -> Self {
+        PreCommitConfig {
+            format: "[$symbol$state]($style) ",
+            symbol: "🪝 ",
+            style: "bold yellow",
+            installed_msg: "",
+            not_installed_msg: "not installed",
+            stale_msg: "stale",
+            check_stale: false,
+            disabled: true,
+            priority: None,
+        }
+    }
+}
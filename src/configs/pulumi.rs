@@ -13,7 +13,10 @@ pub struct PulumiConfig<'a> {
     pub symbol: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub search_upwards: bool,
+    pub preview_file: &'a str,
 }
 
 impl<'a> Default for PulumiConfig<'a> {
@@ -24,7 +27,9 @@ impl<'a> Default for PulumiConfig<'a> {
             symbol: " ",
             style: "bold 5",
             disabled: false,
+            priority: None,
             search_upwards: true,
+            preview_file: ".pulumi/preview.json",
         }
     }
 }
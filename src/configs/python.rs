@@ -16,8 +16,11 @@ pub struct PythonConfig<'a> {
     pub format: &'a str,
     pub version_format: &'a str,
     pub style: &'a str,
+    pub not_capable_style: &'a str,
     pub symbol: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
     pub detect_folders: Vec<&'a str>,
@@ -33,8 +36,10 @@ impl<'a> Default for PythonConfig<'a> {
             format: "via [${symbol}${pyenv_prefix}(${version} )(\\($virtualenv\\) )]($style)",
             version_format: "v${raw}",
             style: "yellow bold",
+            not_capable_style: "bold red",
             symbol: "🐍 ",
             disabled: false,
+            priority: None,
             detect_extensions: vec!["py"],
             detect_files: vec![
                 "requirements.txt",
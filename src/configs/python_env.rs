@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct PythonEnvConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub layer_format: &'a str,
+    pub separator: &'a str,
+    pub truncation_length: usize,
+    pub ignore_base: bool,
+    pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+}
+
+impl<'a> Default for PythonEnvConfig<'a> {
+    fn default() -> Self {
+        PythonEnvConfig {
+            format: "via [$symbol$stack]($style) ",
+            symbol: "🐍 ",
+            style: "yellow bold",
+            layer_format: "$kind:$environment",
+            separator: " > ",
+            truncation_length: 1,
+            ignore_base: true,
+            disabled: true,
+            priority: None,
+        }
+    }
+}
@@ -13,6 +13,8 @@ pub struct QuartoConfig<'a> {
     pub symbol: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
     pub detect_folders: Vec<&'a str>,
@@ -26,6 +28,7 @@ impl<'a> Default for QuartoConfig<'a> {
             symbol: "⨁ ",
             style: "bold #75AADB",
             disabled: false,
+            priority: None,
             detect_extensions: vec!["qmd"],
             detect_files: vec!["_quarto.yml"],
             detect_folders: vec![],
@@ -13,6 +13,8 @@ pub struct RakuConfig<'a> {
     pub symbol: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
     pub detect_folders: Vec<&'a str>,
@@ -26,6 +28,7 @@ impl<'a> Default for RakuConfig<'a> {
             symbol: "🦋 ",
             style: "149 bold",
             disabled: false,
+            priority: None,
             detect_extensions: vec!["p6", "pm6", "pod6", "raku", "rakumod"],
             detect_files: vec!["META6.json"],
             detect_folders: vec![],
@@ -0,0 +1,37 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct ReplConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+    pub detect_processes: Vec<&'a str>,
+    pub aliases: IndexMap<String, &'a str>,
+}
+
+impl<'a> Default for ReplConfig<'a> {
+    fn default() -> Self {
+        ReplConfig {
+            format: "in [$symbol$host]($style) ",
+            symbol: "↩ ",
+            style: "bold purple",
+            disabled: true,
+            priority: None,
+            detect_processes: vec![
+                "emacs", "erl", "ghci", "iex", "ipython", "irb", "julia", "lua", "node", "nvim",
+                "pry", "python", "python3", "R", "vim",
+            ],
+            aliases: IndexMap::new(),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct RepoRemoteConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub remote: &'a str,
+    pub pull_requests: bool,
+    pub pull_requests_ttl: u64,
+    pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+}
+
+impl<'a> Default for RepoRemoteConfig<'a> {
+    fn default() -> Self {
+        RepoRemoteConfig {
+            format: "at [$symbol$owner/$repo( \\($pull_requests PRs\\))]($style) ",
+            symbol: "🌐 ",
+            style: "bold blue",
+            remote: "",
+            pull_requests: false,
+            pull_requests_ttl: 900,
+            disabled: true,
+            priority: None,
+        }
+    }
+}
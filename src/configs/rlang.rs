@@ -12,7 +12,11 @@ pub struct RLangConfig<'a> {
     pub version_format: &'a str,
     pub style: &'a str,
     pub symbol: &'a str,
+    pub renv_synced_symbol: &'a str,
+    pub renv_out_of_sync_symbol: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
     pub detect_folders: Vec<&'a str>,
@@ -25,9 +29,12 @@ impl<'a> Default for RLangConfig<'a> {
             version_format: "v${raw}",
             style: "blue bold",
             symbol: "📐 ",
+            renv_synced_symbol: "✓",
+            renv_out_of_sync_symbol: "✗",
             disabled: false,
+            priority: None,
             detect_extensions: vec!["R", "Rd", "Rmd", "Rproj", "Rsx"],
-            detect_files: vec!["DESCRIPTION"],
+            detect_files: vec!["DESCRIPTION", "renv.lock"],
             detect_folders: vec![".Rproj.user"],
         }
     }
@@ -13,6 +13,9 @@ pub struct RustConfig<'a> {
     pub symbol: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+    pub not_capable_style: &'a str,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
     pub detect_folders: Vec<&'a str>,
@@ -26,6 +29,8 @@ impl<'a> Default for RustConfig<'a> {
             symbol: "🦀 ",
             style: "bold red",
             disabled: false,
+            priority: None,
+            not_capable_style: "bold underline red",
             detect_extensions: vec!["rs"],
             detect_files: vec!["Cargo.toml"],
             detect_folders: vec![],
@@ -11,6 +11,8 @@ pub struct ScalaConfig<'a> {
     pub format: &'a str,
     pub version_format: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub style: &'a str,
     pub symbol: &'a str,
     pub detect_extensions: Vec<&'a str>,
@@ -24,6 +26,7 @@ impl<'a> Default for ScalaConfig<'a> {
             format: "via [$symbol($version )]($style)",
             version_format: "v${raw}",
             disabled: false,
+            priority: None,
             style: "red bold",
             symbol: "🆂 ",
             detect_extensions: vec!["sbt", "scala"],
@@ -22,8 +22,12 @@ pub struct ShellConfig<'a> {
     pub xonsh_indicator: &'a str,
     pub cmd_indicator: &'a str,
     pub unknown_indicator: &'a str,
+    pub version_format: &'a str,
+    pub level_threshold: i64,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
 }
 
 impl<'a> Default for ShellConfig<'a> {
@@ -42,8 +46,11 @@ impl<'a> Default for ShellConfig<'a> {
             xonsh_indicator: "xsh",
             cmd_indicator: "cmd",
             unknown_indicator: "",
+            version_format: "v${raw}",
+            level_threshold: 0,
             style: "white bold",
             disabled: true,
+            priority: None,
         }
     }
 }
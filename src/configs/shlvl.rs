@@ -15,6 +15,8 @@ pub struct ShLvlConfig<'a> {
     pub repeat_offset: u64,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
 }
 
 impl<'a> Default for ShLvlConfig<'a> {
@@ -27,6 +29,7 @@ impl<'a> Default for ShLvlConfig<'a> {
             repeat_offset: 0,
             style: "bold yellow",
             disabled: true,
+            priority: None,
         }
     }
 }
@@ -12,6 +12,8 @@ pub struct SingularityConfig<'a> {
     pub format: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
 }
 
 impl<'a> Default for SingularityConfig<'a> {
@@ -21,6 +23,7 @@ impl<'a> Default for SingularityConfig<'a> {
             symbol: "",
             style: "blue bold dimmed",
             disabled: false,
+            priority: None,
         }
     }
 }
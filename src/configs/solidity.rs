@@ -14,6 +14,8 @@ pub struct SolidityConfig<'a> {
     pub format: &'a str,
     pub version_format: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub style: &'a str,
     pub symbol: &'a str,
     pub compiler: VecOr<&'a str>,
@@ -31,6 +33,7 @@ impl<'a> Default for SolidityConfig<'a> {
             compiler: VecOr(vec!["solc"]),
             version_format: "v${major}.${minor}.${patch}",
             disabled: false,
+            priority: None,
             detect_extensions: vec!["sol"],
             detect_files: vec![],
             detect_folders: vec![],
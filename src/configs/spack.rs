@@ -13,6 +13,8 @@ pub struct SpackConfig<'a> {
     pub symbol: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
 }
 
 impl<'a> Default for SpackConfig<'a> {
@@ -23,6 +25,7 @@ impl<'a> Default for SpackConfig<'a> {
             symbol: "🅢 ",
             style: "blue bold",
             disabled: false,
+            priority: None,
         }
     }
 }
@@ -15,10 +15,26 @@ pub struct StarshipRootConfig {
     pub format: String,
     pub right_format: String,
     pub continuation_prompt: String,
+    pub status_bar_format: String,
     pub scan_timeout: u64,
     pub command_timeout: u64,
     pub add_newline: bool,
     pub follow_symlinks: bool,
+    pub power_save: String,
+    pub power_save_modules: Vec<String>,
+    pub locale: String,
+    pub language: String,
+    pub timezone: String,
+    pub charset: String,
+    pub color_depth: String,
+    pub color_fallbacks: HashMap<String, String>,
+    pub accessibility: bool,
+    pub record_stats: bool,
+    pub cache_versions: bool,
+    pub prompt_cache: bool,
+    pub module_cache: HashMap<String, u64>,
+    pub mask_secrets: bool,
+    pub max_prompt_width: usize,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub palette: Option<String>,
     pub palettes: HashMap<String, Palette>,
@@ -31,14 +47,17 @@ pub type Palette = HashMap<String, String>;
 // NOTE: If this const value is changed then Default prompt order subheading inside
 // prompt heading of config docs needs to be updated according to changes made here.
 pub const PROMPT_ORDER: &[&str] = &[
+    "environment",
     "username",
     "hostname",
     "localip",
+    "ci",
     "shlvl",
     "singularity",
     "kubernetes",
     "nats",
     "directory",
+    "immutable",
     "vcsh",
     "fossil_branch",
     "fossil_metrics",
@@ -47,8 +66,12 @@ pub const PROMPT_ORDER: &[&str] = &[
     "git_state",
     "git_metrics",
     "git_status",
+    "repo_remote",
+    "ticket",
     "hg_branch",
+    "hg_status",
     "pijul_channel",
+    "pre_commit",
     "docker_context",
     "package",
     // ↓ Toolchain version modules ↓
@@ -57,6 +80,7 @@ pub const PROMPT_ORDER: &[&str] = &[
     "c",
     "cmake",
     "cobol",
+    "cpp",
     "daml",
     "dart",
     "deno",
@@ -100,11 +124,16 @@ pub const PROMPT_ORDER: &[&str] = &[
     "vlang",
     "vagrant",
     "zig",
+    "formatter_tools",
     // ↑ Toolchain version modules ↑
     "buf",
+    "build_staleness",
+    "tests",
+    "todos",
     "guix_shell",
     "nix_shell",
     "conda",
+    "python_env",
     "meson",
     "spack",
     "memory_usage",
@@ -113,9 +142,11 @@ pub const PROMPT_ORDER: &[&str] = &[
     "openstack",
     "azure",
     "direnv",
+    "dotenv",
     "env_var",
     "crystal",
     "custom",
+    "annotations",
     "sudo",
     "cmd_duration",
     "line_break",
@@ -126,6 +157,7 @@ pub const PROMPT_ORDER: &[&str] = &[
     "status",
     "container",
     "os",
+    "repl",
     "shell",
     "character",
 ];
@@ -138,11 +170,31 @@ impl Default for StarshipRootConfig {
             format: "$all".to_string(),
             right_format: String::new(),
             continuation_prompt: "[∙](bright-black) ".to_string(),
+            status_bar_format: String::new(),
             profiles: Default::default(),
             scan_timeout: 30,
             command_timeout: 500,
             add_newline: true,
             follow_symlinks: true,
+            power_save: "off".to_string(),
+            power_save_modules: vec![
+                "git_metrics".to_string(),
+                "git_status".to_string(),
+                "package".to_string(),
+            ],
+            locale: String::new(),
+            language: String::new(),
+            timezone: String::new(),
+            charset: String::new(),
+            color_depth: String::new(),
+            color_fallbacks: HashMap::new(),
+            accessibility: false,
+            record_stats: false,
+            cache_versions: true,
+            prompt_cache: false,
+            module_cache: HashMap::new(),
+            mask_secrets: false,
+            max_prompt_width: 0,
             palette: None,
             palettes: HashMap::default(),
         }
@@ -24,6 +24,8 @@ pub struct StatusConfig<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pipestatus_segment_format: Option<&'a str>,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
 }
 
 impl<'a> Default for StatusConfig<'a> {
@@ -45,6 +47,7 @@ impl<'a> Default for StatusConfig<'a> {
                 "\\[$pipestatus\\] => [$symbol$common_meaning$signal_name$maybe_int]($style)",
             pipestatus_segment_format: None,
             disabled: true,
+            priority: None,
         }
     }
 }
@@ -12,7 +12,10 @@ pub struct SudoConfig<'a> {
     pub symbol: &'a str,
     pub style: &'a str,
     pub allow_windows: bool,
+    pub timeout: u64,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
 }
 
 impl<'a> Default for SudoConfig<'a> {
@@ -22,7 +25,9 @@ impl<'a> Default for SudoConfig<'a> {
             symbol: "🧙 ",
             style: "bold blue",
             allow_windows: false,
+            timeout: 15,
             disabled: true,
+            priority: None,
         }
     }
 }
@@ -12,7 +12,10 @@ pub struct SwiftConfig<'a> {
     pub version_format: &'a str,
     pub symbol: &'a str,
     pub style: &'a str,
+    pub not_capable_style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
     pub detect_folders: Vec<&'a str>,
@@ -25,7 +28,9 @@ impl<'a> Default for SwiftConfig<'a> {
             version_format: "v${raw}",
             symbol: "🐦 ",
             style: "bold 202",
+            not_capable_style: "bold red",
             disabled: false,
+            priority: None,
             detect_extensions: vec!["swift"],
             detect_files: vec!["Package.swift"],
             detect_folders: vec![],
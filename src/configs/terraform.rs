@@ -12,7 +12,11 @@ pub struct TerraformConfig<'a> {
     pub version_format: &'a str,
     pub symbol: &'a str,
     pub style: &'a str,
+    pub not_capable_style: &'a str,
+    pub plan_file: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
     pub detect_folders: Vec<&'a str>,
@@ -25,7 +29,10 @@ impl<'a> Default for TerraformConfig<'a> {
             version_format: "v${raw}",
             symbol: "💠 ",
             style: "bold 105",
+            not_capable_style: "bold red",
+            plan_file: ".terraform/plan.json",
             disabled: false,
+            priority: None,
             detect_extensions: vec!["tf", "tfplan", "tfstate"],
             detect_files: vec![],
             detect_folders: vec![".terraform"],
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct TestsConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub failed_style: &'a str,
+    pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+    pub artifacts: Vec<&'a str>,
+}
+
+impl<'a> Default for TestsConfig<'a> {
+    fn default() -> Self {
+        TestsConfig {
+            format: "[$symbol(✓$passed )(✗$failed )]($style)",
+            symbol: "🧪 ",
+            style: "bold green",
+            failed_style: "bold red",
+            disabled: true,
+            priority: None,
+            artifacts: vec![
+                "target/nextest/*/junit.xml",
+                ".pytest_cache/v/cache/lastfailed",
+                "junit.xml",
+                "test-results/*.xml",
+                "build/test-results/test/*.xml",
+                "target/surefire-reports/*.xml",
+            ],
+        }
+    }
+}
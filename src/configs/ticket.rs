@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct TicketConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub pattern: &'a str,
+    pub sources: Vec<&'a str>,
+    pub url: &'a str,
+    pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+}
+
+impl<'a> Default for TicketConfig<'a> {
+    fn default() -> Self {
+        TicketConfig {
+            format: "for [$symbol$ticket]($style) ",
+            symbol: "🎫 ",
+            style: "bold cyan",
+            pattern: r"[A-Z][A-Z0-9]+-\d+",
+            sources: vec!["branch", "commit"],
+            url: "",
+            disabled: true,
+            priority: None,
+        }
+    }
+}
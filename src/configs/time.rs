@@ -10,10 +10,13 @@ use serde::{Deserialize, Serialize};
 pub struct TimeConfig<'a> {
     pub format: &'a str,
     pub style: &'a str,
-    pub use_12hr: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_12hr: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub time_format: Option<&'a str>,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub utc_time_offset: &'a str,
     pub time_range: &'a str,
 }
@@ -23,9 +26,10 @@ impl<'a> Default for TimeConfig<'a> {
         TimeConfig {
             format: "at [$time]($style) ",
             style: "bold yellow",
-            use_12hr: false,
+            use_12hr: None,
             time_format: None,
             disabled: true,
+            priority: None,
             utc_time_offset: "local",
             time_range: "-",
         }
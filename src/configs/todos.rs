@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "config-schema",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+#[serde(default)]
+pub struct TodosConfig<'a> {
+    pub format: &'a str,
+    pub symbol: &'a str,
+    pub style: &'a str,
+    pub markers: Vec<&'a str>,
+    pub threshold: u64,
+    pub max_file_size: u64,
+    pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+}
+
+impl<'a> Default for TodosConfig<'a> {
+    fn default() -> Self {
+        TodosConfig {
+            format: "[$symbol$count]($style) ",
+            symbol: "📝 ",
+            style: "bold yellow",
+            markers: vec!["TODO", "FIXME"],
+            threshold: 1,
+            max_file_size: 1024 * 1024,
+            disabled: true,
+            priority: None,
+        }
+    }
+}
@@ -13,6 +13,8 @@ pub struct TypstConfig<'a> {
     pub symbol: &'a str,
     pub style: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub detect_extensions: Vec<&'a str>,
     pub detect_files: Vec<&'a str>,
     pub detect_folders: Vec<&'a str>,
@@ -26,6 +28,7 @@ impl<'a> Default for TypstConfig<'a> {
             symbol: "t ",
             style: "bold #0093A7",
             disabled: false,
+            priority: None,
             detect_extensions: vec!["typ"],
             detect_files: vec!["template.typ"],
             detect_folders: vec![],
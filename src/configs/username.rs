@@ -15,6 +15,8 @@ pub struct UsernameConfig<'a> {
     pub style_user: &'a str,
     pub show_always: bool,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
     pub aliases: IndexMap<String, &'a str>,
 }
 
@@ -27,6 +29,7 @@ impl<'a> Default for UsernameConfig<'a> {
             style_user: "yellow bold",
             show_always: false,
             disabled: false,
+            priority: None,
             aliases: IndexMap::new(),
         }
     }
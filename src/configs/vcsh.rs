@@ -12,6 +12,8 @@ pub struct VcshConfig<'a> {
     pub style: &'a str,
     pub format: &'a str,
     pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
 }
 
 impl<'a> Default for VcshConfig<'a> {
@@ -21,6 +23,7 @@ impl<'a> Default for VcshConfig<'a> {
             style: "bold yellow",
             format: "vcsh [$symbol$repo]($style) ",
             disabled: false,
+            priority: None,
         }
     }
 }
@@ -1,11 +1,25 @@
 use crate::config::{ModuleConfig, StarshipConfig};
 use crate::configs::StarshipRootConfig;
+use crate::context_container::{self, Container, ContainerRuntime};
 use crate::context_env::Env;
+use crate::context_fingerprint::Fingerprint;
+use crate::context_locale::{parse_timezone, Locale};
+use crate::context_msys::MsysFlavor;
+use crate::context_power::Power;
+use crate::context_process::ParentProcess;
+use crate::context_project::ProjectType;
+use crate::context_session::SessionType;
+use crate::i18n;
+use crate::local_config;
 use crate::module::Module;
-use crate::utils::{create_command, exec_timeout, read_file, CommandOutput, PathExt};
+use crate::utils::{
+    create_command, exec_timeout, exec_timeout_with_status, read_file, CommandOutput, PathExt,
+};
+use crate::version_cache;
 
 use crate::modules;
 use crate::utils;
+use chrono::{DateTime, FixedOffset, Local, Utc};
 use clap::Parser;
 use gix::{
     repository::Kind,
@@ -13,7 +27,8 @@ use gix::{
     state as git_state, Repository, ThreadSafeRepository,
 };
 use once_cell::sync::OnceCell;
-#[cfg(test)]
+use std::borrow::Cow;
+#[cfg(any(test, feature = "test-utils"))]
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::env;
@@ -23,11 +38,23 @@ use std::fs;
 use std::marker::PhantomData;
 use std::num::ParseIntError;
 use std::path::{Path, PathBuf};
+use std::process::Stdio;
 use std::str::FromStr;
 use std::string::String;
+use std::sync::Mutex;
 use std::time::{Duration, Instant};
 use terminal_size::terminal_size;
 
+/// The variables [`Context::global_variable`] provides to the format string of every module
+pub const GLOBAL_VARIABLES: &[&str] = &[
+    "container",
+    "msys_flavor",
+    "parent_process",
+    "project_type",
+    "session_type",
+    "wsl_distro",
+];
+
 /// Context contains data or common methods that may be used by multiple modules.
 /// The data contained within Context will be relevant to this particular rendering
 /// of the prompt.
@@ -52,9 +79,45 @@ pub struct Context<'a> {
     /// Private field to store Git information for modules who need it
     repo: OnceCell<Repo>,
 
+    /// The container the session is running in, detected on first use
+    container: OnceCell<Option<Container>>,
+
+    /// Whether the current directory is on a Windows drive mounted into WSL, checked on first use
+    windows_drive: OnceCell<bool>,
+
+    /// How the user is connected to this machine, detected on first use
+    session_type: OnceCell<SessionType>,
+
+    /// The processes starship was started from, listed on first use
+    parent_processes: OnceCell<Vec<ParentProcess>>,
+
+    /// The kind of project in the current directory, classified on first use
+    project_type: OnceCell<Option<ProjectType>>,
+
+    /// The power state of the machine, detected on first use
+    power: OnceCell<Power>,
+
+    /// The user's locale, detected on first use
+    locale: OnceCell<Option<Locale>>,
+
+    /// The language of messages, detected on first use
+    language: OnceCell<Option<Locale>>,
+
+    /// Modules that logged warnings or errors while they were computed
+    failed_modules: Mutex<Vec<String>>,
+
+    /// How long each computed module took, in the order they finished
+    module_durations: Mutex<Vec<(String, Duration)>>,
+
+    /// Modules that wait for a command running in the background
+    pending_modules: Mutex<Vec<String>>,
+
     /// The shell the user is assumed to be running
     pub shell: Shell,
 
+    /// The POSIX emulation layer the shell runs in on Windows, e.g. Git Bash
+    pub msys_flavor: Option<MsysFlavor>,
+
     /// Which prompt to print (main, right, ...)
     pub target: Target,
 
@@ -65,11 +128,11 @@ pub struct Context<'a> {
     pub env: Env<'a>,
 
     /// A `HashMap` of command mocks
-    #[cfg(test)]
+    #[cfg(any(test, feature = "test-utils"))]
     pub cmd: HashMap<&'a str, Option<CommandOutput>>,
 
     /// a mock of the root directory
-    #[cfg(test)]
+    #[cfg(any(test, feature = "test-utils"))]
     pub root_dir: tempfile::TempDir,
 
     #[cfg(feature = "battery")]
@@ -127,7 +190,7 @@ impl<'a> Context<'a> {
         logical_path: PathBuf,
         env: Env<'a>,
     ) -> Self {
-        let config = StarshipConfig::initialize(&get_config_path_os(&env));
+        let mut config = StarshipConfig::initialize(&get_config_path_os(&env));
 
         // If the vector is zero-length, we should pretend that we didn't get a
         // pipestatus at all (since this is the input `--pipestatus=""`)
@@ -148,16 +211,36 @@ impl<'a> Context<'a> {
             properties.status_code = None;
         }
 
+        // Shells like Git Bash report `/c/Users/...`, which starship cannot access as a
+        // native Windows program
+        let msys_flavor = if cfg!(windows) {
+            MsysFlavor::detect(&env, &logical_path)
+        } else {
+            None
+        };
+        let (path, logical_path) = match msys_flavor {
+            Some(flavor) => (
+                flavor.to_windows_path(path),
+                flavor.to_windows_path(logical_path),
+            ),
+            None => (path, logical_path),
+        };
+
         // Canonicalize the current path to resolve symlinks, etc.
         // NOTE: On Windows this may convert the path to extended-path syntax.
         let current_dir = Context::expand_tilde(path);
         let current_dir = dunce::canonicalize(&current_dir).unwrap_or(current_dir);
         let logical_dir = logical_path;
 
+        if let Some(local) = local_config::load(&current_dir, &env) {
+            config.apply_local_config(local);
+        }
+
         let root_config = config
             .config
             .as_ref()
             .map_or_else(StarshipRootConfig::default, StarshipRootConfig::load);
+        config.apply_charset(&root_config.charset);
 
         let width = properties.terminal_width;
 
@@ -168,13 +251,25 @@ impl<'a> Context<'a> {
             logical_dir,
             dir_contents: OnceCell::new(),
             repo: OnceCell::new(),
+            container: OnceCell::new(),
+            windows_drive: OnceCell::new(),
+            session_type: OnceCell::new(),
+            parent_processes: OnceCell::new(),
+            project_type: OnceCell::new(),
+            power: OnceCell::new(),
+            locale: OnceCell::new(),
+            language: OnceCell::new(),
+            failed_modules: Mutex::new(Vec::new()),
+            module_durations: Mutex::new(Vec::new()),
+            pending_modules: Mutex::new(Vec::new()),
             shell,
+            msys_flavor,
             target,
             width,
             env,
-            #[cfg(test)]
+            #[cfg(any(test, feature = "test-utils"))]
             root_dir: tempfile::TempDir::new().unwrap(),
-            #[cfg(test)]
+            #[cfg(any(test, feature = "test-utils"))]
             cmd: HashMap::new(),
             #[cfg(feature = "battery")]
             battery_info_provider: &crate::modules::BatteryInfoProviderImpl,
@@ -189,6 +284,7 @@ impl<'a> Context<'a> {
         self.config = StarshipConfig {
             config: Some(config),
         };
+        self.config.apply_charset(&self.root_config.charset);
         self
     }
 
@@ -197,18 +293,41 @@ impl<'a> Context<'a> {
         home_dir(&self.env)
     }
 
+    /// The directory starship caches data in, `$STARSHIP_CACHE` or `~/.cache/starship`
+    pub fn get_cache_dir(&self) -> Option<PathBuf> {
+        match self.get_env("STARSHIP_CACHE") {
+            Some(cache) => Some(PathBuf::from(cache)),
+            None => Some(self.get_home()?.join(".cache").join("starship")),
+        }
+    }
+
     // Retrieves a environment variable from the os or from a table if in testing mode
     #[inline]
     pub fn get_env<K: AsRef<str>>(&self, key: K) -> Option<String> {
         self.env.get_env(key)
     }
 
+    /// Whether the environment variable is set to a non-empty value
+    pub fn is_env_set<K: AsRef<str>>(&self, key: K) -> bool {
+        self.get_env(key).map_or(false, |value| !value.is_empty())
+    }
+
     // Retrieves a environment variable from the os or from a table if in testing mode (os version)
     #[inline]
     pub fn get_env_os<K: AsRef<str>>(&self, key: K) -> Option<OsString> {
         self.env.get_env_os(key)
     }
 
+    /// Starts a fingerprint of the inputs a cached value depends on
+    pub fn fingerprint(&self) -> Fingerprint<'_, 'a> {
+        Fingerprint::new(self)
+    }
+
+    /// All environment variables, sorted by name, from the os or from a table if in testing mode
+    pub fn env_vars(&self) -> Vec<(OsString, OsString)> {
+        self.env.vars()
+    }
+
     /// Convert a `~` in a path to the home directory
     pub fn expand_tilde(dir: PathBuf) -> PathBuf {
         if dir.starts_with("~") {
@@ -233,7 +352,22 @@ impl<'a> Context<'a> {
         // If the segment has "disabled" set to "true", don't show it
         let disabled = config.and_then(|table| table.as_table()?.get("disabled")?.as_bool());
 
-        disabled == Some(true)
+        disabled == Some(true) || self.is_module_disabled_by_power_save(name)
+    }
+
+    /// Returns the `priority` of the module, by which it is shrunk when the prompt is too wide
+    pub fn module_priority(&self, name: &str) -> Option<i64> {
+        let path: Vec<&str> = name.split('.').chain(["priority"]).collect();
+        self.config.get_config(&path)?.as_integer()
+    }
+
+    /// Returns true if the module is listed in `power_save_modules` and power saving is active
+    fn is_module_disabled_by_power_save(&self, name: &str) -> bool {
+        self.root_config
+            .power_save_modules
+            .iter()
+            .any(|module| module == name)
+            && self.power_save()
     }
 
     /// Returns true when a negated environment variable is defined in `env_vars` and is present
@@ -361,6 +495,161 @@ impl<'a> Context<'a> {
             })
     }
 
+    /// Detects the container runtime on first use, see [`Container::detect`].
+    pub fn container(&self) -> Option<&Container> {
+        self.container
+            .get_or_init(|| Container::detect(self))
+            .as_ref()
+    }
+
+    /// Returns the name of the WSL distribution the session is running in, if any
+    pub fn wsl_distro(&self) -> Option<String> {
+        self.container()
+            .filter(|container| container.runtime == ContainerRuntime::Wsl)
+            .map(Container::display_name)
+    }
+
+    /// Whether the current directory is on a Windows drive mounted into WSL, where file
+    /// system access is slow
+    pub fn on_windows_drive(&self) -> bool {
+        *self.windows_drive.get_or_init(|| {
+            self.wsl_distro().is_some()
+                && context_container::is_windows_drive(self, &self.current_dir)
+        })
+    }
+
+    /// Classifies the session on first use, see [`SessionType::detect`].
+    pub fn session_type(&self) -> SessionType {
+        *self.session_type.get_or_init(|| SessionType::detect(self))
+    }
+
+    /// Lists the processes starship was started from on first use, its parent first,
+    /// see [`ParentProcess::detect_chain`].
+    pub fn parent_processes(&self) -> &[ParentProcess] {
+        self.parent_processes
+            .get_or_init(|| ParentProcess::detect_chain(self))
+    }
+
+    /// Classifies the current directory on first use, see [`ProjectType::detect`].
+    pub fn project_type(&self) -> Option<ProjectType> {
+        *self
+            .project_type
+            .get_or_init(|| self.dir_contents().ok().and_then(ProjectType::detect))
+    }
+
+    /// Detects the power state on first use, see [`Power::detect`].
+    pub fn power(&self) -> &Power {
+        self.power.get_or_init(|| Power::detect(self))
+    }
+
+    /// Whether expensive work should be skipped to save energy, according to `power_save`:
+    /// `on` always saves power, `auto` only on battery or in low power mode.
+    pub fn power_save(&self) -> bool {
+        match self.root_config.power_save.as_str() {
+            "on" => true,
+            "auto" => {
+                let power = self.power();
+                power.on_battery() || power.low_power_mode
+            }
+            "off" => false,
+            other => {
+                log::warn!("Unknown power_save mode {other:?}, expected on, off or auto");
+                false
+            }
+        }
+    }
+
+    /// Reads the locale on first use, see [`Locale::detect`].
+    pub fn locale(&self) -> Option<&Locale> {
+        self.locale.get_or_init(|| Locale::detect(self)).as_ref()
+    }
+
+    /// Writes a number and its unit, like `35%`, the way the configured locale does
+    pub fn format_number(&self, number: &str, unit: &str) -> String {
+        match self.locale() {
+            Some(locale) => locale.format_number(number, unit),
+            None => format!("{number}{unit}"),
+        }
+    }
+
+    /// Writes a duration, like `1h2m3s`, the way the configured locale does
+    pub fn format_duration(&self, millis: u128, show_millis: bool) -> String {
+        match self.locale() {
+            Some(locale) => locale.format_duration(millis, show_millis),
+            None => utils::render_time(millis, show_millis),
+        }
+    }
+
+    fn language(&self) -> Option<&str> {
+        self.language
+            .get_or_init(|| Locale::detect_language(self))
+            .as_ref()
+            .map(|locale| locale.language.as_str())
+    }
+
+    /// Returns the built-in message `id` in the language of the user,
+    /// see [`Locale::detect_language`] and [`i18n::message`].
+    pub fn message(&self, id: &str) -> String {
+        i18n::message(self.language(), id)
+    }
+
+    /// Translates a configured message while it is the default English text of the
+    /// built-in message `id`, see [`i18n::translate`].
+    pub fn translate<'b>(&self, id: &str, configured: &'b str) -> Cow<'b, str> {
+        i18n::translate(self.language(), id, configured)
+    }
+
+    /// Masks secrets, like API tokens, in untrusted text a module is about to render, when
+    /// `mask_secrets` is enabled.
+    pub fn mask_secrets<'b>(&self, module: &str, text: &'b str) -> Cow<'b, str> {
+        if !self.root_config.mask_secrets {
+            return Cow::Borrowed(text);
+        }
+        match utils::mask_secrets(text) {
+            Some(masked) => {
+                log::warn!("Masked a secret in the output of module `{module}`");
+                Cow::Owned(masked)
+            }
+            None => Cow::Borrowed(text),
+        }
+    }
+
+    /// Returns the current time in the timezone selected by the `timezone` option,
+    /// or in the local timezone if it is unset.
+    pub fn now(&self) -> DateTime<FixedOffset> {
+        let now = Utc::now();
+        let timezone = &self.root_config.timezone;
+        match parse_timezone(timezone) {
+            Some(offset) => now.with_timezone(&offset),
+            None => {
+                if !matches!(timezone.as_str(), "" | "local") {
+                    log::warn!("Invalid timezone {timezone:?}, falling back to the local timezone");
+                }
+                now.with_timezone(&Local).fixed_offset()
+            }
+        }
+    }
+
+    /// Returns the value of a variable that can be used in the format string of any module.
+    ///
+    /// Variables provided by the module itself take precedence.
+    pub fn global_variable(&self, name: &str) -> Option<String> {
+        match name {
+            "container" => self.container().map(Container::display_name),
+            "msys_flavor" => self.msys_flavor.map(|flavor| flavor.to_string()),
+            "parent_process" => self
+                .parent_processes()
+                .first()
+                .map(|process| process.name.clone()),
+            "session_type" => Some(self.session_type().to_string()),
+            "wsl_distro" => self.wsl_distro(),
+            "project_type" => self
+                .project_type()
+                .map(|project_type| project_type.to_string()),
+            _ => None,
+        }
+    }
+
     pub fn dir_contents(&self) -> Result<&DirContents, std::io::Error> {
         self.dir_contents.get_or_try_init(|| {
             let timeout = self.root_config.scan_timeout;
@@ -373,21 +662,7 @@ impl<'a> Context<'a> {
     }
 
     fn get_shell() -> Shell {
-        let shell = env::var("STARSHIP_SHELL").unwrap_or_default();
-        match shell.as_str() {
-            "bash" => Shell::Bash,
-            "fish" => Shell::Fish,
-            "ion" => Shell::Ion,
-            "pwsh" => Shell::Pwsh,
-            "powershell" => Shell::PowerShell,
-            "zsh" => Shell::Zsh,
-            "elvish" => Shell::Elvish,
-            "tcsh" => Shell::Tcsh,
-            "nu" => Shell::Nu,
-            "xonsh" => Shell::Xonsh,
-            "cmd" => Shell::Cmd,
-            _ => Shell::Unknown,
-        }
+        Shell::from_name(&env::var("STARSHIP_SHELL").unwrap_or_default())
     }
 
     // TODO: This should be used directly by clap parse
@@ -398,6 +673,52 @@ impl<'a> Context<'a> {
             .and_then(|cd| cd.parse::<u128>().ok())
     }
 
+    /// Records that a module logged warnings or errors while it was computed
+    pub fn add_failed_module(&self, name: &str) {
+        if let Ok(mut failed_modules) = self.failed_modules.lock() {
+            failed_modules.push(name.to_string());
+        }
+    }
+
+    /// Returns the modules that logged warnings or errors so far, in the order they finished
+    pub fn failed_modules(&self) -> Vec<String> {
+        self.failed_modules
+            .lock()
+            .map(|failed_modules| failed_modules.clone())
+            .unwrap_or_default()
+    }
+
+    /// Records how long a module took to compute
+    pub fn add_module_duration(&self, name: &str, duration: Duration) {
+        if let Ok(mut module_durations) = self.module_durations.lock() {
+            module_durations.push((name.to_string(), duration));
+        }
+    }
+
+    /// Returns how long each module computed so far took, in the order they finished
+    pub fn module_durations(&self) -> Vec<(String, Duration)> {
+        self.module_durations
+            .lock()
+            .map(|module_durations| module_durations.clone())
+            .unwrap_or_default()
+    }
+
+    /// Records that a module waits for a command running in the background, so the shell can
+    /// animate the prompt until it is done
+    pub fn add_pending_module(&self, name: &str) {
+        if let Ok(mut pending_modules) = self.pending_modules.lock() {
+            pending_modules.push(name.to_string());
+        }
+    }
+
+    /// Returns the modules that wait for a command running in the background
+    pub fn pending_modules(&self) -> Vec<String> {
+        self.pending_modules
+            .lock()
+            .map(|pending_modules| pending_modules.clone())
+            .unwrap_or_default()
+    }
+
     /// Execute a command and return the output on stdout and stderr if successful
     #[inline]
     pub fn exec_cmd<T: AsRef<OsStr> + Debug, U: AsRef<OsStr> + Debug>(
@@ -410,15 +731,13 @@ impl<'a> Context<'a> {
             cmd,
             args
         );
-        #[cfg(test)]
+        #[cfg(any(test, feature = "test-utils"))]
         {
             let command = crate::utils::display_command(&cmd, args);
-            if let Some(output) = self
-                .cmd
-                .get(command.as_str())
-                .cloned()
-                .or_else(|| crate::utils::mock_cmd(&cmd, args))
-            {
+            let mocked = self.cmd.get(command.as_str()).cloned();
+            #[cfg(test)]
+            let mocked = mocked.or_else(|| crate::utils::mock_cmd(&cmd, args));
+            if let Some(output) = mocked {
                 return output;
             }
         }
@@ -430,6 +749,157 @@ impl<'a> Context<'a> {
         )
     }
 
+    /// Execute a command that answers with its exit code, like `make -q`, and return it with the
+    /// output, within `time_limit` instead of `command_timeout`
+    ///
+    /// A mocked command that failed exits with `1`.
+    pub fn exec_cmd_with_status<T: AsRef<OsStr> + Debug, U: AsRef<OsStr> + Debug>(
+        &self,
+        cmd: T,
+        args: &[U],
+        time_limit: Duration,
+    ) -> Option<(Option<i64>, CommandOutput)> {
+        log::trace!(
+            "Executing command {:?} with args {:?} from context",
+            cmd,
+            args
+        );
+        #[cfg(any(test, feature = "test-utils"))]
+        {
+            let command = crate::utils::display_command(&cmd, args);
+            let mocked = self.cmd.get(command.as_str()).cloned();
+            #[cfg(test)]
+            let mocked = mocked.or_else(|| crate::utils::mock_cmd(&cmd, args));
+            if let Some(output) = mocked {
+                return Some(match output {
+                    Some(output) => (Some(0), output),
+                    None => (
+                        Some(1),
+                        CommandOutput {
+                            stdout: String::new(),
+                            stderr: String::new(),
+                        },
+                    ),
+                });
+            }
+        }
+        let mut cmd = create_command(cmd).ok()?;
+        cmd.args(args).current_dir(&self.current_dir);
+        exec_timeout_with_status(&mut cmd, time_limit)
+    }
+
+    /// Start a command in the background, writing its output on stdout to `output`, without
+    /// waiting for it to finish, for slow commands whose answer can wait until the next prompt
+    ///
+    /// Mocked commands write their output right away, a mocked command that failed none.
+    pub fn spawn_cmd<T: AsRef<OsStr> + Debug, U: AsRef<OsStr> + Debug>(
+        &self,
+        cmd: T,
+        args: &[U],
+        output: &Path,
+    ) -> std::io::Result<()> {
+        log::trace!(
+            "Starting command {:?} with args {:?} in the background",
+            cmd,
+            args
+        );
+        #[cfg(any(test, feature = "test-utils"))]
+        {
+            let command = crate::utils::display_command(&cmd, args);
+            let mocked = self.cmd.get(command.as_str()).cloned();
+            #[cfg(test)]
+            let mocked = mocked.or_else(|| crate::utils::mock_cmd(&cmd, args));
+            if let Some(mocked) = mocked {
+                return fs::write(
+                    output,
+                    mocked.map(|output| output.stdout).unwrap_or_default(),
+                );
+            }
+        }
+        let output = fs::File::create(output)?;
+        let mut cmd = create_command(cmd)?;
+        cmd.args(args)
+            .current_dir(&self.current_dir)
+            .stdin(Stdio::null())
+            .stdout(output)
+            .stderr(Stdio::null());
+        // The child keeps running after the prompt is printed and starship exits
+        cmd.spawn().map(drop)
+    }
+
+    /// Execute a command that prints the version of a tool, like `node --version`
+    ///
+    /// Unless `cache_versions` is disabled, the output is cached until the binary is modified,
+    /// so the command only runs again after the tool is updated.
+    pub fn exec_version_cmd<T: AsRef<OsStr> + Debug, U: AsRef<OsStr> + Debug>(
+        &self,
+        cmd: T,
+        args: &[U],
+    ) -> Option<CommandOutput> {
+        // Mocked commands are never cached
+        if cfg!(test) || !self.root_config.cache_versions {
+            return self.exec_cmd(cmd, args);
+        }
+
+        let cached_args: Vec<String> = args
+            .iter()
+            .map(|arg| arg.as_ref().to_string_lossy().into_owned())
+            .collect();
+        let cache_file = which::which(cmd.as_ref())
+            .ok()
+            .and_then(|binary| dunce::canonicalize(binary).ok())
+            .and_then(|binary| version_cache::cache_file(self, &binary, &cached_args));
+        let Some(cache_file) = cache_file else {
+            return self.exec_cmd(cmd, args);
+        };
+
+        if let Some(output) = version_cache::read(&cache_file) {
+            log::trace!("Using cached output of {:?} {:?}", cmd, args);
+            return Some(output);
+        }
+        let output = self.exec_cmd(cmd, args)?;
+        if let Err(error) = version_cache::write(&cache_file, &output) {
+            log::debug!("Unable to cache the version output: {error}");
+        }
+        Some(output)
+    }
+
+    /// Execute a command whose output lists files or directories, like the SDKs of
+    /// `dotnet --list-sdks`, and cache its output until one of the `watched` ones changes
+    ///
+    /// Unlike [`Context::exec_version_cmd`], the binary isn't looked up on a cached run. Outputs
+    /// without files to watch are not cached.
+    pub fn exec_watching_cmd(
+        &self,
+        cmd: &str,
+        args: &[&str],
+        watched: impl Fn(&CommandOutput) -> Vec<PathBuf>,
+    ) -> Option<CommandOutput> {
+        // Mocked commands are never cached
+        if cfg!(test) || !self.root_config.cache_versions {
+            return self.exec_cmd(cmd, args);
+        }
+
+        let cached_args: Vec<String> = args.iter().map(ToString::to_string).collect();
+        let Some(cache_file) = version_cache::watching_file(self, cmd, &cached_args) else {
+            return self.exec_cmd(cmd, args);
+        };
+
+        if let Some(output) = version_cache::read_watching(self, &cache_file) {
+            log::trace!("Using cached output of {:?} {:?}", cmd, args);
+            return Some(output);
+        }
+        let output = self.exec_cmd(cmd, args)?;
+        let watched = watched(&output);
+        if watched.is_empty() {
+            return Some(output);
+        }
+        if let Err(error) = version_cache::write_watching(self, &cache_file, &output, &watched) {
+            log::debug!("Unable to cache the output: {error}");
+        }
+        Some(output)
+    }
+
     /// Attempt to execute several commands with `exec_cmd`, return the results of the first that works
     pub fn exec_cmds_return_first(&self, commands: Vec<Vec<&str>>) -> Option<CommandOutput> {
         commands
@@ -460,8 +930,8 @@ impl Default for Context<'_> {
     }
 }
 
-fn home_dir(env: &Env) -> Option<PathBuf> {
-    if cfg!(test) {
+pub(crate) fn home_dir(env: &Env) -> Option<PathBuf> {
+    if cfg!(any(test, feature = "test-utils")) {
         if let Some(home) = env.get_env("HOME") {
             return Some(PathBuf::from(home));
         }
@@ -469,7 +939,7 @@ fn home_dir(env: &Env) -> Option<PathBuf> {
     utils::home_dir()
 }
 
-fn get_config_path_os(env: &Env) -> Option<OsString> {
+pub(crate) fn get_config_path_os(env: &Env) -> Option<OsString> {
     if let Some(config_path) = env.get_env_os("STARSHIP_CONFIG") {
         return Some(config_path);
     }
@@ -829,6 +1299,27 @@ pub enum Shell {
     Unknown,
 }
 
+impl Shell {
+    /// The shell with the given name, like the value of `STARSHIP_SHELL` or the name of the
+    /// shell's executable
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "bash" => Self::Bash,
+            "fish" => Self::Fish,
+            "ion" => Self::Ion,
+            "pwsh" => Self::Pwsh,
+            "powershell" => Self::PowerShell,
+            "zsh" => Self::Zsh,
+            "elvish" => Self::Elvish,
+            "tcsh" => Self::Tcsh,
+            "nu" => Self::Nu,
+            "xonsh" => Self::Xonsh,
+            "cmd" => Self::Cmd,
+            _ => Self::Unknown,
+        }
+    }
+}
+
 /// Which kind of prompt target to print (main prompt, rprompt, ...)
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Target {
@@ -836,10 +1327,11 @@ pub enum Target {
     Right,
     Continuation,
     Profile(String),
+    StatusBar,
 }
 
 /// Properties as passed on from the shell as arguments
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 pub struct Properties {
     /// The status code of the previously run command as an unsigned or signed 32bit integer
     #[clap(short = 's', long = "status")]
@@ -849,14 +1341,14 @@ pub struct Properties {
     pub pipestatus: Option<Vec<String>>,
     /// The width of the current interactive terminal.
     #[clap(short = 'w', long, default_value_t=default_width(), value_parser=parse_width)]
-    terminal_width: usize,
+    pub terminal_width: usize,
     /// The path that the prompt should render for.
     #[clap(short, long)]
-    path: Option<PathBuf>,
+    pub path: Option<PathBuf>,
     /// The logical path that the prompt should render for.
     /// This path should be a virtual/logical representation of the PATH argument.
     #[clap(short = 'P', long)]
-    logical_path: Option<PathBuf>,
+    pub logical_path: Option<PathBuf>,
     /// The execution duration of the last command, in milliseconds
     #[clap(short = 'd', long)]
     pub cmd_duration: Option<String>,
@@ -866,6 +1358,9 @@ pub struct Properties {
     /// The number of currently running jobs
     #[clap(short, long, default_value_t, value_parser=parse_jobs)]
     pub jobs: i64,
+    /// The frame of the pending animation, while the shell waits for an asynchronous refresh
+    #[clap(long)]
+    pub pending_frame: Option<usize>,
 }
 
 impl Default for Properties {
@@ -879,6 +1374,7 @@ impl Default for Properties {
             cmd_duration: None,
             keymap: "viins".to_string(),
             jobs: 0,
+            pending_frame: None,
         }
     }
 }
@@ -1163,6 +1659,18 @@ mod tests {
         assert_ne!(context.config.config, mod_context.config.config);
     }
 
+    #[test]
+    fn power_save_disables_listed_modules() {
+        let context = default_context().set_config(toml::toml! {
+            power_save = "on"
+            power_save_modules = ["git_status"]
+        });
+
+        assert!(context.is_module_disabled_in_config("git_status"));
+        assert!(!context.is_module_disabled_in_config("git_branch"));
+        assert!(!default_context().is_module_disabled_in_config("git_status"));
+    }
+
     #[cfg(windows)]
     #[test]
     fn strip_extended_path_prefix() {
@@ -0,0 +1,325 @@
+use crate::context::Context;
+use std::fmt;
+use std::path::Path;
+
+/// The container runtime starship is running inside of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerRuntime {
+    Docker,
+    Jail,
+    Kubernetes,
+    Lxc,
+    Oci,
+    OpenVz,
+    Podman,
+    Systemd,
+    Wsl,
+    Zone,
+}
+
+impl fmt::Display for ContainerRuntime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Docker => "Docker",
+            Self::Jail => "Jail",
+            Self::Kubernetes => "Kubernetes",
+            Self::Lxc => "LXC",
+            Self::Oci => "OCI",
+            Self::OpenVz => "OpenVZ",
+            Self::Podman => "podman",
+            Self::Systemd => "Systemd",
+            Self::Wsl => "WSL",
+            Self::Zone => "Zone",
+        })
+    }
+}
+
+/// Information about the container the current session is running in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Container {
+    pub runtime: ContainerRuntime,
+
+    /// The name of the container, if exposed by the runtime
+    pub name: Option<String>,
+
+    /// The image the container was created from, if exposed by the runtime
+    pub image: Option<String>,
+}
+
+impl Container {
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "freebsd",
+        target_os = "illumos",
+        target_os = "solaris"
+    ))]
+    const fn new(runtime: ContainerRuntime) -> Self {
+        Self {
+            runtime,
+            name: None,
+            image: None,
+        }
+    }
+
+    /// Returns the most specific name available: the container name, the image name or
+    /// the name of the runtime, in that order.
+    pub fn display_name(&self) -> String {
+        self.name
+            .clone()
+            .or_else(|| {
+                self.image
+                    .as_deref()
+                    .map(|image| image.rsplit('/').next().unwrap_or(image).to_string())
+            })
+            .unwrap_or_else(|| self.runtime.to_string())
+    }
+
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "freebsd",
+        target_os = "illumos",
+        target_os = "solaris"
+    )))]
+    pub fn detect(_context: &Context) -> Option<Self> {
+        None
+    }
+
+    /// Detects whether the session runs inside a FreeBSD jail
+    ///
+    /// The name of a jail cannot be read from inside of it, so its hostname is used instead,
+    /// which is usually set to the name.
+    #[cfg(target_os = "freebsd")]
+    pub fn detect(context: &Context) -> Option<Self> {
+        let output =
+            context.exec_cmd("sysctl", &["-n", "security.jail.jailed", "kern.hostname"])?;
+        let mut lines = output.stdout.lines();
+        if lines.next()?.trim() != "1" {
+            return None;
+        }
+        Some(Self {
+            name: lines
+                .next()
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(ToString::to_string),
+            ..Self::new(ContainerRuntime::Jail)
+        })
+    }
+
+    /// Detects whether the session runs inside a non-global illumos or Solaris zone
+    #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+    pub fn detect(context: &Context) -> Option<Self> {
+        let output = context.exec_cmd("zonename", &[] as &[&str])?;
+        let zone = output.stdout.trim();
+        if zone.is_empty() || zone == "global" {
+            return None;
+        }
+        Some(Self {
+            name: Some(zone.to_string()),
+            ..Self::new(ContainerRuntime::Zone)
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn detect(context: &Context) -> Option<Self> {
+        use crate::utils::{context_path, read_file};
+
+        if context_path(context, "/proc/vz").exists() && !context_path(context, "/proc/bc").exists()
+        {
+            return Some(Self::new(ContainerRuntime::OpenVz));
+        }
+
+        if context_path(context, "/run/host/container-manager").exists() {
+            return Some(Self::new(ContainerRuntime::Oci));
+        }
+
+        let container_env_path = context_path(context, "/run/.containerenv");
+        if container_env_path.exists() {
+            // podman and others
+            let mut container = Self::new(ContainerRuntime::Podman);
+            if let Ok(contents) = read_file(container_env_path) {
+                container.name = containerenv_value(&contents, "name");
+                container.image = containerenv_value(&contents, "image");
+            }
+            return Some(container);
+        }
+
+        // WSL with systemd will set the contents of this file to "wsl"
+        // Honor the contents of this file if "docker" and not running in podman or wsl
+        if let Ok(s) = read_file(context_path(context, "/run/systemd/container")) {
+            match s.trim() {
+                "docker" => return Some(Self::new(ContainerRuntime::Docker)),
+                "wsl" => return Some(wsl(context)),
+                _ => return Some(Self::new(ContainerRuntime::Systemd)),
+            }
+        }
+
+        if context_path(context, "/.dockerenv").exists() {
+            return Some(Self::new(ContainerRuntime::Docker));
+        }
+
+        if let Some(runtime) = read_file(context_path(context, "/proc/1/cgroup"))
+            .ok()
+            .and_then(|cgroup| runtime_from_cgroup(&cgroup))
+        {
+            return Some(Self::new(runtime));
+        }
+
+        let is_wsl = context.get_env("WSL_DISTRO_NAME").is_some()
+            || context.get_env("WSL_INTEROP").is_some()
+            || context_path(context, "/proc/sys/fs/binfmt_misc/WSLInterop").exists();
+        is_wsl.then(|| wsl(context))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn wsl(context: &Context) -> Container {
+    Container {
+        name: context.get_env("WSL_DISTRO_NAME"),
+        ..Container::new(ContainerRuntime::Wsl)
+    }
+}
+
+/// Whether `path` is on a Windows drive mounted into WSL, e.g. `/mnt/c`
+///
+/// Files on these drives are served through the 9P protocol, so scanning them is much slower
+/// than on the Linux file system of the distribution.
+#[cfg(target_os = "linux")]
+pub fn is_windows_drive(context: &Context, path: &Path) -> bool {
+    use crate::utils::{context_path, read_file};
+    use std::path::Component;
+
+    let root = read_file(context_path(context, "/etc/wsl.conf"))
+        .ok()
+        .and_then(|conf| automount_root(&conf))
+        .unwrap_or_else(|| "/mnt/".to_string());
+    let Ok(rest) = path.strip_prefix(root) else {
+        return false;
+    };
+    match rest.components().next() {
+        Some(Component::Normal(drive)) => drive.to_str().map_or(false, |drive| {
+            drive.len() == 1 && drive.chars().all(|c| c.is_ascii_alphabetic())
+        }),
+        _ => false,
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn is_windows_drive(_context: &Context, _path: &Path) -> bool {
+    false
+}
+
+/// Reads the `root` option of the `[automount]` section of `/etc/wsl.conf`
+#[cfg(target_os = "linux")]
+fn automount_root(conf: &str) -> Option<String> {
+    let mut in_automount = false;
+    for line in conf.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_automount = line == "[automount]";
+        } else if in_automount {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            if key.trim() == "root" {
+                let value = value.trim().trim_matches('"');
+                return (!value.is_empty()).then(|| value.to_string());
+            }
+        }
+    }
+    None
+}
+
+/// Reads a `key="value"` entry from a `.containerenv` file
+#[cfg(target_os = "linux")]
+fn containerenv_value(contents: &str, key: &str) -> Option<String> {
+    contents.lines().find_map(|line| {
+        line.strip_prefix(key)?
+            .strip_prefix("=\"")?
+            .strip_suffix('"')
+            .filter(|value| !value.is_empty())
+            .map(ToString::to_string)
+    })
+}
+
+/// Infers the container runtime from the control groups of the init process
+#[cfg(target_os = "linux")]
+fn runtime_from_cgroup(cgroup: &str) -> Option<ContainerRuntime> {
+    cgroup.lines().find_map(|line| {
+        let path = line.splitn(3, ':').nth(2)?;
+        if path.contains("kubepods") {
+            Some(ContainerRuntime::Kubernetes)
+        } else if path.contains("docker") {
+            Some(ContainerRuntime::Docker)
+        } else if path.contains("libpod") {
+            Some(ContainerRuntime::Podman)
+        } else if path.contains("lxc") {
+            Some(ContainerRuntime::Lxc)
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_name_prefers_name_over_image() {
+        let container = Container {
+            runtime: ContainerRuntime::Podman,
+            name: Some("my-fedora".to_string()),
+            image: Some("registry.fedoraproject.org/fedora-toolbox:35".to_string()),
+        };
+        assert_eq!(container.display_name(), "my-fedora");
+
+        let container = Container {
+            name: None,
+            ..container
+        };
+        assert_eq!(container.display_name(), "fedora-toolbox:35");
+
+        let container = Container {
+            image: None,
+            ..container
+        };
+        assert_eq!(container.display_name(), "podman");
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn containerenv_values() {
+        let contents =
+            "engine=\"podman-4.0.0\"\nname=\"\"\nimage=\"docker.io/library/alpine:latest\"\n";
+        assert_eq!(containerenv_value(contents, "name"), None);
+        assert_eq!(
+            containerenv_value(contents, "image"),
+            Some("docker.io/library/alpine:latest".to_string())
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn wsl_conf_automount_root() {
+        let conf = "[boot]\nsystemd=true\n\n[automount]\nenabled = true\nroot = \"/win/\"\n";
+        assert_eq!(automount_root(conf), Some("/win/".to_string()));
+        assert_eq!(automount_root("[boot]\nroot = /win/\n"), None);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn cgroup_runtimes() {
+        assert_eq!(
+            runtime_from_cgroup("12:pids:/docker/3601745b3bd5\n0::/"),
+            Some(ContainerRuntime::Docker)
+        );
+        assert_eq!(
+            runtime_from_cgroup("0::/kubepods/besteffort/pod1234/abcd"),
+            Some(ContainerRuntime::Kubernetes)
+        );
+        assert_eq!(
+            runtime_from_cgroup("0::/init.scope\n1:name=systemd:/"),
+            None
+        );
+    }
+}
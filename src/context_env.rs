@@ -1,47 +1,66 @@
-#[cfg(test)]
+#[cfg(any(test, feature = "test-utils"))]
 use std::collections::HashMap;
-#[cfg(not(test))]
+#[cfg(not(any(test, feature = "test-utils")))]
 use std::env;
 use std::ffi::OsString;
 
 #[derive(Default)]
 pub struct Env<'a> {
     /// A `HashMap` of environment variable mocks
-    #[cfg(test)]
+    #[cfg(any(test, feature = "test-utils"))]
     pub env: HashMap<&'a str, String>,
 
-    #[cfg(not(test))]
+    #[cfg(not(any(test, feature = "test-utils")))]
     _marker: std::marker::PhantomData<&'a ()>,
 }
 
 impl<'a> Env<'a> {
     // Retrieves a environment variable from the os or from a table if in testing mode
-    #[cfg(test)]
+    #[cfg(any(test, feature = "test-utils"))]
     pub fn get_env<K: AsRef<str>>(&self, key: K) -> Option<String> {
         self.env
             .get(key.as_ref())
             .map(std::string::ToString::to_string)
     }
 
-    #[cfg(not(test))]
+    #[cfg(not(any(test, feature = "test-utils")))]
     #[inline]
     pub fn get_env<K: AsRef<str>>(&self, key: K) -> Option<String> {
         env::var(key.as_ref()).ok()
     }
 
     // Retrieves a environment variable from the os or from a table if in testing mode (os version)
-    #[cfg(test)]
+    #[cfg(any(test, feature = "test-utils"))]
     pub fn get_env_os<K: AsRef<str>>(&self, key: K) -> Option<OsString> {
         self.env.get(key.as_ref()).map(OsString::from)
     }
 
-    #[cfg(not(test))]
+    #[cfg(not(any(test, feature = "test-utils")))]
     #[inline]
     pub fn get_env_os<K: AsRef<str>>(&self, key: K) -> Option<OsString> {
         env::var_os(key.as_ref())
     }
 
-    #[cfg(test)]
+    // Retrieves all environment variables, sorted by name
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn vars(&self) -> Vec<(OsString, OsString)> {
+        let mut vars: Vec<(OsString, OsString)> = self
+            .env
+            .iter()
+            .map(|(key, value)| (OsString::from(key), OsString::from(value)))
+            .collect();
+        vars.sort();
+        vars
+    }
+
+    #[cfg(not(any(test, feature = "test-utils")))]
+    pub fn vars(&self) -> Vec<(OsString, OsString)> {
+        let mut vars: Vec<(OsString, OsString)> = env::vars_os().collect();
+        vars.sort();
+        vars
+    }
+
+    #[cfg(any(test, feature = "test-utils"))]
     pub fn insert(&mut self, k: &'a str, v: String) -> Option<String> {
         self.env.insert(k, v)
     }
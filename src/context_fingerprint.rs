@@ -0,0 +1,218 @@
+use sha1::{Digest, Sha1};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::context::{get_config_path_os, Context};
+use crate::local_config;
+use crate::utils::encode_to_hex;
+
+/// A hash of the inputs a cached value was computed from
+///
+/// Each cache declares what its value depends on and reuses the value for as long as the
+/// fingerprint stays the same. Inputs are hashed separately, so `text("ab")` and
+/// `text("a").text("b")` do not collide.
+pub struct Fingerprint<'c, 'a> {
+    context: &'c Context<'a>,
+    hasher: Sha1,
+}
+
+impl<'c, 'a> Fingerprint<'c, 'a> {
+    pub fn new(context: &'c Context<'a>) -> Self {
+        Self {
+            context,
+            hasher: Sha1::new(),
+        }
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        self.hasher.update(bytes);
+        self.hasher.update([0]);
+    }
+
+    /// Any other value the cached value depends on, like the arguments of a command
+    #[must_use]
+    pub fn text<T: AsRef<[u8]>>(mut self, text: T) -> Self {
+        self.update(text.as_ref());
+        self
+    }
+
+    /// The physical and logical current directory
+    #[must_use]
+    pub fn cwd(mut self) -> Self {
+        let context = self.context;
+        self.update(context.current_dir.to_string_lossy().as_bytes());
+        self.update(context.logical_dir.to_string_lossy().as_bytes());
+        self
+    }
+
+    /// The value of an environment variable, which may be unset
+    #[must_use]
+    pub fn env(mut self, name: &str) -> Self {
+        let value = self.context.get_env_os(name);
+        self.update(name.as_bytes());
+        self.update(&[u8::from(value.is_some())]);
+        self.update(value.unwrap_or_default().to_string_lossy().as_bytes());
+        self
+    }
+
+    /// All environment variables
+    #[must_use]
+    pub fn all_env(mut self) -> Self {
+        for (name, value) in self.context.env_vars() {
+            // Bash sets `_` to the last argument of the previous command
+            if name != "_" {
+                self.update(name.to_string_lossy().as_bytes());
+                self.update(value.to_string_lossy().as_bytes());
+            }
+        }
+        self
+    }
+
+    /// The modification time of a file or directory, which may not exist
+    #[must_use]
+    pub fn modified(mut self, path: &Path) -> Self {
+        let modified = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok());
+        self.update(path.to_string_lossy().as_bytes());
+        self.update(
+            &modified
+                .map_or(0, |modified| modified.as_nanos())
+                .to_le_bytes(),
+        );
+        self
+    }
+
+    /// The git repository containing the current directory, whose `HEAD` and index change on
+    /// commits, checkouts and staging
+    #[must_use]
+    pub fn repo_head(self) -> Self {
+        git_files(&self.context.current_dir)
+            .iter()
+            .fold(self, |fingerprint, file| fingerprint.modified(file))
+    }
+
+    /// The modification time of the configuration file, and of the `.starship.toml` of the
+    /// repository, which is applied on top of it
+    #[must_use]
+    pub fn config(self) -> Self {
+        let fingerprint = match get_config_path_os(&self.context.env) {
+            Some(path) => self.modified(Path::new(&path)),
+            None => self.text(""),
+        };
+        // Allowing the file with `starship allow` doesn't change it, but applies all its settings
+        let local = local_config::find(&fingerprint.context.current_dir);
+        match local {
+            Some(local) => {
+                let is_allowed = local_config::is_file_allowed(&local, &fingerprint.context.env);
+                fingerprint.modified(&local).text([u8::from(is_allowed)])
+            }
+            None => fingerprint.text(""),
+        }
+    }
+
+    /// Returns the fingerprint as a hex string, which can be used as a file name
+    pub fn finish(self) -> String {
+        encode_to_hex(&self.hasher.finalize())
+    }
+}
+
+/// The `HEAD` and index of the git repository containing `dir`
+fn git_files(dir: &Path) -> Vec<PathBuf> {
+    for dir in dir.ancestors() {
+        let dot_git = dir.join(".git");
+        let git_dir = if dot_git.is_dir() {
+            dot_git
+        } else if dot_git.is_file() {
+            // Worktrees and submodules point to their git directory
+            let Some(git_dir) = fs::read_to_string(&dot_git).ok().and_then(|contents| {
+                let path = contents.strip_prefix("gitdir:")?.trim();
+                Some(dir.join(path))
+            }) else {
+                continue;
+            };
+            git_dir
+        } else {
+            continue;
+        };
+        return vec![git_dir.join("HEAD"), git_dir.join("index")];
+    }
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::default_context;
+    use std::io;
+
+    #[test]
+    fn inputs_are_separated() {
+        let context = default_context();
+        assert_eq!(
+            Fingerprint::new(&context).text("a").finish(),
+            Fingerprint::new(&context).text("a").finish()
+        );
+        assert_ne!(
+            Fingerprint::new(&context).text("ab").finish(),
+            Fingerprint::new(&context).text("a").text("b").finish()
+        );
+    }
+
+    #[test]
+    fn env_vars() {
+        let mut context = default_context();
+        let unset = Fingerprint::new(&context).env("AWS_PROFILE").finish();
+        context.env.insert("AWS_PROFILE", String::new());
+        let empty = Fingerprint::new(&context).env("AWS_PROFILE").finish();
+        context.env.insert("AWS_PROFILE", "prod".to_string());
+        let set = Fingerprint::new(&context).env("AWS_PROFILE").finish();
+
+        assert_ne!(unset, empty);
+        assert_ne!(empty, set);
+    }
+
+    #[test]
+    fn repo_config() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let data_dir = tempfile::tempdir()?;
+        fs::create_dir_all(dir.path().join(".git"))?;
+        let mut context = default_context();
+        context.current_dir = dir.path().to_path_buf();
+        context.env.insert(
+            "XDG_DATA_HOME",
+            data_dir.path().to_string_lossy().to_string(),
+        );
+        let fingerprint = |context: &Context| Fingerprint::new(context).config().finish();
+
+        let without_config = fingerprint(&context);
+        let file = dir.path().join(local_config::FILE_NAME);
+        fs::write(&file, "format = \"$all\"")?;
+        let with_config = fingerprint(&context);
+        assert_ne!(without_config, with_config);
+
+        local_config::allow(&file, &context.env)?;
+        assert_ne!(with_config, fingerprint(&context));
+        dir.close()?;
+        data_dir.close()
+    }
+
+    #[test]
+    fn finds_git_directory() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let nested = dir.path().join("src");
+        fs::create_dir_all(dir.path().join(".git"))?;
+        fs::create_dir_all(&nested)?;
+
+        assert_eq!(
+            git_files(&nested),
+            vec![
+                dir.path().join(".git").join("HEAD"),
+                dir.path().join(".git").join("index")
+            ]
+        );
+        dir.close()
+    }
+}
@@ -0,0 +1,195 @@
+use crate::context::Context;
+use crate::utils::{render_time, render_time_with_units};
+use chrono::FixedOffset;
+use std::fmt;
+
+/// The user's locale, e.g. `en_US` for `LANG=en_US.UTF-8`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locale {
+    pub language: String,
+    pub region: Option<String>,
+}
+
+impl Locale {
+    /// Reads the locale from the `locale` option.
+    ///
+    /// Unlike the language, the locale isn't detected from `LC_TIME` or `LANG`, so times,
+    /// durations and numbers keep their usual format unless it is configured. Returns `None` for
+    /// the `C` and `POSIX` locales.
+    pub fn detect(context: &Context) -> Option<Self> {
+        Self::parse(&context.root_config.locale)
+    }
+
+    /// Reads the language of messages from the `language` option, falling back to `LC_ALL`,
+    /// `LC_MESSAGES` and `LANG`.
+    pub fn detect_language(context: &Context) -> Option<Self> {
+        let configured = &context.root_config.language;
+        if !configured.is_empty() {
+            return Self::parse(configured);
+        }
+
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|key| context.get_env(key))
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::parse(&value))
+    }
+
+    /// Parses a POSIX locale name like `de_DE.UTF-8@euro` or a BCP 47 tag like `de-DE`
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.split(['.', '@']).next()?;
+        if name.is_empty() || name == "C" || name == "POSIX" {
+            return None;
+        }
+
+        let (language, region) = match name.split_once(['_', '-']) {
+            Some((language, region)) => (language, Some(region.to_uppercase())),
+            None => (name, None),
+        };
+
+        Some(Self {
+            language: language.to_lowercase(),
+            region,
+        })
+    }
+
+    /// Whether times are conventionally written with a 12 hour clock in this locale
+    pub fn uses_12_hour_clock(&self) -> bool {
+        match (self.language.as_str(), self.region.as_deref()) {
+            ("en", Some(region)) => {
+                matches!(region, "US" | "CA" | "AU" | "NZ" | "PH" | "IN" | "PK")
+            }
+            ("hi" | "ur" | "bn" | "ar", _) => true,
+            _ => false,
+        }
+    }
+
+    /// Whether numbers are written with a decimal comma, like `1,5`, and units are set apart by a
+    /// space, like `35 %` or `1 h 2 min`
+    fn uses_decimal_comma(&self) -> bool {
+        matches!(
+            self.language.as_str(),
+            "cs" | "da"
+                | "de"
+                | "es"
+                | "fi"
+                | "fr"
+                | "it"
+                | "nb"
+                | "nl"
+                | "pl"
+                | "pt"
+                | "ru"
+                | "sv"
+                | "tr"
+        )
+    }
+
+    /// Writes a number like `1.5` and its unit the way the locale does, e.g. `1.5%` in English
+    /// and `1,5 %` in German
+    pub fn format_number(&self, number: &str, unit: &str) -> String {
+        if !self.uses_decimal_comma() {
+            return format!("{number}{unit}");
+        }
+        let number = number.replacen('.', ",", 1);
+        if unit.is_empty() {
+            number
+        } else {
+            format!("{number}\u{a0}{unit}")
+        }
+    }
+
+    /// Writes a duration the way the locale does, e.g. `1h2m3s` in English and `1 h 2 min 3 s`
+    /// in German
+    pub fn format_duration(&self, millis: u128, show_millis: bool) -> String {
+        if !self.uses_decimal_comma() {
+            return render_time(millis, show_millis);
+        }
+        let day = if self.language == "fr" { "j" } else { "d" };
+        let units = [day, "h", "min", "s", "ms"];
+        render_time_with_units(millis, show_millis, units, "\u{a0}", " ")
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.region {
+            Some(region) => write!(f, "{}_{}", self.language, region),
+            None => f.write_str(&self.language),
+        }
+    }
+}
+
+/// Parses the `timezone` option: `UTC` or a fixed offset like `+05:30` or `-08:00`.
+///
+/// An empty string selects the local timezone and yields `None`.
+pub fn parse_timezone(timezone: &str) -> Option<FixedOffset> {
+    match timezone.trim() {
+        "" | "local" => None,
+        "UTC" | "utc" | "Z" => FixedOffset::east_opt(0),
+        offset => {
+            let (sign, rest) = match offset.split_at(1) {
+                ("+", rest) => (1, rest),
+                ("-", rest) => (-1, rest),
+                _ => return None,
+            };
+            let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+            let hours: i32 = hours.parse().ok()?;
+            let minutes: i32 = minutes.parse().ok()?;
+            if minutes >= 60 {
+                return None;
+            }
+            FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_posix_locale() {
+        assert_eq!(
+            Locale::parse("de_DE.UTF-8@euro"),
+            Some(Locale {
+                language: "de".to_string(),
+                region: Some("DE".to_string()),
+            })
+        );
+        assert_eq!(Locale::parse("en-us").unwrap().to_string(), "en_US");
+        assert_eq!(Locale::parse("C.UTF-8"), None);
+        assert_eq!(Locale::parse("POSIX"), None);
+    }
+
+    #[test]
+    fn twelve_hour_clock() {
+        assert!(Locale::parse("en_US.UTF-8").unwrap().uses_12_hour_clock());
+        assert!(!Locale::parse("en_GB.UTF-8").unwrap().uses_12_hour_clock());
+        assert!(!Locale::parse("de_DE").unwrap().uses_12_hour_clock());
+    }
+
+    #[test]
+    fn format_numbers_and_durations() {
+        let german = Locale::parse("de_DE").unwrap();
+        assert_eq!(german.format_number("35", "%"), "35\u{a0}%");
+        assert_eq!(german.format_number("1.5", ""), "1,5");
+        assert_eq!(
+            german.format_duration(3_723_000, false),
+            "1\u{a0}h 2\u{a0}min 3\u{a0}s"
+        );
+
+        let english = Locale::parse("en_GB").unwrap();
+        assert_eq!(english.format_number("35", "%"), "35%");
+        assert_eq!(english.format_duration(3_723_000, false), "1h2m3s");
+    }
+
+    #[test]
+    fn parse_timezones() {
+        assert_eq!(parse_timezone(""), None);
+        assert_eq!(parse_timezone("UTC"), FixedOffset::east_opt(0));
+        assert_eq!(parse_timezone("+05:30"), FixedOffset::east_opt(19800));
+        assert_eq!(parse_timezone("-8"), FixedOffset::west_opt(8 * 3600));
+        assert_eq!(parse_timezone("Europe/Berlin"), None);
+    }
+}
@@ -0,0 +1,128 @@
+use crate::context_env::Env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// The POSIX emulation layer a shell on Windows runs in
+///
+/// These shells report paths like `/c/Users/dev` in `PWD`, while starship itself is a
+/// native Windows program that expects `C:\Users\dev`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MsysFlavor {
+    Cygwin,
+    GitBash,
+    Msys2,
+}
+
+impl fmt::Display for MsysFlavor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Cygwin => "cygwin",
+            Self::GitBash => "git-bash",
+            Self::Msys2 => "msys2",
+        })
+    }
+}
+
+impl MsysFlavor {
+    /// Detects the emulation layer from the variables its shells export
+    pub fn detect(env: &Env, logical_path: &Path) -> Option<Self> {
+        if env.get_env("MSYSTEM").is_some() {
+            // Git for Windows sets `EXEPATH` to its installation directory
+            return Some(if env.get_env("EXEPATH").is_some() {
+                Self::GitBash
+            } else {
+                Self::Msys2
+            });
+        }
+
+        let is_cygwin = env.get_env("CYGWIN").is_some() || logical_path.starts_with("/cygdrive");
+        is_cygwin.then_some(Self::Cygwin)
+    }
+
+    /// Translates a path on a drive, e.g. `/c/Users/dev` or `/cygdrive/c/Users/dev`, to the
+    /// Windows path `C:\Users\dev`. Other paths are returned as they are.
+    pub fn to_windows_path(self, path: PathBuf) -> PathBuf {
+        let Some(text) = path.to_str() else {
+            return path;
+        };
+        let rest = match self {
+            Self::Cygwin => text.strip_prefix("/cygdrive/"),
+            Self::GitBash | Self::Msys2 => text.strip_prefix('/'),
+        };
+        let Some(rest) = rest else {
+            return path;
+        };
+
+        let (drive, rest) = rest.split_once('/').unwrap_or((rest, ""));
+        match drive.chars().next() {
+            Some(letter) if drive.len() == 1 && letter.is_ascii_alphabetic() => {
+                PathBuf::from(format!(
+                    "{}:\\{}",
+                    letter.to_ascii_uppercase(),
+                    rest.replace('/', "\\")
+                ))
+            }
+            _ => path,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_flavor() {
+        let mut env = Env::default();
+        assert_eq!(MsysFlavor::detect(&env, Path::new("/c/Users")), None);
+        assert_eq!(
+            MsysFlavor::detect(&env, Path::new("/cygdrive/c/Users")),
+            Some(MsysFlavor::Cygwin)
+        );
+
+        env.insert("MSYSTEM", "UCRT64".to_string());
+        assert_eq!(
+            MsysFlavor::detect(&env, Path::new("/c/Users")),
+            Some(MsysFlavor::Msys2)
+        );
+
+        env.insert("EXEPATH", r"C:\Program Files\Git".to_string());
+        assert_eq!(
+            MsysFlavor::detect(&env, Path::new("/c/Users")),
+            Some(MsysFlavor::GitBash)
+        );
+    }
+
+    #[test]
+    fn drive_paths_are_translated() {
+        let translate = |flavor: MsysFlavor, path: &str| flavor.to_windows_path(path.into());
+
+        assert_eq!(
+            translate(MsysFlavor::GitBash, "/c/Users/dev/project"),
+            PathBuf::from(r"C:\Users\dev\project")
+        );
+        assert_eq!(translate(MsysFlavor::Msys2, "/d"), PathBuf::from(r"D:\"));
+        assert_eq!(
+            translate(MsysFlavor::Cygwin, "/cygdrive/c/Users/dev"),
+            PathBuf::from(r"C:\Users\dev")
+        );
+    }
+
+    #[test]
+    fn other_paths_are_kept() {
+        let translate = |flavor: MsysFlavor, path: &str| flavor.to_windows_path(path.into());
+
+        assert_eq!(
+            translate(MsysFlavor::Msys2, "/usr/bin"),
+            PathBuf::from("/usr/bin")
+        );
+        assert_eq!(
+            translate(MsysFlavor::Cygwin, "/c/Users"),
+            PathBuf::from("/c/Users")
+        );
+        assert_eq!(
+            translate(MsysFlavor::GitBash, r"C:\Users"),
+            PathBuf::from(r"C:\Users")
+        );
+    }
+}
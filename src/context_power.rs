@@ -0,0 +1,163 @@
+use crate::context::Context;
+
+/// Where the machine currently draws its power from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerSource {
+    Ac,
+    Battery,
+}
+
+/// The power state of the machine starship is running on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Power {
+    /// The current power source, or `None` if it could not be determined
+    pub source: Option<PowerSource>,
+
+    /// Whether the operating system asks applications to save energy
+    pub low_power_mode: bool,
+}
+
+impl Power {
+    /// Whether the machine is running on battery
+    pub fn on_battery(&self) -> bool {
+        self.source == Some(PowerSource::Battery)
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn detect(context: &Context) -> Self {
+        use crate::utils::{context_path, read_file};
+
+        let supplies = std::fs::read_dir(context_path(context, "/sys/class/power_supply"))
+            .into_iter()
+            .flatten()
+            .filter_map(Result::ok)
+            .map(|entry| {
+                let read = |name: &str| read_file(entry.path().join(name)).unwrap_or_default();
+                (read("type"), read("online"), read("status"))
+            })
+            .collect::<Vec<_>>();
+
+        let source = source_from_supplies(
+            supplies
+                .iter()
+                .map(|(kind, online, status)| (kind.trim(), online.trim(), status.trim())),
+        );
+
+        // `platform_profile` is set by power-profiles-daemon and the firmware settings
+        let low_power_mode =
+            read_file(context_path(context, "/sys/firmware/acpi/platform_profile"))
+                .map_or(false, |profile| {
+                    matches!(profile.trim(), "low-power" | "quiet")
+                });
+
+        Self {
+            source,
+            low_power_mode,
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub fn detect(context: &Context) -> Self {
+        let source = context
+            .exec_cmd("pmset", &["-g", "batt"])
+            .and_then(|output| source_from_pmset(&output.stdout));
+        let low_power_mode = context.exec_cmd("pmset", &["-g"]).map_or(false, |output| {
+            output.stdout.lines().any(|line| {
+                let mut words = line.split_whitespace();
+                words.next() == Some("lowpowermode") && words.next() == Some("1")
+            })
+        });
+
+        Self {
+            source,
+            low_power_mode,
+        }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    pub fn detect(_context: &Context) -> Self {
+        Self::default()
+    }
+}
+
+/// Determines the power source from the `type`, `online` and `status` attributes of the
+/// entries in `/sys/class/power_supply`
+#[cfg(target_os = "linux")]
+fn source_from_supplies<'a, I>(supplies: I) -> Option<PowerSource>
+where
+    I: Iterator<Item = (&'a str, &'a str, &'a str)>,
+{
+    let mut source = None;
+    for (kind, online, status) in supplies {
+        match kind {
+            "Mains" | "USB" if online == "1" => return Some(PowerSource::Ac),
+            "Battery" if status == "Discharging" => source = Some(PowerSource::Battery),
+            "Battery" if source.is_none() => source = Some(PowerSource::Ac),
+            _ => {}
+        }
+    }
+    source
+}
+
+/// Determines the power source from the first line of `pmset -g batt`, e.g.
+/// `Now drawing from 'Battery Power'`
+#[cfg(any(target_os = "macos", test))]
+fn source_from_pmset(output: &str) -> Option<PowerSource> {
+    let line = output.lines().next()?;
+    if line.contains("'Battery Power'") {
+        Some(PowerSource::Battery)
+    } else if line.contains("'AC Power'") {
+        Some(PowerSource::Ac)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pmset_sources() {
+        assert_eq!(
+            source_from_pmset("Now drawing from 'Battery Power'\n -InternalBattery-0\t80%"),
+            Some(PowerSource::Battery)
+        );
+        assert_eq!(
+            source_from_pmset("Now drawing from 'AC Power'"),
+            Some(PowerSource::Ac)
+        );
+        assert_eq!(source_from_pmset(""), None);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn sysfs_discharging_battery() {
+        let context: Context = crate::test::ModuleRenderer::new("battery")
+            .file("/sys/class/power_supply/AC/type", "Mains\n")
+            .file("/sys/class/power_supply/AC/online", "0\n")
+            .file("/sys/class/power_supply/BAT0/type", "Battery\n")
+            .file("/sys/class/power_supply/BAT0/status", "Discharging\n")
+            .file("/sys/firmware/acpi/platform_profile", "low-power\n")
+            .into();
+
+        let power = Power::detect(&context);
+        assert!(power.on_battery());
+        assert!(power.low_power_mode);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn sysfs_mains_online() {
+        let context: Context = crate::test::ModuleRenderer::new("battery")
+            .file("/sys/class/power_supply/AC/type", "Mains\n")
+            .file("/sys/class/power_supply/AC/online", "1\n")
+            .file("/sys/class/power_supply/BAT0/type", "Battery\n")
+            .file("/sys/class/power_supply/BAT0/status", "Charging\n")
+            .into();
+
+        let power = Power::detect(&context);
+        assert_eq!(power.source, Some(PowerSource::Ac));
+        assert!(!power.low_power_mode);
+    }
+}
@@ -0,0 +1,223 @@
+//! The processes starship was started from: the shell, and whatever the shell runs in, like a
+//! terminal, an editor or a REPL.
+
+use crate::context::Context;
+use crate::session;
+#[cfg(any(test, not(target_os = "linux")))]
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Processes further up than this are not inspected, which also guards against cycles of
+/// reused process ids
+const MAX_DEPTH: usize = 64;
+
+/// A process starship descends from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParentProcess {
+    pub pid: u32,
+    /// The name of the executable, without its directory or `.exe` extension
+    pub name: String,
+}
+
+impl ParentProcess {
+    /// Lists the processes starship descends from, its parent first
+    ///
+    /// The chain doesn't change while the shell is running, so it is kept in the state
+    /// directory of the shell session.
+    pub fn detect_chain(context: &Context) -> Vec<Self> {
+        let cache = session::session_dir(context).map(|dir| dir.join("parent_processes"));
+        if let Some(chain) = cache.as_deref().and_then(read_cache) {
+            return chain;
+        }
+
+        let chain = list_chain(context);
+        if let Some(cache) = cache.filter(|_| !chain.is_empty()) {
+            let contents: String = chain
+                .iter()
+                .map(|process| format!("{}\t{}\n", process.pid, process.name))
+                .collect();
+            if let Err(error) = fs::write(cache, contents) {
+                log::debug!("Unable to cache the parent processes: {error}");
+            }
+        }
+        chain
+    }
+}
+
+fn read_cache(file: &Path) -> Option<Vec<ParentProcess>> {
+    let chain: Vec<ParentProcess> = fs::read_to_string(file)
+        .ok()?
+        .lines()
+        .filter_map(|line| {
+            let (pid, name) = line.split_once('\t')?;
+            Some(ParentProcess {
+                pid: pid.parse().ok()?,
+                name: name.to_string(),
+            })
+        })
+        .collect();
+
+    // The cache belongs to another shell if the session key was inherited
+    match parent_pid() {
+        Some(pid) if chain.first().map(|parent| parent.pid) != Some(pid) => None,
+        _ => Some(chain),
+    }
+}
+
+#[cfg(unix)]
+fn parent_pid() -> Option<u32> {
+    Some(std::os::unix::process::parent_id())
+}
+
+#[cfg(not(unix))]
+fn parent_pid() -> Option<u32> {
+    None
+}
+
+/// Follows the parents of starship, using `lookup` to find the parent and name of a process
+fn walk_chain<F>(lookup: F) -> Vec<ParentProcess>
+where
+    F: Fn(u32) -> Option<(u32, String)>,
+{
+    let mut chain = Vec::new();
+    let mut pid = lookup(std::process::id()).map(|(parent, _)| parent);
+    // Process 1 is init on unix, and 0 the idle process on Windows
+    while let Some(current) = pid.filter(|&pid| pid > 1) {
+        if chain.len() >= MAX_DEPTH {
+            break;
+        }
+        let Some((parent, name)) = lookup(current) else {
+            break;
+        };
+        chain.push(ParentProcess { pid: current, name });
+        pid = Some(parent);
+    }
+    chain
+}
+
+/// Strips the directory, `.exe` extension and the dash of login shells from an executable
+#[cfg(any(test, not(target_os = "linux")))]
+fn process_name(executable: &str) -> Option<String> {
+    let name = Path::new(executable.trim()).file_name()?.to_str()?;
+    let name = name.strip_suffix(".exe").unwrap_or(name);
+    Some(name.trim_start_matches('-').to_string())
+}
+
+#[cfg(all(target_os = "linux", not(test)))]
+fn list_chain(_context: &Context) -> Vec<ParentProcess> {
+    walk_chain(|pid| {
+        let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+        parse_stat(&stat)
+    })
+}
+
+/// Parses the parent and name of a process from `/proc/<pid>/stat`, where the name is
+/// enclosed in parentheses and may contain any character, including spaces and parentheses
+#[cfg(any(target_os = "linux", test))]
+fn parse_stat(stat: &str) -> Option<(u32, String)> {
+    let (name, fields) = stat.split_once(" (")?.1.rsplit_once(") ")?;
+    // The state of the process comes before its parent
+    let parent = fields.split_whitespace().nth(1)?.parse().ok()?;
+    Some((parent, name.to_string()))
+}
+
+#[cfg(all(windows, not(test)))]
+fn list_chain(_context: &Context) -> Vec<ParentProcess> {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
+        TH32CS_SNAPPROCESS,
+    };
+
+    let Ok(snapshot) = (unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) }) else {
+        return Vec::new();
+    };
+    let mut processes = HashMap::new();
+    let mut entry = PROCESSENTRY32W {
+        dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+        ..Default::default()
+    };
+    let mut next = unsafe { Process32FirstW(snapshot, &mut entry) };
+    while next.is_ok() {
+        let length = entry
+            .szExeFile
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(entry.szExeFile.len());
+        let executable = String::from_utf16_lossy(&entry.szExeFile[..length]);
+        if let Some(name) = process_name(&executable) {
+            processes.insert(entry.th32ProcessID, (entry.th32ParentProcessID, name));
+        }
+        next = unsafe { Process32NextW(snapshot, &mut entry) };
+    }
+    if let Err(e) = unsafe { CloseHandle(snapshot) } {
+        log::debug!("CloseHandle failed: {e:?}");
+    }
+    walk_chain(|pid| processes.get(&pid).cloned())
+}
+
+/// Lists the processes with `ps` where there is no `/proc` to read them from, like on macOS
+#[cfg(any(test, not(any(target_os = "linux", windows))))]
+fn list_chain(context: &Context) -> Vec<ParentProcess> {
+    let Some(output) = context.exec_cmd("ps", &["-A", "-o", "pid=,ppid=,comm="]) else {
+        return Vec::new();
+    };
+    let processes: HashMap<u32, (u32, String)> =
+        output.stdout.lines().filter_map(parse_ps_line).collect();
+    walk_chain(|pid| processes.get(&pid).cloned())
+}
+
+/// Parses a line of `ps -o pid=,ppid=,comm=` into the process id, and the id of its parent
+/// and its name
+#[cfg(any(test, not(any(target_os = "linux", windows))))]
+fn parse_ps_line(line: &str) -> Option<(u32, (u32, String))> {
+    let (pid, rest) = line.trim().split_once(char::is_whitespace)?;
+    let (parent, executable) = rest.trim_start().split_once(char::is_whitespace)?;
+    Some((
+        pid.parse().ok()?,
+        (parent.parse().ok()?, process_name(executable)?),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::{default_context, mock_parent_processes};
+
+    #[test]
+    fn lists_parents_of_starship() {
+        let mut context = default_context();
+        mock_parent_processes(&mut context, &["-zsh", "/usr/bin/nvim", "tmux"]);
+        let names: Vec<String> = ParentProcess::detect_chain(&context)
+            .into_iter()
+            .map(|process| process.name)
+            .collect();
+        assert_eq!(names, ["zsh", "nvim", "tmux"]);
+    }
+
+    #[test]
+    fn parse_ps_lines() {
+        assert_eq!(
+            parse_ps_line("  790     1 /Applications/Code Helper"),
+            Some((790, (1, "Code Helper".to_string())))
+        );
+        assert_eq!(
+            parse_ps_line(" 4242  812 pwsh.exe"),
+            Some((4242, (812, "pwsh".to_string())))
+        );
+        assert_eq!(parse_ps_line("  812"), None);
+    }
+
+    #[test]
+    fn parse_proc_stat() {
+        assert_eq!(
+            parse_stat("4242 (tmux: server) S 1 4242 4242 0 -1 4194624"),
+            Some((1, "tmux: server".to_string()))
+        );
+        assert_eq!(
+            parse_stat("812 (a) (b)) R 790 812"),
+            Some((790, "a) (b)".to_string()))
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use crate::context::DirContents;
+use std::fmt;
+
+/// Files that mark the root of a repository containing several projects
+const MONOREPO_FILES: &[&str] = &[
+    "pnpm-workspace.yaml",
+    "lerna.json",
+    "nx.json",
+    "turbo.json",
+    "rush.json",
+];
+
+/// A coarse classification of the project in the current directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectType {
+    Rust,
+    Node,
+    Terraform,
+    Python,
+    /// Either an explicit workspace root or markers of more than one project type
+    Monorepo,
+}
+
+impl ProjectType {
+    /// Classifies the project based on the files in the current directory.
+    ///
+    /// Returns `None` if no known project markers are present.
+    pub fn detect(dir_contents: &DirContents) -> Option<Self> {
+        if dir_contents.has_any_positive_file_name(MONOREPO_FILES) {
+            return Some(Self::Monorepo);
+        }
+
+        let mut detected = [Self::Rust, Self::Node, Self::Terraform, Self::Python]
+            .into_iter()
+            .filter(|project_type| project_type.is_present(dir_contents));
+
+        match (detected.next(), detected.next()) {
+            (Some(project_type), None) => Some(project_type),
+            (Some(_), Some(_)) => Some(Self::Monorepo),
+            (None, _) => None,
+        }
+    }
+
+    fn is_present(self, dir_contents: &DirContents) -> bool {
+        match self {
+            Self::Rust => dir_contents.has_file_name("Cargo.toml"),
+            Self::Node => dir_contents.has_file_name("package.json"),
+            Self::Terraform => {
+                dir_contents.has_folder(".terraform")
+                    || dir_contents.has_any_positive_extension(&["tf", "tfplan", "tfstate"])
+            }
+            Self::Python => dir_contents.has_any_positive_file_name(&[
+                "pyproject.toml",
+                "setup.py",
+                "setup.cfg",
+                "Pipfile",
+                "requirements.txt",
+            ]),
+            Self::Monorepo => false,
+        }
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Rust => "rust",
+            Self::Node => "node",
+            Self::Terraform => "terraform",
+            Self::Python => "python",
+            Self::Monorepo => "monorepo",
+        }
+    }
+}
+
+impl fmt::Display for ProjectType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::default_context;
+    use std::fs::File;
+    use std::io;
+
+    fn detect_in(files: &[&str]) -> io::Result<Option<ProjectType>> {
+        let dir = tempfile::tempdir()?;
+        for file in files {
+            File::create(dir.path().join(file))?.sync_all()?;
+        }
+
+        let mut context = default_context();
+        context.current_dir = dir.path().to_path_buf();
+        let project_type = ProjectType::detect(context.dir_contents()?);
+        dir.close()?;
+        Ok(project_type)
+    }
+
+    #[test]
+    fn no_project() -> io::Result<()> {
+        assert_eq!(detect_in(&["README.md"])?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn single_project() -> io::Result<()> {
+        assert_eq!(detect_in(&["Cargo.toml"])?, Some(ProjectType::Rust));
+        assert_eq!(detect_in(&["package.json"])?, Some(ProjectType::Node));
+        assert_eq!(detect_in(&["main.tf"])?, Some(ProjectType::Terraform));
+        assert_eq!(detect_in(&["pyproject.toml"])?, Some(ProjectType::Python));
+        Ok(())
+    }
+
+    #[test]
+    fn mixed_project_is_monorepo() -> io::Result<()> {
+        assert_eq!(
+            detect_in(&["Cargo.toml", "package.json"])?,
+            Some(ProjectType::Monorepo)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn workspace_marker_is_monorepo() -> io::Result<()> {
+        assert_eq!(
+            detect_in(&["package.json", "pnpm-workspace.yaml"])?,
+            Some(ProjectType::Monorepo)
+        );
+        Ok(())
+    }
+}
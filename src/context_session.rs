@@ -0,0 +1,140 @@
+use crate::context::Context;
+use std::fmt;
+
+/// How the user is connected to the machine starship is running on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionType {
+    Local,
+    Ssh,
+    Mosh,
+    Serial,
+    /// A browser based terminal, e.g. a cloud provider's shell
+    Web,
+}
+
+impl SessionType {
+    /// Classifies the current session based on the environment and the controlling terminal
+    pub fn detect(context: &Context) -> Self {
+        classify(|key| context.get_env(key), controlling_tty().as_deref())
+    }
+
+    /// Whether the session is connected over the network
+    pub const fn is_remote(self) -> bool {
+        matches!(self, Self::Ssh | Self::Mosh | Self::Web)
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Local => "local",
+            Self::Ssh => "ssh",
+            Self::Mosh => "mosh",
+            Self::Serial => "serial",
+            Self::Web => "web",
+        }
+    }
+}
+
+impl fmt::Display for SessionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+fn classify<F>(get_env: F, tty: Option<&str>) -> SessionType
+where
+    F: Fn(&str) -> Option<String>,
+{
+    // mosh-server is started through ssh, so check it before the ssh variables
+    if get_env("MOSH_SERVER_PID").is_some() {
+        return SessionType::Mosh;
+    }
+
+    let is_cloud_shell = get_env("CLOUD_SHELL").as_deref() == Some("true")
+        || get_env("AWS_EXECUTION_ENV").as_deref() == Some("CloudShell")
+        || get_env("ACC_CLOUD").is_some();
+    if is_cloud_shell {
+        return SessionType::Web;
+    }
+
+    if ["SSH_CONNECTION", "SSH_CLIENT", "SSH_TTY"]
+        .iter()
+        .any(|key| get_env(key).is_some())
+    {
+        return SessionType::Ssh;
+    }
+
+    let is_serial = tty.map_or(false, |tty| {
+        ["/dev/ttyS", "/dev/ttyUSB", "/dev/ttyAMA", "/dev/ttyACM"]
+            .iter()
+            .any(|prefix| tty.starts_with(prefix))
+    });
+    if is_serial {
+        return SessionType::Serial;
+    }
+
+    SessionType::Local
+}
+
+/// The terminal device attached to standard input, if any
+#[cfg(all(target_os = "linux", not(test)))]
+fn controlling_tty() -> Option<String> {
+    std::fs::read_link("/proc/self/fd/0")
+        .ok()
+        .map(|path| path.to_string_lossy().into_owned())
+}
+
+#[cfg(any(not(target_os = "linux"), test))]
+fn controlling_tty() -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn classify_with(env: &[(&str, &str)], tty: Option<&str>) -> SessionType {
+        let env: HashMap<&str, &str> = env.iter().copied().collect();
+        classify(|key| env.get(key).map(ToString::to_string), tty)
+    }
+
+    #[test]
+    fn local_session() {
+        assert_eq!(classify_with(&[], Some("/dev/pts/1")), SessionType::Local);
+        assert_eq!(classify_with(&[], None), SessionType::Local);
+    }
+
+    #[test]
+    fn ssh_session() {
+        let env = [("SSH_CONNECTION", "10.0.0.1 51234 10.0.0.2 22")];
+        assert_eq!(classify_with(&env, None), SessionType::Ssh);
+        assert!(SessionType::Ssh.is_remote());
+    }
+
+    #[test]
+    fn mosh_takes_precedence_over_ssh() {
+        let env = [
+            ("SSH_CLIENT", "10.0.0.1 51234 22"),
+            ("MOSH_SERVER_PID", "42"),
+        ];
+        assert_eq!(classify_with(&env, None), SessionType::Mosh);
+    }
+
+    #[test]
+    fn cloud_shell_session() {
+        assert_eq!(
+            classify_with(&[("CLOUD_SHELL", "true")], None),
+            SessionType::Web
+        );
+        assert_eq!(
+            classify_with(&[("AWS_EXECUTION_ENV", "CloudShell")], None),
+            SessionType::Web
+        );
+    }
+
+    #[test]
+    fn serial_console_session() {
+        assert_eq!(classify_with(&[], Some("/dev/ttyS0")), SessionType::Serial);
+        assert!(!SessionType::Serial.is_remote());
+    }
+}
@@ -7,6 +7,7 @@ use std::fmt;
 
 use crate::config::{parse_style_string, Style};
 use crate::context::{Context, Shell};
+use crate::logger;
 use crate::segment::Segment;
 
 use super::model::*;
@@ -116,11 +117,14 @@ impl<'a> StringFormatter<'a> {
         T: Into<Cow<'a, str>>,
         M: Fn(&str) -> Option<Result<T, StringFormatterError>> + Sync,
     {
+        let module = logger::current_module();
         self.variables
             .par_iter_mut()
             .filter(|(_, value)| value.is_none())
             .for_each(|(key, value)| {
-                *value = mapper(key).map(|var| var.map(|var| VariableValue::Plain(var.into())));
+                logger::with_module(module.clone(), || {
+                    *value = mapper(key).map(|var| var.map(|var| VariableValue::Plain(var.into())));
+                });
             });
         self
     }
@@ -137,12 +141,15 @@ impl<'a> StringFormatter<'a> {
         T: Into<Cow<'a, str>>,
         M: Fn(&str) -> Option<Result<T, StringFormatterError>> + Sync,
     {
+        let module = logger::current_module();
         self.variables
             .par_iter_mut()
             .filter(|(_, value)| value.is_none())
             .for_each(|(key, value)| {
-                *value = mapper(key)
-                    .map(|var| var.map(|var| VariableValue::NoEscapingPlain(var.into())));
+                logger::with_module(module.clone(), || {
+                    *value = mapper(key)
+                        .map(|var| var.map(|var| VariableValue::NoEscapingPlain(var.into())));
+                });
             });
         self
     }
@@ -200,11 +207,14 @@ impl<'a> StringFormatter<'a> {
     where
         M: Fn(&str) -> Option<Result<Vec<Segment>, StringFormatterError>> + Sync,
     {
+        let module = logger::current_module();
         self.variables
             .par_iter_mut()
             .filter(|(_, value)| value.is_none())
             .for_each(|(key, value)| {
-                *value = mapper(key).map(|var| var.map(VariableValue::Styled));
+                logger::with_module(module.clone(), || {
+                    *value = mapper(key).map(|var| var.map(VariableValue::Styled));
+                });
             });
         self
     }
@@ -218,15 +228,63 @@ impl<'a> StringFormatter<'a> {
         T: Into<Cow<'a, str>>,
         M: Fn(&str) -> Option<Result<T, StringFormatterError>> + Sync,
     {
+        let module = logger::current_module();
         self.style_variables
             .par_iter_mut()
             .filter(|(_, value)| value.is_none())
             .for_each(|(key, value)| {
-                *value = mapper(key).map(|var| var.map(Into::into));
+                logger::with_module(module.clone(), || {
+                    *value = mapper(key).map(|var| var.map(Into::into));
+                });
             });
         self
     }
 
+    /// Drops conditional format strings that could never be shown, because every variable
+    /// inside them is known to be empty
+    ///
+    /// Variables only referenced in the dropped format strings are forgotten, so the mappers
+    /// are never called for them. This should be called **before** `map_meta`.
+    #[must_use]
+    pub fn prune_conditionals<F>(mut self, is_empty: F) -> Self
+    where
+        F: Fn(&str) -> bool,
+    {
+        fn prune<'a>(
+            elements: Vec<FormatElement<'a>>,
+            is_empty: &dyn Fn(&str) -> bool,
+        ) -> Vec<FormatElement<'a>> {
+            elements
+                .into_iter()
+                .filter_map(|element| match element {
+                    FormatElement::Conditional(format) => {
+                        let format = prune(format, is_empty);
+                        // Mirrors `should_show_elements`, which needs a non-empty variable
+                        let can_show = format.get_variables().iter().any(|var| !is_empty(var));
+                        can_show.then_some(FormatElement::Conditional(format))
+                    }
+                    FormatElement::TextGroup(textgroup) => {
+                        Some(FormatElement::TextGroup(TextGroup {
+                            format: prune(textgroup.format, is_empty),
+                            style: textgroup.style,
+                        }))
+                    }
+                    element => Some(element),
+                })
+                .collect()
+        }
+
+        self.format = prune(self.format, &is_empty);
+
+        let variables = self.format.get_variables();
+        self.variables
+            .retain(|key, _| variables.contains(key.as_str()));
+        let style_variables = self.format.get_style_variables();
+        self.style_variables
+            .retain(|key, _| style_variables.contains(key.as_str()));
+        self
+    }
+
     /// Parse the format string and consume self.
     ///
     /// This method will throw an Error in the following conditions:
@@ -273,10 +331,10 @@ impl<'a> StringFormatter<'a> {
                 })
                 .collect::<Result<Vec<Cow<str>>, StringFormatterError>>();
             style_strings
-                .map(|style_strings| {
-                    let style_string: String =
-                        style_strings.iter().flat_map(|s| s.chars()).collect();
-                    parse_style_string(&style_string, context)
+                .map(|style_strings| match style_strings.as_slice() {
+                    // Most styles are a single string, which can be parsed without copying
+                    [style_string] => parse_style_string(style_string, context),
+                    _ => parse_style_string(&style_strings.concat(), context),
                 })
                 .transpose()
         }
@@ -313,31 +371,33 @@ impl<'a> StringFormatter<'a> {
                             .get(name.as_ref())
                             .expect("Uncached variable found")
                             .as_ref()
-                            .map(|segments| match segments.clone()? {
-                                VariableValue::Styled(segments) => Ok(segments
-                                    .into_iter()
+                            .map(|value| match value {
+                                Err(error) => Err(error.clone()),
+                                Ok(VariableValue::Styled(segments)) => Ok(segments
+                                    .iter()
+                                    .cloned()
                                     .map(|mut segment| {
                                         // Derive upper style if the style of segments are none.
                                         segment.set_style_if_empty(style);
                                         segment
                                     })
                                     .collect()),
-                                VariableValue::Plain(text) => Ok(Segment::from_text(
+                                Ok(VariableValue::Plain(text)) => Ok(Segment::from_text(
                                     style,
                                     shell_prompt_escape(
-                                        text,
+                                        text.as_ref(),
                                         match context {
                                             None => Shell::Unknown,
                                             Some(c) => c.shell,
                                         },
                                     ),
                                 )),
-                                VariableValue::NoEscapingPlain(text) => {
-                                    Ok(Segment::from_text(style, text))
+                                Ok(VariableValue::NoEscapingPlain(text)) => {
+                                    Ok(Segment::from_text(style, text.as_ref()))
                                 }
-                                VariableValue::Meta(format) => {
+                                Ok(VariableValue::Meta(format)) => {
                                     let formatter = StringFormatter {
-                                        format,
+                                        format: format.clone(),
                                         variables: clone_without_meta(variables),
                                         style_variables: style_variables.clone(),
                                     };
@@ -379,9 +439,14 @@ impl<'a> StringFormatter<'a> {
                                                     VariableValue::NoEscapingPlain(
                                                         no_escaping_plain_value,
                                                     ) => !no_escaping_plain_value.is_empty(),
-                                                    VariableValue::Styled(segments) => segments
-                                                        .iter()
-                                                        .any(|x| !x.value().is_empty()),
+                                                    // A line break alone does not make a group
+                                                    // visible, so whole lines can be collapsed
+                                                    VariableValue::Styled(segments) => {
+                                                        segments.iter().any(|x| {
+                                                            !matches!(x, Segment::LineTerm)
+                                                                && !x.value().is_empty()
+                                                        })
+                                                    }
                                                 })
                                         })
                                 })
@@ -401,11 +466,17 @@ impl<'a> StringFormatter<'a> {
             Ok(results?.into_iter().flatten().collect())
         }
 
+        // Fill in variables that are available in every format string
+        let formatter = match context {
+            Some(context) => self.map(|variable| context.global_variable(variable).map(Ok)),
+            None => self,
+        };
+
         parse_format(
-            self.format,
+            formatter.format,
             default_style,
-            &self.variables,
-            &self.style_variables,
+            &formatter.variables,
+            &formatter.style_variables,
             context,
         )
     }
@@ -441,25 +512,33 @@ fn clone_without_meta<'a>(variables: &VariableMapType<'a>) -> VariableMapType<'a
 }
 
 /// Escape interpretable characters for the shell prompt
-pub fn shell_prompt_escape<T>(text: T, shell: Shell) -> String
+pub fn shell_prompt_escape<'a, T>(text: T, shell: Shell) -> Cow<'a, str>
 where
-    T: Into<String>,
+    T: Into<Cow<'a, str>>,
 {
+    let text = text.into();
     // Handle other interpretable characters
-    match shell {
+    let special: &[char] = match shell {
         // Bash might interpret backslashes, backticks and $
         // see #658 for more details
-        Shell::Bash => text
-            .into()
-            .replace('\\', r"\\")
-            .replace('$', r"\$")
-            .replace('`', r"\`"),
-        Shell::Zsh => {
-            // % is an escape in zsh, see PROMPT in `man zshmisc`
-            text.into().replace('%', "%%")
+        Shell::Bash => &['\\', '$', '`'],
+        // % is an escape in zsh, see PROMPT in `man zshmisc`
+        Shell::Zsh => &['%'],
+        _ => &[],
+    };
+    // Most text needs no escaping, so it is passed through without copying
+    if !text.contains(special) {
+        return text;
+    }
+
+    let mut escaped = String::with_capacity(text.len() + 4);
+    for c in text.chars() {
+        if special.contains(&c) {
+            escaped.push(if shell == Shell::Zsh { '%' } else { '\\' });
         }
-        _ => text.into(),
+        escaped.push(c);
     }
+    escaped.into()
 }
 
 #[cfg(test)]
@@ -726,6 +805,26 @@ mod tests {
         assert_eq!(result.len(), 0);
     }
 
+    #[test]
+    fn test_conditional_line_break_alone() {
+        const FORMAT_STR: &str = "($none$line_break)$some";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|var| match var {
+                "some" => Some(Ok("$some")),
+                _ => None,
+            })
+            .map_variables_to_segments(|var| match var {
+                "line_break" => Some(Ok(vec![Segment::LineTerm])),
+                _ => None,
+            });
+        let result = formatter.parse(None, None).unwrap();
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "$some", None);
+        assert!(result_iter.next().is_none());
+    }
+
     #[test]
     fn test_nested_conditional() {
         const FORMAT_STR: &str = "($some ($none)) and ($none ($some))";
@@ -783,6 +882,36 @@ mod tests {
         assert_eq!(variables, expected_variables);
     }
 
+    #[test]
+    fn test_prune_conditionals() {
+        const FORMAT_STR: &str = "($a [($b) $c](none $s)) ($b[$d]($t)) $e";
+        let expected_variables = vec!["a", "c", "e"].into_iter().map(String::from).collect();
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .prune_conditionals(|var| matches!(var, "b" | "d"));
+        assert_eq!(formatter.get_variables(), expected_variables);
+        assert_eq!(
+            formatter.get_style_variables(),
+            vec!["s".to_string()].into_iter().collect()
+        );
+
+        let result = StringFormatter::new("($a) ($b$d) $e")
+            .unwrap()
+            .prune_conditionals(|var| matches!(var, "b" | "d"))
+            .map(|var| {
+                assert_ne!(var, "b", "pruned variables must not be mapped");
+                Some(Ok(var.to_string()))
+            })
+            .parse(None, None)
+            .unwrap();
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "a", None);
+        match_next!(result_iter, " ", None);
+        match_next!(result_iter, " ", None);
+        match_next!(result_iter, "e", None);
+    }
+
     #[test]
     fn test_parse_error() {
         // brackets without escape
@@ -30,6 +30,14 @@ impl<'a> VersionFormatter<'a> {
 
     /// Formats a version structure into a readable string
     pub fn format(self, version: &'a str) -> Result<String, StringFormatterError> {
+        self.format_with(version, |_| None)
+    }
+
+    /// Like [`Self::format`], with additional variables provided by `mapper`
+    pub fn format_with<M>(self, version: &'a str, mapper: M) -> Result<String, StringFormatterError>
+    where
+        M: Fn(&str) -> Option<String> + Sync,
+    {
         let parsed = Lazy::new(|| Versioning::new(version));
         let formatted = self
             .formatter
@@ -50,7 +58,7 @@ impl<'a> VersionFormatter<'a> {
                     Some(Versioning::General(v)) => Some(Ok(v.nth_lenient(2)?.to_string())),
                     _ => None,
                 },
-                _ => None,
+                _ => mapper(variable).map(Ok),
             })
             .parse(None, None);
 
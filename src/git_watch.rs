@@ -0,0 +1,361 @@
+//! `starship watch-git` keeps the output of `git status` for the `git_status` module in a
+//! snapshot file in the git directory, updated whenever something in the repository changes,
+//! so the prompt doesn't have to scan the working tree of huge repositories.
+//!
+//! The watcher touches a heartbeat file while it runs. The snapshot is only used while the
+//! heartbeat is recent and the snapshot is newer than the index and `HEAD`, otherwise
+//! `git_status` runs `git status` itself. The watcher removes the snapshot as soon as it hears
+//! of a change, so the prompt never shows a status older than the working tree.
+
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::context::{Context, Repo};
+use crate::modules;
+
+/// The output of `git status`, after a header with its arguments
+const SNAPSHOT_FILE: &str = "starship-status";
+/// The process id of the watcher, written every `HEARTBEAT`
+const HEARTBEAT_FILE: &str = "starship-watch";
+
+const HEARTBEAT: Duration = Duration::from_secs(5);
+/// Snapshots of watchers that missed a few heartbeats, e.g. after being killed, are not used
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(15);
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// How long to wait for more changes after the first one, as commands change many files
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Watches the repository of the current directory until the process is stopped
+pub fn watch(context: &Context) -> io::Result<()> {
+    let repo = context
+        .get_repo()
+        .map_err(|error| io::Error::new(io::ErrorKind::NotFound, error.to_string()))?;
+    let workdir = repo.workdir.as_deref().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            "bare repositories have no status",
+        )
+    })?;
+    if let Some(pid) = running_watcher(&repo.path) {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("the repository is already watched by process {pid}"),
+        ));
+    }
+
+    let mut watcher = Watcher::new(context, repo, workdir)?;
+    write_heartbeat(&repo.path)?;
+    update_snapshot(context, repo)?;
+    println!("Watching {}", workdir.display());
+
+    let mut last_heartbeat = SystemTime::now();
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        if watcher.has_changes(context, repo) {
+            remove_snapshot(&repo.path)?;
+            thread::sleep(DEBOUNCE);
+            watcher.has_changes(context, repo);
+            update_snapshot(context, repo)?;
+        }
+        if last_heartbeat
+            .elapsed()
+            .map_or(true, |elapsed| elapsed >= HEARTBEAT)
+        {
+            write_heartbeat(&repo.path)?;
+            last_heartbeat = SystemTime::now();
+        }
+    }
+}
+
+/// Returns the output of `git status` with `args` kept by a running watcher, if it is up to date
+pub(crate) fn read_snapshot(repo: &Repo, args: &[&str]) -> Option<String> {
+    let snapshot = current_snapshot(&repo.path)?;
+    let contents = fs::read_to_string(snapshot).ok()?;
+    let (header, status) = contents.split_once('\n')?;
+    (header == snapshot_header(args)).then(|| status.to_string())
+}
+
+/// Returns the snapshot of a running watcher of the git directory, if it is up to date
+///
+/// It is written again after every change to the working tree, so its modification time tells
+/// whether anything changed.
+pub(crate) fn current_snapshot(git_dir: &Path) -> Option<PathBuf> {
+    running_watcher(git_dir)?;
+    let snapshot = git_dir.join(SNAPSHOT_FILE);
+    let written = modified(&snapshot)?;
+    // Git commands write the index and `HEAD` before the watcher hears of them
+    let is_outdated = ["index", "HEAD"]
+        .iter()
+        .filter_map(|file| modified(&git_dir.join(file)))
+        .any(|changed| changed > written);
+    (!is_outdated).then_some(snapshot)
+}
+
+fn snapshot_header(args: &[&str]) -> String {
+    format!("# starship watch-git: {}", args.join(" "))
+}
+
+fn update_snapshot(context: &Context, repo: &Repo) -> io::Result<()> {
+    let Some((args, status)) = modules::watched_git_status(context, repo) else {
+        log::warn!("Unable to get the status of the repository");
+        return Ok(());
+    };
+    let contents = format!("{}\n{status}", snapshot_header(&args));
+    // Renamed into place, so the prompt never reads a partial snapshot
+    let partial = repo.path.join(format!("{SNAPSHOT_FILE}.tmp"));
+    fs::write(&partial, contents)?;
+    fs::rename(partial, repo.path.join(SNAPSHOT_FILE))
+}
+
+/// Removes the snapshot while the working tree changes, until the next one is written
+fn remove_snapshot(git_dir: &Path) -> io::Result<()> {
+    match fs::remove_file(git_dir.join(SNAPSHOT_FILE)) {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+        _ => Ok(()),
+    }
+}
+
+fn write_heartbeat(git_dir: &Path) -> io::Result<()> {
+    fs::write(git_dir.join(HEARTBEAT_FILE), std::process::id().to_string())
+}
+
+/// Returns the process id of the watcher of the repository, if it is still running
+fn running_watcher(git_dir: &Path) -> Option<String> {
+    let heartbeat = git_dir.join(HEARTBEAT_FILE);
+    let age = modified(&heartbeat)?.elapsed().ok()?;
+    if age > HEARTBEAT_TIMEOUT {
+        return None;
+    }
+    fs::read_to_string(heartbeat).ok()
+}
+
+fn modified(file: &Path) -> Option<SystemTime> {
+    fs::metadata(file).and_then(|meta| meta.modified()).ok()
+}
+
+/// Files the watcher and git itself write into the git directory, which don't change the status
+fn is_own_file(name: &str) -> bool {
+    name.starts_with("starship-") || name.ends_with(".lock")
+}
+
+/// Ignored directories, like build output, would only cause needless updates
+///
+/// The whole working tree is listed, no matter which directory the watcher was started in.
+fn ignored_dirs(context: &Context, repo: &Repo, workdir: &Path) -> HashSet<PathBuf> {
+    repo.exec_git(
+        context,
+        &[
+            "ls-files",
+            "--others",
+            "--ignored",
+            "--exclude-standard",
+            "--directory",
+            "--full-name",
+            "--",
+            ":/",
+        ],
+    )
+    .map(|output| {
+        output
+            .stdout
+            .lines()
+            .filter(|line| line.ends_with('/'))
+            .map(|dir| workdir.join(dir.trim_end_matches('/')))
+            .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// Whether a directory created while watching is ignored, like the `target` directory of the
+/// first build
+fn is_ignored(context: &Context, repo: &Repo, dir: &Path) -> bool {
+    repo.exec_git(
+        context,
+        &[
+            OsStr::new("check-ignore"),
+            OsStr::new("-q"),
+            OsStr::new("--"),
+            dir.as_os_str(),
+        ],
+    )
+    .is_some()
+}
+
+/// Listens for changes in the working tree, the index and the references with inotify
+#[cfg(target_os = "linux")]
+struct Watcher {
+    inotify: nix::sys::inotify::Inotify,
+    dirs: std::collections::HashMap<nix::sys::inotify::WatchDescriptor, PathBuf>,
+    git_dir: PathBuf,
+    ignored: HashSet<PathBuf>,
+}
+
+#[cfg(target_os = "linux")]
+impl Watcher {
+    fn new(context: &Context, repo: &Repo, workdir: &Path) -> io::Result<Self> {
+        use nix::sys::inotify::{InitFlags, Inotify};
+
+        let mut watcher = Self {
+            inotify: Inotify::init(InitFlags::IN_NONBLOCK | InitFlags::IN_CLOEXEC)?,
+            dirs: Default::default(),
+            git_dir: repo.path.clone(),
+            ignored: ignored_dirs(context, repo, workdir),
+        };
+        watcher.watch_tree(workdir);
+        watcher.watch_dir(&repo.path);
+        watcher.watch_tree(&repo.path.join("refs"));
+        Ok(watcher)
+    }
+
+    fn watch_dir(&mut self, dir: &Path) {
+        use nix::sys::inotify::AddWatchFlags;
+
+        let flags = AddWatchFlags::IN_MODIFY
+            | AddWatchFlags::IN_ATTRIB
+            | AddWatchFlags::IN_CLOSE_WRITE
+            | AddWatchFlags::IN_CREATE
+            | AddWatchFlags::IN_DELETE
+            | AddWatchFlags::IN_MOVED_FROM
+            | AddWatchFlags::IN_MOVED_TO;
+        match self.inotify.add_watch(dir, flags) {
+            Ok(descriptor) => {
+                self.dirs.insert(descriptor, dir.to_path_buf());
+            }
+            Err(error) => log::warn!("Unable to watch {}: {error}", dir.display()),
+        }
+    }
+
+    /// Watches a directory and all directories below it, except for git directories of
+    /// submodules and ignored directories
+    fn watch_tree(&mut self, dir: &Path) {
+        if self.ignored.contains(dir) || dir.file_name().map_or(false, |name| name == ".git") {
+            return;
+        }
+        self.watch_dir(dir);
+        for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+            if entry.file_type().map_or(false, |kind| kind.is_dir()) {
+                self.watch_tree(&entry.path());
+            }
+        }
+    }
+
+    fn has_changes(&mut self, context: &Context, repo: &Repo) -> bool {
+        use nix::sys::inotify::AddWatchFlags;
+
+        let mut changed = false;
+        // Reading fails with `EAGAIN` once all events are read
+        while let Ok(events) = self.inotify.read_events() {
+            for event in events {
+                let Some(dir) = self.dirs.get(&event.wd).cloned() else {
+                    continue;
+                };
+                let name = event.name.unwrap_or_default();
+                if dir == self.git_dir && is_own_file(&name.to_string_lossy()) {
+                    continue;
+                }
+                if event.mask.contains(AddWatchFlags::IN_ISDIR)
+                    && event
+                        .mask
+                        .intersects(AddWatchFlags::IN_CREATE | AddWatchFlags::IN_MOVED_TO)
+                {
+                    let created = dir.join(name);
+                    if is_ignored(context, repo, &created) {
+                        self.ignored.insert(created);
+                    } else {
+                        self.watch_tree(&created);
+                    }
+                }
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+/// Listens for changes in the working tree, the index and the references with the file system
+/// events of the platform, like FSEvents on macOS and `ReadDirectoryChangesW` on Windows
+#[cfg(not(target_os = "linux"))]
+struct Watcher {
+    // The events stop once the watcher is dropped
+    _watcher: notify::RecommendedWatcher,
+    events: std::sync::mpsc::Receiver<notify::Result<notify::Event>>,
+    git_dir: PathBuf,
+    ignored: HashSet<PathBuf>,
+}
+
+#[cfg(not(target_os = "linux"))]
+impl Watcher {
+    fn new(context: &Context, repo: &Repo, workdir: &Path) -> io::Result<Self> {
+        use notify::{RecursiveMode, Watcher as _};
+
+        let to_io_error = |error: notify::Error| io::Error::new(io::ErrorKind::Other, error);
+        let (sender, events) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender).map_err(to_io_error)?;
+        watcher
+            .watch(workdir, RecursiveMode::Recursive)
+            .map_err(to_io_error)?;
+        // The git directory of a worktree is outside of its working tree
+        if !repo.path.starts_with(workdir) {
+            watcher
+                .watch(&repo.path, RecursiveMode::Recursive)
+                .map_err(to_io_error)?;
+        }
+
+        Ok(Self {
+            _watcher: watcher,
+            events,
+            git_dir: repo.path.clone(),
+            ignored: ignored_dirs(context, repo, workdir),
+        })
+    }
+
+    fn has_changes(&mut self, context: &Context, repo: &Repo) -> bool {
+        let mut changed = false;
+        while let Ok(event) = self.events.try_recv() {
+            changed |= match event {
+                Ok(event) => {
+                    if event.kind.is_create() {
+                        self.note_ignored(context, repo, &event.paths);
+                    }
+                    event.paths.iter().any(|path| self.is_relevant(path))
+                }
+                // Events may have been lost, e.g. when the queue overflowed
+                Err(error) => {
+                    log::debug!("Unable to watch the repository: {error}");
+                    true
+                }
+            };
+        }
+        changed
+    }
+
+    /// Adds the created directories that are ignored to the ignored ones
+    fn note_ignored(&mut self, context: &Context, repo: &Repo, paths: &[PathBuf]) {
+        for path in paths {
+            if path.is_dir() && self.is_relevant(path) && is_ignored(context, repo, path) {
+                self.ignored.insert(path.clone());
+            }
+        }
+    }
+
+    /// Whether a change of `path` can change the status, like the inotify watcher on Linux
+    /// decides by the directories it watches
+    fn is_relevant(&self, path: &Path) -> bool {
+        if let Ok(relative) = path.strip_prefix(&self.git_dir) {
+            let mut components = relative.components();
+            return match (components.next(), components.next()) {
+                (Some(name), None) => !is_own_file(&name.as_os_str().to_string_lossy()),
+                (Some(dir), Some(_)) => dir.as_os_str() == "refs",
+                _ => false,
+            };
+        }
+        !self.ignored.iter().any(|dir| path.starts_with(dir))
+            // The git directories of submodules
+            && !path.components().any(|component| component.as_os_str() == ".git")
+    }
+}
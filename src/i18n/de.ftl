@@ -0,0 +1,16 @@
+allowed = erlaubt
+not-allowed = nicht erlaubt
+denied = abgelehnt
+loaded = geladen
+not-loaded = nicht geladen
+stale = veraltet
+
+battery-full = voll
+battery-charging = lädt
+battery-discharging = entlädt
+battery-empty = leer
+battery-unknown = unbekannt
+
+explain-heading = Die Bestandteile deines Prompts:
+timings-heading = Die Laufzeiten der Module in deinem Prompt (>=1ms oder mit Ausgabe):
+problems-heading = Die Probleme der Module:
@@ -0,0 +1,19 @@
+# States shown by modules like `direnv` and `dotenv`
+allowed = allowed
+not-allowed = not allowed
+denied = denied
+loaded = loaded
+not-loaded = not loaded
+stale = stale
+
+# Charging states of the `battery` module
+battery-full = full
+battery-charging = charging
+battery-discharging = discharging
+battery-empty = empty
+battery-unknown = unknown
+
+# Headings of `starship explain` and `starship timings`
+explain-heading = Here's a breakdown of your prompt:
+timings-heading = Here are the timings of modules in your prompt (>=1ms or output):
+problems-heading = Here are the problems modules ran into:
@@ -0,0 +1,16 @@
+allowed = permitido
+not-allowed = no permitido
+denied = denegado
+loaded = cargado
+not-loaded = no cargado
+stale = obsoleto
+
+battery-full = llena
+battery-charging = cargando
+battery-discharging = descargando
+battery-empty = vacía
+battery-unknown = desconocido
+
+explain-heading = Este es el desglose de tu prompt:
+timings-heading = Estos son los tiempos de los módulos de tu prompt (>=1ms o con salida):
+problems-heading = Estos son los problemas de los módulos:
@@ -0,0 +1,16 @@
+allowed = autorisé
+not-allowed = non autorisé
+denied = refusé
+loaded = chargé
+not-loaded = non chargé
+stale = obsolète
+
+battery-full = pleine
+battery-charging = en charge
+battery-discharging = en décharge
+battery-empty = vide
+battery-unknown = inconnu
+
+explain-heading = Voici le détail de votre prompt :
+timings-heading = Voici les durées des modules de votre prompt (>=1ms ou avec sortie) :
+problems-heading = Voici les problèmes rencontrés par les modules :
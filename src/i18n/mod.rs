@@ -0,0 +1,122 @@
+//! Translations of the built-in messages, kept in the Fluent files next to this module.
+//!
+//! Messages are looked up by their id, like `not-loaded`. English is used for languages and
+//! messages without a translation.
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::FluentResource;
+use once_cell::sync::Lazy;
+use std::borrow::Cow;
+use unic_langid::LanguageIdentifier;
+
+const FALLBACK_LANGUAGE: &str = "en";
+
+/// The Fluent source of the messages of each language
+const RESOURCES: &[(&str, &str)] = &[
+    ("de", include_str!("de.ftl")),
+    ("en", include_str!("en.ftl")),
+    ("es", include_str!("es.ftl")),
+    ("fr", include_str!("fr.ftl")),
+];
+
+type Bundle = FluentBundle<FluentResource>;
+
+static BUNDLES: Lazy<Vec<(&str, Bundle)>> = Lazy::new(|| {
+    RESOURCES
+        .iter()
+        .filter_map(|(language, source)| Some((*language, load_bundle(language, source)?)))
+        .collect()
+});
+
+fn load_bundle(language: &str, source: &str) -> Option<Bundle> {
+    let id: LanguageIdentifier = language.parse().ok()?;
+    let resource = match FluentResource::try_new(source.to_string()) {
+        Ok(resource) => resource,
+        Err((_, errors)) => {
+            log::error!("Unable to parse the messages in {language:?}: {errors:?}");
+            return None;
+        }
+    };
+
+    let mut bundle = FluentBundle::new_concurrent(vec![id]);
+    // Terminals would show the marks that isolate arguments from the text around them
+    bundle.set_use_isolating(false);
+    if let Err(errors) = bundle.add_resource(resource) {
+        log::error!("Unable to load the messages in {language:?}: {errors:?}");
+    }
+    Some(bundle)
+}
+
+/// Formats the message `id` in `language`, if it has been translated into it
+fn format(language: &str, id: &str) -> Option<String> {
+    let (_, bundle) = BUNDLES.iter().find(|(name, _)| *name == language)?;
+    let pattern = bundle.get_message(id)?.value()?;
+    let mut errors = Vec::new();
+    let message = bundle.format_pattern(pattern, None, &mut errors);
+    if !errors.is_empty() {
+        log::warn!("Unable to format the message {id:?} in {language:?}: {errors:?}");
+    }
+    Some(message.into_owned())
+}
+
+/// Returns the built-in message `id` in `language`, falling back to English
+pub fn message(language: Option<&str>, id: &str) -> String {
+    language
+        .and_then(|language| format(language, id))
+        .or_else(|| format(FALLBACK_LANGUAGE, id))
+        .unwrap_or_else(|| id.to_string())
+}
+
+/// Translates a message from the configuration into `language` while it is the English text
+/// of the built-in message `id`, the default of the option
+///
+/// Messages changed by the user are returned unchanged.
+pub fn translate<'a>(language: Option<&str>, id: &str, configured: &'a str) -> Cow<'a, str> {
+    let is_default = || format(FALLBACK_LANGUAGE, id).as_deref() == Some(configured);
+    match language {
+        Some(language) if language != FALLBACK_LANGUAGE && is_default() => {
+            format(language, id).map_or(Cow::Borrowed(configured), Cow::Owned)
+        }
+        _ => Cow::Borrowed(configured),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The ids of the messages in a Fluent source
+    fn ids(source: &str) -> Vec<&str> {
+        source
+            .lines()
+            .filter(|line| !line.starts_with(['#', ' ']))
+            .filter_map(|line| line.split_once(" = "))
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    #[test]
+    fn all_messages_are_translated() {
+        let english = ids(include_str!("en.ftl"));
+        for (language, source) in RESOURCES {
+            assert_eq!(ids(source), english, "messages in {language:?}");
+            for id in &english {
+                assert!(format(language, id).is_some(), "{id:?} in {language:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn messages_fall_back_to_english() {
+        assert_eq!(message(Some("de"), "not-loaded"), "nicht geladen");
+        assert_eq!(message(Some("ja"), "not-loaded"), "not loaded");
+        assert_eq!(message(None, "battery-charging"), "charging");
+    }
+
+    #[test]
+    fn configured_messages_are_kept() {
+        assert_eq!(translate(Some("fr"), "stale", "stale"), "obsolète");
+        assert_eq!(translate(Some("fr"), "stale", "outdated"), "outdated");
+        assert_eq!(translate(None, "stale", "stale"), "stale");
+    }
+}
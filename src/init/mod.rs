@@ -213,7 +213,10 @@ pub fn init_main(shell_name: &str) -> io::Result<()> {
         "bash" => print_script(BASH_INIT, &starship_path.sprint_posix()?),
         "zsh" => print_script(ZSH_INIT, &starship_path.sprint_posix()?),
         "fish" => print_script(FISH_INIT, &starship_path.sprint_posix()?),
-        "powershell" => print_script(PWSH_INIT, &starship_path.sprint_pwsh()?),
+        "powershell" => print_script(
+            &PWSH_INIT.replace("::STARSHIP_MODULE::", PWSH_MODULE),
+            &starship_path.sprint_pwsh()?,
+        ),
         "ion" => print_script(ION_INIT, &starship_path.sprint()?),
         "elvish" => print_script(ELVISH_INIT, &starship_path.sprint_posix()?),
         "tcsh" => print_script(TCSH_INIT, &starship_path.sprint_posix()?),
@@ -229,6 +232,18 @@ pub fn init_main(shell_name: &str) -> io::Result<()> {
     Ok(())
 }
 
+/* This function (called when `--module` is passed to `starship init`) prints out the
+init script as a module that is saved to a file and imported, instead of being evaluated */
+pub fn init_module(shell_name: &str) -> io::Result<()> {
+    let starship_path = StarshipPath::init()?;
+
+    match shell_name {
+        "powershell" => print_script(PWSH_MODULE, &starship_path.sprint_pwsh()?),
+        _ => eprintln!("`--module` is only supported for powershell, not {shell_name}."),
+    }
+    Ok(())
+}
+
 fn print_script(script: &str, path: &str) {
     let script = script.replace("::STARSHIP::", path);
     print!("{script}");
@@ -257,6 +272,8 @@ const FISH_INIT: &str = include_str!("starship.fish");
 
 const PWSH_INIT: &str = include_str!("starship.ps1");
 
+const PWSH_MODULE: &str = include_str!("starship.psm1");
+
 const ION_INIT: &str = include_str!("starship.ion");
 
 const ELVISH_INIT: &str = include_str!("starship.elv");
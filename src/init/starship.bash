@@ -77,6 +77,9 @@ starship_precmd() {
         ARGS+=( --cmd-duration="${STARSHIP_DURATION}")
         STARSHIP_START_TIME=""
     fi
+    if [[ ${STARSHIP_STATUS_BAR-} ]]; then
+        printf '%s' "$(::STARSHIP:: prompt --status-bar "${ARGS[@]}")"
+    fi
     PS1="$(::STARSHIP:: prompt "${ARGS[@]}")"
     if [[ ${BLE_ATTACHED-} ]]; then
         local nlns=${PS1//[!$'\n']}
@@ -148,6 +151,24 @@ STARSHIP_SESSION_KEY="$RANDOM$RANDOM$RANDOM$RANDOM$RANDOM"; # Random generates a
 STARSHIP_SESSION_KEY="${STARSHIP_SESSION_KEY}0000000000000000" # Pad it to 16+ chars.
 export STARSHIP_SESSION_KEY=${STARSHIP_SESSION_KEY:0:16}; # Trim to 16-digits if excess.
 
+# Create the session state directory, and clean it up on exit unless another EXIT trap is set
+::STARSHIP:: session start --key "$STARSHIP_SESSION_KEY" >/dev/null 2>&1
+starship_session_end() {
+    ::STARSHIP:: session end >/dev/null 2>&1
+    # Give the rows of the status bar back to the terminal
+    if [[ ${STARSHIP_STATUS_BAR-} ]]; then
+        printf '\e[r'
+    fi
+}
+if [[ -z "$(trap -p EXIT)" ]]; then
+    trap starship_session_end EXIT
+fi
+
+# Draw the status bar before each prompt if `status_bar_format` is set
+if [[ -n "$(::STARSHIP:: prompt --status-bar)" ]]; then
+    STARSHIP_STATUS_BAR=1
+fi
+
 # Set the continuation prompt
 PS2="$(::STARSHIP:: prompt --continuation)"
 
@@ -94,3 +94,23 @@ end
 # Set up the session key that will be used to store logs
 # We don't use `random [min] [max]` because it is unavailable in older versions of fish shell
 set -gx STARSHIP_SESSION_KEY (string sub -s1 -l16 (random)(random)(random)(random)(random)0000000000000000)
+
+# Create the session state directory, and clean it up when the shell exits
+::STARSHIP:: session start --key $STARSHIP_SESSION_KEY >/dev/null 2>&1
+function __starship_session_end --on-event fish_exit
+    ::STARSHIP:: session end >/dev/null 2>&1
+    # Give the rows of the status bar back to the terminal
+    if set -q STARSHIP_STATUS_BAR
+        printf \e\[r
+    end
+end
+
+# Draw the status bar before each prompt if `status_bar_format` is set
+if string length -q -- (::STARSHIP:: prompt --status-bar)
+    set -g STARSHIP_STATUS_BAR 1
+    function __starship_status_bar --on-event fish_prompt
+        set -l STARSHIP_CMD_PIPESTATUS $pipestatus
+        set -l STARSHIP_CMD_STATUS $status
+        ::STARSHIP:: prompt --status-bar --terminal-width="$COLUMNS" --status=$STARSHIP_CMD_STATUS --pipestatus="$STARSHIP_CMD_PIPESTATUS" --cmd-duration="$CMD_DURATION$cmd_duration" --jobs=(count (jobs -p))
+    end
+end
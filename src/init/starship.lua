@@ -28,37 +28,60 @@ clink.onendedit(function (curr_line)
   end
 end)
 
+-- The state of the shell, as passed on to every starship invocation
+local function starship_args()
+  return " --status="..os.geterrorlevel()
+    .." --cmd-duration="..math.floor(curr_duration*1000)
+    .." --terminal-width="..console.getwidth()
+    .." --keymap="..rl.getvariable('keymap')
+end
+
+local function starship_run(args)
+  local handle = io.popen([[::STARSHIP::]]..args)
+  if handle == nil then
+    return ""
+  end
+  local output = handle:read("*a")
+  handle:close()
+  return output
+end
+
+-- cmd asks for the rest of a line continued with `^` with the prompt `More? `
+local function is_continuation(prompt)
+  return string.match(prompt, "^More%? ?$") ~= nil
+end
+
 function starship_prompt:filter(prompt)
+  if is_continuation(prompt) then
+    return starship_run(" prompt --continuation")
+  end
   if starship_preprompt_user_func ~= nil then
     starship_preprompt_user_func(prompt)
   end
-  return io.popen([[::STARSHIP::]].." prompt"
-    .." --status="..os.geterrorlevel()
-    .." --cmd-duration="..math.floor(curr_duration*1000)
-    .." --terminal-width="..console.getwidth()
-    .." --keymap="..rl.getvariable('keymap')
-  ):read("*a")
+  return starship_run(" prompt"..starship_args())
 end
 
 function starship_prompt:rightfilter(prompt)
-  return io.popen([[::STARSHIP::]].." prompt --right"
-    .." --status="..os.geterrorlevel()
-    .." --cmd-duration="..math.floor(curr_duration*1000)
-    .." --terminal-width="..console.getwidth()
-    .." --keymap="..rl.getvariable('keymap')
-  ):read("*a")
+  if is_continuation(prompt) then
+    return ""
+  end
+  return starship_run(" prompt --right"..starship_args())
 end
 
-if starship_transient_prompt_func ~= nil then
-  function starship_prompt:transientfilter(prompt)
+-- Transient prompts are enabled with `clink set prompt.transient`. The functions are looked up
+-- on every prompt, so they can also be defined after loading starship.
+function starship_prompt:transientfilter(prompt)
+  if starship_transient_prompt_func ~= nil then
     return starship_transient_prompt_func(prompt)
   end
+  return starship_run(" module character"..starship_args())
 end
 
-if starship_transient_rprompt_func ~= nil then
-  function starship_prompt:transientrightfilter(prompt)
+function starship_prompt:transientrightfilter(prompt)
+  if starship_transient_rprompt_func ~= nil then
     return starship_transient_rprompt_func(prompt)
   end
+  return ""
 end
 
 local characterset = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz"
@@ -71,3 +94,7 @@ end
 
 os.setenv('STARSHIP_SHELL', 'cmd')
 os.setenv('STARSHIP_SESSION_KEY', randomkey)
+
+-- Create the session state directory. Clink has no event for the exit of cmd, so the directory
+-- is removed with the stale sessions once it hasn't been used for a while.
+starship_run(" session start --key="..randomkey)
@@ -6,21 +6,52 @@ extern crate shadow_rs;
 shadow!(shadow);
 
 // Lib is present to allow for benchmarking
+mod accessibility;
+mod alloc_stats;
+pub mod bench;
 pub mod bug_report;
+mod color_depth;
 pub mod config;
 pub mod configs;
 pub mod configure;
 pub mod context;
+pub mod context_container;
 pub mod context_env;
+pub mod context_fingerprint;
+pub mod context_locale;
+pub mod context_msys;
+pub mod context_power;
+pub mod context_process;
+pub mod context_project;
+pub mod context_session;
 pub mod formatter;
+pub mod git_watch;
+mod i18n;
 pub mod init;
+pub mod lint;
+pub mod local_config;
 pub mod logger;
 pub mod module;
+pub mod module_cache;
 mod modules;
+pub mod playground;
 pub mod print;
-mod segment;
+mod prompt_cache;
+pub mod scenario;
+pub mod screenshot;
+pub mod segment;
+#[cfg(feature = "self-update")]
+pub mod self_update;
 mod serde_utils;
+pub mod session;
+pub mod stats;
+pub mod theme;
 mod utils;
+mod version_cache;
 
-#[cfg(test)]
-mod test;
+pub use modules::fill_todo_index;
+#[cfg(feature = "embed")]
+pub use print::render;
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test;
@@ -0,0 +1,448 @@
+use nu_ansi_term::Color;
+use std::collections::BTreeSet;
+use toml::Value;
+
+use crate::config::parse_style_string;
+use crate::configs::FullConfig;
+use crate::context::{Context, GLOBAL_VARIABLES};
+use crate::formatter::{StringFormatter, VariableHolder};
+use crate::module::ALL_MODULES;
+
+/// The minimum contrast ratio WCAG 2 requires for normal text (level AA)
+const MIN_CONTRAST_RATIO: f64 = 4.5;
+
+/// The programs a module runs to find its version, any of which is enough
+const MODULE_TOOLS: &[(&str, &[&str])] = &[
+    ("bun", &["bun"]),
+    ("cmake", &["cmake"]),
+    ("crystal", &["crystal"]),
+    ("dart", &["dart"]),
+    ("deno", &["deno"]),
+    ("dotnet", &["dotnet"]),
+    ("elixir", &["elixir"]),
+    ("erlang", &["erl"]),
+    ("gleam", &["gleam"]),
+    ("golang", &["go"]),
+    ("haskell", &["ghc", "stack"]),
+    ("helm", &["helm"]),
+    ("java", &["java"]),
+    ("julia", &["julia"]),
+    ("kotlin", &["kotlin", "kotlinc"]),
+    ("lua", &["lua", "luajit"]),
+    ("nim", &["nim"]),
+    ("nodejs", &["node"]),
+    ("ocaml", &["ocaml"]),
+    ("perl", &["perl"]),
+    ("php", &["php"]),
+    ("python", &["python", "python3", "python2"]),
+    ("ruby", &["ruby"]),
+    ("rust", &["rustc"]),
+    ("scala", &["scalac", "scala-cli"]),
+    ("swift", &["swift"]),
+    ("terraform", &["terraform", "tofu"]),
+    ("zig", &["zig"]),
+];
+
+/// A problem found in the configuration, with the dotted path of the offending option
+#[derive(Debug, PartialEq, Eq)]
+pub struct LintIssue {
+    pub path: String,
+    pub message: String,
+}
+
+/// Prints the problems of the effective configuration and returns whether there were none
+pub fn print_lint(context: &Context) -> bool {
+    let issues = lint(context);
+    if issues.is_empty() {
+        println!("No problems found in the configuration.");
+        return true;
+    }
+    for issue in &issues {
+        println!("{}: {}", issue.path, issue.message);
+    }
+    println!("\nFound {} problem(s).", issues.len());
+    false
+}
+
+/// Analyzes the configuration for styles that are hard to read, symbols the terminal may not
+/// be able to show, misspelled format variables and modules whose tool is not installed
+pub fn lint(context: &Context) -> Vec<LintIssue> {
+    let user_config = context.config.config.clone().unwrap_or_default();
+    let effective =
+        Value::try_from(FullConfig::load(&user_config)).unwrap_or(Value::Boolean(false));
+    let defaults = Value::try_from(FullConfig::default()).unwrap_or(Value::Boolean(false));
+
+    let mut issues = Vec::new();
+    let mut linter = Linter {
+        context,
+        defaults: &defaults,
+        utf8: locale_is_utf8(context),
+        issues: &mut issues,
+    };
+    linter.walk("", &effective);
+    lint_tools(&user_config, &effective, &mut issues);
+    issues
+}
+
+struct Linter<'a, 'b> {
+    context: &'a Context<'b>,
+    defaults: &'a Value,
+    utf8: bool,
+    issues: &'a mut Vec<LintIssue>,
+}
+
+impl Linter<'_, '_> {
+    fn walk(&mut self, path: &str, value: &Value) {
+        match value {
+            Value::Table(table) => {
+                for (key, value) in table {
+                    let path = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{path}.{key}")
+                    };
+                    self.walk(&path, value);
+                }
+            }
+            Value::Array(values) => {
+                for (idx, value) in values.iter().enumerate() {
+                    self.walk(&format!("{path}[{idx}]"), value);
+                }
+            }
+            Value::String(text) => self.check_string(path, text),
+            _ => {}
+        }
+    }
+
+    fn check_string(&mut self, path: &str, text: &str) {
+        let key = path.rsplit('.').next().unwrap_or(path);
+        let key = key.split('[').next().unwrap_or(key);
+        let parent = path.rsplit_once('.').map_or("", |(parent, _)| parent);
+
+        if key == "style" || key.ends_with("_style") {
+            self.check_contrast(path, text);
+        }
+        if key == "format" || key.ends_with("_format") {
+            self.check_format(path, text);
+        }
+        let is_symbol =
+            key.ends_with("symbol") || parent.ends_with(".symbols") || parent == "symbols";
+        if is_symbol && !self.utf8 && !text.is_ascii() {
+            self.issues.push(LintIssue {
+                path: path.to_string(),
+                message: format!(
+                    "{text:?} contains characters outside of the character set of the locale"
+                ),
+            });
+        }
+    }
+
+    fn check_contrast(&mut self, path: &str, style: &str) {
+        let Some(style) = parse_style_string(style, Some(self.context)) else {
+            return;
+        };
+        let style = style.to_ansi_style(None);
+        let (Some(fg), Some(bg)) = (
+            style.foreground.and_then(to_rgb),
+            style.background.and_then(to_rgb),
+        ) else {
+            return;
+        };
+        let ratio = contrast_ratio(fg, bg);
+        if ratio < MIN_CONTRAST_RATIO {
+            self.issues.push(LintIssue {
+                path: path.to_string(),
+                message: format!(
+                    "the contrast ratio of {ratio:.1}:1 is below the minimum of {MIN_CONTRAST_RATIO}:1 for readable text"
+                ),
+            });
+        }
+    }
+
+    fn check_format(&mut self, path: &str, format: &str) {
+        let formatter = match StringFormatter::new(format) {
+            Ok(formatter) => formatter,
+            Err(error) => {
+                self.issues.push(LintIssue {
+                    path: path.to_string(),
+                    message: format!("invalid format string: {error}"),
+                });
+                return;
+            }
+        };
+
+        let module = path.rsplit_once('.').map(|(module, _)| module);
+        let known = match module {
+            // The root formats and profiles reference modules
+            None | Some("profiles") => root_variables(self.context),
+            Some(module) => module_variables(self.defaults, module),
+        };
+        for variable in formatter.get_variables() {
+            if known.contains(&variable) || GLOBAL_VARIABLES.contains(&variable.as_str()) {
+                continue;
+            }
+            let suggestion = known
+                .iter()
+                .map(|candidate| (strsim::levenshtein(candidate, &variable), candidate))
+                .filter(|(distance, _)| *distance <= 2)
+                .min();
+            let message = match (module, suggestion) {
+                (None | Some("profiles"), suggestion) => format!(
+                    "${variable} is not a module{}",
+                    suggestion.map_or(String::new(), |(_, name)| format!(
+                        ", did you mean ${name}?"
+                    ))
+                ),
+                // Modules provide more variables than their default formats use, so only
+                // report what looks like a typo
+                (Some(_), Some((_, name))) => {
+                    format!("${variable} is not a variable of the module, did you mean ${name}?")
+                }
+                (Some(_), None) => continue,
+            };
+            self.issues.push(LintIssue {
+                path: path.to_string(),
+                message,
+            });
+        }
+    }
+}
+
+/// The names that can be used in the root `format`: all modules and the user's custom modules
+pub(crate) fn root_variables(context: &Context) -> BTreeSet<String> {
+    let mut variables: BTreeSet<String> = ALL_MODULES.iter().map(ToString::to_string).collect();
+    variables.insert("all".to_string());
+    let config = &context.config;
+    for (prefix, modules) in [
+        ("custom", config.get_custom_modules()),
+        ("env_var", config.get_env_var_modules()),
+    ] {
+        variables.insert(prefix.to_string());
+        for name in modules.into_iter().flat_map(|modules| modules.keys()) {
+            variables.insert(format!("{prefix}.{name}"));
+        }
+    }
+    variables
+}
+
+/// The variables the default formats of a module use
+pub(crate) fn module_variables(defaults: &Value, module: &str) -> BTreeSet<String> {
+    let mut table = Some(defaults);
+    for key in module.split('.') {
+        table = table.and_then(|table| table.get(key));
+    }
+    let Some(Value::Table(table)) = table else {
+        return BTreeSet::new();
+    };
+    table
+        .iter()
+        .filter(|(key, _)| *key == "format" || key.ends_with("_format"))
+        .filter_map(|(_, value)| StringFormatter::new(value.as_str()?).ok())
+        .flat_map(|formatter| formatter.get_variables())
+        .collect()
+}
+
+/// Reports modules configured by the user whose tool cannot be found
+fn lint_tools(user_config: &toml::Table, effective: &Value, issues: &mut Vec<LintIssue>) {
+    for (module, tools) in MODULE_TOOLS {
+        // Modules the user never configured stay quiet, most people only use a few languages
+        if !user_config.contains_key(*module) {
+            continue;
+        }
+        let disabled = effective
+            .get(module)
+            .and_then(|config| config.get("disabled"))
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        if disabled || tools.iter().any(|tool| which::which(tool).is_ok()) {
+            continue;
+        }
+        issues.push(LintIssue {
+            path: (*module).to_string(),
+            message: format!(
+                "the module is enabled, but {} was not found in PATH",
+                tools.join(" or ")
+            ),
+        });
+    }
+}
+
+/// Whether the locale of the terminal can encode characters beyond ASCII
+fn locale_is_utf8(context: &Context) -> bool {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|name| context.get_env(name).filter(|value| !value.is_empty()));
+    match locale {
+        // Terminals on Windows and macOS default to UTF-8 without a locale
+        None => true,
+        Some(locale) => {
+            let locale = locale.to_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        }
+    }
+}
+
+/// Approximates a terminal color with the default palette of xterm
+fn to_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    const ANSI: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 0, 0),
+        (0, 205, 0),
+        (205, 205, 0),
+        (0, 0, 238),
+        (205, 0, 205),
+        (0, 205, 205),
+        (229, 229, 229),
+        (127, 127, 127),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (92, 92, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+    let index = match color {
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Purple | Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::White => 7,
+        Color::DarkGray => 8,
+        Color::LightRed => 9,
+        Color::LightGreen => 10,
+        Color::LightYellow => 11,
+        Color::LightBlue => 12,
+        Color::LightPurple | Color::LightMagenta => 13,
+        Color::LightCyan => 14,
+        Color::LightGray => 15,
+        Color::Fixed(index) => index,
+        Color::Rgb(r, g, b) => return Some((r, g, b)),
+        // The default colors depend on the terminal theme
+        Color::Default => return None,
+    };
+    Some(match index {
+        0..=15 => ANSI[usize::from(index)],
+        16..=231 => {
+            let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+            let index = index - 16;
+            (level(index / 36), level(index / 6 % 6), level(index % 6))
+        }
+        232..=255 => {
+            let gray = 8 + (index - 232) * 10;
+            (gray, gray, gray)
+        }
+    })
+}
+
+/// The contrast ratio between two colors as defined by WCAG 2, from 1 to 21
+fn contrast_ratio(a: (u8, u8, u8), b: (u8, u8, u8)) -> f64 {
+    fn luminance((r, g, b): (u8, u8, u8)) -> f64 {
+        let channel = |value: u8| {
+            let value = f64::from(value) / 255.0;
+            if value <= 0.03928 {
+                value / 12.92
+            } else {
+                ((value + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * channel(r) + 0.7152 * channel(g) + 0.0722 * channel(b)
+    }
+    let (a, b) = (luminance(a), luminance(b));
+    let (lighter, darker) = if a > b { (a, b) } else { (b, a) };
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::default_context;
+
+    fn lint_config(config: toml::Table) -> Vec<LintIssue> {
+        let mut context = default_context().set_config(config);
+        context.env.insert("LANG", "C".to_string());
+        lint(&context)
+    }
+
+    #[test]
+    fn contrast_ratios() {
+        assert!((contrast_ratio((0, 0, 0), (255, 255, 255)) - 21.0).abs() < 0.01);
+        assert!((contrast_ratio((10, 20, 30), (10, 20, 30)) - 1.0).abs() < 0.01);
+        assert_eq!(to_rgb(Color::Fixed(196)), Some((255, 0, 0)));
+        assert_eq!(to_rgb(Color::Fixed(244)), Some((128, 128, 128)));
+    }
+
+    #[test]
+    fn low_contrast_style() {
+        let issues = lint_config(toml::toml! {
+            [directory]
+            style = "fg:#555555 bg:#333333"
+            read_only_style = "black bg:white"
+        });
+
+        assert!(issues.iter().any(|issue| issue.path == "directory.style"));
+        assert!(issues
+            .iter()
+            .all(|issue| issue.path != "directory.read_only_style"));
+    }
+
+    #[test]
+    fn unknown_variables() {
+        let issues = lint_config(toml::toml! {
+            format = "$directoy$git_branch$all"
+            [git_branch]
+            format = "$symbol$brnch "
+            [os]
+            format = "$symbol$codename"
+        });
+        let messages: Vec<(&str, &str)> = issues
+            .iter()
+            .filter(|issue| issue.path.ends_with("format"))
+            .map(|issue| (issue.path.as_str(), issue.message.as_str()))
+            .collect();
+
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    "format",
+                    "$directoy is not a module, did you mean $directory?"
+                ),
+                (
+                    "git_branch.format",
+                    "$brnch is not a variable of the module, did you mean $branch?"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn symbols_outside_of_charset() {
+        let issues = lint_config(toml::toml! {
+            [git_branch]
+            symbol = "git:"
+            [directory]
+            read_only = " ro"
+        });
+        let paths: Vec<&str> = issues.iter().map(|issue| issue.path.as_str()).collect();
+
+        assert!(paths.contains(&"character.success_symbol"));
+        assert!(!paths.contains(&"git_branch.symbol"));
+        assert!(!paths.contains(&"directory.read_only"));
+    }
+
+    #[test]
+    fn utf8_locales() {
+        let mut context = default_context();
+        assert!(locale_is_utf8(&context));
+
+        context.env.insert("LANG", "C".to_string());
+        assert!(!locale_is_utf8(&context));
+
+        context.env.insert("LC_ALL", "de_DE.utf8".to_string());
+        assert!(locale_is_utf8(&context));
+    }
+}
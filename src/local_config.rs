@@ -0,0 +1,275 @@
+//! Configuration files checked into repositories: `.starship.toml` in the root of a repository
+//! or one of its directories, applied on top of the configuration of the user.
+//!
+//! Anyone who can push to a repository can change its configuration file, so only the keys
+//! that change how the prompt looks are taken from it until it is allowed with
+//! `starship allow`, like the `.envrc` files of direnv. Commands of `custom` modules and all
+//! other settings are ignored until then. An allowed file is recorded by the hash of its path
+//! and contents, so every change to the file has to be allowed again.
+
+use sha1::{Digest, Sha1};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::context::home_dir;
+use crate::context_env::Env;
+use crate::theme;
+use crate::utils::{self, encode_to_hex};
+
+pub const FILE_NAME: &str = ".starship.toml";
+
+/// Finds the configuration file closest to `dir` within its repository
+pub fn find(dir: &Path) -> Option<PathBuf> {
+    let mut found = None;
+    for dir in dir.ancestors() {
+        let file = dir.join(FILE_NAME);
+        if found.is_none() && file.is_file() {
+            found = Some(file);
+        }
+        // Files above the root of the repository belong to another project
+        if dir.join(".git").exists() {
+            return found;
+        }
+    }
+    None
+}
+
+/// The configuration file `path` points to, or the one of the repository `path` is in
+pub fn resolve(path: &Path) -> io::Result<PathBuf> {
+    let path = dunce::canonicalize(path)?;
+    if path.is_file() {
+        return Ok(path);
+    }
+    find(&path).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no {FILE_NAME} in the repository of {}", path.display()),
+        )
+    })
+}
+
+/// Reads the configuration file of the repository `dir` is in, keeping only the keys that
+/// change how the prompt looks unless the file was allowed
+pub fn load(dir: &Path, env: &Env) -> Option<toml::Table> {
+    let file = find(dir)?;
+    let content = utils::read_file(&file).ok()?;
+    let config: toml::Table = match toml::from_str(&content) {
+        Ok(config) => config,
+        Err(error) => {
+            log::error!("Unable to parse {}: {error}", file.display());
+            return None;
+        }
+    };
+
+    if is_allowed(&file, &content, env) {
+        log::debug!("Using the allowed configuration {}", file.display());
+        return Some(config);
+    }
+
+    // Custom modules run commands and `env_var` modules show any environment variable, like
+    // secrets, so neither is configured or referenced by the prompt until the file is allowed
+    let mut presentation = theme::presentation_keys(&config, 0);
+    presentation.remove("custom");
+    presentation.remove("env_var");
+    remove_formats_of_env(&mut presentation);
+    if presentation != config {
+        log::warn!(
+            "Only the look of the prompt is taken from {}. Run `starship allow` to use its commands and settings after reviewing it.",
+            file.display()
+        );
+    }
+    Some(presentation)
+}
+
+/// Removes the formats, like `format` or the `profiles`, that refer to a custom or `env_var`
+/// module by its name, which shows the environment variable of that name even without a
+/// configuration
+fn remove_formats_of_env(table: &mut toml::Table) {
+    table.retain(|_, value| match value {
+        toml::Value::String(format) => !["${env_var.", "${custom."]
+            .iter()
+            .any(|prefix| format.contains(prefix)),
+        toml::Value::Table(table) => {
+            remove_formats_of_env(table);
+            true
+        }
+        _ => true,
+    });
+}
+
+/// Allows all settings of a configuration file, in its current contents
+pub fn allow(file: &Path, env: &Env) -> io::Result<()> {
+    let content = fs::read_to_string(file)?;
+    let allow_file = allow_file(file, &content, env).ok_or_else(no_data_dir)?;
+    if let Some(dir) = allow_file.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(allow_file, file.to_string_lossy().as_bytes())
+}
+
+/// Revokes all allowed contents of a configuration file
+pub fn deny(file: &Path, env: &Env) -> io::Result<()> {
+    let dir = allow_dir(env).ok_or_else(no_data_dir)?;
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(error) => return Err(error),
+    };
+    let file = file.to_string_lossy();
+    for entry in entries {
+        let path = entry?.path();
+        if fs::read_to_string(&path).map_or(false, |allowed| allowed == file) {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether the configuration file of the repository `dir` is in was allowed, which also allows
+/// modules to run the tools of the repository, like `make`, that run commands of its files
+pub fn is_repo_allowed(dir: &Path, env: &Env) -> bool {
+    find(dir).map_or(false, |file| is_file_allowed(&file, env))
+}
+
+/// Whether the configuration file was allowed in its current contents
+pub fn is_file_allowed(file: &Path, env: &Env) -> bool {
+    utils::read_file(file).map_or(false, |content| is_allowed(file, &content, env))
+}
+
+fn is_allowed(file: &Path, content: &str, env: &Env) -> bool {
+    allow_file(file, content, env).map_or(false, |allow_file| allow_file.is_file())
+}
+
+/// `$XDG_DATA_HOME/starship/allow`, or `~/.local/share/starship/allow`
+fn allow_dir(env: &Env) -> Option<PathBuf> {
+    let data_dir = match env.get_env("XDG_DATA_HOME") {
+        Some(data_dir) => PathBuf::from(data_dir),
+        None => home_dir(env)?.join(".local").join("share"),
+    };
+    Some(data_dir.join("starship").join("allow"))
+}
+
+fn allow_file(file: &Path, content: &str, env: &Env) -> Option<PathBuf> {
+    let mut hasher = Sha1::new();
+    hasher.update(file.to_string_lossy().as_bytes());
+    hasher.update(b"\n");
+    hasher.update(content.as_bytes());
+    Some(allow_dir(env)?.join(encode_to_hex(&hasher.finalize())))
+}
+
+fn no_data_dir() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        "unable to determine the data directory, set XDG_DATA_HOME",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo_with_config(config: &str) -> io::Result<tempfile::TempDir> {
+        let repo = tempfile::tempdir()?;
+        fs::create_dir(repo.path().join(".git"))?;
+        fs::create_dir(repo.path().join("src"))?;
+        fs::write(repo.path().join(FILE_NAME), config)?;
+        Ok(repo)
+    }
+
+    fn env_with_data_dir(data_dir: &Path) -> Env<'static> {
+        let mut env = Env::default();
+        env.insert("XDG_DATA_HOME", data_dir.to_string_lossy().to_string());
+        env
+    }
+
+    #[test]
+    fn finds_file_within_repository() -> io::Result<()> {
+        let repo = repo_with_config("")?;
+        let file = repo.path().join(FILE_NAME);
+        assert_eq!(find(&repo.path().join("src")), Some(file));
+
+        let outside = tempfile::tempdir()?;
+        fs::write(outside.path().join(FILE_NAME), "")?;
+        assert_eq!(find(outside.path()), None);
+        repo.close()?;
+        outside.close()
+    }
+
+    #[test]
+    fn commands_need_to_be_allowed() -> io::Result<()> {
+        let repo = repo_with_config(
+            r#"
+format = "$custom$character"
+command_timeout = 5000
+
+[custom.make]
+command = "make status"
+format = "[$output]($style)"
+"#,
+        )?;
+        let file = repo.path().join(FILE_NAME);
+        let data_dir = tempfile::tempdir()?;
+        let env = env_with_data_dir(data_dir.path());
+
+        let expected = toml::toml! {
+            format = "$custom$character"
+        };
+        assert_eq!(load(repo.path(), &env), Some(expected));
+
+        allow(&file, &env)?;
+        let config = load(repo.path(), &env).unwrap();
+        assert_eq!(
+            config["custom"]["make"]["command"].as_str(),
+            Some("make status")
+        );
+
+        // Changed files have to be allowed again
+        fs::write(&file, "[custom.make]\ncommand = \"rm -rf ~\"")?;
+        assert_eq!(load(repo.path(), &env), Some(toml::Table::new()));
+
+        fs::write(&file, "[custom.make]\ncommand = \"make status\"")?;
+        allow(&file, &env)?;
+        assert!(load(repo.path(), &env).unwrap().contains_key("custom"));
+        assert!(is_repo_allowed(&repo.path().join("src"), &env));
+        deny(&file, &env)?;
+        assert_eq!(load(repo.path(), &env), Some(toml::Table::new()));
+        assert!(!is_repo_allowed(&repo.path().join("src"), &env));
+
+        repo.close()?;
+        data_dir.close()
+    }
+
+    #[test]
+    fn environment_variables_need_to_be_allowed() -> io::Result<()> {
+        let repo = repo_with_config(
+            r#"
+format = "${env_var.AWS_SECRET_ACCESS_KEY}$character"
+right_format = "$time"
+
+[env_var.AWS_SECRET_ACCESS_KEY]
+format = "[$env_value]($style)"
+style = "red"
+"#,
+        )?;
+        let file = repo.path().join(FILE_NAME);
+        let data_dir = tempfile::tempdir()?;
+        let env = env_with_data_dir(data_dir.path());
+
+        let expected = toml::toml! {
+            right_format = "$time"
+        };
+        assert_eq!(load(repo.path(), &env), Some(expected));
+
+        allow(&file, &env)?;
+        let config = load(repo.path(), &env).unwrap();
+        assert!(config.contains_key("env_var"));
+        assert_eq!(
+            config["format"].as_str(),
+            Some("${env_var.AWS_SECRET_ACCESS_KEY}$character")
+        );
+
+        repo.close()?;
+        data_dir.close()
+    }
+}
@@ -1,17 +1,59 @@
 use crate::utils;
 use log::{Level, LevelFilter, Metadata, Record};
 use nu_ansi_term::Color;
-use once_cell::sync::OnceCell;
+use once_cell::sync::{Lazy, OnceCell};
 use std::{
+    cell::RefCell,
     cmp,
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     env,
     fs::{self, File, OpenOptions},
     io::Write,
     path::{Path, PathBuf},
-    sync::{Mutex, RwLock},
+    sync::{Arc, Mutex, RwLock},
 };
 
+/// Warnings and errors logged while `capture_problems` is running, by the module they were
+/// logged for
+static CAPTURED_PROBLEMS: Lazy<Mutex<HashMap<Arc<str>, Vec<String>>>> = Lazy::new(Default::default);
+
+thread_local! {
+    /// The module this thread currently works for
+    static CURRENT_MODULE: RefCell<Option<Arc<str>>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` and returns its result along with the warnings and errors it logged for `module`
+///
+/// Problems are captured regardless of the configured log level and of duplicate detection.
+/// Modules render in parallel, and the variables of a module may be computed on other threads,
+/// which have to run their work with `with_module`.
+pub fn capture_problems<T>(module: &str, f: impl FnOnce() -> T) -> (T, Vec<String>) {
+    let module: Arc<str> = Arc::from(module);
+    if let Ok(mut captured) = CAPTURED_PROBLEMS.lock() {
+        captured.entry(module.clone()).or_default();
+    }
+    let result = with_module(Some(module.clone()), f);
+    let problems = CAPTURED_PROBLEMS
+        .lock()
+        .ok()
+        .and_then(|mut captured| captured.remove(&module))
+        .unwrap_or_default();
+    (result, problems)
+}
+
+/// The module the current thread works for, to pass on to `with_module` on other threads
+pub fn current_module() -> Option<Arc<str>> {
+    CURRENT_MODULE.with(|current| current.borrow().clone())
+}
+
+/// Runs `f` on the current thread for `module`, so the problems it logs are captured for it
+pub fn with_module<T>(module: Option<Arc<str>>, f: impl FnOnce() -> T) -> T {
+    let outer = CURRENT_MODULE.with(|current| current.replace(module));
+    let result = f();
+    CURRENT_MODULE.with(|current| current.replace(outer));
+    result
+}
+
 pub struct StarshipLogger {
     log_file: OnceCell<Mutex<File>>,
     log_file_path: PathBuf,
@@ -142,6 +184,16 @@ impl log::Log for StarshipLogger {
     }
 
     fn log(&self, record: &Record) {
+        if record.level() <= Level::Warn {
+            if let Some(module) = current_module() {
+                if let Ok(mut captured) = CAPTURED_PROBLEMS.lock() {
+                    if let Some(problems) = captured.get_mut(&module) {
+                        problems.push(record.args().to_string());
+                    }
+                }
+            }
+        }
+
         // Early return if the log level is not enabled
         if !self.enabled(record.metadata()) {
             return;
@@ -352,6 +404,33 @@ mod test {
         log_dir.close()
     }
 
+    #[test]
+    fn test_capture_problems_of_other_threads() -> io::Result<()> {
+        let log_dir = tempfile::tempdir()?;
+        let mut logger = StarshipLogger::default();
+        logger.set_log_file_path(log_dir.path().join("test.log"));
+        let warn = |message: &str| {
+            logger.log(
+                &Record::builder()
+                    .level(Level::Warn)
+                    .args(format_args!("{message}"))
+                    .build(),
+            );
+        };
+
+        let ((), problems) = capture_problems("git_status", || {
+            let module = current_module();
+            std::thread::scope(|scope| {
+                scope.spawn(|| with_module(module, || warn("timed out")));
+                // Threads working for no module, or for another one, don't count
+                scope.spawn(|| warn("unrelated"));
+                scope.spawn(|| with_module(Some(Arc::from("aws")), || warn("expired")));
+            });
+        });
+        assert_eq!(problems, vec!["timed out".to_string()]);
+        log_dir.close()
+    }
+
     #[test]
     #[cfg(unix)]
     fn test_cleanup() -> io::Result<()> {
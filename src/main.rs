@@ -8,8 +8,6 @@ use std::time::SystemTime;
 
 use clap::{CommandFactory, Parser, Subcommand};
 use clap_complete::{generate, Shell as CompletionShell};
-use rand::distributions::Alphanumeric;
-use rand::Rng;
 use starship::context::{Context, Properties, Target};
 use starship::module::ALL_MODULES;
 use starship::*;
@@ -30,6 +28,29 @@ struct Cli {
 
 #[derive(Subcommand, Debug)]
 enum Commands {
+    /// Allow the commands and settings of the `.starship.toml` of a repository, after reviewing it
+    Allow {
+        /// The configuration file, or a directory of the repository
+        #[clap(default_value = ".")]
+        path: PathBuf,
+    },
+    /// Renders the prompt repeatedly and reports how long each module takes
+    Bench {
+        /// How often the prompt is rendered
+        #[clap(short = 'n', long, default_value_t = 20)]
+        runs: usize,
+        /// Render the prompt of a scenario file instead of the current directory
+        #[clap(long)]
+        scenario: Option<PathBuf>,
+        /// Another configuration to render the prompt with, for comparison
+        #[clap(long)]
+        compare: Option<PathBuf>,
+        /// Print the durations of all runs as JSON
+        #[clap(long)]
+        json: bool,
+        #[clap(flatten)]
+        properties: Properties,
+    },
     /// Create a pre-populated GitHub issue with information about your configuration
     BugReport,
     /// Generate starship shell completions for your shell to stdout
@@ -44,14 +65,30 @@ enum Commands {
         name: Option<String>,
         /// Value to place into that key
         value: Option<String>,
+        /// Check the configuration for unreadable styles, unsupported symbols, unknown
+        /// variables and missing tools
+        #[clap(long, conflicts_with = "name")]
+        lint: bool,
+    },
+    /// Revoke the commands and settings of the `.starship.toml` of a repository
+    Deny {
+        /// The configuration file, or a directory of the repository
+        #[clap(default_value = ".")]
+        path: PathBuf,
     },
     /// Explains the currently showing modules
     Explain(Properties),
+    /// Counts the markers of the todos module in the repository in the current directory
+    #[clap(hide = true)]
+    IndexTodos,
     ///  Prints the shell function used to execute starship
     Init {
         shell: String,
         #[clap(long)]
         print_full_init: bool,
+        /// Print the init script as a module to save and import, for powershell
+        #[clap(long, conflicts_with = "print_full_init")]
+        module: bool,
     },
     ///  Prints a specific prompt module
     Module {
@@ -64,6 +101,14 @@ enum Commands {
         #[clap(flatten)]
         properties: Properties,
     },
+    /// Renders format strings as you type them
+    Playground {
+        /// The module whose format to replace, instead of the whole prompt
+        #[clap(long)]
+        module: Option<String>,
+        #[clap(flatten)]
+        properties: Properties,
+    },
     /// Prints a preset config
     Preset {
         /// The name of preset to be printed
@@ -95,11 +140,59 @@ enum Commands {
         /// Print the continuation prompt (instead of the standard left prompt)
         #[clap(long, conflicts_with = "right", conflicts_with = "profile")]
         continuation: bool,
+        /// Print the status bar drawn at the top of the terminal (instead of the standard left prompt)
+        #[clap(
+            long,
+            conflicts_with = "right",
+            conflicts_with = "profile",
+            conflicts_with = "continuation"
+        )]
+        status_bar: bool,
         #[clap(flatten)]
         properties: Properties,
     },
-    /// Generate random session key
-    Session,
+    /// Recomputes the cached output of modules listed in `module_cache`
+    Refresh {
+        /// The modules to refresh, all cached modules if none are given
+        modules: Vec<String>,
+    },
+    /// Renders the prompts of scenario files into images
+    Screenshot {
+        /// The directory of the scenario files
+        #[clap(long)]
+        scenarios: PathBuf,
+        /// The directory the images are written to
+        #[clap(long)]
+        out: PathBuf,
+        /// The format of the images, PNG images need `rsvg-convert`
+        #[clap(long, value_enum, default_value_t = screenshot::ImageFormat::Svg)]
+        format: screenshot::ImageFormat,
+    },
+    /// Generate random session key, or manage the state of a shell session
+    Session {
+        #[clap(subcommand)]
+        action: Option<SessionAction>,
+    },
+    /// Renders the prompt of a scenario file and compares it with its snapshot
+    TestRender {
+        /// The scenario describing the environment, directory tree and command outputs
+        #[clap(long)]
+        scenario: PathBuf,
+        /// Write the rendered prompt to the snapshot instead of comparing it
+        #[clap(long)]
+        update: bool,
+    },
+    /// Summarizes the prompts recorded locally with `record_stats`
+    Stats {
+        /// How many days of history to include
+        #[clap(long, default_value_t = 7)]
+        days: i64,
+    },
+    /// Share the look of your prompt as a theme bundle, without behavioral settings
+    Theme {
+        #[clap(subcommand)]
+        action: ThemeAction,
+    },
     /// Prints time in milliseconds
     #[clap(hide = true)]
     Time,
@@ -113,9 +206,55 @@ enum Commands {
         #[clap(default_value = "disabled")]
         value: String,
     },
+    /// Keep the git status of the repository in the current directory up to date in the
+    /// background, so `git_status` doesn't have to compute it
+    WatchGit,
     #[cfg(feature = "config-schema")]
     /// Generate a schema for the starship configuration as JSON-schema
     ConfigSchema,
+    #[cfg(feature = "self-update")]
+    /// Replaces starship with the newest release from GitHub
+    SelfUpdate {
+        /// Which releases to update to
+        #[clap(long, value_enum, default_value_t = self_update::Channel::Stable)]
+        channel: self_update::Channel,
+        /// Only check whether a newer release is available
+        #[clap(long)]
+        check: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum SessionAction {
+    /// Create the state directory of a new session and print its key
+    Start {
+        /// Use this key instead of generating one
+        #[clap(long)]
+        key: Option<String>,
+    },
+    /// Remove the state directory of the session in `STARSHIP_SESSION_KEY`
+    End,
+    /// Print the state directory of the session in `STARSHIP_SESSION_KEY`
+    #[clap(hide = true)]
+    Dir,
+}
+
+#[derive(Subcommand, Debug)]
+enum ThemeAction {
+    /// Print the format strings, palettes and symbols of your configuration as a theme bundle
+    Export {
+        /// The name of the theme
+        #[clap(long)]
+        name: Option<String>,
+        /// Output the theme bundle to a file instead of stdout
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Apply the format strings, palettes and symbols of a theme bundle to your configuration
+    Import {
+        /// The theme bundle to import
+        file: PathBuf,
+    },
 }
 
 fn main() {
@@ -166,11 +305,39 @@ fn main() {
     log::trace!("Parsed arguments: {:#?}", args);
 
     match args.command {
+        Commands::Allow { path } => {
+            let env = Default::default();
+            match local_config::resolve(&path)
+                .and_then(|file| local_config::allow(&file, &env).map(|()| file))
+            {
+                Ok(file) => println!("Allowed {}", file.display()),
+                Err(error) => {
+                    eprintln!("Could not allow {}: {error}", path.display());
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Deny { path } => {
+            let env = Default::default();
+            match local_config::resolve(&path)
+                .and_then(|file| local_config::deny(&file, &env).map(|()| file))
+            {
+                Ok(file) => println!("Denied {}", file.display()),
+                Err(error) => {
+                    eprintln!("Could not deny {}: {error}", path.display());
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::IndexTodos => fill_todo_index(&Context::default()),
         Commands::Init {
             shell,
             print_full_init,
+            module,
         } => {
-            if print_full_init {
+            if module {
+                init::init_module(&shell).expect("can't init_module");
+            } else if print_full_init {
                 init::init_main(&shell).expect("can't init_main");
             } else {
                 init::init_stub(&shell).expect("can't init_stub");
@@ -181,14 +348,21 @@ fn main() {
             right,
             profile,
             continuation,
+            status_bar,
         } => {
-            let target = match (right, profile, continuation) {
-                (true, _, _) => Target::Right,
-                (_, Some(profile_name), _) => Target::Profile(profile_name),
-                (_, _, true) => Target::Continuation,
-                (_, _, _) => Target::Main,
+            let target = match (right, profile, continuation, status_bar) {
+                (true, _, _, _) => Target::Right,
+                (_, Some(profile_name), _, _) => Target::Profile(profile_name),
+                (_, _, true, _) => Target::Continuation,
+                (_, _, _, true) => Target::StatusBar,
+                (_, _, _, _) => Target::Main,
             };
+            // Panicking modules are skipped, so report panics through the logger instead of
+            // writing a raw backtrace hint into the middle of the prompt.
+            let default_hook = std::panic::take_hook();
+            std::panic::set_hook(Box::new(|info| log::error!("{info}")));
             print::prompt(properties, target);
+            std::panic::set_hook(default_hook);
         }
         Commands::Module {
             name,
@@ -207,9 +381,13 @@ fn main() {
             }
         }
         Commands::Preset { name, list, output } => print::preset_command(name, output, list),
-        Commands::Config { name, value } => {
+        Commands::Config { name, value, lint } => {
             let context = Context::default();
-            if let Some(name) = name {
+            if lint {
+                if !lint::print_lint(&context) {
+                    std::process::exit(1);
+                }
+            } else if let Some(name) = name {
                 if let Some(value) = value {
                     configure::update_configuration(&context, &name, &value);
                 }
@@ -224,6 +402,24 @@ fn main() {
         Commands::Toggle { name, value } => {
             configure::toggle_configuration(&Context::default(), &name, &value);
         }
+        Commands::Bench {
+            runs,
+            scenario,
+            compare,
+            json,
+            properties,
+        } => {
+            let options = bench::BenchOptions {
+                runs,
+                scenario,
+                compare,
+                json,
+            };
+            if let Err(error) = bench::bench(properties, options) {
+                eprintln!("Could not run the benchmark: {error}");
+                std::process::exit(1);
+            }
+        }
         Commands::BugReport => bug_report::create(),
         Commands::Time => {
             match SystemTime::now()
@@ -234,6 +430,18 @@ fn main() {
                 None => println!("{}", -1),
             }
         }
+        Commands::Playground { module, properties } => {
+            if let Err(error) = playground::playground(properties, module) {
+                eprintln!("{error}");
+                std::process::exit(1);
+            }
+        }
+        Commands::Stats { days } => {
+            if let Err(error) = stats::stats(days) {
+                eprintln!("Could not read the recorded statistics: {error}");
+                std::process::exit(1);
+            }
+        }
         Commands::Explain(props) => print::explain(props),
         Commands::Timings(props) => print::timings(props),
         Commands::Completions { shell } => generate(
@@ -242,16 +450,88 @@ fn main() {
             "starship",
             &mut io::stdout().lock(),
         ),
-        Commands::Session => println!(
-            "{}",
-            rand::thread_rng()
-                .sample_iter(&Alphanumeric)
-                .take(16)
-                .map(char::from)
-                .collect::<String>()
-        ),
+        Commands::TestRender { scenario, update } => {
+            match scenario::test_render(&scenario, update) {
+                Ok(true) => {}
+                Ok(false) => std::process::exit(1),
+                Err(error) => {
+                    eprintln!("Could not render scenario {}: {error}", scenario.display());
+                    std::process::exit(2);
+                }
+            }
+        }
+        Commands::Refresh { modules } => {
+            if let Err(error) = module_cache::refresh(&Context::default(), &modules) {
+                eprintln!("Could not refresh the modules: {error}");
+                std::process::exit(1);
+            }
+        }
+        Commands::Screenshot {
+            scenarios,
+            out,
+            format,
+        } => {
+            if let Err(error) = screenshot::screenshot(&scenarios, &out, format) {
+                eprintln!("Could not render the screenshots: {error}");
+                std::process::exit(1);
+            }
+        }
+        Commands::Session { action } => match action {
+            None => println!("{}", session::new_key()),
+            Some(SessionAction::Start { key }) => match session::start(&Context::default(), key) {
+                Ok(key) => println!("{key}"),
+                Err(error) => {
+                    eprintln!("Could not start the session: {error}");
+                    std::process::exit(1);
+                }
+            },
+            Some(SessionAction::End) => {
+                if let Err(error) = session::end(&Context::default()) {
+                    eprintln!("Could not end the session: {error}");
+                    std::process::exit(1);
+                }
+            }
+            Some(SessionAction::Dir) => {
+                if let Some(dir) = session::session_dir(&Context::default()) {
+                    println!("{}", dir.display());
+                }
+            }
+        },
+        Commands::Theme { action } => match action {
+            ThemeAction::Export { name, output } => {
+                let bundle = theme::export(&Context::default(), name.as_deref());
+                match output {
+                    Some(output) => {
+                        if let Err(error) = std::fs::write(output, bundle) {
+                            eprintln!("Could not write the theme bundle: {error}");
+                            std::process::exit(1);
+                        }
+                    }
+                    None => print!("{bundle}"),
+                }
+            }
+            ThemeAction::Import { file } => {
+                if let Err(error) = theme::import(&Context::default(), &file) {
+                    eprintln!("Could not import {}: {error}", file.display());
+                    std::process::exit(1);
+                }
+            }
+        },
+        Commands::WatchGit => {
+            if let Err(error) = git_watch::watch(&Context::default()) {
+                eprintln!("Could not watch the repository: {error}");
+                std::process::exit(1);
+            }
+        }
         #[cfg(feature = "config-schema")]
         Commands::ConfigSchema => print::print_schema(),
+        #[cfg(feature = "self-update")]
+        Commands::SelfUpdate { channel, check } => {
+            if let Err(error) = self_update::self_update(channel, check) {
+                eprintln!("Could not update starship: {error}");
+                std::process::exit(1);
+            }
+        }
     }
 }
 
@@ -7,19 +7,23 @@ use std::time::Duration;
 // List of all modules
 // Default ordering is handled in configs/starship_root.rs
 pub const ALL_MODULES: &[&str] = &[
+    "annotations",
     "aws",
     "azure",
     #[cfg(feature = "battery")]
     "battery",
     "buf",
+    "build_staleness",
     "bun",
     "c",
     "character",
+    "ci",
     "cmake",
     "cmd_duration",
     "cobol",
     "conda",
     "container",
+    "cpp",
     "crystal",
     "daml",
     "dart",
@@ -27,12 +31,16 @@ pub const ALL_MODULES: &[&str] = &[
     "directory",
     "direnv",
     "docker_context",
+    "dotenv",
     "dotnet",
     "elixir",
     "elm",
+    "environment",
     "erlang",
+    "errors",
     "fennel",
     "fill",
+    "formatter_tools",
     "fossil_branch",
     "fossil_metrics",
     "gcloud",
@@ -49,7 +57,9 @@ pub const ALL_MODULES: &[&str] = &[
     "haxe",
     "helm",
     "hg_branch",
+    "hg_status",
     "hostname",
+    "immutable",
     "java",
     "jobs",
     "julia",
@@ -74,12 +84,16 @@ pub const ALL_MODULES: &[&str] = &[
     "perl",
     "php",
     "pijul_channel",
+    "pre_commit",
     "pulumi",
     "purescript",
     "python",
+    "python_env",
     "quarto",
     "raku",
     "red",
+    "repl",
+    "repo_remote",
     "rlang",
     "ruby",
     "rust",
@@ -93,7 +107,10 @@ pub const ALL_MODULES: &[&str] = &[
     "sudo",
     "swift",
     "terraform",
+    "tests",
+    "ticket",
     "time",
+    "todos",
     "typst",
     "username",
     "vagrant",
@@ -119,6 +136,9 @@ pub struct Module<'a> {
 
     /// the time it took to compute this module
     pub duration: Duration,
+
+    /// The warnings and errors logged while computing this module
+    pub problems: Vec<String>,
 }
 
 impl<'a> Module<'a> {
@@ -130,6 +150,7 @@ impl<'a> Module<'a> {
             description: desc.to_string(),
             segments: Vec::new(),
             duration: Duration::default(),
+            problems: Vec::new(),
         }
     }
 
@@ -217,27 +238,84 @@ where
     if chunks.is_empty() {
         current
     } else {
-        let fill_size = term_width
-            .and_then(|tw| if tw > used { Some(tw - used) } else { None })
-            .map(|remaining| remaining / chunks.len());
+        let fill_sizes = fill_sizes(term_width, used, chunks.len());
         chunks
             .into_iter()
-            .flat_map(|(strs, fill)| {
-                let fill_string = fill.ansi_string(
+            .zip(fill_sizes)
+            .flat_map(|((strs, fill), fill_size)| {
+                let fill_strings = fill.ansi_strings(
                     fill_size,
                     strs.last().map(nu_ansi_term::AnsiGenericString::style_ref),
                 );
-                strs.into_iter().chain(std::iter::once(fill_string))
+                strs.into_iter().chain(fill_strings)
             })
             .chain(current)
             .collect::<Vec<AnsiString>>()
     }
 }
 
+/// Splits the space left on a line between its fills.
+///
+/// If the space cannot be split evenly, the last fills get one column more each, so that
+/// right-aligned text always ends at the edge of the terminal.
+fn fill_sizes(term_width: Option<usize>, used: usize, count: usize) -> Vec<Option<usize>> {
+    if count == 0 {
+        return Vec::new();
+    }
+
+    let Some(remaining) = term_width.and_then(|tw| tw.checked_sub(used).filter(|&r| r > 0)) else {
+        return vec![None; count];
+    };
+
+    let (size, extra) = (remaining / count, remaining % count);
+    (0..count)
+        .map(|i| Some(size + usize::from(i >= count - extra)))
+        .collect()
+}
+
+/// Returns the width of every fill segment in `segments` when rendered for a terminal of
+/// `term_width` columns, in order.
+///
+/// This is useful to position other content relative to the fills, e.g. for embedders
+/// laying out the segments returned by `starship::render`.
+pub fn fill_widths(segments: &[Segment], term_width: usize) -> Vec<usize> {
+    segments
+        .split_inclusive(|segment| matches!(segment, Segment::LineTerm))
+        .flat_map(|line| {
+            let (fills, others): (Vec<&Segment>, Vec<&Segment>) = line
+                .iter()
+                .partition(|segment| matches!(segment, Segment::Fill(_)));
+            let used = others.iter().map(|segment| segment.width_graphemes()).sum();
+            fill_sizes(Some(term_width), used, fills.len())
+                .into_iter()
+                .zip(fills)
+                // Without space left, the fill symbol is printed once
+                .map(|(size, fill)| size.unwrap_or_else(|| fill.width_graphemes()))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_fill_widths_give_remainder_to_last_fill() {
+        let segments = [
+            Segment::from_text(None, "AA"),
+            vec![Segment::fill(None, ".")],
+            Segment::from_text(None, "B"),
+            vec![Segment::fill(None, "."), Segment::LineTerm],
+            Segment::from_text(None, "CC"),
+            vec![Segment::fill(None, ".")],
+        ]
+        .concat();
+
+        assert_eq!(fill_widths(&segments, 10), vec![3, 4, 8]);
+        assert_eq!(fill_widths(&segments, 2), vec![1, 1, 1]);
+    }
+
     #[test]
     fn test_all_modules_is_in_alphabetical_order() {
         let mut sorted_modules: Vec<&str> = ALL_MODULES.to_vec();
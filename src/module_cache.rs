@@ -0,0 +1,241 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::config::parse_style_string;
+use crate::context::Context;
+use crate::module::Module;
+use crate::modules;
+use crate::segment::Segment;
+
+/// A segment of a cached module, with its style as a style string
+#[derive(Serialize, Deserialize)]
+enum CachedSegment {
+    Text {
+        value: String,
+        style: Option<String>,
+        link: Option<String>,
+    },
+    LineTerm,
+}
+
+/// Returns the cached segments of a module listed in `module_cache` while they are younger
+/// than its lifetime, where an empty list means the module had no output
+pub fn read(module: &str, context: &Context) -> Option<Vec<Segment>> {
+    let (file, ttl) = cache_entry(module, context)?;
+    if !is_fresh(&file, ttl, SystemTime::now()) {
+        return None;
+    }
+    let contents = fs::read_to_string(&file).ok()?;
+    let cached: Vec<CachedSegment> = serde_json::from_str(&contents).ok()?;
+    log::trace!("Using the cached output of module {module:?}");
+
+    let segments = cached
+        .into_iter()
+        .flat_map(|segment| match segment {
+            CachedSegment::Text { value, style, link } => {
+                let style = style.and_then(|style| parse_style_string(&style, None));
+                let mut segments = Segment::from_text(style, value);
+                if let Some(link) = link {
+                    segments
+                        .iter_mut()
+                        .for_each(|segment| segment.set_link(&link));
+                }
+                segments
+            }
+            CachedSegment::LineTerm => vec![Segment::LineTerm],
+        })
+        .collect();
+    Some(segments)
+}
+
+/// Caches the output of a module listed in `module_cache`
+///
+/// Modules with fill segments are not cached, as their width depends on the rest of the prompt.
+pub fn write(module: &str, context: &Context, output: Option<&Module>) {
+    let Some((file, ttl)) = cache_entry(module, context) else {
+        return;
+    };
+    let segments = output.map_or(&[][..], |module| &module.segments);
+    let cached: Option<Vec<CachedSegment>> = segments
+        .iter()
+        .map(|segment| match segment {
+            Segment::Text(_) => Some(CachedSegment::Text {
+                value: segment.value().to_string(),
+                style: segment.raw_style().map(|style| style.to_string()),
+                link: segment.link().map(str::to_string),
+            }),
+            Segment::LineTerm => Some(CachedSegment::LineTerm),
+            Segment::Fill(_) => None,
+        })
+        .collect();
+    let Some(cached) = cached else {
+        return;
+    };
+
+    let result = serde_json::to_string(&cached)
+        .map_err(io::Error::from)
+        .and_then(|contents| {
+            let dir = file.parent().unwrap_or(&file);
+            fs::create_dir_all(dir)?;
+            remove_expired(dir, ttl, SystemTime::now())?;
+            fs::write(&file, contents)
+        });
+    if let Err(error) = result {
+        log::debug!("Unable to cache module {module:?}: {error}");
+    }
+}
+
+/// Recomputes the given modules, or all modules listed in `module_cache`, and caches their
+/// output again, no matter how long it would have been reused for
+pub fn refresh(context: &Context, names: &[String]) -> io::Result<()> {
+    let names: Vec<&str> = if names.is_empty() {
+        context
+            .root_config
+            .module_cache
+            .keys()
+            .map(String::as_str)
+            .collect()
+    } else {
+        names.iter().map(String::as_str).collect()
+    };
+
+    for name in names {
+        if !context.root_config.module_cache.contains_key(name) {
+            log::warn!("Module {name:?} is not cached, add it to `module_cache` first");
+            continue;
+        }
+        if let Some(dir) = module_dir(name, context) {
+            if dir.exists() {
+                fs::remove_dir_all(dir)?;
+            }
+        }
+        // Computing the module caches it again
+        modules::handle(name, context);
+    }
+    Ok(())
+}
+
+/// The directory holding the cached outputs of all modules, which is modified whenever
+/// modules are refreshed
+pub fn cache_dir(context: &Context) -> Option<PathBuf> {
+    Some(context.get_cache_dir()?.join("modules"))
+}
+
+/// The directory holding the cached outputs of a module
+fn module_dir(module: &str, context: &Context) -> Option<PathBuf> {
+    let name: String = module
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    Some(cache_dir(context)?.join(name))
+}
+
+/// The file caching the output of a module in the current directory and environment, and how
+/// long it is reused for
+fn cache_entry(module: &str, context: &Context) -> Option<(PathBuf, Duration)> {
+    let ttl = *context.root_config.module_cache.get(module)?;
+    let key = context
+        .fingerprint()
+        .text(module)
+        .cwd()
+        .config()
+        .all_env()
+        .finish();
+    Some((
+        module_dir(module, context)?.join(key),
+        lifetime(context, ttl),
+    ))
+}
+
+/// How long a value cached for `ttl` seconds is reused for, which is twice as long while
+/// `power_save` is active, as saving power is worth slightly older values
+pub(crate) fn lifetime(context: &Context, ttl: u64) -> Duration {
+    if context.power_save() {
+        Duration::from_secs(ttl.saturating_mul(2))
+    } else {
+        Duration::from_secs(ttl)
+    }
+}
+
+/// Whether a cache file was written less than `ttl` ago
+pub(crate) fn is_fresh(file: &Path, ttl: Duration, now: SystemTime) -> bool {
+    fs::metadata(file)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| now.duration_since(modified).ok())
+        .map_or(false, |age| age < ttl)
+}
+
+/// Removes the outputs of a module that are too old to be used again
+fn remove_expired(dir: &Path, ttl: Duration, now: SystemTime) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let file = entry?.path();
+        if file.is_file() && !is_fresh(&file, ttl, now) {
+            fs::remove_file(file)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::default_context;
+    use nu_ansi_term::Color;
+
+    fn context<'a>(cache: &Path) -> Context<'a> {
+        let mut context = default_context().set_config(toml::toml! {
+            [module_cache]
+            aws = 300
+        });
+        context
+            .env
+            .insert("STARSHIP_CACHE", cache.to_string_lossy().to_string());
+        context
+    }
+
+    #[test]
+    fn caches_listed_modules() -> io::Result<()> {
+        let cache = tempfile::tempdir()?;
+        let context = context(cache.path());
+
+        let mut module = context.new_module("aws");
+        let mut segments = Segment::from_text(Some(Color::Yellow.bold().into()), "☁️ prod");
+        segments[0].set_link("https://console.aws.amazon.com");
+        module.set_segments(segments);
+        write("aws", &context, Some(&module));
+        write("azure", &context, Some(&module));
+
+        let cached = read("aws", &context).unwrap();
+        assert_eq!(cached.len(), 1);
+        assert_eq!(cached[0].value(), "☁️ prod");
+        assert_eq!(cached[0].style(), Some(Color::Yellow.bold()));
+        assert_eq!(cached[0].link(), Some("https://console.aws.amazon.com"));
+        assert!(read("azure", &context).is_none());
+
+        write("aws", &context, None);
+        assert_eq!(
+            read("aws", &context).map(|segments| segments.len()),
+            Some(0)
+        );
+        cache.close()
+    }
+
+    #[test]
+    fn expired_outputs_are_removed() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("abc");
+        fs::write(&file, "[]")?;
+        let ttl = Duration::from_secs(300);
+
+        remove_expired(dir.path(), ttl, SystemTime::now())?;
+        assert!(file.exists());
+
+        remove_expired(dir.path(), ttl, SystemTime::now() + ttl * 2)?;
+        assert!(!file.exists());
+        dir.close()
+    }
+}
@@ -0,0 +1,234 @@
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::annotations::AnnotationsConfig;
+use crate::formatter::StringFormatter;
+use crate::segment::Segment;
+use crate::utils::sanitize_escape_sequences;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// A message another program left for the prompt, read from a TOML or JSON file
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct Annotation {
+    text: String,
+    symbol: Option<String>,
+    style: Option<String>,
+    /// Seconds after the file was last written until the annotation expires, `0` never expires
+    ttl: Option<u64>,
+}
+
+/// Creates a module with the annotations other programs, like build scripts or deploy tools,
+/// dropped into the annotations directory
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("annotations");
+    let config = AnnotationsConfig::try_load(module.config);
+
+    if config.disabled {
+        return None;
+    }
+
+    let dir = annotations_dir(context)?;
+    let annotations = read_annotations(&dir, config.ttl, SystemTime::now());
+    if annotations.is_empty() {
+        return None;
+    }
+
+    let mut segments: Vec<Segment> = Vec::new();
+    for annotation in annotations.iter().take(config.max_annotations) {
+        // Annotations are files anyone with access to the directory can write, so they must not
+        // control the terminal
+        let sanitize =
+            |value: &str| sanitize_escape_sequences(value, &config.allowed_escape_sequences);
+        let text = sanitize(&annotation.text);
+        let text = context.mask_secrets("annotations", &text);
+        let symbol = annotation.symbol.as_deref().map(sanitize);
+        let style = annotation
+            .style
+            .as_deref()
+            .map(|style| sanitize_escape_sequences(style, &[]));
+        let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+            formatter
+                .map_meta(|var, _| match var {
+                    "symbol" => Some(symbol.as_deref().unwrap_or(config.symbol)),
+                    _ => None,
+                })
+                .map_style(|variable| match variable {
+                    "style" => Some(Ok(style.as_deref().unwrap_or(config.style))),
+                    _ => None,
+                })
+                .map(|variable| match variable {
+                    "text" => Some(Ok(text.as_ref())),
+                    _ => None,
+                })
+                .parse(None, Some(context))
+        });
+
+        match parsed {
+            Ok(parsed) => segments.extend(parsed),
+            Err(error) => {
+                log::warn!("Error in module `annotations`:\n{}", error);
+                return None;
+            }
+        }
+    }
+
+    module.set_segments(segments);
+    Some(module)
+}
+
+/// The directory other programs write annotations to, in the runtime directory of the user
+/// where it is cleared on logout
+fn annotations_dir(context: &Context) -> Option<PathBuf> {
+    let base = match context.get_env("XDG_RUNTIME_DIR") {
+        Some(runtime_dir) => PathBuf::from(runtime_dir).join("starship"),
+        None => context.get_cache_dir()?,
+    };
+    Some(base.join("annotations"))
+}
+
+/// Reads the annotations in the directory, the most recently written first
+///
+/// Files of expired annotations are removed.
+fn read_annotations(dir: &Path, default_ttl: u64, now: SystemTime) -> Vec<Annotation> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut annotations: Vec<(SystemTime, Annotation)> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let modified = fs::metadata(&path).and_then(|meta| meta.modified()).ok()?;
+            let annotation = parse_annotation(&path)?;
+            if is_expired(modified, annotation.ttl.unwrap_or(default_ttl), now) {
+                log::debug!("Removing expired annotation {}", path.display());
+                if let Err(error) = fs::remove_file(&path) {
+                    log::debug!("Unable to remove {}: {error}", path.display());
+                }
+                return None;
+            }
+            Some((modified, annotation))
+        })
+        .collect();
+    annotations.sort_by(|a, b| b.0.cmp(&a.0));
+    annotations
+        .into_iter()
+        .map(|(_, annotation)| annotation)
+        .collect()
+}
+
+fn parse_annotation(path: &Path) -> Option<Annotation> {
+    let extension = path.extension()?.to_str()?;
+    if !matches!(extension, "toml" | "json") {
+        return None;
+    }
+    let contents = fs::read_to_string(path).ok()?;
+    let annotation = if extension == "toml" {
+        toml::from_str(&contents).map_err(|error| error.to_string())
+    } else {
+        serde_json::from_str(&contents).map_err(|error| error.to_string())
+    };
+    annotation
+        .map_err(|error| log::warn!("Unable to read annotation {}: {error}", path.display()))
+        .ok()
+}
+
+fn is_expired(modified: SystemTime, ttl: u64, now: SystemTime) -> bool {
+    ttl > 0
+        && now
+            .duration_since(modified)
+            .map_or(false, |age| age > Duration::from_secs(ttl))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::ModuleRenderer;
+    use nu_ansi_term::Color;
+    use std::io;
+
+    fn write_annotation(runtime_dir: &Path, name: &str, contents: &str) -> io::Result<()> {
+        let dir = runtime_dir.join("starship").join("annotations");
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join(name), contents)
+    }
+
+    #[test]
+    fn no_annotations() -> io::Result<()> {
+        let runtime_dir = tempfile::tempdir()?;
+        let actual = ModuleRenderer::new("annotations")
+            .env("XDG_RUNTIME_DIR", runtime_dir.path().to_string_lossy())
+            .collect();
+        assert_eq!(None, actual);
+        runtime_dir.close()
+    }
+
+    #[test]
+    fn toml_and_json_annotations() -> io::Result<()> {
+        let runtime_dir = tempfile::tempdir()?;
+        write_annotation(
+            runtime_dir.path(),
+            "deploy.toml",
+            "text = \"deploying\"\nsymbol = \"🚀 \"\nstyle = \"green\"",
+        )?;
+        write_annotation(runtime_dir.path(), "notes.txt", "not an annotation")?;
+
+        let actual = ModuleRenderer::new("annotations")
+            .env("XDG_RUNTIME_DIR", runtime_dir.path().to_string_lossy())
+            .collect();
+        let expected = Some(format!("{} ", Color::Green.paint("🚀 deploying")));
+        assert_eq!(expected, actual);
+
+        write_annotation(
+            runtime_dir.path(),
+            "build.json",
+            r#"{"text": "build failed"}"#,
+        )?;
+        let actual = ModuleRenderer::new("annotations")
+            .env("XDG_RUNTIME_DIR", runtime_dir.path().to_string_lossy())
+            .config(toml::toml! {
+                [annotations]
+                format = "<$text>"
+                max_annotations = 1
+            })
+            .collect();
+        assert!(matches!(
+            actual.as_deref(),
+            Some("<deploying>" | "<build failed>")
+        ));
+        runtime_dir.close()
+    }
+
+    #[test]
+    fn escape_sequences_are_removed() -> io::Result<()> {
+        let runtime_dir = tempfile::tempdir()?;
+        write_annotation(
+            runtime_dir.path(),
+            "build.json",
+            r#"{"text": "\u001b]0;pwned\u0007done", "symbol": "\u001b[2J🚀 ", "style": "green\u001b[8m"}"#,
+        )?;
+
+        let actual = ModuleRenderer::new("annotations")
+            .env("XDG_RUNTIME_DIR", runtime_dir.path().to_string_lossy())
+            .config(toml::toml! {
+                [annotations]
+                format = "<$symbol$text>"
+            })
+            .collect();
+        assert_eq!(actual.as_deref(), Some("<🚀 done>"));
+        runtime_dir.close()
+    }
+
+    #[test]
+    fn expiration() {
+        let written = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        let later = |secs| written + Duration::from_secs(secs);
+        assert!(!is_expired(written, 60, later(30)));
+        assert!(is_expired(written, 60, later(61)));
+        assert!(!is_expired(written, 0, later(100_000)));
+        // Clocks going backwards don't expire annotations
+        assert!(!is_expired(later(10), 60, written));
+    }
+}
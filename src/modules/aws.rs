@@ -2,15 +2,16 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::str::FromStr;
 
-use chrono::DateTime;
 use ini::Ini;
 use once_cell::unsync::OnceCell;
+use serde::Deserialize;
 
+use super::utils::cloud;
 use super::{Context, Module, ModuleConfig};
 
 use crate::configs::aws::AwsConfig;
 use crate::formatter::StringFormatter;
-use crate::utils::render_time;
+use crate::utils;
 
 type Profile = String;
 type Region = String;
@@ -93,10 +94,8 @@ fn get_aws_region_from_config(
     section.get("region").map(std::borrow::ToOwned::to_owned)
 }
 
-fn get_aws_profile_and_region(
-    context: &Context,
-    aws_config: &AwsConfigFile,
-) -> (Option<Profile>, Option<Region>) {
+/// Returns the active AWS profile from the environment variables set by the various AWS tools
+pub(super) fn get_aws_profile(context: &Context) -> Option<Profile> {
     let profile_env_vars = [
         "AWSU_PROFILE",
         "AWS_VAULT",
@@ -104,10 +103,17 @@ fn get_aws_profile_and_region(
         "AWS_PROFILE",
         "AWS_SSO_PROFILE",
     ];
-    let region_env_vars = ["AWS_REGION", "AWS_DEFAULT_REGION"];
-    let profile = profile_env_vars
+    profile_env_vars
         .iter()
-        .find_map(|env_var| context.get_env(env_var));
+        .find_map(|env_var| context.get_env(env_var))
+}
+
+fn get_aws_profile_and_region(
+    context: &Context,
+    aws_config: &AwsConfigFile,
+) -> (Option<Profile>, Option<Region>) {
+    let region_env_vars = ["AWS_REGION", "AWS_DEFAULT_REGION"];
+    let profile = get_aws_profile(context);
     let region = region_env_vars
         .iter()
         .find_map(|env_var| context.get_env(env_var));
@@ -122,7 +128,8 @@ fn get_aws_profile_and_region(
     }
 }
 
-fn get_credentials_duration(
+/// Returns when the credentials of the profile expire, as a Unix timestamp
+fn get_credentials_expiration(
     context: &Context,
     aws_profile: Option<&Profile>,
     aws_creds: &AwsCredsFile,
@@ -132,30 +139,62 @@ fn get_credentials_duration(
         "AWS_SESSION_EXPIRATION",
         "AWSUME_EXPIRATION",
     ];
-    let expiration_date = if let Some(expiration_date) = expiration_env_vars
+    if let Some(expiration_date) = expiration_env_vars
         .iter()
         .find_map(|env_var| context.get_env(env_var))
     {
-        chrono::DateTime::parse_from_rfc3339(&expiration_date).ok()
-    } else {
-        let creds = get_creds(context, aws_creds)?;
-        let section = get_profile_creds(creds, aws_profile)?;
+        return cloud::parse_expiration(&expiration_date);
+    }
 
-        let expiration_keys = ["expiration", "x_security_token_expires"];
-        expiration_keys
-            .iter()
-            .find_map(|expiration_key| section.get(expiration_key))
-            .and_then(|expiration| DateTime::parse_from_rfc3339(expiration).ok())
-    }?;
+    let creds = get_creds(context, aws_creds)?;
+    let section = get_profile_creds(creds, aws_profile)?;
+
+    let expiration_keys = ["expiration", "x_security_token_expires"];
+    expiration_keys
+        .iter()
+        .find_map(|expiration_key| section.get(expiration_key))
+        .and_then(cloud::parse_expiration)
+}
 
-    Some(expiration_date.timestamp() - chrono::Local::now().timestamp())
+/// A friendly name and style for an AWS account, read from `account_aliases_file`
+#[derive(Deserialize)]
+struct AccountAlias {
+    name: Option<String>,
+    style: Option<String>,
 }
 
-fn alias_name(name: Option<String>, aliases: &HashMap<String, &str>) -> Option<String> {
-    name.as_ref()
-        .and_then(|n| aliases.get(n))
-        .map(|&a| a.to_string())
-        .or(name)
+/// Returns the ID of the account the profile signs in to, from the environment or the SSO
+/// account or role ARN of the profile
+fn get_account_id(
+    context: &Context,
+    aws_profile: Option<&Profile>,
+    aws_config: &AwsConfigFile,
+) -> Option<String> {
+    if let Some(account_id) = context.get_env("AWS_SSO_ACCOUNT_ID") {
+        return Some(account_id);
+    }
+    let config = get_config(context, aws_config)?;
+    let section = get_profile_config(config, aws_profile)?;
+    if let Some(account_id) = section.get("sso_account_id") {
+        return Some(account_id.to_string());
+    }
+    // arn:aws:iam::123456789012:role/name
+    let role_arn = section.get("role_arn")?;
+    role_arn.split(':').nth(4).map(ToString::to_string)
+}
+
+/// Looks up an account in the aliases file, which is read on every prompt so edits to a
+/// centrally distributed file apply right away
+fn get_account_alias(config: &AwsConfig, account_id: &str) -> Option<AccountAlias> {
+    if config.account_aliases_file.is_empty() {
+        return None;
+    }
+    let path = Context::expand_tilde(PathBuf::from(config.account_aliases_file));
+    let contents = utils::read_file(path).ok()?;
+    let mut aliases: HashMap<String, AccountAlias> = toml::from_str(&contents)
+        .map_err(|error| log::warn!("Unable to parse the AWS account aliases file: {error}"))
+        .ok()?;
+    aliases.remove(account_id)
 }
 
 fn has_credential_process_or_sso(
@@ -257,19 +296,29 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    let duration = {
-        get_credentials_duration(context, aws_profile.as_ref(), &aws_creds).map(|duration| {
-            if duration > 0 {
-                render_time((duration * 1000) as u128, false)
-            } else {
-                config.expiration_symbol.to_string()
-            }
-        })
-    };
+    let duration = get_credentials_expiration(context, aws_profile.as_ref(), &aws_creds)
+        .map(|expires_at| cloud::render_expiration(context, expires_at, config.expiration_symbol));
 
-    let mapped_region = alias_name(aws_region, &config.region_aliases);
+    let mapped_region = aws_region
+        .as_deref()
+        .map(|region| cloud::alias(region, &config.region_aliases));
+
+    let account_id = get_account_id(context, aws_profile.as_ref(), &aws_config);
+    let account_alias = account_id
+        .as_deref()
+        .and_then(|account_id| get_account_alias(&config, account_id));
+    let account = account_alias
+        .as_ref()
+        .and_then(|alias| alias.name.clone())
+        .or_else(|| account_id.clone());
+    let style = account_alias
+        .as_ref()
+        .and_then(|alias| alias.style.as_deref())
+        .unwrap_or(config.style);
 
-    let mapped_profile = alias_name(aws_profile, &config.profile_aliases);
+    let mapped_profile = aws_profile
+        .as_deref()
+        .map(|profile| cloud::alias(profile, &config.profile_aliases));
 
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
@@ -278,13 +327,15 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map_style(|variable| match variable {
-                "style" => Some(Ok(config.style)),
+                "style" => Some(Ok(style)),
                 _ => None,
             })
             .map(|variable| match variable {
-                "profile" => mapped_profile.as_ref().map(Ok),
-                "region" => mapped_region.as_ref().map(Ok),
-                "duration" => duration.as_ref().map(Ok),
+                "profile" => mapped_profile.map(Ok),
+                "region" => mapped_region.map(Ok),
+                "account" => account.as_deref().map(Ok),
+                "account_id" => account_id.as_deref().map(Ok),
+                "duration" | "expiration" => duration.as_deref().map(Ok),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -1196,4 +1247,56 @@ source_profile = starship
         assert_eq!(expected, actual);
         dir.close()
     }
+
+    #[test]
+    fn account_alias_from_file() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let config_path = dir.path().join("config");
+        let mut config = File::create(&config_path)?;
+        config.write_all(
+            "[profile astronauts]
+sso_session = my-sso
+sso_account_id = 123456789011
+
+[profile pilots]
+role_arn = arn:aws:iam::210987654321:role/pilot
+credential_process = /opt/bin/awscreds-retriever
+"
+            .as_bytes(),
+        )?;
+        let aliases_path = dir.path().join("accounts.toml");
+        let mut aliases = File::create(&aliases_path)?;
+        aliases.write_all(
+            "123456789011 = { name = 'prod-payments', style = 'bold red' }\n".as_bytes(),
+        )?;
+
+        let render = |profile: &str| {
+            ModuleRenderer::new("aws")
+                .env("AWS_CONFIG_FILE", config_path.to_string_lossy().as_ref())
+                .env("AWS_PROFILE", profile)
+                .config(
+                    toml::from_str(&format!(
+                        "
+                        [aws]
+                        format = 'on [$account]($style) '
+                        account_aliases_file = '{}'
+                        ",
+                        aliases_path.display()
+                    ))
+                    .unwrap(),
+                )
+                .collect()
+        };
+
+        let expected = Some(format!("on {} ", Color::Red.bold().paint("prod-payments")));
+        assert_eq!(expected, render("astronauts"));
+
+        // Accounts missing from the file are shown by their ID
+        let expected = Some(format!(
+            "on {} ",
+            Color::Yellow.bold().paint("210987654321")
+        ));
+        assert_eq!(expected, render("pilots"));
+        dir.close()
+    }
 }
@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
+use super::utils::cloud;
 use super::{Context, Module, ModuleConfig};
 
 use crate::configs::azure::AzureConfig;
@@ -26,6 +29,23 @@ struct Subscription {
     name: String,
     user: User,
     is_default: bool,
+    #[serde(default)]
+    tenant_id: String,
+}
+
+/// The access tokens cached by the Azure CLI
+#[derive(Deserialize)]
+struct MsalTokenCache {
+    #[serde(rename = "AccessToken", default)]
+    access_tokens: HashMap<String, AccessToken>,
+}
+
+#[derive(Deserialize)]
+struct AccessToken {
+    /// The tenant the token was issued for
+    #[serde(default)]
+    realm: String,
+    expires_on: String,
 }
 
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
@@ -56,12 +76,17 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map(|variable| match variable {
-                "subscription" => Some(Ok(config
-                    .subscription_aliases
-                    .get(&subscription.name)
-                    .copied()
-                    .unwrap_or(&subscription.name))),
-                "username" => Some(Ok(&subscription.user.name)),
+                "subscription" => Some(Ok(Cow::Borrowed(cloud::alias(
+                    &subscription.name,
+                    &config.subscription_aliases,
+                )))),
+                "username" => Some(Ok(Cow::Borrowed(subscription.user.name.as_str()))),
+                "expiration" => get_token_expiration(context, &subscription.tenant_id)
+                    .map(|expires_at| {
+                        cloud::render_expiration(context, expires_at, config.expiration_symbol)
+                    })
+                    .map(Cow::Owned)
+                    .map(Ok),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -89,6 +114,24 @@ fn get_azure_profile_info(context: &Context) -> Option<Subscription> {
         .find(|s| s.is_default)
 }
 
+/// Returns when the last access token the Azure CLI received for the tenant expires, as a
+/// Unix timestamp
+fn get_token_expiration(context: &Context, tenant_id: &str) -> Option<i64> {
+    let mut cache_path = get_config_file_location(context)?;
+    cache_path.push("msal_token_cache.json");
+
+    let json_data = fs::read_to_string(cache_path).ok()?;
+    let cache: MsalTokenCache = serde_json::from_str(&json_data)
+        .map_err(|error| log::info!("Failed to parse the azure token cache: {error}"))
+        .ok()?;
+    cache
+        .access_tokens
+        .values()
+        .filter(|token| tenant_id.is_empty() || token.realm == tenant_id)
+        .filter_map(|token| cloud::parse_expiration(&token.expires_on))
+        .max()
+}
+
 fn load_azure_profile(config_path: &PathBuf) -> Option<AzureProfile> {
     let json_data = fs::read_to_string(config_path).ok()?;
     let sanitized_json_data = json_data.strip_prefix('\u{feff}').unwrap_or(&json_data);
@@ -781,6 +824,75 @@ mod tests {
         dir.close()
     }
 
+    #[test]
+    fn expiration_of_tenant_token() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let azure_profile_contents = r#"{
+            "installationId": "3deacd2a-b9db-77e1-aa42-23e2f8dfffc3",
+            "subscriptions": [
+              {
+                "id": "f3935dc9-92b5-9a93-da7b-42c325d86939",
+                "name": "Subscription 1",
+                "state": "Enabled",
+                "user": {
+                  "name": "user@domain.com",
+                  "type": "user"
+                },
+                "isDefault": true,
+                "tenantId": "f0273a19-7779-e40a-00a1-53b8331b3bb6",
+                "environmentName": "AzureCloud"
+              }
+            ]
+          }
+        "#;
+        generate_test_config(&dir, azure_profile_contents)?;
+
+        let now = chrono::Local::now().timestamp();
+        let token_cache_contents = format!(
+            r#"{{
+            "AccessToken": {{
+                "current": {{
+                    "credential_type": "AccessToken",
+                    "realm": "f0273a19-7779-e40a-00a1-53b8331b3bb6",
+                    "expires_on": "{}"
+                }},
+                "other-tenant": {{
+                    "credential_type": "AccessToken",
+                    "realm": "0e8a15ec-b0f5-d355-7062-8ece54c59aee",
+                    "expires_on": "{}"
+                }}
+            }}
+        }}"#,
+            now + 1800,
+            now + 7200
+        );
+        save_string_to_file(
+            &dir,
+            token_cache_contents,
+            String::from("msal_token_cache.json"),
+        )?;
+
+        let dir_path = &dir.path().to_string_lossy();
+        let actual = ModuleRenderer::new("azure")
+            .config(toml::toml! {
+            [azure]
+            disabled = false
+            format = "on [$symbol($subscription )\\[$expiration\\]]($style) "
+            })
+            .env("AZURE_CONFIG_DIR", dir_path.as_ref())
+            .collect();
+        let possible_values = ["30m0s", "29m59s", "29m58s", "29m57s"].map(|duration| {
+            let segment_colored = format!("󰠅 Subscription 1 [{duration}]");
+            Some(format!("on {} ", Color::Blue.bold().paint(segment_colored)))
+        });
+        assert!(
+            possible_values.contains(&actual),
+            "time is not in range: {actual:?}"
+        );
+        dir.close()
+    }
+
     fn save_string_to_file(
         dir: &TempDir,
         contents: String,
@@ -2,18 +2,23 @@ use super::{Context, Module, ModuleConfig};
 use crate::configs::battery::BatteryConfig;
 #[cfg(test)]
 use mockall::automock;
+use once_cell::unsync::OnceCell;
 use starship_battery as battery;
+use std::fs;
+use std::path::PathBuf;
 
 use crate::formatter::StringFormatter;
+use crate::utils::{self, context_path};
 
 /// Creates a module for the battery percentage and charging state
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
-    let battery_status = get_battery_status(context)?;
-    let BatteryStatus { state, percentage } = battery_status;
-
     let mut module = context.new_module("battery");
     let config: BatteryConfig = BatteryConfig::try_load(module.config);
 
+    let upower = UpowerProvider::new(context);
+    let battery_status = get_battery_status(context, &upower, config.backend)?;
+    let BatteryStatus { state, percentage } = battery_status;
+
     // Parse config under `display`.
     // Select the first style that match the threshold,
     // if all thresholds are lower do not display battery module.
@@ -45,7 +50,26 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                     _ => None,
                 })
                 .map(|variable| match variable {
-                    "percentage" => Some(Ok(format!("{}%", percentage.round()))),
+                    "percentage" => Some(Ok(
+                        context.format_number(&percentage.round().to_string(), "%")
+                    )),
+                    "state" => Some(Ok(context.message(match state {
+                        battery::State::Full => "battery-full",
+                        battery::State::Charging => "battery-charging",
+                        battery::State::Discharging => "battery-discharging",
+                        battery::State::Unknown => "battery-unknown",
+                        battery::State::Empty => "battery-empty",
+                    }))),
+                    "peripherals" => {
+                        let peripherals = upower.get_peripherals();
+                        (!peripherals.is_empty()).then(|| {
+                            Ok(peripherals
+                                .iter()
+                                .map(|p| format!("{} {}%", p.name, p.percentage.round()))
+                                .collect::<Vec<_>>()
+                                .join(", "))
+                        })
+                    }
                     _ => None,
                 });
 
@@ -67,8 +91,32 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     }
 }
 
-fn get_battery_status(context: &Context) -> Option<BatteryStatus> {
-    let battery_info = context.battery_info_provider.get_battery_info()?;
+fn get_battery_status(
+    context: &Context,
+    upower: &UpowerProvider,
+    backend: &str,
+) -> Option<BatteryStatus> {
+    let battery_info = match backend {
+        "native" => context.battery_info_provider.get_battery_info(),
+        "upower" => upower.get_battery_info(),
+        other => {
+            if other != "auto" {
+                log::warn!("Unknown battery backend {other:?}, expected auto, native or upower");
+            }
+            // Some laptops only expose their batteries through UPower, where the native
+            // interface fails to read them. Machines without batteries are read natively and
+            // don't ask UPower, while machines where UPower finds no batteries either are
+            // remembered until the next boot.
+            context
+                .battery_info_provider
+                .get_battery_info()
+                .or_else(|| {
+                    cfg!(target_os = "linux")
+                        .then(|| upower_fallback(context, upower))
+                        .flatten()
+                })
+        }
+    }?;
     if battery_info.energy_full != 0.0 {
         let battery = BatteryStatus {
             percentage: battery_info.energy / battery_info.energy_full * 100.0,
@@ -81,6 +129,36 @@ fn get_battery_status(context: &Context) -> Option<BatteryStatus> {
     }
 }
 
+/// Reads the batteries from UPower, unless it had none since the machine booted
+fn upower_fallback(context: &Context, upower: &UpowerProvider) -> Option<BatteryInfo> {
+    let boot_id = utils::read_file(context_path(context, "/proc/sys/kernel/random/boot_id")).ok();
+    let marker = no_upower_batteries_path(context);
+    if let (Some(boot_id), Some(marker)) = (&boot_id, &marker) {
+        if utils::read_file(marker).is_ok_and(|marked| marked == *boot_id) {
+            return None;
+        }
+    }
+
+    let battery_info = upower.get_battery_info();
+    if battery_info.is_none() {
+        if let (Some(boot_id), Some(marker)) = (boot_id, marker) {
+            let result = marker
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|_| fs::write(&marker, boot_id));
+            if let Err(error) = result {
+                log::debug!("Unable to remember that UPower has no batteries: {error}");
+            }
+        }
+    }
+    battery_info
+}
+
+/// The file holding the boot id of the machine once UPower has found no batteries
+fn no_upower_batteries_path(context: &Context) -> Option<PathBuf> {
+    Some(context.get_cache_dir()?.join("battery_no_upower"))
+}
+
 /// the merge returns Charging if at least one is charging
 ///                   Discharging if at least one is Discharging
 ///                   Full if both are Full or one is Full and the other Unknown
@@ -109,65 +187,216 @@ pub struct BatteryInfo {
     state: battery::State,
 }
 
+/// A device powered by its own battery, like a wireless mouse or headset
+#[derive(Debug, PartialEq)]
+struct Peripheral {
+    name: String,
+    percentage: f32,
+}
+
 #[derive(Debug)]
 struct BatteryStatus {
     percentage: f32,
     state: battery::State,
 }
 
+/// A source of battery information, combining all batteries of the machine
 #[cfg_attr(test, automock)]
 pub trait BatteryInfoProvider {
     fn get_battery_info(&self) -> Option<BatteryInfo>;
 }
 
+/// Reads batteries through the native interface of the platform:
+/// sysfs on Linux, IOKit on macOS and the power management API on Windows
+///
+/// Returns `None` when the batteries can't be read, and no energy without batteries.
 pub struct BatteryInfoProviderImpl;
 
 impl BatteryInfoProvider for BatteryInfoProviderImpl {
     fn get_battery_info(&self) -> Option<BatteryInfo> {
         let battery_manager = battery::Manager::new().ok()?;
         let batteries = battery_manager.batteries().ok()?;
-        Some(
-            batteries
-                .filter_map(|battery| match battery {
-                    Ok(battery) => {
-                        log::debug!("Battery found: {:?}", battery);
-                        Some(BatteryInfo {
-                            energy: battery.energy().value,
-                            energy_full: battery.energy_full().value,
-                            state: battery.state(),
-                        })
-                    }
-                    Err(e) => {
-                        let level = if cfg!(target_os = "linux") {
-                            log::Level::Info
-                        } else {
-                            log::Level::Warn
-                        };
-                        log::log!(level, "Unable to access battery information:\n{}", &e);
-                        None
-                    }
+        let mut has_errors = false;
+        let battery_info = batteries
+            .filter_map(|battery| match battery {
+                Ok(battery) => {
+                    log::debug!("Battery found: {:?}", battery);
+                    Some(BatteryInfo {
+                        energy: battery.energy().value,
+                        energy_full: battery.energy_full().value,
+                        state: battery.state(),
+                    })
+                }
+                Err(e) => {
+                    let level = if cfg!(target_os = "linux") {
+                        log::Level::Info
+                    } else {
+                        log::Level::Warn
+                    };
+                    log::log!(level, "Unable to access battery information:\n{}", &e);
+                    has_errors = true;
+                    None
+                }
+            })
+            .fold(
+                BatteryInfo {
+                    energy: 0.0,
+                    energy_full: 0.0,
+                    state: battery::State::Unknown,
+                },
+                |mut acc, x| {
+                    acc.energy += x.energy;
+                    acc.energy_full += x.energy_full;
+                    acc.state = merge_battery_states(acc.state, x.state);
+                    acc
+                },
+            );
+        // Batteries that exist but can't be read are an error, not a machine without batteries
+        (battery_info.energy_full != 0.0 || !has_errors).then_some(battery_info)
+    }
+}
+
+/// Reads batteries from the UPower daemon, through its `upower` command line client, which
+/// runs at most once per prompt
+struct UpowerProvider<'a, 'b> {
+    context: &'a Context<'b>,
+    devices: OnceCell<Vec<UpowerDevice>>,
+}
+
+impl<'a, 'b> UpowerProvider<'a, 'b> {
+    fn new(context: &'a Context<'b>) -> Self {
+        Self {
+            context,
+            devices: OnceCell::new(),
+        }
+    }
+
+    fn devices(&self) -> &[UpowerDevice] {
+        self.devices.get_or_init(|| {
+            self.context
+                .exec_cmd("upower", &["-d"])
+                .map(|output| parse_upower_devices(&output.stdout))
+                .unwrap_or_default()
+        })
+    }
+
+    /// Returns the devices with their own battery, which do not power the machine
+    fn get_peripherals(&self) -> Vec<Peripheral> {
+        self.devices()
+            .iter()
+            .filter(|device| !device.power_supply)
+            .filter_map(|device| {
+                Some(Peripheral {
+                    percentage: device.percentage?,
+                    name: device.model.clone().unwrap_or_else(|| device.kind.clone()),
                 })
-                .fold(
-                    BatteryInfo {
-                        energy: 0.0,
-                        energy_full: 0.0,
-                        state: battery::State::Unknown,
-                    },
-                    |mut acc, x| {
-                        acc.energy += x.energy;
-                        acc.energy_full += x.energy_full;
-                        acc.state = merge_battery_states(acc.state, x.state);
-                        acc
-                    },
-                ),
-        )
+            })
+            .collect()
     }
 }
 
+impl BatteryInfoProvider for UpowerProvider<'_, '_> {
+    fn get_battery_info(&self) -> Option<BatteryInfo> {
+        self.devices()
+            .iter()
+            .filter(|device| device.power_supply && device.kind == "battery")
+            .map(|device| BatteryInfo {
+                energy: device.energy,
+                energy_full: device.energy_full,
+                state: device.state,
+            })
+            .reduce(|mut acc, x| {
+                acc.energy += x.energy;
+                acc.energy_full += x.energy_full;
+                acc.state = merge_battery_states(acc.state, x.state);
+                acc
+            })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct UpowerDevice {
+    kind: String,
+    model: Option<String>,
+    power_supply: bool,
+    state: battery::State,
+    energy: f32,
+    energy_full: f32,
+    percentage: Option<f32>,
+}
+
+/// Parses the devices listed by `upower -d`
+///
+/// Each device starts with a `Device:` line, followed by indented `key: value` properties.
+/// The kind of the device, e.g. `battery` or `mouse`, is a line of its own.
+fn parse_upower_devices(output: &str) -> Vec<UpowerDevice> {
+    output
+        .split("Device: ")
+        .skip(1)
+        // The display device is a summary of the batteries, which would count them twice
+        .filter(|block| {
+            !block
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .ends_with("DisplayDevice")
+        })
+        .filter_map(|block| {
+            let mut kind = None;
+            let mut device = UpowerDevice {
+                kind: String::new(),
+                model: None,
+                power_supply: false,
+                state: battery::State::Unknown,
+                energy: 0.0,
+                energy_full: 0.0,
+                percentage: None,
+            };
+            // Values carry their unit, e.g. `35.1 Wh` or `70%`
+            let number = |value: &str| {
+                value
+                    .trim_end_matches(|c: char| c.is_ascii_alphabetic() || c == '%')
+                    .trim()
+                    .parse::<f32>()
+                    .ok()
+            };
+            for line in block.lines().skip(1) {
+                let Some((key, value)) = line.split_once(':') else {
+                    if kind.is_none() && !line.trim().is_empty() {
+                        kind = Some(line.trim().to_string());
+                    }
+                    continue;
+                };
+                let value = value.trim();
+                match key.trim() {
+                    "model" if !value.is_empty() => device.model = Some(value.to_string()),
+                    "power supply" => device.power_supply = value == "yes",
+                    "state" => {
+                        device.state = match value {
+                            "charging" => battery::State::Charging,
+                            "discharging" => battery::State::Discharging,
+                            "fully-charged" => battery::State::Full,
+                            "empty" => battery::State::Empty,
+                            _ => battery::State::Unknown,
+                        }
+                    }
+                    "energy" => device.energy = number(value).unwrap_or_default(),
+                    "energy-full" => device.energy_full = number(value).unwrap_or_default(),
+                    "percentage" => device.percentage = number(value),
+                    _ => {}
+                }
+            }
+            device.kind = kind?;
+            Some(device)
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::test::ModuleRenderer;
+    use crate::utils::CommandOutput;
     use nu_ansi_term::Color;
 
     #[test]
@@ -339,6 +568,34 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn battery_state_in_language() {
+        let mut mock = MockBatteryInfoProvider::new();
+
+        mock.expect_get_battery_info().times(1).returning(|| {
+            Some(BatteryInfo {
+                energy: 800.0,
+                energy_full: 1000.0,
+                state: battery::State::Charging,
+            })
+        });
+
+        let actual = ModuleRenderer::new("battery")
+            .config(toml::toml! {
+                language = "de"
+                [battery]
+                format = "$percentage ($state)"
+                [[battery.display]]
+                threshold = 90
+                style = ""
+            })
+            .battery_info_provider(&mock)
+            .collect();
+        let expected = Some(String::from("80% (lädt)"));
+
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn battery_hidden_when_percentage_above_threshold() {
         let mut mock = MockBatteryInfoProvider::new();
@@ -413,4 +670,175 @@ mod tests {
 
         assert_eq!(expected, actual);
     }
+
+    const UPOWER: &str = "\
+Device: /org/freedesktop/UPower/devices/line_power_AC
+  native-path:          AC
+  power supply:         yes
+  line-power
+    online:              no
+
+Device: /org/freedesktop/UPower/devices/battery_BAT0
+  native-path:          BAT0
+  model:                5B10W13930
+  power supply:         yes
+  updated:              Thu 15 Oct 2026 09:12:41 (12 seconds ago)
+  battery
+    present:             yes
+    state:               discharging
+    energy:              18.2 Wh
+    energy-full:         52.0 Wh
+    energy-rate:         7.4 W
+    percentage:          35%
+
+Device: /org/freedesktop/UPower/devices/mouse_hidpp_battery_0
+  native-path:          hidpp_battery_0
+  model:                MX Master 3
+  power supply:         no
+  mouse
+    present:             yes
+    percentage:          55%
+
+Device: /org/freedesktop/UPower/devices/DisplayDevice
+  power supply:         yes
+  battery
+    state:               discharging
+    energy:              18.2 Wh
+    energy-full:         52.0 Wh
+    percentage:          35%
+
+Daemon:
+  daemon-version:  1.90.2
+  on-battery:      yes
+";
+
+    #[test]
+    fn parse_upower() {
+        let devices = parse_upower_devices(UPOWER);
+
+        assert_eq!(devices.len(), 3);
+        assert_eq!(devices[0].kind, "line-power");
+        assert_eq!(
+            devices[1],
+            UpowerDevice {
+                kind: "battery".to_string(),
+                model: Some("5B10W13930".to_string()),
+                power_supply: true,
+                state: battery::State::Discharging,
+                energy: 18.2,
+                energy_full: 52.0,
+                percentage: Some(35.0),
+            }
+        );
+        assert_eq!(devices[2].model.as_deref(), Some("MX Master 3"));
+        assert!(!devices[2].power_supply);
+    }
+
+    #[test]
+    fn upower_backend() {
+        let actual = ModuleRenderer::new("battery")
+            .config(toml::toml! {
+                [battery]
+                backend = "upower"
+                format = "$percentage( \\[$peripherals\\])"
+                [[battery.display]]
+                threshold = 100
+                style = ""
+            })
+            .cmd(
+                "upower -d",
+                Some(CommandOutput {
+                    stdout: UPOWER.to_string(),
+                    stderr: String::default(),
+                }),
+            )
+            .collect();
+        let expected = Some(String::from("35% [MX Master 3 55%]"));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn auto_backend_falls_back_to_upower_on_errors() {
+        let render = |mock: &MockBatteryInfoProvider| {
+            ModuleRenderer::new("battery")
+                .config(toml::toml! {
+                    [battery]
+                    format = "$percentage( \\[$peripherals\\])"
+                    [[battery.display]]
+                    threshold = 100
+                    style = ""
+                })
+                .cmd(
+                    "upower -d",
+                    Some(CommandOutput {
+                        stdout: UPOWER.to_string(),
+                        stderr: String::default(),
+                    }),
+                )
+                .battery_info_provider(mock)
+                .collect()
+        };
+
+        let mut failing = MockBatteryInfoProvider::new();
+        failing
+            .expect_get_battery_info()
+            .times(1)
+            .returning(|| None);
+        assert_eq!(
+            render(&failing),
+            Some(String::from("35% [MX Master 3 55%]"))
+        );
+
+        // Machines without batteries don't ask UPower
+        let mut without_batteries = MockBatteryInfoProvider::new();
+        without_batteries
+            .expect_get_battery_info()
+            .times(1)
+            .returning(|| {
+                Some(BatteryInfo {
+                    energy: 0.0,
+                    energy_full: 0.0,
+                    state: battery::State::Unknown,
+                })
+            });
+        assert_eq!(render(&without_batteries), None);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn auto_backend_remembers_upower_without_batteries() -> std::io::Result<()> {
+        let cache = tempfile::tempdir()?;
+        let render = |upower: &str| {
+            let mut failing = MockBatteryInfoProvider::new();
+            failing.expect_get_battery_info().returning(|| None);
+            ModuleRenderer::new("battery")
+                .config(toml::toml! {
+                    [[battery.display]]
+                    threshold = 100
+                    style = ""
+                })
+                .env("STARSHIP_CACHE", cache.path().to_string_lossy())
+                .file("/proc/sys/kernel/random/boot_id", "0f3c1d2e\n")
+                .cmd(
+                    "upower -d",
+                    Some(CommandOutput {
+                        stdout: upower.to_string(),
+                        stderr: String::default(),
+                    }),
+                )
+                .battery_info_provider(&failing)
+                .collect()
+        };
+
+        assert_eq!(render(""), None);
+        // UPower is not asked again until the next boot
+        assert_eq!(render(UPOWER), None);
+        assert_eq!(
+            fs::read_to_string(cache.path().join("battery_no_upower"))?,
+            "0f3c1d2e\n"
+        );
+        cache.close()
+    }
 }
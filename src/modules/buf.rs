@@ -3,6 +3,7 @@ use crate::configs::buf::BufConfig;
 use crate::formatter::StringFormatter;
 use crate::formatter::VersionFormatter;
 
+/// Creates a module with the current buf version and the name of the buf module
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("buf");
     let config: BufConfig = BufConfig::try_load(module.config);
@@ -30,8 +31,9 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             })
             .map(|variable| match variable {
                 "version" => {
-                    let buf_version =
-                        parse_buf_version(&context.exec_cmd("buf", &["--version"])?.stdout)?;
+                    let buf_version = parse_buf_version(
+                        &context.exec_version_cmd("buf", &["--version"])?.stdout,
+                    )?;
                     VersionFormatter::format_module_version(
                         module.get_name(),
                         &buf_version,
@@ -39,6 +41,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                     )
                 }
                 .map(Ok),
+                "name" => get_module_name(context).map(Ok),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -55,6 +58,26 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// Reads the module name from `buf.yaml`.
+///
+/// Version 1 configurations name the module at the top level, version 2 configurations
+/// list the modules of the workspace, of which the first named one is used.
+fn get_module_name(context: &Context) -> Option<String> {
+    let contents = context.read_file_from_pwd("buf.yaml")?;
+    let yaml = yaml_rust2::YamlLoader::load_from_str(&contents).ok()?;
+    let config = yaml.first()?;
+
+    let name = match config["name"].as_str() {
+        Some(name) => name,
+        None => config["modules"]
+            .as_vec()?
+            .iter()
+            .find_map(|module| module["name"].as_str())?,
+    };
+
+    Some(name.to_string())
+}
+
 fn parse_buf_version(buf_version: &str) -> Option<String> {
     Some(buf_version.split_whitespace().next()?.to_string())
 }
@@ -64,7 +87,7 @@ mod tests {
     use super::parse_buf_version;
     use crate::test::ModuleRenderer;
     use nu_ansi_term::Color;
-    use std::fs::File;
+    use std::fs::{self, File};
     use std::io;
 
     #[test]
@@ -121,4 +144,63 @@ mod tests {
             dir.close().unwrap();
         }
     }
+
+    #[test]
+    fn module_name_v1() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("buf.yaml"),
+            "version: v1\nname: buf.build/acme/weather\n",
+        )?;
+
+        let actual = ModuleRenderer::new("buf").path(dir.path()).collect();
+
+        let expected = Some(format!(
+            "with {}",
+            Color::Blue
+                .bold()
+                .paint("🐃 v1.0.0 buf.build/acme/weather ")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn module_name_v2() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("buf.yaml"),
+            "version: v2\nmodules:\n  - path: vendor\n  - path: proto\n    name: buf.build/acme/petapis\n",
+        )?;
+
+        let actual = ModuleRenderer::new("buf")
+            .path(dir.path())
+            .config(toml::toml! {
+                [buf]
+                format = "$name"
+            })
+            .collect();
+
+        let expected = Some("buf.build/acme/petapis".to_string());
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn module_without_name() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join("buf.yaml"), "version: v1\n")?;
+
+        let actual = ModuleRenderer::new("buf")
+            .path(dir.path())
+            .config(toml::toml! {
+                [buf]
+                format = "with [$symbol($name )]($style)"
+            })
+            .collect();
+
+        let expected = Some(format!("with {}", Color::Blue.bold().paint("🐃 ")));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
 }
@@ -0,0 +1,321 @@
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::build_staleness::BuildStalenessConfig;
+use crate::formatter::StringFormatter;
+use crate::local_config;
+use crate::module_cache;
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BuildTool {
+    Ninja,
+    Make,
+}
+
+impl BuildTool {
+    /// The build tool of the current directory, preferring ninja as CMake and Meson generate
+    /// `build.ninja` next to other files
+    fn detect(context: &Context) -> Option<Self> {
+        if context
+            .try_begin_scan()?
+            .set_files(&["build.ninja"])
+            .is_match()
+        {
+            Some(Self::Ninja)
+        } else if context
+            .try_begin_scan()?
+            .set_files(&["GNUmakefile", "makefile", "Makefile"])
+            .is_match()
+        {
+            Some(Self::Make)
+        } else {
+            None
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Ninja => "ninja",
+            Self::Make => "make",
+        }
+    }
+
+    /// Asks the build tool whether anything needs to be built, without building it
+    fn is_stale(self, context: &Context, timeout: Duration) -> Option<bool> {
+        match self {
+            Self::Ninja => {
+                let (code, output) = context.exec_cmd_with_status("ninja", &["-n"], timeout)?;
+                (code == Some(0)).then(|| !output.stdout.contains("ninja: no work to do"))
+            }
+            // `make -q` exits with 1 if targets are out of date and 2 on errors
+            Self::Make => match context.exec_cmd_with_status("make", &["-q"], timeout)?.0 {
+                Some(0) => Some(false),
+                Some(1) => Some(true),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Creates a module showing whether the build of the current directory is up to date, asking
+/// `ninja -n` or `make -q`
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("build_staleness");
+    let config = BuildStalenessConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled {
+        return None;
+    }
+
+    let tool = BuildTool::detect(context)?;
+    if tool == BuildTool::Make && !is_trusted(context, &config) {
+        log::debug!("Not running make -q in a directory that isn't trusted");
+        return None;
+    }
+    let is_stale = cached_is_stale(context, tool, &config)?;
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|var, _| match var {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" if is_stale => Some(Ok(config.stale_style)),
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "state" if is_stale => Some(Ok(config.stale_msg)),
+                "state" => Some(Ok(config.up_to_date_msg)),
+                "tool" => Some(Ok(tool.name())),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `build_staleness`:\n{}", error);
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+/// Whether `make -q` may run in the current directory
+///
+/// Even without building anything, make runs the `$(shell ...)` functions of makefiles and the
+/// recipes of lines with `+` or `$(MAKE)`, so only directories below `trusted_dirs` and
+/// repositories whose `.starship.toml` was allowed with `starship allow` are asked.
+fn is_trusted(context: &Context, config: &BuildStalenessConfig) -> bool {
+    let is_trusted_dir = config.trusted_dirs.iter().any(|dir| {
+        let dir = Context::expand_tilde(PathBuf::from(dir));
+        context.current_dir.starts_with(dir)
+    });
+    is_trusted_dir || local_config::is_repo_allowed(&context.current_dir, &context.env)
+}
+
+/// Whether the build is stale, cached for `cache_ttl` seconds, or until the build files or the
+/// entries of the current directory change
+///
+/// There is one cache file per directory and build tool, which starts with a fingerprint of the
+/// modification times it was written for and is overwritten whenever they change.
+fn cached_is_stale(
+    context: &Context,
+    tool: BuildTool,
+    config: &BuildStalenessConfig,
+) -> Option<bool> {
+    let timeout = Duration::from_millis(config.timeout);
+    let cache = context
+        .get_cache_dir()
+        .filter(|_| config.cache_ttl > 0)
+        .map(|dir| {
+            let key = context.fingerprint().cwd().text(tool.name()).finish();
+            dir.join("build_staleness").join(key)
+        });
+    let dir_of = |file: &str| context.current_dir.join(file);
+    let modified = context
+        .fingerprint()
+        .modified(&context.current_dir)
+        .modified(&dir_of("build.ninja"))
+        .modified(&dir_of(".ninja_log"))
+        .modified(&dir_of("GNUmakefile"))
+        .modified(&dir_of("makefile"))
+        .modified(&dir_of("Makefile"))
+        .finish();
+
+    if let Some(cache) = &cache {
+        let ttl = module_cache::lifetime(context, config.cache_ttl);
+        if module_cache::is_fresh(cache, ttl, SystemTime::now()) {
+            let cached = fs::read_to_string(cache).unwrap_or_default();
+            match cached.split_once(' ') {
+                Some((cached_modified, state)) if cached_modified == modified => {
+                    return Some(state == "1");
+                }
+                _ => (),
+            }
+        }
+    }
+
+    let Some(is_stale) = tool.is_stale(context, timeout) else {
+        log::debug!(
+            "Unable to ask {} whether the build is up to date",
+            tool.name()
+        );
+        return None;
+    };
+    if let Some(cache) = cache {
+        let written = cache
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| {
+                let state = if is_stale { "1" } else { "0" };
+                fs::write(&cache, format!("{modified} {state}"))
+            });
+        if let Err(error) = written {
+            log::debug!("Unable to cache the state of the build: {error}");
+        }
+    }
+    Some(is_stale)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test::ModuleRenderer;
+    use crate::utils::CommandOutput;
+    use nu_ansi_term::Color;
+    use std::fs::{self, File};
+    use std::io;
+    use std::path::Path;
+
+    fn config(trusted_dir: &Path, cache_ttl: i64) -> toml::Table {
+        let mut config = toml::toml! {
+            [build_staleness]
+            disabled = false
+        };
+        let module = config
+            .get_mut("build_staleness")
+            .and_then(toml::Value::as_table_mut)
+            .unwrap();
+        module.insert("cache_ttl".to_string(), cache_ttl.into());
+        module.insert(
+            "trusted_dirs".to_string(),
+            vec![trusted_dir.to_string_lossy().to_string()].into(),
+        );
+        config
+    }
+
+    fn render(dir: &Path, cmd: &str, output: Option<CommandOutput>) -> Option<String> {
+        ModuleRenderer::new("build_staleness")
+            .path(dir)
+            .config(config(dir, 0))
+            .cmd(cmd, output)
+            .collect()
+    }
+
+    #[test]
+    fn folder_without_build_files() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        assert_eq!(render(dir.path(), "make -q", None), None);
+        dir.close()
+    }
+
+    #[test]
+    fn ninja_build() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("build.ninja"))?.sync_all()?;
+
+        let up_to_date = CommandOutput {
+            stdout: "ninja: no work to do.\n".to_string(),
+            stderr: String::default(),
+        };
+        let expected = Some(format!("{} ", Color::Green.bold().paint("🔨 up to date")));
+        assert_eq!(render(dir.path(), "ninja -n", Some(up_to_date)), expected);
+
+        let stale = CommandOutput {
+            stdout: "[1/2] Building C object main.c.o\n[2/2] Linking C executable app\n"
+                .to_string(),
+            stderr: String::default(),
+        };
+        let expected = Some(format!("{} ", Color::Yellow.bold().paint("🔨 stale")));
+        assert_eq!(render(dir.path(), "ninja -n", Some(stale)), expected);
+        dir.close()
+    }
+
+    #[test]
+    fn make_build() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("Makefile"))?.sync_all()?;
+
+        let up_to_date = CommandOutput {
+            stdout: String::default(),
+            stderr: String::default(),
+        };
+        let expected = Some(format!("{} ", Color::Green.bold().paint("🔨 up to date")));
+        assert_eq!(render(dir.path(), "make -q", Some(up_to_date)), expected);
+
+        // Mocked commands that fail exit with 1, like `make -q` for targets out of date
+        let expected = Some(format!("{} ", Color::Yellow.bold().paint("🔨 stale")));
+        assert_eq!(render(dir.path(), "make -q", None), expected);
+        dir.close()
+    }
+
+    #[test]
+    fn untrusted_make_build() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("Makefile"))?.sync_all()?;
+        let other_dir = tempfile::tempdir()?;
+
+        let actual = ModuleRenderer::new("build_staleness")
+            .path(dir.path())
+            .config(config(other_dir.path(), 0))
+            .cmd("make -q", None)
+            .collect();
+        assert_eq!(actual, None);
+        dir.close()?;
+        other_dir.close()
+    }
+
+    #[test]
+    fn cache_is_overwritten_in_place() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cache_dir = tempfile::tempdir()?;
+        File::create(dir.path().join("build.ninja"))?.sync_all()?;
+        let render = |stdout: &str| {
+            ModuleRenderer::new("build_staleness")
+                .path(dir.path())
+                .env("STARSHIP_CACHE", cache_dir.path().to_string_lossy())
+                .config(config(dir.path(), 30))
+                .cmd(
+                    "ninja -n",
+                    Some(CommandOutput {
+                        stdout: stdout.to_string(),
+                        stderr: String::default(),
+                    }),
+                )
+                .collect()
+        };
+
+        let expected = Some(format!("{} ", Color::Green.bold().paint("🔨 up to date")));
+        assert_eq!(render("ninja: no work to do.\n"), expected);
+        // Cached until the build files change
+        assert_eq!(render("[1/1] Linking C executable app\n"), expected);
+
+        fs::write(dir.path().join("build.ninja"), "rule cc\n")?;
+        File::create(dir.path().join(".ninja_log"))?.sync_all()?;
+        let expected = Some(format!("{} ", Color::Yellow.bold().paint("🔨 stale")));
+        assert_eq!(render("[1/1] Linking C executable app\n"), expected);
+        let cached = fs::read_dir(cache_dir.path().join("build_staleness"))?.count();
+        assert_eq!(cached, 1);
+        dir.close()?;
+        cache_dir.close()
+    }
+}
@@ -59,7 +59,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
 fn get_bun_version(context: &Context) -> Option<String> {
     context
-        .exec_cmd("bun", &["--version"])
+        .exec_version_cmd("bun", &["--version"])
         .map(get_command_string_output)
         .map(parse_bun_version)
 }
@@ -5,6 +5,7 @@ use crate::formatter::StringFormatter;
 use crate::formatter::VersionFormatter;
 
 use once_cell::sync::Lazy;
+use regex::Regex;
 use semver::Version;
 use std::borrow::Cow;
 use std::ops::Deref;
@@ -39,40 +40,19 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             .map(|variable| match variable {
                 "name" => {
                     let c_compiler_info = &c_compiler_info.deref().as_ref()?.stdout;
-
-                    let c_compiler = if c_compiler_info.contains("clang") {
-                        "clang"
-                    } else if c_compiler_info.contains("Free Software Foundation") {
-                        "gcc"
-                    } else {
-                        return None;
-                    };
-                    Some(Ok(Cow::Borrowed(c_compiler)))
+                    compiler_name(c_compiler_info).map(Cow::Borrowed).map(Ok)
                 }
                 "version" => {
                     let c_compiler_info = &c_compiler_info.deref().as_ref()?.stdout;
-
-                    // Clang says ...
-                    //   Apple clang version 13.0.0 ...\n
-                    //   OpenBSD clang version 11.1.0\n...
-                    //   FreeBSD clang version 11.0.1 ...\n
-                    // so we always want the first semver-ish whitespace-
-                    // separated "word".
-                    // gcc says ...
-                    //   gcc (OmniOS 151036/9.3.0-il-1) 9.3.0\n...
-                    //   gcc (Debian 10.2.1-6) 10.2.1 ...\n
-                    //   cc (GCC) 3.3.5 (Debian 1:3.3.5-13)\n...
-                    // so again we always want the first semver-ish word.
                     VersionFormatter::format_module_version(
                         module.get_name(),
-                        c_compiler_info
-                            .split_whitespace()
-                            .find(|word| Version::parse(word).is_ok())?,
+                        compiler_version(c_compiler_info)?,
                         config.version_format,
                     )
                     .map(Cow::Owned)
                     .map(Ok)
                 }
+                "std" => get_standard(context, Language::C).map(Cow::Owned).map(Ok),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -89,8 +69,127 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// The family of a compiler from its `--version`, `clang` or `gcc`
+pub(super) fn compiler_name(compiler_info: &str) -> Option<&'static str> {
+    if compiler_info.contains("clang") {
+        Some("clang")
+    } else if compiler_info.contains("Free Software Foundation") {
+        Some("gcc")
+    } else {
+        None
+    }
+}
+
+pub(super) fn compiler_version(compiler_info: &str) -> Option<&str> {
+    // Clang says ...
+    //   Apple clang version 13.0.0 ...\n
+    //   OpenBSD clang version 11.1.0\n...
+    //   FreeBSD clang version 11.0.1 ...\n
+    // so we always want the first semver-ish whitespace-
+    // separated "word".
+    // gcc says ...
+    //   gcc (OmniOS 151036/9.3.0-il-1) 9.3.0\n...
+    //   gcc (Debian 10.2.1-6) 10.2.1 ...\n
+    //   cc (GCC) 3.3.5 (Debian 1:3.3.5-13)\n...
+    // so again we always want the first semver-ish word.
+    compiler_info
+        .split_whitespace()
+        .find(|word| Version::parse(word).is_ok())
+}
+
+/// The languages of the `c` and `cpp` modules, which share their build files
+#[derive(Clone, Copy)]
+pub(super) enum Language {
+    C,
+    Cpp,
+}
+
+impl Language {
+    /// The name of the language in CMake variables and compile features, like `CMAKE_CXX_STANDARD`
+    fn cmake_name(self) -> &'static str {
+        match self {
+            Self::C => "C",
+            Self::Cpp => "CXX",
+        }
+    }
+
+    /// The name of the language in Meson options, like `cpp_std`
+    fn meson_name(self) -> &'static str {
+        match self {
+            Self::C => "c",
+            Self::Cpp => "cpp",
+        }
+    }
+
+    /// The `-std=` value of a standard CMake names by its year, like `c++17` for `17`
+    fn standard(self, year: &str) -> String {
+        match self {
+            Self::C => format!("c{year}"),
+            Self::Cpp => format!("c++{year}"),
+        }
+    }
+
+    /// Whether a `-std=` value like `gnu11` or `c++20` is a standard of the language
+    fn is_standard(self, std: &str) -> bool {
+        match self {
+            Self::C => !std.contains("++"),
+            Self::Cpp => std.contains("++"),
+        }
+    }
+}
+
+/// The language standard the project builds with, as its `-std=` value
+///
+/// It is taken from the flags in `compile_flags.txt`, which clangd reads, else from
+/// `CMakeLists.txt` or `meson.build`.
+pub(super) fn get_standard(context: &Context, language: Language) -> Option<String> {
+    let file = |name: &str| context.read_file_from_pwd(name);
+    file("compile_flags.txt")
+        .and_then(|flags| parse_std_flag(&flags, language))
+        .or_else(|| file("CMakeLists.txt").and_then(|cmake| parse_cmake_standard(&cmake, language)))
+        .or_else(|| file("meson.build").and_then(|meson| parse_meson_standard(&meson, language)))
+}
+
+/// The value of a `-std=` flag, or of the `/std:` flag of MSVC
+fn parse_std_flag(flags: &str, language: Language) -> Option<String> {
+    flags
+        .split_whitespace()
+        .map(|flag| flag.trim_matches(|c| matches!(c, '"' | '\'' | '(' | ')')))
+        .filter_map(|flag| {
+            flag.strip_prefix("-std=")
+                .or_else(|| flag.strip_prefix("/std:"))
+        })
+        .find(|std| language.is_standard(std))
+        .map(ToString::to_string)
+}
+
+/// The `CMAKE_<LANG>_STANDARD` variable, else a compile feature like `cxx_std_20`, else a
+/// `-std=` flag in the compile options
+///
+/// Only the names of CMake commands are case-insensitive.
+fn parse_cmake_standard(cmake: &str, language: Language) -> Option<String> {
+    let name = language.cmake_name();
+    let variable = Regex::new(&format!(
+        r#"(?i-u:set)\s*\(\s*CMAKE_{name}_STANDARD\s+"?(\d+)"#
+    ))
+    .ok()?;
+    let feature = Regex::new(&format!(r"\b{}_std_(\d+)\b", name.to_lowercase())).ok()?;
+    variable
+        .captures(cmake)
+        .or_else(|| feature.captures(cmake))
+        .map(|captures| language.standard(&captures[1]))
+        .or_else(|| parse_std_flag(cmake, language))
+}
+
+/// The `<lang>_std` option in the `default_options` of the project, like `'cpp_std=c++17'`
+fn parse_meson_standard(meson: &str, language: Language) -> Option<String> {
+    let option = Regex::new(&format!(r"\b{}_std\s*=\s*([\w+]+)", language.meson_name())).ok()?;
+    Some(option.captures(meson)?[1].to_string())
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::{test::ModuleRenderer, utils::CommandOutput};
     use nu_ansi_term::Color;
     use std::fs::File;
@@ -215,6 +314,62 @@ warranty; not even for MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.",
         dir.close()
     }
 
+    #[test]
+    fn standard_from_cmake() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("main.c"))?.sync_all()?;
+        std::fs::write(
+            dir.path().join("CMakeLists.txt"),
+            "project(demo C)\nset(CMAKE_C_STANDARD 11)\nset(CMAKE_CXX_STANDARD 20)\n",
+        )?;
+
+        let actual = ModuleRenderer::new("c").path(dir.path()).collect();
+        let expected = Some(format!(
+            "via {}",
+            Color::Fixed(149).bold().paint("C v11.0.1-clang c11 ")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn standards_of_build_files() {
+        assert_eq!(
+            parse_std_flag("-Wall\n-std=gnu17\n-Iinclude\n", Language::C),
+            Some("gnu17".to_string())
+        );
+        assert_eq!(parse_std_flag("-std=c++20\n", Language::C), None);
+        assert_eq!(
+            parse_std_flag("/std:c++latest", Language::Cpp),
+            Some("c++latest".to_string())
+        );
+
+        let cmake = "add_library(core)\ntarget_compile_features(core PUBLIC cxx_std_17 c_std_99)\n";
+        assert_eq!(
+            parse_cmake_standard(cmake, Language::Cpp),
+            Some("c++17".to_string())
+        );
+        assert_eq!(
+            parse_cmake_standard(cmake, Language::C),
+            Some("c99".to_string())
+        );
+        assert_eq!(
+            parse_cmake_standard(r#"set(CMAKE_CXX_FLAGS "-O2 -std=gnu++14")"#, Language::Cpp),
+            Some("gnu++14".to_string())
+        );
+
+        let meson =
+            "project('demo', ['c', 'cpp'],\n  default_options : ['c_std=c11', 'cpp_std=c++20'])\n";
+        assert_eq!(
+            parse_meson_standard(meson, Language::C),
+            Some("c11".to_string())
+        );
+        assert_eq!(
+            parse_meson_standard(meson, Language::Cpp),
+            Some("c++20".to_string())
+        );
+    }
+
     #[test]
     fn folder_with_h_file() -> io::Result<()> {
         let dir = tempfile::tempdir()?;
@@ -10,6 +10,10 @@ use crate::formatter::StringFormatter;
 ///   (green arrow by default)
 /// - If the exit-code was anything else, it will be formatted with
 ///   `error_symbol` (red arrow by default)
+/// - In remote sessions, `remote_success_symbol` and `remote_error_symbol`
+///   are used instead when set
+/// - While an asynchronous refresh is pending, the shell passes the frame to
+///   render and one of `pending_symbols` is shown instead
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     enum ShellEditMode {
         Normal,
@@ -44,16 +48,32 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         _ => ASSUMED_MODE,
     };
 
+    // While an asynchronous refresh is pending, the shell passes the animation frame to show
+    let pending_symbol = props
+        .pending_frame
+        .filter(|_| !config.pending_symbols.is_empty())
+        .map(|frame| config.pending_symbols[frame % config.pending_symbols.len()]);
+
     let symbol = match mode {
         ShellEditMode::Normal => config.vimcmd_symbol,
         ShellEditMode::Visual => config.vimcmd_visual_symbol,
         ShellEditMode::Replace => config.vimcmd_replace_symbol,
         ShellEditMode::ReplaceOne => config.vimcmd_replace_one_symbol,
         ShellEditMode::Insert => {
-            if exit_success {
-                config.success_symbol
+            if let Some(pending_symbol) = pending_symbol {
+                pending_symbol
             } else {
-                config.error_symbol
+                let (success_symbol, error_symbol) = if context.session_type().is_remote() {
+                    (config.remote_success_symbol, config.remote_error_symbol)
+                } else {
+                    ("", "")
+                };
+                match (exit_success, success_symbol, error_symbol) {
+                    (true, "", _) => config.success_symbol,
+                    (true, symbol, _) => symbol,
+                    (false, _, "") => config.error_symbol,
+                    (false, _, symbol) => symbol,
+                }
             }
         }
     };
@@ -97,6 +117,36 @@ mod test {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn remote_symbols() {
+        let config = toml::toml! {
+            [character]
+            remote_success_symbol = "[R](bold blue)"
+            remote_error_symbol = "[R](bold purple)"
+        };
+
+        let expected = Some(format!("{} ", Color::Blue.bold().paint("R")));
+        let actual = ModuleRenderer::new("character")
+            .config(config.clone())
+            .env("SSH_CONNECTION", "something")
+            .status(0)
+            .collect();
+        assert_eq!(expected, actual);
+
+        let expected = Some(format!("{} ", Color::Purple.bold().paint("R")));
+        let actual = ModuleRenderer::new("character")
+            .config(config.clone())
+            .env("SSH_CONNECTION", "something")
+            .status(1)
+            .collect();
+        assert_eq!(expected, actual);
+
+        // Local sessions keep using the regular symbols
+        let expected = Some(format!("{} ", Color::Green.bold().paint("❯")));
+        let actual = ModuleRenderer::new("character").config(config).collect();
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn failure_status() {
         let expected = Some(format!("{} ", Color::Red.bold().paint("❯")));
@@ -141,6 +191,49 @@ mod test {
         assert_eq!(expected_success, actual);
     }
 
+    #[test]
+    fn pending_frames() {
+        let config = toml::toml! {
+            [character]
+            pending_symbols = ["[-](yellow)", "[+](yellow)", "[|](yellow)", "[x](yellow)"]
+        };
+
+        let actual = ModuleRenderer::new("character")
+            .config(config.clone())
+            .pending_frame(1)
+            .collect();
+        assert_eq!(Some(format!("{} ", Color::Yellow.paint("+"))), actual);
+
+        // Frames wrap around
+        let actual = ModuleRenderer::new("character")
+            .config(config.clone())
+            .pending_frame(6)
+            .collect();
+        assert_eq!(Some(format!("{} ", Color::Yellow.paint("|"))), actual);
+
+        // Vi modes take precedence over the pending animation
+        let actual = ModuleRenderer::new("character")
+            .config(config)
+            .shell(Shell::Zsh)
+            .keymap("vicmd")
+            .pending_frame(0)
+            .collect();
+        assert_eq!(Some(format!("{} ", Color::Green.bold().paint("❮"))), actual);
+    }
+
+    #[test]
+    fn no_pending_symbols() {
+        let actual = ModuleRenderer::new("character")
+            .config(toml::toml! {
+                [character]
+                pending_symbols = []
+            })
+            .status(1)
+            .pending_frame(2)
+            .collect();
+        assert_eq!(Some(format!("{} ", Color::Red.bold().paint("❯"))), actual);
+    }
+
     #[test]
     fn zsh_keymap() {
         let expected_vicmd = Some(format!("{} ", Color::Green.bold().paint("❮")));
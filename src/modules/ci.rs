@@ -0,0 +1,185 @@
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::ci::CiConfig;
+use crate::formatter::StringFormatter;
+
+/// The CI job the shell is running in
+#[derive(Debug, PartialEq, Eq)]
+struct CiJob {
+    provider: &'static str,
+    job: Option<String>,
+    attempt: Option<String>,
+}
+
+impl CiJob {
+    fn detect(context: &Context) -> Option<Self> {
+        if context.get_env("GITHUB_ACTIONS").as_deref() == Some("true") {
+            Some(Self {
+                provider: "github",
+                job: context.get_env("GITHUB_JOB"),
+                attempt: context.get_env("GITHUB_RUN_ATTEMPT"),
+            })
+        } else if context.get_env("GITLAB_CI").as_deref() == Some("true") {
+            // GitLab creates a new job for every retry, so there is no attempt counter
+            Some(Self {
+                provider: "gitlab",
+                job: context.get_env("CI_JOB_NAME"),
+                attempt: None,
+            })
+        } else if context.get_env("BUILDKITE").as_deref() == Some("true") {
+            // `BUILDKITE_RETRY_COUNT` is 0 for the first attempt
+            let attempt = context
+                .get_env("BUILDKITE_RETRY_COUNT")
+                .and_then(|retries| retries.parse::<u64>().ok())
+                .map(|retries| (retries + 1).to_string());
+            Some(Self {
+                provider: "buildkite",
+                job: context.get_env("BUILDKITE_LABEL"),
+                attempt,
+            })
+        } else if context.is_env_set("JENKINS_URL") {
+            Some(Self {
+                provider: "jenkins",
+                job: context.get_env("JOB_NAME"),
+                attempt: None,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Creates a module with the CI provider, job name and attempt when running on a CI runner
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("ci");
+    let config: CiConfig = CiConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled {
+        return None;
+    };
+
+    let ci_job = CiJob::detect(context)?;
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "provider" => Some(Ok(ci_job.provider)),
+                "job" => ci_job.job.as_deref().filter(|job| !job.is_empty()).map(Ok),
+                "attempt" => ci_job.attempt.as_deref().map(Ok),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `ci`:\n{}", error);
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test::ModuleRenderer;
+    use nu_ansi_term::Color;
+
+    #[test]
+    fn not_in_ci() {
+        let actual = ModuleRenderer::new("ci")
+            .config(toml::toml! {
+                [ci]
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(None, actual);
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let actual = ModuleRenderer::new("ci")
+            .env("GITHUB_ACTIONS", "true")
+            .collect();
+
+        assert_eq!(None, actual);
+    }
+
+    #[test]
+    fn github_actions() {
+        let actual = ModuleRenderer::new("ci")
+            .env("GITHUB_ACTIONS", "true")
+            .env("GITHUB_JOB", "test")
+            .env("GITHUB_RUN_ATTEMPT", "2")
+            .config(toml::toml! {
+                [ci]
+                disabled = false
+            })
+            .collect();
+        let expected = Some(format!(
+            "in {} ",
+            Color::Purple.bold().paint("⚙️  github test (attempt 2)")
+        ));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn gitlab_ci() {
+        let actual = ModuleRenderer::new("ci")
+            .env("GITLAB_CI", "true")
+            .env("CI_JOB_NAME", "lint")
+            .config(toml::toml! {
+                [ci]
+                format = "$provider:$job( #$attempt)"
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(Some("gitlab:lint".to_string()), actual);
+    }
+
+    #[test]
+    fn buildkite_retry() {
+        let actual = ModuleRenderer::new("ci")
+            .env("BUILDKITE", "true")
+            .env("BUILDKITE_LABEL", ":rust: build")
+            .env("BUILDKITE_RETRY_COUNT", "1")
+            .config(toml::toml! {
+                [ci]
+                format = "$provider:$job( #$attempt)"
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(Some("buildkite::rust: build #2".to_string()), actual);
+    }
+
+    #[test]
+    fn jenkins() {
+        let actual = ModuleRenderer::new("ci")
+            .env("JENKINS_URL", "https://ci.example.com/")
+            .env("JOB_NAME", "release/main")
+            .config(toml::toml! {
+                [ci]
+                format = "$provider:$job"
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(Some("jenkins:release/main".to_string()), actual);
+    }
+}
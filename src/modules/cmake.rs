@@ -32,8 +32,9 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             })
             .map(|variable| match variable {
                 "version" => {
-                    let cmake_version =
-                        parse_cmake_version(&context.exec_cmd("cmake", &["--version"])?.stdout)?;
+                    let cmake_version = parse_cmake_version(
+                        &context.exec_version_cmd("cmake", &["--version"])?.stdout,
+                    )?;
                     VersionFormatter::format_module_version(
                         module.get_name(),
                         &cmake_version,
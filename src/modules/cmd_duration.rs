@@ -2,7 +2,6 @@ use super::{Context, Module, ModuleConfig};
 
 use crate::configs::cmd_duration::CmdDurationConfig;
 use crate::formatter::StringFormatter;
-use crate::utils::render_time;
 
 /// Outputs the time it took the last command to execute
 ///
@@ -34,7 +33,9 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map(|variable| match variable {
-                "duration" => Some(Ok(render_time(elapsed, config.show_milliseconds))),
+                "duration" => Some(Ok(
+                    context.format_duration(elapsed, config.show_milliseconds)
+                )),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -133,6 +134,22 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn duration_in_locale() {
+        let actual = ModuleRenderer::new("cmd_duration")
+            .config(toml::toml! {
+                locale = "de_DE"
+            })
+            .cmd_duration(65000)
+            .collect();
+
+        let expected = Some(format!(
+            "took {} ",
+            Color::Yellow.bold().paint("1\u{a0}min 5\u{a0}s")
+        ));
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn config_5s_duration_3s() {
         let actual = ModuleRenderer::new("cmd_duration")
@@ -31,8 +31,9 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             })
             .map(|variable| match variable {
                 "version" => {
-                    let cobol_version =
-                        get_cobol_version(&context.exec_cmd("cobc", &["-version"])?.stdout)?;
+                    let cobol_version = get_cobol_version(
+                        &context.exec_version_cmd("cobc", &["-version"])?.stdout,
+                    )?;
 
                     VersionFormatter::format_module_version(
                         module.get_name(),
@@ -17,6 +17,11 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("conda");
     let config: CondaConfig = CondaConfig::try_load(module.config);
 
+    // The environment is shown as part of the `python_env` stack instead
+    if super::python_env::is_merged(context) {
+        return None;
+    }
+
     if config.ignore_base && conda_env == "base" {
         return None;
     }
@@ -1,77 +1,11 @@
-use super::{Context, Module};
+use super::{Context, Module, ModuleConfig};
 
-#[cfg(not(target_os = "linux"))]
-pub fn module<'a>(_context: &'a Context) -> Option<Module<'a>> {
-    None
-}
+use crate::configs::container::ContainerConfig;
+use crate::context_container::ContainerRuntime;
+use crate::formatter::StringFormatter;
 
-#[cfg(target_os = "linux")]
+/// Creates a module showing the container the session is running in
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
-    use super::ModuleConfig;
-    use crate::configs::container::ContainerConfig;
-    use crate::formatter::StringFormatter;
-    use crate::utils::{self, read_file};
-
-    pub fn container_name(context: &Context) -> Option<String> {
-        use crate::utils::context_path;
-
-        if context_path(context, "/proc/vz").exists() && !context_path(context, "/proc/bc").exists()
-        {
-            // OpenVZ
-            return Some("OpenVZ".into());
-        }
-
-        if context_path(context, "/run/host/container-manager").exists() {
-            // OCI
-            return Some("OCI".into());
-        }
-
-        let container_env_path = context_path(context, "/run/.containerenv");
-
-        if container_env_path.exists() {
-            // podman and others
-
-            let image_res = read_file(container_env_path)
-                .map(|s| {
-                    s.lines()
-                        .find_map(|l| {
-                            if let Some(name_val) = l.strip_prefix("name=\"") {
-                                return name_val.strip_suffix('"').map(|n| n.to_string());
-                            }
-
-                            l.starts_with("image=\"").then(|| {
-                                let r = l.split_at(7).1;
-                                let name = r.rfind('/').map(|n| r.split_at(n + 1).1);
-                                String::from(name.unwrap_or(r).trim_end_matches('"'))
-                            })
-                        })
-                        .unwrap_or_else(|| "podman".into())
-                })
-                .unwrap_or_else(|_| "podman".into());
-
-            return Some(image_res);
-        }
-
-        // WSL with systemd will set the contents of this file to "wsl"
-        // Avoid showing the container module in that case
-        // Honor the contents of this file if "docker" and not running in podman or wsl
-        let systemd_path = context_path(context, "/run/systemd/container");
-        if let Ok(s) = utils::read_file(systemd_path) {
-            match s.trim() {
-                "docker" => return Some("Docker".into()),
-                "wsl" => (),
-                _ => return Some("Systemd".into()),
-            }
-        }
-
-        if context_path(context, "/.dockerenv").exists() {
-            // docker
-            return Some("Docker".into());
-        }
-
-        None
-    }
-
     let mut module = context.new_module("container");
     let config: ContainerConfig = ContainerConfig::try_load(module.config);
 
@@ -79,7 +13,11 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    let container_name = container_name(context)?;
+    // WSL is detected as a container runtime, but is a full system environment
+    let container = context
+        .container()
+        .filter(|container| container.runtime != ContainerRuntime::Wsl)?;
+    let container_name = container.display_name();
 
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
@@ -92,7 +30,8 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map(|variable| match variable {
-                "name" => Some(Ok(&container_name)),
+                "name" => Some(Ok(container_name.as_str())),
+                "image" => container.image.as_deref().map(Ok),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -113,6 +52,8 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 mod tests {
     use crate::test::ModuleRenderer;
     use crate::utils;
+    #[cfg(any(target_os = "freebsd", target_os = "illumos", target_os = "solaris"))]
+    use crate::utils::CommandOutput;
     use nu_ansi_term::Color;
     use std::fs;
 
@@ -286,6 +227,54 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_containerenv_image_variable() {
+        let actual = ModuleRenderer::new("container")
+            .config(toml::toml! {
+               [container]
+               format = "$image"
+            })
+            .file(
+                "/run/.containerenv",
+                "name=\"my-fedora\"\nimage=\"registry.fedoraproject.org/fedora-toolbox:35\"\n",
+            )
+            .collect();
+
+        let expected = Some("registry.fedoraproject.org/fedora-toolbox:35".to_string());
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_cgroup_kubernetes() {
+        let actual = ModuleRenderer::new("container")
+            .file("/proc/1/cgroup", "0::/kubepods/besteffort/pod1234/abcd\n")
+            .collect();
+
+        let expected = Some(format!(
+            "{} ",
+            Color::Red.bold().dimmed().paint("⬢ [Kubernetes]")
+        ));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_container_variable_in_other_module() {
+        let actual = ModuleRenderer::new("username")
+            .config(toml::toml! {
+               [username]
+               show_always = true
+               format = "$container"
+            })
+            .env("USER", "astronaut")
+            .file("/.dockerenv", "")
+            .collect();
+
+        assert_eq!(actual, Some("Docker".to_string()));
+    }
+
     #[test]
     #[cfg(not(target_os = "linux"))]
     fn test_containerenv() -> std::io::Result<()> {
@@ -296,4 +285,37 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    #[cfg(target_os = "freebsd")]
+    fn test_freebsd_jail() {
+        let actual = ModuleRenderer::new("container")
+            .cmd(
+                "sysctl -n security.jail.jailed kern.hostname",
+                Some(CommandOutput {
+                    stdout: String::from("1\nwww\n"),
+                    stderr: String::default(),
+                }),
+            )
+            .collect();
+        let expected = Some(format!("{} ", Color::Red.bold().dimmed().paint("⬢ [www]")));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+    fn test_global_zone() {
+        let actual = ModuleRenderer::new("container")
+            .cmd(
+                "zonename",
+                Some(CommandOutput {
+                    stdout: String::from("global\n"),
+                    stderr: String::default(),
+                }),
+            )
+            .collect();
+
+        assert_eq!(actual, None);
+    }
 }
@@ -0,0 +1,164 @@
+use super::c::{compiler_name, compiler_version, get_standard, Language};
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::cpp::CppConfig;
+use crate::formatter::StringFormatter;
+use crate::formatter::VersionFormatter;
+
+use once_cell::sync::Lazy;
+use std::borrow::Cow;
+use std::ops::Deref;
+
+/// Creates a module with the current C++ compiler and version
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("cpp");
+    let config: CppConfig = CppConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled {
+        return None;
+    }
+
+    let is_cpp_project = context
+        .try_begin_scan()?
+        .set_extensions(&config.detect_extensions)
+        .set_files(&config.detect_files)
+        .set_folders(&config.detect_folders)
+        .is_match();
+
+    if !is_cpp_project {
+        return None;
+    }
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        let cpp_compiler_info = Lazy::new(|| context.exec_cmds_return_first(config.commands));
+
+        formatter
+            .map_meta(|var, _| match var {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "name" => {
+                    let cpp_compiler_info = &cpp_compiler_info.deref().as_ref()?.stdout;
+                    let cpp_compiler = match compiler_name(cpp_compiler_info)? {
+                        "clang" => "clang++",
+                        _ => "g++",
+                    };
+                    Some(Ok(Cow::Borrowed(cpp_compiler)))
+                }
+                "version" => {
+                    let cpp_compiler_info = &cpp_compiler_info.deref().as_ref()?.stdout;
+                    VersionFormatter::format_module_version(
+                        module.get_name(),
+                        compiler_version(cpp_compiler_info)?,
+                        config.version_format,
+                    )
+                    .map(Cow::Owned)
+                    .map(Ok)
+                }
+                "std" => get_standard(context, Language::Cpp).map(Cow::Owned).map(Ok),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `cpp`:\n{}", error);
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test::ModuleRenderer;
+    use nu_ansi_term::Color;
+    use std::fs::{self, File};
+    use std::io;
+
+    #[test]
+    fn folder_without_cpp_files() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let actual = ModuleRenderer::new("cpp")
+            .path(dir.path())
+            .config(toml::toml! {
+                [cpp]
+                disabled = false
+            })
+            .collect();
+
+        let expected = None;
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_cpp_file() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("main.cpp"))?.sync_all()?;
+
+        let actual = ModuleRenderer::new("cpp")
+            .path(dir.path())
+            .config(toml::toml! {
+                [cpp]
+                disabled = false
+            })
+            .collect();
+        let expected = Some(format!(
+            "via {}",
+            Color::Fixed(149).bold().paint("C++ v11.0.1-clang++ ")
+        ));
+        assert_eq!(expected, actual);
+
+        // Falls back to g++ when `c++` is missing
+        let actual = ModuleRenderer::new("cpp")
+            .cmd("c++ --version", None)
+            .path(dir.path())
+            .config(toml::toml! {
+                [cpp]
+                disabled = false
+            })
+            .collect();
+        let expected = Some(format!(
+            "via {}",
+            Color::Fixed(149).bold().paint("C++ v10.2.1-g++ ")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn standard_from_meson() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("main.cc"))?.sync_all()?;
+        fs::write(
+            dir.path().join("meson.build"),
+            "project('demo', 'cpp', default_options : ['cpp_std=c++20'])\n",
+        )?;
+
+        let actual = ModuleRenderer::new("cpp")
+            .path(dir.path())
+            .config(toml::toml! {
+                [cpp]
+                disabled = false
+            })
+            .collect();
+        let expected = Some(format!(
+            "via {}",
+            Color::Fixed(149).bold().paint("C++ v11.0.1-clang++ c++20 ")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+}
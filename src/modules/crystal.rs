@@ -33,7 +33,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             .map(|variable| match variable {
                 "version" => {
                     let crystal_version = parse_crystal_version(
-                        &context.exec_cmd("crystal", &["--version"])?.stdout,
+                        &context.exec_version_cmd("crystal", &["--version"])?.stdout,
                     )?;
                     VersionFormatter::format_module_version(
                         module.get_name(),
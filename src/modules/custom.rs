@@ -10,8 +10,10 @@ use process_control::{ChildExt, Control, Output};
 use super::{Context, Module, ModuleConfig};
 
 use crate::{
-    config::Either, configs::custom::CustomConfig, formatter::StringFormatter,
-    utils::create_command,
+    config::Either,
+    configs::custom::CustomConfig,
+    formatter::StringFormatter,
+    utils::{create_command, sanitize_escape_sequences},
 };
 
 /// Creates a custom module with some configuration
@@ -62,6 +64,8 @@ pub fn module<'a>(name: &str, context: &'a Context) -> Option<Module<'a>> {
     let variables_closure = |variable: &str| match variable {
         "output" => {
             let output = exec_command(config.command, context, &config)?;
+            let output = sanitize_escape_sequences(&output, &config.allowed_escape_sequences);
+            let output = context.mask_secrets(&format!("custom.{name}"), &output);
             let trimmed = output.trim();
 
             if trimmed.is_empty() {
@@ -256,6 +260,10 @@ fn exec_command(cmd: &str, context: &Context, config: &CustomConfig) -> Option<S
     if cmd == "__starship_to_be_escaped" {
         return Some("`to_be_escaped`".to_string());
     }
+    #[cfg(test)]
+    if cmd == "__starship_escape_sequences" {
+        return Some("\x1b]0;title\x07\x1b[2J\x1b[32mgreen\x1b[0m".to_string());
+    }
 
     if let Some(output) = shell_command(cmd, config, context) {
         if !output.status.success() {
@@ -818,4 +826,43 @@ mod tests {
 
         dir.close()
     }
+
+    #[test]
+    fn escape_sequences_are_stripped() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let actual = ModuleRenderer::new("custom.test")
+            .path(dir.path())
+            .config(toml::toml! {
+                [custom.test]
+                format = "$output"
+                command = "__starship_escape_sequences"
+                when = true
+            })
+            .collect();
+        let expected = Some("green".to_string());
+        assert_eq!(expected, actual);
+
+        dir.close()
+    }
+
+    #[test]
+    fn allowed_escape_sequences() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let actual = ModuleRenderer::new("custom.test")
+            .path(dir.path())
+            .config(toml::toml! {
+                [custom.test]
+                format = "$output"
+                command = "__starship_escape_sequences"
+                when = true
+                allowed_escape_sequences = ["color"]
+            })
+            .collect();
+        let expected = Some("\x1b[32mgreen\x1b[0m".to_string());
+        assert_eq!(expected, actual);
+
+        dir.close()
+    }
 }
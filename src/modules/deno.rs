@@ -32,7 +32,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             .map(|variable| match variable {
                 "version" => {
                     let deno_version =
-                        parse_deno_version(&context.exec_cmd("deno", &["-V"])?.stdout)?;
+                        parse_deno_version(&context.exec_version_cmd("deno", &["-V"])?.stdout)?;
                     VersionFormatter::format_module_version(
                         module.get_name(),
                         &deno_version,
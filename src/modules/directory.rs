@@ -4,6 +4,7 @@ use super::utils::directory_nix as directory_utils;
 use super::utils::directory_win as directory_utils;
 use super::utils::path::PathExt as SPathExt;
 use indexmap::IndexMap;
+use once_cell::unsync::OnceCell;
 use path_slash::{PathBufExt, PathExt};
 use std::borrow::Cow;
 use std::iter::FromIterator;
@@ -56,7 +57,9 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     } else {
         None
     };
-    let dir_string = if config.truncate_to_repo {
+    // Bookmarked directories are contracted to their label instead
+    let bookmark = find_bookmark(context, &config, display_dir);
+    let dir_string = if config.truncate_to_repo && bookmark.is_none() {
         repo.and_then(|r| r.workdir.as_ref())
             .filter(|&root| root != &home_dir)
             .and_then(|root| contract_repo_path(display_dir, root))
@@ -67,8 +70,10 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut is_truncated = dir_string.is_some();
 
     // the home directory if required.
-    let dir_string = dir_string
-        .unwrap_or_else(|| contract_path(display_dir, &home_dir, config.home_symbol).to_string());
+    let dir_string = dir_string.unwrap_or_else(|| match &bookmark {
+        Some(bookmark) => contract_path(display_dir, &bookmark.path, &bookmark.label).to_string(),
+        None => contract_path(display_dir, &home_dir, config.home_symbol).to_string(),
+    });
 
     #[cfg(windows)]
     let dir_string = remove_extended_path_prefix(dir_string);
@@ -137,11 +142,13 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let repo_root_style = config.repo_root_style.unwrap_or(config.style);
     let before_repo_root_style = config.before_repo_root_style.unwrap_or(config.style);
 
+    let denied_reason = OnceCell::new();
     let parsed = StringFormatter::new(display_format).and_then(|formatter| {
         formatter
             .map_style(|variable| match variable {
                 "style" => Some(Ok(config.style)),
                 "read_only_style" => Some(Ok(config.read_only_style)),
+                "windows_drive_style" => Some(Ok(config.windows_drive_style)),
                 "repo_root_style" => Some(Ok(repo_root_style)),
                 "before_repo_root_style" => Some(Ok(before_repo_root_style)),
                 _ => None,
@@ -150,13 +157,19 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 "path" => Some(Ok(path_vec[2].as_str())),
                 "before_root_path" => Some(Ok(path_vec[0].as_str())),
                 "repo_root" => Some(Ok(path_vec[1].as_str())),
-                "read_only" => {
-                    if is_readonly_dir(physical_dir) {
-                        Some(Ok(config.read_only))
-                    } else {
-                        None
-                    }
-                }
+                "bookmark" => bookmark.as_ref().map(|bookmark| Ok(bookmark.name.as_str())),
+                "read_only" => denied_reason
+                    .get_or_init(|| write_denied_reason(physical_dir))
+                    .as_ref()
+                    .map(|_| Ok(config.read_only)),
+                "write_denied_reason" => denied_reason
+                    .get_or_init(|| write_denied_reason(physical_dir))
+                    .as_deref()
+                    .map(Ok),
+                // Git and the other modules scanning the directory are slow on these drives
+                "windows_drive" => context
+                    .on_windows_drive()
+                    .then_some(Ok(config.windows_drive)),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -191,16 +204,123 @@ fn remove_extended_path_prefix(path: String) -> String {
     path
 }
 
-fn is_readonly_dir(path: &Path) -> bool {
-    match directory_utils::is_write_allowed(path) {
-        Ok(res) => !res,
+/// A directory that is shown as a short label, like `@work`
+struct Bookmark {
+    name: String,
+    label: String,
+    path: PathBuf,
+}
+
+/// Finds the deepest bookmark containing `display_dir`, out of the configured bookmarks and
+/// the directories zoxide ranks highest, which are named after their rank
+fn find_bookmark(
+    context: &Context,
+    config: &DirectoryConfig,
+    display_dir: &Path,
+) -> Option<Bookmark> {
+    let configured = config
+        .bookmarks
+        .iter()
+        .map(|(name, path)| (name.clone(), Context::expand_tilde(PathBuf::from(path))));
+    let zoxide = (config.zoxide_bookmarks > 0)
+        .then(|| zoxide_dirs(context))
+        .flatten()
+        .unwrap_or_default()
+        .into_iter()
+        .take(config.zoxide_bookmarks)
+        .enumerate()
+        .map(|(i, path)| ((i + 1).to_string(), path));
+
+    let mut found: Option<(String, PathBuf)> = None;
+    for (name, path) in configured.chain(zoxide) {
+        let is_deeper = found.as_ref().map_or(true, |(_, found)| {
+            path.components().count() > found.components().count()
+        });
+        if is_deeper && display_dir.normalised_starts_with(&path) {
+            found = Some((name, path));
+        }
+    }
+
+    found.map(|(name, path)| Bookmark {
+        label: format!("{}{name}", config.bookmark_symbol),
+        name,
+        path,
+    })
+}
+
+/// Reads the directories in zoxide's database, highest scored first
+fn zoxide_dirs(context: &Context) -> Option<Vec<PathBuf>> {
+    let data_dir = match context.get_env("_ZO_DATA_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => dirs::data_local_dir()?.join("zoxide"),
+    };
+    let bytes = std::fs::read(data_dir.join("db.zo"))
+        .map_err(|e| log::debug!("Unable to read the zoxide database: {e}"))
+        .ok()?;
+    let now = chrono::Utc::now()
+        .timestamp()
+        .try_into()
+        .unwrap_or_default();
+    parse_zoxide_db(&bytes, now)
+}
+
+/// Parses version 3 of zoxide's database, a bincode encoded list of directories with their
+/// rank and the time they were last accessed, and orders them the way `zoxide query` does
+fn parse_zoxide_db(mut bytes: &[u8], now: u64) -> Option<Vec<PathBuf>> {
+    fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+        if bytes.len() < len {
+            return None;
+        }
+        let (head, tail) = bytes.split_at(len);
+        *bytes = tail;
+        Some(head)
+    }
+    fn take_u64(bytes: &mut &[u8]) -> Option<u64> {
+        take(bytes, 8)?.try_into().ok().map(u64::from_le_bytes)
+    }
+
+    let version = take(&mut bytes, 4)?
+        .try_into()
+        .ok()
+        .map(u32::from_le_bytes)?;
+    if version != 3 {
+        log::debug!("Unsupported zoxide database version {version}");
+        return None;
+    }
+
+    let len = take_u64(&mut bytes)?;
+    let mut dirs = Vec::new();
+    for _ in 0..len {
+        let path_len = take_u64(&mut bytes)?.try_into().ok()?;
+        let path = std::str::from_utf8(take(&mut bytes, path_len)?).ok()?;
+        let rank = f64::from_bits(take_u64(&mut bytes)?);
+        let last_accessed = take_u64(&mut bytes)?;
+
+        const HOUR: u64 = 60 * 60;
+        let score = match now.saturating_sub(last_accessed) {
+            age if age < HOUR => rank * 4.0,
+            age if age < 24 * HOUR => rank * 2.0,
+            age if age < 7 * 24 * HOUR => rank * 0.5,
+            _ => rank * 0.25,
+        };
+        dirs.push((score, PathBuf::from(path)));
+    }
+
+    dirs.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+    Some(dirs.into_iter().map(|(_, path)| path).collect())
+}
+
+/// Returns why the current user can't write to the directory, if they can't
+fn write_denied_reason(path: &Path) -> Option<String> {
+    match directory_utils::write_denied_reason(path) {
+        Ok(reason) => reason,
         Err(e) => {
             log::debug!(
                 "Failed to determine read only status of directory '{:?}': {}",
                 path,
                 e
             );
-            false
+            None
         }
     }
 }
@@ -1804,4 +1924,116 @@ mod tests {
             "~/user/gitrepo-diff/gitrepo".to_string()
         );
     }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn windows_drive_in_wsl() {
+        let actual = ModuleRenderer::new("directory")
+            .env("WSL_DISTRO_NAME", "Ubuntu")
+            .path("/mnt/c/Users/dev")
+            .config(toml::toml! {
+                [directory]
+                read_only = ""
+            })
+            .collect();
+        let expected = Some(format!(
+            "{}{} ",
+            Color::Cyan.bold().paint("c/Users/dev"),
+            Color::Yellow.paint(" 🐢")
+        ));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn mnt_outside_wsl() {
+        let actual = ModuleRenderer::new("directory")
+            .path("/mnt/c/Users/dev")
+            .config(toml::toml! {
+                [directory]
+                read_only = ""
+            })
+            .collect();
+        let expected = Some(format!("{} ", Color::Cyan.bold().paint("c/Users/dev")));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn bookmarked_directory() {
+        let actual = ModuleRenderer::new("directory")
+            .path("/srv/work/projects/api/src")
+            .config(toml::toml! {
+                [directory]
+                format = "[$path]($style) [$bookmark]($style)"
+                [directory.bookmarks]
+                work = "/srv/work"
+                api = "/srv/work/projects/api"
+            })
+            .collect();
+        let expected = Some(format!(
+            "{} {}",
+            Color::Cyan.bold().paint(convert_path_sep("@api/src")),
+            Color::Cyan.bold().paint("api")
+        ));
+
+        assert_eq!(expected, actual);
+    }
+
+    fn zoxide_db(dirs: &[(&str, f64, u64)]) -> Vec<u8> {
+        let mut bytes = 3u32.to_le_bytes().to_vec();
+        bytes.extend((dirs.len() as u64).to_le_bytes());
+        for (path, rank, last_accessed) in dirs {
+            bytes.extend((path.len() as u64).to_le_bytes());
+            bytes.extend(path.as_bytes());
+            bytes.extend(rank.to_le_bytes());
+            bytes.extend(last_accessed.to_le_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn zoxide_db_order() {
+        let now = 1_700_000_000;
+        let db = zoxide_db(&[
+            ("/old", 40.0, now - 30 * 24 * 60 * 60),
+            ("/recent", 3.0, now - 60),
+            ("/today", 4.0, now - 2 * 60 * 60),
+        ]);
+
+        assert_eq!(
+            parse_zoxide_db(&db, now),
+            Some(vec![
+                PathBuf::from("/recent"),
+                PathBuf::from("/old"),
+                PathBuf::from("/today")
+            ])
+        );
+        assert_eq!(parse_zoxide_db(&db[..db.len() - 1], now), None);
+    }
+
+    #[test]
+    fn zoxide_bookmarks() -> io::Result<()> {
+        let data_dir = tempfile::tempdir()?;
+        let now = chrono::Utc::now().timestamp() as u64;
+        let db = zoxide_db(&[("/srv/docs", 1.0, now), ("/srv/api", 9.0, now)]);
+        fs::write(data_dir.path().join("db.zo"), db)?;
+
+        let actual = ModuleRenderer::new("directory")
+            .path("/srv/docs/guide")
+            .env("_ZO_DATA_DIR", data_dir.path().to_string_lossy())
+            .config(toml::toml! {
+                [directory]
+                zoxide_bookmarks = 9
+            })
+            .collect();
+        let expected = Some(format!(
+            "{} ",
+            Color::Cyan.bold().paint(convert_path_sep("@2/guide"))
+        ));
+
+        assert_eq!(expected, actual);
+        data_dir.close()
+    }
 }
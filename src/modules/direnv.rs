@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::str::FromStr;
 
@@ -7,20 +8,32 @@ use super::{Context, Module, ModuleConfig};
 use crate::configs::direnv::DirenvConfig;
 use crate::formatter::StringFormatter;
 
+use gix::features::zlib;
 use serde::Deserialize;
 
+/// The plain dotenv file direnv loads when there is no `.envrc`, if `load_dotenv` is enabled
+const DOTENV_FILE: &str = ".env";
+
 /// Creates a module with the current direnv rc
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("direnv");
     let config = DirenvConfig::try_load(module.config);
 
-    let direnv_applies = !config.disabled
-        && context
-            .try_begin_scan()?
-            .set_extensions(&config.detect_extensions)
-            .set_files(&config.detect_files)
-            .set_folders(&config.detect_folders)
-            .is_match();
+    if config.disabled {
+        return None;
+    }
+
+    let direnv_applies = context
+        .try_begin_scan()?
+        .set_extensions(&config.detect_extensions)
+        .set_files(&config.detect_files)
+        .set_folders(&config.detect_folders)
+        .is_match()
+        || (loads_dotenv(context)
+            && context
+                .try_begin_scan()?
+                .set_files(&[DOTENV_FILE])
+                .is_match());
 
     if !direnv_applies {
         return None;
@@ -40,23 +53,48 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
             .map_style(|variable| match variable {
-                "style" => Some(Ok(config.style)),
+                "style" => Some(Ok(match state.allowed {
+                    AllowStatus::Allowed => config.allowed_style,
+                    AllowStatus::NotAllowed => config.not_allowed_style,
+                    AllowStatus::Denied => config.denied_style,
+                }
+                .unwrap_or(config.style))),
                 _ => None,
             })
             .map(|variable| match variable {
                 "symbol" => Some(Ok(Cow::from(config.symbol))),
                 "rc_path" => Some(Ok(state.rc_path.to_string_lossy())),
+                "rc_kind" => Some(Ok(Cow::from(
+                    if state
+                        .rc_path
+                        .file_name()
+                        .map_or(false, |name| name == DOTENV_FILE)
+                    {
+                        config.dotenv_msg
+                    } else {
+                        config.envrc_msg
+                    },
+                ))),
                 "allowed" => Some(Ok(match state.allowed {
-                    AllowStatus::Allowed => Cow::from(config.allowed_msg),
-                    AllowStatus::NotAllowed => Cow::from(config.not_allowed_msg),
-                    AllowStatus::Denied => Cow::from(config.denied_msg),
+                    AllowStatus::Allowed => context.translate("allowed", config.allowed_msg),
+                    AllowStatus::NotAllowed => {
+                        context.translate("not-allowed", config.not_allowed_msg)
+                    }
+                    AllowStatus::Denied => context.translate("denied", config.denied_msg),
+                })),
+                "loaded" => Some(Ok(if state.loaded {
+                    context.translate("loaded", config.loaded_msg)
+                } else {
+                    context.translate("not-loaded", config.unloaded_msg)
                 })),
-                "loaded" => state
-                    .loaded
-                    .then_some(config.loaded_msg)
-                    .or(Some(config.unloaded_msg))
-                    .map(Cow::from)
-                    .map(Ok),
+                "stale" => state
+                    .is_stale()
+                    .then(|| Ok(context.translate("stale", config.stale_msg))),
+                "env_diff_count" => context
+                    .get_env("DIRENV_DIFF")
+                    .and_then(|diff| count_env_diff(&diff))
+                    .filter(|count| *count > 0)
+                    .map(|count| Ok(Cow::from(count.to_string()))),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -74,10 +112,57 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// Whether direnv loads `.env` files, enabled with `load_dotenv` in the `[global]` section of
+/// `direnv.toml` since direnv 2.31
+fn loads_dotenv(context: &Context) -> bool {
+    let Some(config_dir) = config_dir(context) else {
+        return false;
+    };
+    // `config.toml` is the name of the file before direnv 2.21
+    ["direnv.toml", "config.toml"]
+        .iter()
+        .find_map(|name| std::fs::read_to_string(config_dir.join(name)).ok())
+        .and_then(|config| toml::from_str::<toml::Table>(&config).ok())
+        .and_then(|config| config.get("global")?.get("load_dotenv")?.as_bool())
+        .unwrap_or(false)
+}
+
+/// The configuration directory of direnv, where it looks for `direnv.toml` like `direnv status`
+/// reports it as `DIRENV_CONFIG`
+fn config_dir(context: &Context) -> Option<PathBuf> {
+    if let Some(dir) = context.get_env("DIRENV_CONFIG") {
+        return Some(PathBuf::from(dir));
+    }
+    if let Some(dir) = context.get_env("XDG_CONFIG_HOME") {
+        return Some(PathBuf::from(dir).join("direnv"));
+    }
+    // Without XDG variables, direnv keeps its files in the local application data on Windows
+    if cfg!(windows) {
+        if let Some(dir) = context.get_env("LOCALAPPDATA") {
+            return Some(PathBuf::from(dir).join("direnv"));
+        }
+    }
+    Some(context.get_home()?.join(".config").join("direnv"))
+}
+
 struct DirenvState {
     pub rc_path: PathBuf,
     pub allowed: AllowStatus,
     pub loaded: bool,
+    /// The rc file whose environment is loaded, which is another one than the one found after
+    /// changing directories until direnv reloads, e.g. when its hook didn't run
+    pub loaded_rc_path: Option<PathBuf>,
+}
+
+impl DirenvState {
+    /// Whether the loaded environment is of another rc file than the one of the current directory
+    fn is_stale(&self) -> bool {
+        self.loaded
+            && self
+                .loaded_rc_path
+                .as_ref()
+                .map_or(false, |loaded_rc_path| *loaded_rc_path != self.rc_path)
+    }
 }
 
 impl FromStr for DirenvState {
@@ -92,6 +177,7 @@ impl FromStr for DirenvState {
                     raw.state.loaded_rc.allowed.try_into()?,
                     AllowStatus::Allowed
                 ),
+                loaded_rc_path: Some(raw.state.loaded_rc.path),
             }),
             Err(_) => Self::from_lines(s),
         }
@@ -103,10 +189,13 @@ impl DirenvState {
         let mut rc_path = PathBuf::new();
         let mut allowed = None;
         let mut loaded = true;
+        let mut loaded_rc_path = None;
 
         for line in s.lines() {
             if let Some(path) = line.strip_prefix("Found RC path") {
                 rc_path = PathBuf::from_str(path.trim()).map_err(|e| Cow::from(e.to_string()))?
+            } else if let Some(path) = line.trim_start().strip_prefix("Loaded RC path") {
+                loaded_rc_path = Some(PathBuf::from(path.trim()));
             } else if let Some(value) = line.strip_prefix("Found RC allowed") {
                 allowed = Some(AllowStatus::from_str(value.trim())?);
             } else if line.contains("No .envrc or .env loaded") {
@@ -122,6 +211,7 @@ impl DirenvState {
             rc_path,
             allowed: allowed.unwrap(),
             loaded,
+            loaded_rc_path,
         })
     }
 }
@@ -159,6 +249,76 @@ impl TryFrom<u8> for AllowStatus {
     }
 }
 
+/// The variables direnv set or unset for the loaded `.envrc`, from the `DIRENV_DIFF` it exports to
+/// undo them when leaving the directory
+///
+/// The diff is JSON with the previous values in `p` and the new ones in `n`, compressed with zlib
+/// and encoded with the URL-safe base64 alphabet.
+fn count_env_diff(diff: &str) -> Option<usize> {
+    let compressed = decode_base64_url(diff.trim())?;
+    let diff: EnvDiff = serde_json::from_slice(&inflate(&compressed)?).ok()?;
+    let changed: HashSet<&String> = diff
+        .previous
+        .keys()
+        .chain(diff.next.keys())
+        .filter(|name| !name.starts_with("DIRENV_"))
+        .collect();
+    Some(changed.len())
+}
+
+#[derive(Debug, Deserialize)]
+struct EnvDiff {
+    #[serde(rename = "p", default)]
+    previous: HashMap<String, String>,
+    #[serde(rename = "n", default)]
+    next: HashMap<String, String>,
+}
+
+fn decode_base64_url(encoded: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(encoded.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for byte in encoded.bytes().take_while(|byte| *byte != b'=') {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'-' => 62,
+            b'_' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+        }
+    }
+    Some(decoded)
+}
+
+fn inflate(compressed: &[u8]) -> Option<Vec<u8>> {
+    let mut inflate = zlib::Inflate::default();
+    let mut inflated = vec![0; compressed.len() * 4];
+    let (mut read, mut written) = (0, 0);
+    loop {
+        let (status, consumed, produced) = inflate
+            .once(&compressed[read..], &mut inflated[written..])
+            .ok()?;
+        read += consumed;
+        written += produced;
+        match status {
+            zlib::Status::StreamEnd => break,
+            _ if written == inflated.len() => inflated.resize(inflated.len() * 2, 0),
+            // Truncated diffs don't make any progress
+            _ if consumed == 0 && produced == 0 => return None,
+            _ => (),
+        }
+    }
+    inflated.truncate(written);
+    Some(inflated)
+}
+
 #[derive(Debug, Deserialize)]
 struct RawDirenvState {
     pub state: State,
@@ -180,13 +340,14 @@ struct RCStatus {
 
 #[cfg(test)]
 mod tests {
+    use super::count_env_diff;
     use serde_json::json;
 
     use crate::test::ModuleRenderer;
     use crate::utils::CommandOutput;
     use nu_ansi_term::Color;
     use std::io;
-    use std::path::Path;
+    use std::path::{Path, PathBuf};
     #[test]
     fn folder_without_rc_files_pre_2_33() {
         let renderer = ModuleRenderer::new("direnv")
@@ -338,6 +499,36 @@ mod tests {
         dir.close()
     }
     #[test]
+    fn folder_with_loaded_rc_file_in_german() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let rc_path = dir.path().join(".envrc");
+
+        std::fs::File::create(rc_path)?.sync_all()?;
+
+        let actual = ModuleRenderer::new("direnv")
+            .config(toml::toml! {
+                [direnv]
+                disabled = false
+            })
+            .env("LANG", "de_DE.UTF-8")
+            .path(dir.path())
+            .cmd(
+                "direnv status --json",
+                Some(CommandOutput {
+                    stdout: status_cmd_output_with_rc_json(dir.path(), 0, 0),
+                    stderr: String::default(),
+                }),
+            )
+            .collect();
+        let expected = Some(format!(
+            "{} ",
+            Color::LightYellow.bold().paint("direnv geladen/erlaubt")
+        ));
+
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+    #[test]
     fn folder_with_loaded_and_denied_rc_file_pre_2_33() -> io::Result<()> {
         let dir = tempfile::tempdir()?;
         let rc_path = dir.path().join(".envrc");
@@ -424,6 +615,193 @@ mod tests {
         assert_eq!(expected, actual);
         dir.close()
     }
+    #[test]
+    fn styles_of_allow_status() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::File::create(dir.path().join(".envrc"))?.sync_all()?;
+        let render = |allowed: u8| {
+            ModuleRenderer::new("direnv")
+                .config(toml::toml! {
+                    [direnv]
+                    disabled = false
+                    format = "[$allowed]($style)"
+                    allowed_style = "green"
+                    denied_style = "red"
+                })
+                .path(dir.path())
+                .cmd(
+                    "direnv status --json",
+                    Some(CommandOutput {
+                        stdout: status_cmd_output_with_rc_json(dir.path(), 0, allowed),
+                        stderr: String::default(),
+                    }),
+                )
+                .collect()
+        };
+
+        assert_eq!(Some(Color::Green.paint("allowed").to_string()), render(0));
+        assert_eq!(Some(Color::Red.paint("denied").to_string()), render(2));
+        // Without a style of its own, the status uses `style`
+        assert_eq!(
+            Some(Color::LightYellow.bold().paint("not allowed").to_string()),
+            render(1)
+        );
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_loaded_rc_file_and_env_diff() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::File::create(dir.path().join(".envrc"))?.sync_all()?;
+
+        // PATH is changed, DATABASE_URL and RUST_LOG are added
+        let diff = "eNqrVipQsqpWCnAM8VCyUtIvLS7ST8rMU6rVUcpDFi8oys8CSVjBVegouTiGODo5BrvGhwb5ANUU5BeXpBelFlvp6-fkJyfmZAD5-okFBUCVQaHBIfE-_u5AVSmpSaXpSrW1AJW4JXw=";
+        let actual = ModuleRenderer::new("direnv")
+            .config(toml::toml! {
+                [direnv]
+                disabled = false
+                format = "$loaded( \\(+$env_diff_count\\))"
+            })
+            .path(dir.path())
+            .env("DIRENV_DIFF", diff)
+            .cmd(
+                "direnv status --json",
+                Some(CommandOutput {
+                    stdout: status_cmd_output_with_rc_json(dir.path(), 0, 0),
+                    stderr: String::default(),
+                }),
+            )
+            .collect();
+        assert_eq!(Some("loaded (+3)".to_string()), actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_dotenv_file() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let rc_path = dir.path().join(".env");
+        std::fs::write(&rc_path, "DATABASE_URL=postgres://localhost/app\n")?;
+        let config_dir = tempfile::tempdir()?;
+
+        let render = || {
+            ModuleRenderer::new("direnv")
+                .config(toml::toml! {
+                    [direnv]
+                    disabled = false
+                    format = "$rc_kind $loaded"
+                })
+                .path(dir.path())
+                .env("DIRENV_CONFIG", config_dir.path().to_string_lossy())
+                .cmd(
+                    "direnv status --json",
+                    Some(CommandOutput {
+                        stdout: status_cmd_output_with_rc_file_json(rc_path.clone(), 0, 0),
+                        stderr: String::default(),
+                    }),
+                )
+                .collect()
+        };
+
+        // direnv ignores `.env` files unless `load_dotenv` is enabled
+        assert_eq!(None, render());
+
+        std::fs::write(
+            config_dir.path().join("direnv.toml"),
+            "[global]\nload_dotenv = true\n",
+        )?;
+        assert_eq!(Some(".env loaded".to_string()), render());
+        dir.close()?;
+        config_dir.close()
+    }
+
+    #[test]
+    fn config_dir_of_xdg_config_home() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let rc_path = dir.path().join(".env");
+        std::fs::write(&rc_path, "DATABASE_URL=postgres://localhost/app\n")?;
+        let config_home = tempfile::tempdir()?;
+        std::fs::create_dir(config_home.path().join("direnv"))?;
+        std::fs::write(
+            config_home.path().join("direnv").join("direnv.toml"),
+            "[global]\nload_dotenv = true\n",
+        )?;
+
+        let actual = ModuleRenderer::new("direnv")
+            .config(toml::toml! {
+                [direnv]
+                disabled = false
+                format = "$rc_kind"
+            })
+            .path(dir.path())
+            .env("XDG_CONFIG_HOME", config_home.path().to_string_lossy())
+            .cmd(
+                "direnv status --json",
+                Some(CommandOutput {
+                    stdout: status_cmd_output_with_rc_file_json(rc_path, 0, 0),
+                    stderr: String::default(),
+                }),
+            )
+            .collect();
+        assert_eq!(Some(".env".to_string()), actual);
+        dir.close()?;
+        config_home.close()
+    }
+
+    #[test]
+    fn folder_with_stale_rc_file() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::File::create(dir.path().join(".envrc"))?.sync_all()?;
+        let parent_rc_path = dir.path().parent().unwrap().join(".envrc");
+        let status = |loaded_rc_path: &Path| {
+            json!({
+                "state": {
+                    "foundRC": {
+                        "allowed": 0,
+                        "path": dir.path().join(".envrc"),
+                    },
+                    "loadedRC": {
+                        "allowed": 0,
+                        "path": loaded_rc_path,
+                    }
+                }
+            })
+            .to_string()
+        };
+        let render = |stdout: String| {
+            ModuleRenderer::new("direnv")
+                .config(toml::toml! {
+                    [direnv]
+                    disabled = false
+                    format = "$loaded( $stale)"
+                })
+                .path(dir.path())
+                .cmd(
+                    "direnv status --json",
+                    Some(CommandOutput {
+                        stdout,
+                        stderr: String::default(),
+                    }),
+                )
+                .collect()
+        };
+
+        assert_eq!(
+            Some("loaded".to_string()),
+            render(status(&dir.path().join(".envrc")))
+        );
+        assert_eq!(
+            Some("loaded stale".to_string()),
+            render(status(&parent_rc_path))
+        );
+        dir.close()
+    }
+
+    #[test]
+    fn invalid_env_diff() {
+        assert_eq!(count_env_diff("not base64!"), None);
+        assert_eq!(count_env_diff("eNqrVipQ"), None);
+    }
+
     fn status_cmd_output_without_rc() -> String {
         String::from(
             r"\
@@ -501,7 +879,9 @@ Found RC allowPath /home/test/.local/share/direnv/allow/abcd
         )
     }
     fn status_cmd_output_with_rc_json(dir: impl AsRef<Path>, loaded: u8, allowed: u8) -> String {
-        let rc_path = dir.as_ref().join(".envrc");
+        status_cmd_output_with_rc_file_json(dir.as_ref().join(".envrc"), loaded, allowed)
+    }
+    fn status_cmd_output_with_rc_file_json(rc_path: PathBuf, loaded: u8, allowed: u8) -> String {
         let rc_path = rc_path.to_string_lossy();
 
         json!({
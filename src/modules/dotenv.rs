@@ -0,0 +1,229 @@
+use std::borrow::Cow;
+
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::dotenv::DotenvConfig;
+use crate::formatter::StringFormatter;
+
+/// Creates a module showing whether the dotenv files of the current directory are loaded
+///
+/// A file counts as loaded when the first `sample_size` variables it defines are set in the
+/// environment of the shell, to the values given in the file.
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("dotenv");
+    let config = DotenvConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled {
+        return None;
+    }
+
+    let files: Vec<(&str, bool)> = config
+        .detect_files
+        .iter()
+        .filter_map(|&name| {
+            let contents = context.read_file_from_pwd(name)?;
+            let entries = parse_entries(&contents);
+            if entries.is_empty() {
+                return None;
+            }
+            Some((name, is_loaded(context, &entries, config.sample_size)))
+        })
+        .collect();
+
+    if files.is_empty() {
+        return None;
+    }
+
+    let loaded = files.iter().all(|(_, loaded)| *loaded);
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" if loaded => Some(Ok(config.style)),
+                "style" => Some(Ok(config.unloaded_style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "files" => Some(Ok(Cow::from(
+                    files
+                        .iter()
+                        .map(|(name, _)| *name)
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ))),
+                "loaded" => Some(Ok(if loaded {
+                    context.translate("loaded", config.loaded_msg)
+                } else {
+                    context.translate("not-loaded", config.unloaded_msg)
+                })),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `dotenv`:\n{}", error);
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+/// Returns the variables a dotenv file defines, with their values unless they refer to other
+/// variables or span multiple lines
+fn parse_entries(contents: &str) -> Vec<(&str, Option<&str>)> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let key = key.trim();
+            let is_name = |c: char| c.is_ascii_alphanumeric() || c == '_';
+            if key.is_empty() || !key.chars().all(is_name) {
+                return None;
+            }
+            Some((key, parse_value(value.trim())))
+        })
+        .collect()
+}
+
+fn parse_value(value: &str) -> Option<&str> {
+    if let Some(quoted) = value.strip_prefix('\'') {
+        return quoted.split_once('\'').map(|(value, _)| value);
+    }
+    let value = if let Some(quoted) = value.strip_prefix('"') {
+        quoted.split_once('"')?.0
+    } else {
+        value.split(" #").next().unwrap_or_default().trim_end()
+    };
+    // Values with interpolation or escapes can't be compared to the environment as written
+    (!value.contains(['$', '\\'])).then_some(value)
+}
+
+fn is_loaded(context: &Context, entries: &[(&str, Option<&str>)], sample_size: usize) -> bool {
+    entries
+        .iter()
+        .take(sample_size.max(1))
+        .all(|(key, value)| match context.get_env(key) {
+            Some(actual) => value.map_or(true, |value| value == actual),
+            None => false,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_entries;
+    use crate::test::ModuleRenderer;
+    use nu_ansi_term::Color;
+    use std::fs;
+    use std::io;
+
+    #[test]
+    fn folder_without_dotenv_files() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let actual = ModuleRenderer::new("dotenv")
+            .config(toml::toml! {
+                [dotenv]
+                disabled = false
+            })
+            .path(dir.path())
+            .collect();
+
+        assert_eq!(None, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn dotenv_not_loaded() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join(".env"),
+            "DATABASE_URL=postgres://localhost\n",
+        )?;
+
+        let actual = ModuleRenderer::new("dotenv")
+            .config(toml::toml! {
+                [dotenv]
+                disabled = false
+            })
+            .path(dir.path())
+            .collect();
+        let expected = Some(format!("{} ", Color::Red.bold().paint(".env not loaded")));
+
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn dotenv_loaded() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join(".env"),
+            "# Local services\nexport DATABASE_URL=\"postgres://localhost\"\nPORT=8080 # web\n",
+        )?;
+
+        let actual = ModuleRenderer::new("dotenv")
+            .config(toml::toml! {
+                [dotenv]
+                disabled = false
+            })
+            .env("DATABASE_URL", "postgres://localhost")
+            .env("PORT", "8080")
+            .path(dir.path())
+            .collect();
+        let expected = Some(format!("{} ", Color::Green.bold().paint(".env loaded")));
+
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn dotenv_loaded_with_other_values() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join(".env"), "PORT=8080\n")?;
+        fs::write(dir.path().join(".env.local"), "PORT=3000\n")?;
+
+        let actual = ModuleRenderer::new("dotenv")
+            .config(toml::toml! {
+                [dotenv]
+                format = "[$files $loaded]($style)"
+                disabled = false
+            })
+            .env("PORT", "8080")
+            .path(dir.path())
+            .collect();
+        let expected = Some(format!(
+            "{}",
+            Color::Red.bold().paint(".env, .env.local not loaded")
+        ));
+
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn values_of_entries() {
+        let contents = "A=1\nexport B = 'x $y'\nC=\"${A}\"\nD=\"multi\nline\"\n-E=2\nF=\n";
+        assert_eq!(
+            parse_entries(contents),
+            vec![
+                ("A", Some("1")),
+                ("B", Some("x $y")),
+                ("C", None),
+                ("D", None),
+                ("F", Some("")),
+            ]
+        );
+    }
+}
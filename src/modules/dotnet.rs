@@ -1,5 +1,7 @@
+use once_cell::sync::Lazy;
 use quick_xml::events::Event;
 use quick_xml::Reader;
+use semver::Version;
 use std::ffi::OsStr;
 use std::iter::Iterator;
 use std::path::{Path, PathBuf};
@@ -8,7 +10,7 @@ use std::str;
 use super::{Context, Module, ModuleConfig};
 use crate::configs::dotnet::DotnetConfig;
 use crate::formatter::StringFormatter;
-use crate::utils;
+use crate::utils::{self, CommandOutput};
 
 type JValue = serde_json::Value;
 use crate::formatter::VersionFormatter;
@@ -40,6 +42,14 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     // Internally, this module uses its own mechanism for version detection.
     // Typically it is twice as fast as running `dotnet --version`.
     let enable_heuristic = config.heuristic;
+    let sdk = Lazy::new(|| {
+        if enable_heuristic {
+            let repo_root = context.get_repo().ok().and_then(|r| r.workdir.as_deref());
+            estimate_dotnet_version(context, &dotnet_files, &context.current_dir, repo_root)
+        } else {
+            get_version_from_cli(context).map(SelectedSdk::found)
+        }
+    });
 
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
@@ -52,25 +62,13 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map(|variable| match variable {
-                "version" => {
-                    let version = if enable_heuristic {
-                        let repo_root = context.get_repo().ok().and_then(|r| r.workdir.as_deref());
-                        estimate_dotnet_version(
-                            context,
-                            &dotnet_files,
-                            &context.current_dir,
-                            repo_root,
-                        )
-                    } else {
-                        get_version_from_cli(context)
-                    };
-                    VersionFormatter::format_module_version(
-                        module.get_name(),
-                        &version?,
-                        config.version_format,
-                    )
-                    .map(Ok)
-                }
+                "version" => VersionFormatter::format_module_version(
+                    module.get_name(),
+                    &sdk.as_ref()?.version,
+                    config.version_format,
+                )
+                .map(Ok),
+                "mismatch" => sdk.as_ref()?.missing.clone().map(Ok),
                 "tfm" => find_current_tfm(&dotnet_files).map(Ok),
                 _ => None,
             })
@@ -145,7 +143,7 @@ fn estimate_dotnet_version(
     files: &[DotNetFile],
     current_dir: &Path,
     repo_root: Option<&Path>,
-) -> Option<String> {
+) -> Option<SelectedSdk> {
     let get_file_of_type = |t: FileType| files.iter().find(|f| f.file_type == t);
 
     // It's important to check for a global.json or a solution file first,
@@ -155,32 +153,59 @@ fn estimate_dotnet_version(
         .or_else(|| files.iter().next())?;
 
     match relevant_file.file_type {
-        FileType::GlobalJson => get_pinned_sdk_version_from_file(relevant_file.path.as_path())
-            .or_else(|| get_latest_sdk_from_cli(context)),
+        FileType::GlobalJson => match read_global_json(relevant_file.path.as_path()) {
+            Some(global_json) => select_sdk(context, &global_json),
+            None => get_latest_sdk_from_cli(context).map(SelectedSdk::found),
+        },
         FileType::SolutionFile => {
             // With this heuristic, we'll assume that a "global.json" won't
             // be found in any directory above the solution file.
-            get_latest_sdk_from_cli(context)
+            get_latest_sdk_from_cli(context).map(SelectedSdk::found)
         }
         _ => {
             // If we see a dotnet project, we'll check a small number of neighboring
             // directories to see if we can find a global.json. Otherwise, assume the
             // latest SDK is in use.
-            try_find_nearby_global_json(current_dir, repo_root)
-                .or_else(|| get_latest_sdk_from_cli(context))
+            match try_find_nearby_global_json(current_dir, repo_root) {
+                Some(global_json) => select_sdk(context, &global_json),
+                None => get_latest_sdk_from_cli(context).map(SelectedSdk::found),
+            }
         }
     }
 }
 
+/// Selects the installed SDK the `global.json` rolls forward to, like the `dotnet` host does
+///
+/// Without a list of the installed SDKs the requested version is shown as it is.
+fn select_sdk(context: &Context, global_json: &GlobalJson) -> Option<SelectedSdk> {
+    let Some(installed) = get_installed_sdks(context) else {
+        return match &global_json.version {
+            Some(version) => Some(SelectedSdk::found(version.to_string())),
+            None => get_version_from_cli(context).map(SelectedSdk::found),
+        };
+    };
+    if let Some(selected) = global_json.resolve(&installed) {
+        return Some(SelectedSdk::found(selected.to_string()));
+    }
+
+    // `dotnet` fails to run commands when no installed SDK matches
+    let requested = global_json.version.as_ref()?.to_string();
+    log::debug!("No installed .NET SDK matches the version {requested} of global.json");
+    Some(SelectedSdk {
+        version: requested.clone(),
+        missing: Some(requested),
+    })
+}
+
 /// Looks for a `global.json` which may exist in one of the parent directories of the current path.
-/// If there is one present, and it contains valid version pinning information, then return that version.
+/// If there is one present, then return its SDK settings.
 ///
 /// The following places are scanned:
 ///     - The parent of the current directory
 ///       (Unless there is a git repository, and the parent is above the root of that repository)
 ///     - The root of the git repository
 ///       (If there is one)
-fn try_find_nearby_global_json(current_dir: &Path, repo_root: Option<&Path>) -> Option<String> {
+fn try_find_nearby_global_json(current_dir: &Path, repo_root: Option<&Path>) -> Option<GlobalJson> {
     let current_dir_is_repo_root = repo_root.map_or(false, |r| r == current_dir);
     let parent_dir = if current_dir_is_repo_root {
         // Don't scan the parent directory if it's above the root of a git repository
@@ -207,51 +232,60 @@ fn try_find_nearby_global_json(current_dir: &Path, repo_root: Option<&Path>) ->
         .find_map(|d| check_directory_for_global_json(d))
 }
 
-fn check_directory_for_global_json(path: &Path) -> Option<String> {
+fn check_directory_for_global_json(path: &Path) -> Option<GlobalJson> {
     let global_json_path = path.join(GLOBAL_JSON_FILE);
     log::debug!(
         "Checking if global.json exists at: {}",
         &global_json_path.display()
     );
     if global_json_path.exists() {
-        get_pinned_sdk_version_from_file(&global_json_path)
+        read_global_json(&global_json_path)
     } else {
         None
     }
 }
 
-fn get_pinned_sdk_version_from_file(path: &Path) -> Option<String> {
+fn read_global_json(path: &Path) -> Option<GlobalJson> {
     let json_text = crate::utils::read_file(path).ok()?;
     log::debug!(
         "Checking if .NET SDK version is pinned in: {}",
         path.display()
     );
-    get_pinned_sdk_version(&json_text)
+    parse_global_json(&json_text)
 }
 
-fn get_pinned_sdk_version(json: &str) -> Option<String> {
+/// Reads the `sdk` section of a `global.json`, which may be missing or empty
+fn parse_global_json(json: &str) -> Option<GlobalJson> {
     let parsed_json: JValue = serde_json::from_str(json).ok()?;
-
-    match parsed_json {
-        JValue::Object(root) => {
-            let sdk = root.get("sdk")?;
-            match sdk {
-                JValue::Object(sdk) => {
-                    let version = sdk.get("version")?;
-                    match version {
-                        JValue::String(version_string) => {
-                            let mut buffer = String::with_capacity(version_string.len() + 1);
-                            buffer.push_str(version_string);
-                            Some(buffer)
-                        }
-                        _ => None,
-                    }
-                }
-                _ => None,
+    let root = parsed_json.as_object()?;
+    let sdk = root.get("sdk").and_then(JValue::as_object);
+    let get = |key: &str| sdk.and_then(|sdk| sdk.get(key));
+
+    let version = match get("version") {
+        Some(JValue::String(version)) => match Version::parse(version) {
+            Ok(version) => Some(version),
+            Err(error) => {
+                log::warn!("Unable to parse the SDK version {version} of global.json: {error}");
+                return None;
             }
-        }
+        },
         _ => None,
-    }
+    };
+    let roll_forward = match get("rollForward").and_then(JValue::as_str) {
+        Some(policy) => RollForward::parse(policy)?,
+        // Without a version any SDK will do
+        None if version.is_none() => RollForward::LatestMajor,
+        None => RollForward::Patch,
+    };
+    let allow_prerelease = get("allowPrerelease")
+        .and_then(JValue::as_bool)
+        .unwrap_or(true);
+
+    Some(GlobalJson {
+        version,
+        roll_forward,
+        allow_prerelease,
+    })
 }
 
 fn get_local_dotnet_files(context: &Context) -> Result<Vec<DotNetFile>, std::io::Error> {
@@ -298,29 +332,8 @@ fn get_version_from_cli(context: &Context) -> Option<String> {
 }
 
 fn get_latest_sdk_from_cli(context: &Context) -> Option<String> {
-    match context.exec_cmd("dotnet", &["--list-sdks"]) {
-        Some(sdks_output) => {
-            fn parse_failed<T>() -> Option<T> {
-                log::warn!("Unable to parse the output from `dotnet --list-sdks`.");
-                None
-            }
-            let latest_sdk = sdks_output
-                .stdout
-                .lines()
-                .map(str::trim)
-                .filter(|l| !l.is_empty())
-                .last()
-                .or_else(parse_failed)?;
-            let take_until = latest_sdk.find('[').or_else(parse_failed)? - 1;
-            if take_until > 1 {
-                let version = &latest_sdk[..take_until];
-                let mut buffer = String::with_capacity(version.len() + 1);
-                buffer.push_str(version);
-                Some(buffer)
-            } else {
-                parse_failed()
-            }
-        }
+    match get_installed_sdks(context) {
+        Some(installed) => installed.iter().max().map(Version::to_string),
         None => {
             // Older versions of the dotnet cli do not support the --list-sdks command
             // So, if the status code indicates failure, fall back to `dotnet --version`
@@ -333,6 +346,176 @@ fn get_latest_sdk_from_cli(context: &Context) -> Option<String> {
     }
 }
 
+/// The installed SDKs, from `dotnet --list-sdks`
+fn get_installed_sdks(context: &Context) -> Option<Vec<Version>> {
+    let output = list_sdks(context)?;
+    let installed = parse_sdk_list(&output.stdout);
+    if installed.is_none() {
+        log::warn!("Unable to parse the output from `dotnet --list-sdks`.");
+    }
+    installed
+}
+
+/// Runs `dotnet --list-sdks`, cached until the directories the SDKs are installed in change
+///
+/// Installing an SDK adds a directory to `sdk` without touching the `dotnet` host, so the cache
+/// of `Context::exec_version_cmd` would keep the old list.
+fn list_sdks(context: &Context) -> Option<CommandOutput> {
+    context.exec_watching_cmd("dotnet", &["--list-sdks"], |output| {
+        sdk_dirs(&output.stdout)
+    })
+}
+
+/// The directories of lines like `8.0.301 [/usr/share/dotnet/sdk]`, each listed once
+fn sdk_dirs(list: &str) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for line in list.lines() {
+        let Some((_, dir)) = line.trim().split_once(" [") else {
+            continue;
+        };
+        let dir = PathBuf::from(dir.trim_end_matches(']'));
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+/// Parses lines like `8.0.301 [/usr/share/dotnet/sdk]`
+fn parse_sdk_list(list: &str) -> Option<Vec<Version>> {
+    list.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (version, _) = line.split_once(" [")?;
+            Version::parse(version).ok()
+        })
+        .collect()
+}
+
+/// The SDK settings of a `global.json`
+#[derive(Debug, PartialEq)]
+struct GlobalJson {
+    version: Option<Version>,
+    roll_forward: RollForward,
+    allow_prerelease: bool,
+}
+
+impl GlobalJson {
+    /// The installed SDK the `dotnet` host selects, `None` if none of them matches
+    ///
+    /// SDK versions are `major.minor.patch`, where the hundreds of the patch are the feature
+    /// band, e.g. `8.0.301` is the patch `1` of the band `3`.
+    fn resolve<'v>(&self, installed: &'v [Version]) -> Option<&'v Version> {
+        let candidates: Vec<&Version> = installed
+            .iter()
+            .filter(|sdk| {
+                self.version
+                    .as_ref()
+                    .map_or(true, |requested| *sdk >= requested)
+            })
+            .filter(|sdk| self.allows_prerelease() || sdk.pre.is_empty())
+            .collect();
+        let Some(requested) = &self.version else {
+            return candidates.into_iter().max();
+        };
+
+        let latest_in = |scope: &dyn Fn(&Version) -> bool| {
+            candidates.iter().copied().filter(|sdk| scope(sdk)).max()
+        };
+        let same_major = |sdk: &Version| sdk.major == requested.major;
+        let same_minor = |sdk: &Version| same_major(sdk) && sdk.minor == requested.minor;
+        let same_band =
+            |sdk: &Version| same_minor(sdk) && feature_band(sdk) == feature_band(requested);
+        // The latest patch of the lowest feature band above the requested one
+        let nearest_band = |scope: &dyn Fn(&Version) -> bool| {
+            let band = candidates
+                .iter()
+                .filter(|sdk| scope(sdk))
+                .map(|sdk| (sdk.major, sdk.minor, feature_band(sdk)))
+                .min()?;
+            latest_in(&|sdk: &Version| (sdk.major, sdk.minor, feature_band(sdk)) == band)
+        };
+        let exact = || candidates.iter().copied().find(|sdk| *sdk == requested);
+
+        match self.roll_forward {
+            RollForward::Disable => exact(),
+            RollForward::Patch => exact().or_else(|| latest_in(&same_band)),
+            RollForward::Feature => latest_in(&same_band).or_else(|| nearest_band(&same_minor)),
+            RollForward::Minor => latest_in(&same_band).or_else(|| nearest_band(&same_major)),
+            RollForward::Major => {
+                latest_in(&same_band).or_else(|| nearest_band(&|_: &Version| true))
+            }
+            RollForward::LatestPatch => latest_in(&same_band),
+            RollForward::LatestFeature => latest_in(&same_minor),
+            RollForward::LatestMinor => latest_in(&same_major),
+            RollForward::LatestMajor => latest_in(&|_: &Version| true),
+        }
+    }
+
+    /// Requesting a preview SDK allows previews even with `"allowPrerelease": false`
+    fn allows_prerelease(&self) -> bool {
+        self.allow_prerelease
+            || self
+                .version
+                .as_ref()
+                .map_or(false, |version| !version.pre.is_empty())
+    }
+}
+
+fn feature_band(version: &Version) -> u64 {
+    version.patch / 100
+}
+
+/// The `rollForward` policies of `global.json`
+#[derive(Debug, PartialEq)]
+enum RollForward {
+    Disable,
+    Patch,
+    Feature,
+    Minor,
+    Major,
+    LatestPatch,
+    LatestFeature,
+    LatestMinor,
+    LatestMajor,
+}
+
+impl RollForward {
+    fn parse(policy: &str) -> Option<Self> {
+        Some(match policy.to_ascii_lowercase().as_str() {
+            "disable" => Self::Disable,
+            "patch" => Self::Patch,
+            "feature" => Self::Feature,
+            "minor" => Self::Minor,
+            "major" => Self::Major,
+            "latestpatch" => Self::LatestPatch,
+            "latestfeature" => Self::LatestFeature,
+            "latestminor" => Self::LatestMinor,
+            "latestmajor" => Self::LatestMajor,
+            _ => {
+                log::warn!("Unknown rollForward policy {policy} in global.json");
+                return None;
+            }
+        })
+    }
+}
+
+/// The SDK to show, with the requested version if no installed SDK matches it
+struct SelectedSdk {
+    version: String,
+    missing: Option<String>,
+}
+
+impl SelectedSdk {
+    fn found(version: String) -> Self {
+        Self {
+            version,
+            missing: None,
+        }
+    }
+}
+
 struct DotNetFile {
     path: PathBuf,
     file_type: FileType,
@@ -642,6 +825,143 @@ mod tests {
             .replace("TFM_VALUE", tfm)
     }
 
+    fn global_json(json: &str) -> GlobalJson {
+        parse_global_json(json).unwrap()
+    }
+
+    fn versions(versions: &[&str]) -> Vec<Version> {
+        versions
+            .iter()
+            .map(|v| Version::parse(v).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn rolls_forward_to_latest_patch_of_feature_band() -> io::Result<()> {
+        let workspace = create_workspace(false)?;
+        touch_path(
+            &workspace,
+            "global.json",
+            Some(&make_pinned_sdk_json("8.0.300")),
+        )?;
+        let actual = ModuleRenderer::new("dotnet")
+            .path(workspace.path())
+            .cmd(
+                "dotnet --list-sdks",
+                Some(CommandOutput {
+                    stdout: "8.0.204 [/usr/share/dotnet/sdk]\n\
+                             8.0.303 [/usr/share/dotnet/sdk]\n\
+                             8.0.401 [/usr/share/dotnet/sdk]\n"
+                        .to_string(),
+                    stderr: String::new(),
+                }),
+            )
+            .collect();
+        let expected = Some(format!(
+            "via {}",
+            Color::Blue.bold().paint(".NET v8.0.303 ")
+        ));
+        assert_eq!(expected, actual);
+        workspace.close()
+    }
+
+    #[test]
+    fn shows_mismatch_without_matching_sdk() -> io::Result<()> {
+        let workspace = create_workspace(false)?;
+        touch_path(
+            &workspace,
+            "global.json",
+            Some(&make_pinned_sdk_json("8.0.100")),
+        )?;
+        let actual = ModuleRenderer::new("dotnet")
+            .path(workspace.path())
+            .config(toml::toml! {
+                [dotnet]
+                format = "$version( ⚠ $mismatch)"
+            })
+            .collect();
+        assert_eq!(Some("v8.0.100 ⚠ 8.0.100".to_string()), actual);
+        workspace.close()
+    }
+
+    #[test]
+    fn roll_forward_policies() {
+        let installed = versions(&[
+            "6.0.100",
+            "6.0.101",
+            "6.0.202",
+            "6.0.300",
+            "6.1.100",
+            "7.0.100",
+            "7.0.400",
+            "9.0.100-preview.1",
+        ]);
+        let resolve = |json: &str| {
+            global_json(json)
+                .resolve(&installed)
+                .map(ToString::to_string)
+        };
+        let sdk = |version: &str, policy: &str| {
+            format!(r#"{{"sdk": {{"version": "{version}", "rollForward": "{policy}"}}}}"#)
+        };
+
+        assert_eq!(
+            resolve(&make_pinned_sdk_json("6.0.100")),
+            Some("6.0.100".into())
+        );
+        assert_eq!(
+            resolve(&make_pinned_sdk_json("6.0.200")),
+            Some("6.0.202".into())
+        );
+        assert_eq!(resolve(&make_pinned_sdk_json("6.0.203")), None);
+        assert_eq!(resolve(&sdk("6.0.102", "disable")), None);
+        assert_eq!(
+            resolve(&sdk("6.0.100", "latestPatch")),
+            Some("6.0.101".into())
+        );
+        assert_eq!(resolve(&sdk("6.0.203", "feature")), Some("6.0.300".into()));
+        assert_eq!(
+            resolve(&sdk("6.0.100", "latestFeature")),
+            Some("6.0.300".into())
+        );
+        assert_eq!(resolve(&sdk("6.0.400", "feature")), None);
+        assert_eq!(resolve(&sdk("6.0.400", "minor")), Some("6.1.100".into()));
+        assert_eq!(
+            resolve(&sdk("6.0.100", "latestMinor")),
+            Some("6.1.100".into())
+        );
+        assert_eq!(resolve(&sdk("6.2.100", "major")), Some("7.0.100".into()));
+        assert_eq!(
+            resolve(&sdk("6.0.100", "latestMajor")),
+            Some("9.0.100-preview.1".into())
+        );
+        assert_eq!(
+            resolve(r#"{"sdk": {"rollForward": "latestMajor", "allowPrerelease": false}}"#),
+            Some("7.0.400".into())
+        );
+        assert_eq!(resolve("{}"), Some("9.0.100-preview.1".into()));
+    }
+
+    #[test]
+    fn should_parse_sdk_list() {
+        assert_eq!(
+            parse_sdk_list(
+                "6.0.100 [C:\\Program Files\\dotnet\\sdk]\n8.0.100-rc.1.23463.5 [/sdk]\n"
+            ),
+            Some(versions(&["6.0.100", "8.0.100-rc.1.23463.5"]))
+        );
+        assert_eq!(parse_sdk_list("not a list"), None);
+    }
+
+    #[test]
+    fn should_list_sdk_dirs_once() {
+        assert_eq!(
+            sdk_dirs("6.0.100 [/usr/share/dotnet/sdk]\n8.0.100 [/usr/share/dotnet/sdk]\n"),
+            vec![PathBuf::from("/usr/share/dotnet/sdk")]
+        );
+        assert_eq!(sdk_dirs("not a list"), Vec::<PathBuf>::new());
+    }
+
     fn expect_output(dir: &Path, expected: Option<String>) {
         let actual = ModuleRenderer::new("dotnet").path(dir).collect();
 
@@ -658,15 +978,18 @@ mod tests {
         }
     "#;
 
-        let version = get_pinned_sdk_version(json_text).unwrap();
-        assert_eq!("1.2.3", version);
+        let global_json = parse_global_json(json_text).unwrap();
+        assert_eq!(Some(Version::new(1, 2, 3)), global_json.version);
+        assert_eq!(RollForward::Patch, global_json.roll_forward);
+        assert!(global_json.allow_prerelease);
     }
 
     #[test]
     fn should_ignore_empty_global_json() {
         let json_text = "{}";
 
-        let version = get_pinned_sdk_version(json_text);
-        assert!(version.is_none());
+        let global_json = parse_global_json(json_text).unwrap();
+        assert!(global_json.version.is_none());
+        assert_eq!(RollForward::LatestMajor, global_json.roll_forward);
     }
 }
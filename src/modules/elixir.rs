@@ -1,3 +1,4 @@
+use super::utils::version::{self, VersionConstraint};
 use super::{Context, Module, ModuleConfig};
 
 use crate::configs::elixir::ElixirConfig;
@@ -5,6 +6,7 @@ use crate::formatter::StringFormatter;
 
 use crate::formatter::VersionFormatter;
 use once_cell::sync::Lazy;
+use regex::Regex;
 use std::ops::Deref;
 
 /// Create a module with the current Elixir version
@@ -24,6 +26,16 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     }
 
     let versions = Lazy::new(|| get_elixir_version(context));
+    let tool_versions = Lazy::new(|| ToolVersions::read(context));
+    let satisfies = Lazy::new(|| {
+        let constraint = tool_versions
+            .elixir
+            .as_deref()
+            .and_then(VersionConstraint::pinned)
+            .or_else(|| get_mix_elixir_requirement(context))?;
+        let elixir_version = versions.as_ref().map(|(_, elixir_version)| elixir_version);
+        version::satisfies(elixir_version.map(String::as_str), Some(&constraint))
+    });
 
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
@@ -32,7 +44,13 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map_style(|variable| match variable {
-                "style" => Some(Ok(config.style)),
+                "style" => {
+                    if *satisfies == Some(false) {
+                        Some(Ok(config.not_capable_style))
+                    } else {
+                        Some(Ok(config.style))
+                    }
+                }
                 _ => None,
             })
             .map(|variable| match variable {
@@ -53,6 +71,13 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                     .as_ref()
                     .map(|(otp_version, _)| otp_version.to_string())
                     .map(Ok),
+                "target_otp_version" => {
+                    let target = tool_versions.otp.as_deref()?;
+                    let (otp_version, _) = versions.deref().as_ref()?;
+                    (target != otp_version).then(|| Ok(target.to_string()))
+                }
+                "mix_env" => context.get_env("MIX_ENV").map(Ok),
+                "satisfies" => satisfies.map(|satisfies| Ok(satisfies.to_string())),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -69,8 +94,64 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// The versions asdf or mise install for the project, from `.tool-versions`
+#[derive(Default)]
+struct ToolVersions {
+    /// The Elixir version, without the OTP release it was compiled for
+    elixir: Option<String>,
+    /// The major OTP release, of the `erlang` entry or of an Elixir version like `1.15.7-otp-26`
+    otp: Option<String>,
+}
+
+impl ToolVersions {
+    fn read(context: &Context) -> Self {
+        context
+            .read_file_from_pwd(".tool-versions")
+            .map(|contents| Self::parse(&contents))
+            .unwrap_or_default()
+    }
+
+    fn parse(contents: &str) -> Self {
+        let mut tool_versions = Self::default();
+        let mut compiled_for = None;
+        for line in contents.lines() {
+            let mut fields = line
+                .split('#')
+                .next()
+                .unwrap_or_default()
+                .split_whitespace();
+            match (fields.next(), fields.next()) {
+                (Some("erlang"), Some(version)) => {
+                    tool_versions.otp = version.split('.').next().map(str::to_string);
+                }
+                (Some("elixir"), Some(version)) => {
+                    let (elixir, otp) = match version.split_once("-otp-") {
+                        Some((elixir, otp)) => (elixir, Some(otp)),
+                        None => (version, None),
+                    };
+                    tool_versions.elixir = Some(elixir.to_string());
+                    compiled_for = otp.map(str::to_string);
+                }
+                _ => {}
+            }
+        }
+        tool_versions.otp = tool_versions.otp.or(compiled_for);
+        tool_versions
+    }
+}
+
+/// The `elixir` requirement of the project in `mix.exs`, like `elixir: "~> 1.15"`
+fn get_mix_elixir_requirement(context: &Context) -> Option<VersionConstraint> {
+    let mix_exs = context.read_file_from_pwd("mix.exs")?;
+    let requirement_re = Regex::new(r#"elixir:\s*"([^"]+)""#).unwrap();
+    let requirement = requirement_re.captures(&mix_exs)?[1]
+        .replace(" or ", " || ")
+        .replace(" and ", ", ");
+    Some(VersionConstraint::Range(requirement))
+}
+
 fn get_elixir_version(context: &Context) -> Option<(String, String)> {
-    let output = context.exec_cmd("elixir", &["--version"])?.stdout;
+    let output = context.exec_version_cmd("elixir", &["--version"])?.stdout;
 
     parse_elixir_version(&output)
 }
@@ -153,4 +234,43 @@ Elixir 1.13.0-dev (compiled with Erlang/OTP 23)
 
         dir.close()
     }
+
+    #[test]
+    fn test_mix_env_and_requirements() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(
+            dir.path().join("mix.exs"),
+            "def project do\n  [app: :shop, elixir: \"~> 1.15\"]\nend\n",
+        )?;
+        std::fs::write(dir.path().join(".tool-versions"), "erlang 26.2.1\n")?;
+
+        let expected = Some(format!(
+            "via {}",
+            Color::Red
+                .bold()
+                .paint("💧 v1.10 (OTP 22, targets 26) prod false ")
+        ));
+        let output = ModuleRenderer::new("elixir")
+            .path(dir.path())
+            .env("MIX_ENV", "prod")
+            .config(toml::toml! {
+                [elixir]
+                format = "via [$symbol$version \\(OTP $otp_version(, targets $target_otp_version)\\) $mix_env $satisfies ]($style)"
+            })
+            .collect();
+
+        assert_eq!(output, expected);
+
+        dir.close()
+    }
+
+    #[test]
+    fn test_parse_tool_versions() {
+        let tool_versions = ToolVersions::parse("nodejs 20.11.0\nelixir 1.15.7-otp-26 # pinned\n");
+        assert_eq!(tool_versions.elixir.as_deref(), Some("1.15.7"));
+        assert_eq!(tool_versions.otp.as_deref(), Some("26"));
+
+        let tool_versions = ToolVersions::parse("erlang 25.3\nelixir 1.14.5-otp-26\n");
+        assert_eq!(tool_versions.otp.as_deref(), Some("25"));
+    }
 }
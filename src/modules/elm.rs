@@ -32,7 +32,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             })
             .map(|variable| match variable {
                 "version" => {
-                    let elm_version = context.exec_cmd("elm", &["--version"])?.stdout;
+                    let elm_version = context.exec_version_cmd("elm", &["--version"])?.stdout;
                     VersionFormatter::format_module_version(
                         module.get_name(),
                         elm_version.trim(),
@@ -0,0 +1,302 @@
+use once_cell::unsync::OnceCell;
+use regex::Regex;
+
+use super::{Context, Module, ModuleConfig};
+
+use crate::config::parse_style_string;
+use crate::configs::environment::{EnvironmentConfig, EnvironmentRuleConfig};
+use crate::formatter::StringFormatter;
+use crate::segment::Segment;
+
+/// Creates a module classifying the current context as a deployment environment
+///
+/// The first rule whose conditions all match decides the environment. Environments listed in
+/// `banner_environments` are rendered as a full-width banner line instead of a plain segment.
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("environment");
+    let config: EnvironmentConfig = EnvironmentConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled {
+        return None;
+    };
+
+    let kube_context = OnceCell::new();
+    let rule = config
+        .rules
+        .iter()
+        .find(|rule| rule_matches(context, rule, &kube_context))?;
+
+    let symbol = rule.symbol.unwrap_or(config.symbol);
+    let style = rule.style.unwrap_or(config.style);
+    let is_banner = config.banner_environments.contains(&rule.environment);
+    let format = if is_banner {
+        config.banner_format
+    } else {
+        config.format
+    };
+
+    let parsed = StringFormatter::new(format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "environment" => Some(Ok(rule.environment)),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    let segments = match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `environment`:\n{}", error);
+            return None;
+        }
+    };
+
+    module.set_segments(if is_banner {
+        let fill_style = parse_style_string(style, Some(context));
+        let mut banner = vec![Segment::fill(fill_style, config.banner_symbol)];
+        banner.extend(segments);
+        banner.push(Segment::fill(fill_style, config.banner_symbol));
+        banner.push(Segment::LineTerm);
+        banner
+    } else {
+        segments
+    });
+
+    Some(module)
+}
+
+/// A rule matches if all of its conditions match. A rule without conditions always matches.
+fn rule_matches(
+    context: &Context,
+    rule: &EnvironmentRuleConfig,
+    kube_context: &OnceCell<Option<String>>,
+) -> bool {
+    if let Some(env_var) = rule.env_var {
+        let matches = match (context.get_env(env_var), rule.env_value_pattern) {
+            (Some(value), Some(pattern)) => is_match(pattern, &value),
+            (Some(value), None) => !value.is_empty(),
+            (None, _) => false,
+        };
+        if !matches {
+            return false;
+        }
+    }
+
+    if let Some(pattern) = rule.kube_context_pattern {
+        let kube_context =
+            kube_context.get_or_init(|| super::kubernetes::get_current_kube_context(context));
+        if !kube_context
+            .as_deref()
+            .map_or(false, |kube_context| is_match(pattern, kube_context))
+        {
+            return false;
+        }
+    }
+
+    if let Some(pattern) = rule.aws_profile_pattern {
+        if !super::aws::get_aws_profile(context)
+            .map_or(false, |profile| is_match(pattern, &profile))
+        {
+            return false;
+        }
+    }
+
+    if let Some(pattern) = rule.directory_pattern {
+        if !is_match(pattern, &context.current_dir.to_string_lossy()) {
+            return false;
+        }
+    }
+
+    true
+}
+
+fn is_match(pattern: &str, value: &str) -> bool {
+    match Regex::new(pattern) {
+        Ok(re) => re.is_match(value),
+        Err(error) => {
+            log::warn!("Invalid pattern in module `environment`:\n{}", error);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test::ModuleRenderer;
+    use nu_ansi_term::Color;
+    use std::fs::File;
+    use std::io::{self, Write};
+
+    #[test]
+    fn disabled_by_default() {
+        let actual = ModuleRenderer::new("environment")
+            .env("DEPLOY_ENV", "dev")
+            .config(toml::toml! {
+                [[environment.rules]]
+                environment = "dev"
+                env_var = "DEPLOY_ENV"
+            })
+            .collect();
+
+        assert_eq!(None, actual);
+    }
+
+    #[test]
+    fn no_rule_matches() {
+        let actual = ModuleRenderer::new("environment")
+            .config(toml::toml! {
+                [environment]
+                disabled = false
+                [[environment.rules]]
+                environment = "dev"
+                env_var = "DEPLOY_ENV"
+            })
+            .collect();
+
+        assert_eq!(None, actual);
+    }
+
+    #[test]
+    fn env_var_value() {
+        let actual = ModuleRenderer::new("environment")
+            .env("DEPLOY_ENV", "staging-eu")
+            .config(toml::toml! {
+                [environment]
+                disabled = false
+                [[environment.rules]]
+                environment = "dev"
+                env_var = "DEPLOY_ENV"
+                env_value_pattern = "^dev"
+                [[environment.rules]]
+                environment = "staging"
+                env_var = "DEPLOY_ENV"
+                env_value_pattern = "^staging"
+            })
+            .collect();
+        let expected = Some(format!("on {} ", Color::Yellow.bold().paint("🚦 staging")));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let actual = ModuleRenderer::new("environment")
+            .env("AWS_PROFILE", "acme-prod")
+            .config(toml::toml! {
+                [environment]
+                format = "$environment"
+                disabled = false
+                [[environment.rules]]
+                environment = "prod-admin"
+                aws_profile_pattern = "prod"
+                env_var = "ADMIN"
+                [[environment.rules]]
+                environment = "staging"
+                aws_profile_pattern = "staging"
+                [[environment.rules]]
+                environment = "live"
+                aws_profile_pattern = "prod"
+                [[environment.rules]]
+                environment = "dev"
+            })
+            .collect();
+
+        assert_eq!(Some("live".to_string()), actual);
+    }
+
+    #[test]
+    fn kube_context() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let filename = dir.path().join("config");
+        let mut file = File::create(&filename)?;
+        file.write_all(
+            b"
+apiVersion: v1
+clusters: []
+contexts:
+  - context:
+      cluster: prod-cluster
+      user: admin
+    name: gke_acme_prod
+current-context: gke_acme_prod
+kind: Config
+preferences: {}
+users: []
+",
+        )?;
+        file.sync_all()?;
+
+        let actual = ModuleRenderer::new("environment")
+            .env("KUBECONFIG", filename.to_string_lossy().as_ref())
+            .config(toml::toml! {
+                [environment]
+                format = "$environment"
+                disabled = false
+                [[environment.rules]]
+                environment = "production"
+                kube_context_pattern = "_prod$"
+            })
+            .collect();
+
+        assert_eq!(Some("production".to_string()), actual);
+        dir.close()
+    }
+
+    #[test]
+    fn directory_pattern() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let deploy_dir = dir.path().join("deploy-staging");
+        std::fs::create_dir(&deploy_dir)?;
+
+        let actual = ModuleRenderer::new("environment")
+            .path(&deploy_dir)
+            .config(toml::toml! {
+                [environment]
+                format = "[$symbol$environment]($style)"
+                disabled = false
+                [[environment.rules]]
+                environment = "staging"
+                directory_pattern = "deploy-staging$"
+                symbol = "🧪 "
+                style = "blue"
+            })
+            .collect();
+
+        assert_eq!(Some(Color::Blue.paint("🧪 staging").to_string()), actual);
+        dir.close()
+    }
+
+    #[test]
+    fn prod_banner() {
+        let actual = ModuleRenderer::new("environment")
+            .env("DEPLOY_ENV", "prod")
+            .config(toml::toml! {
+                [environment]
+                disabled = false
+                [[environment.rules]]
+                environment = "prod"
+                env_var = "DEPLOY_ENV"
+                env_value_pattern = "^prod$"
+                style = "bold white bg:red"
+            })
+            .collect();
+        // Without a known terminal width the fill symbols are printed once
+        let expected = Some(format!(
+            "{}\n",
+            Color::White.bold().on(Color::Red).paint("━ 🚦 prod ━")
+        ));
+
+        assert_eq!(expected, actual);
+    }
+}
@@ -57,7 +57,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 }
 
 fn get_erlang_version(context: &Context) -> Option<String> {
-    Some(context.exec_cmd(
+    Some(context.exec_version_cmd(
         "erl",
         &[
             "-noshell",
@@ -0,0 +1,128 @@
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::errors::ErrorsConfig;
+use crate::formatter::StringFormatter;
+
+/// Creates a module with the number of modules that failed or timed out in this prompt
+///
+/// A module counts as failed if it logged a warning or an error while it was computed.
+/// The prompt renders this module after all other modules, so that it sees their problems.
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("errors");
+    let config: ErrorsConfig = ErrorsConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled {
+        return None;
+    };
+
+    let failed_modules = context.failed_modules();
+    if failed_modules.is_empty() {
+        return None;
+    }
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "count" => Some(Ok(failed_modules.len().to_string())),
+                "modules" => Some(Ok(failed_modules.join(config.separator))),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `errors`:\n{}", error);
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::context::Target;
+    use crate::print::get_prompt;
+    use crate::test::{default_context, ModuleRenderer};
+    use nu_ansi_term::Color;
+    use std::io;
+
+    #[test]
+    fn no_failed_modules() {
+        let actual = ModuleRenderer::new("errors")
+            .config(toml::toml! {
+                [errors]
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(None, actual);
+    }
+
+    #[test]
+    fn failed_module_in_prompt() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut context = default_context().set_config(toml::toml! {
+            add_newline = false
+            format = "$errors$custom"
+            [errors]
+            disabled = false
+            [custom.broken]
+            format = "[$output"
+            when = true
+        });
+        context.target = Target::Main;
+        context.current_dir = dir.path().to_path_buf();
+
+        let expected = format!("{} ", Color::Red.bold().paint("⚠ 1"));
+        assert_eq!(expected, get_prompt(context));
+        dir.close()
+    }
+
+    #[test]
+    fn failed_module_names() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut context = default_context().set_config(toml::toml! {
+            add_newline = false
+            format = "$custom$errors"
+            [errors]
+            format = "$modules"
+            disabled = false
+            [custom.broken]
+            format = "[$output"
+            when = true
+        });
+        context.current_dir = dir.path().to_path_buf();
+
+        assert_eq!("custom.broken", get_prompt(context));
+        dir.close()
+    }
+
+    #[test]
+    fn disabled_by_default() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut context = default_context().set_config(toml::toml! {
+            add_newline = false
+            format = "$custom$errors"
+            [custom.broken]
+            format = "[$output"
+            when = true
+        });
+        context.current_dir = dir.path().to_path_buf();
+
+        assert_eq!("", get_prompt(context));
+        dir.close()
+    }
+}
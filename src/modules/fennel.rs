@@ -31,8 +31,9 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             })
             .map(|variable| match variable {
                 "version" => {
-                    let fennel_version_string =
-                        get_command_string_output(context.exec_cmd("fennel", &["--version"])?);
+                    let fennel_version_string = get_command_string_output(
+                        context.exec_version_cmd("fennel", &["--version"])?,
+                    );
                     let fennel_version = parse_fennel_version(&fennel_version_string)?;
                     VersionFormatter::format_module_version(
                         module.get_name(),
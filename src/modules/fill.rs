@@ -14,9 +14,22 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    let style = parse_style_string(config.style, Some(context));
+    let sections: Vec<_> = config
+        .styles
+        .iter()
+        .filter_map(|style| parse_style_string(style, Some(context)))
+        .collect();
 
-    module.set_segments(vec![Segment::fill(style, config.symbol)]);
+    let segment = if sections.is_empty() {
+        Segment::fill(
+            parse_style_string(config.style, Some(context)),
+            config.symbol,
+        )
+    } else {
+        Segment::fill_sections(sections, config.symbol)
+    };
+
+    module.set_segments(vec![segment]);
 
     Some(module)
 }
@@ -26,6 +39,21 @@ mod tests {
     use crate::test::ModuleRenderer;
     use nu_ansi_term::Color;
 
+    #[test]
+    fn section_styles() {
+        let actual = ModuleRenderer::new("fill")
+            .config(toml::toml! {
+                [fill]
+                styles = ["red", "blue"]
+                symbol = "-"
+            })
+            .collect();
+        // Without a known terminal width the symbol is printed once, in the first style
+        let expected = Some(format!("{}", Color::Red.paint("-")));
+
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn basic() {
         let actual = ModuleRenderer::new("fill")
@@ -0,0 +1,503 @@
+use super::utils::version::VersionConstraint;
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::formatter_tools::FormatterToolsConfig;
+use crate::formatter::StringFormatter;
+use serde_json as json;
+use std::iter;
+use std::path::PathBuf;
+
+/// A formatter that is configured through files in the project directory
+struct FormatterTool {
+    name: &'static str,
+    config_files: &'static [&'static str],
+    command: &'static str,
+    required_version: fn(&Context) -> Option<String>,
+}
+
+const FORMATTER_TOOLS: &[FormatterTool] = &[
+    FormatterTool {
+        name: "prettier",
+        config_files: &[
+            ".prettierrc",
+            ".prettierrc.json",
+            ".prettierrc.yaml",
+            ".prettierrc.yml",
+            ".prettierrc.toml",
+            ".prettierrc.js",
+            ".prettierrc.cjs",
+            ".prettierrc.mjs",
+            "prettier.config.js",
+            "prettier.config.cjs",
+            "prettier.config.mjs",
+        ],
+        command: "prettier",
+        required_version: |context| get_npm_dependency_version(context, "prettier"),
+    },
+    FormatterTool {
+        name: "biome",
+        config_files: &["biome.json", "biome.jsonc"],
+        command: "biome",
+        required_version: |context| get_npm_dependency_version(context, "@biomejs/biome"),
+    },
+    FormatterTool {
+        name: "dprint",
+        config_files: &[
+            "dprint.json",
+            ".dprint.json",
+            "dprint.jsonc",
+            ".dprint.jsonc",
+        ],
+        command: "dprint",
+        required_version: |_| None,
+    },
+    FormatterTool {
+        name: "rustfmt",
+        config_files: &["rustfmt.toml", ".rustfmt.toml"],
+        command: "rustfmt",
+        required_version: |context| {
+            get_toml_version(
+                context,
+                &["rustfmt.toml", ".rustfmt.toml"],
+                "required_version",
+            )
+        },
+    },
+    FormatterTool {
+        name: "ruff",
+        config_files: &["ruff.toml", ".ruff.toml"],
+        command: "ruff",
+        required_version: |context| {
+            get_toml_version(context, &["ruff.toml", ".ruff.toml"], "required-version")
+        },
+    },
+    FormatterTool {
+        name: "clang-format",
+        config_files: &[".clang-format", "_clang-format"],
+        command: "clang-format",
+        required_version: |_| None,
+    },
+    FormatterTool {
+        name: "stylua",
+        config_files: &["stylua.toml", ".stylua.toml"],
+        command: "stylua",
+        required_version: |_| None,
+    },
+    FormatterTool {
+        name: "taplo",
+        config_files: &["taplo.toml", ".taplo.toml"],
+        command: "taplo",
+        required_version: |_| None,
+    },
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ToolStatus {
+    Ok,
+    Mismatch,
+    Missing,
+}
+
+impl ToolStatus {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Mismatch => "mismatch",
+            Self::Missing => "missing",
+        }
+    }
+}
+
+/// Creates a module showing whether the formatters configured in the current directory
+/// are installed, and at the version the project requires
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("formatter_tools");
+    let config: FormatterToolsConfig = FormatterToolsConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled {
+        return None;
+    };
+
+    let dir_contents = context.dir_contents().ok()?;
+    let tools = FORMATTER_TOOLS
+        .iter()
+        .filter(|tool| !config.ignore.contains(&tool.name))
+        .filter(|tool| dir_contents.has_any_positive_file_name(tool.config_files))
+        .map(|tool| {
+            let version = get_tool_version(context, tool.command);
+            let required = (tool.required_version)(context);
+            let status = match (&version, &required) {
+                (None, _) => ToolStatus::Missing,
+                (Some(version), Some(required))
+                    if VersionConstraint::Range(required.clone()).is_satisfied_by(version)
+                        == Some(false) =>
+                {
+                    ToolStatus::Mismatch
+                }
+                _ => ToolStatus::Ok,
+            };
+            (tool.name, version, required, status)
+        })
+        .filter(|(_, _, _, status)| !config.only_problems || *status != ToolStatus::Ok)
+        .collect::<Vec<_>>();
+
+    if tools.is_empty() {
+        return None;
+    }
+
+    let tool_format_with_separator = [config.tool_format, config.tool_separator].join("");
+    let tool_segments = tools
+        .iter()
+        .enumerate()
+        .filter_map(|(i, (name, version, required, status))| {
+            let format: &str = if i == tools.len() - 1 {
+                config.tool_format
+            } else {
+                &tool_format_with_separator
+            };
+            let style = match status {
+                ToolStatus::Ok => config.style,
+                ToolStatus::Mismatch => config.mismatch_style,
+                ToolStatus::Missing => config.missing_style,
+            };
+            let parsed = StringFormatter::new(format).and_then(|formatter| {
+                formatter
+                    .map_style(|variable| match variable {
+                        "style" => Some(Ok(style)),
+                        _ => None,
+                    })
+                    .map(|variable| match variable {
+                        "name" => Some(Ok(*name)),
+                        "version" => version.as_deref().map(Ok),
+                        "required" => required.as_deref().map(Ok),
+                        "status" => Some(Ok(status.as_str())),
+                        _ => None,
+                    })
+                    .parse(None, Some(context))
+            });
+            match parsed {
+                Ok(segments) => Some(segments),
+                Err(e) => {
+                    log::warn!(
+                        "Error parsing format string in `formatter_tools.tool_format`: {e:?}"
+                    );
+                    None
+                }
+            }
+        })
+        .flatten()
+        .collect::<Vec<_>>();
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map_variables_to_segments(|variable| match variable {
+                "tools" => Some(Ok(tool_segments.clone())),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `formatter_tools`:\n{}", error);
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+/// Runs `<command> --version` and extracts the first version-like word, e.g. `1.7.0-stable`
+/// from `rustfmt 1.7.0-stable (aedd173a2c 2024-03-17)`
+///
+/// Formatters installed by the project's package manager are preferred over the one in `PATH`.
+fn get_tool_version(context: &Context, command: &str) -> Option<String> {
+    let output = match get_local_command(context, command) {
+        Some(local) => context.exec_version_cmd(local, &["--version"]),
+        None => context.exec_version_cmd(command, &["--version"]),
+    }?;
+    parse_tool_version(&output.stdout)
+}
+
+/// Finds `command` in the `node_modules/.bin` of the repository root, or else of the current
+/// directory
+fn get_local_command(context: &Context, command: &str) -> Option<PathBuf> {
+    let file_name = if cfg!(windows) {
+        format!("{command}.cmd")
+    } else {
+        command.to_string()
+    };
+    let repo_root = context
+        .get_repo()
+        .ok()
+        .and_then(|repo| repo.workdir.as_deref());
+
+    repo_root
+        .into_iter()
+        .chain(iter::once(context.current_dir.as_path()))
+        .map(|dir| dir.join("node_modules").join(".bin").join(&file_name))
+        .find(|path| path.is_file())
+}
+
+fn parse_tool_version(output: &str) -> Option<String> {
+    output
+        .split_whitespace()
+        .map(|word| word.trim_start_matches('v'))
+        .find(|word| word.starts_with(|c: char| c.is_ascii_digit()))
+        .map(ToString::to_string)
+}
+
+fn get_npm_dependency_version(context: &Context, name: &str) -> Option<String> {
+    let package_json: json::Value =
+        json::from_str(&context.read_file_from_pwd("package.json")?).ok()?;
+
+    ["devDependencies", "dependencies"]
+        .iter()
+        .find_map(|section| package_json.get(section)?.get(name)?.as_str())
+        .map(ToString::to_string)
+}
+
+fn get_toml_version(context: &Context, files: &[&str], key: &str) -> Option<String> {
+    files.iter().find_map(|file| {
+        let contents = context.read_file_from_pwd(file)?;
+        let table: toml::Table = toml::from_str(&contents).ok()?;
+        table.get(key)?.as_str().map(ToString::to_string)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::ModuleRenderer;
+    use crate::utils::{create_command, CommandOutput};
+    use nu_ansi_term::Color;
+    use std::fs;
+    use std::io;
+
+    fn version_output(stdout: &str) -> Option<CommandOutput> {
+        Some(CommandOutput {
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+        })
+    }
+
+    #[test]
+    fn parse_versions() {
+        assert_eq!(
+            parse_tool_version("rustfmt 1.7.0-stable (aedd173a2c 2024-03-17)"),
+            Some("1.7.0-stable".to_string())
+        );
+        assert_eq!(
+            parse_tool_version("Version: 1.5.3"),
+            Some("1.5.3".to_string())
+        );
+        assert_eq!(
+            parse_tool_version("clang-format version 17.0.6"),
+            Some("17.0.6".to_string())
+        );
+        assert_eq!(parse_tool_version("3.2.5\n"), Some("3.2.5".to_string()));
+    }
+
+    #[test]
+    fn disabled_by_default() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join(".prettierrc"), "{}")?;
+
+        let actual = ModuleRenderer::new("formatter_tools")
+            .path(dir.path())
+            .cmd("prettier --version", None)
+            .collect();
+
+        assert_eq!(None, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn no_formatter_config() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let actual = ModuleRenderer::new("formatter_tools")
+            .path(dir.path())
+            .config(toml::toml! {
+                [formatter_tools]
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(None, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn missing_tool() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join("dprint.json"), "{}")?;
+
+        let actual = ModuleRenderer::new("formatter_tools")
+            .path(dir.path())
+            .cmd("dprint --version", None)
+            .config(toml::toml! {
+                [formatter_tools]
+                disabled = false
+            })
+            .collect();
+        let expected = Some(format!(
+            "with {}{} ",
+            Color::Green.bold().paint("🧹 "),
+            Color::Red.bold().paint("dprint")
+        ));
+
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn version_mismatch() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join(".prettierrc"), "{}")?;
+        fs::write(
+            dir.path().join("package.json"),
+            r#"{"devDependencies": {"prettier": "3.1.0"}}"#,
+        )?;
+
+        let actual = ModuleRenderer::new("formatter_tools")
+            .path(dir.path())
+            .cmd("prettier --version", version_output("3.2.5\n"))
+            .config(toml::toml! {
+                [formatter_tools]
+                disabled = false
+            })
+            .collect();
+        let expected = Some(format!(
+            "with {}{} ",
+            Color::Green.bold().paint("🧹 "),
+            Color::Yellow.bold().paint("prettier 3.2.5 (wants 3.1.0)")
+        ));
+
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn prefer_tool_of_repository_root() -> io::Result<()> {
+        let repo_dir = tempfile::tempdir()?;
+        create_command("git")?
+            .args(["init"])
+            .current_dir(&repo_dir)
+            .output()?;
+        let package_dir = repo_dir.path().join("web");
+        fs::create_dir_all(&package_dir)?;
+        fs::write(package_dir.join(".prettierrc"), "{}")?;
+        fs::write(
+            package_dir.join("package.json"),
+            r#"{"devDependencies": {"prettier": "3.1.0"}}"#,
+        )?;
+        let file_name = if cfg!(windows) {
+            "prettier.cmd"
+        } else {
+            "prettier"
+        };
+        let mut local = None;
+        for dir in [repo_dir.path(), &package_dir] {
+            let bin_dir = dir.join("node_modules").join(".bin");
+            fs::create_dir_all(&bin_dir)?;
+            fs::write(bin_dir.join(file_name), "")?;
+            local.get_or_insert(format!("{} --version", bin_dir.join(file_name).display()));
+        }
+        let local = local.unwrap();
+
+        let actual = ModuleRenderer::new("formatter_tools")
+            .path(&package_dir)
+            .cmd("prettier --version", version_output("3.2.5\n"))
+            .cmd(&local, version_output("3.1.0\n"))
+            .config(toml::toml! {
+                [formatter_tools]
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(None, actual);
+        repo_dir.close()
+    }
+
+    #[test]
+    fn satisfied_tools_are_hidden() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("ruff.toml"),
+            "required-version = \">=0.4\"\n",
+        )?;
+
+        let actual = ModuleRenderer::new("formatter_tools")
+            .path(dir.path())
+            .cmd("ruff --version", version_output("ruff 0.4.1\n"))
+            .config(toml::toml! {
+                [formatter_tools]
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(None, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn show_all_tools() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("ruff.toml"),
+            "required-version = \">=0.4\"\n",
+        )?;
+        fs::write(dir.path().join("rustfmt.toml"), "edition = \"2021\"\n")?;
+
+        let actual = ModuleRenderer::new("formatter_tools")
+            .path(dir.path())
+            .cmd("ruff --version", version_output("ruff 0.4.1\n"))
+            .cmd(
+                "rustfmt --version",
+                version_output("rustfmt 1.7.0-stable (aedd173a2c 2024-03-17)\n"),
+            )
+            .config(toml::toml! {
+                [formatter_tools]
+                only_problems = false
+                format = "$tools"
+                tool_format = "$name:$status"
+                tool_separator = ","
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(Some("rustfmt:ok,ruff:ok".to_string()), actual);
+        dir.close()
+    }
+
+    #[test]
+    fn ignored_tool() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join(".clang-format"), "BasedOnStyle: LLVM\n")?;
+
+        let actual = ModuleRenderer::new("formatter_tools")
+            .path(dir.path())
+            .cmd("clang-format --version", None)
+            .config(toml::toml! {
+                [formatter_tools]
+                ignore = ["clang-format"]
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(None, actual);
+        dir.close()
+    }
+}
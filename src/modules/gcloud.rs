@@ -1,9 +1,11 @@
+use chrono::NaiveDateTime;
 use ini::Ini;
 use once_cell::sync::{Lazy, OnceCell};
 use std::borrow::Cow;
 use std::path::Path;
 use std::path::PathBuf;
 
+use super::utils::cloud;
 use super::{Context, Module, ModuleConfig};
 
 use crate::configs::gcloud::GcloudConfig;
@@ -33,9 +35,13 @@ impl<'a> GcloudContext {
             .as_ref()
     }
 
-    pub fn get_account(&'a self) -> Option<Account<'a>> {
+    pub fn get_account_id(&'a self) -> Option<&'a str> {
         let config = self.get_config()?;
-        let account = config.section(Some("core"))?.get("account")?;
+        config.section(Some("core"))?.get("account")
+    }
+
+    pub fn get_account(&'a self) -> Option<Account<'a>> {
+        let account = self.get_account_id()?;
         let mut segments = account.splitn(2, '@');
         Some((segments.next()?, segments.next()))
     }
@@ -80,6 +86,29 @@ fn get_active_config(context: &Context, config_dir: &Path) -> Option<String> {
     })
 }
 
+/// Returns when the access token `gcloud` cached for the account expires, as a Unix timestamp
+///
+/// `gcloud` keeps its access tokens in an SQLite database, which is queried with the `sqlite3`
+/// command.
+fn get_token_expiration(context: &Context, account_id: &str) -> Option<i64> {
+    let database = get_config_dir(context)?.join("access_tokens.db");
+    if !database.is_file() {
+        return None;
+    }
+
+    let query = format!(
+        "SELECT token_expiry FROM access_tokens WHERE account_id = '{}'",
+        account_id.replace('\'', "''")
+    );
+    let database = database.to_string_lossy();
+    let output = context.exec_cmd("sqlite3", &["-readonly", &*database, query.as_str()])?;
+    // The expiry is written by Python as a UTC time, e.g. `2024-01-01 12:00:00.123456`
+    let expiry = output.stdout.lines().next()?.trim();
+    NaiveDateTime::parse_from_str(expiry, "%Y-%m-%d %H:%M:%S%.f")
+        .map(|expiry| expiry.and_utc().timestamp())
+        .ok()
+}
+
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("gcloud");
     let config: GcloudConfig = GcloudConfig::try_load(module.config);
@@ -117,7 +146,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                     .map(Ok),
                 "region" => gcloud_context
                     .get_region()
-                    .map(|region| config.region_aliases.get(region).copied().unwrap_or(region))
+                    .map(|region| cloud::alias(region, &config.region_aliases))
                     .map(Cow::Borrowed)
                     .map(Ok),
                 "project" => context
@@ -132,7 +161,18 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                             .map_or(project, Cow::Borrowed)
                     })
                     .map(Ok),
-                "active" => Some(Ok(Cow::Borrowed(&gcloud_context.config_name))),
+                "active" => Some(Ok(Cow::Borrowed(cloud::alias(
+                    &gcloud_context.config_name,
+                    &config.active_aliases,
+                )))),
+                "expiration" => gcloud_context
+                    .get_account_id()
+                    .and_then(|account_id| get_token_expiration(context, account_id))
+                    .map(|expires_at| {
+                        cloud::render_expiration(context, expires_at, config.expiration_symbol)
+                    })
+                    .map(Cow::Owned)
+                    .map(Ok),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -157,6 +197,7 @@ mod tests {
     use nu_ansi_term::Color;
 
     use crate::test::ModuleRenderer;
+    use crate::utils::CommandOutput;
 
     #[test]
     fn account_set_but_not_shown_because_of_detect_env_vars() -> io::Result<()> {
@@ -236,6 +277,56 @@ account = foo@example.com
         dir.close()
     }
 
+    #[test]
+    fn expiration_of_access_token() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let active_config_path = dir.path().join("active_config");
+        let mut active_config_file = File::create(active_config_path)?;
+        active_config_file.write_all(b"default")?;
+
+        create_dir(dir.path().join("configurations"))?;
+        let config_default_path = dir.path().join("configurations").join("config_default");
+        let mut config_default_file = File::create(config_default_path)?;
+        config_default_file.write_all(
+            b"\
+[core]
+account = foo@example.com
+",
+        )?;
+        let database_path = dir.path().join("access_tokens.db");
+        File::create(&database_path)?;
+
+        let expiry = chrono::Utc::now().naive_utc() + chrono::Duration::minutes(30);
+        let query = format!(
+            "sqlite3 -readonly {} SELECT token_expiry FROM access_tokens WHERE account_id = 'foo@example.com'",
+            database_path.to_string_lossy()
+        );
+        let actual = ModuleRenderer::new("gcloud")
+            .env("CLOUDSDK_CONFIG", dir.path().to_string_lossy())
+            .cmd(
+                &query,
+                Some(CommandOutput {
+                    stdout: format!("{}\n", expiry.format("%Y-%m-%d %H:%M:%S%.6f")),
+                    stderr: String::new(),
+                }),
+            )
+            .config(toml::toml! {
+                [gcloud]
+                format = "on [$symbol$account \\[$expiration\\]]($style) "
+            })
+            .collect();
+        let possible_values = ["30m0s", "29m59s", "29m58s", "29m57s"].map(|duration| {
+            let segment_colored = format!("☁️  foo [{duration}]");
+            Some(format!("on {} ", Color::Blue.bold().paint(segment_colored)))
+        });
+
+        assert!(
+            possible_values.contains(&actual),
+            "time is not in range: {actual:?}"
+        );
+        dir.close()
+    }
+
     #[test]
     fn account_with_custom_format_set() -> io::Result<()> {
         let dir = tempfile::tempdir()?;
@@ -354,6 +445,28 @@ region = us-central1
         dir.close()
     }
 
+    #[test]
+    fn active_set_with_alias() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let active_config_path = dir.path().join("active_config");
+        let mut active_config_file = File::create(active_config_path)?;
+        active_config_file.write_all(b"production-europe")?;
+
+        let actual = ModuleRenderer::new("gcloud")
+            .env("CLOUDSDK_CONFIG", dir.path().to_string_lossy())
+            .config(toml::toml! {
+                [gcloud]
+                format = "on [$symbol$active]($style) "
+                [gcloud.active_aliases]
+                production-europe = "prod-eu"
+            })
+            .collect();
+        let expected = Some(format!("on {} ", Color::Blue.bold().paint("☁️  prod-eu")));
+
+        assert_eq!(actual, expected);
+        dir.close()
+    }
+
     #[test]
     fn project_set() -> io::Result<()> {
         let dir = tempfile::tempdir()?;
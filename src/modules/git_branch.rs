@@ -1,9 +1,12 @@
+use once_cell::sync::Lazy;
 use unicode_segmentation::UnicodeSegmentation;
 
 use super::{Context, Module, ModuleConfig};
 
 use crate::configs::git_branch::GitBranchConfig;
+use crate::context::Repo;
 use crate::formatter::StringFormatter;
+use crate::print::Grapheme;
 
 /// Creates a module with the Git branch in the current directory
 ///
@@ -59,7 +62,15 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         &mut remote_name_graphemes,
     ] {
         let e = &mut **e;
-        let trunc_len = len.min(e.len());
+        // Wide characters take two of the `truncation_length` columns
+        let mut width = 0;
+        let trunc_len = e
+            .iter()
+            .position(|grapheme| {
+                width += Grapheme(grapheme).width();
+                width > len
+            })
+            .unwrap_or(e.len());
         if trunc_len < e.len() {
             // The truncation symbol should only be added if we truncate
             e[trunc_len] = truncation_symbol;
@@ -67,6 +78,15 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         }
     }
 
+    let protected = Lazy::new(|| is_protected(repo, branch_name, &config.protected_branches));
+    let style = if !config.protected_style.is_empty() && *protected {
+        config.protected_style
+    } else {
+        config.style
+    };
+
+    let checkout = Lazy::new(|| CheckoutKind::detect(repo));
+
     let show_remote = config.always_show_remote
         || (!graphemes.eq(&remote_branch_graphemes) && !remote_branch_graphemes.is_empty());
 
@@ -77,11 +97,21 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map_style(|variable| match variable {
-                "style" => Some(Ok(config.style)),
+                "style" => Some(Ok(style)),
                 _ => None,
             })
             .map(|variable| match variable {
                 "branch" => Some(Ok(graphemes.concat())),
+                "protected" => protected.then(|| Ok(config.protected_symbol.to_string())),
+                "sparse" => checkout
+                    .sparse
+                    .then(|| Ok(config.sparse_symbol.to_string())),
+                "shallow" => checkout
+                    .shallow
+                    .then(|| Ok(config.shallow_symbol.to_string())),
+                "partial" => checkout
+                    .partial
+                    .then(|| Ok(config.partial_symbol.to_string())),
                 "remote_branch" => {
                     if show_remote && !remote_branch_graphemes.is_empty() {
                         Some(Ok(remote_branch_graphemes.concat()))
@@ -112,6 +142,59 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// Ways a clone can be incomplete, which changes how many commands behave
+struct CheckoutKind {
+    /// Only some paths are checked out, see `git sparse-checkout`
+    sparse: bool,
+    /// The history is truncated, as with `git clone --depth`
+    shallow: bool,
+    /// Objects are fetched on demand, as with `git clone --filter`
+    partial: bool,
+}
+
+impl CheckoutKind {
+    fn detect(repo: &Repo) -> Self {
+        let repository = repo.open();
+        let config = repository.config_snapshot();
+        Self {
+            sparse: config.boolean("core.sparseCheckout").unwrap_or(false),
+            // Worktrees share the `shallow` file of the main repository
+            shallow: repository.common_dir().join("shallow").is_file(),
+            partial: config.string("extensions.partialClone").is_some(),
+        }
+    }
+}
+
+/// Whether commits on the branch should be made through the remote instead, because it is the
+/// default branch of the remote or matches one of the `protected_branches` patterns
+fn is_protected(repo: &Repo, branch_name: &str, patterns: &[&str]) -> bool {
+    let matches_pattern = patterns
+        .iter()
+        .any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => branch_name.starts_with(prefix),
+            None => branch_name == *pattern,
+        });
+    matches_pattern || default_branch(repo).map_or(false, |default| default == branch_name)
+}
+
+/// Reads the default branch of the remote from `refs/remotes/<remote>/HEAD`, which is created by
+/// `git clone` and `git remote set-head`
+fn default_branch(repo: &Repo) -> Option<String> {
+    let remote = repo
+        .remote
+        .as_ref()
+        .and_then(|remote| remote.name.as_deref())
+        .unwrap_or("origin");
+    let reference = repo
+        .open()
+        .try_find_reference(format!("refs/remotes/{remote}/HEAD").as_str())
+        .ok()??;
+    let target = reference.target().try_name()?.shorten().to_string();
+    target
+        .strip_prefix(&format!("{remote}/"))
+        .map(ToString::to_string)
+}
+
 fn get_first_grapheme(text: &str) -> &str {
     UnicodeSegmentation::graphemes(text, true)
         .next()
@@ -214,7 +297,7 @@ mod tests {
 
     #[test]
     fn test_japanese_truncation() -> io::Result<()> {
-        test_truncate_length("がんばってね", 4, "がんばっ", "…")
+        test_truncate_length("がんばってね", 4, "がん", "…")
     }
 
     #[test]
@@ -392,6 +475,140 @@ mod tests {
         repo_dir.close()
     }
 
+    #[test]
+    fn test_protected_branches() -> io::Result<()> {
+        let repo_dir = fixture_repo(FixtureProvider::Git)?;
+
+        create_command("git")?
+            .args(["checkout", "-b", "release/1.0"])
+            .current_dir(repo_dir.path())
+            .output()?;
+
+        let actual = ModuleRenderer::new("git_branch")
+            .config(toml::toml! {
+                [git_branch]
+                    format = "[$protected$branch]($style)"
+                    protected_branches = ["main", "release/*"]
+                    protected_style = "bold red"
+            })
+            .path(repo_dir.path())
+            .collect();
+
+        let expected = Some(format!("{}", Color::Red.bold().paint("⚠ release/1.0")));
+
+        assert_eq!(expected, actual);
+        repo_dir.close()
+    }
+
+    #[test]
+    fn test_default_branch_is_protected() -> io::Result<()> {
+        let repo_dir = tempfile::tempdir()?;
+
+        create_command("git")?
+            .args(["init"])
+            .current_dir(&repo_dir)
+            .output()?;
+
+        create_command("git")?
+            .args(["symbolic-ref", "HEAD", "refs/heads/main"])
+            .current_dir(&repo_dir)
+            .output()?;
+
+        create_command("git")?
+            .args([
+                "symbolic-ref",
+                "refs/remotes/origin/HEAD",
+                "refs/remotes/origin/main",
+            ])
+            .current_dir(&repo_dir)
+            .output()?;
+
+        let actual = ModuleRenderer::new("git_branch")
+            .config(toml::toml! {
+                [git_branch]
+                    format = "$protected$branch"
+            })
+            .path(repo_dir.path())
+            .collect();
+
+        let expected = Some("⚠ main".to_string());
+
+        assert_eq!(expected, actual);
+        repo_dir.close()
+    }
+
+    #[test]
+    fn test_sparse_partial_clone() -> io::Result<()> {
+        let repo_dir = tempfile::tempdir()?;
+
+        create_command("git")?
+            .args(["init"])
+            .current_dir(&repo_dir)
+            .output()?;
+
+        create_command("git")?
+            .args(["symbolic-ref", "HEAD", "refs/heads/main"])
+            .current_dir(&repo_dir)
+            .output()?;
+
+        for (key, value) in [
+            ("core.sparseCheckout", "true"),
+            ("extensions.partialClone", "origin"),
+        ] {
+            create_command("git")?
+                .args(["config", key, value])
+                .current_dir(&repo_dir)
+                .output()?;
+        }
+
+        let actual = ModuleRenderer::new("git_branch")
+            .config(toml::toml! {
+                [git_branch]
+                    format = "$sparse$shallow$partial$branch"
+            })
+            .path(repo_dir.path())
+            .collect();
+
+        let expected = Some("sparse partial main".to_string());
+
+        assert_eq!(expected, actual);
+        repo_dir.close()
+    }
+
+    #[test]
+    fn test_shallow_worktree() -> io::Result<()> {
+        let repo_dir = fixture_repo(FixtureProvider::Git)?;
+        let worktree_dir = tempfile::tempdir()?;
+        let worktree = worktree_dir.path().join("shallow");
+
+        let head = create_command("git")?
+            .args(["rev-parse", "HEAD"])
+            .current_dir(repo_dir.path())
+            .output()?
+            .stdout;
+        std::fs::write(repo_dir.path().join(".git").join("shallow"), head)?;
+
+        create_command("git")?
+            .args(["worktree", "add", "-b", "feature"])
+            .arg(&worktree)
+            .current_dir(repo_dir.path())
+            .output()?;
+
+        let actual = ModuleRenderer::new("git_branch")
+            .config(toml::toml! {
+                [git_branch]
+                    format = "$shallow$branch"
+            })
+            .path(&worktree)
+            .collect();
+
+        let expected = Some("shallow feature".to_string());
+
+        assert_eq!(expected, actual);
+        worktree_dir.close()?;
+        repo_dir.close()
+    }
+
     #[test]
     fn test_remote() -> io::Result<()> {
         let remote_dir = fixture_repo(FixtureProvider::Git)?;
@@ -6,11 +6,11 @@ use super::{Context, Module, ModuleConfig};
 use crate::configs::git_status::GitStatusConfig;
 use crate::context;
 use crate::formatter::StringFormatter;
+use crate::git_watch;
 use crate::segment::Segment;
 use std::sync::Arc;
 
-const ALL_STATUS_FORMAT: &str =
-    "$conflicted$stashed$deleted$renamed$modified$typechanged$staged$untracked";
+const ALL_STATUS_FORMAT: &str = "$conflicted$stashed$deleted$renamed$modified$typechanged$staged$untracked$submodules_dirty$lfs_missing";
 
 /// Creates a module with the Git branch in the current directory
 ///
@@ -107,6 +107,27 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                     "typechanged" => info.get_typechanged().and_then(|count| {
                         format_count(config.typechanged, "git_status.typechanged", context, count)
                     }),
+                    // Both are empty by default, which skips looking them up
+                    "submodules_dirty" if !config.submodules_dirty.is_empty() => {
+                        info.get_submodules_dirty().and_then(|count| {
+                            format_count(
+                                config.submodules_dirty,
+                                "git_status.submodules_dirty",
+                                context,
+                                count,
+                            )
+                        })
+                    }
+                    "lfs_missing" if !config.lfs_missing.is_empty() => {
+                        info.get_lfs_missing().and_then(|count| {
+                            format_count(
+                                config.lfs_missing,
+                                "git_status.lfs_missing",
+                                context,
+                                count,
+                            )
+                        })
+                    }
                     _ => None,
                 };
                 segments.map(Ok)
@@ -137,6 +158,7 @@ struct GitStatusInfo<'a> {
     config: GitStatusConfig<'a>,
     repo_status: OnceCell<Option<RepoStatus>>,
     stashed_count: OnceCell<Option<usize>>,
+    lfs_missing_count: OnceCell<Option<usize>>,
 }
 
 impl<'a> GitStatusInfo<'a> {
@@ -151,6 +173,7 @@ impl<'a> GitStatusInfo<'a> {
             config,
             repo_status: OnceCell::new(),
             stashed_count: OnceCell::new(),
+            lfs_missing_count: OnceCell::new(),
         }
     }
 
@@ -208,6 +231,22 @@ impl<'a> GitStatusInfo<'a> {
     pub fn get_typechanged(&self) -> Option<usize> {
         self.get_repo_status().map(|data| data.typechanged)
     }
+
+    pub fn get_submodules_dirty(&self) -> Option<usize> {
+        self.get_repo_status().map(|data| data.submodules_dirty)
+    }
+
+    pub fn get_lfs_missing(&self) -> Option<usize> {
+        *self.lfs_missing_count.get_or_init(|| {
+            match get_lfs_missing_count(self.context, self.repo) {
+                Some(count) => Some(count),
+                None => {
+                    log::debug!("get_lfs_missing_count: git lfs execution failed");
+                    None
+                }
+            }
+        })
+    }
 }
 
 /// Gets the number of files in various git states (staged, modified, deleted, etc...)
@@ -219,6 +258,41 @@ fn get_repo_status(
     log::debug!("New repo status created");
 
     let mut repo_status = RepoStatus::default();
+    let mut args = status_args(config);
+    let snapshot = match config.scope {
+        // Only count changes below the current directory, e.g. one package of a monorepo
+        "subdir" => {
+            args.extend(["--", "."]);
+            None
+        }
+        // `starship watch-git` keeps the status of the whole repository
+        scope => {
+            if scope != "repo" {
+                log::warn!("Unknown git_status scope {scope:?}, expected \"repo\" or \"subdir\"");
+            }
+            git_watch::read_snapshot(repo, &args)
+        }
+    };
+
+    let status_output = match snapshot {
+        Some(status) => status,
+        None => repo.exec_git(context, &args)?.stdout,
+    };
+    let statuses = status_output.lines();
+
+    statuses.for_each(|status| {
+        if status.starts_with("# branch.ab ") {
+            repo_status.set_ahead_behind(status);
+        } else if !status.starts_with('#') {
+            repo_status.add(status);
+        }
+    });
+
+    Some(repo_status)
+}
+
+/// The arguments of `git status`, asking only for what the configuration shows
+fn status_args(config: &GitStatusConfig) -> Vec<&'static str> {
     let mut args = vec!["status", "--porcelain=2"];
 
     // for performance reasons, only pass flags if necessary...
@@ -238,19 +312,18 @@ fn get_repo_status(
     } else if !has_untracked {
         args.push("--ignore-submodules=untracked");
     }
+    args
+}
 
-    let status_output = repo.exec_git(context, &args)?;
-    let statuses = status_output.stdout.lines();
-
-    statuses.for_each(|status| {
-        if status.starts_with("# branch.ab ") {
-            repo_status.set_ahead_behind(status);
-        } else if !status.starts_with('#') {
-            repo_status.add(status);
-        }
-    });
-
-    Some(repo_status)
+/// Runs `git status` for the snapshot of `starship watch-git`, returning its arguments and output
+pub fn watched_status(
+    context: &Context,
+    repo: &context::Repo,
+) -> Option<(Vec<&'static str>, String)> {
+    let config = GitStatusConfig::try_load(context.config.get_module_config("git_status"));
+    let args = status_args(&config);
+    let output = repo.exec_git(context, &args)?;
+    Some((args, output.stdout))
 }
 
 fn get_stashed_count(repo: &context::Repo) -> Option<usize> {
@@ -279,6 +352,22 @@ fn get_stashed_count(repo: &context::Repo) -> Option<usize> {
     }
 }
 
+/// Counts the files tracked by Git LFS whose contents were not downloaded, e.g. after cloning
+/// with `GIT_LFS_SKIP_SMUDGE=1`
+fn get_lfs_missing_count(context: &Context, repo: &context::Repo) -> Option<usize> {
+    let output = repo.exec_git(context, &["lfs", "ls-files"])?;
+    Some(count_lfs_missing(&output.stdout))
+}
+
+/// `git lfs ls-files` prints `<oid> * <path>` for downloaded files and `<oid> - <path>` for
+/// files that are still pointers
+fn count_lfs_missing(ls_files: &str) -> usize {
+    ls_files
+        .lines()
+        .filter(|line| line.split(' ').nth(1) == Some("-"))
+        .count()
+}
+
 #[derive(Default, Debug, Copy, Clone)]
 struct RepoStatus {
     ahead: Option<usize>,
@@ -290,6 +379,7 @@ struct RepoStatus {
     staged: usize,
     typechanged: usize,
     untracked: usize,
+    submodules_dirty: usize,
 }
 
 impl RepoStatus {
@@ -332,7 +422,19 @@ impl RepoStatus {
         }
     }
 
+    /// Checks the `<sub>` field of a changed entry, e.g. `S.M.` for a submodule with
+    /// modifications or `N...` for a file
+    fn is_submodule_dirty(s: &str) -> bool {
+        s.split(' ')
+            .nth(2)
+            .and_then(|sub| sub.strip_prefix('S'))
+            .map_or(false, |changes| changes.chars().any(|c| c != '.'))
+    }
+
     fn add(&mut self, s: &str) {
+        if matches!(s.chars().next(), Some('1' | '2')) && Self::is_submodule_dirty(s) {
+            self.submodules_dirty += 1;
+        }
         match s.chars().next() {
             Some('1') => self.parse_normal_status(&s[2..4]),
             Some('2') => {
@@ -405,21 +507,15 @@ fn format_symbol(format_str: &str, config_path: &str, context: &Context) -> Opti
 #[cfg(target_os = "linux")]
 fn git_status_wsl(context: &Context, conf: &GitStatusConfig) -> Option<String> {
     use crate::utils::create_command;
-    use nix::sys::utsname::uname;
     use std::env;
     use std::ffi::OsString;
     use std::io::ErrorKind;
 
     let starship_exe = conf.windows_starship?;
 
-    // Ensure this is WSL
-    // This is lowercase in WSL1 and uppercase in WSL2, just skip the first letter
-    if !uname()
-        .ok()?
-        .release()
-        .to_string_lossy()
-        .contains("icrosoft")
-    {
+    // Git on the Linux file system of the distribution is faster than Git for Windows through
+    // the `\\wsl$` share
+    if !context.on_windows_drive() {
         return None;
     }
 
@@ -715,6 +811,28 @@ mod tests {
         repo_dir.close()
     }
 
+    #[test]
+    fn shows_changes_in_subdir_scope() -> io::Result<()> {
+        let repo_dir = fixture_repo(FixtureProvider::Git)?;
+        let package_dir = repo_dir.path().join("package");
+        fs::create_dir(&package_dir)?;
+
+        create_modified(repo_dir.path())?;
+        create_untracked(&package_dir)?;
+
+        let actual = ModuleRenderer::new("git_status")
+            .config(toml::toml! {
+                [git_status]
+                scope = "subdir"
+            })
+            .path(&package_dir)
+            .collect();
+        let expected = format_output("?");
+
+        assert_eq!(expected, actual);
+        repo_dir.close()
+    }
+
     #[test]
     fn shows_untracked_file_with_count() -> io::Result<()> {
         let repo_dir = fixture_repo(FixtureProvider::Git)?;
@@ -1173,6 +1291,35 @@ mod tests {
         repo_dir.close()
     }
 
+    #[test]
+    fn uses_status_of_watcher() -> io::Result<()> {
+        let repo_dir = fixture_repo(FixtureProvider::Git)?;
+        let git_dir = repo_dir.path().join(".git");
+
+        // A clean repository, where the watcher knows better
+        fs::write(git_dir.join("starship-watch"), "42")?;
+        fs::write(
+            git_dir.join("starship-status"),
+            "# starship watch-git: status --porcelain=2 --branch\n\
+             1 .M N... 100644 100644 100644 abcdef abcdef readme.md\n",
+        )?;
+        let actual = ModuleRenderer::new("git_status")
+            .path(repo_dir.path())
+            .collect();
+        assert_eq!(format_output("!"), actual);
+
+        // Snapshots of other arguments are not used
+        let actual = ModuleRenderer::new("git_status")
+            .config(toml::toml! {
+                [git_status]
+                untracked = ""
+            })
+            .path(repo_dir.path())
+            .collect();
+        assert_eq!(None, actual);
+        repo_dir.close()
+    }
+
     #[test]
     fn doesnt_generate_git_status_for_bare_repo() -> io::Result<()> {
         let repo_dir = fixture_repo(FixtureProvider::GitBare)?;
@@ -1188,6 +1335,35 @@ mod tests {
         repo_dir.close()
     }
 
+    #[test]
+    fn counts_dirty_submodules() {
+        let mut status = super::RepoStatus::default();
+        for line in [
+            "1 .M S.M. 160000 160000 160000 8a1f 8a1f vendor/lib",
+            "1 .M N... 100644 100644 100644 3b18 3b18 readme.md",
+            "1 M. SC.. 160000 160000 160000 8a1f 9c2e vendor/other",
+            "? S.M. untracked",
+        ] {
+            status.add(line);
+        }
+
+        assert_eq!(status.submodules_dirty, 2);
+        assert_eq!(status.modified, 2);
+        assert_eq!(status.untracked, 1);
+    }
+
+    #[test]
+    fn counts_missing_lfs_objects() {
+        let ls_files = "\
+4d7a214614 * assets/logo.png
+8c2e4f1a9b - assets/video.mp4
+0b1c2d3e4f - models/weights - final.bin
+";
+
+        assert_eq!(super::count_lfs_missing(ls_files), 2);
+        assert_eq!(super::count_lfs_missing(""), 0);
+    }
+
     fn ahead(repo_dir: &Path) -> io::Result<()> {
         File::create(repo_dir.join("readme.md"))?.sync_all()?;
 
@@ -32,8 +32,9 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             })
             .map(|variable| match variable {
                 "version" => {
-                    let gleam_version =
-                        parse_gleam_version(&context.exec_cmd("gleam", &["--version"])?.stdout)?;
+                    let gleam_version = parse_gleam_version(
+                        &context.exec_version_cmd("gleam", &["--version"])?.stdout,
+                    )?;
                     VersionFormatter::format_module_version(
                         module.get_name(),
                         &gleam_version,
@@ -1,14 +1,14 @@
+use super::utils::version::{self, VersionConstraint};
 use super::{Context, Module, ModuleConfig};
 
 use crate::configs::go::GoConfig;
 use crate::formatter::StringFormatter;
 use crate::formatter::VersionFormatter;
+use crate::utils;
 
 use once_cell::sync::Lazy;
-use regex::Regex;
-use semver::Version;
-use semver::VersionReq;
 use std::ops::Deref;
+use std::path::PathBuf;
 
 /// Creates a module with the current Go version
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
@@ -25,9 +25,29 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    let golang_version =
-        Lazy::new(|| parse_go_version(&context.exec_cmd("go", &["version"])?.stdout));
-    let mod_version = Lazy::new(|| get_go_mod_version(context));
+    let workspace = Lazy::new(|| get_go_work(context));
+    let directives = Lazy::new(|| {
+        let go_mod = || Some(parse_directives(&context.read_file_from_pwd("go.mod")?));
+        workspace
+            .as_ref()
+            .map(|workspace| workspace.directives.clone())
+            .or_else(go_mod)
+            .unwrap_or_default()
+    });
+    let mod_version = Lazy::new(|| get_directive(&directives, "go"));
+    let toolchain = Lazy::new(|| {
+        get_directive(&directives, "toolchain").filter(|toolchain| toolchain != "default")
+    });
+    let golang_version = Lazy::new(|| {
+        let installed = parse_go_version(&context.exec_version_cmd("go", &["version"])?.stdout)?;
+        Some(select_toolchain(
+            installed,
+            context.get_env("GOTOOLCHAIN").as_deref(),
+            toolchain.as_deref().or(mod_version.as_deref()),
+        ))
+    });
+    let satisfies =
+        Lazy::new(|| check_go_version(golang_version.as_deref(), mod_version.as_deref()));
 
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
@@ -37,13 +57,10 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             })
             .map_style(|variable| match variable {
                 "style" => {
-                    let in_mod_range =
-                        check_go_version(golang_version.as_deref(), mod_version.as_deref());
-
-                    if in_mod_range {
-                        Some(Ok(config.style))
-                    } else {
+                    if *satisfies == Some(false) {
                         Some(Ok(config.not_capable_style))
+                    } else {
+                        Some(Ok(config.style))
                     }
                 }
                 _ => None,
@@ -60,12 +77,15 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                     .map(Ok)
                 }
                 "mod_version" => {
-                    let in_mod_range =
-                        check_go_version(golang_version.as_deref(), mod_version.as_deref());
                     let mod_ver = mod_version.as_deref()?.to_string();
 
-                    (!in_mod_range).then_some(Ok(mod_ver))
+                    (*satisfies == Some(false)).then_some(Ok(mod_ver))
                 }
+                "satisfies" => satisfies.map(|satisfies| Ok(satisfies.to_string())),
+                "toolchain" => toolchain.deref().clone().map(Ok),
+                "workspace" => workspace
+                    .as_ref()
+                    .map(|workspace| Ok(workspace.modules.to_string())),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -99,30 +119,114 @@ fn parse_go_version(go_stdout: &str) -> Option<String> {
     Some(version.to_string())
 }
 
-fn get_go_mod_version(context: &Context) -> Option<String> {
-    let mod_str = context.read_file_from_pwd("go.mod")?;
-    let re = Regex::new(r"(?:go\s)(\d+(\.\d+)+)").unwrap();
+/// The `go.work` file of the workspace the current directory is in
+struct GoWork {
+    directives: Vec<(String, String)>,
+    /// The number of modules of the workspace, from its `use` directives
+    modules: usize,
+}
 
-    if let Some(cap) = re.captures(&mod_str) {
-        let mod_ver = cap.get(1)?.as_str();
-        Some(mod_ver.to_string())
-    } else {
-        None
+/// Finds `go.work` like the go command does, in `$GOWORK` or the closest parent directory,
+/// unless `GOWORK=off` turns off the workspace
+fn get_go_work(context: &Context) -> Option<GoWork> {
+    let path = match context.get_env("GOWORK").as_deref() {
+        Some("off") => return None,
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => context
+            .begin_ancestor_scan()
+            .set_files(&["go.work"])
+            .scan()?
+            .join("go.work"),
+    };
+    let directives = parse_directives(&utils::read_file(path).ok()?);
+    let modules = directives
+        .iter()
+        .filter(|(directive, _)| directive == "use")
+        .count();
+    Some(GoWork {
+        directives,
+        modules,
+    })
+}
+
+/// Parses the directives of `go.mod` or `go.work` into pairs of names and arguments, with the
+/// lines of blocks like `use ( ... )` as directives of their own
+fn parse_directives(contents: &str) -> Vec<(String, String)> {
+    let mut directives = Vec::new();
+    let mut block: Option<&str> = None;
+    for line in contents.lines() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        match block {
+            Some(_) if line == ")" => block = None,
+            Some(name) => directives.push((name.to_string(), line.to_string())),
+            None => {
+                let (name, argument) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+                match argument.trim() {
+                    "(" => block = Some(name),
+                    argument => directives.push((name.to_string(), argument.to_string())),
+                }
+            }
+        }
     }
+    directives
+}
+
+fn get_directive(directives: &[(String, String)], name: &str) -> Option<String> {
+    directives
+        .iter()
+        .find(|(directive, _)| directive == name)
+        .map(|(_, argument)| argument.clone())
 }
 
-fn check_go_version(go_version: Option<&str>, mod_version: Option<&str>) -> bool {
-    let (Some(go_version), Some(mod_version)) = (go_version, mod_version) else {
-        return true;
+/// The version of the toolchain the go command runs, as it switches from the installed version
+/// to the `toolchain` or `go` directive when those are newer, unless `$GOTOOLCHAIN` says otherwise
+///
+/// Go versions before 1.21 don't switch toolchains.
+fn select_toolchain(
+    installed: String,
+    gotoolchain: Option<&str>,
+    required: Option<&str>,
+) -> String {
+    let can_switch = VersionConstraint::Minimum("1.21".to_string())
+        .is_satisfied_by(&installed)
+        .unwrap_or(false);
+    if !can_switch {
+        return installed;
+    }
+
+    let gotoolchain = gotoolchain.unwrap_or("auto");
+    let (default, may_switch) = match gotoolchain.split_once('+') {
+        Some((default, _)) => (default, true),
+        None => (gotoolchain, false),
     };
-    let Ok(r) = VersionReq::parse(mod_version) else {
-        return true;
+    let default = match default {
+        "local" | "auto" | "path" => installed,
+        name => name.trim_start_matches("go").to_string(),
     };
-    let Ok(v) = Version::parse(go_version) else {
-        return true;
+    if !may_switch && !matches!(gotoolchain, "auto" | "path") {
+        return default;
+    }
+
+    let Some(required) = required.map(|required| required.trim_start_matches("go")) else {
+        return default;
     };
+    let is_new_enough = VersionConstraint::Minimum(required.to_string())
+        .is_satisfied_by(&default)
+        .unwrap_or(true);
+    if is_new_enough {
+        default
+    } else {
+        required.to_string()
+    }
+}
 
-    r.matches(&v)
+/// The `go` directive of `go.mod` is the oldest Go version the module builds with
+fn check_go_version(go_version: Option<&str>, mod_version: Option<&str>) -> Option<bool> {
+    let constraint = mod_version.map(|minimum| VersionConstraint::Minimum(minimum.to_string()));
+    version::satisfies(go_version, constraint.as_ref())
 }
 
 #[cfg(test)]
@@ -304,4 +408,76 @@ mod tests {
         assert_eq!(expected, actual);
         dir.close()
     }
+
+    #[test]
+    fn satisfies_go_mod_version() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join("go.mod"), "module test\n\ngo 1.12\n")?;
+
+        let actual = ModuleRenderer::new("golang")
+            .path(dir.path())
+            .config(toml::toml! {
+                [golang]
+                format = "$satisfies"
+            })
+            .collect();
+
+        assert_eq!(Some("true".to_string()), actual);
+        dir.close()
+    }
+
+    #[test]
+    fn workspace_with_modules() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("go.work"),
+            "go 1.12\n\nuse (\n\t./api // the server\n\t./cli\n)\n\nuse ./tools\n",
+        )?;
+        let module_dir = dir.path().join("api");
+        fs::create_dir(&module_dir)?;
+        fs::write(module_dir.join("go.mod"), "module api\n\ngo 1.16\n")?;
+
+        let actual = ModuleRenderer::new("golang")
+            .path(&module_dir)
+            .config(toml::toml! {
+                [golang]
+                format = "$version $workspace $satisfies"
+            })
+            .collect();
+
+        assert_eq!(Some("v1.12.1 3 true".to_string()), actual);
+        dir.close()
+    }
+
+    #[test]
+    fn test_parse_directives() {
+        let directives = parse_directives(
+            "module example.com/app\n\ngo 1.21\ntoolchain go1.22.1\n\nrequire (\n\tgolang.org/x/text v0.14.0 // indirect\n)\n",
+        );
+        assert_eq!(get_directive(&directives, "go").as_deref(), Some("1.21"));
+        assert_eq!(
+            get_directive(&directives, "toolchain").as_deref(),
+            Some("go1.22.1")
+        );
+        assert_eq!(
+            get_directive(&directives, "require").as_deref(),
+            Some("golang.org/x/text v0.14.0")
+        );
+    }
+
+    #[test]
+    fn test_select_toolchain() {
+        let select =
+            |gotoolchain, required| select_toolchain("1.21.5".to_string(), gotoolchain, required);
+        assert_eq!(select(None, Some("go1.22.1")), "1.22.1");
+        assert_eq!(select(None, Some("1.20")), "1.21.5");
+        assert_eq!(select(Some("local"), Some("go1.22.1")), "1.21.5");
+        assert_eq!(select(Some("go1.23.0"), Some("go1.22.1")), "1.23.0");
+        assert_eq!(select(Some("go1.21.0+auto"), Some("1.21.2")), "1.21.2");
+        // Older Go versions don't know about toolchains
+        assert_eq!(
+            select_toolchain("1.20.3".to_string(), None, Some("go1.22.1")),
+            "1.20.3"
+        );
+    }
 }
@@ -6,6 +6,14 @@ use crate::{
     module::Module,
     utils,
 };
+use once_cell::sync::Lazy;
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How long the check for a running daemon is reused, as it lists the daemon logs
+const DAEMON_CACHE_TTL: Duration = Duration::from_secs(30);
 
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("gradle");
@@ -21,6 +29,11 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
+    let gradle_version = Lazy::new(|| {
+        let properties = get_wrapper_properties_file(context, config.recursive)?;
+        parse_gradle_version_from_properties(&properties)
+    });
+
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
             .map_meta(|var, _| match var {
@@ -33,17 +46,19 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             })
             .map(|variable| match variable {
                 "version" => {
-                    let gradle_version = {
-                        let properties = get_wrapper_properties_file(context, config.recursive)?;
-                        parse_gradle_version_from_properties(&properties)?
-                    };
+                    let gradle_version = gradle_version.deref().as_ref()?;
                     VersionFormatter::format_module_version(
                         module.get_name(),
-                        &gradle_version,
+                        gradle_version,
                         config.version_format,
                     )
                     .map(Ok)
                 }
+                "daemon" => {
+                    let gradle_version = gradle_version.deref().as_deref()?;
+                    is_daemon_running(context, gradle_version)
+                        .then(|| Ok(config.daemon_symbol.to_string()))
+                }
                 _ => None,
             })
             .parse(None, Some(context))
@@ -69,17 +84,109 @@ fn parse_gradle_version_from_properties(wrapper_properties: &str) -> Option<Stri
         zipStoreBase=GRADLE_USER_HOME
         zipStorePath=wrapper/dists
     */
+    parse_wrapper_version(wrapper_properties, "gradle-")
+}
+
+/// Parses the version from the `distributionUrl` of the properties of the Gradle or Maven
+/// wrapper, whose archives are named like `<prefix><version>-bin.zip`
+pub(super) fn parse_wrapper_version(wrapper_properties: &str, prefix: &str) -> Option<String> {
     let version = wrapper_properties
         .lines()
         .find(|line| line.starts_with("distributionUrl="))?
         .rsplit_once('/')?
         .1
-        .strip_prefix("gradle-")?
+        .strip_prefix(prefix)?
         .rsplit_once('-')?
         .0;
     Some(version.to_string())
 }
 
+/// Whether a Gradle daemon of the version is running, so the next build starts right away
+///
+/// Daemons keep a log named after their process id in `$GRADLE_USER_HOME/daemon/<version>`.
+fn is_daemon_running(context: &Context, gradle_version: &str) -> bool {
+    let cache = context
+        .get_cache_dir()
+        .map(|dir| dir.join("gradle").join(format!("daemon-{gradle_version}")));
+    if let Some(cached) = cache.as_deref().and_then(read_daemon_cache) {
+        return cached;
+    }
+
+    let is_running = gradle_user_home(context).map_or(false, |home| {
+        let dir = home.join("daemon").join(gradle_version);
+        fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name();
+                let pid = name
+                    .to_str()?
+                    .strip_prefix("daemon-")?
+                    .strip_suffix(".out.log")?;
+                pid.parse().ok()
+            })
+            .any(is_process_running)
+    });
+
+    if let Some(cache) = cache {
+        let written = cache
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(&cache, if is_running { "1" } else { "0" }));
+        if let Err(error) = written {
+            log::debug!("Unable to cache the state of the Gradle daemon: {error}");
+        }
+    }
+    is_running
+}
+
+fn read_daemon_cache(file: &Path) -> Option<bool> {
+    let age = fs::metadata(file).ok()?.modified().ok()?.elapsed().ok()?;
+    if age > DAEMON_CACHE_TTL {
+        return None;
+    }
+    Some(fs::read_to_string(file).ok()? == "1")
+}
+
+fn gradle_user_home(context: &Context) -> Option<PathBuf> {
+    match context.get_env("GRADLE_USER_HOME") {
+        Some(home) => Some(PathBuf::from(home)),
+        None => Some(context.get_home()?.join(".gradle")),
+    }
+}
+
+#[cfg(unix)]
+fn is_process_running(pid: i32) -> bool {
+    use nix::errno::Errno;
+    use nix::sys::signal::kill;
+    use nix::unistd::Pid;
+
+    // Sending no signal only checks whether the process exists
+    matches!(kill(Pid::from_raw(pid), None), Ok(()) | Err(Errno::EPERM))
+}
+
+#[cfg(windows)]
+fn is_process_running(pid: i32) -> bool {
+    use windows::Win32::Foundation::{CloseHandle, STILL_ACTIVE};
+    use windows::Win32::System::Threading::{
+        GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    let Ok(process) =
+        (unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid as u32) })
+    else {
+        return false;
+    };
+    let mut exit_code = 0;
+    let is_running = unsafe { GetExitCodeProcess(process, &mut exit_code) }.is_ok()
+        && exit_code == STILL_ACTIVE.0 as u32;
+    if let Err(e) = unsafe { CloseHandle(process) } {
+        log::debug!("CloseHandle failed: {e:?}");
+    }
+    is_running
+}
+
 /// Tries to find the gradle-wrapper.properties file.
 fn get_wrapper_properties_file(context: &Context, recursive: bool) -> Option<String> {
     let mut properties = None;
@@ -240,4 +347,32 @@ zipStorePath=wrapper/dists
             Some("7.5.1-20220729132837+0000".to_string())
         );
     }
+
+    #[test]
+    fn running_daemon() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let properties = dir.path().join("gradle/wrapper/gradle-wrapper.properties");
+        fs::create_dir_all(properties.parent().unwrap())?;
+        fs::write(
+            properties,
+            "distributionUrl=https\\://services.gradle.org/distributions/gradle-8.5-bin.zip",
+        )?;
+        let gradle_home = tempfile::tempdir()?;
+        let daemon_dir = gradle_home.path().join("daemon").join("8.5");
+        fs::create_dir_all(&daemon_dir)?;
+        File::create(daemon_dir.join(format!("daemon-{}.out.log", std::process::id())))?;
+
+        let actual = ModuleRenderer::new("gradle")
+            .path(dir.path())
+            .env("GRADLE_USER_HOME", gradle_home.path().to_string_lossy())
+            .env("STARSHIP_CACHE", gradle_home.path().to_string_lossy())
+            .config(toml::toml! {
+                [gradle]
+                format = "$version( $daemon)"
+            })
+            .collect();
+        assert_eq!(Some("v8.5 ⚡".to_string()), actual);
+        dir.close()?;
+        gradle_home.close()
+    }
 }
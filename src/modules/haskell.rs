@@ -2,6 +2,8 @@ use super::{Context, Module, ModuleConfig};
 
 use crate::configs::haskell::HaskellConfig;
 use crate::formatter::StringFormatter;
+use std::fs;
+use std::path::PathBuf;
 
 /// Creates a module with the current Haskell version
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
@@ -33,6 +35,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 "version" => get_version(context).map(Ok),
                 "ghc_version" => get_ghc_version(context).map(Ok),
                 "snapshot" => get_snapshot(context).map(Ok),
+                "tool" => get_build_tool(context).map(|tool| Ok(tool.to_string())),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -49,10 +52,15 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// The GHC version cabal builds with, the `with-compiler` of the project or else the GHC ghcup
+/// or the system selected
 fn get_ghc_version(context: &Context) -> Option<String> {
+    if let Some(compiler) = get_cabal_compiler(context).or_else(|| get_ghcup_version(context)) {
+        return Some(compiler);
+    }
     Some(
         context
-            .exec_cmd("ghc", &["--numeric-version"])?
+            .exec_version_cmd("ghc", &["--numeric-version"])?
             .stdout
             .trim()
             .to_string(),
@@ -73,6 +81,53 @@ fn get_snapshot(context: &Context) -> Option<String> {
     Some(version.to_string())
 }
 
+/// The compiler of `with-compiler: ghc-9.4.8`, in `cabal.project.local` or `cabal.project`
+fn get_cabal_compiler(context: &Context) -> Option<String> {
+    ["cabal.project.local", "cabal.project"]
+        .iter()
+        .filter_map(|file| context.read_file_from_pwd(file))
+        .find_map(|contents| parse_with_compiler(&contents))
+}
+
+fn parse_with_compiler(cabal_project: &str) -> Option<String> {
+    let compiler = cabal_project
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("with-compiler:"))?
+        .trim();
+    // The compiler is a path or a name like `ghc-9.4.8`, whose version is all that matters here
+    let name = compiler.rsplit(['/', '\\']).next()?;
+    let version = name.strip_prefix("ghc-")?;
+    Some(version.trim_end_matches(".exe").to_string())
+}
+
+/// The version `ghcup set ghc` selected, from the link `~/.ghcup/bin/ghc` points to, which saves
+/// running `ghc`
+///
+/// Only used while the link is the `ghc` in `PATH`, and not e.g. one of Nix or the system.
+fn get_ghcup_version(context: &Context) -> Option<String> {
+    let base = context
+        .get_env("GHCUP_INSTALL_BASE_PREFIX")
+        .map(PathBuf::from)
+        .or_else(|| context.get_home())?;
+    let link = base.join(".ghcup").join("bin").join("ghc");
+    let ghc = which::which_in("ghc", context.get_env_os("PATH"), &context.current_dir).ok()?;
+    if ghc != link {
+        return None;
+    }
+    let target = fs::read_link(link).ok()?;
+    let version = target.file_name()?.to_str()?.strip_prefix("ghc-")?;
+    Some(version.to_string())
+}
+
+/// The build tool of the project: `stack` with a `stack.yaml`, otherwise `cabal`
+fn get_build_tool(context: &Context) -> Option<&'static str> {
+    if is_stack_project(context) {
+        return Some("stack");
+    }
+    let dir = context.dir_contents().ok()?;
+    (dir.has_file_name("cabal.project") || dir.has_extension("cabal")).then_some("cabal")
+}
+
 fn get_version(context: &Context) -> Option<String> {
     get_snapshot(context).or_else(|| get_ghc_version(context))
 }
@@ -86,6 +141,7 @@ fn is_stack_project(context: &Context) -> bool {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::test::ModuleRenderer;
     use nu_ansi_term::Color;
     use std::fs::File;
@@ -147,6 +203,107 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn cabal_project_with_compiler() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(
+            dir.path().join("cabal.project"),
+            "packages: .\nwith-compiler: ghc-9.4.8\n",
+        )?;
+        let actual = ModuleRenderer::new("haskell")
+            .path(dir.path())
+            .config(toml::toml! {
+                [haskell]
+                format = "$tool $version"
+            })
+            .collect();
+        assert_eq!(Some("cabal 9.4.8".to_string()), actual);
+        dir.close()
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn ghcup_selected_version() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("Main.hs"))?.sync_all()?;
+        let ghcup = tempfile::tempdir()?;
+        let bin = ghcup.path().join(".ghcup").join("bin");
+        create_executable(&bin.join("ghc-9.6.4"))?;
+        std::os::unix::fs::symlink("ghc-9.6.4", bin.join("ghc"))?;
+        let actual = ModuleRenderer::new("haskell")
+            .path(dir.path())
+            .env("GHCUP_INSTALL_BASE_PREFIX", ghcup.path().to_string_lossy())
+            .env("PATH", bin.to_string_lossy())
+            .config(toml::toml! {
+                [haskell]
+                format = "$ghc_version"
+            })
+            .collect();
+        assert_eq!(Some("9.6.4".to_string()), actual);
+        ghcup.close()?;
+        dir.close()
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn ghcup_link_not_in_path() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("Main.hs"))?.sync_all()?;
+        let ghcup = tempfile::tempdir()?;
+        let bin = ghcup.path().join(".ghcup").join("bin");
+        create_executable(&bin.join("ghc-9.6.4"))?;
+        std::os::unix::fs::symlink("ghc-9.6.4", bin.join("ghc"))?;
+        let nix_bin = ghcup.path().join("nix").join("bin");
+        create_executable(&nix_bin.join("ghc"))?;
+        let path = std::env::join_paths([&nix_bin, &bin]).unwrap();
+        let actual = ModuleRenderer::new("haskell")
+            .path(dir.path())
+            .env("GHCUP_INSTALL_BASE_PREFIX", ghcup.path().to_string_lossy())
+            .env("PATH", path.to_string_lossy())
+            .config(toml::toml! {
+                [haskell]
+                format = "$ghc_version"
+            })
+            .collect();
+        assert_eq!(Some("9.2.1".to_string()), actual);
+        ghcup.close()?;
+        dir.close()
+    }
+
+    #[cfg(unix)]
+    fn create_executable(path: &std::path::Path) -> io::Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        std::fs::write(path, "")?;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+    }
+
+    #[test]
+    fn stack_tool() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join("stack.yaml"), "resolver: lts-22.6\n")?;
+        let actual = ModuleRenderer::new("haskell")
+            .path(dir.path())
+            .config(toml::toml! {
+                [haskell]
+                format = "$tool $snapshot"
+            })
+            .collect();
+        assert_eq!(Some("stack lts-22.6".to_string()), actual);
+        dir.close()
+    }
+
+    #[test]
+    fn test_parse_with_compiler() {
+        assert_eq!(
+            parse_with_compiler("with-compiler: /opt/ghc/bin/ghc-9.6.3\n"),
+            Some("9.6.3".to_string())
+        );
+        assert_eq!(parse_with_compiler("with-compiler: ghc\n"), None);
+        assert_eq!(parse_with_compiler("packages: .\n"), None);
+    }
+
     static REANIMATE_STACK_YAML: &str = r"
 resolver: lts-14.27
 
@@ -62,7 +62,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
 fn get_haxe_version(context: &Context) -> Option<String> {
     get_haxerc_version(context).or_else(|| {
-        let cmd_output = context.exec_cmd("haxe", &["--version"])?;
+        let cmd_output = context.exec_version_cmd("haxe", &["--version"])?;
         parse_haxe_version(cmd_output.stdout.as_str())
     })
 }
@@ -3,8 +3,17 @@ use super::{Context, Module, ModuleConfig};
 use crate::configs::helm::HelmConfig;
 use crate::formatter::StringFormatter;
 use crate::formatter::VersionFormatter;
+use yaml_rust2::{Yaml, YamlLoader};
+
+/// The chart deployed from the current directory
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ChartInfo {
+    chart: Option<String>,
+    chart_version: Option<String>,
+    namespace: Option<String>,
+}
 
-/// Creates a module with the current Helm version
+/// Creates a module with the current Helm version and chart
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("helm");
     let config = HelmConfig::try_load(module.config);
@@ -20,6 +29,11 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
+    let chart_info = get_chart_info(context);
+    let namespace = chart_info
+        .namespace
+        .or_else(|| context.get_env("HELM_NAMESPACE"));
+
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
             .map_meta(|var, _| match var {
@@ -34,7 +48,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 "version" => {
                     let helm_version = parse_helm_version(
                         &context
-                            .exec_cmd("helm", &["version", "--short", "--client"])?
+                            .exec_version_cmd("helm", &["version", "--short", "--client"])?
                             .stdout,
                     )?;
                     VersionFormatter::format_module_version(
@@ -44,6 +58,9 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                     )
                     .map(Ok)
                 }
+                "chart" => chart_info.chart.as_deref().map(Ok),
+                "chart_version" => chart_info.chart_version.as_deref().map(Ok),
+                "namespace" => namespace.as_deref().map(Ok),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -60,6 +77,44 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// Reads the chart from `Chart.yaml`, or the first release of `helmfile.yaml`
+fn get_chart_info(context: &Context) -> ChartInfo {
+    if let Some(contents) = context.read_file_from_pwd("Chart.yaml") {
+        return parse_chart_yaml(&contents).unwrap_or_default();
+    }
+
+    context
+        .read_file_from_pwd("helmfile.yaml")
+        .and_then(|contents| parse_helmfile(&contents))
+        .unwrap_or_default()
+}
+
+fn parse_chart_yaml(contents: &str) -> Option<ChartInfo> {
+    let yaml = YamlLoader::load_from_str(contents).ok()?;
+    let chart = yaml.first()?;
+
+    Some(ChartInfo {
+        chart: chart["name"].as_str().map(ToString::to_string),
+        chart_version: chart["version"].as_str().map(ToString::to_string),
+        namespace: None,
+    })
+}
+
+fn parse_helmfile(contents: &str) -> Option<ChartInfo> {
+    // helmfiles can consist of several documents, only one of which lists the releases
+    let documents = YamlLoader::load_from_str(contents).ok()?;
+    let release = documents
+        .iter()
+        .find_map(|document| document["releases"].as_vec()?.first())?;
+    let string = |value: &Yaml| value.as_str().map(ToString::to_string);
+
+    Some(ChartInfo {
+        chart: string(&release["chart"]),
+        chart_version: string(&release["version"]),
+        namespace: string(&release["namespace"]),
+    })
+}
+
 fn parse_helm_version(helm_stdout: &str) -> Option<String> {
     // `helm version --short --client` output looks like this:
     // v3.1.1+gafe7058
@@ -84,7 +139,7 @@ mod tests {
     use super::*;
     use crate::test::ModuleRenderer;
     use nu_ansi_term::Color;
-    use std::fs::File;
+    use std::fs::{self, File};
     use std::io;
 
     #[test]
@@ -122,6 +177,67 @@ mod tests {
         dir.close()
     }
 
+    #[test]
+    fn folder_with_chart_metadata() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("Chart.yaml"),
+            "apiVersion: v2\nname: api\nversion: 1.4.2\n",
+        )?;
+
+        let actual = ModuleRenderer::new("helm")
+            .path(dir.path())
+            .config(toml::toml! {
+                [helm]
+                format = "via [$symbol$chart@$chart_version( \\($namespace\\))]($style)"
+            })
+            .collect();
+
+        let expected = Some(format!("via {}", Color::White.bold().paint("⎈ api@1.4.2")));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_helmfile_release() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("helmfile.yaml"),
+            "repositories:\n  - name: bitnami\n    url: https://charts.bitnami.com/bitnami\n---\nreleases:\n  - name: web\n    namespace: frontend\n    chart: bitnami/nginx\n    version: 15.0.0\n",
+        )?;
+
+        let actual = ModuleRenderer::new("helm")
+            .path(dir.path())
+            .config(toml::toml! {
+                [helm]
+                format = "$chart $chart_version $namespace"
+            })
+            .collect();
+
+        let expected = Some("bitnami/nginx 15.0.0 frontend".to_string());
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn namespace_from_env() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join("Chart.yaml"), "name: api\n")?;
+
+        let actual = ModuleRenderer::new("helm")
+            .path(dir.path())
+            .env("HELM_NAMESPACE", "staging")
+            .config(toml::toml! {
+                [helm]
+                format = "$chart:$namespace"
+            })
+            .collect();
+
+        let expected = Some("api:staging".to_string());
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
     #[test]
     fn test_parse_helm_version() {
         let helm_2 = "Client: v2.16.9+g8ad7037";
@@ -32,7 +32,8 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     };
 
     let repo_root = context.begin_ancestor_scan().set_folders(&[".hg"]).scan()?;
-    let branch_name = get_hg_current_bookmark(repo_root).unwrap_or_else(|_| {
+    let bookmark = get_hg_current_bookmark(repo_root).ok();
+    let branch_name = bookmark.clone().unwrap_or_else(|| {
         get_hg_branch_name(repo_root).unwrap_or_else(|_| String::from("default"))
     });
 
@@ -56,6 +57,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             .map(|variable| match variable {
                 "branch" => Some(Ok(branch_graphemes.as_str())),
                 "topic" => Some(Ok(topic_graphemes.as_str())),
+                "bookmark" => bookmark.as_deref().map(Ok),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -177,6 +179,31 @@ mod tests {
         tempdir.close()
     }
 
+    #[test]
+    fn test_hg_bookmark_variable() -> io::Result<()> {
+        let repo_dir = tempfile::tempdir()?;
+        let hg_dir = repo_dir.path().join(".hg");
+        fs::create_dir(&hg_dir)?;
+        fs::write(hg_dir.join("branch"), "stable\n")?;
+
+        let render = || {
+            ModuleRenderer::new("hg_branch")
+                .path(repo_dir.path())
+                .config(toml::toml! {
+                    [hg_branch]
+                    format = "$branch( \\($bookmark\\))"
+                    disabled = false
+                })
+                .collect()
+        };
+
+        assert_eq!(Some(String::from("stable")), render());
+
+        fs::write(hg_dir.join("bookmarks.current"), "feature")?;
+        assert_eq!(Some(String::from("feature (feature)")), render());
+        repo_dir.close()
+    }
+
     #[test]
     #[ignore]
     fn test_hg_topic() -> io::Result<()> {
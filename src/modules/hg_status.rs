@@ -0,0 +1,323 @@
+use once_cell::sync::OnceCell;
+
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::hg_status::HgStatusConfig;
+use crate::formatter::StringFormatter;
+use crate::segment::Segment;
+
+const ALL_STATUS_FORMAT: &str = "$conflicted$deleted$modified$added$untracked";
+
+/// Creates a module with the status of the Mercurial repo in the current directory
+///
+/// By default, the following symbols will be used to represent the repo's status:
+///   - `=` – There are unresolved merge conflicts
+///   - `⇡` – There are draft changesets that have not been pushed
+///   - `✘` – A file was removed with `hg remove` or is missing from the working directory
+///   - `!` — There are file modifications in the working directory
+///   - `+` — A new file has been added with `hg add`
+///   - `?` — There are untracked files in the working directory
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("hg_status");
+    let config = HgStatusConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled {
+        return None;
+    };
+
+    context.begin_ancestor_scan().set_folders(&[".hg"]).scan()?;
+
+    let info = HgStatusInfo {
+        context,
+        status: OnceCell::new(),
+        conflicted: OnceCell::new(),
+        phase: OnceCell::new(),
+        ahead: OnceCell::new(),
+    };
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "all_status" => Some(ALL_STATUS_FORMAT),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map_variables_to_segments(|variable| {
+                let segments = match variable {
+                    "conflicted" => info.get_conflicted().and_then(|count| {
+                        format_count(config.conflicted, "hg_status.conflicted", context, count)
+                    }),
+                    "deleted" => info.get_status().and_then(|status| {
+                        format_count(config.deleted, "hg_status.deleted", context, status.deleted)
+                    }),
+                    "modified" => info.get_status().and_then(|status| {
+                        let count = status.modified;
+                        format_count(config.modified, "hg_status.modified", context, count)
+                    }),
+                    "added" => info.get_status().and_then(|status| {
+                        format_count(config.added, "hg_status.added", context, status.added)
+                    }),
+                    "untracked" => info.get_status().and_then(|status| {
+                        let count = status.untracked;
+                        format_count(config.untracked, "hg_status.untracked", context, count)
+                    }),
+                    "ahead" => info.get_ahead().and_then(|count| {
+                        format_count(config.ahead, "hg_status.ahead", context, count)
+                    }),
+                    "phase" => info
+                        .get_phase()
+                        .map(|phase| Segment::from_text(None, phase)),
+                    _ => None,
+                };
+                segments.map(Ok)
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) if segments.is_empty() => return None,
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `hg_status`:\n{}", error);
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+/// Runs the `hg` commands a variable needs when it is first used, since each one has to start
+/// a Python interpreter
+struct HgStatusInfo<'a> {
+    context: &'a Context<'a>,
+    status: OnceCell<Option<RepoStatus>>,
+    conflicted: OnceCell<Option<usize>>,
+    phase: OnceCell<Option<String>>,
+    ahead: OnceCell<Option<usize>>,
+}
+
+impl<'a> HgStatusInfo<'a> {
+    fn get_status(&self) -> Option<&RepoStatus> {
+        self.status
+            .get_or_init(|| {
+                let output = self.context.exec_cmd("hg", &["status"])?;
+                Some(RepoStatus::parse(&output.stdout))
+            })
+            .as_ref()
+    }
+
+    fn get_conflicted(&self) -> Option<usize> {
+        *self.conflicted.get_or_init(|| {
+            let output = self.context.exec_cmd("hg", &["resolve", "--list"])?;
+            Some(
+                output
+                    .stdout
+                    .lines()
+                    .filter(|line| line.starts_with("U "))
+                    .count(),
+            )
+        })
+    }
+
+    fn get_phase(&self) -> Option<String> {
+        self.phase
+            .get_or_init(|| {
+                let output = self
+                    .context
+                    .exec_cmd("hg", &["log", "-r", ".", "-T", "{phase}"])?;
+                let phase = output.stdout.trim();
+                (!phase.is_empty()).then(|| phase.to_string())
+            })
+            .clone()
+    }
+
+    /// Counts the unpublished changesets the working directory is based on, which Mercurial
+    /// marks as draft until they are pushed to a publishing repository
+    fn get_ahead(&self) -> Option<usize> {
+        *self.ahead.get_or_init(|| {
+            let output = self
+                .context
+                .exec_cmd("hg", &["log", "-r", "draft() and ::.", "-T", "x"])?;
+            Some(output.stdout.trim().len())
+        })
+    }
+}
+
+/// The number of files in each state reported by `hg status`
+#[derive(Debug, Default, PartialEq, Eq)]
+struct RepoStatus {
+    modified: usize,
+    added: usize,
+    deleted: usize,
+    untracked: usize,
+}
+
+impl RepoStatus {
+    fn parse(status: &str) -> Self {
+        let mut repo_status = Self::default();
+        for line in status.lines() {
+            match line.chars().next() {
+                Some('M') => repo_status.modified += 1,
+                Some('A') => repo_status.added += 1,
+                // Removed with `hg remove` or deleted without it
+                Some('R' | '!') => repo_status.deleted += 1,
+                Some('?') => repo_status.untracked += 1,
+                _ => {}
+            }
+        }
+        repo_status
+    }
+}
+
+fn format_count(
+    format_str: &str,
+    config_path: &str,
+    context: &Context,
+    count: usize,
+) -> Option<Vec<Segment>> {
+    if count == 0 {
+        return None;
+    }
+
+    match StringFormatter::new(format_str) {
+        Ok(formatter) => formatter
+            .map(|variable| match variable {
+                "count" => Some(Ok(count.to_string())),
+                _ => None,
+            })
+            .parse(None, Some(context))
+            .ok(),
+        Err(_) => {
+            log::warn!("Error parsing format string `{}`", config_path);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_ansi_term::Color;
+    use std::fs;
+    use std::io;
+
+    use super::RepoStatus;
+    use crate::test::ModuleRenderer;
+    use crate::utils::CommandOutput;
+
+    fn output(stdout: &str) -> Option<CommandOutput> {
+        Some(CommandOutput {
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+        })
+    }
+
+    #[test]
+    fn parse_status() {
+        let status = "M src/main.rs\nM readme.md\nA new.rs\nR old.rs\n! gone.rs\n? scratch.txt\n";
+
+        assert_eq!(
+            RepoStatus::parse(status),
+            RepoStatus {
+                modified: 2,
+                added: 1,
+                deleted: 2,
+                untracked: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn disabled_per_default() -> io::Result<()> {
+        let repo_dir = tempfile::tempdir()?;
+        fs::create_dir(repo_dir.path().join(".hg"))?;
+
+        let actual = ModuleRenderer::new("hg_status")
+            .path(repo_dir.path())
+            .collect();
+
+        assert_eq!(None, actual);
+        repo_dir.close()
+    }
+
+    #[test]
+    fn shows_nothing_outside_of_repo() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let actual = ModuleRenderer::new("hg_status")
+            .path(dir.path())
+            .config(toml::toml! {
+                [hg_status]
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(None, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn shows_status() -> io::Result<()> {
+        let repo_dir = tempfile::tempdir()?;
+        fs::create_dir(repo_dir.path().join(".hg"))?;
+
+        let actual = ModuleRenderer::new("hg_status")
+            .path(repo_dir.path())
+            .config(toml::toml! {
+                [hg_status]
+                disabled = false
+                ahead = "⇡$count"
+            })
+            .cmd("hg status", output("M readme.md\n? scratch.txt\n"))
+            .cmd("hg resolve --list", output("R merged.rs\n"))
+            .cmd("hg log -r draft() and ::. -T x", output("xx"))
+            .collect();
+        let expected = Some(format!("{} ", Color::Red.bold().paint("[!?⇡2]")));
+
+        assert_eq!(expected, actual);
+        repo_dir.close()
+    }
+
+    #[test]
+    fn shows_phase() -> io::Result<()> {
+        let repo_dir = tempfile::tempdir()?;
+        fs::create_dir(repo_dir.path().join(".hg"))?;
+
+        let actual = ModuleRenderer::new("hg_status")
+            .path(repo_dir.path())
+            .config(toml::toml! {
+                [hg_status]
+                disabled = false
+                format = "$phase"
+            })
+            .cmd("hg log -r . -T {phase}", output("draft"))
+            .collect();
+
+        assert_eq!(Some("draft".to_string()), actual);
+        repo_dir.close()
+    }
+
+    #[test]
+    fn clean_repo() -> io::Result<()> {
+        let repo_dir = tempfile::tempdir()?;
+        fs::create_dir(repo_dir.path().join(".hg"))?;
+
+        let actual = ModuleRenderer::new("hg_status")
+            .path(repo_dir.path())
+            .config(toml::toml! {
+                [hg_status]
+                disabled = false
+            })
+            .cmd("hg status", output(""))
+            .cmd("hg resolve --list", output(""))
+            .cmd("hg log -r draft() and ::. -T x", output(""))
+            .collect();
+
+        assert_eq!(None, actual);
+        repo_dir.close()
+    }
+}
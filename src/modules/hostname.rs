@@ -9,17 +9,15 @@ use crate::formatter::StringFormatter;
 ///
 /// Will display the hostname if all of the following criteria are met:
 ///     - `hostname.disabled` is absent or false
-///     - `hostname.ssh_only` is false OR the user is currently connected as an SSH session (`$SSH_CONNECTION`)
+///     - `hostname.ssh_only` is false OR the user is connected remotely (ssh, mosh or a web terminal)
 ///     - `hostname.ssh_only` is false AND `hostname.detect_env_vars` is either empty or contains a defined environment variable
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("hostname");
     let config: HostnameConfig = HostnameConfig::try_load(module.config);
 
-    let ssh_connection = context.get_env("SSH_CONNECTION");
+    let is_remote = context.session_type().is_remote();
 
-    if (config.ssh_only && ssh_connection.is_none())
-        || !context.detect_env_vars(&config.detect_env_vars)
-    {
+    if (config.ssh_only && !is_remote) || !context.detect_env_vars(&config.detect_env_vars) {
         return None;
     }
 
@@ -53,7 +51,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         formatter
             .map_meta(|var, _| match var {
                 "ssh_symbol" => {
-                    if ssh_connection.is_some() {
+                    if is_remote {
                         Some(config.ssh_symbol)
                     } else {
                         None
@@ -62,6 +60,9 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map_style(|variable| match variable {
+                "style" if is_remote && !config.remote_style.is_empty() => {
+                    Some(Ok(config.remote_style))
+                }
                 "style" => Some(Ok(config.style)),
                 _ => None,
             })
@@ -230,6 +231,28 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn remote_style() {
+        let hostname = get_hostname!();
+        let actual = ModuleRenderer::new("hostname")
+            .config(toml::toml! {
+                [hostname]
+                ssh_only = true
+                trim_at = ""
+                remote_style = "bold yellow"
+            })
+            .env("MOSH_SERVER_PID", "42")
+            .collect();
+        let expected = Some(format!(
+            "{} in ",
+            Color::Yellow
+                .bold()
+                .paint("🌐 ".to_owned() + hostname.as_str())
+        ));
+
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn no_trim_at() {
         let hostname = get_hostname!();
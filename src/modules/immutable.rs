@@ -0,0 +1,289 @@
+use std::path::{Path, PathBuf};
+
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::immutable::ImmutableConfig;
+use crate::formatter::StringFormatter;
+use crate::utils::{context_path, read_file};
+
+/// Why edits in the current directory fail or do not persist
+#[derive(Debug, PartialEq, Eq)]
+enum Immutability {
+    NixStore,
+    Ostree,
+    Overlay,
+    ReadOnly,
+}
+
+/// Creates a module warning that the current directory is immutable
+///
+/// Edits inside the Nix store, the `/usr` tree of an ostree system or a read-only mount
+/// silently fail or disappear, so these are rendered as a distinct segment.
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("immutable");
+    let config: ImmutableConfig = ImmutableConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled {
+        return None;
+    };
+
+    let kind = match detect(context, &context.current_dir)? {
+        Immutability::NixStore => config.nix_store_msg,
+        Immutability::Ostree => config.ostree_msg,
+        Immutability::Overlay => config.overlay_msg,
+        Immutability::ReadOnly => config.read_only_msg,
+    };
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "kind" => Some(Ok(kind)),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `immutable`:\n{}", error);
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+fn detect(context: &Context, dir: &Path) -> Option<Immutability> {
+    let nix_store = context
+        .get_env("NIX_STORE_DIR")
+        .map_or_else(|| PathBuf::from("/nix/store"), PathBuf::from);
+    if dir.starts_with(nix_store) {
+        return Some(Immutability::NixStore);
+    }
+
+    // ostree mounts `/usr` read-only, changes are only possible through a new deployment
+    if dir.starts_with("/usr") && context_path(context, "/run/ostree-booted").exists() {
+        return Some(Immutability::Ostree);
+    }
+
+    let mountinfo = read_file(context_path(context, "/proc/self/mountinfo")).ok()?;
+    let mount = find_mount(&mountinfo, dir)?;
+    if !mount.options.split(',').any(|option| option == "ro") {
+        None
+    } else if mount.fs_type == "overlay" {
+        Some(Immutability::Overlay)
+    } else {
+        Some(Immutability::ReadOnly)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Mount<'a> {
+    mount_point: PathBuf,
+    options: &'a str,
+    fs_type: &'a str,
+}
+
+/// Finds the mount containing `dir` in the contents of `/proc/self/mountinfo`
+///
+/// Each line reads `id parent major:minor root mount_point options [optional...] - fs_type ...`.
+fn find_mount<'a>(mountinfo: &'a str, dir: &Path) -> Option<Mount<'a>> {
+    mountinfo
+        .lines()
+        .filter_map(|line| {
+            let (fields, fs_fields) = line.split_once(" - ")?;
+            let mut fields = fields.split(' ').skip(4);
+            let mount_point = PathBuf::from(unescape_octal(fields.next()?));
+            let options = fields.next()?;
+            let fs_type = fs_fields.split(' ').next()?;
+            Some(Mount {
+                mount_point,
+                options,
+                fs_type,
+            })
+        })
+        .filter(|mount| dir.starts_with(&mount.mount_point))
+        // The deepest mount point wins. Of several mounts on the same mount point the last one
+        // is visible, which is also the one `max_by_key` returns.
+        .max_by_key(|mount| mount.mount_point.components().count())
+}
+
+/// Mount points escape spaces, tabs, newlines and backslashes as octal, e.g. `\040`
+fn unescape_octal(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(idx) = rest.find('\\') {
+        unescaped.push_str(&rest[..idx]);
+        let escaped = rest.get(idx + 1..idx + 4);
+        match escaped.and_then(|octal| u8::from_str_radix(octal, 8).ok()) {
+            Some(byte) => {
+                unescaped.push(char::from(byte));
+                rest = &rest[idx + 4..];
+            }
+            None => {
+                unescaped.push('\\');
+                rest = &rest[idx + 1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::ModuleRenderer;
+    use nu_ansi_term::Color;
+
+    const MOUNTINFO: &str = "\
+22 1 259:2 / / rw,relatime shared:1 - ext4 /dev/nvme0n1p2 rw
+35 22 0:32 / /usr rw,relatime shared:2 - ext4 /dev/nvme0n1p3 rw
+41 22 0:40 / /mnt/image ro,relatime shared:5 - squashfs /dev/loop0 ro
+42 41 0:41 / /mnt/image/data rw,relatime shared:6 - tmpfs tmpfs rw
+50 22 0:45 / /srv/app ro,relatime - overlay overlay ro,lowerdir=/a,upperdir=/b
+51 22 0:46 / /media/My\\040Disk ro,relatime - vfat /dev/sdb1 ro
+";
+
+    #[test]
+    fn mount_of_directory() {
+        let mount = find_mount(MOUNTINFO, Path::new("/mnt/image/bin")).unwrap();
+        assert_eq!(mount.mount_point, PathBuf::from("/mnt/image"));
+        assert_eq!(mount.fs_type, "squashfs");
+
+        let mount = find_mount(MOUNTINFO, Path::new("/mnt/image/data/cache")).unwrap();
+        assert_eq!(mount.mount_point, PathBuf::from("/mnt/image/data"));
+
+        let mount = find_mount(MOUNTINFO, Path::new("/media/My Disk/photos")).unwrap();
+        assert_eq!(mount.fs_type, "vfat");
+
+        let mount = find_mount(MOUNTINFO, Path::new("/mnt/images")).unwrap();
+        assert_eq!(mount.mount_point, PathBuf::from("/"));
+    }
+
+    #[test]
+    fn unescape_mount_points() {
+        assert_eq!(unescape_octal("/media/My\\040Disk"), "/media/My Disk");
+        assert_eq!(unescape_octal("/a\\134b"), "/a\\b");
+        assert_eq!(unescape_octal("/trailing\\"), "/trailing\\");
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let actual = ModuleRenderer::new("immutable")
+            .path("/nix/store/abc-hello-2.12")
+            .collect();
+
+        assert_eq!(None, actual);
+    }
+
+    #[test]
+    fn nix_store() {
+        let actual = ModuleRenderer::new("immutable")
+            .path("/nix/store/abc-hello-2.12")
+            .config(toml::toml! {
+                [immutable]
+                disabled = false
+            })
+            .collect();
+        let expected = Some(format!("in {} ", Color::Red.bold().paint("🧊 nix store")));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn custom_nix_store_dir() {
+        let actual = ModuleRenderer::new("immutable")
+            .path("/opt/nix/store/abc-hello-2.12")
+            .env("NIX_STORE_DIR", "/opt/nix/store")
+            .config(toml::toml! {
+                [immutable]
+                format = "$kind"
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(Some("nix store".to_string()), actual);
+    }
+
+    #[test]
+    fn ostree_usr() {
+        let renderer = ModuleRenderer::new("immutable")
+            .path("/usr/share/doc")
+            .config(toml::toml! {
+                [immutable]
+                format = "$kind"
+                disabled = false
+            })
+            .file("/run/ostree-booted", "")
+            .file("/proc/self/mountinfo", MOUNTINFO);
+
+        assert_eq!(Some("ostree".to_string()), renderer.collect());
+    }
+
+    #[test]
+    fn writable_usr_without_ostree() {
+        let renderer = ModuleRenderer::new("immutable")
+            .path("/usr/share/doc")
+            .config(toml::toml! {
+                [immutable]
+                disabled = false
+            })
+            .file("/proc/self/mountinfo", MOUNTINFO);
+
+        assert_eq!(None, renderer.collect());
+    }
+
+    #[test]
+    fn read_only_overlay() {
+        let renderer = ModuleRenderer::new("immutable")
+            .path("/srv/app/config")
+            .config(toml::toml! {
+                [immutable]
+                format = "$kind"
+                disabled = false
+            })
+            .file("/proc/self/mountinfo", MOUNTINFO);
+
+        assert_eq!(Some("read-only overlay".to_string()), renderer.collect());
+    }
+
+    #[test]
+    fn read_only_mount() {
+        let renderer = ModuleRenderer::new("immutable")
+            .path("/mnt/image/bin")
+            .config(toml::toml! {
+                [immutable]
+                format = "$kind"
+                read_only_msg = "ro"
+                disabled = false
+            })
+            .file("/proc/self/mountinfo", MOUNTINFO);
+
+        assert_eq!(Some("ro".to_string()), renderer.collect());
+    }
+
+    #[test]
+    fn writable_mount_inside_read_only_mount() {
+        let renderer = ModuleRenderer::new("immutable")
+            .path("/mnt/image/data")
+            .config(toml::toml! {
+                [immutable]
+                disabled = false
+            })
+            .file("/proc/self/mountinfo", MOUNTINFO);
+
+        assert_eq!(None, renderer.collect());
+    }
+}
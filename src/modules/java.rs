@@ -1,9 +1,10 @@
 use super::{Context, Module, ModuleConfig};
 use crate::configs::java::JavaConfig;
 use crate::formatter::{StringFormatter, VersionFormatter};
-use crate::utils::get_command_string_output;
+use crate::utils::{self, get_command_string_output};
 use std::path::PathBuf;
 
+use once_cell::sync::Lazy;
 use regex::Regex;
 const JAVA_VERSION_PATTERN: &str =
     "(?:JRE.*\\(|OpenJ9 )(?P<version>\\d+(?:\\.\\d+){0,2}).*, built on";
@@ -24,6 +25,8 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
+    let build_tool = Lazy::new(|| get_build_tool(context));
+
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
             .map_meta(|var, _| match var {
@@ -44,6 +47,16 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                     )
                     .map(Ok)
                 }
+                "build_tool" => build_tool.as_ref().map(|(tool, _)| Ok(tool.to_string())),
+                "build_tool_version" => {
+                    let (_, version) = build_tool.as_ref()?;
+                    VersionFormatter::format_module_version(
+                        module.get_name(),
+                        version,
+                        config.version_format,
+                    )
+                    .map(Ok)
+                }
                 _ => None,
             })
             .parse(None, Some(context))
@@ -60,6 +73,28 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// The build tool of the project and the version its wrapper downloads, from the properties of
+/// the Gradle or Maven wrapper
+fn get_build_tool(context: &Context) -> Option<(&'static str, String)> {
+    let wrappers = [
+        (
+            "gradle",
+            "gradle/wrapper/gradle-wrapper.properties",
+            "gradle-",
+        ),
+        (
+            "maven",
+            ".mvn/wrapper/maven-wrapper.properties",
+            "apache-maven-",
+        ),
+    ];
+    wrappers.iter().find_map(|(tool, properties, prefix)| {
+        let properties = utils::read_file(context.current_dir.join(properties)).ok()?;
+        let version = super::gradle::parse_wrapper_version(&properties, prefix)?;
+        Some((*tool, version))
+    })
+}
+
 fn get_java_version(context: &Context) -> Option<String> {
     let java_command = context
         .get_env("JAVA_HOME")
@@ -73,7 +108,7 @@ fn get_java_version(context: &Context) -> Option<String> {
         })
         .unwrap_or_else(|| String::from("java"));
 
-    let output = context.exec_cmd(java_command, &["-Xinternalversion"])?;
+    let output = context.exec_version_cmd(java_command, &["-Xinternalversion"])?;
     let java_version_string = get_command_string_output(output);
 
     parse_java_version(&java_version_string)
@@ -252,6 +287,27 @@ mod tests {
         dir.close()
     }
 
+    #[test]
+    fn folder_with_maven_wrapper() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("pom.xml"))?.sync_all()?;
+        let wrapper = dir.path().join(".mvn").join("wrapper");
+        std::fs::create_dir_all(&wrapper)?;
+        std::fs::write(
+            wrapper.join("maven-wrapper.properties"),
+            "distributionUrl=https://repo.maven.apache.org/maven2/org/apache/maven/apache-maven/3.9.6/apache-maven-3.9.6-bin.zip\n",
+        )?;
+        let actual = ModuleRenderer::new("java")
+            .path(dir.path())
+            .config(toml::toml! {
+                [java]
+                format = "$build_tool $build_tool_version"
+            })
+            .collect();
+        assert_eq!(Some("maven v3.9.6".to_string()), actual);
+        dir.close()
+    }
+
     #[test]
     fn folder_with_sdkman_file() -> io::Result<()> {
         let dir = tempfile::tempdir()?;
@@ -4,6 +4,15 @@ use crate::configs::julia::JuliaConfig;
 use crate::formatter::StringFormatter;
 use crate::formatter::VersionFormatter;
 
+use super::utils::version::VersionConstraint;
+use once_cell::sync::Lazy;
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+/// The names of project files, in the order Julia looks for them
+const PROJECT_FILES: [&str; 2] = ["JuliaProject.toml", "Project.toml"];
+
 /// Creates a module with the current Julia version
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("julia");
@@ -20,6 +29,11 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
+    let julia_version = Lazy::new(|| {
+        parse_julia_version(&context.exec_version_cmd("julia", &["--version"])?.stdout)
+    });
+    let project = Lazy::new(|| find_project(context));
+
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
             .map_meta(|var, _| match var {
@@ -31,15 +45,23 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map(|variable| match variable {
-                "version" => {
-                    let julia_version =
-                        parse_julia_version(&context.exec_cmd("julia", &["--version"])?.stdout)?;
-                    VersionFormatter::format_module_version(
-                        module.get_name(),
-                        &julia_version,
-                        config.version_format,
-                    )
-                    .map(Ok)
+                "version" => VersionFormatter::format_module_version(
+                    module.get_name(),
+                    julia_version.deref().as_ref()?,
+                    config.version_format,
+                )
+                .map(Ok),
+                "environment" => project.as_ref()?.name().map(Ok),
+                "manifest_synced" => {
+                    let symbol = if project
+                        .as_ref()?
+                        .is_manifest_synced(julia_version.as_deref())?
+                    {
+                        config.manifest_synced_symbol
+                    } else {
+                        config.manifest_out_of_sync_symbol
+                    };
+                    Some(Ok(symbol.to_string()))
                 }
                 _ => None,
             })
@@ -72,6 +94,145 @@ fn parse_julia_version(julia_stdout: &str) -> Option<String> {
     Some(version.to_string())
 }
 
+/// The project environment Julia activates for the current directory
+struct Project {
+    /// The project file, `Project.toml` or `JuliaProject.toml`
+    file: PathBuf,
+    /// The name of a shared environment, like `@v1.10`
+    shared_name: Option<String>,
+}
+
+/// The project named by `JULIA_PROJECT`, or the project the current directory is in, which
+/// `julia --project` activates
+fn find_project(context: &Context) -> Option<Project> {
+    let julia_project = context.get_env("JULIA_PROJECT").unwrap_or_default();
+    match julia_project.as_str() {
+        "" | "@." => {
+            let file = context.current_dir.ancestors().find_map(project_file_in)?;
+            Some(Project {
+                file,
+                shared_name: None,
+            })
+        }
+        shared if shared.starts_with('@') => {
+            let depot = match context
+                .get_env_os("JULIA_DEPOT_PATH")
+                .and_then(|depots| std::env::split_paths(&depots).next())
+                .filter(|depot| !depot.as_os_str().is_empty())
+            {
+                Some(depot) => depot,
+                None => context.get_home()?.join(".julia"),
+            };
+            let dir = depot.join("environments").join(&shared[1..]);
+            Some(Project {
+                file: project_file_in(&dir).unwrap_or_else(|| dir.join("Project.toml")),
+                shared_name: Some(shared.to_string()),
+            })
+        }
+        path => {
+            let path = context.current_dir.join(path);
+            let file = if path
+                .extension()
+                .map_or(false, |extension| extension == "toml")
+            {
+                path
+            } else {
+                project_file_in(&path)?
+            };
+            Some(Project {
+                file,
+                shared_name: None,
+            })
+        }
+    }
+}
+
+fn project_file_in(dir: &Path) -> Option<PathBuf> {
+    PROJECT_FILES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|file| file.is_file())
+}
+
+impl Project {
+    fn dir(&self) -> &Path {
+        self.file.parent().unwrap_or(&self.file)
+    }
+
+    fn read(&self) -> Option<toml::Table> {
+        toml::from_str(&fs::read_to_string(&self.file).ok()?).ok()
+    }
+
+    /// The `name` of a package project, the name of a shared environment or the name of the
+    /// directory of the project
+    fn name(&self) -> Option<String> {
+        let name = self
+            .read()
+            .and_then(|project| Some(project.get("name")?.as_str()?.to_string()));
+        name.or_else(|| self.shared_name.clone()).or_else(|| {
+            self.dir()
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+    }
+
+    /// The manifest of the project, preferring the one of the installed Julia version, like
+    /// `Manifest-v1.10.toml`
+    fn manifest_file(&self, julia_version: Option<&str>) -> Option<PathBuf> {
+        let minor_version = julia_version.and_then(minor_version);
+        let mut names = Vec::new();
+        for prefix in ["JuliaManifest", "Manifest"] {
+            if let Some(minor_version) = &minor_version {
+                names.push(format!("{prefix}-v{minor_version}.toml"));
+            }
+        }
+        names.extend([
+            "JuliaManifest.toml".to_string(),
+            "Manifest.toml".to_string(),
+        ]);
+        names
+            .iter()
+            .map(|name| self.dir().join(name))
+            .find(|file| file.is_file())
+    }
+
+    /// Whether the manifest was resolved with the minor version of the installed Julia and has
+    /// all dependencies of the project, `None` without a manifest
+    fn is_manifest_synced(&self, julia_version: Option<&str>) -> Option<bool> {
+        let manifest_file = self.manifest_file(julia_version)?;
+        let manifest: toml::Table =
+            toml::from_str(&fs::read_to_string(manifest_file).ok()?).ok()?;
+
+        // Manifests of Julia 1.6 and older have no `julia_version`
+        if let Some(resolved_with) = manifest
+            .get("julia_version")
+            .and_then(|version| version.as_str())
+        {
+            let resolved_with = VersionConstraint::pinned(&minor_version(resolved_with)?)?;
+            if !resolved_with.is_satisfied_by(julia_version?)? {
+                return Some(false);
+            }
+        }
+
+        // The packages are in `[deps]` since the manifest format 2.0, at the top level before
+        let packages = match manifest.get("deps").and_then(|deps| deps.as_table()) {
+            Some(deps) => deps,
+            None => &manifest,
+        };
+        let project = self.read()?;
+        let dependencies = project.get("deps").and_then(|deps| deps.as_table());
+        Some(dependencies.map_or(true, |dependencies| {
+            dependencies.keys().all(|name| packages.contains_key(name))
+        }))
+    }
+}
+
+/// The major and minor parts of a version, like `1.10` of `1.10.4`
+fn minor_version(version: &str) -> Option<String> {
+    let mut parts = version.split('.');
+    Some(format!("{}.{}", parts.next()?, parts.next()?))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -127,6 +288,74 @@ mod tests {
         dir.close()
     }
 
+    #[test]
+    fn project_environment_and_manifest_sync() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("Project.toml"),
+            "name = \"Example\"\n\n[deps]\nJSON = \"682c06a0-de6a-54ab-a142-c8b1cf79cde6\"\n",
+        )?;
+        let write_manifest = |julia_version: &str, package: &str| {
+            fs::write(
+                dir.path().join("Manifest.toml"),
+                format!(
+                    "julia_version = \"{julia_version}\"\nmanifest_format = \"2.0\"\n\n[[deps.{package}]]\nversion = \"0.21.4\"\n"
+                ),
+            )
+        };
+        let render = || {
+            ModuleRenderer::new("julia")
+                .path(dir.path())
+                .config(toml::toml! {
+                    [julia]
+                    format = "$environment( $manifest_synced)"
+                })
+                .collect()
+        };
+
+        write_manifest("1.4.2", "JSON")?;
+        assert_eq!(render(), Some("Example ✓".to_string()));
+
+        // Resolved with another minor version of Julia
+        write_manifest("1.9.0", "JSON")?;
+        assert_eq!(render(), Some("Example ✗".to_string()));
+
+        // A dependency was added to the project without resolving
+        write_manifest("1.4.2", "Parsers")?;
+        assert_eq!(render(), Some("Example ✗".to_string()));
+
+        // The manifest of the installed Julia version is preferred
+        fs::write(
+            dir.path().join("Manifest-v1.4.toml"),
+            "julia_version = \"1.4.0\"\nmanifest_format = \"2.0\"\n\n[[deps.JSON]]\n",
+        )?;
+        assert_eq!(render(), Some("Example ✓".to_string()));
+        dir.close()
+    }
+
+    #[test]
+    fn shared_environment() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("hello.jl"))?.sync_all()?;
+        let depot = tempfile::tempdir()?;
+        let environment = depot.path().join("environments").join("v1.4");
+        fs::create_dir_all(&environment)?;
+        fs::write(environment.join("Project.toml"), "[deps]\n")?;
+
+        let actual = ModuleRenderer::new("julia")
+            .path(dir.path())
+            .env("JULIA_PROJECT", "@v1.4")
+            .env("JULIA_DEPOT_PATH", depot.path().to_string_lossy())
+            .config(toml::toml! {
+                [julia]
+                format = "$environment( $manifest_synced)"
+            })
+            .collect();
+        assert_eq!(actual, Some("@v1.4".to_string()));
+        dir.close()?;
+        depot.close()
+    }
+
     #[test]
     fn test_parse_julia_version() {
         let input = "julia version 1.4.0";
@@ -61,7 +61,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 }
 
 fn get_kotlin_version(context: &Context, kotlin_binary: &str) -> Option<String> {
-    let command = context.exec_cmd(kotlin_binary, &["-version"])?;
+    let command = context.exec_version_cmd(kotlin_binary, &["-version"])?;
     let kotlin_version_string = get_command_string_output(command);
 
     parse_kotlin_version(&kotlin_version_string)
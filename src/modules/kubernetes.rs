@@ -169,19 +169,8 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    let default_config_file = context.get_home()?.join(".kube").join("config");
-
-    let kube_cfg = context
-        .get_env("KUBECONFIG")
-        .unwrap_or(default_config_file.to_str()?.to_string());
-
-    let raw_kubeconfigs = env::split_paths(&kube_cfg).map(|file| utils::read_file(file).ok());
-    let kubeconfigs = parse_kubeconfigs(raw_kubeconfigs);
-
-    let current_kube_ctx_name = kubeconfigs.iter().find_map(|v| match v {
-        Document::Json(json) => get_current_kube_context_name(json),
-        Document::Yaml(yaml) => get_current_kube_context_name(yaml),
-    })?;
+    let (kube_cfg, kubeconfigs) = load_kubeconfigs(context)?;
+    let current_kube_ctx_name = find_current_kube_context_name(&kubeconfigs)?;
 
     // Even if we have multiple config files, the first key wins
     // https://kubernetes.io/docs/concepts/configuration/organize-cluster-access-kubeconfig/
@@ -234,11 +223,26 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let display_user =
         display_user.and_then(|user| deprecated::get_alias(user, &config.user_aliases, "user"));
 
+    // The group named by the matching context entry, or the first group with a matching pattern
+    let group = match matched_context_config.and_then(|ctx_cfg| ctx_cfg.group) {
+        Some(name) => config.groups.get_key_value(name).or_else(|| {
+            log::warn!("Kubernetes context group `{name}` is not defined");
+            None
+        }),
+        None => config.groups.iter().find(|(_, group)| {
+            group.context_patterns.iter().any(|&pattern| {
+                get_aliased_name(Some(pattern), Some(current_kube_ctx_name), None).is_some()
+            })
+        }),
+    };
+
     let display_style = matched_context_config
         .and_then(|ctx_cfg| ctx_cfg.style)
+        .or_else(|| group.and_then(|(_, group)| group.style))
         .unwrap_or(config.style);
     let display_symbol = matched_context_config
         .and_then(|ctx_cfg| ctx_cfg.symbol)
+        .or_else(|| group.and_then(|(_, group)| group.symbol))
         .unwrap_or(config.symbol);
 
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
@@ -264,6 +268,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 "user" => display_user
                     .as_ref()
                     .map(|kube_user| Ok(Cow::Borrowed(kube_user.as_str()))),
+                "group" => group.map(|(name, _)| Ok(Cow::Borrowed(name.as_str()))),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -280,6 +285,32 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// Returns the name of the current kube context, without any aliases applied
+pub(super) fn get_current_kube_context(context: &Context) -> Option<String> {
+    let (_, kubeconfigs) = load_kubeconfigs(context)?;
+    find_current_kube_context_name(&kubeconfigs).map(ToOwned::to_owned)
+}
+
+/// Reads the kubeconfig files listed in `KUBECONFIG`, or `~/.kube/config`
+fn load_kubeconfigs(context: &Context) -> Option<(String, Vec<Document>)> {
+    let default_config_file = context.get_home()?.join(".kube").join("config");
+
+    let kube_cfg = context
+        .get_env("KUBECONFIG")
+        .unwrap_or(default_config_file.to_str()?.to_string());
+
+    let raw_kubeconfigs = env::split_paths(&kube_cfg).map(|file| utils::read_file(file).ok());
+    let kubeconfigs = parse_kubeconfigs(raw_kubeconfigs);
+    Some((kube_cfg, kubeconfigs))
+}
+
+fn find_current_kube_context_name(kubeconfigs: &[Document]) -> Option<&str> {
+    kubeconfigs.iter().find_map(|v| match v {
+        Document::Json(json) => get_current_kube_context_name(json),
+        Document::Yaml(yaml) => get_current_kube_context_name(yaml),
+    })
+}
+
 fn parse_kubeconfigs<I>(raw_kubeconfigs: I) -> Vec<Document>
 where
     I: Iterator<Item = Option<String>>,
@@ -1641,4 +1672,71 @@ users: []
         assert_eq!(expected, actual);
         dir.close()
     }
+
+    #[test]
+    fn test_context_groups() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let filename = dir.path().join("config");
+        let render = |ctx_name: &str| -> io::Result<Option<String>> {
+            let mut file = File::create(&filename)?;
+            file.write_all(
+                format!(
+                    "
+apiVersion: v1
+clusters: []
+contexts: []
+current-context: {ctx_name}
+kind: Config
+preferences: {{}}
+users: []
+"
+                )
+                .as_bytes(),
+            )?;
+            file.sync_all()?;
+
+            Ok(ModuleRenderer::new("kubernetes")
+                .path(dir.path())
+                .env("KUBECONFIG", filename.to_string_lossy().as_ref())
+                .config(toml::toml! {
+                    [kubernetes]
+                    disabled = false
+                    format = "[$symbol$context( \\[$group\\])]($style) in "
+                    [[kubernetes.contexts]]
+                    context_pattern = "legacy-cluster"
+                    context_alias = "legacy"
+                    group = "prod"
+                    [[kubernetes.contexts]]
+                    context_pattern = "prod-db"
+                    style = "bold yellow"
+                    [kubernetes.groups.prod]
+                    context_patterns = ["prod-.*", "gke_.*_prod"]
+                    style = "bold red"
+                    symbol = "🔥 "
+                    [kubernetes.groups.dev]
+                    context_patterns = ["dev-.*"]
+                })
+                .collect())
+        };
+
+        // Contexts matching a pattern of a group inherit its style and symbol
+        let expected = format!("{} in ", Color::Red.bold().paint("🔥 prod-eu [prod]"));
+        assert_eq!(render("prod-eu")?, Some(expected));
+
+        // Context entries can name their group
+        let expected = format!("{} in ", Color::Red.bold().paint("🔥 legacy [prod]"));
+        assert_eq!(render("legacy-cluster")?, Some(expected));
+
+        // Context entries override the style of their group
+        let expected = format!("{} in ", Color::Yellow.bold().paint("🔥 prod-db [prod]"));
+        assert_eq!(render("prod-db")?, Some(expected));
+
+        // Groups without a style or symbol keep the module's
+        let expected = format!("{} in ", Color::Cyan.bold().paint("☸ dev-1 [dev]"));
+        assert_eq!(render("dev-1")?, Some(expected));
+
+        let expected = format!("{} in ", Color::Cyan.bold().paint("☸ other"));
+        assert_eq!(render("other")?, Some(expected));
+        dir.close()
+    }
 }
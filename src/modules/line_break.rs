@@ -1,9 +1,23 @@
-use super::{Context, Module};
+use super::{Context, Module, ModuleConfig};
+use crate::configs::line_break::LineBreakConfig;
 use crate::segment::Segment;
 
 /// Creates a module for the line break
+///
+/// The line break can be limited to a range of terminal widths. If the width is unknown, it is
+/// always shown.
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("line_break");
+    let config = LineBreakConfig::try_load(module.config);
+
+    let width = context.width;
+    if width > 0 {
+        let too_narrow = config.min_width > 0 && width < config.min_width;
+        let too_wide = config.max_width > 0 && width > config.max_width;
+        if too_narrow || too_wide {
+            return None;
+        }
+    }
 
     module.set_segments(vec![Segment::LineTerm]);
 
@@ -20,4 +34,34 @@ mod test {
         let actual = ModuleRenderer::new("line_break").collect();
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn only_below_max_width() {
+        let render = |width| {
+            ModuleRenderer::new("line_break")
+                .width(width)
+                .config(toml::toml! {
+                    [line_break]
+                    max_width = 100
+                })
+                .collect()
+        };
+
+        assert_eq!(render(80), Some(String::from("\n")));
+        assert_eq!(render(120), None);
+        // Unknown terminal width
+        assert_eq!(render(0), Some(String::from("\n")));
+    }
+
+    #[test]
+    fn only_above_min_width() {
+        let actual = ModuleRenderer::new("line_break")
+            .width(40)
+            .config(toml::toml! {
+                [line_break]
+                min_width = 60
+            })
+            .collect();
+        assert_eq!(None, actual);
+    }
 }
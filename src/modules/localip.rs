@@ -5,7 +5,7 @@ use crate::configs::localip::LocalipConfig;
 use crate::formatter::StringFormatter;
 
 use std::io::Error;
-use std::net::UdpSocket;
+use std::net::{IpAddr, UdpSocket};
 
 fn get_local_ipv4() -> Result<String, Error> {
     let socket = UdpSocket::bind("0.0.0.0:0")?;
@@ -16,6 +16,134 @@ fn get_local_ipv4() -> Result<String, Error> {
     Ok(addr.ip().to_string())
 }
 
+/// Lists the addresses of all network interfaces
+#[cfg(all(
+    not(test),
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_os = "dragonfly",
+        target_os = "illumos",
+        target_os = "solaris"
+    )
+))]
+fn get_interface_addresses() -> Vec<(String, IpAddr)> {
+    let addresses = match nix::ifaddrs::getifaddrs() {
+        Ok(addresses) => addresses,
+        Err(e) => {
+            log::warn!("unable to list network interfaces: {e}");
+            return Vec::new();
+        }
+    };
+
+    addresses
+        .filter_map(|interface| {
+            let address = interface.address?;
+            let ip = if let Some(v4) = address.as_sockaddr_in() {
+                IpAddr::V4(v4.ip())
+            } else {
+                IpAddr::V6(address.as_sockaddr_in6()?.ip())
+            };
+            Some((interface.interface_name, ip))
+        })
+        .collect()
+}
+
+#[cfg(all(
+    not(test),
+    not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_os = "dragonfly",
+        target_os = "illumos",
+        target_os = "solaris"
+    ))
+))]
+fn get_interface_addresses() -> Vec<(String, IpAddr)> {
+    log::warn!("listing network interfaces is not supported on this platform");
+    Vec::new()
+}
+
+#[cfg(test)]
+fn get_interface_addresses() -> Vec<(String, IpAddr)> {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    vec![
+        ("lo".to_string(), IpAddr::V4(Ipv4Addr::LOCALHOST)),
+        ("lo".to_string(), IpAddr::V6(Ipv6Addr::LOCALHOST)),
+        (
+            "eth0".to_string(),
+            IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20)),
+        ),
+        (
+            "eth0".to_string(),
+            IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 0x20)),
+        ),
+        (
+            "tailscale0".to_string(),
+            IpAddr::V6(Ipv6Addr::new(0xfd7a, 0x115c, 0xa1e0, 0, 0, 0, 0, 0x1)),
+        ),
+        (
+            "tailscale0".to_string(),
+            IpAddr::V4(Ipv4Addr::new(100, 64, 0, 1)),
+        ),
+    ]
+}
+
+/// Matches an interface name against a pattern from the `interfaces` option,
+/// which may end in `*` to match any suffix.
+fn matches_interface(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => name == pattern,
+    }
+}
+
+/// Picks one address for every interface matching `patterns`, in the order of the patterns.
+///
+/// Link-local addresses are only used if an interface has no other address. Otherwise addresses
+/// of the `prefer`red family win.
+fn select_addresses(
+    addresses: &[(String, IpAddr)],
+    patterns: &[&str],
+    prefer: &str,
+) -> Vec<(String, IpAddr)> {
+    let prefer_ipv6 = prefer == "ipv6";
+    let rank = |ip: &IpAddr| {
+        let link_local = match ip {
+            IpAddr::V4(ip) => ip.is_link_local(),
+            IpAddr::V6(ip) => (ip.segments()[0] & 0xffc0) == 0xfe80,
+        };
+        (link_local, ip.is_ipv6() != prefer_ipv6)
+    };
+
+    let mut selected: Vec<(String, IpAddr)> = Vec::new();
+    for pattern in patterns {
+        for (name, _) in addresses {
+            if !matches_interface(pattern, name) || selected.iter().any(|(n, _)| n == name) {
+                continue;
+            }
+            let best = addresses
+                .iter()
+                .filter(|(n, _)| n == name)
+                .map(|(_, ip)| *ip)
+                .min_by_key(rank);
+            if let Some(ip) = best {
+                selected.push((name.clone(), ip));
+            }
+        }
+    }
+    selected
+}
+
 /// Creates a module with the ipv4 address of the local machine.
 ///
 /// The IP address is gathered from the local endpoint of an UDP socket
@@ -41,6 +169,10 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
+    if !config.interfaces.is_empty() {
+        return interfaces_module(context, module, &config);
+    }
+
     let localip = match get_local_ipv4() {
         Ok(ip) => ip,
         Err(e) => {
@@ -78,6 +210,89 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// Shows the addresses of the interfaces selected with the `interfaces` option
+fn interfaces_module<'a>(
+    context: &'a Context,
+    mut module: Module<'a>,
+    config: &LocalipConfig,
+) -> Option<Module<'a>> {
+    let selected = select_addresses(
+        &get_interface_addresses(),
+        &config.interfaces,
+        config.prefer,
+    );
+    if selected.is_empty() {
+        log::debug!("No network interface matches {:?}", config.interfaces);
+        return None;
+    }
+
+    let interface_format_with_separator =
+        [config.interface_format, config.interface_separator].join("");
+    let interfaces = selected
+        .iter()
+        .enumerate()
+        .filter_map(|(i, (name, ip))| {
+            let format: &str = if i == selected.len() - 1 {
+                config.interface_format
+            } else {
+                &interface_format_with_separator
+            };
+            let label = config.labels.get(name).copied().unwrap_or(name.as_str());
+            let address = ip.to_string();
+            let parsed = StringFormatter::new(format).and_then(|formatter| {
+                formatter
+                    .map_style(|variable| match variable {
+                        "style" => Some(Ok(config.style)),
+                        _ => None,
+                    })
+                    .map(|variable| match variable {
+                        "interface" => Some(Ok(name.as_str())),
+                        "label" => Some(Ok(label)),
+                        "address" => Some(Ok(address.as_str())),
+                        _ => None,
+                    })
+                    .parse(None, Some(context))
+            });
+            match parsed {
+                Ok(segments) => Some(segments),
+                Err(e) => {
+                    log::warn!("Error parsing format string in `localip.interface_format`: {e:?}");
+                    None
+                }
+            }
+        })
+        .flatten()
+        .collect::<Vec<_>>();
+
+    let first_address = selected[0].1.to_string();
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "localipv4" => Some(Ok(&first_address)),
+                _ => None,
+            })
+            .map_variables_to_segments(|variable| match variable {
+                "interfaces" => Some(Ok(interfaces.clone())),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `localip`:\n{}", error);
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::modules::localip::get_local_ipv4;
@@ -163,6 +378,59 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn interfaces_by_pattern() {
+        let actual = ModuleRenderer::new("localip")
+            .config(toml::toml! {
+                [localip]
+                ssh_only = false
+                format = "[$interfaces]($style) "
+                interfaces = ["tailscale*", "eth0", "wlan0"]
+                disabled = false
+            })
+            .collect();
+        let expected = Some(format!(
+            "{} ",
+            style().paint("tailscale0:100.64.0.1 eth0:192.168.1.20")
+        ));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn interfaces_prefer_ipv6_with_labels() {
+        let actual = ModuleRenderer::new("localip")
+            .config(toml::toml! {
+                [localip]
+                ssh_only = false
+                format = "$interfaces"
+                interfaces = ["tailscale0", "eth0"]
+                prefer = "ipv6"
+                interface_separator = ", "
+                disabled = false
+                [localip.labels]
+                tailscale0 = "ts"
+            })
+            .collect();
+        let expected = Some("ts:fd7a:115c:a1e0::1, eth0:192.168.1.20".to_string());
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn interfaces_without_match() {
+        let actual = ModuleRenderer::new("localip")
+            .config(toml::toml! {
+                [localip]
+                ssh_only = false
+                interfaces = ["wlan*"]
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(None, actual);
+    }
+
     fn style() -> Style {
         Color::Yellow.bold()
     }
@@ -33,8 +33,9 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             })
             .map(|variable| match variable {
                 "version" => {
-                    let lua_version_string =
-                        get_command_string_output(context.exec_cmd(config.lua_binary, &["-v"])?);
+                    let lua_version_string = get_command_string_output(
+                        context.exec_version_cmd(config.lua_binary, &["-v"])?,
+                    );
                     let lua_version = parse_lua_version(&lua_version_string)?;
                     VersionFormatter::format_module_version(
                         module.get_name(),
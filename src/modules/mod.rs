@@ -1,15 +1,19 @@
 // While adding out new module add out module to src/module.rs ALL_MODULES const array also.
+mod annotations;
 mod aws;
 mod azure;
 mod buf;
+mod build_staleness;
 mod bun;
 mod c;
 mod character;
+mod ci;
 mod cmake;
 mod cmd_duration;
 mod cobol;
 mod conda;
 mod container;
+mod cpp;
 mod crystal;
 pub mod custom;
 mod daml;
@@ -18,13 +22,17 @@ mod deno;
 mod directory;
 mod direnv;
 mod docker_context;
+mod dotenv;
 mod dotnet;
 mod elixir;
 mod elm;
 mod env_var;
+mod environment;
 mod erlang;
+mod errors;
 mod fennel;
 mod fill;
+mod formatter_tools;
 mod fossil_branch;
 mod fossil_metrics;
 mod gcloud;
@@ -41,7 +49,9 @@ mod haskell;
 mod haxe;
 mod helm;
 mod hg_branch;
+mod hg_status;
 mod hostname;
+mod immutable;
 mod java;
 mod jobs;
 mod julia;
@@ -66,12 +76,16 @@ mod package;
 mod perl;
 mod php;
 mod pijul_channel;
+mod pre_commit;
 mod pulumi;
 mod purescript;
 mod python;
+mod python_env;
 mod quarto;
 mod raku;
 mod red;
+mod repl;
+mod repo_remote;
 mod rlang;
 mod ruby;
 mod rust;
@@ -85,7 +99,10 @@ mod status;
 mod sudo;
 mod swift;
 mod terraform;
+mod tests;
+mod ticket;
 mod time;
+mod todos;
 mod username;
 mod utils;
 mod vagrant;
@@ -99,154 +116,228 @@ mod typst;
 
 #[cfg(feature = "battery")]
 pub use self::battery::{BatteryInfoProvider, BatteryInfoProviderImpl};
+pub use self::git_status::watched_status as watched_git_status;
+pub use self::todos::fill_index as fill_todo_index;
 
+use crate::accessibility;
 use crate::config::ModuleConfig;
 use crate::context::{Context, Shell};
+use crate::logger;
 use crate::module::Module;
+use crate::module_cache;
+use std::panic::{self, AssertUnwindSafe};
 use std::time::Instant;
 
 pub fn handle<'a>(module: &str, context: &'a Context) -> Option<Module<'a>> {
     let start: Instant = Instant::now();
-    let mut m: Option<Module> = {
-        match module {
-            // Keep these ordered alphabetically.
-            // Default ordering is handled in configs/starship_root.rs
-            "aws" => aws::module(context),
-            "azure" => azure::module(context),
-            #[cfg(feature = "battery")]
-            "battery" => battery::module(context),
-            "buf" => buf::module(context),
-            "bun" => bun::module(context),
-            "c" => c::module(context),
-            "character" => character::module(context),
-            "cmake" => cmake::module(context),
-            "cmd_duration" => cmd_duration::module(context),
-            "cobol" => cobol::module(context),
-            "conda" => conda::module(context),
-            "container" => container::module(context),
-            "daml" => daml::module(context),
-            "dart" => dart::module(context),
-            "deno" => deno::module(context),
-            "directory" => directory::module(context),
-            "direnv" => direnv::module(context),
-            "docker_context" => docker_context::module(context),
-            "dotnet" => dotnet::module(context),
-            "elixir" => elixir::module(context),
-            "elm" => elm::module(context),
-            "erlang" => erlang::module(context),
-            "env_var" => env_var::module(None, context),
-            "fennel" => fennel::module(context),
-            "fill" => fill::module(context),
-            "fossil_branch" => fossil_branch::module(context),
-            "fossil_metrics" => fossil_metrics::module(context),
-            "gcloud" => gcloud::module(context),
-            "git_branch" => git_branch::module(context),
-            "git_commit" => git_commit::module(context),
-            "git_metrics" => git_metrics::module(context),
-            "git_state" => git_state::module(context),
-            "git_status" => git_status::module(context),
-            "gleam" => gleam::module(context),
-            "golang" => golang::module(context),
-            "gradle" => gradle::module(context),
-            "guix_shell" => guix_shell::module(context),
-            "haskell" => haskell::module(context),
-            "haxe" => haxe::module(context),
-            "helm" => helm::module(context),
-            "hg_branch" => hg_branch::module(context),
-            "hostname" => hostname::module(context),
-            "java" => java::module(context),
-            "jobs" => jobs::module(context),
-            "julia" => julia::module(context),
-            "kotlin" => kotlin::module(context),
-            "kubernetes" => kubernetes::module(context),
-            "line_break" => line_break::module(context),
-            "localip" => localip::module(context),
-            "lua" => lua::module(context),
-            "memory_usage" => memory_usage::module(context),
-            "meson" => meson::module(context),
-            "mojo" => mojo::module(context),
-            "nats" => nats::module(context),
-            "nim" => nim::module(context),
-            "nix_shell" => nix_shell::module(context),
-            "nodejs" => nodejs::module(context),
-            "ocaml" => ocaml::module(context),
-            "odin" => odin::module(context),
-            "opa" => opa::module(context),
-            "openstack" => openstack::module(context),
-            "os" => os::module(context),
-            "package" => package::module(context),
-            "perl" => perl::module(context),
-            "php" => php::module(context),
-            "pijul_channel" => pijul_channel::module(context),
-            "pulumi" => pulumi::module(context),
-            "purescript" => purescript::module(context),
-            "python" => python::module(context),
-            "quarto" => quarto::module(context),
-            "raku" => raku::module(context),
-            "rlang" => rlang::module(context),
-            "red" => red::module(context),
-            "ruby" => ruby::module(context),
-            "rust" => rust::module(context),
-            "scala" => scala::module(context),
-            "shell" => shell::module(context),
-            "shlvl" => shlvl::module(context),
-            "singularity" => singularity::module(context),
-            "solidity" => solidity::module(context),
-            "spack" => spack::module(context),
-            "swift" => swift::module(context),
-            "status" => status::module(context),
-            "sudo" => sudo::module(context),
-            "terraform" => terraform::module(context),
-            "time" => time::module(context),
-            "typst" => typst::module(context),
-            "crystal" => crystal::module(context),
-            "username" => username::module(context),
-            "vlang" => vlang::module(context),
-            "vagrant" => vagrant::module(context),
-            "vcsh" => vcsh::module(context),
-            "zig" => zig::module(context),
-            env if env.starts_with("env_var.") => {
-                env_var::module(env.strip_prefix("env_var."), context)
-            }
-            custom if custom.starts_with("custom.") => {
-                // SAFETY: We just checked that the module starts with "custom."
-                custom::module(custom.strip_prefix("custom.").unwrap(), context)
-            }
-            _ => {
-                eprintln!("Error: Unknown module {module}. Use starship module --list to list out all supported modules.");
-                None
-            }
+    let cached = module_cache::read(module, context);
+    let is_cached = cached.is_some();
+    let (mut m, problems) = logger::capture_problems(module, || {
+        if let Some(segments) = cached {
+            return (!segments.is_empty()).then(|| {
+                let mut m = context.new_module(module);
+                m.set_segments(segments);
+                m
+            });
         }
-    };
+        // A panic in a single module should not take the whole prompt down with it,
+        // so treat it like a module that produced no output.
+        let computed = panic::catch_unwind(AssertUnwindSafe(|| compute(module, context)));
+        computed.unwrap_or_else(|payload| {
+            log::error!(
+                "Module {:?} panicked: {}",
+                module,
+                panic_message(payload.as_ref())
+            );
+            None
+        })
+    });
+    // Outputs computed while something went wrong are not worth keeping
+    if !is_cached && problems.is_empty() {
+        module_cache::write(module, context, m.as_ref());
+    }
 
     let elapsed = start.elapsed();
     log::trace!("Took {:?} to compute module {:?}", elapsed, module);
-    if elapsed.as_millis() >= 1 {
+    context.add_module_duration(module, elapsed);
+    if elapsed.as_millis() >= 1 || m.is_some() {
         // If we take less than 1ms to compute a None, then we will not return a module at all
-        // if we have a module: default duration is 0 so no need to change it
         // if we took more than 1ms we want to report that and so--in case we have None currently--
         // need to create an empty module just to hold the duration for that case
         m.get_or_insert_with(|| context.new_module(module)).duration = elapsed;
     }
+    if !problems.is_empty() {
+        // Keep the problems around for `starship explain`, even if the module has no output
+        context.add_failed_module(module);
+        m.get_or_insert_with(|| context.new_module(module)).problems = problems;
+    }
+    if context.root_config.accessibility {
+        if let Some(m) = m.as_mut() {
+            let segments = std::mem::take(&mut m.segments);
+            m.segments = accessibility::describe_module(context, module, segments);
+        }
+    }
     m
 }
 
+fn compute<'a>(module: &str, context: &'a Context) -> Option<Module<'a>> {
+    match module {
+        // Keep these ordered alphabetically.
+        // Default ordering is handled in configs/starship_root.rs
+        "annotations" => annotations::module(context),
+        "aws" => aws::module(context),
+        "azure" => azure::module(context),
+        #[cfg(feature = "battery")]
+        "battery" => battery::module(context),
+        "buf" => buf::module(context),
+        "build_staleness" => build_staleness::module(context),
+        "bun" => bun::module(context),
+        "c" => c::module(context),
+        "character" => character::module(context),
+        "ci" => ci::module(context),
+        "cmake" => cmake::module(context),
+        "cmd_duration" => cmd_duration::module(context),
+        "cobol" => cobol::module(context),
+        "conda" => conda::module(context),
+        "container" => container::module(context),
+        "cpp" => cpp::module(context),
+        "daml" => daml::module(context),
+        "dart" => dart::module(context),
+        "deno" => deno::module(context),
+        "directory" => directory::module(context),
+        "direnv" => direnv::module(context),
+        "docker_context" => docker_context::module(context),
+        "dotenv" => dotenv::module(context),
+        "dotnet" => dotnet::module(context),
+        "elixir" => elixir::module(context),
+        "elm" => elm::module(context),
+        "environment" => environment::module(context),
+        "erlang" => erlang::module(context),
+        "errors" => errors::module(context),
+        "env_var" => env_var::module(None, context),
+        "fennel" => fennel::module(context),
+        "fill" => fill::module(context),
+        "formatter_tools" => formatter_tools::module(context),
+        "fossil_branch" => fossil_branch::module(context),
+        "fossil_metrics" => fossil_metrics::module(context),
+        "gcloud" => gcloud::module(context),
+        "git_branch" => git_branch::module(context),
+        "git_commit" => git_commit::module(context),
+        "git_metrics" => git_metrics::module(context),
+        "git_state" => git_state::module(context),
+        "git_status" => git_status::module(context),
+        "gleam" => gleam::module(context),
+        "golang" => golang::module(context),
+        "gradle" => gradle::module(context),
+        "guix_shell" => guix_shell::module(context),
+        "haskell" => haskell::module(context),
+        "haxe" => haxe::module(context),
+        "helm" => helm::module(context),
+        "hg_branch" => hg_branch::module(context),
+        "hg_status" => hg_status::module(context),
+        "hostname" => hostname::module(context),
+        "immutable" => immutable::module(context),
+        "java" => java::module(context),
+        "jobs" => jobs::module(context),
+        "julia" => julia::module(context),
+        "kotlin" => kotlin::module(context),
+        "kubernetes" => kubernetes::module(context),
+        "line_break" => line_break::module(context),
+        "localip" => localip::module(context),
+        "lua" => lua::module(context),
+        "memory_usage" => memory_usage::module(context),
+        "meson" => meson::module(context),
+        "mojo" => mojo::module(context),
+        "nats" => nats::module(context),
+        "nim" => nim::module(context),
+        "nix_shell" => nix_shell::module(context),
+        "nodejs" => nodejs::module(context),
+        "ocaml" => ocaml::module(context),
+        "odin" => odin::module(context),
+        "opa" => opa::module(context),
+        "openstack" => openstack::module(context),
+        "os" => os::module(context),
+        "package" => package::module(context),
+        "perl" => perl::module(context),
+        "php" => php::module(context),
+        "pijul_channel" => pijul_channel::module(context),
+        "pre_commit" => pre_commit::module(context),
+        "pulumi" => pulumi::module(context),
+        "purescript" => purescript::module(context),
+        "python" => python::module(context),
+        "python_env" => python_env::module(context),
+        "quarto" => quarto::module(context),
+        "raku" => raku::module(context),
+        "rlang" => rlang::module(context),
+        "red" => red::module(context),
+        "repl" => repl::module(context),
+        "repo_remote" => repo_remote::module(context),
+        "ruby" => ruby::module(context),
+        "rust" => rust::module(context),
+        "scala" => scala::module(context),
+        "shell" => shell::module(context),
+        "shlvl" => shlvl::module(context),
+        "singularity" => singularity::module(context),
+        "solidity" => solidity::module(context),
+        "spack" => spack::module(context),
+        "swift" => swift::module(context),
+        "status" => status::module(context),
+        "sudo" => sudo::module(context),
+        "terraform" => terraform::module(context),
+        "tests" => tests::module(context),
+        "ticket" => ticket::module(context),
+        "time" => time::module(context),
+        "todos" => todos::module(context),
+        "typst" => typst::module(context),
+        "crystal" => crystal::module(context),
+        "username" => username::module(context),
+        "vlang" => vlang::module(context),
+        "vagrant" => vagrant::module(context),
+        "vcsh" => vcsh::module(context),
+        "zig" => zig::module(context),
+        env if env.starts_with("env_var.") => {
+            env_var::module(env.strip_prefix("env_var."), context)
+        }
+        custom if custom.starts_with("custom.") => {
+            // SAFETY: We just checked that the module starts with "custom."
+            custom::module(custom.strip_prefix("custom.").unwrap(), context)
+        }
+        _ => {
+            eprintln!("Error: Unknown module {module}. Use starship module --list to list out all supported modules.");
+            None
+        }
+    }
+}
+
+/// Extracts a human-readable message from a panic payload
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("<unknown panic payload>")
+}
+
 pub fn description(module: &str) -> &'static str {
     match module {
+        "annotations" => "Messages other programs left for the prompt",
         "aws" => "The current AWS region and profile",
         "azure" => "The current Azure subscription",
         "battery" => "The current charge of the device's battery and its current charging status",
         "buf" => "The currently installed version of the Buf CLI",
+        "build_staleness" => "Whether the build of the current directory is up to date",
         "bun" => "The currently installed version of the Bun",
         "c" => "Your C compiler type",
         "character" => {
             "A character (usually an arrow) beside where the text is entered in your terminal"
         }
+        "ci" => "The CI provider, job and attempt when running on a CI runner",
         "cmake" => "The currently installed version of CMake",
         "cmd_duration" => "How long the last command took to execute",
         "cobol" => "The currently installed version of COBOL/GNUCOBOL",
         "conda" => "The current conda environment, if $CONDA_DEFAULT_ENV is set",
         "container" => "The container indicator, if inside a container.",
+        "cpp" => "Your C++ compiler type",
         "crystal" => "The currently installed version of Crystal",
         "daml" => "The Daml SDK version of your project",
         "dart" => "The currently installed version of Dart",
@@ -254,12 +345,16 @@ pub fn description(module: &str) -> &'static str {
         "directory" => "The current working directory",
         "direnv" => "The currently applied direnv file",
         "docker_context" => "The current docker context",
+        "dotenv" => "Whether the dotenv files of the current directory are loaded",
         "dotnet" => "The relevant version of the .NET Core SDK for the current directory",
         "elixir" => "The currently installed versions of Elixir and OTP",
         "elm" => "The currently installed version of Elm",
+        "environment" => "The deployment environment of the current context",
         "erlang" => "Current OTP version",
+        "errors" => "The number of modules that failed or timed out in this prompt",
         "fennel" => "The currently installed version of Fennel",
         "fill" => "Fills the remaining space on the line with a pad string",
+        "formatter_tools" => "Whether the formatters configured in the current directory are installed at the required version",
         "fossil_branch" => "The active branch of the check-out in your current directory",
         "fossil_metrics" => "The currently added/deleted lines in your check-out",
         "gcloud" => "The current GCP client configuration",
@@ -276,7 +371,9 @@ pub fn description(module: &str) -> &'static str {
         "haxe" => "The currently installed version of Haxe",
         "helm" => "The currently installed version of Helm",
         "hg_branch" => "The active branch and topic of the repo in your current directory",
+        "hg_status" => "Symbol representing the current status of the Mercurial repo",
         "hostname" => "The system hostname",
+        "immutable" => "Warns when the current directory is read-only or managed immutably",
         "java" => "The currently installed version of Java",
         "jobs" => "The current number of jobs running",
         "julia" => "The currently installed version of Julia",
@@ -303,12 +400,16 @@ pub fn description(module: &str) -> &'static str {
         "perl" => "The currently installed version of Perl",
         "php" => "The currently installed version of PHP",
         "pijul_channel" => "The current channel of the repo in the current directory",
+        "pre_commit" => "Whether the pre-commit hooks of the current repo are installed",
         "pulumi" => "The current username, stack, and installed version of Pulumi",
         "purescript" => "The currently installed version of PureScript",
         "python" => "The currently installed version of Python",
+        "python_env" => "The stack of active conda, pixi, poetry and virtual environments",
         "quarto" => "The current installed version of quarto",
         "raku" => "The currently installed version of Raku",
         "red" => "The currently installed version of Red",
+        "repl" => "The REPL or editor the shell was started from",
+        "repo_remote" => "The forge and repository of the git remote, and its open pull requests",
         "rlang" => "The currently installed version of R",
         "ruby" => "The currently installed version of Ruby",
         "rust" => "The currently installed version of Rust",
@@ -322,7 +423,10 @@ pub fn description(module: &str) -> &'static str {
         "sudo" => "The sudo credentials are currently cached",
         "swift" => "The currently installed version of Swift",
         "terraform" => "The currently selected terraform workspace and version",
+        "tests" => "The outcome of the last local test run",
+        "ticket" => "The issue key found in the current git branch or commit message",
         "time" => "The current local time",
+        "todos" => "The number of TODO and FIXME markers in the repository",
         "typst" => "The current installed version of typst",
         "username" => "The active user's username",
         "vagrant" => "The currently installed version of Vagrant",
@@ -345,4 +449,14 @@ mod test {
             assert_ne!(description(module), "<no description>");
         }
     }
+
+    #[test]
+    fn panic_message_from_payload() {
+        let payload = panic::catch_unwind(|| panic!("static message")).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "static message");
+
+        let name = "formatted";
+        let payload = panic::catch_unwind(|| panic!("{name} message")).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "formatted message");
+    }
 }
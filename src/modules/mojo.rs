@@ -60,7 +60,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 }
 
 fn get_mojo_version(context: &Context) -> Option<(String, Option<String>)> {
-    let mojo_version_output = context.exec_cmd("mojo", &["--version"])?.stdout;
+    let mojo_version_output = context.exec_version_cmd("mojo", &["--version"])?.stdout;
 
     let version_items = mojo_version_output
         .split_ascii_whitespace()
@@ -31,7 +31,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             })
             .map(|variable| match variable {
                 "version" => context
-                    .exec_cmd("nim", &["--version"])
+                    .exec_version_cmd("nim", &["--version"])
                     .map(|command_output| command_output.stdout)
                     .and_then(|nim_version_output| {
                         let nim_version = parse_nim_version(&nim_version_output)?;
@@ -2,6 +2,7 @@ use super::{Context, Module, ModuleConfig};
 
 use crate::configs::nix_shell::NixShellConfig;
 use crate::formatter::StringFormatter;
+use std::path::Path;
 
 enum NixShellType {
     Pure,
@@ -29,6 +30,15 @@ impl NixShellType {
         }
     }
 
+    /// The raw purity of the shell, independent of the `*_msg` options
+    const fn purity(&self) -> &'static str {
+        match self {
+            NixShellType::Pure => "pure",
+            NixShellType::Impure => "impure",
+            NixShellType::Unknown => "unknown",
+        }
+    }
+
     // Hack to detect if we're in a `nix shell` (in contrast to a `nix-shell`).
     // A better way to do this will be enabled by https://github.com/NixOS/nix/issues/6677.
     fn in_new_nix_shell(context: &Context) -> Option<()> {
@@ -40,6 +50,32 @@ impl NixShellType {
     }
 }
 
+/// The name of the flake devShell, from `$FLAKE_DEVSHELL` or the `$name` set by `nix develop`.
+///
+/// `mkShell` names shells `nix-shell` unless told otherwise, which says nothing about the
+/// devShell, so that name is ignored.
+fn devshell_name(context: &Context) -> Option<String> {
+    context
+        .get_env("FLAKE_DEVSHELL")
+        .or_else(|| context.get_env("name"))
+        .filter(|name| !name.is_empty() && name != "nix-shell")
+}
+
+/// The active user profile: the last, highest priority, entry of `$NIX_PROFILES`
+fn active_profile(context: &Context) -> Option<String> {
+    let profiles = context.get_env("NIX_PROFILES")?;
+    let profile = profiles.split_whitespace().last()?;
+    let home = context.get_home();
+
+    match home
+        .as_deref()
+        .and_then(|home| Path::new(profile).strip_prefix(home).ok())
+    {
+        Some(relative) => Some(Path::new("~").join(relative).to_string_lossy().into_owned()),
+        None => Some(profile.to_string()),
+    }
+}
+
 /// Creates a module showing if inside a nix-shell
 ///
 /// The module will use the `$IN_NIX_SHELL` and `$name` environment variable to
@@ -57,12 +93,15 @@ impl NixShellType {
 ///     - impure         // $name == "" in an impure nix-shell
 ///     - unknown (name) // $name == "name" in an unknown nix-shell
 ///     - unknown        // $name == "" in an unknown nix-shell
+///
+/// `$purity`, `$devshell` and `$profile` are also available for custom formats.
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("nix_shell");
     let config: NixShellConfig = NixShellConfig::try_load(module.config);
 
     let shell_name = context.get_env("name");
     let shell_type = NixShellType::detect_shell_type(config.heuristic, context)?;
+    let purity = shell_type.purity();
     let shell_type_format = match shell_type {
         NixShellType::Pure => config.pure_msg,
         NixShellType::Impure => config.impure_msg,
@@ -81,7 +120,10 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map(|variable| match variable {
-                "name" => shell_name.as_ref().map(Ok),
+                "name" => shell_name.clone().map(Ok),
+                "purity" => Some(Ok(purity.to_string())),
+                "devshell" => devshell_name(context).map(Ok),
+                "profile" => active_profile(context).map(Ok),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -169,6 +211,78 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn purity_ignores_msg() {
+        let actual = ModuleRenderer::new("nix_shell")
+            .env("IN_NIX_SHELL", "impure")
+            .config(toml::toml! {
+                [nix_shell]
+                impure_msg = "⚠"
+                format = "[$purity]($style) "
+            })
+            .collect();
+        let expected = Some(format!("{} ", Color::Blue.bold().paint("impure")));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn flake_devshell() {
+        let actual = ModuleRenderer::new("nix_shell")
+            .env("IN_NIX_SHELL", "impure")
+            .env("name", "nix-shell-env")
+            .env("FLAKE_DEVSHELL", "backend")
+            .config(toml::toml! {
+                [nix_shell]
+                format = "[$symbol$devshell]($style) "
+            })
+            .collect();
+        let expected = Some(format!("{} ", Color::Blue.bold().paint("❄️  backend")));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn devshell_ignores_default_mkshell_name() {
+        let actual = ModuleRenderer::new("nix_shell")
+            .env("IN_NIX_SHELL", "pure")
+            .env("name", "nix-shell")
+            .config(toml::toml! {
+                [nix_shell]
+                format = "[$purity( \\($devshell\\))]($style) "
+            })
+            .collect();
+        let expected = Some(format!("{} ", Color::Blue.bold().paint("pure")));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn active_profile() {
+        let actual = ModuleRenderer::new("nix_shell")
+            .env("IN_NIX_SHELL", "pure")
+            .env("HOME", "/home/user")
+            .env(
+                "NIX_PROFILES",
+                "/nix/var/nix/profiles/default /home/user/.nix-profile",
+            )
+            .config(toml::toml! {
+                [nix_shell]
+                format = "[$profile]($style) "
+            })
+            .collect();
+        let expected = Some(format!(
+            "{} ",
+            Color::Blue.bold().paint(
+                std::path::Path::new("~")
+                    .join(".nix-profile")
+                    .to_string_lossy()
+            )
+        ));
+
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn new_nix_shell() {
         let actual = ModuleRenderer::new("nix_shell")
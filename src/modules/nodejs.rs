@@ -1,12 +1,10 @@
+use super::utils::version::{self, VersionConstraint};
 use super::{Context, Module, ModuleConfig};
 
 use crate::configs::nodejs::NodejsConfig;
 use crate::formatter::{StringFormatter, VersionFormatter};
 
 use once_cell::sync::Lazy;
-use regex::Regex;
-use semver::Version;
-use semver::VersionReq;
 use serde_json as json;
 use std::ops::Deref;
 
@@ -32,10 +30,17 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
     let nodejs_version = Lazy::new(|| {
         context
-            .exec_cmd("node", &["--version"])
+            .exec_version_cmd("node", &["--version"])
             .map(|cmd| cmd.stdout)
     });
     let engines_version = Lazy::new(|| get_engines_version(context));
+    let satisfies = Lazy::new(|| {
+        let constraint = match engines_version.deref() {
+            Some(engines_version) => Some(VersionConstraint::Range(engines_version.clone())),
+            None => get_pinned_version(context),
+        };
+        version::satisfies(nodejs_version.as_deref(), constraint.as_ref())
+    });
 
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
@@ -45,15 +50,10 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             })
             .map_style(|variable| match variable {
                 "style" => {
-                    let in_engines_range = check_engines_version(
-                        nodejs_version.as_deref(),
-                        engines_version.as_deref(),
-                    );
-
-                    if in_engines_range {
-                        Some(Ok(config.style))
-                    } else {
+                    if *satisfies == Some(false) {
                         Some(Ok(config.not_capable_style))
+                    } else {
+                        Some(Ok(config.style))
                     }
                 }
                 _ => None,
@@ -82,6 +82,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
                     (!in_engines_range).then_some(Ok(eng_ver))
                 }
+                "satisfies" => satisfies.map(|satisfies| Ok(satisfies.to_string())),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -106,28 +107,17 @@ fn get_engines_version(context: &Context) -> Option<String> {
     Some(raw_version.to_string())
 }
 
+/// The version in `.nvmrc` or `.node-version`, as nvm, fnm and nodenv read it
+fn get_pinned_version(context: &Context) -> Option<VersionConstraint> {
+    let pinned = context
+        .read_file_from_pwd(".nvmrc")
+        .or_else(|| context.read_file_from_pwd(".node-version"))?;
+    VersionConstraint::pinned(pinned.lines().next()?)
+}
+
 fn check_engines_version(nodejs_version: Option<&str>, engines_version: Option<&str>) -> bool {
-    let (Some(nodejs_version), Some(engines_version)) = (nodejs_version, engines_version) else {
-        return true;
-    };
-
-    let Ok(r) = VersionReq::parse(engines_version) else {
-        return true;
-    };
-
-    let re = Regex::new(r"\d+\.\d+\.\d+").unwrap();
-    let version = re
-        .captures(nodejs_version)
-        .unwrap()
-        .get(0)
-        .unwrap()
-        .as_str();
-
-    let v = match Version::parse(version) {
-        Ok(v) => v,
-        Err(_e) => return true,
-    };
-    r.matches(&v)
+    let constraint = engines_version.map(|range| VersionConstraint::Range(range.to_string()));
+    version::satisfies(nodejs_version, constraint.as_ref()) != Some(false)
 }
 
 #[cfg(test)]
@@ -360,6 +350,26 @@ mod tests {
         dir.close()
     }
 
+    #[test]
+    fn nvmrc_version_not_installed() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join(".nvmrc"), "v20\n")?;
+
+        let actual = ModuleRenderer::new("nodejs")
+            .path(dir.path())
+            .config(toml::toml! {
+                [nodejs]
+                format = "via [$symbol($version )$satisfies ]($style)"
+            })
+            .collect();
+        let expected = Some(format!(
+            "via {}",
+            Color::Red.bold().paint(" v12.0.0 false ")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
     #[test]
     fn no_node_installed() -> io::Result<()> {
         let dir = tempfile::tempdir()?;
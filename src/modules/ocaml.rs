@@ -3,6 +3,7 @@ use once_cell::sync::Lazy;
 use std::ops::Deref;
 use std::path::Path;
 
+use super::utils::version::VersionConstraint;
 use crate::configs::ocaml::OCamlConfig;
 use crate::formatter::StringFormatter;
 use crate::formatter::VersionFormatter;
@@ -30,6 +31,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     }
 
     let opam_switch: Lazy<Option<OpamSwitch>, _> = Lazy::new(|| get_opam_switch(context));
+    let dune_lang = Lazy::new(|| get_dune_lang(context));
 
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
@@ -53,6 +55,15 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                     let (_, name) = opam_switch.deref().as_ref()?;
                     Some(Ok(name.to_string()))
                 }
+                "dune_lang" => dune_lang.deref().clone().map(Ok),
+                "dune_mismatch" => {
+                    let dune_lang = dune_lang.deref().as_ref()?;
+                    let dune_version = context.exec_version_cmd("dune", &["--version"])?.stdout;
+                    let dune_version = dune_version.trim();
+                    let is_supported = VersionConstraint::Minimum(dune_lang.clone())
+                        .is_satisfied_by(dune_version)?;
+                    (!is_supported).then(|| Ok(dune_version.to_string()))
+                }
                 "version" => {
                     let is_esy_project = context
                         .try_begin_scan()?
@@ -62,7 +73,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                     let ocaml_version = if is_esy_project {
                         context.exec_cmd("esy", &["ocaml", "-vnum"])?.stdout
                     } else {
-                        context.exec_cmd("ocaml", &["-vnum"])?.stdout
+                        context.exec_version_cmd("ocaml", &["-vnum"])?.stdout
                     };
                     VersionFormatter::format_module_version(
                         module.get_name(),
@@ -108,9 +119,30 @@ fn parse_opam_switch(opam_switch: &str) -> Option<OpamSwitch> {
     }
 }
 
+/// The version of the dune language the closest `dune-project` declares with `(lang dune 3.11)`
+///
+/// Dune refuses to build projects with a newer version of the language than its own.
+fn get_dune_lang(context: &Context) -> Option<String> {
+    let dune_project = context
+        .current_dir
+        .ancestors()
+        .find_map(|dir| std::fs::read_to_string(dir.join("dune-project")).ok())?;
+    parse_dune_lang(&dune_project)
+}
+
+fn parse_dune_lang(dune_project: &str) -> Option<String> {
+    let start = dune_project.find("(lang")?;
+    let stanza = &dune_project[start + 1..];
+    let stanza = &stanza[..stanza.find(')')?];
+    match stanza.split_whitespace().collect::<Vec<_>>()[..] {
+        ["lang", "dune", version] => Some(version.to_string()),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{parse_opam_switch, SwitchType};
+    use super::{parse_dune_lang, parse_opam_switch, SwitchType};
     use crate::{test::ModuleRenderer, utils::CommandOutput};
     use nu_ansi_term::Color;
     use std::fs::{self, File};
@@ -130,6 +162,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_dune_lang() {
+        assert_eq!(
+            parse_dune_lang("(lang dune 3.11)\n(name my_project)\n"),
+            Some("3.11".to_string())
+        );
+        assert_eq!(
+            parse_dune_lang("; generated\n(lang\n  dune 2.9)"),
+            Some("2.9".to_string())
+        );
+        assert_eq!(parse_dune_lang("(name my_project)"), None);
+    }
+
+    #[test]
+    fn dune_lang_and_mismatch() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let render = || {
+            ModuleRenderer::new("ocaml")
+                .path(dir.path())
+                .config(toml::toml! {
+                    [ocaml]
+                    format = "dune $dune_lang( ⚠ $dune_mismatch)"
+                })
+                .collect()
+        };
+
+        fs::write(dir.path().join("dune-project"), "(lang dune 3.6)\n")?;
+        assert_eq!(render(), Some("dune 3.6".to_string()));
+
+        // The installed dune 3.10.0 doesn't support the newer language
+        fs::write(dir.path().join("dune-project"), "(lang dune 3.11)\n")?;
+        assert_eq!(render(), Some("dune 3.11 ⚠ 3.10.0".to_string()));
+        dir.close()
+    }
+
     #[test]
     fn folder_without_ocaml_file() -> io::Result<()> {
         let dir = tempfile::tempdir()?;
@@ -31,7 +31,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             })
             .map(|variable| match variable {
                 "version" => {
-                    let odin_version = context.exec_cmd("odin", &["version"])?.stdout;
+                    let odin_version = context.exec_version_cmd("odin", &["version"])?.stdout;
                     let trimmed_version = odin_version.split(' ').last()?.trim().to_string();
 
                     if config.show_commit {
@@ -60,7 +60,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
 fn get_opa_version(context: &Context) -> Option<String> {
     let version_output: String = context
-        .exec_cmd("opa", &["version"])
+        .exec_version_cmd("opa", &["version"])
         .map(get_command_string_output)?;
     parse_opa_version(version_output)
 }
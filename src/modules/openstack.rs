@@ -1,5 +1,6 @@
 use yaml_rust2::YamlLoader;
 
+use super::utils::cloud;
 use super::{Context, Module, ModuleConfig};
 
 use crate::configs::openstack::OspConfig;
@@ -9,7 +10,12 @@ use crate::utils;
 type Cloud = String;
 type Project = String;
 
-fn get_osp_project_from_config(context: &Context, osp_cloud: &str) -> Option<Project> {
+/// Reads the setting at `keys` of the cloud from the first `clouds.yaml` that has it
+fn get_osp_setting_from_config(
+    context: &Context,
+    osp_cloud: &str,
+    keys: &[&str],
+) -> Option<String> {
     // Attempt to follow OpenStack standards for clouds.yaml location:
     // 1st = $PWD/clouds.yaml, 2nd = $HOME/.config/openstack/clouds.yaml, 3rd = /etc/openstack/clouds.yaml
     let config = [
@@ -27,7 +33,9 @@ fn get_osp_project_from_config(context: &Context, osp_cloud: &str) -> Option<Pro
         .filter_map(|file| {
             let config = utils::read_file(file.as_ref()?).ok()?;
             let clouds = YamlLoader::load_from_str(config.as_str()).ok()?;
-            clouds.first()?["clouds"][osp_cloud]["auth"]["project_name"]
+            let cloud = &clouds.first()?["clouds"][osp_cloud];
+            keys.iter()
+                .fold(cloud, |setting, key| &setting[*key])
                 .as_str()
                 .map(ToOwned::to_owned)
         })
@@ -41,7 +49,10 @@ fn get_osp_cloud_and_project(context: &Context) -> (Option<Cloud>, Option<Projec
     ) {
         (Some(p), Some(r)) => (Some(p), Some(r)),
         (None, Some(r)) => (None, Some(r)),
-        (Some(ref p), None) => (Some(p.clone()), get_osp_project_from_config(context, p)),
+        (Some(ref p), None) => (
+            Some(p.clone()),
+            get_osp_setting_from_config(context, p, &["auth", "project_name"]),
+        ),
         (None, None) => (None, None),
     }
 }
@@ -53,6 +64,9 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let (osp_cloud, osp_project) = get_osp_cloud_and_project(context);
 
     osp_cloud.as_ref()?;
+    let osp_region = context
+        .get_env("OS_REGION_NAME")
+        .or_else(|| get_osp_setting_from_config(context, osp_cloud.as_deref()?, &["region_name"]));
 
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
@@ -65,8 +79,15 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map(|variable| match variable {
-                "cloud" => osp_cloud.as_ref().map(Ok),
-                "project" => osp_project.as_ref().map(Ok),
+                "cloud" => osp_cloud
+                    .as_deref()
+                    .map(|name| Ok(cloud::alias(name, &config.cloud_aliases))),
+                "project" => osp_project
+                    .as_deref()
+                    .map(|name| Ok(cloud::alias(name, &config.project_aliases))),
+                "region" => osp_region
+                    .as_deref()
+                    .map(|name| Ok(cloud::alias(name, &config.region_aliases))),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -122,6 +143,39 @@ clouds:
         dir.close()
     }
 
+    #[test]
+    fn region_from_config_with_alias() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let config_path = dir.path().join("clouds.yaml");
+        let mut file = File::create(config_path)?;
+        file.write_all(
+            b"---
+clouds:
+  corp:
+    auth:
+      project_name: testproject
+    region_name: eu-de-1
+",
+        )?;
+        let actual = ModuleRenderer::new("openstack")
+            .env("PWD", dir.path().to_str().unwrap())
+            .env("OS_CLOUD", "corp")
+            .config(toml::toml! {
+                [openstack]
+                format = "on [$symbol$cloud(\\($region\\))]($style) "
+                [openstack.region_aliases]
+                eu-de-1 = "frankfurt"
+            })
+            .collect();
+        let expected = Some(format!(
+            "on {} ",
+            Color::Yellow.bold().paint("☁️  corp(frankfurt)")
+        ));
+
+        assert_eq!(actual, expected);
+        dir.close()
+    }
+
     #[test]
     fn parse_broken_config() -> io::Result<()> {
         let dir = tempfile::tempdir()?;
@@ -164,4 +218,24 @@ dummy_yaml
         assert_eq!(actual, expected);
         dir.close()
     }
+
+    #[test]
+    fn cloud_and_project_aliases() {
+        let actual = ModuleRenderer::new("openstack")
+            .env("OS_CLOUD", "corp-overcloud")
+            .env("OS_PROJECT_NAME", "team-payments-prod")
+            .config(toml::toml! {
+                [openstack.cloud_aliases]
+                corp-overcloud = "corp"
+                [openstack.project_aliases]
+                team-payments-prod = "payments"
+            })
+            .collect();
+        let expected = Some(format!(
+            "on {} ",
+            Color::Yellow.bold().paint("☁️  corp(payments)")
+        ));
+
+        assert_eq!(actual, expected);
+    }
 }
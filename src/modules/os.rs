@@ -2,6 +2,7 @@ use super::{Context, Module, ModuleConfig};
 
 use crate::configs::os::OSConfig;
 use crate::formatter::StringFormatter;
+use once_cell::sync::Lazy;
 
 /// Creates a module with the current operating system
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
@@ -18,10 +19,19 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     #[cfg(test)]
     let os = os_info::Info::default();
 
+    // Inside a container, describe the distribution of the container rather than the host
+    let os_release = Lazy::new(|| OsRelease::read(context));
+    let os_type = Lazy::new(|| {
+        context
+            .container()
+            .and_then(|_| os_release.os_type())
+            .unwrap_or_else(|| os.os_type())
+    });
+
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
             .map_meta(|variable, _| match variable {
-                "symbol" => get_symbol(&config, &os.os_type()),
+                "symbol" => get_symbol(&config, &os_type),
                 _ => None,
             })
             .map_style(|variable| match variable {
@@ -29,10 +39,16 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map(|variable| match variable {
-                "codename" => get_codename(&os).map(Ok),
+                "arch" => os.architecture().map(|arch| Ok(arch.to_string())),
+                "arch_translated" => is_translated(context).then(|| Ok("rosetta".to_string())),
+                "base_image" => context.container().and_then(|_| os_release.image()).map(Ok),
+                "build" => os_release.build_id.clone().map(Ok),
+                "codename" => get_codename(&os)
+                    .or_else(|| os_release.version_codename.clone())
+                    .map(Ok),
                 "edition" => get_edition(&os).map(Ok),
-                "name" => get_name(&os).map(Ok),
-                "type" => get_type(&os).map(Ok),
+                "name" => get_name(&os_type).map(Ok),
+                "type" => get_type(&os_type).map(Ok),
                 "version" => get_version(&os).map(Ok),
                 _ => None,
             })
@@ -57,6 +73,19 @@ fn get_symbol<'a>(config: &'a OSConfig, os_type: &os_info::Type) -> Option<&'a s
         .or_else(|| OSConfig::default().get_symbol(os_type))
 }
 
+/// Whether the shell runs as an Intel binary translated by Rosetta 2 on Apple Silicon
+#[cfg(target_os = "macos")]
+fn is_translated(context: &Context) -> bool {
+    context
+        .exec_cmd("sysctl", &["-n", "sysctl.proc_translated"])
+        .map_or(false, |output| output.stdout.trim() == "1")
+}
+
+#[cfg(not(target_os = "macos"))]
+fn is_translated(_context: &Context) -> bool {
+    false
+}
+
 fn get_codename(os: &os_info::Info) -> Option<String> {
     os.codename().map(String::from)
 }
@@ -65,13 +94,13 @@ fn get_edition(os: &os_info::Info) -> Option<String> {
     os.edition().map(String::from)
 }
 
-fn get_name(os: &os_info::Info) -> Option<String> {
-    Some(os.os_type().to_string())
+fn get_name(os_type: &os_info::Type) -> Option<String> {
+    Some(os_type.to_string())
 }
 
-fn get_type(os: &os_info::Info) -> Option<String> {
+fn get_type(os_type: &os_info::Type) -> Option<String> {
     // String from os_info::Type
-    Some(format!("{:?}", os.os_type()))
+    Some(format!("{os_type:?}"))
 }
 
 fn get_version(os: &os_info::Info) -> Option<String> {
@@ -80,10 +109,95 @@ fn get_version(os: &os_info::Info) -> Option<String> {
         .map(os_info::Version::to_string)
 }
 
+/// The fields of `/etc/os-release` that are not exposed by `os_info`
+#[derive(Debug, Default, PartialEq, Eq)]
+struct OsRelease {
+    id: Option<String>,
+    version_id: Option<String>,
+    version_codename: Option<String>,
+    build_id: Option<String>,
+}
+
+impl OsRelease {
+    #[cfg(target_os = "linux")]
+    fn read(context: &Context) -> Self {
+        crate::utils::read_file(crate::utils::context_path(context, "/etc/os-release"))
+            .map(|contents| Self::parse(&contents))
+            .unwrap_or_default()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn read(_context: &Context) -> Self {
+        Self::default()
+    }
+
+    fn parse(contents: &str) -> Self {
+        let mut os_release = Self::default();
+        for line in contents.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+            if value.is_empty() {
+                continue;
+            }
+            let field = match key.trim() {
+                "ID" => &mut os_release.id,
+                "VERSION_ID" => &mut os_release.version_id,
+                "VERSION_CODENAME" => &mut os_release.version_codename,
+                "BUILD_ID" => &mut os_release.build_id,
+                _ => continue,
+            };
+            *field = Some(value.to_string());
+        }
+        os_release
+    }
+
+    /// The image the system is most likely based on, e.g. `alpine:3.19`
+    fn image(&self) -> Option<String> {
+        let id = self.id.as_deref()?;
+        Some(match &self.version_id {
+            Some(version) => format!("{id}:{version}"),
+            None => id.to_string(),
+        })
+    }
+
+    /// Maps the `ID` field to an `os_info` type, following the mapping used by `os_info`
+    fn os_type(&self) -> Option<os_info::Type> {
+        use os_info::Type;
+
+        Some(match self.id.as_deref()? {
+            "almalinux" => Type::AlmaLinux,
+            "alpaquita" => Type::Alpaquita,
+            "alpine" => Type::Alpine,
+            "amzn" => Type::Amazon,
+            "arch" | "archarm" => Type::Arch,
+            "artix" => Type::Artix,
+            "centos" => Type::CentOS,
+            "debian" => Type::Debian,
+            "fedora" => Type::Fedora,
+            "gentoo" => Type::Gentoo,
+            "kali" => Type::Kali,
+            "linuxmint" => Type::Mint,
+            "mariner" => Type::Mariner,
+            "nixos" => Type::NixOS,
+            "ol" => Type::OracleLinux,
+            "opensuse" | "opensuse-leap" | "opensuse-tumbleweed" => Type::openSUSE,
+            "rhel" => Type::RedHatEnterprise,
+            "rocky" => Type::RockyLinux,
+            "sled" | "sles" | "sles_sap" => Type::SUSE,
+            "ubuntu" => Type::Ubuntu,
+            "void" => Type::Void,
+            _ => return None,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::test::ModuleRenderer;
+    use crate::utils::CommandOutput;
     use nu_ansi_term::Color;
     use os_info::Type;
 
@@ -126,6 +240,28 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    #[cfg(target_os = "macos")]
+    fn rosetta() {
+        let actual = ModuleRenderer::new("os")
+            .config(toml::toml! {
+                [os]
+                disabled = false
+            })
+            .cmd(
+                "sysctl -n sysctl.proc_translated",
+                Some(CommandOutput {
+                    stdout: String::from("1\n"),
+                    stderr: String::default(),
+                }),
+            )
+            .collect();
+
+        let expected = Some(format!("{}", Color::White.bold().paint("❓ rosetta ")));
+
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn get_symbol_default() {
         let config = OSConfig::default();
@@ -378,4 +514,57 @@ mod tests {
             _ => "",
         };
     }
+
+    #[test]
+    fn parse_os_release() {
+        let os_release = OsRelease::parse(
+            "NAME=\"Alpine Linux\"\nID=alpine\nVERSION_ID=3.19.1\nBUILD_ID='20240126'\n",
+        );
+
+        assert_eq!(os_release.os_type(), Some(Type::Alpine));
+        assert_eq!(os_release.image(), Some("alpine:3.19.1".to_string()));
+        assert_eq!(os_release.build_id, Some("20240126".to_string()));
+        assert_eq!(os_release.version_codename, None);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn container_base_image() {
+        let actual = ModuleRenderer::new("os")
+            .file("/run/.containerenv", "")
+            .file(
+                "/etc/os-release",
+                "ID=debian\nVERSION_ID=\"12\"\nVERSION_CODENAME=bookworm\n",
+            )
+            .config(toml::toml! {
+                [os]
+                disabled = false
+                format = "[$symbol$base_image( $codename)]($style)"
+            })
+            .collect();
+
+        let expected = Some(format!(
+            "{}",
+            Color::White.bold().paint("🌀 debian:12 bookworm")
+        ));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn no_base_image_outside_container() {
+        let actual = ModuleRenderer::new("os")
+            .file("/etc/os-release", "ID=debian\nVERSION_ID=\"12\"\n")
+            .config(toml::toml! {
+                [os]
+                disabled = false
+                format = "[$symbol($base_image)]($style)"
+            })
+            .collect();
+
+        let expected = Some(format!("{}", Color::White.bold().paint("❓ ")));
+
+        assert_eq!(actual, expected);
+    }
 }
@@ -1,6 +1,6 @@
 use super::{Context, Module, ModuleConfig};
 use crate::configs::package::PackageConfig;
-use crate::formatter::{StringFormatter, VersionFormatter};
+use crate::formatter::{StringFormatter, VariableHolder, VersionFormatter};
 
 use ini::Ini;
 use quick_xml::events::Event as QXEvent;
@@ -24,10 +24,14 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             })
             .map_style(|variable| match variable {
                 "style" => Some(Ok(config.style)),
+                "windows_drive_style" => Some(Ok(config.windows_drive_style)),
                 _ => None,
             })
             .map(|variable| match variable {
-                "version" => Some(Ok(&module_version)),
+                "version" => Some(Ok(module_version.as_str())),
+                "windows_drive" => context
+                    .on_windows_drive()
+                    .then_some(Ok(config.windows_drive)),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -59,7 +63,7 @@ fn get_node_package_version(context: &Context, config: &PackageConfig) -> Option
         return None;
     };
 
-    let formatted_version = format_version(raw_version, config.version_format)?;
+    let formatted_version = format_version(context, raw_version, config.version_format)?;
     if formatted_version == "v0.0.0-development" || formatted_version.starts_with("v0.0.0-semantic")
     {
         return Some("semantic".to_string());
@@ -86,7 +90,7 @@ fn get_pyproject_version(context: &Context, config: &PackageConfig) -> Option<St
 
     get_pep621_version(&pyproject_toml)
         .or_else(|| get_poetry_version(&pyproject_toml))
-        .and_then(|raw_version| format_version(raw_version, config.version_format))
+        .and_then(|raw_version| format_version(context, raw_version, config.version_format))
 }
 
 fn get_setup_cfg_version(context: &Context, config: &PackageConfig) -> Option<String> {
@@ -97,7 +101,7 @@ fn get_setup_cfg_version(context: &Context, config: &PackageConfig) -> Option<St
     if raw_version.starts_with("attr:") || raw_version.starts_with("file:") {
         None
     } else {
-        format_version(raw_version, config.version_format)
+        format_version(context, raw_version, config.version_format)
     }
 }
 
@@ -107,12 +111,12 @@ fn get_gradle_version(context: &Context, config: &PackageConfig) -> Option<Strin
         .and_then(|contents| {
             let re = Regex::new(r"(?m)^\s*version\s*=\s*(?P<version>.*)").unwrap();
             let caps = re.captures(&contents)?;
-            format_version(&caps["version"], config.version_format)
+            format_version(context, &caps["version"], config.version_format)
         }).or_else(|| {
             let build_file_contents = context.read_file_from_pwd("build.gradle")?;
             let re = Regex::new(r#"(?m)^version( |\s*=\s*)['"](?P<version>[^'"]+)['"]$"#).unwrap(); /*dark magic*/
             let caps = re.captures(&build_file_contents)?;
-            format_version(&caps["version"], config.version_format)
+            format_version(context, &caps["version"], config.version_format)
 
         })
 }
@@ -122,7 +126,7 @@ fn get_composer_version(context: &Context, config: &PackageConfig) -> Option<Str
     let composer_json: json::Value = json::from_str(&file_contents).ok()?;
     let raw_version = composer_json.get("version")?.as_str()?;
 
-    format_version(raw_version, config.version_format)
+    format_version(context, raw_version, config.version_format)
 }
 
 fn get_julia_project_version(context: &Context, config: &PackageConfig) -> Option<String> {
@@ -130,7 +134,7 @@ fn get_julia_project_version(context: &Context, config: &PackageConfig) -> Optio
     let project_toml: toml::Table = toml::from_str(&file_contents).ok()?;
     let raw_version = project_toml.get("version")?.as_str()?;
 
-    format_version(raw_version, config.version_format)
+    format_version(context, raw_version, config.version_format)
 }
 
 fn get_helm_package_version(context: &Context, config: &PackageConfig) -> Option<String> {
@@ -138,7 +142,7 @@ fn get_helm_package_version(context: &Context, config: &PackageConfig) -> Option
     let yaml = yaml_rust2::YamlLoader::load_from_str(&file_contents).ok()?;
     let version = yaml.first()?["version"].as_str()?;
 
-    format_version(version, config.version_format)
+    format_version(context, version, config.version_format)
 }
 
 fn get_mix_version(context: &Context, config: &PackageConfig) -> Option<String> {
@@ -146,7 +150,7 @@ fn get_mix_version(context: &Context, config: &PackageConfig) -> Option<String>
     let re = Regex::new(r#"(?m)version: "(?P<version>[^"]+)""#).unwrap();
     let caps = re.captures(&file_contents)?;
 
-    format_version(&caps["version"], config.version_format)
+    format_version(context, &caps["version"], config.version_format)
 }
 
 fn get_maven_version(context: &Context, config: &PackageConfig) -> Option<String> {
@@ -171,7 +175,9 @@ fn get_maven_version(context: &Context, config: &PackageConfig) -> Option<String
                 let ver = t.unescape().ok().map(std::borrow::Cow::into_owned);
                 return match ver {
                     // Ignore version which is just a property reference
-                    Some(ref v) if !v.starts_with('$') => format_version(v, config.version_format),
+                    Some(ref v) if !v.starts_with('$') => {
+                        format_version(context, v, config.version_format)
+                    }
                     _ => None,
                 };
             }
@@ -197,14 +203,14 @@ fn get_meson_version(context: &Context, config: &PackageConfig) -> Option<String
     let re = Regex::new(r"project\([^())]*,version:'(?P<version>[^']+)'[^())]*\)").unwrap();
     let caps = re.captures(&file_contents)?;
 
-    format_version(&caps["version"], config.version_format)
+    format_version(context, &caps["version"], config.version_format)
 }
 
 fn get_vmod_version(context: &Context, config: &PackageConfig) -> Option<String> {
     let file_contents = context.read_file_from_pwd("v.mod")?;
     let re = Regex::new(r"(?m)^\s*version\s*:\s*'(?P<version>[^']+)'").unwrap();
     let caps = re.captures(&file_contents)?;
-    format_version(&caps["version"], config.version_format)
+    format_version(context, &caps["version"], config.version_format)
 }
 
 fn get_vpkg_version(context: &Context, config: &PackageConfig) -> Option<String> {
@@ -212,14 +218,14 @@ fn get_vpkg_version(context: &Context, config: &PackageConfig) -> Option<String>
     let vpkg_json: json::Value = json::from_str(&file_contents).ok()?;
     let raw_version = vpkg_json.get("version")?.as_str()?;
 
-    format_version(raw_version, config.version_format)
+    format_version(context, raw_version, config.version_format)
 }
 
 fn get_sbt_version(context: &Context, config: &PackageConfig) -> Option<String> {
     let file_contents = context.read_file_from_pwd("build.sbt")?;
     let re = Regex::new(r"(?m)^(.*/)*\s*version\s*:=\s*.(?P<version>[\d\.]+)").unwrap();
     let caps = re.captures(&file_contents)?;
-    format_version(&caps["version"], config.version_format)
+    format_version(context, &caps["version"], config.version_format)
 }
 
 fn get_cargo_version(context: &Context, config: &PackageConfig) -> Option<String> {
@@ -263,7 +269,7 @@ fn get_cargo_version(context: &Context, config: &PackageConfig) -> Option<String
             .as_str()?
     };
 
-    format_version(raw_version, config.version_format)
+    format_version(context, raw_version, config.version_format)
 }
 
 fn get_nimble_version(context: &Context, config: &PackageConfig) -> Option<String> {
@@ -280,7 +286,7 @@ fn get_nimble_version(context: &Context, config: &PackageConfig) -> Option<Strin
 
     let raw_version = nimble_json.get("version")?.as_str()?;
 
-    format_version(raw_version, config.version_format)
+    format_version(context, raw_version, config.version_format)
 }
 
 fn get_shard_version(context: &Context, config: &PackageConfig) -> Option<String> {
@@ -289,7 +295,7 @@ fn get_shard_version(context: &Context, config: &PackageConfig) -> Option<String
     let data = yaml_rust2::YamlLoader::load_from_str(&file_contents).ok()?;
     let raw_version = data.first()?["version"].as_str()?;
 
-    format_version(raw_version, config.version_format)
+    format_version(context, raw_version, config.version_format)
 }
 
 fn get_daml_project_version(context: &Context, config: &PackageConfig) -> Option<String> {
@@ -298,7 +304,7 @@ fn get_daml_project_version(context: &Context, config: &PackageConfig) -> Option
     let daml_yaml = yaml_rust2::YamlLoader::load_from_str(&file_contents).ok()?;
     let raw_version = daml_yaml.first()?["version"].as_str()?;
 
-    format_version(raw_version, config.version_format)
+    format_version(context, raw_version, config.version_format)
 }
 
 fn get_dart_pub_version(context: &Context, config: &PackageConfig) -> Option<String> {
@@ -307,14 +313,14 @@ fn get_dart_pub_version(context: &Context, config: &PackageConfig) -> Option<Str
     let data = yaml_rust2::YamlLoader::load_from_str(&file_contents).ok()?;
     let raw_version = data.first()?["version"].as_str()?;
 
-    format_version(raw_version, config.version_format)
+    format_version(context, raw_version, config.version_format)
 }
 
 fn get_rlang_version(context: &Context, config: &PackageConfig) -> Option<String> {
     let file_contents = context.read_file_from_pwd("DESCRIPTION")?;
     let re = Regex::new(r"(?m)^Version:\s*(?P<version>.*$)").unwrap();
     let caps = re.captures(&file_contents)?;
-    format_version(&caps["version"], config.version_format)
+    format_version(context, &caps["version"], config.version_format)
 }
 
 fn get_version(context: &Context, config: &PackageConfig) -> Option<String> {
@@ -343,20 +349,120 @@ fn get_version(context: &Context, config: &PackageConfig) -> Option<String> {
     package_version_fn.iter().find_map(|f| f(context, config))
 }
 
-fn format_version(version: &str, version_format: &str) -> Option<String> {
+fn format_version(context: &Context, version: &str, version_format: &str) -> Option<String> {
     let cleaned = version
         .replace('"', "")
         .trim()
         .trim_start_matches('v')
         .to_string();
 
-    VersionFormatter::format_module_version("package", &cleaned, version_format)
+    let uses_vcs = StringFormatter::new(version_format).map_or(false, |formatter| {
+        formatter
+            .get_variables()
+            .iter()
+            .any(|variable| matches!(variable.as_str(), "commits" | "hash" | "dirty"))
+    });
+    if !uses_vcs {
+        return VersionFormatter::format_module_version("package", &cleaned, version_format);
+    }
+
+    let describe = describe(context);
+    let formatted = VersionFormatter::new(version_format).and_then(|formatter| {
+        formatter.format_with(&cleaned, |variable| {
+            describe.as_ref()?.get_variable(variable, &cleaned)
+        })
+    });
+    match formatted {
+        Ok(formatted) => Some(formatted),
+        Err(error) => {
+            log::warn!("Error formatting `package` version:\n{}", error);
+            Some(format!("v{cleaned}"))
+        }
+    }
+}
+
+/// Where the working tree is relative to the closest tag, as reported by `git describe`
+#[derive(Debug, PartialEq, Eq)]
+struct Describe {
+    tag: Option<String>,
+    commits: usize,
+    hash: String,
+    dirty: bool,
+}
+
+impl Describe {
+    /// Parses output like `v1.2.3-3-gabc1234-dirty`, or `abc1234` when there is no tag
+    fn parse(output: &str) -> Option<Self> {
+        let output = output.trim();
+        let (rest, dirty) = match output.strip_suffix("-dirty") {
+            Some(rest) => (rest, true),
+            None => (output, false),
+        };
+        if rest.is_empty() {
+            return None;
+        }
+
+        let Some((rest, hash)) = rest.rsplit_once("-g") else {
+            return Some(Self {
+                tag: None,
+                commits: 0,
+                hash: rest.to_string(),
+                dirty,
+            });
+        };
+        let (tag, commits) = rest.rsplit_once('-')?;
+        Some(Self {
+            tag: Some(tag.to_string()),
+            commits: commits.parse().ok()?,
+            hash: hash.to_string(),
+            dirty,
+        })
+    }
+
+    /// The commits since the tag only qualify the version if the tag is for that version,
+    /// e.g. `v1.2.3`, `1.2.3` or `my-package@1.2.3`
+    fn is_version_tag(&self, version: &str) -> bool {
+        self.tag
+            .as_deref()
+            .and_then(|tag| tag.strip_suffix(version))
+            .map_or(false, |prefix| {
+                prefix.is_empty() || prefix.ends_with(['v', '@', '-', '/'])
+            })
+    }
+
+    fn get_variable(&self, variable: &str, version: &str) -> Option<String> {
+        match variable {
+            "dirty" => self.dirty.then(|| "dirty".to_string()),
+            "commits" if self.commits > 0 && self.is_version_tag(version) => {
+                Some(self.commits.to_string())
+            }
+            "hash" if self.commits > 0 && self.is_version_tag(version) => Some(self.hash.clone()),
+            _ => None,
+        }
+    }
+}
+
+fn describe(context: &Context) -> Option<Describe> {
+    let repo = context.get_repo().ok()?;
+    let output = repo.exec_git(
+        context,
+        &[
+            "describe",
+            "--tags",
+            "--long",
+            "--dirty",
+            "--always",
+            "--abbrev=7",
+        ],
+    )?;
+    Describe::parse(&output.stdout)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{test::ModuleRenderer, utils::CommandOutput};
+    use crate::test::{default_context, fixture_repo, FixtureProvider, ModuleRenderer};
+    use crate::utils::{create_command, CommandOutput};
     use nu_ansi_term::Color;
     use std::fs::File;
     use std::io;
@@ -365,32 +471,72 @@ mod tests {
 
     #[test]
     fn test_format_version() {
+        let context = default_context();
         let raw_expected = Some(String::from("v1.2.3"));
 
-        assert_eq!(format_version("1.2.3", "v${raw}"), raw_expected);
-        assert_eq!(format_version(" 1.2.3 ", "v${raw}"), raw_expected);
-        assert_eq!(format_version("1.2.3 ", "v${raw}"), raw_expected);
-        assert_eq!(format_version(" 1.2.3", "v${raw}"), raw_expected);
-        assert_eq!(format_version("\"1.2.3\"", "v${raw}"), raw_expected);
+        assert_eq!(format_version(&context, "1.2.3", "v${raw}"), raw_expected);
+        assert_eq!(format_version(&context, " 1.2.3 ", "v${raw}"), raw_expected);
+        assert_eq!(format_version(&context, "1.2.3 ", "v${raw}"), raw_expected);
+        assert_eq!(format_version(&context, " 1.2.3", "v${raw}"), raw_expected);
+        assert_eq!(
+            format_version(&context, "\"1.2.3\"", "v${raw}"),
+            raw_expected
+        );
 
-        assert_eq!(format_version("v1.2.3", "v${raw}"), raw_expected);
-        assert_eq!(format_version(" v1.2.3 ", "v${raw}"), raw_expected);
-        assert_eq!(format_version(" v1.2.3", "v${raw}"), raw_expected);
-        assert_eq!(format_version("v1.2.3 ", "v${raw}"), raw_expected);
-        assert_eq!(format_version("\"v1.2.3\"", "v${raw}"), raw_expected);
+        assert_eq!(format_version(&context, "v1.2.3", "v${raw}"), raw_expected);
+        assert_eq!(
+            format_version(&context, " v1.2.3 ", "v${raw}"),
+            raw_expected
+        );
+        assert_eq!(format_version(&context, " v1.2.3", "v${raw}"), raw_expected);
+        assert_eq!(format_version(&context, "v1.2.3 ", "v${raw}"), raw_expected);
+        assert_eq!(
+            format_version(&context, "\"v1.2.3\"", "v${raw}"),
+            raw_expected
+        );
 
         let major_expected = Some(String::from("v1"));
-        assert_eq!(format_version("1.2.3", "v${major}"), major_expected);
-        assert_eq!(format_version(" 1.2.3 ", "v${major}"), major_expected);
-        assert_eq!(format_version("1.2.3 ", "v${major}"), major_expected);
-        assert_eq!(format_version(" 1.2.3", "v${major}"), major_expected);
-        assert_eq!(format_version("\"1.2.3\"", "v${major}"), major_expected);
+        assert_eq!(
+            format_version(&context, "1.2.3", "v${major}"),
+            major_expected
+        );
+        assert_eq!(
+            format_version(&context, " 1.2.3 ", "v${major}"),
+            major_expected
+        );
+        assert_eq!(
+            format_version(&context, "1.2.3 ", "v${major}"),
+            major_expected
+        );
+        assert_eq!(
+            format_version(&context, " 1.2.3", "v${major}"),
+            major_expected
+        );
+        assert_eq!(
+            format_version(&context, "\"1.2.3\"", "v${major}"),
+            major_expected
+        );
 
-        assert_eq!(format_version("v1.2.3", "v${major}"), major_expected);
-        assert_eq!(format_version(" v1.2.3 ", "v${major}"), major_expected);
-        assert_eq!(format_version(" v1.2.3", "v${major}"), major_expected);
-        assert_eq!(format_version("v1.2.3 ", "v${major}"), major_expected);
-        assert_eq!(format_version("\"v1.2.3\"", "v${major}"), major_expected);
+        assert_eq!(
+            format_version(&context, "v1.2.3", "v${major}"),
+            major_expected
+        );
+        assert_eq!(
+            format_version(&context, " v1.2.3 ", "v${major}"),
+            major_expected
+        );
+        assert_eq!(
+            format_version(&context, " v1.2.3", "v${major}"),
+            major_expected
+        );
+        assert_eq!(
+            format_version(&context, "v1.2.3 ", "v${major}"),
+            major_expected
+        );
+        assert_eq!(
+            format_version(&context, "\"v1.2.3\"", "v${major}"),
+            major_expected
+        );
     }
 
     #[test]
@@ -712,6 +858,39 @@ license = "MIT"
         project_dir.close()
     }
 
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_package_on_windows_drive_in_wsl() -> io::Result<()> {
+        let mount_root = create_project_dir()?;
+        let project_dir = mount_root.path().join("c");
+        std::fs::create_dir(&project_dir)?;
+        std::fs::write(
+            project_dir.join("package.json"),
+            json::json!({ "name": "starship", "version": "0.1.0" }).to_string(),
+        )?;
+
+        let actual = ModuleRenderer::new("package")
+            .env("WSL_DISTRO_NAME", "Ubuntu")
+            .file(
+                "/etc/wsl.conf",
+                format!("[automount]\nroot = {}\n", mount_root.path().display()),
+            )
+            .path(&project_dir)
+            .config(toml::toml! {
+                [package]
+                format = "is [$symbol$version]($style)[$windows_drive]($windows_drive_style) "
+            })
+            .collect();
+        let expected = Some(format!(
+            "is {}{} ",
+            Color::Fixed(208).bold().paint("📦 v0.1.0"),
+            Color::Yellow.paint(" 🐢")
+        ));
+
+        assert_eq!(actual, expected);
+        mount_root.close()
+    }
+
     #[test]
     fn test_node_package_version_with_semantic_other_version() -> io::Result<()> {
         let config_name = "package.json";
@@ -1442,6 +1621,67 @@ Title: Starship
         expect_output(&project_dir, Some("v1.0.0"), None);
         project_dir.close()
     }
+    #[test]
+    fn test_parse_describe() {
+        assert_eq!(
+            Describe::parse("v1.2.3-3-gabc1234-dirty\n"),
+            Some(Describe {
+                tag: Some("v1.2.3".to_string()),
+                commits: 3,
+                hash: "abc1234".to_string(),
+                dirty: true,
+            })
+        );
+        assert_eq!(
+            Describe::parse("abc1234"),
+            Some(Describe {
+                tag: None,
+                commits: 0,
+                hash: "abc1234".to_string(),
+                dirty: false,
+            })
+        );
+        assert_eq!(Describe::parse(""), None);
+
+        let describe = Describe::parse("my-package@1.2.3-1-gabc1234").unwrap();
+        assert!(describe.is_version_tag("1.2.3"));
+        assert!(!describe.is_version_tag("11.2.3"));
+        assert!(!describe.is_version_tag("2.3"));
+    }
+
+    #[test]
+    fn test_version_qualifiers() -> io::Result<()> {
+        let repo_dir = fixture_repo(FixtureProvider::Git)?;
+        let git = |args: &[&str]| -> io::Result<String> {
+            let output = create_command("git")?
+                .args(args)
+                .current_dir(repo_dir.path())
+                .output()?;
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        };
+        git(&["tag", "v1.0.0", "HEAD~1"])?;
+        let hash = git(&["rev-parse", "--short=7", "HEAD"])?;
+        fill_config(&repo_dir, "package.json", Some(r#"{"version": "1.0.0"}"#))?;
+        let config = toml::toml! {
+            [package]
+            version_format = "v${raw}(-${commits}-g${hash})(+${dirty})"
+        };
+
+        expect_output(
+            &repo_dir,
+            Some(&format!("v1.0.0-1-g{hash}")),
+            Some(config.clone()),
+        );
+
+        fill_config(&repo_dir, "readme.md", Some("changed"))?;
+        expect_output(
+            &repo_dir,
+            Some(&format!("v1.0.0-1-g{hash}+dirty")),
+            Some(config),
+        );
+        repo_dir.close()
+    }
+
     fn create_project_dir() -> io::Result<TempDir> {
         tempfile::tempdir()
     }
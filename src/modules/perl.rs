@@ -32,7 +32,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             .map(|variable| match variable {
                 "version" => {
                     let perl_version = context
-                        .exec_cmd("perl", &["-e", "printf q#%vd#,$^V;"])?
+                        .exec_version_cmd("perl", &["-e", "printf q#%vd#,$^V;"])?
                         .stdout;
                     VersionFormatter::format_module_version(
                         module.get_name(),
@@ -1,9 +1,14 @@
+use super::utils::version::{self, VersionConstraint};
 use super::{Context, Module, ModuleConfig};
 
 use crate::configs::php::PhpConfig;
 use crate::formatter::StringFormatter;
 use crate::formatter::VersionFormatter;
 
+use once_cell::sync::Lazy;
+use serde_json as json;
+use std::ops::Deref;
+
 /// Creates a module with the current PHP version
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("php");
@@ -19,6 +24,25 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
+    let php_version = Lazy::new(|| {
+        context
+            .exec_version_cmd(
+                "php",
+                &[
+                    "-nr",
+                    "echo PHP_MAJOR_VERSION.\".\".PHP_MINOR_VERSION.\".\".PHP_RELEASE_VERSION;",
+                ],
+            )
+            .map(|output| output.stdout)
+    });
+    let composer_version = Lazy::new(|| get_composer_php_requirement(context));
+    let satisfies = Lazy::new(|| {
+        let constraint = composer_version
+            .as_deref()
+            .map(|requirement| VersionConstraint::Range(composer_to_range(requirement)));
+        version::satisfies(php_version.as_deref(), constraint.as_ref())
+    });
+
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
             .map_meta(|variable, _| match variable {
@@ -26,20 +50,30 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map_style(|variable| match variable {
-                "style" => Some(Ok(config.style)),
+                "style" => {
+                    if *satisfies == Some(false) {
+                        Some(Ok(config.not_capable_style))
+                    } else {
+                        Some(Ok(config.style))
+                    }
+                }
                 _ => None,
             })
             .map(|variable| match variable {
                 "version" => {
-                    let php_version = context.exec_cmd(
-                        "php",
-                        &[
-                            "-nr",
-                            "echo PHP_MAJOR_VERSION.\".\".PHP_MINOR_VERSION.\".\".PHP_RELEASE_VERSION;",
-                        ],
-                    )?.stdout;
-                    VersionFormatter::format_module_version(module.get_name(), &php_version, config.version_format).map(Ok)
+                    let php_version = php_version.deref().as_ref()?;
+                    VersionFormatter::format_module_version(
+                        module.get_name(),
+                        php_version,
+                        config.version_format,
+                    )
+                    .map(Ok)
+                }
+                "composer_version" => {
+                    let requirement = composer_version.deref().clone()?;
+                    (*satisfies == Some(false)).then_some(Ok(requirement))
                 }
+                "satisfies" => satisfies.map(|satisfies| Ok(satisfies.to_string())),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -56,8 +90,26 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// The PHP versions the project supports, from `require.php` of `composer.json`
+fn get_composer_php_requirement(context: &Context) -> Option<String> {
+    let composer_json: json::Value =
+        json::from_str(&context.read_file_from_pwd("composer.json")?).ok()?;
+    let requirement = composer_json.get("require")?.get("php")?.as_str()?;
+    Some(requirement.to_string())
+}
+
+/// Rewrites the operators of Composer that mean something else for npm and Cargo: `|` as a
+/// shorter `||`, and `~8.1`, which allows all of PHP 8 like `~> 8.1`
+fn composer_to_range(requirement: &str) -> String {
+    requirement
+        .replace("||", "|")
+        .replace('|', "||")
+        .replace('~', "~>")
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::test::ModuleRenderer;
     use nu_ansi_term::Color;
     use std::fs::File;
@@ -118,4 +170,35 @@ mod tests {
         assert_eq!(expected, actual);
         dir.close()
     }
+
+    #[test]
+    fn composer_requires_newer_php() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(
+            dir.path().join("composer.json"),
+            r#"{"require": {"php": "^8.1 | ^8.2"}}"#,
+        )?;
+
+        let actual = ModuleRenderer::new("php")
+            .path(dir.path())
+            .config(toml::toml! {
+                [php]
+                format = "via [$symbol($version )($composer_version )]($style)"
+            })
+            .collect();
+
+        let expected = Some(format!(
+            "via {}",
+            Color::Red.bold().paint("🐘 v7.3.8 ^8.1 | ^8.2 ")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn composer_tilde_requirement() {
+        let range = VersionConstraint::Range(composer_to_range("~7.1"));
+        assert_eq!(range.is_satisfied_by("7.3.8"), Some(true));
+        assert_eq!(range.is_satisfied_by("8.0.0"), Some(false));
+    }
 }
@@ -0,0 +1,302 @@
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::pre_commit::PreCommitConfig;
+use crate::formatter::StringFormatter;
+use crate::utils::read_file;
+
+const PRE_COMMIT_CONFIG: &str = ".pre-commit-config.yaml";
+
+/// The marker pre-commit writes into the git hooks it installs
+const PRE_COMMIT_HOOK_MARKER: &str = "File generated by pre-commit";
+
+/// Creates a module showing whether the pre-commit hooks of the current repo are installed
+///
+/// With `check_stale` enabled, the hooks are also reported as stale if the configuration
+/// changed after pre-commit last updated its hook environments.
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("pre_commit");
+    let config: PreCommitConfig = PreCommitConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled {
+        return None;
+    };
+
+    let repo = context.get_repo().ok()?;
+    let workdir = repo.workdir.as_ref()?;
+    let config_path = workdir.join(PRE_COMMIT_CONFIG);
+    if !config_path.is_file() {
+        return None;
+    }
+
+    let hooks_dir = get_hooks_dir(&repo.open(), workdir);
+    let is_installed = read_file(hooks_dir.join("pre-commit"))
+        .map_or(false, |hook| hook.contains(PRE_COMMIT_HOOK_MARKER));
+
+    let environments_updated = if config.check_stale {
+        get_cache_dir(context).and_then(|cache| modified(&cache.join("db.db")))
+    } else {
+        None
+    };
+    let is_stale = environments_updated
+        .zip(modified(&config_path))
+        .map_or(false, |(updated, config_modified)| {
+            is_stale(config_modified, updated)
+        });
+
+    let state = if !is_installed {
+        config.not_installed_msg
+    } else if is_stale {
+        config.stale_msg
+    } else {
+        config.installed_msg
+    };
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(config.symbol),
+                "state" => Some(state),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "age" => {
+                    let elapsed = environments_updated?.elapsed().ok()?;
+                    Some(Ok(context.format_duration(elapsed.as_millis(), false)))
+                }
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `pre_commit`:\n{}", error);
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+/// Returns `core.hooksPath` if configured, or the `hooks` directory shared by all worktrees
+fn get_hooks_dir(repository: &gix::Repository, workdir: &Path) -> PathBuf {
+    match repository.config_snapshot().string("core.hooksPath") {
+        Some(hooks_path) => workdir.join(hooks_path.to_string()),
+        None => repository.common_dir().join("hooks"),
+    }
+}
+
+/// The directory pre-commit keeps its hook environments in
+fn get_cache_dir(context: &Context) -> Option<PathBuf> {
+    if let Some(home) = context.get_env("PRE_COMMIT_HOME") {
+        return Some(PathBuf::from(home));
+    }
+
+    match context.get_env("XDG_CACHE_HOME") {
+        Some(cache) => Some(PathBuf::from(cache).join("pre-commit")),
+        None => Some(context.get_home()?.join(".cache").join("pre-commit")),
+    }
+}
+
+/// The hook environments are stale if the configuration changed after they were last updated
+fn is_stale(config_modified: SystemTime, environments_updated: SystemTime) -> bool {
+    config_modified > environments_updated
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    path.metadata()
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::ModuleRenderer;
+    use crate::utils::create_command;
+    use nu_ansi_term::Color;
+    use std::fs;
+    use std::io;
+    use std::time::Duration;
+
+    fn init_repo(path: &Path) -> io::Result<()> {
+        create_command("git")?
+            .args(["init"])
+            .current_dir(path)
+            .output()
+            .map(|_| ())
+    }
+
+    fn install_hook(path: &Path) -> io::Result<()> {
+        fs::write(
+            path.join(".git/hooks/pre-commit"),
+            "#!/usr/bin/env bash\n# File generated by pre-commit: https://pre-commit.com\n",
+        )
+    }
+
+    #[test]
+    fn no_pre_commit_config() -> io::Result<()> {
+        let repo_dir = tempfile::tempdir()?;
+        init_repo(repo_dir.path())?;
+
+        let actual = ModuleRenderer::new("pre_commit")
+            .path(repo_dir.path())
+            .config(toml::toml! {
+                [pre_commit]
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(None, actual);
+        repo_dir.close()
+    }
+
+    #[test]
+    fn hooks_not_installed() -> io::Result<()> {
+        let repo_dir = tempfile::tempdir()?;
+        init_repo(repo_dir.path())?;
+        fs::write(
+            repo_dir.path().join(".pre-commit-config.yaml"),
+            "repos: []\n",
+        )?;
+
+        let actual = ModuleRenderer::new("pre_commit")
+            .path(repo_dir.path())
+            .config(toml::toml! {
+                [pre_commit]
+                disabled = false
+            })
+            .collect();
+        let expected = Some(format!(
+            "{} ",
+            Color::Yellow.bold().paint("🪝 not installed")
+        ));
+
+        assert_eq!(expected, actual);
+        repo_dir.close()
+    }
+
+    #[test]
+    fn hooks_installed() -> io::Result<()> {
+        let repo_dir = tempfile::tempdir()?;
+        init_repo(repo_dir.path())?;
+        fs::write(
+            repo_dir.path().join(".pre-commit-config.yaml"),
+            "repos: []\n",
+        )?;
+        install_hook(repo_dir.path())?;
+
+        let actual = ModuleRenderer::new("pre_commit")
+            .path(repo_dir.path())
+            .config(toml::toml! {
+                [pre_commit]
+                disabled = false
+            })
+            .collect();
+        let expected = Some(format!("{} ", Color::Yellow.bold().paint("🪝 ")));
+
+        assert_eq!(expected, actual);
+        repo_dir.close()
+    }
+
+    #[test]
+    fn foreign_hook_is_not_installed() -> io::Result<()> {
+        let repo_dir = tempfile::tempdir()?;
+        init_repo(repo_dir.path())?;
+        fs::write(
+            repo_dir.path().join(".pre-commit-config.yaml"),
+            "repos: []\n",
+        )?;
+        fs::write(
+            repo_dir.path().join(".git/hooks/pre-commit"),
+            "#!/bin/sh\nmake lint\n",
+        )?;
+
+        let actual = ModuleRenderer::new("pre_commit")
+            .path(repo_dir.path())
+            .config(toml::toml! {
+                [pre_commit]
+                format = "$state"
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(Some("not installed".to_string()), actual);
+        repo_dir.close()
+    }
+
+    #[test]
+    fn stale_when_config_is_newer() {
+        let now = SystemTime::now();
+        let hour_ago = now - Duration::from_secs(3600);
+
+        assert!(is_stale(now, hour_ago));
+        assert!(!is_stale(hour_ago, now));
+    }
+
+    #[test]
+    fn age_of_hook_environments() -> io::Result<()> {
+        let repo_dir = tempfile::tempdir()?;
+        let cache_dir = tempfile::tempdir()?;
+        init_repo(repo_dir.path())?;
+        install_hook(repo_dir.path())?;
+        fs::write(
+            repo_dir.path().join(".pre-commit-config.yaml"),
+            "repos: []\n",
+        )?;
+        fs::File::create(cache_dir.path().join("db.db"))?.sync_all()?;
+
+        let actual = ModuleRenderer::new("pre_commit")
+            .path(repo_dir.path())
+            .env("PRE_COMMIT_HOME", cache_dir.path().to_str().unwrap())
+            .config(toml::toml! {
+                [pre_commit]
+                format = "$age"
+                check_stale = true
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(Some("0s".to_string()), actual);
+        repo_dir.close()?;
+        cache_dir.close()
+    }
+
+    #[test]
+    fn age_requires_check_stale() -> io::Result<()> {
+        let repo_dir = tempfile::tempdir()?;
+        let cache_dir = tempfile::tempdir()?;
+        init_repo(repo_dir.path())?;
+        install_hook(repo_dir.path())?;
+        fs::write(
+            repo_dir.path().join(".pre-commit-config.yaml"),
+            "repos: []\n",
+        )?;
+        fs::File::create(cache_dir.path().join("db.db"))?.sync_all()?;
+
+        let actual = ModuleRenderer::new("pre_commit")
+            .path(repo_dir.path())
+            .env("PRE_COMMIT_HOME", cache_dir.path().to_str().unwrap())
+            .config(toml::toml! {
+                [pre_commit]
+                format = "$state( $age)"
+                installed_msg = "ok"
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(Some("ok".to_string()), actual);
+        repo_dir.close()?;
+        cache_dir.close()
+    }
+}
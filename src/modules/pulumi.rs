@@ -3,12 +3,14 @@ use serde::Deserialize;
 use sha1::{Digest, Sha1};
 use std::collections::HashMap;
 use std::ffi::OsStr;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::{BufReader, Read};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::SystemTime;
 use yaml_rust2::{Yaml, YamlLoader};
 
+use super::utils::plan::{self, PlanSummary};
 use super::{Context, Module, ModuleConfig};
 use crate::configs::pulumi::PulumiConfig;
 use crate::formatter::{StringFormatter, VersionFormatter};
@@ -54,7 +56,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             })
             .map(|variable| match variable {
                 "version" => {
-                    let stdout = context.exec_cmd("pulumi", &["version"])?.stdout;
+                    let stdout = context.exec_version_cmd("pulumi", &["version"])?.stdout;
                     VersionFormatter::format_module_version(
                         module.get_name(),
                         parse_version(&stdout),
@@ -64,6 +66,17 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 .map(Ok),
                 "username" => get_pulumi_username(context).map(Ok),
                 "stack" => stack_name(&project_file, context).map(Ok),
+                "preview" => {
+                    let project_dir = project_file.parent()?;
+                    // The preview is outdated once the program, the project or the stack
+                    // settings changed
+                    let sources: Vec<PathBuf> =
+                        newest_project_file(project_dir).into_iter().collect();
+                    plan::read_plan(project_dir, config.preview_file, &sources)
+                }
+                .and_then(|preview| PlanSummary::from_pulumi_preview(&preview))
+                .filter(PlanSummary::has_changes)
+                .map(|summary| Ok(summary.to_string())),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -122,6 +135,37 @@ fn find_package_file(path: &Path) -> Option<PathBuf> {
     None
 }
 
+/// The most recently modified file of the project, skipping the installed dependencies and
+/// the files of Pulumi and git
+fn newest_project_file(project_dir: &Path) -> Option<PathBuf> {
+    let mut newest: Option<(SystemTime, PathBuf)> = None;
+    let mut dirs = vec![project_dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                let name = entry.file_name();
+                if !matches!(name.to_str(), Some("node_modules" | ".pulumi" | ".git")) {
+                    dirs.push(entry.path());
+                }
+                continue;
+            }
+            let Ok(modified) = entry.metadata().and_then(|meta| meta.modified()) else {
+                continue;
+            };
+            if newest.as_ref().map_or(true, |(time, _)| modified > *time) {
+                newest = Some((modified, entry.path()));
+            }
+        }
+    }
+    newest.map(|(_, path)| path)
+}
+
 /// We get the name of the current stack.
 ///
 /// Pulumi has no CLI option that is fast enough to get this for us, but finding
@@ -437,6 +481,33 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn newest_file_of_project() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let root = dir.path();
+        std::fs::write(root.join("Pulumi.yaml"), "name: starship\n")?;
+
+        // Far more than the resolution of modification times
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        std::fs::create_dir_all(root.join("node_modules").join("@pulumi"))?;
+        std::fs::write(
+            root.join("node_modules").join("@pulumi").join("index.js"),
+            "",
+        )?;
+        std::fs::create_dir(root.join(".pulumi"))?;
+        std::fs::write(root.join(".pulumi").join("preview.json"), "{}")?;
+        assert_eq!(newest_project_file(root), Some(root.join("Pulumi.yaml")));
+
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        std::fs::create_dir(root.join("src"))?;
+        std::fs::write(root.join("src").join("index.ts"), "")?;
+        assert_eq!(
+            newest_project_file(root),
+            Some(root.join("src").join("index.ts"))
+        );
+        dir.close()
+    }
+
     #[test]
     fn empty_config_file() -> io::Result<()> {
         let dir = tempfile::tempdir()?;
@@ -31,7 +31,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             })
             .map(|variable| match variable {
                 "version" => {
-                    let purs_version = context.exec_cmd("purs", &["--version"])?.stdout;
+                    let purs_version = context.exec_version_cmd("purs", &["--version"])?.stdout;
                     VersionFormatter::format_module_version(
                         module.get_name(),
                         purs_version.trim(),
@@ -1,6 +1,9 @@
 use ini::Ini;
+use once_cell::sync::Lazy;
+use std::ops::Deref;
 use std::path::Path;
 
+use super::utils::version::{self, VersionConstraint};
 use super::{Context, Module, ModuleConfig};
 use crate::configs::python::PythonConfig;
 use crate::formatter::StringFormatter;
@@ -32,6 +35,12 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         ""
     };
 
+    let python_version = Lazy::new(|| get_python_version(context, &config));
+    let satisfies = Lazy::new(|| {
+        let constraint = get_pinned_version(context)?;
+        version::satisfies(python_version.as_deref(), Some(&constraint))
+    });
+
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
             .map_meta(|var, _| match var {
@@ -39,7 +48,13 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map_style(|variable| match variable {
-                "style" => Some(Ok(config.style)),
+                "style" => {
+                    if *satisfies == Some(false) {
+                        Some(Ok(config.not_capable_style))
+                    } else {
+                        Some(Ok(config.style))
+                    }
+                }
                 _ => None,
             })
             .map(|variable| match variable {
@@ -47,19 +62,21 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                     if config.pyenv_version_name {
                         return get_pyenv_version(context).map(Ok);
                     }
-                    let python_version = get_python_version(context, &config)?;
+                    let python_version = python_version.deref().as_ref()?;
                     VersionFormatter::format_module_version(
                         module.get_name(),
-                        &python_version,
+                        python_version,
                         config.version_format,
                     )
                     .map(Ok)
                 }
+                "virtualenv" if super::python_env::is_merged(context) => None,
                 "virtualenv" => {
                     let virtual_env = get_python_virtual_env(context);
                     virtual_env.as_ref().map(|e| Ok(e.trim().to_string()))
                 }
                 "pyenv_prefix" => Some(Ok(pyenv_prefix.to_string())),
+                "satisfies" => satisfies.map(|satisfies| Ok(satisfies.to_string())),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -92,12 +109,18 @@ fn get_pyenv_version(context: &Context) -> Option<String> {
     version_name
 }
 
+/// The version in `.python-version`, the first one where pyenv lists several
+fn get_pinned_version(context: &Context) -> Option<VersionConstraint> {
+    let pinned = context.read_file_from_pwd(".python-version")?;
+    VersionConstraint::pinned(pinned.lines().next()?)
+}
+
 fn get_python_version(context: &Context, config: &PythonConfig) -> Option<String> {
     let version = config
         .python_binary
         .0
         .iter()
-        .find_map(|binary| context.exec_cmd(binary, &["--version"]))
+        .find_map(|binary| context.exec_version_cmd(binary, &["--version"]))
         .map(get_command_string_output)?;
 
     parse_python_version(&version)
@@ -113,7 +136,7 @@ fn parse_python_version(python_version_string: &str) -> Option<String> {
     Some(version.to_string())
 }
 
-fn get_python_virtual_env(context: &Context) -> Option<String> {
+pub(super) fn get_python_virtual_env(context: &Context) -> Option<String> {
     context.get_env("VIRTUAL_ENV").and_then(|venv| {
         get_prompt_from_venv(Path::new(&venv)).or_else(|| {
             Path::new(&venv)
@@ -195,6 +218,25 @@ Python 3.7.9 (7e6e2bb30ac5fbdbd443619cae28c51d5c162a02, Nov 24 2020, 10:03:59)
         dir.close()
     }
 
+    #[test]
+    fn python_version_not_installed() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join(".python-version"), "3.12.1\n3.8\n")?;
+
+        let actual = ModuleRenderer::new("python")
+            .path(dir.path())
+            .config(toml::toml! {
+                [python]
+                python_binary = "python3"
+                format = "[$version $satisfies]($style)"
+            })
+            .collect();
+
+        let expected = Some(Color::Red.bold().paint("v3.8.0 false").to_string());
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
     #[test]
     fn folder_with_requirements_txt() -> io::Result<()> {
         let dir = tempfile::tempdir()?;
@@ -354,6 +396,25 @@ Python 3.7.9 (7e6e2bb30ac5fbdbd443619cae28c51d5c162a02, Nov 24 2020, 10:03:59)
         dir.close()
     }
 
+    #[test]
+    fn with_virtual_env_merged_into_python_env() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("main.py"))?.sync_all()?;
+        let actual = ModuleRenderer::new("python")
+            .path(dir.path())
+            .env("VIRTUAL_ENV", "/foo/bar/my_venv")
+            .config(toml::toml! {
+                [python_env]
+                disabled = false
+            })
+            .collect();
+
+        let expected = Some(format!("via {}", Color::Yellow.bold().paint("🐍 v3.8.0 ")));
+
+        assert_eq!(actual, expected);
+        dir.close()
+    }
+
     #[test]
     fn with_active_venv() -> io::Result<()> {
         let dir = tempfile::tempdir()?;
@@ -0,0 +1,292 @@
+use super::utils::directory::truncate;
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::python_env::PythonEnvConfig;
+use crate::formatter::StringFormatter;
+
+/// One activated environment in the stack
+#[derive(Debug, PartialEq, Eq)]
+struct Layer {
+    kind: &'static str,
+    environment: String,
+}
+
+/// Creates a module showing all active Python environments as a single stack
+///
+/// The stack is ordered from the outermost environment to the innermost one, e.g.
+/// `conda:ml > venv:api` for a virtualenv created on top of a conda environment.
+/// While this module is enabled, the `conda` module and the `$virtualenv` variable of
+/// the `python` module are hidden to avoid showing the same environments twice.
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("python_env");
+    let config: PythonEnvConfig = PythonEnvConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled {
+        return None;
+    };
+
+    let layers = get_layers(context, &config);
+    if layers.is_empty() {
+        return None;
+    }
+
+    let layer_format_with_separator = [config.layer_format, config.separator].join("");
+    let stack = layers
+        .iter()
+        .enumerate()
+        .filter_map(|(i, layer)| {
+            let format: &str = if i == layers.len() - 1 {
+                config.layer_format
+            } else {
+                &layer_format_with_separator
+            };
+            let parsed = StringFormatter::new(format).and_then(|formatter| {
+                formatter
+                    .map_style(|variable| match variable {
+                        "style" => Some(Ok(config.style)),
+                        _ => None,
+                    })
+                    .map(|variable| match variable {
+                        "kind" => Some(Ok(layer.kind)),
+                        "environment" => Some(Ok(layer.environment.as_str())),
+                        _ => None,
+                    })
+                    .parse(None, Some(context))
+            });
+            match parsed {
+                Ok(segments) => Some(segments),
+                Err(e) => {
+                    log::warn!("Error parsing format string in `python_env.layer_format`: {e:?}");
+                    None
+                }
+            }
+        })
+        .flatten()
+        .collect::<Vec<_>>();
+
+    let innermost = layers.last()?;
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "kind" => Some(Ok(innermost.kind)),
+                "environment" => Some(Ok(innermost.environment.as_str())),
+                _ => None,
+            })
+            .map_variables_to_segments(|variable| match variable {
+                "stack" => Some(Ok(stack.clone())),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `python_env`:\n{}", error);
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+/// Whether the `python_env` module is enabled and takes over displaying Python environments
+pub(super) fn is_merged(context: &Context) -> bool {
+    let config = PythonEnvConfig::try_load(context.config.get_module_config("python_env"));
+    !config.disabled
+}
+
+fn get_layers(context: &Context, config: &PythonEnvConfig) -> Vec<Layer> {
+    let mut layers = Vec::new();
+
+    let pixi_project = context
+        .get_env("PIXI_PROJECT_NAME")
+        .filter(|name| !name.is_empty());
+
+    // pixi activates its environments through conda variables, so those are left to the
+    // pixi layer
+    let conda_env = context
+        .get_env("CONDA_DEFAULT_ENV")
+        .filter(|env| !env.trim().is_empty())
+        .filter(|env| !(config.ignore_base && env == "base"))
+        .filter(|_| pixi_project.is_none() || !is_pixi_prefix(context));
+    if let Some(env) = conda_env {
+        layers.push(Layer {
+            kind: "conda",
+            environment: truncate(&env, config.truncation_length).unwrap_or(env),
+        });
+    }
+
+    if let Some(project) = pixi_project {
+        let environment = match context.get_env("PIXI_ENVIRONMENT_NAME") {
+            Some(env) if !env.is_empty() && env != "default" => format!("{project}/{env}"),
+            _ => project,
+        };
+        layers.push(Layer {
+            kind: "pixi",
+            environment,
+        });
+    }
+
+    if let Some(env) = super::python::get_python_virtual_env(context) {
+        let kind = if context.get_env("POETRY_ACTIVE").as_deref() == Some("1") {
+            "poetry"
+        } else {
+            "venv"
+        };
+        layers.push(Layer {
+            kind,
+            environment: env.trim().to_string(),
+        });
+    }
+
+    layers
+}
+
+fn is_pixi_prefix(context: &Context) -> bool {
+    context.get_env("CONDA_PREFIX").map_or(false, |prefix| {
+        std::path::Path::new(&prefix)
+            .components()
+            .any(|component| component.as_os_str() == ".pixi")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test::ModuleRenderer;
+    use nu_ansi_term::Color;
+
+    #[test]
+    fn disabled_by_default() {
+        let actual = ModuleRenderer::new("python_env")
+            .env("CONDA_DEFAULT_ENV", "ml")
+            .collect();
+
+        assert_eq!(None, actual);
+    }
+
+    #[test]
+    fn no_environment() {
+        let actual = ModuleRenderer::new("python_env")
+            .config(toml::toml! {
+                [python_env]
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(None, actual);
+    }
+
+    #[test]
+    fn conda_base_is_ignored() {
+        let actual = ModuleRenderer::new("python_env")
+            .env("CONDA_DEFAULT_ENV", "base")
+            .config(toml::toml! {
+                [python_env]
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(None, actual);
+    }
+
+    #[test]
+    fn conda_and_venv_stack() {
+        let actual = ModuleRenderer::new("python_env")
+            .env("CONDA_DEFAULT_ENV", "/opt/conda/envs/ml")
+            .env("VIRTUAL_ENV", "/home/user/api/.venv-api")
+            .config(toml::toml! {
+                [python_env]
+                disabled = false
+            })
+            .collect();
+        let expected = Some(format!(
+            "via {} ",
+            Color::Yellow.bold().paint("🐍 conda:ml > venv:.venv-api")
+        ));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn poetry_environment() {
+        let actual = ModuleRenderer::new("python_env")
+            .env(
+                "VIRTUAL_ENV",
+                "/home/user/.cache/pypoetry/virtualenvs/api-py3.11",
+            )
+            .env("POETRY_ACTIVE", "1")
+            .config(toml::toml! {
+                [python_env]
+                disabled = false
+            })
+            .collect();
+        let expected = Some(format!(
+            "via {} ",
+            Color::Yellow.bold().paint("🐍 poetry:api-py3.11")
+        ));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn pixi_replaces_its_conda_environment() {
+        let actual = ModuleRenderer::new("python_env")
+            .env("CONDA_DEFAULT_ENV", "api:test")
+            .env("CONDA_PREFIX", "/home/user/api/.pixi/envs/test")
+            .env("PIXI_PROJECT_NAME", "api")
+            .env("PIXI_ENVIRONMENT_NAME", "test")
+            .config(toml::toml! {
+                [python_env]
+                disabled = false
+            })
+            .collect();
+        let expected = Some(format!(
+            "via {} ",
+            Color::Yellow.bold().paint("🐍 pixi:api/test")
+        ));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn custom_layer_format() {
+        let actual = ModuleRenderer::new("python_env")
+            .env("CONDA_DEFAULT_ENV", "ml")
+            .env("VIRTUAL_ENV", "/home/user/api/venv")
+            .config(toml::toml! {
+                [python_env]
+                format = "$stack"
+                layer_format = "$environment"
+                separator = "/"
+                disabled = false
+            })
+            .collect();
+        let expected = Some("ml/venv".to_string());
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn hides_conda_module_when_enabled() {
+        let actual = ModuleRenderer::new("conda")
+            .env("CONDA_DEFAULT_ENV", "ml")
+            .config(toml::toml! {
+                [python_env]
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(None, actual);
+    }
+}
@@ -32,7 +32,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             .map(|variable| match variable {
                 "version" => {
                     let version = context
-                        .exec_cmd("quarto", &["--version"])?
+                        .exec_version_cmd("quarto", &["--version"])?
                         .stdout
                         .trim_end()
                         .to_owned();
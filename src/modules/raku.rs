@@ -68,7 +68,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 }
 
 fn get_raku_version(context: &Context) -> Option<(String, String)> {
-    let output = context.exec_cmd("raku", &["--version"])?.stdout;
+    let output = context.exec_version_cmd("raku", &["--version"])?.stdout;
 
     parse_raku_version(&output)
 }
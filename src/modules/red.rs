@@ -30,7 +30,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             })
             .map(|variable| match variable {
                 "version" => context
-                    .exec_cmd("red", &["--version"])
+                    .exec_version_cmd("red", &["--version"])
                     .map(|output| {
                         VersionFormatter::format_module_version(
                             module.get_name(),
@@ -0,0 +1,98 @@
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::repl::ReplConfig;
+use crate::formatter::StringFormatter;
+
+/// Creates a module with the REPL or editor the shell was started from, e.g. with `!bash` in
+/// ipython or `:terminal` in vim, where `exit` returns to that program
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("repl");
+    let config = ReplConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled {
+        return None;
+    }
+
+    // The first parent process is the shell itself
+    let host = context
+        .parent_processes()
+        .iter()
+        .skip(1)
+        .map(|process| process.name.as_str())
+        .find(|name| config.detect_processes.contains(name))?;
+    let host = config.aliases.get(host).copied().unwrap_or(host);
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|var, _| match var {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "host" => Some(Ok(host)),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `repl`:\n{}", error);
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test::ModuleRenderer;
+    use nu_ansi_term::Color;
+
+    #[test]
+    fn shell_started_from_editor() {
+        let actual = ModuleRenderer::new("repl")
+            .config(toml::toml! {
+                [repl]
+                disabled = false
+            })
+            .parent_processes(&["bash", "nvim", "zsh", "tmux"])
+            .collect();
+        let expected = Some(format!("in {} ", Color::Purple.bold().paint("↩ nvim")));
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn aliased_repl() {
+        let actual = ModuleRenderer::new("repl")
+            .config(toml::toml! {
+                [repl]
+                disabled = false
+                format = "$host"
+                aliases = { ipython = "IPython" }
+            })
+            .parent_processes(&["bash", "ipython", "zsh"])
+            .collect();
+        assert_eq!(Some("IPython".to_string()), actual);
+    }
+
+    #[test]
+    fn shell_of_terminal() {
+        let actual = ModuleRenderer::new("repl")
+            .config(toml::toml! {
+                [repl]
+                disabled = false
+            })
+            .parent_processes(&["zsh", "alacritty", "systemd"])
+            .collect();
+        assert_eq!(None, actual);
+    }
+}
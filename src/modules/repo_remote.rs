@@ -0,0 +1,524 @@
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::repo_remote::RepoRemoteConfig;
+use crate::formatter::StringFormatter;
+use crate::module_cache;
+
+/// The location of a repository on a forge, parsed from a remote URL
+#[derive(Debug, PartialEq, Eq)]
+struct RemoteUrl {
+    host: String,
+    owner: String,
+    repo: String,
+}
+
+impl RemoteUrl {
+    /// Parses URLs like `https://github.com/org/repo.git`, `ssh://git@host:22/org/repo`
+    /// and the scp-like `git@gitlab.com:group/subgroup/repo.git`
+    fn parse(url: &str) -> Option<Self> {
+        let (authority, path) = match url.split_once("://") {
+            Some((_, rest)) => rest.split_once('/')?,
+            None => url.split_once(':')?,
+        };
+        let host = authority.rsplit('@').next()?;
+        let host = host.split(':').next()?;
+
+        let path = path.trim_matches('/');
+        let path = path.strip_suffix(".git").unwrap_or(path);
+        let (owner, repo) = path.rsplit_once('/')?;
+        if host.is_empty() || owner.is_empty() || repo.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            host: host.to_lowercase(),
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+        })
+    }
+
+    fn forge(&self) -> &str {
+        match self.host.as_str() {
+            "github.com" => "github",
+            "gitlab.com" => "gitlab",
+            "bitbucket.org" => "bitbucket",
+            "codeberg.org" => "codeberg",
+            host if host.contains("github") => "github",
+            host if host.contains("gitlab") => "gitlab",
+            host => host,
+        }
+    }
+}
+
+/// Creates a module with the forge and repository of the current git remote
+///
+/// With `pull_requests` enabled, the number of open pull requests for the current branch is
+/// looked up through the `gh` or `glab` CLI and cached for `pull_requests_ttl` seconds.
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("repo_remote");
+    let config: RepoRemoteConfig = RepoRemoteConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled {
+        return None;
+    };
+
+    let repo = context.get_repo().ok()?;
+    let remote_name = match config.remote {
+        "" => repo
+            .remote
+            .as_ref()
+            .and_then(|remote| remote.name.clone())
+            .unwrap_or_else(|| "origin".to_string()),
+        remote => remote.to_string(),
+    };
+    let url = repo
+        .open()
+        .find_remote(remote_name.as_str())
+        .ok()?
+        .url(gix::remote::Direction::Fetch)?
+        .to_bstring()
+        .to_string();
+    let remote = RemoteUrl::parse(&url)?;
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "forge" => Some(Ok(remote.forge().to_string())),
+                "host" => Some(Ok(remote.host.clone())),
+                "owner" => Some(Ok(remote.owner.clone())),
+                "repo" => Some(Ok(remote.repo.clone())),
+                "remote" => Some(Ok(remote_name.clone())),
+                "pull_requests" if config.pull_requests => {
+                    let branch = repo.branch.as_deref()?;
+                    get_pull_request_count(context, &config, &remote, branch).map(Ok)
+                }
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `repo_remote`:\n{}", error);
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+/// How long a lookup in the background may take before it counts as failed
+const REFRESH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Returns the cached number of open pull requests, looking it up again in the background once
+/// the cache expired
+///
+/// The last number is shown until the lookup finishes, usually by the next prompt. Failed
+/// lookups, e.g. while offline or with an expired token, are cached as well, so the CLIs run at
+/// most once every `pull_requests_ttl` seconds.
+fn get_pull_request_count(
+    context: &Context,
+    config: &RepoRemoteConfig,
+    remote: &RemoteUrl,
+    branch: &str,
+) -> Option<String> {
+    let key = format!(
+        "{}_{}_{}_{}",
+        remote.host, remote.owner, remote.repo, branch
+    )
+    .replace(
+        |c: char| !c.is_ascii_alphanumeric() && c != '.' && c != '-',
+        "_",
+    );
+    let dir = context.get_cache_dir()?.join("repo_remote");
+    let cache_file = dir.join(&key);
+    let pending_file = dir.join(format!("{key}.pending"));
+
+    // An empty cache file records a failed lookup
+    let last_count = fs::read_to_string(&cache_file)
+        .ok()
+        .filter(|count| !count.is_empty());
+    if pending_file.exists() {
+        let count = collect_lookup(&cache_file, &pending_file, remote);
+        note_pending(context, &pending_file);
+        return count.or(last_count);
+    }
+
+    let ttl = module_cache::lifetime(context, config.pull_requests_ttl);
+    if module_cache::is_fresh(&cache_file, ttl, SystemTime::now()) {
+        return last_count;
+    }
+
+    if let Err(error) = fs::create_dir_all(&dir) {
+        log::debug!("Unable to cache pull requests of {}: {error}", remote.repo);
+        return last_count;
+    }
+    if !start_lookup(context, remote, branch, &pending_file) {
+        write_count(&cache_file, "", remote);
+        return None;
+    }
+    // Lookups that are done right away don't have to wait for the next prompt
+    let count = collect_lookup(&cache_file, &pending_file, remote);
+    note_pending(context, &pending_file);
+    count.or(last_count)
+}
+
+/// Lets the shell animate the prompt while the lookup is running
+fn note_pending(context: &Context, pending_file: &Path) {
+    if pending_file.exists() {
+        context.add_pending_module("repo_remote");
+    }
+}
+
+/// Moves the number of a finished lookup into the cache and returns it, nothing while the
+/// lookup is running
+fn collect_lookup(cache_file: &Path, pending_file: &Path, remote: &RemoteUrl) -> Option<String> {
+    let output = fs::read_to_string(pending_file).ok()?;
+    let count = parse_pull_request_count(remote, &output);
+    let timed_out = !module_cache::is_fresh(pending_file, REFRESH_TIMEOUT, SystemTime::now());
+    if count.is_none() && !timed_out {
+        return None;
+    }
+    write_count(cache_file, count.as_deref().unwrap_or_default(), remote);
+    if let Err(error) = fs::remove_file(pending_file) {
+        log::debug!("Unable to remove {}: {error}", pending_file.display());
+    }
+    count
+}
+
+fn write_count(cache_file: &Path, count: &str, remote: &RemoteUrl) {
+    if let Err(error) = fs::write(cache_file, count) {
+        log::debug!("Unable to cache pull requests of {}: {error}", remote.repo);
+    }
+}
+
+/// Starts counting the open pull requests for `branch` with the CLI of the forge, writing its
+/// output to `output`
+///
+/// The CLIs are only run if their token is set in the environment, so they never prompt for
+/// authentication.
+fn start_lookup(context: &Context, remote: &RemoteUrl, branch: &str, output: &Path) -> bool {
+    let started = match remote.forge() {
+        "github" if context.is_env_set("GH_TOKEN") || context.is_env_set("GITHUB_TOKEN") => {
+            let repo = format!("{}/{}/{}", remote.host, remote.owner, remote.repo);
+            context.spawn_cmd(
+                "gh",
+                &[
+                    "pr", "list", "--repo", &repo, "--head", branch, "--state", "open", "--json",
+                    "number", "--jq", "length",
+                ],
+                output,
+            )
+        }
+        "gitlab" if context.is_env_set("GITLAB_TOKEN") => {
+            let endpoint = format!(
+                "projects/{}/merge_requests?state=opened&source_branch={}",
+                percent_encode(&format!("{}/{}", remote.owner, remote.repo)),
+                percent_encode(branch)
+            );
+            context.spawn_cmd(
+                "glab",
+                &["api", "--hostname", remote.host.as_str(), endpoint.as_str()],
+                output,
+            )
+        }
+        _ => return false,
+    };
+    started
+        .map_err(|error| log::debug!("Unable to look up pull requests: {error}"))
+        .is_ok()
+}
+
+/// Parses the output of a finished lookup, nothing for incomplete output
+fn parse_pull_request_count(remote: &RemoteUrl, output: &str) -> Option<String> {
+    match remote.forge() {
+        // `gh` prints the number on a line of its own
+        "github" if output.ends_with('\n') => {
+            let count = output.trim().parse::<u64>().ok()?;
+            Some(count.to_string())
+        }
+        "gitlab" => {
+            let merge_requests: serde_json::Value = serde_json::from_str(output).ok()?;
+            Some(merge_requests.as_array()?.len().to_string())
+        }
+        _ => None,
+    }
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                char::from(byte).to_string()
+            }
+            byte => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::ModuleRenderer;
+    use crate::utils::{create_command, CommandOutput};
+    use nu_ansi_term::Color;
+    use std::io;
+
+    fn init_repo(path: &Path, url: &str) -> io::Result<()> {
+        create_command("git")?
+            .args(["init"])
+            .current_dir(path)
+            .output()?;
+        create_command("git")?
+            .args(["symbolic-ref", "HEAD", "refs/heads/main"])
+            .current_dir(path)
+            .output()?;
+        create_command("git")?
+            .args(["remote", "add", "origin", url])
+            .current_dir(path)
+            .output()
+            .map(|_| ())
+    }
+
+    #[test]
+    fn parse_remote_urls() {
+        let expected = Some(RemoteUrl {
+            host: "github.com".to_string(),
+            owner: "starship".to_string(),
+            repo: "starship".to_string(),
+        });
+        assert_eq!(
+            RemoteUrl::parse("https://github.com/starship/starship.git"),
+            expected
+        );
+        assert_eq!(
+            RemoteUrl::parse("git@github.com:starship/starship.git"),
+            expected
+        );
+        assert_eq!(
+            RemoteUrl::parse("ssh://git@GitHub.com:22/starship/starship/"),
+            expected
+        );
+
+        let nested = RemoteUrl::parse("git@gitlab.example.com:group/sub/project").unwrap();
+        assert_eq!(nested.owner, "group/sub");
+        assert_eq!(nested.forge(), "gitlab");
+
+        assert_eq!(RemoteUrl::parse("/srv/git/project.git"), None);
+    }
+
+    #[test]
+    fn encode_gitlab_parameters() {
+        assert_eq!(percent_encode("group/sub project"), "group%2Fsub%20project");
+    }
+
+    #[test]
+    fn no_remote() -> io::Result<()> {
+        let repo_dir = tempfile::tempdir()?;
+        create_command("git")?
+            .args(["init"])
+            .current_dir(repo_dir.path())
+            .output()?;
+
+        let actual = ModuleRenderer::new("repo_remote")
+            .path(repo_dir.path())
+            .config(toml::toml! {
+                [repo_remote]
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(None, actual);
+        repo_dir.close()
+    }
+
+    #[test]
+    fn github_remote() -> io::Result<()> {
+        let repo_dir = tempfile::tempdir()?;
+        init_repo(repo_dir.path(), "git@github.com:starship/starship.git")?;
+
+        let actual = ModuleRenderer::new("repo_remote")
+            .path(repo_dir.path())
+            .config(toml::toml! {
+                [repo_remote]
+                disabled = false
+            })
+            .collect();
+        let expected = Some(format!(
+            "at {} ",
+            Color::Blue.bold().paint("🌐 starship/starship")
+        ));
+
+        assert_eq!(expected, actual);
+        repo_dir.close()
+    }
+
+    #[test]
+    fn forge_and_remote_name() -> io::Result<()> {
+        let repo_dir = tempfile::tempdir()?;
+        init_repo(repo_dir.path(), "https://gitlab.com/group/sub/project.git")?;
+
+        let actual = ModuleRenderer::new("repo_remote")
+            .path(repo_dir.path())
+            .config(toml::toml! {
+                [repo_remote]
+                format = "$forge:$owner/$repo@$remote"
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(Some("gitlab:group/sub/project@origin".to_string()), actual);
+        repo_dir.close()
+    }
+
+    #[test]
+    fn pull_requests_need_token() -> io::Result<()> {
+        let repo_dir = tempfile::tempdir()?;
+        let cache_dir = tempfile::tempdir()?;
+        init_repo(repo_dir.path(), "git@github.com:starship/starship.git")?;
+
+        let actual = ModuleRenderer::new("repo_remote")
+            .path(repo_dir.path())
+            .env("STARSHIP_CACHE", cache_dir.path().to_str().unwrap())
+            .config(toml::toml! {
+                [repo_remote]
+                format = "$repo( $pull_requests)"
+                pull_requests = true
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(Some("starship".to_string()), actual);
+        repo_dir.close()?;
+        cache_dir.close()
+    }
+
+    #[test]
+    fn pull_requests_are_cached() -> io::Result<()> {
+        let repo_dir = tempfile::tempdir()?;
+        let cache_dir = tempfile::tempdir()?;
+        init_repo(repo_dir.path(), "git@github.com:starship/starship.git")?;
+
+        let render = |output: Option<CommandOutput>| {
+            ModuleRenderer::new("repo_remote")
+                .path(repo_dir.path())
+                .env("STARSHIP_CACHE", cache_dir.path().to_str().unwrap())
+                .env("GITHUB_TOKEN", "token")
+                .cmd(
+                    "gh pr list --repo github.com/starship/starship --head main --state open --json number --jq length",
+                    output,
+                )
+                .config(toml::toml! {
+                    [repo_remote]
+                    format = "$pull_requests"
+                    pull_requests = true
+                    disabled = false
+                })
+                .collect()
+        };
+
+        let first = render(Some(CommandOutput {
+            stdout: "3\n".to_string(),
+            stderr: String::new(),
+        }));
+        assert_eq!(Some("3".to_string()), first);
+
+        // The second prompt is served from the cache without running `gh`
+        let second = render(None);
+        assert_eq!(Some("3".to_string()), second);
+
+        repo_dir.close()?;
+        cache_dir.close()
+    }
+
+    #[test]
+    fn last_count_while_looking_up() -> io::Result<()> {
+        let repo_dir = tempfile::tempdir()?;
+        let cache_dir = tempfile::tempdir()?;
+        init_repo(repo_dir.path(), "git@github.com:starship/starship.git")?;
+
+        let render = |output: Option<CommandOutput>| {
+            ModuleRenderer::new("repo_remote")
+                .path(repo_dir.path())
+                .env("STARSHIP_CACHE", cache_dir.path().to_str().unwrap())
+                .env("GITHUB_TOKEN", "token")
+                .cmd(
+                    "gh pr list --repo github.com/starship/starship --head main --state open --json number --jq length",
+                    output,
+                )
+                .config(toml::toml! {
+                    [repo_remote]
+                    format = "$pull_requests"
+                    pull_requests = true
+                    pull_requests_ttl = 0
+                    disabled = false
+                })
+                .collect()
+        };
+
+        let first = render(Some(CommandOutput {
+            stdout: "3\n".to_string(),
+            stderr: String::new(),
+        }));
+        assert_eq!(Some("3".to_string()), first);
+
+        // A lookup without output is still running, or failed, which only shows after a while
+        assert_eq!(Some("3".to_string()), render(None));
+        let pending = cache_dir
+            .path()
+            .join("repo_remote")
+            .join("github.com_starship_starship_main.pending");
+        assert!(pending.exists());
+
+        repo_dir.close()?;
+        cache_dir.close()
+    }
+
+    #[test]
+    fn failed_lookups_are_cached() -> io::Result<()> {
+        let repo_dir = tempfile::tempdir()?;
+        let cache_dir = tempfile::tempdir()?;
+        init_repo(repo_dir.path(), "git@gitlab.com:group/project.git")?;
+
+        let render = || {
+            ModuleRenderer::new("repo_remote")
+                .path(repo_dir.path())
+                .env("STARSHIP_CACHE", cache_dir.path().to_str().unwrap())
+                .config(toml::toml! {
+                    [repo_remote]
+                    format = "$repo( $pull_requests)"
+                    pull_requests = true
+                    disabled = false
+                })
+                .collect()
+        };
+
+        assert_eq!(Some("project".to_string()), render());
+        let cached = fs::read_to_string(
+            cache_dir
+                .path()
+                .join("repo_remote")
+                .join("gitlab.com_group_project_main"),
+        )?;
+        assert_eq!(cached, "");
+
+        repo_dir.close()?;
+        cache_dir.close()
+    }
+}
@@ -5,6 +5,11 @@ use crate::configs::rlang::RLangConfig;
 use crate::formatter::StringFormatter;
 use crate::utils::get_command_string_output;
 
+use once_cell::sync::Lazy;
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("rlang");
     let config: RLangConfig = RLangConfig::try_load(module.config);
@@ -19,6 +24,12 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
+    let r_version = Lazy::new(|| {
+        let r_version_string =
+            get_command_string_output(context.exec_version_cmd("R", &["--version"])?);
+        parse_r_version(&r_version_string)
+    });
+
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
             .map_meta(|var, _| match var {
@@ -30,16 +41,19 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map(|variable| match variable {
-                "version" => {
-                    let r_version_string =
-                        get_command_string_output(context.exec_cmd("R", &["--version"])?);
-                    let r_version = parse_r_version(&r_version_string)?;
-                    VersionFormatter::format_module_version(
-                        module.get_name(),
-                        &r_version,
-                        config.version_format,
-                    )
-                    .map(Ok)
+                "version" => VersionFormatter::format_module_version(
+                    module.get_name(),
+                    r_version.deref().as_ref()?,
+                    config.version_format,
+                )
+                .map(Ok),
+                "renv_synced" => {
+                    let symbol = if is_library_synced(context, r_version.as_deref())? {
+                        config.renv_synced_symbol
+                    } else {
+                        config.renv_out_of_sync_symbol
+                    };
+                    Some(Ok(symbol.to_string()))
                 }
                 _ => None,
             })
@@ -69,9 +83,150 @@ fn parse_r_version(r_version: &str) -> Option<String> {
         .map(ToString::to_string)
 }
 
+/// Whether the packages in the project library have the versions of the lockfile of renv, or of
+/// packrat, `None` without a lockfile or a library
+///
+/// Reading the package descriptions is cached until the lockfile or the library change.
+fn is_library_synced(context: &Context, r_version: Option<&str>) -> Option<bool> {
+    let (lockfile, library_root) = if let Some(renv_lock) = context.read_file_from_pwd("renv.lock")
+    {
+        let root = match context.get_env("RENV_PATHS_LIBRARY") {
+            Some(root) => PathBuf::from(root),
+            None => context.current_dir.join("renv").join("library"),
+        };
+        (parse_renv_lock(&renv_lock)?, root)
+    } else {
+        let packrat_lock = context.read_file_from_pwd("packrat/packrat.lock")?;
+        (
+            parse_packrat_lock(&packrat_lock),
+            context.current_dir.join("packrat").join("lib"),
+        )
+    };
+    let library = find_library(&library_root, r_version)?;
+
+    let cache = context.get_cache_dir().map(|dir| {
+        let key = context
+            .fingerprint()
+            .cwd()
+            .modified(&context.current_dir.join("renv.lock"))
+            .modified(&context.current_dir.join("packrat").join("packrat.lock"))
+            .modified(&library)
+            .finish();
+        dir.join("rlang").join(key)
+    });
+    if let Some(cached) = cache
+        .as_ref()
+        .and_then(|file| fs::read_to_string(file).ok())
+    {
+        return Some(cached == "1");
+    }
+
+    let is_synced = lockfile.iter().all(|(package, version)| {
+        installed_version(&library, package).map_or(false, |installed| installed == *version)
+    });
+    if let Some(cache) = cache {
+        let written = cache
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(&cache, if is_synced { "1" } else { "0" }));
+        if let Err(error) = written {
+            log::debug!("Unable to cache the state of the R library: {error}");
+        }
+    }
+    Some(is_synced)
+}
+
+/// The packages and their versions in `renv.lock`
+fn parse_renv_lock(lockfile: &str) -> Option<Vec<(String, String)>> {
+    let lockfile: serde_json::Value = serde_json::from_str(lockfile).ok()?;
+    let packages = lockfile.get("Packages")?.as_object()?;
+    Some(
+        packages
+            .iter()
+            .filter_map(|(name, package)| {
+                let version = package.get("Version")?.as_str()?;
+                Some((name.clone(), version.to_string()))
+            })
+            .collect(),
+    )
+}
+
+/// The packages and their versions in `packrat.lock`, whose records are separated by blank lines
+fn parse_packrat_lock(lockfile: &str) -> Vec<(String, String)> {
+    lockfile
+        .split("\n\n")
+        .filter_map(|record| {
+            Some((
+                dcf_field(record, "Package")?.to_string(),
+                dcf_field(record, "Version")?.to_string(),
+            ))
+        })
+        .collect()
+}
+
+/// A field of the Debian control format, which R uses for `DESCRIPTION` files
+fn dcf_field<'a>(record: &'a str, name: &str) -> Option<&'a str> {
+    record.lines().find_map(|line| {
+        let (field, value) = line.split_once(':')?;
+        (field == name).then(|| value.trim())
+    })
+}
+
+fn installed_version(library: &Path, package: &str) -> Option<String> {
+    let description = fs::read_to_string(library.join(package).join("DESCRIPTION")).ok()?;
+    dcf_field(&description, "Version").map(ToString::to_string)
+}
+
+/// The library of the project below the library root, like `R-4.3/x86_64-pc-linux-gnu` for renv
+/// or `x86_64-pc-linux-gnu/4.3.1` for packrat
+///
+/// There is a library for every R version the project was used with, the one of the installed
+/// R is preferred, then the most recently changed one.
+fn find_library(root: &Path, r_version: Option<&str>) -> Option<PathBuf> {
+    fn collect(dir: &Path, depth: usize, libraries: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        let dirs: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect();
+        if dirs.iter().any(|dir| dir.join("DESCRIPTION").is_file()) {
+            libraries.push(dir.to_path_buf());
+        } else if depth > 0 {
+            for dir in dirs {
+                collect(&dir, depth - 1, libraries);
+            }
+        }
+    }
+
+    let mut libraries = Vec::new();
+    collect(root, 3, &mut libraries);
+    let minor_version = r_version.and_then(|version| {
+        let mut parts = version.split('.');
+        Some(format!("{}.{}", parts.next()?, parts.next()?))
+    });
+    let is_of_installed_r = |library: &PathBuf| {
+        minor_version.as_ref().map_or(false, |minor_version| {
+            library.strip_prefix(root).map_or(false, |relative| {
+                relative.iter().any(|component| {
+                    let component = component.to_string_lossy();
+                    let version = component.strip_prefix("R-").unwrap_or(&component);
+                    version == minor_version || version.starts_with(&format!("{minor_version}."))
+                })
+            })
+        })
+    };
+    let modified = |library: &PathBuf| fs::metadata(library).and_then(|meta| meta.modified()).ok();
+    libraries
+        .into_iter()
+        .max_by_key(|library| (is_of_installed_r(library), modified(library)))
+}
+
 #[cfg(test)]
 mod tests {
-    use super::parse_r_version;
+    use super::*;
     use crate::test::ModuleRenderer;
     use nu_ansi_term::Color;
     use std::fs;
@@ -149,6 +304,71 @@ https://www.gnu.org/licenses/."#;
         dir.close()
     }
 
+    fn write_package(library: &Path, package: &str, version: &str) -> io::Result<()> {
+        fs::create_dir_all(library.join(package))?;
+        fs::write(
+            library.join(package).join("DESCRIPTION"),
+            format!("Package: {package}\nType: Package\nVersion: {version}\n"),
+        )
+    }
+
+    #[test]
+    fn renv_library_sync() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("renv.lock"),
+            r#"{
+  "R": { "Version": "4.1.0" },
+  "Packages": {
+    "dplyr": { "Package": "dplyr", "Version": "1.1.3", "Source": "Repository" },
+    "renv": { "Package": "renv", "Version": "1.0.3", "Source": "Repository" }
+  }
+}"#,
+        )?;
+        let library = dir.path().join("renv/library/R-4.1/x86_64-pc-linux-gnu");
+        write_package(&library, "dplyr", "1.1.2")?;
+        write_package(&library, "renv", "1.0.3")?;
+        // Libraries of other R versions are ignored
+        let other_library = dir.path().join("renv/library/R-4.3/x86_64-pc-linux-gnu");
+        write_package(&other_library, "dplyr", "1.1.3")?;
+        write_package(&other_library, "renv", "1.0.3")?;
+
+        // Changes within the resolution of modification times would hit the cache
+        let render = || -> io::Result<Option<String>> {
+            let cache_dir = tempfile::tempdir()?;
+            let actual = ModuleRenderer::new("rlang")
+                .path(dir.path())
+                .env("STARSHIP_CACHE", cache_dir.path().to_string_lossy())
+                .config(toml::toml! {
+                    [rlang]
+                    format = "$renv_synced"
+                })
+                .collect();
+            cache_dir.close()?;
+            Ok(actual)
+        };
+        assert_eq!(Some("✗".to_string()), render()?);
+
+        fs::remove_dir_all(library.join("dplyr"))?;
+        write_package(&library, "dplyr", "1.1.3")?;
+        assert_eq!(Some("✓".to_string()), render()?);
+        dir.close()
+    }
+
+    #[test]
+    fn test_parse_packrat_lock() {
+        let lockfile = "PackratFormat: 1.4\nPackratVersion: 0.9.1\nRVersion: 4.1.0\n\n\
+                        Package: BH\nSource: CRAN\nVersion: 1.81.0-1\nHash: 7a18d3\n\n\
+                        Package: Rcpp\nSource: CRAN\nVersion: 1.0.11\n";
+        assert_eq!(
+            parse_packrat_lock(lockfile),
+            vec![
+                ("BH".to_string(), "1.81.0-1".to_string()),
+                ("Rcpp".to_string(), "1.0.11".to_string()),
+            ]
+        );
+    }
+
     fn check_r_render(dir: &tempfile::TempDir) {
         let actual = ModuleRenderer::new("rlang").path(dir.path()).collect();
         let expected = Some(format!("via {}", Color::Blue.bold().paint("📐 v4.1.0 ")));
@@ -1,5 +1,8 @@
+use once_cell::sync::Lazy;
 use regex::Regex;
+use std::ops::Deref;
 
+use super::utils::version::{self, VersionConstraint};
 use super::{Context, Module, ModuleConfig};
 
 use crate::configs::ruby::RubyConfig;
@@ -31,6 +34,17 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
+    let ruby_version = Lazy::new(|| {
+        context
+            .exec_version_cmd("ruby", &["-v"])
+            .map(|cmd| cmd.stdout)
+    });
+    let satisfies = Lazy::new(|| {
+        let constraint = get_pinned_version(context).or_else(|| get_gemspec_version(context))?;
+        let installed = ruby_version.as_deref().and_then(parse_ruby_version);
+        version::satisfies(installed, Some(&constraint))
+    });
+
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
             .map_meta(|var, _| match var {
@@ -38,15 +52,21 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map_style(|variable| match variable {
-                "style" => Some(Ok(config.style)),
+                "style" => {
+                    if *satisfies == Some(false) {
+                        Some(Ok(config.not_capable_style))
+                    } else {
+                        Some(Ok(config.style))
+                    }
+                }
                 _ => None,
             })
             .map(|variable| match variable {
-                "version" => format_ruby_version(
-                    &context.exec_cmd("ruby", &["-v"])?.stdout,
-                    config.version_format,
-                )
-                .map(Ok),
+                "version" => {
+                    format_ruby_version(ruby_version.deref().as_ref()?, config.version_format)
+                        .map(Ok)
+                }
+                "satisfies" => satisfies.map(|satisfies| Ok(satisfies.to_string())),
                 "gemset" => {
                     format_rvm_gemset(&context.exec_cmd("rvm", &["current"])?.stdout).map(Ok)
                 }
@@ -66,8 +86,8 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
-fn format_ruby_version(ruby_version: &str, version_format: &str) -> Option<String> {
-    let version = ruby_version
+fn parse_ruby_version(ruby_version: &str) -> Option<&str> {
+    ruby_version
         // split into ["ruby", "2.6.0p0", "linux/amd64"]
         .split_whitespace()
         // return "2.6.0p0"
@@ -75,7 +95,11 @@ fn format_ruby_version(ruby_version: &str, version_format: &str) -> Option<Strin
         // split into ["2.6.0", "0"]
         .split('p')
         // return "2.6.0"
-        .next()?;
+        .next()
+}
+
+fn format_ruby_version(ruby_version: &str, version_format: &str) -> Option<String> {
+    let version = parse_ruby_version(ruby_version)?;
 
     match VersionFormatter::format_version(version, version_format) {
         Ok(formatted) => Some(formatted),
@@ -86,6 +110,37 @@ fn format_ruby_version(ruby_version: &str, version_format: &str) -> Option<Strin
     }
 }
 
+/// The version in `.ruby-version`, which rbenv, rvm and chruby write as `3.2.2` or `ruby-3.2.2`
+fn get_pinned_version(context: &Context) -> Option<VersionConstraint> {
+    let pinned = context.read_file_from_pwd(".ruby-version")?;
+    VersionConstraint::pinned(pinned.lines().next()?.trim().trim_start_matches("ruby-"))
+}
+
+/// The `required_ruby_version` of the gemspec in the current directory
+fn get_gemspec_version(context: &Context) -> Option<VersionConstraint> {
+    let gemspec = context
+        .dir_contents()
+        .ok()?
+        .files()
+        .find(|file| file.extension().map_or(false, |ext| ext == "gemspec"))?;
+    let contents = context.read_file_from_pwd(gemspec.to_str()?)?;
+    parse_required_ruby_version(&contents)
+}
+
+/// Parses `required_ruby_version = ">= 2.7"` or `required_ruby_version = [">= 2.7", "< 4"]`
+fn parse_required_ruby_version(gemspec: &str) -> Option<VersionConstraint> {
+    let line = gemspec
+        .lines()
+        .find_map(|line| line.split_once("required_ruby_version"))?
+        .1;
+    let requirement_re = Regex::new(r#"["']([^"']+)["']"#).unwrap();
+    let requirements: Vec<String> = requirement_re
+        .captures_iter(line)
+        .map(|requirement| requirement[1].trim().to_string())
+        .collect();
+    (!requirements.is_empty()).then(|| VersionConstraint::Range(requirements.join(", ")))
+}
+
 fn format_rvm_gemset(current: &str) -> Option<String> {
     let gemset_re = Regex::new(r"@(\S+)").unwrap();
     if let Some(gemset) = gemset_re.captures(current) {
@@ -231,6 +286,47 @@ mod tests {
         dir.close()
     }
 
+    #[test]
+    fn gemspec_requires_newer_ruby() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("Gemfile"))?.sync_all()?;
+        std::fs::write(
+            dir.path().join("gem.gemspec"),
+            "Gem::Specification.new do |spec|\n  spec.required_ruby_version = \"~> 3.1\"\nend\n",
+        )?;
+
+        let actual = ModuleRenderer::new("ruby")
+            .path(dir.path())
+            .config(toml::toml! {
+                [ruby]
+                format = "[$version $satisfies]($style)"
+            })
+            .collect();
+
+        let expected = Some(
+            Color::Red
+                .bold()
+                .underline()
+                .paint("v2.5.1 false")
+                .to_string(),
+        );
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn test_parse_required_ruby_version() {
+        assert_eq!(
+            parse_required_ruby_version(r#"s.required_ruby_version = [">= 2.7", "< 4"]"#),
+            Some(VersionConstraint::Range(">= 2.7, < 4".to_string()))
+        );
+        assert_eq!(
+            parse_required_ruby_version("s.required_ruby_version = '~> 2.7.1'"),
+            Some(VersionConstraint::Range("~> 2.7.1".to_string()))
+        );
+        assert_eq!(parse_required_ruby_version("s.name = 'gem'"), None);
+    }
+
     #[test]
     fn test_format_ruby_version() {
         let config = RubyConfig::default();
@@ -5,6 +5,7 @@ use std::process::Output;
 use serde::Deserialize;
 use std::collections::HashMap;
 
+use super::utils::version::{self, VersionConstraint};
 use super::{Context, Module, ModuleConfig};
 
 use crate::configs::rust::RustConfig;
@@ -12,7 +13,7 @@ use crate::formatter::{StringFormatter, VersionFormatter};
 use crate::utils::create_command;
 use home::rustup_home;
 
-use once_cell::sync::OnceCell;
+use once_cell::sync::{Lazy, OnceCell};
 
 use guess_host_triple::guess_host_triple;
 
@@ -172,6 +173,12 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     }
 
     let rust_env_info = RustToolingEnvironmentInfo::new();
+    // The toolchain file pins the toolchain, which `$RUSTUP_TOOLCHAIN` or overrides can replace
+    let satisfies = Lazy::new(|| {
+        let constraint = VersionConstraint::pinned(&find_rust_toolchain_file(context)?)?;
+        let installed = get_module_numeric_version(context, &config, &rust_env_info);
+        version::satisfies(installed.as_deref(), Some(&constraint))
+    });
 
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
@@ -180,13 +187,20 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map_style(|variable| match variable {
-                "style" => Some(Ok(config.style)),
+                "style" => {
+                    if *satisfies == Some(false) {
+                        Some(Ok(config.not_capable_style))
+                    } else {
+                        Some(Ok(config.style))
+                    }
+                }
                 _ => None,
             })
             .map(|variable| match variable {
                 "version" => get_module_version(context, &config, &rust_env_info).map(Ok),
                 "numver" => get_module_numeric_version(context, &config, &rust_env_info).map(Ok),
                 "toolchain" => get_toolchain_version(context, &config, &rust_env_info).map(Ok),
+                "satisfies" => satisfies.map(|satisfies| Ok(satisfies.to_string())),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -64,7 +64,7 @@ fn get_scala_version(context: &Context) -> Option<String> {
         .filter(|out| !out.stdout.is_empty())
         .map(|std_out_only| std_out_only.stdout.trim().to_string())
         .or_else(|| {
-            let command = context.exec_cmd("scalac", &["-version"])?;
+            let command = context.exec_version_cmd("scalac", &["-version"])?;
             let scala_version_string = get_command_string_output(command);
             parse_scala_version(&scala_version_string)
         })
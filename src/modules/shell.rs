@@ -1,7 +1,7 @@
 use super::{Context, Module, ModuleConfig, Shell};
 
 use crate::configs::shell::ShellConfig;
-use crate::formatter::StringFormatter;
+use crate::formatter::{StringFormatter, VersionFormatter};
 
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("shell");
@@ -11,7 +11,20 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    let shell = &context.shell;
+    // Without `STARSHIP_SHELL`, e.g. when the prompt is set up by hand, the shell is the
+    // process starship was started from
+    let shell = &match context.shell {
+        Shell::Unknown => context
+            .parent_processes()
+            .first()
+            .map_or(Shell::Unknown, |process| Shell::from_name(&process.name)),
+        shell => shell,
+    };
+
+    let level = nesting_level(context);
+    if config.level_threshold > 0 && level.map_or(true, |level| level <= config.level_threshold) {
+        return None;
+    }
 
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
@@ -48,6 +61,16 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 "xonsh_indicator" => Some(Ok(config.xonsh_indicator)),
                 "cmd_indicator" => Some(Ok(config.cmd_indicator)),
                 "unknown_indicator" => Some(Ok(config.unknown_indicator)),
+                "level" => level.map(|level| Ok(level.to_string())),
+                "version" => get_shell_version(context, shell)
+                    .and_then(|version| {
+                        VersionFormatter::format_module_version(
+                            module.get_name(),
+                            &version,
+                            config.version_format,
+                        )
+                    })
+                    .map(Ok),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -64,10 +87,60 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// Returns how deeply the current shell is nested, based on `SHLVL`.
+///
+/// Terminal multiplexers are usually started from a shell and pass its `SHLVL` on to the shells
+/// in their panes, so inside them the level is corrected by one.
+fn nesting_level(context: &Context) -> Option<i64> {
+    let shlvl = context.get_env("SHLVL")?.trim().parse::<i64>().ok()?;
+    let in_multiplexer = ["TMUX", "STY", "ZELLIJ"]
+        .iter()
+        .any(|key| context.get_env(key).is_some());
+
+    if in_multiplexer {
+        Some((shlvl - 1).max(1))
+    } else {
+        Some(shlvl)
+    }
+}
+
+fn get_shell_version(context: &Context, shell: &Shell) -> Option<String> {
+    let output = match shell {
+        Shell::Bash => context.exec_version_cmd("bash", &["--version"]),
+        Shell::Fish => context.exec_version_cmd("fish", &["--version"]),
+        Shell::Zsh => context.exec_version_cmd("zsh", &["--version"]),
+        Shell::Pwsh => context.exec_version_cmd("pwsh", &["--version"]),
+        Shell::Ion => context.exec_version_cmd("ion", &["--version"]),
+        Shell::Elvish => context.exec_version_cmd("elvish", &["-version"]),
+        Shell::Tcsh => context.exec_version_cmd("tcsh", &["--version"]),
+        Shell::Nu => context.exec_version_cmd("nu", &["--version"]),
+        Shell::Xonsh => context.exec_version_cmd("xonsh", &["--version"]),
+        Shell::PowerShell | Shell::Cmd | Shell::Unknown => None,
+    }?;
+
+    parse_shell_version(&output.stdout)
+}
+
+/// Extracts the first version number from the output of `<shell> --version`, e.g.
+/// `5.2.15` from `GNU bash, version 5.2.15(1)-release (x86_64-pc-linux-gnu)`
+fn parse_shell_version(output: &str) -> Option<String> {
+    let token = output
+        .split(|c: char| c.is_whitespace() || c == ',' || c == '/')
+        .find(|token| token.starts_with(|c: char| c.is_ascii_digit()))?;
+    let version = token
+        .split(|c: char| c == '(' || c == '-')
+        .next()?
+        .trim_end_matches('.');
+
+    Some(version.to_string())
+}
+
 #[cfg(test)]
 mod tests {
+    use super::parse_shell_version;
     use crate::context::Shell;
     use crate::test::ModuleRenderer;
+    use crate::utils::CommandOutput;
     use nu_ansi_term::Color;
 
     #[test]
@@ -86,6 +159,21 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn test_shell_from_parent_process() {
+        let expected = Some(format!("{} ", Color::White.bold().paint("zsh")));
+        let actual = ModuleRenderer::new("shell")
+            .shell(Shell::Unknown)
+            .parent_processes(&["-zsh", "alacritty"])
+            .config(toml::toml! {
+                [shell]
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn test_bash_default_format() {
         let expected = Some(format!("{} ", Color::White.bold().paint("bsh")));
@@ -453,4 +541,84 @@ mod tests {
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_parse_shell_version() {
+        let outputs = [
+            (
+                "GNU bash, version 5.2.15(1)-release (x86_64-pc-linux-gnu)",
+                "5.2.15",
+            ),
+            ("zsh 5.9 (x86_64-apple-darwin23.0)", "5.9"),
+            ("fish, version 3.7.0", "3.7.0"),
+            ("PowerShell 7.4.1", "7.4.1"),
+            ("xonsh/0.14.4", "0.14.4"),
+            ("0.91.0", "0.91.0"),
+        ];
+
+        for (output, expected) in outputs {
+            assert_eq!(parse_shell_version(output).as_deref(), Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_version() {
+        let expected = Some(format!("{} ", Color::White.bold().paint("bsh v5.2.15")));
+        let actual = ModuleRenderer::new("shell")
+            .shell(Shell::Bash)
+            .cmd(
+                "bash --version",
+                Some(CommandOutput {
+                    stdout: String::from("GNU bash, version 5.2.15(1)-release\n"),
+                    stderr: String::default(),
+                }),
+            )
+            .config(toml::toml! {
+                [shell]
+                format = "[$indicator $version]($style) "
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_level_threshold() {
+        let render = |shlvl: &str| {
+            ModuleRenderer::new("shell")
+                .shell(Shell::Zsh)
+                .env("SHLVL", shlvl)
+                .config(toml::toml! {
+                    [shell]
+                    format = "[$indicator $level]($style) "
+                    level_threshold = 1
+                    disabled = false
+                })
+                .collect()
+        };
+
+        assert_eq!(render("1"), None);
+        assert_eq!(
+            render("2"),
+            Some(format!("{} ", Color::White.bold().paint("zsh 2")))
+        );
+    }
+
+    #[test]
+    fn test_level_corrected_in_multiplexer() {
+        let actual = ModuleRenderer::new("shell")
+            .shell(Shell::Zsh)
+            .env("SHLVL", "2")
+            .env("TMUX", "/tmp/tmux-1000/default,1234,0")
+            .config(toml::toml! {
+                [shell]
+                format = "[$indicator $level]($style) "
+                level_threshold = 1
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(actual, None);
+    }
 }
@@ -58,7 +58,7 @@ fn get_solidity_version(context: &Context, config: &SolidityConfig) -> Option<St
         .compiler
         .0
         .iter()
-        .find_map(|compiler_name| context.exec_cmd(compiler_name, &["--version"]))
+        .find_map(|compiler_name| context.exec_version_cmd(compiler_name, &["--version"]))
         .map(get_command_string_output)?;
 
     parse_solidity_version(&version)
@@ -1,9 +1,11 @@
 use std::env;
+use std::time::{Duration, SystemTime};
 
 use super::{Context, Module, ModuleConfig};
 
 use crate::configs::sudo::SudoConfig;
 use crate::formatter::StringFormatter;
+use crate::utils::{context_path, read_file};
 
 /// Creates a module with sudo credential cache status
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
@@ -18,6 +20,8 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
+    // Checking for cached credentials refreshes them, so their age is read before
+    let last_use = last_use(context);
     let is_sudo_cached = context.exec_cmd("sudo", &["-n", "true"]).is_some();
 
     if !is_sudo_cached {
@@ -34,6 +38,15 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 "style" => Some(Ok(config.style)),
                 _ => None,
             })
+            .map(|variable| match variable {
+                "remaining" => {
+                    let timeout = timestamp_timeout(context)
+                        .unwrap_or_else(|| Some(Duration::from_secs(config.timeout * 60)));
+                    let remaining = remaining(last_use?, timeout?, SystemTime::now())?;
+                    Some(Ok(context.format_duration(remaining.as_millis(), false)))
+                }
+                _ => None,
+            })
             .parse(None, Some(context))
     });
 
@@ -48,10 +61,70 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// Returns when sudo last refreshed the credentials of the current user, from the modification
+/// time of its timestamp file
+///
+/// The timestamp directory is usually only readable by root, in which case this returns `None`.
+fn last_use(context: &Context) -> Option<SystemTime> {
+    let user = context
+        .get_env("USER")
+        .or_else(|| context.get_env("LOGNAME"))?;
+
+    [
+        "/run/sudo/ts",
+        "/var/run/sudo/ts",
+        "/var/db/sudo/ts",
+        "/var/lib/sudo/ts",
+    ]
+    .iter()
+    .find_map(|dir| {
+        context_path(context, dir)
+            .join(&user)
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    })
+}
+
+/// Reads `timestamp_timeout` from the sudoers file, where it is readable
+///
+/// Returns `Some(None)` for a negative timeout, with which credentials never expire.
+fn timestamp_timeout(context: &Context) -> Option<Option<Duration>> {
+    let sudoers = read_file(context_path(context, "/etc/sudoers")).ok()?;
+    let minutes = parse_timestamp_timeout(&sudoers)?;
+    Some((minutes >= 0.0).then(|| Duration::from_secs_f64(minutes * 60.0)))
+}
+
+/// The last `timestamp_timeout` of the `Defaults` lines of a sudoers file, in minutes
+fn parse_timestamp_timeout(sudoers: &str) -> Option<f64> {
+    sudoers
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("Defaults"))
+        .flat_map(|settings| settings.split(','))
+        .filter_map(|setting| setting.split_once("timestamp_timeout"))
+        .filter_map(|(_, value)| value.trim_start().strip_prefix('='))
+        .filter_map(|value| value.trim().parse().ok())
+        .filter(|minutes: &f64| minutes.is_finite())
+        // The last setting wins
+        .next_back()
+}
+
+/// The time until the credentials expire, or `None` once they did and `sudo -n` only
+/// succeeds because no password is required
+fn remaining(last_use: SystemTime, timeout: Duration, now: SystemTime) -> Option<Duration> {
+    let elapsed = now.duration_since(last_use).unwrap_or_default();
+    timeout
+        .checked_sub(elapsed)
+        .filter(|remaining| !remaining.is_zero())
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::{test::ModuleRenderer, utils::CommandOutput};
     use nu_ansi_term::Color;
+    use std::fs::File;
+    use std::io;
 
     #[test]
     fn test_sudo_not_cached() {
@@ -110,4 +183,78 @@ mod tests {
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_sudo_remaining_without_timestamp() {
+        let actual = ModuleRenderer::new("sudo")
+            .cmd(
+                "sudo -n true",
+                Some(CommandOutput {
+                    stdout: String::new(),
+                    stderr: String::new(),
+                }),
+            )
+            .env("USER", "astronaut")
+            .config(toml::toml! {
+                [sudo]
+                format = "[as $symbol(\\($remaining\\))]($style)"
+                timeout = 5
+                disabled = false
+                allow_windows = true
+            })
+            .collect();
+        let expected = Some(format!("{}", Color::Blue.bold().paint("as 🧙 ")));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_sudo_remaining_after_timeout() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let timestamp = dir.path().join("astronaut");
+        File::create(&timestamp)?.sync_all()?;
+        let last_use = timestamp.metadata()?.modified()?;
+        let timeout = Duration::from_secs(15 * 60);
+
+        assert_eq!(
+            remaining(last_use, timeout, last_use + Duration::from_secs(5 * 60)),
+            Some(Duration::from_secs(10 * 60))
+        );
+        assert_eq!(remaining(last_use, timeout, last_use + timeout * 2), None);
+        dir.close()
+    }
+
+    #[test]
+    fn test_parse_timestamp_timeout() {
+        let sudoers = "Defaults env_reset\nDefaults\tmail_badpass, timestamp_timeout=5\n\
+                       root ALL=(ALL:ALL) ALL\nDefaults timestamp_timeout = -1\n";
+        assert_eq!(parse_timestamp_timeout(sudoers), Some(-1.0));
+        assert_eq!(parse_timestamp_timeout("Defaults env_reset\n"), None);
+    }
+
+    #[test]
+    fn test_sudo_remaining_from_timestamp() {
+        let actual = ModuleRenderer::new("sudo")
+            .cmd(
+                "sudo -n true",
+                Some(CommandOutput {
+                    stdout: String::new(),
+                    stderr: String::new(),
+                }),
+            )
+            .env("USER", "astronaut")
+            .file("/run/sudo/ts/astronaut", "")
+            .config(toml::toml! {
+                [sudo]
+                format = "[$remaining]($style)"
+                timeout = 60
+                disabled = false
+                allow_windows = true
+            })
+            .collect()
+            .unwrap();
+
+        // The timestamp was just written, so almost the whole hour is left
+        assert!(actual.contains("59m") || actual.contains("1h0m"));
+    }
 }
@@ -1,9 +1,15 @@
+use super::utils::version::{self, VersionConstraint};
 use super::{Context, Module, ModuleConfig};
 
 use crate::configs::swift::SwiftConfig;
 use crate::formatter::StringFormatter;
 use crate::formatter::VersionFormatter;
 
+use once_cell::sync::Lazy;
+use std::fs;
+use std::ops::Deref;
+use std::path::Path;
+
 /// Creates a module with the current Swift version
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("swift");
@@ -20,6 +26,18 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
+    let swift_version = Lazy::new(|| {
+        parse_swift_version(&context.exec_version_cmd("swift", &["--version"])?.stdout)
+    });
+    let tools_version =
+        Lazy::new(|| parse_tools_version(&context.read_file_from_pwd("Package.swift")?));
+    let satisfies = Lazy::new(|| {
+        let constraint = tools_version
+            .as_ref()
+            .map(|minimum| VersionConstraint::Minimum(minimum.clone()));
+        version::satisfies(swift_version.as_deref(), constraint.as_ref())
+    });
+
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
             .map_meta(|var, _| match var {
@@ -27,20 +45,25 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map_style(|variable| match variable {
-                "style" => Some(Ok(config.style)),
+                "style" => {
+                    if *satisfies == Some(false) {
+                        Some(Ok(config.not_capable_style))
+                    } else {
+                        Some(Ok(config.style))
+                    }
+                }
                 _ => None,
             })
             .map(|variable| match variable {
-                "version" => {
-                    let swift_version =
-                        parse_swift_version(&context.exec_cmd("swift", &["--version"])?.stdout)?;
-                    VersionFormatter::format_module_version(
-                        module.get_name(),
-                        &swift_version,
-                        config.version_format,
-                    )
-                    .map(Ok)
-                }
+                "version" => VersionFormatter::format_module_version(
+                    module.get_name(),
+                    swift_version.deref().as_ref()?,
+                    config.version_format,
+                )
+                .map(Ok),
+                "tools_version" => tools_version.deref().clone().map(Ok),
+                "toolchain" => get_toolchain(context).map(Ok),
+                "satisfies" => satisfies.map(|satisfies| Ok(satisfies.to_string())),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -68,9 +91,55 @@ fn parse_swift_version(swift_version: &str) -> Option<String> {
     Some(version.to_string())
 }
 
+/// The oldest Swift the package builds with, from the first line of `Package.swift`, like
+/// `// swift-tools-version:5.9`
+fn parse_tools_version(manifest: &str) -> Option<String> {
+    let declaration = manifest
+        .lines()
+        .next()?
+        .trim()
+        .strip_prefix("//")?
+        .trim_start();
+    let version = declaration
+        .strip_prefix("swift-tools-version:")?
+        .split(';')
+        .next()?
+        .trim();
+    Some(version.to_string())
+}
+
+/// The toolchain `swift` runs from on macOS: the one `TOOLCHAINS` selects, else the Xcode that
+/// `DEVELOPER_DIR` or `xcode-select` point at
+fn get_toolchain(context: &Context) -> Option<String> {
+    if let Some(toolchain) = context.get_env("TOOLCHAINS") {
+        return Some(toolchain);
+    }
+    let developer_dir = match context.get_env("DEVELOPER_DIR") {
+        Some(developer_dir) => developer_dir.into(),
+        // The link `xcode-select --switch` changes, read without running `xcode-select -p`
+        None => fs::read_link("/var/db/xcode_select_link").ok()?,
+    };
+    developer_dir_name(&developer_dir)
+}
+
+/// Names developer directories after their Xcode, like `Xcode-15.2` for
+/// `/Applications/Xcode-15.2.app/Contents/Developer`, or else after the directory itself, like
+/// `CommandLineTools`
+fn developer_dir_name(developer_dir: &Path) -> Option<String> {
+    let app = developer_dir
+        .iter()
+        .filter_map(|component| component.to_str()?.strip_suffix(".app"))
+        .last();
+    let name = match app {
+        Some(app) => app,
+        None => developer_dir.file_name()?.to_str()?,
+    };
+    Some(name.to_string())
+}
+
 #[cfg(test)]
 mod tests {
-    use super::parse_swift_version;
+    use super::*;
     use crate::test::ModuleRenderer;
     use nu_ansi_term::Color;
     use std::fs::File;
@@ -88,6 +157,66 @@ mod tests {
         assert_eq!(parse_swift_version(input), Some(String::from("5.3-dev")));
     }
 
+    #[test]
+    fn test_parse_tools_version() {
+        let manifest = "// swift-tools-version:5.9\nimport PackageDescription\n";
+        assert_eq!(parse_tools_version(manifest), Some("5.9".to_string()));
+        let manifest = "//swift-tools-version: 5.7.1; (xcode)\n";
+        assert_eq!(parse_tools_version(manifest), Some("5.7.1".to_string()));
+        assert_eq!(parse_tools_version("import PackageDescription\n"), None);
+    }
+
+    #[test]
+    fn test_developer_dir_name() {
+        let name = |path: &str| developer_dir_name(Path::new(path));
+        assert_eq!(
+            name("/Applications/Xcode-15.2.app/Contents/Developer"),
+            Some("Xcode-15.2".to_string())
+        );
+        assert_eq!(
+            name("/Library/Developer/CommandLineTools"),
+            Some("CommandLineTools".to_string())
+        );
+    }
+
+    #[test]
+    fn package_requires_newer_swift() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("Package.swift"),
+            "// swift-tools-version:5.9\nimport PackageDescription\n",
+        )?;
+        let actual = ModuleRenderer::new("swift")
+            .path(dir.path())
+            .env(
+                "DEVELOPER_DIR",
+                "/Applications/Xcode-15.2.app/Contents/Developer",
+            )
+            .config(toml::toml! {
+                [swift]
+                format = "via [$symbol($version )(tools $tools_version )(on $toolchain )]($style)"
+            })
+            .collect();
+        let expected = Some(format!(
+            "via {}",
+            Color::Red
+                .bold()
+                .paint("🐦 v5.2.2 tools 5.9 on Xcode-15.2 ")
+        ));
+        assert_eq!(expected, actual);
+
+        let actual = ModuleRenderer::new("swift")
+            .path(dir.path())
+            .env("TOOLCHAINS", "org.swift.59202309261a")
+            .config(toml::toml! {
+                [swift]
+                format = "$toolchain"
+            })
+            .collect();
+        assert_eq!(Some("org.swift.59202309261a".to_string()), actual);
+        dir.close()
+    }
+
     #[test]
     fn folder_without_swift_files() -> io::Result<()> {
         let dir = tempfile::tempdir()?;
@@ -4,11 +4,24 @@ use crate::configs::terraform::TerraformConfig;
 use crate::formatter::StringFormatter;
 use crate::utils;
 
+use super::utils::plan::{self, PlanSummary};
+use super::utils::version::{self, VersionConstraint};
 use crate::formatter::VersionFormatter;
+use once_cell::sync::Lazy;
 use std::io;
 use std::path::PathBuf;
 
-/// Creates a module with the current Terraform version and workspace
+/// The settings declared in the `terraform` block of the configuration
+#[derive(Debug, Default, PartialEq, Eq)]
+struct TerraformSettings {
+    required_version: Option<String>,
+    backend: Option<String>,
+}
+
+/// Creates a module with the current Terraform version, workspace and backend
+///
+/// The module uses `not_capable_style` if the installed version does not satisfy the
+/// `required_version` constraint of the configuration.
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("terraform");
     let config: TerraformConfig = TerraformConfig::try_load(module.config);
@@ -24,29 +37,68 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
+    let settings = Lazy::new(|| get_terraform_settings(context));
+    let terraform_version = Lazy::new(|| {
+        parse_terraform_version(
+            context
+                .exec_version_cmd("terraform", &["version"])?
+                .stdout
+                .as_str(),
+        )
+    });
+
+    let satisfies = Lazy::new(|| {
+        let constraint = settings
+            .required_version
+            .clone()
+            .map(VersionConstraint::Range);
+        version::satisfies(terraform_version.as_deref(), constraint.as_ref())
+    });
+
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        // Terraform is slow to start, so the version is only checked if it is shown anyway
+        let shows_version = formatter
+            .get_variables()
+            .iter()
+            .any(|variable| matches!(variable.as_str(), "version" | "satisfies"));
+
         formatter
             .map_meta(|variable, _| match variable {
                 "symbol" => Some(config.symbol),
                 _ => None,
             })
             .map_style(|variable| match variable {
-                "style" => Some(Ok(config.style)),
+                "style" => {
+                    if shows_version && *satisfies == Some(false) {
+                        Some(Ok(config.not_capable_style))
+                    } else {
+                        Some(Ok(config.style))
+                    }
+                }
                 _ => None,
             })
             .map(|variable| match variable {
-                "version" => {
-                    let terraform_version = parse_terraform_version(
-                        context.exec_cmd("terraform", &["version"])?.stdout.as_str(),
-                    )?;
-                    VersionFormatter::format_module_version(
-                        module.get_name(),
-                        &terraform_version,
-                        config.version_format,
-                    )
-                }
+                "version" => VersionFormatter::format_module_version(
+                    module.get_name(),
+                    terraform_version.as_deref()?,
+                    config.version_format,
+                )
                 .map(Ok),
                 "workspace" => get_terraform_workspace(context).map(Ok),
+                "required_version" => settings.required_version.clone().map(Ok),
+                "satisfies" => satisfies.map(|satisfies| Ok(satisfies.to_string())),
+                "plan" => plan::read_plan(
+                    &context.current_dir,
+                    config.plan_file,
+                    &get_plan_sources(context),
+                )
+                .and_then(|plan| PlanSummary::from_terraform_plan(&plan))
+                .filter(PlanSummary::has_changes)
+                .map(|summary| Ok(summary.to_string())),
+                "backend" => Some(Ok(settings
+                    .backend
+                    .clone()
+                    .unwrap_or_else(|| "local".to_string()))),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -83,6 +135,104 @@ fn get_terraform_workspace(context: &Context) -> Option<String> {
     }
 }
 
+/// Reads the `terraform` block settings from the `*.tf` files in the current directory
+fn get_terraform_settings(context: &Context) -> TerraformSettings {
+    let mut settings = TerraformSettings::default();
+    let Ok(dir_contents) = context.dir_contents() else {
+        return settings;
+    };
+
+    let mut files = dir_contents
+        .files()
+        .filter(|path| path.extension().map_or(false, |ext| ext == "tf"))
+        .collect::<Vec<_>>();
+    files.sort();
+
+    for file in files {
+        if let Ok(contents) = utils::read_file(context.current_dir.join(file)) {
+            parse_terraform_settings(&contents, &mut settings);
+        }
+    }
+
+    if settings.backend.is_none() {
+        settings.backend = get_initialized_backend(context);
+    }
+
+    settings
+}
+
+/// Extracts `required_version` and the backend type from HCL source.
+///
+/// This is a line based scan rather than a full HCL parser, which is enough for the
+/// conventional layout of the `terraform` block.
+fn parse_terraform_settings(contents: &str, settings: &mut TerraformSettings) {
+    for line in contents.lines().map(str::trim) {
+        if line.starts_with('#') || line.starts_with("//") {
+            continue;
+        }
+
+        if let Some(value) = line
+            .strip_prefix("required_version")
+            .and_then(|rest| rest.trim_start().strip_prefix('='))
+        {
+            settings
+                .required_version
+                .get_or_insert_with(|| value.trim().trim_matches('"').to_string());
+        } else if let Some(rest) = line.strip_prefix("backend") {
+            // `backend "s3" {`, not the `backend = "s3"` attribute of `terraform_remote_state`
+            let mut labels = rest.trim_start().split('"');
+            if let (Some(""), Some(kind)) = (labels.next(), labels.next()) {
+                settings.backend.get_or_insert_with(|| kind.to_string());
+            }
+        } else if line
+            .strip_prefix("cloud")
+            .map_or(false, |rest| rest.trim_start().starts_with('{'))
+        {
+            settings.backend.get_or_insert_with(|| "cloud".to_string());
+        }
+    }
+}
+
+/// Reads the backend recorded by `terraform init` in the data directory
+fn get_initialized_backend(context: &Context) -> Option<String> {
+    let state = utils::read_file(get_data_dir(context).join("terraform.tfstate")).ok()?;
+    let state: serde_json::Value = serde_json::from_str(&state).ok()?;
+
+    state
+        .get("backend")?
+        .get("type")?
+        .as_str()
+        .map(ToString::to_string)
+}
+
+/// The files a plan is made from: the `*.tf` files and the variables of `*.tfvars` and
+/// `*.auto.tfvars` in the current directory, the local state and the state of `terraform init`
+fn get_plan_sources(context: &Context) -> Vec<PathBuf> {
+    let mut sources = context
+        .dir_contents()
+        .map(|contents| {
+            contents
+                .files()
+                .filter(|path| {
+                    path.extension()
+                        .map_or(false, |ext| ext == "tf" || ext == "tfvars")
+                })
+                .map(|path| context.current_dir.join(path))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    sources.push(context.current_dir.join("terraform.tfstate"));
+    sources.push(get_data_dir(context).join("terraform.tfstate"));
+    sources
+}
+
+fn get_data_dir(context: &Context) -> PathBuf {
+    match context.get_env("TF_DATA_DIR") {
+        Some(s) => PathBuf::from(s),
+        None => context.current_dir.join(".terraform"),
+    }
+}
+
 fn parse_terraform_version(version: &str) -> Option<String> {
     // `terraform version` output looks like this
     // Terraform v0.12.14
@@ -140,6 +290,193 @@ is 0.12.14. You can update by downloading from www.terraform.io/downloads.html
         assert_eq!(parse_terraform_version(input), Some("0.12.13".to_string()));
     }
 
+    #[test]
+    fn pending_changes_of_saved_plan() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::create_dir(dir.path().join(".terraform"))?;
+        let render = || {
+            ModuleRenderer::new("terraform")
+                .path(dir.path())
+                .config(toml::toml! {
+                    [terraform]
+                    format = "$workspace( $plan)"
+                })
+                .collect()
+        };
+        assert_eq!(render(), Some("default".to_string()));
+
+        fs::write(
+            dir.path().join(".terraform").join("plan.json"),
+            r#"{"resource_changes": [
+                {"change": {"actions": ["create"]}},
+                {"change": {"actions": ["delete"]}}
+            ]}"#,
+        )?;
+        assert_eq!(render(), Some("default +1 ~0 -1".to_string()));
+
+        // Far more than the resolution of modification times
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        fs::write(
+            dir.path().join("prod.auto.tfvars"),
+            "region = \"eu-west-1\"",
+        )?;
+        assert_eq!(render(), Some("default".to_string()));
+        dir.close()
+    }
+
+    #[test]
+    fn test_parse_terraform_settings() {
+        let mut settings = TerraformSettings::default();
+        parse_terraform_settings(
+            r#"
+terraform {
+  # required_version = "0.11"
+  required_version = ">= 1.5"
+  backend "gcs" {
+    bucket = "state"
+  }
+}
+
+data "terraform_remote_state" "network" {
+  backend = "s3"
+}
+"#,
+            &mut settings,
+        );
+
+        assert_eq!(
+            settings,
+            TerraformSettings {
+                required_version: Some(">= 1.5".to_string()),
+                backend: Some("gcs".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn required_version_mismatch() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("versions.tf"),
+            "terraform {\n  required_version = \"~> 1.5\"\n}\n",
+        )?;
+
+        let actual = ModuleRenderer::new("terraform")
+            .path(dir.path())
+            .config(toml::toml! {
+                [terraform]
+                format = "via [$symbol$version]($style) "
+            })
+            .collect();
+        let expected = Some(format!("via {} ", Color::Red.bold().paint("💠 v0.12.14")));
+
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn required_version_satisfied() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("versions.tf"),
+            "terraform {\n  required_version = \">= 0.12\"\n}\n",
+        )?;
+
+        let actual = ModuleRenderer::new("terraform")
+            .path(dir.path())
+            .config(toml::toml! {
+                [terraform]
+                format = "via [$symbol$version $satisfies]($style) "
+            })
+            .collect();
+        let expected = Some(format!(
+            "via {} ",
+            Color::Fixed(105).bold().paint("💠 v0.12.14 true")
+        ));
+
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn required_version_unchecked_without_version() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("versions.tf"),
+            "terraform {\n  required_version = \"~> 1.5\"\n}\n",
+        )?;
+
+        let actual = ModuleRenderer::new("terraform").path(dir.path()).collect();
+        let expected = Some(format!(
+            "via {} ",
+            Color::Fixed(105).bold().paint("💠 default")
+        ));
+
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn backend_from_configuration() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("main.tf"),
+            "terraform {\n  backend \"s3\" {}\n}\n",
+        )?;
+
+        let actual = ModuleRenderer::new("terraform")
+            .path(dir.path())
+            .config(toml::toml! {
+                [terraform]
+                format = "[$workspace@$backend]($style)"
+            })
+            .collect();
+        let expected = Some(Color::Fixed(105).bold().paint("default@s3").to_string());
+
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn backend_from_initialized_state() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("main.tf"))?;
+        let tf_dir = dir.path().join(".terraform");
+        fs::create_dir(&tf_dir)?;
+        fs::write(
+            tf_dir.join("terraform.tfstate"),
+            r#"{"version": 3, "backend": {"type": "gcs", "config": {}}}"#,
+        )?;
+
+        let actual = ModuleRenderer::new("terraform")
+            .path(dir.path())
+            .config(toml::toml! {
+                [terraform]
+                format = "$backend"
+            })
+            .collect();
+
+        assert_eq!(Some("gcs".to_string()), actual);
+        dir.close()
+    }
+
+    #[test]
+    fn local_backend_by_default() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("main.tf"))?;
+
+        let actual = ModuleRenderer::new("terraform")
+            .path(dir.path())
+            .config(toml::toml! {
+                [terraform]
+                format = "$backend"
+            })
+            .collect();
+
+        assert_eq!(Some("local".to_string()), actual);
+        dir.close()
+    }
+
     #[test]
     fn folder_with_dotterraform_with_version_no_environment() -> io::Result<()> {
         let dir = tempfile::tempdir()?;
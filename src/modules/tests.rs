@@ -0,0 +1,396 @@
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::tests::TestsConfig;
+use crate::formatter::StringFormatter;
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// The outcome of the last test run, where pytest only records the tests that failed
+#[derive(Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+struct TestRun {
+    passed: Option<usize>,
+    failed: usize,
+    skipped: usize,
+}
+
+/// The last test run of a directory, kept in the cache directory with a fingerprint of the
+/// modification times and sizes of the reports it was read from
+#[derive(Debug, Deserialize, Serialize)]
+struct CachedRun {
+    reports: String,
+    run: TestRun,
+}
+
+/// Creates a module with the outcome of the last local test run, read from the artifacts test
+/// runners leave behind, like JUnit XML reports
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("tests");
+    let config = TestsConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled {
+        return None;
+    }
+
+    let root = match context
+        .get_repo()
+        .ok()
+        .and_then(|repo| repo.workdir.clone())
+    {
+        Some(workdir) => workdir,
+        None => context.current_dir.clone(),
+    };
+    let run = last_test_run(context, &root, &config.artifacts)?;
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|var, _| match var {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" if run.failed > 0 => Some(Ok(config.failed_style)),
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "passed" => run.passed.map(|passed| Ok(passed.to_string())),
+                "failed" => (run.failed > 0).then(|| Ok(run.failed.to_string())),
+                "skipped" => (run.skipped > 0).then(|| Ok(run.skipped.to_string())),
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `tests`:\n{}", error);
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+/// Reads the artifacts of the pattern with the most recently written file, as all reports of a
+/// run, like one per test class of Maven, are written together
+///
+/// The outcome is cached until one of the reports changes, with one cache file per directory
+/// that is overwritten in place.
+fn last_test_run(context: &Context, root: &Path, patterns: &[&str]) -> Option<TestRun> {
+    let (_, files) = patterns
+        .iter()
+        .filter_map(|pattern| {
+            let files = expand_pattern(root, pattern);
+            let written = files.iter().filter_map(|file| modified(file)).max()?;
+            Some((written, files))
+        })
+        .max_by_key(|(written, _)| *written)?;
+
+    let cache = context.get_cache_dir().map(|dir| {
+        let key = context
+            .fingerprint()
+            .text(root.to_string_lossy().as_bytes())
+            .finish();
+        dir.join("tests").join(key)
+    });
+    let reports = files
+        .iter()
+        .fold(context.fingerprint(), |fingerprint, file| {
+            let size = fs::metadata(file).map_or(0, |meta| meta.len());
+            fingerprint.modified(file).text(size.to_le_bytes())
+        })
+        .finish();
+
+    let cached: Option<CachedRun> = cache
+        .as_ref()
+        .and_then(|cache| fs::read(cache).ok())
+        .and_then(|contents| serde_json::from_slice(&contents).ok());
+    if let Some(cached) = cached.filter(|cached| cached.reports == reports) {
+        return Some(cached.run);
+    }
+
+    let run = read_test_run(&files)?;
+    if let Some(cache) = cache {
+        let cached = CachedRun { reports, run };
+        let written = cache
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(&cache, serde_json::to_vec(&cached)?));
+        if let Err(error) = written {
+            log::debug!("Unable to cache the last test run: {error}");
+        }
+        return Some(cached.run);
+    }
+    Some(run)
+}
+
+/// Adds up the outcomes of all reports of a run
+fn read_test_run(files: &[PathBuf]) -> Option<TestRun> {
+    let mut run = TestRun::default();
+    for file in files {
+        let contents = fs::read_to_string(file).ok()?;
+        let file_run = if file.file_name().map_or(false, |name| name == "lastfailed") {
+            parse_pytest_lastfailed(&contents)?
+        } else {
+            parse_junit(&contents)?
+        };
+        run.passed = match (run.passed, file_run.passed) {
+            (None, None) => None,
+            (passed, file_passed) => Some(passed.unwrap_or(0) + file_passed.unwrap_or(0)),
+        };
+        run.failed += file_run.failed;
+        run.skipped += file_run.skipped;
+    }
+    Some(run)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+impl TestRun {
+    fn count(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::Passed => *self.passed.get_or_insert(0) += 1,
+            Outcome::Failed => self.failed += 1,
+            Outcome::Skipped => self.skipped += 1,
+        }
+    }
+}
+
+fn modified(file: &Path) -> Option<SystemTime> {
+    fs::metadata(file).and_then(|meta| meta.modified()).ok()
+}
+
+/// The files below `root` matching a pattern whose path components may contain `*` and `?`
+///
+/// Only the directories the pattern leads to are read, so patterns like
+/// `target/nextest/*/junit.xml` don't walk build directories.
+fn expand_pattern(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let mut paths = vec![root.to_path_buf()];
+    for component in pattern.split('/').filter(|component| !component.is_empty()) {
+        if !component.contains(['*', '?']) {
+            paths = paths.iter().map(|path| path.join(component)).collect();
+            continue;
+        }
+        paths = paths
+            .iter()
+            .filter_map(|dir| fs::read_dir(dir).ok())
+            .flatten()
+            .flatten()
+            .filter(|entry| matches_wildcard(component, &entry.file_name().to_string_lossy()))
+            .map(|entry| entry.path())
+            .collect();
+    }
+    paths.retain(|path| path.is_file());
+    paths
+}
+
+/// Whether a name matches a pattern where `*` matches any characters and `?` a single one
+fn matches_wildcard(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    // The positions of the last `*` and of the name it was matched at, to backtrack to
+    let mut star: Option<(usize, usize)> = None;
+    let (mut p, mut n) = (0, 0);
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    star = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Counts the `testcase` elements of a JUnit XML report, written by JUnit, cargo nextest, pytest
+/// with `--junitxml` and most other test runners
+fn parse_junit(report: &str) -> Option<TestRun> {
+    let mut reader = Reader::from_str(report);
+    reader.config_mut().trim_text(true);
+
+    let mut run = TestRun {
+        passed: Some(0),
+        ..Default::default()
+    };
+    // The outcome of the current `testcase`, which passed unless it has a `failure`, an `error`
+    // or is `skipped`
+    let mut outcome: Option<Outcome> = None;
+    loop {
+        let (element, is_empty) = match reader.read_event() {
+            Ok(Event::Start(element)) => (element, false),
+            Ok(Event::Empty(element)) => (element, true),
+            Ok(Event::End(element)) if element.name().as_ref() == b"testcase" => {
+                run.count(outcome.take().unwrap_or(Outcome::Passed));
+                continue;
+            }
+            Ok(Event::Eof) => break,
+            Err(error) => {
+                log::debug!("Unable to parse JUnit report: {error}");
+                return None;
+            }
+            _ => continue,
+        };
+        match element.name().as_ref() {
+            b"testcase" if is_empty => run.count(Outcome::Passed),
+            b"testcase" => outcome = Some(Outcome::Passed),
+            b"failure" | b"error" if outcome.is_some() => outcome = Some(Outcome::Failed),
+            b"skipped" if outcome == Some(Outcome::Passed) => outcome = Some(Outcome::Skipped),
+            _ => (),
+        }
+    }
+    Some(run)
+}
+
+/// Counts the failed tests in the cache of pytest, a JSON object with the failed test ids
+fn parse_pytest_lastfailed(lastfailed: &str) -> Option<TestRun> {
+    let failed: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(lastfailed).ok()?;
+    Some(TestRun {
+        passed: None,
+        failed: failed.len(),
+        skipped: 0,
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod tests {
+    use super::*;
+    use crate::test::ModuleRenderer;
+    use nu_ansi_term::Color;
+    use std::io;
+
+    const REPORT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="nextest-run" tests="4" failures="1" errors="0">
+    <testsuite name="starship" tests="4" disabled="0" errors="0" failures="1">
+        <testcase name="parses_config" classname="starship" time="0.004"/>
+        <testcase name="renders_prompt" classname="starship" time="0.012">
+            <system-out>ok</system-out>
+        </testcase>
+        <testcase name="reads_cache" classname="starship" time="0.020">
+            <failure type="test failure">assertion failed</failure>
+        </testcase>
+        <testcase name="slow_test" classname="starship">
+            <skipped/>
+        </testcase>
+    </testsuite>
+</testsuites>"#;
+
+    #[test]
+    fn test_parse_junit() {
+        let expected = TestRun {
+            passed: Some(2),
+            failed: 1,
+            skipped: 1,
+        };
+        assert_eq!(parse_junit(REPORT), Some(expected));
+    }
+
+    #[test]
+    fn test_matches_wildcard() {
+        assert!(matches_wildcard("*.xml", "TEST-com.example.AppTest.xml"));
+        assert!(matches_wildcard("TEST-*-?.xml", "TEST-unit-1.xml"));
+        assert!(!matches_wildcard("*.xml", "report.json"));
+        assert!(!matches_wildcard("junit?.xml", "junit.xml"));
+    }
+
+    #[test]
+    fn nextest_report() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let report_dir = dir.path().join("target").join("nextest").join("ci");
+        fs::create_dir_all(&report_dir)?;
+        fs::write(report_dir.join("junit.xml"), REPORT)?;
+
+        let starship_cache = tempfile::tempdir()?;
+        let actual = ModuleRenderer::new("tests")
+            .path(dir.path())
+            .env("STARSHIP_CACHE", starship_cache.path().to_string_lossy())
+            .config(toml::toml! {
+                [tests]
+                disabled = false
+            })
+            .collect();
+        let expected = Some(format!("{}", Color::Red.bold().paint("🧪 ✓2 ✗1 ")));
+        assert_eq!(expected, actual);
+        dir.close()?;
+        starship_cache.close()
+    }
+
+    #[test]
+    fn pytest_cache() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let starship_cache = tempfile::tempdir()?;
+        let cache_dir = dir.path().join(".pytest_cache").join("v").join("cache");
+        fs::create_dir_all(&cache_dir)?;
+        let render = || {
+            ModuleRenderer::new("tests")
+                .path(dir.path())
+                .env("STARSHIP_CACHE", starship_cache.path().to_string_lossy())
+                .config(toml::toml! {
+                    [tests]
+                    disabled = false
+                })
+                .collect()
+        };
+
+        fs::write(
+            cache_dir.join("lastfailed"),
+            r#"{"tests/test_app.py::test_login": true}"#,
+        )?;
+        let expected = Some(format!("{}", Color::Red.bold().paint("🧪 ✗1 ")));
+        assert_eq!(expected, render());
+
+        // The last run is cached until the report changes
+        assert_eq!(expected, render());
+        let cached = fs::read_dir(starship_cache.path().join("tests"))?.count();
+        assert_eq!(cached, 1);
+
+        fs::write(cache_dir.join("lastfailed"), "{}")?;
+        let expected = Some(format!("{}", Color::Green.bold().paint("🧪 ")));
+        assert_eq!(expected, render());
+        let cached = fs::read_dir(starship_cache.path().join("tests"))?.count();
+        assert_eq!(cached, 1);
+        dir.close()?;
+        starship_cache.close()
+    }
+
+    #[test]
+    fn without_artifacts() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let actual = ModuleRenderer::new("tests")
+            .path(dir.path())
+            .env("STARSHIP_CACHE", dir.path().join("cache").to_string_lossy())
+            .config(toml::toml! {
+                [tests]
+                disabled = false
+            })
+            .collect();
+        assert_eq!(None, actual);
+        dir.close()
+    }
+}
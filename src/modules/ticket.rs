@@ -0,0 +1,230 @@
+use regex::Regex;
+
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::ticket::TicketConfig;
+use crate::context::Repo;
+use crate::formatter::StringFormatter;
+use crate::segment::Segment;
+
+/// Creates a module with the issue key found in the current git branch or commit message
+///
+/// If `url` is set, the issue key is rendered as a hyperlink to the issue tracker.
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("ticket");
+    let config: TicketConfig = TicketConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled {
+        return None;
+    };
+
+    let pattern = match Regex::new(config.pattern) {
+        Ok(pattern) => pattern,
+        Err(error) => {
+            log::warn!("Invalid pattern in module `ticket`:\n{}", error);
+            return None;
+        }
+    };
+
+    let repo = context.get_repo().ok()?;
+    let ticket = config.sources.iter().find_map(|source| {
+        let text = match *source {
+            "branch" => repo.branch.clone(),
+            "commit" => get_commit_message(repo),
+            _ => {
+                log::warn!("Unknown source `{}` in module `ticket`", source);
+                None
+            }
+        }?;
+        pattern.find(&text).map(|found| found.as_str().to_string())
+    })?;
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|variable, _| match variable {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map_variables_to_segments(|variable| match variable {
+                "ticket" => {
+                    let mut segments = Segment::from_text(None, ticket.clone());
+                    if !config.url.is_empty() {
+                        let url = config.url.replace("$ticket", &ticket);
+                        segments
+                            .iter_mut()
+                            .for_each(|segment| segment.set_link(url.as_str()));
+                    }
+                    Some(Ok(segments))
+                }
+                _ => None,
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `ticket`:\n{}", error);
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+fn get_commit_message(repo: &Repo) -> Option<String> {
+    let git_repo = repo.open();
+    let head_commit = git_repo.head_commit().ok()?;
+    let message = head_commit.message_raw_sloppy();
+    Some(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test::{fixture_repo, FixtureProvider, ModuleRenderer};
+    use crate::utils::create_command;
+    use nu_ansi_term::Color;
+    use std::io;
+    use std::path::Path;
+
+    fn run_git(path: &Path, args: &[&str]) -> io::Result<()> {
+        create_command("git")?
+            .args(args)
+            .current_dir(path)
+            .output()
+            .map(|_| ())
+    }
+
+    #[test]
+    fn not_in_repo() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let actual = ModuleRenderer::new("ticket")
+            .path(dir.path())
+            .config(toml::toml! {
+                [ticket]
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(None, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn no_ticket() -> io::Result<()> {
+        let repo_dir = fixture_repo(FixtureProvider::Git)?;
+        run_git(repo_dir.path(), &["checkout", "-b", "cleanup"])?;
+
+        let actual = ModuleRenderer::new("ticket")
+            .path(repo_dir.path())
+            .config(toml::toml! {
+                [ticket]
+                sources = ["branch"]
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(None, actual);
+        repo_dir.close()
+    }
+
+    #[test]
+    fn ticket_from_branch() -> io::Result<()> {
+        let repo_dir = fixture_repo(FixtureProvider::Git)?;
+        run_git(
+            repo_dir.path(),
+            &["checkout", "-b", "feature/ABC-123-login"],
+        )?;
+
+        let actual = ModuleRenderer::new("ticket")
+            .path(repo_dir.path())
+            .config(toml::toml! {
+                [ticket]
+                disabled = false
+            })
+            .collect();
+        let expected = Some(format!("for {} ", Color::Cyan.bold().paint("🎫 ABC-123")));
+
+        assert_eq!(expected, actual);
+        repo_dir.close()
+    }
+
+    #[test]
+    fn ticket_from_commit_message() -> io::Result<()> {
+        let repo_dir = fixture_repo(FixtureProvider::Git)?;
+        run_git(
+            repo_dir.path(),
+            &[
+                "commit",
+                "--allow-empty",
+                "--message",
+                "Fix login redirect\n\nRefs ENG-42",
+                "--no-gpg-sign",
+            ],
+        )?;
+
+        let actual = ModuleRenderer::new("ticket")
+            .path(repo_dir.path())
+            .config(toml::toml! {
+                [ticket]
+                format = "$ticket"
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(Some("ENG-42".to_string()), actual);
+        repo_dir.close()
+    }
+
+    #[test]
+    fn custom_pattern() -> io::Result<()> {
+        let repo_dir = fixture_repo(FixtureProvider::Git)?;
+        run_git(repo_dir.path(), &["checkout", "-b", "fix/gh-1234"])?;
+
+        let actual = ModuleRenderer::new("ticket")
+            .path(repo_dir.path())
+            .config(toml::toml! {
+                [ticket]
+                format = "$ticket"
+                pattern = "gh-\\d+"
+                disabled = false
+            })
+            .collect();
+
+        assert_eq!(Some("gh-1234".to_string()), actual);
+        repo_dir.close()
+    }
+
+    #[test]
+    fn ticket_with_link() -> io::Result<()> {
+        let repo_dir = fixture_repo(FixtureProvider::Git)?;
+        run_git(repo_dir.path(), &["checkout", "-b", "ABC-123"])?;
+
+        let actual = ModuleRenderer::new("ticket")
+            .path(repo_dir.path())
+            .config(toml::toml! {
+                [ticket]
+                format = "[$ticket]($style)"
+                url = "https://linear.app/issue/$ticket"
+                disabled = false
+            })
+            .collect();
+        let expected = Some(
+            Color::Cyan
+                .bold()
+                .paint("ABC-123")
+                .hyperlink("https://linear.app/issue/ABC-123")
+                .to_string(),
+        );
+
+        assert_eq!(expected, actual);
+        repo_dir.close()
+    }
+}
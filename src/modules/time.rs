@@ -1,4 +1,5 @@
-use chrono::{DateTime, FixedOffset, Local, NaiveTime, Utc};
+use chrono::{DateTime, FixedOffset, NaiveTime, TimeZone, Utc};
+use std::fmt::Display;
 
 use super::{Context, Module, ModuleConfig};
 use crate::configs::time::TimeConfig;
@@ -17,12 +18,18 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
     // Hide prompt if current time is not inside time_range
     let (display_start, display_end) = parse_time_range(config.time_range);
-    let time_now = Local::now().time();
-    if !is_inside_time_range(time_now, display_start, display_end) {
+    let now = context.now();
+    if !is_inside_time_range(now.time(), display_start, display_end) {
         return None;
     }
 
-    let default_format = if config.use_12hr { "%r" } else { "%T" };
+    // Without an explicit setting, follow the clock convention of the configured locale
+    let use_12hr = config.use_12hr.unwrap_or_else(|| {
+        context
+            .locale()
+            .map_or(false, |locale| locale.uses_12_hour_clock())
+    });
+    let default_format = if use_12hr { "%r" } else { "%T" };
     let time_format = config.time_format.unwrap_or(default_format);
 
     log::trace!(
@@ -37,11 +44,11 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 log::warn!(
                     "Invalid utc_time_offset configuration provided! Falling back to \"local\"."
                 );
-                format_time(time_format, Local::now())
+                format_time(time_format, now)
             }
         }
     } else {
-        format_time(time_format, Local::now())
+        format_time(time_format, now)
     };
 
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
@@ -96,8 +103,11 @@ fn create_offset_time_string(
 
 /// Format a given time into the given string. This function should be referentially
 /// transparent, which makes it easy to test (unlike anything involving the actual time)
-fn format_time(time_format: &str, local_time: DateTime<Local>) -> String {
-    local_time.format(time_format).to_string()
+fn format_time<Tz: TimeZone>(time_format: &str, time: DateTime<Tz>) -> String
+where
+    Tz::Offset: Display,
+{
+    time.format(time_format).to_string()
 }
 
 fn format_time_fixed_offset(time_format: &str, utc_time: DateTime<FixedOffset>) -> String {
@@ -156,7 +166,7 @@ tests become extra important */
 mod tests {
     use super::*;
     use crate::test::ModuleRenderer;
-    use chrono::offset::TimeZone;
+    use chrono::Local;
 
     const FMT_12: &str = "%r";
     const FMT_24: &str = "%T";
@@ -507,4 +517,49 @@ mod tests {
         assert!(actual.starts_with(&col_prefix));
         assert!(actual.ends_with(&col_suffix));
     }
+
+    #[test]
+    fn timezone_override() {
+        let actual = ModuleRenderer::new("time")
+            .config(toml::toml! {
+                timezone = "+05:30"
+                [time]
+                disabled = false
+                format = "$time"
+                time_format = "%:z"
+            })
+            .collect();
+
+        assert_eq!(actual.as_deref(), Some("+05:30"));
+    }
+
+    #[test]
+    fn locale_selects_12hr_clock() {
+        let actual = ModuleRenderer::new("time")
+            .config(toml::toml! {
+                locale = "en_US"
+                [time]
+                disabled = false
+                format = "$time"
+            })
+            .collect()
+            .unwrap();
+
+        assert!(actual.ends_with(" AM") || actual.ends_with(" PM"));
+    }
+
+    #[test]
+    fn environment_keeps_24hr_clock() {
+        let actual = ModuleRenderer::new("time")
+            .env("LANG", "en_US.UTF-8")
+            .config(toml::toml! {
+                [time]
+                disabled = false
+                format = "$time"
+            })
+            .collect()
+            .unwrap();
+
+        assert!(!actual.ends_with(" AM") && !actual.ends_with(" PM"));
+    }
 }
@@ -0,0 +1,478 @@
+use super::{Context, Module, ModuleConfig};
+
+use crate::configs::todos::TodosConfig;
+use crate::formatter::StringFormatter;
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Files with a NUL byte in their first bytes are binary, like git decides
+const BINARY_PROBE_SIZE: usize = 8000;
+
+/// How long a background fill of the index is waited for before another one is started
+const FILL_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// The number of markers in the files of the repository, kept in the cache directory between
+/// prompts
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct TodoIndex {
+    /// The number of each marker in the blobs of the git index, by their object id
+    blobs: HashMap<String, Vec<usize>>,
+    /// The files whose contents in the work tree differ from the git index
+    changed: HashMap<String, IndexedFile>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct IndexedFile {
+    modified: Duration,
+    size: u64,
+    /// The number of each marker, in the order of the configuration
+    counts: Vec<usize>,
+}
+
+/// Creates a module with the number of TODO and FIXME markers in the files of the repository
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("todos");
+    let config = TodosConfig::try_load(module.config);
+
+    // As we default to disabled=true, we have to check here after loading our config module,
+    // before it was only checking against whatever is in the config starship.toml
+    if config.disabled || config.markers.is_empty() {
+        return None;
+    }
+
+    let time_limit = Duration::from_millis(context.root_config.scan_timeout);
+    let counts = count_markers_in_repo(context, &config, Some(time_limit))?;
+    let total: usize = counts.iter().sum();
+    if (total as u64) < config.threshold {
+        return None;
+    }
+
+    let parsed = StringFormatter::new(config.format).and_then(|formatter| {
+        formatter
+            .map_meta(|var, _| match var {
+                "symbol" => Some(config.symbol),
+                _ => None,
+            })
+            .map_style(|variable| match variable {
+                "style" => Some(Ok(config.style)),
+                _ => None,
+            })
+            .map(|variable| match variable {
+                "count" => Some(Ok(total.to_string())),
+                // Each marker is a variable of its own, like `$todo` and `$fixme`
+                marker => config
+                    .markers
+                    .iter()
+                    .position(|name| name.to_lowercase() == marker)
+                    .map(|index| Ok(counts[index].to_string())),
+            })
+            .parse(None, Some(context))
+    });
+
+    module.set_segments(match parsed {
+        Ok(segments) => segments,
+        Err(error) => {
+            log::warn!("Error in module `todos`:\n{}", error);
+            return None;
+        }
+    });
+
+    Some(module)
+}
+
+/// Counts the markers of all files of the repository in the current directory without a time
+/// limit, for `starship index-todos` in the background
+pub fn fill_index(context: &Context) {
+    let module = context.new_module("todos");
+    let config = TodosConfig::try_load(module.config);
+    count_markers_in_repo(context, &config, None);
+    if let Some(marker) = index_path(context, &config).map(|index| fill_marker(&index)) {
+        fs::remove_file(marker).ok();
+    }
+}
+
+/// The number of each marker in the files tracked by git
+///
+/// The files git reports as changed are read again when their modification time or size
+/// changed since the last prompt, all others are the blobs of the git index, whose counts come
+/// from the index in the cache directory. Files that aren't read within the `time_limit` are
+/// left to `starship index-todos` in the background, and nothing is returned until it is done.
+fn count_markers_in_repo(
+    context: &Context,
+    config: &TodosConfig,
+    time_limit: Option<Duration>,
+) -> Option<Vec<usize>> {
+    use gix::index::entry::Mode;
+
+    let repo = context.get_repo().ok()?;
+    let workdir = repo.workdir.as_deref()?;
+    let tracked = match repo.repo.to_thread_local().index_or_empty() {
+        Ok(index) => index
+            .entries()
+            .iter()
+            // Symlinks and submodules are tracked too, but only files have markers
+            .filter(|entry| entry.mode == Mode::FILE || entry.mode == Mode::FILE_EXECUTABLE)
+            .map(|entry| (entry.path(&index).to_string(), entry.id.to_string()))
+            .collect::<Vec<_>>(),
+        Err(error) => {
+            log::debug!("Unable to read the git index: {error}");
+            return None;
+        }
+    };
+    let changed: HashSet<String> = repo
+        .exec_git(context, &["diff-files", "--name-only", "-z"])?
+        .stdout
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(ToString::to_string)
+        .collect();
+
+    let cache = index_path(context, config);
+    let old_index: TodoIndex = cache
+        .as_ref()
+        .and_then(|cache| fs::read(cache).ok())
+        .and_then(|contents| serde_json::from_slice(&contents).ok())
+        .unwrap_or_default();
+
+    let deadline = time_limit.map(|time_limit| Instant::now() + time_limit);
+    let is_late = || deadline.map_or(false, |deadline| Instant::now() >= deadline);
+    let mut index = TodoIndex::default();
+    let mut counts = vec![0; config.markers.len()];
+    let mut is_changed = false;
+    let mut is_complete = true;
+    for (path, id) in tracked {
+        let file = workdir.join(&path);
+        let file_counts = if changed.contains(&path) {
+            // Deleted files are changed too
+            let Ok(metadata) = fs::symlink_metadata(&file) else {
+                continue;
+            };
+            if !metadata.is_file() {
+                continue;
+            }
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .unwrap_or_default();
+            let size = metadata.len();
+            let file_counts = match old_index.changed.get(&path) {
+                Some(indexed) if indexed.modified == modified && indexed.size == size => {
+                    indexed.counts.clone()
+                }
+                _ if is_late() => {
+                    is_complete = false;
+                    continue;
+                }
+                _ => {
+                    is_changed = true;
+                    read_counts(&file, size, config)
+                }
+            };
+            index.changed.insert(
+                path,
+                IndexedFile {
+                    modified,
+                    size,
+                    counts: file_counts.clone(),
+                },
+            );
+            file_counts
+        } else {
+            // The file in the work tree is the blob of the index
+            let file_counts = match old_index.blobs.get(&id).or_else(|| index.blobs.get(&id)) {
+                Some(file_counts) => file_counts.clone(),
+                None if is_late() => {
+                    is_complete = false;
+                    continue;
+                }
+                None => {
+                    is_changed = true;
+                    let size = fs::metadata(&file).map_or(0, |metadata| metadata.len());
+                    read_counts(&file, size, config)
+                }
+            };
+            index.blobs.insert(id, file_counts.clone());
+            file_counts
+        };
+        for (count, file_count) in counts.iter_mut().zip(&file_counts) {
+            *count += file_count;
+        }
+    }
+    is_changed |= index.blobs.len() != old_index.blobs.len()
+        || index.changed.len() != old_index.changed.len();
+
+    if let Some(cache) = cache.as_ref().filter(|_| is_changed) {
+        if let Err(error) = write_index(cache, &index) {
+            log::debug!("Unable to write the index of markers: {error}");
+        }
+    }
+
+    if !is_complete {
+        if let Some(cache) = cache {
+            start_fill(context, &cache);
+        }
+        return None;
+    }
+    Some(counts)
+}
+
+/// The file of the index of markers of the repository in the current directory
+fn index_path(context: &Context, config: &TodosConfig) -> Option<PathBuf> {
+    let repo = context.get_repo().ok()?;
+    let workdir = repo.workdir.as_deref()?;
+    let key = config
+        .markers
+        .iter()
+        .fold(
+            context
+                .fingerprint()
+                .text(workdir.to_string_lossy().as_bytes()),
+            |fingerprint, marker| fingerprint.text(marker),
+        )
+        .finish();
+    Some(context.get_cache_dir()?.join("todos").join(key))
+}
+
+/// The file that exists while `starship index-todos` fills the index, holding its output
+fn fill_marker(index: &Path) -> PathBuf {
+    index.with_extension("filling")
+}
+
+/// Starts `starship index-todos` in the background, unless it is already filling the index
+fn start_fill(context: &Context, index: &Path) {
+    let marker = fill_marker(index);
+    let is_filling = fs::metadata(&marker)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .map_or(false, |age| age < FILL_TIMEOUT);
+    if is_filling {
+        return;
+    }
+    log::debug!("Counting the markers of the repository in the background");
+    let result = marker
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| std::env::current_exe())
+        .and_then(|starship| context.spawn_cmd(starship, &["index-todos"], &marker));
+    if let Err(error) = result {
+        log::debug!("Unable to count the markers in the background: {error}");
+    }
+}
+
+/// The number of each marker in a file, none in binary files and files larger than
+/// `max_file_size`, like generated or vendored code
+fn read_counts(file: &Path, size: u64, config: &TodosConfig) -> Vec<usize> {
+    let no_counts = vec![0; config.markers.len()];
+    if size > config.max_file_size {
+        return no_counts;
+    }
+    let Ok(contents) = fs::read(file) else {
+        return no_counts;
+    };
+    if contents
+        .iter()
+        .take(BINARY_PROBE_SIZE)
+        .any(|byte| *byte == 0)
+    {
+        return no_counts;
+    }
+    config
+        .markers
+        .iter()
+        .map(|marker| count_marker(&contents, marker.as_bytes()))
+        .collect()
+}
+
+/// The number of times a marker occurs as a word, so `TODO` counts `// TODO: ...` and
+/// `TODO(name)` but not `TODOS` or `MASTODON`
+fn count_marker(contents: &[u8], marker: &[u8]) -> usize {
+    if marker.is_empty() {
+        return 0;
+    }
+    let is_word = |byte: Option<&u8>| {
+        byte.map_or(false, |byte| byte.is_ascii_alphanumeric() || *byte == b'_')
+    };
+    contents
+        .windows(marker.len())
+        .enumerate()
+        .filter(|(start, window)| {
+            *window == marker
+                && !is_word(start.checked_sub(1).and_then(|before| contents.get(before)))
+                && !is_word(contents.get(start + marker.len()))
+        })
+        .count()
+}
+
+/// Writes the index next to its final place and renames it, so the prompt of another shell
+/// never reads a partial index
+fn write_index(cache: &Path, index: &TodoIndex) -> std::io::Result<()> {
+    if let Some(dir) = cache.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut partial = PathBuf::from(cache);
+    partial.set_extension(format!("{}.tmp", std::process::id()));
+    fs::write(&partial, serde_json::to_vec(index)?)?;
+    fs::rename(partial, cache)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::ModuleRenderer;
+    use crate::utils::{create_command, display_command, CommandOutput};
+    use nu_ansi_term::Color;
+    use std::io;
+    use tempfile::TempDir;
+
+    fn render(repo_dir: &Path, cache_dir: &Path, config: toml::Table) -> Option<String> {
+        ModuleRenderer::new("todos")
+            .path(repo_dir)
+            .env("STARSHIP_CACHE", cache_dir.to_string_lossy())
+            .config(config)
+            .collect()
+    }
+
+    fn write_and_add(repo_dir: &Path, file: &str, contents: &str) -> io::Result<()> {
+        fs::write(repo_dir.join(file), contents)?;
+        create_command("git")?
+            .args(["add", file])
+            .current_dir(repo_dir)
+            .output()?;
+        Ok(())
+    }
+
+    fn repo_with_file(file: &str, contents: &str) -> io::Result<TempDir> {
+        let repo_dir = tempfile::tempdir()?;
+        create_command("git")?
+            .args(["init"])
+            .current_dir(&repo_dir)
+            .output()?;
+        write_and_add(repo_dir.path(), file, contents)?;
+        Ok(repo_dir)
+    }
+
+    #[test]
+    fn test_count_marker() {
+        let contents = b"// TODO: parse\n// TODO(lib): TODOS\nlet mastodon = \"FIXME\"; // TODO";
+        assert_eq!(count_marker(contents, b"TODO"), 3);
+        assert_eq!(count_marker(contents, b"FIXME"), 1);
+        assert_eq!(count_marker(contents, b"XXX"), 0);
+    }
+
+    #[test]
+    fn counts_tracked_files_incrementally() -> io::Result<()> {
+        let repo_dir = repo_with_file(
+            "main.rs",
+            "// TODO: parse arguments\nfn main() {} // FIXME\n",
+        )?;
+        let cache_dir = tempfile::tempdir()?;
+        fs::write(repo_dir.path().join("untracked.rs"), "// TODO: ignored\n")?;
+        let config = toml::toml! {
+            [todos]
+            disabled = false
+            format = "[$symbol$count \\($todo/$fixme\\)]($style) "
+        };
+
+        let expected = Some(format!("{} ", Color::Yellow.bold().paint("📝 2 (1/1)")));
+        assert_eq!(
+            render(repo_dir.path(), cache_dir.path(), config.clone()),
+            expected
+        );
+        assert!(cache_dir.path().join("todos").read_dir()?.next().is_some());
+
+        write_and_add(repo_dir.path(), "lib.rs", "// TODO: docs\n// TODO: tests\n")?;
+        let expected = Some(format!("{} ", Color::Yellow.bold().paint("📝 4 (3/1)")));
+        assert_eq!(
+            render(repo_dir.path(), cache_dir.path(), config.clone()),
+            expected
+        );
+
+        fs::write(repo_dir.path().join("main.rs"), "fn main() {}\n")?;
+        let expected = Some(format!("{} ", Color::Yellow.bold().paint("📝 2 (2/0)")));
+        assert_eq!(render(repo_dir.path(), cache_dir.path(), config), expected);
+        repo_dir.close()?;
+        cache_dir.close()
+    }
+
+    #[test]
+    fn counts_in_the_background_after_the_scan_timeout() -> io::Result<()> {
+        let repo_dir = repo_with_file("main.rs", "// TODO: parse arguments\n")?;
+        let cache_dir = tempfile::tempdir()?;
+        let fill = display_command(std::env::current_exe()?, &["index-todos"]);
+        let actual = ModuleRenderer::new("todos")
+            .path(repo_dir.path())
+            .env("STARSHIP_CACHE", cache_dir.path().to_string_lossy())
+            .config(toml::toml! {
+                scan_timeout = 0
+                [todos]
+                disabled = false
+            })
+            .cmd(
+                &fill,
+                Some(CommandOutput {
+                    stdout: String::new(),
+                    stderr: String::new(),
+                }),
+            )
+            .collect();
+        assert_eq!(actual, None);
+        let is_filling = cache_dir
+            .path()
+            .join("todos")
+            .read_dir()?
+            .filter_map(Result::ok)
+            .any(|entry| entry.path().extension() == Some("filling".as_ref()));
+        assert!(is_filling);
+        repo_dir.close()?;
+        cache_dir.close()
+    }
+
+    #[test]
+    fn below_threshold() -> io::Result<()> {
+        let repo_dir = repo_with_file("main.rs", "// TODO: parse arguments\n")?;
+        let cache_dir = tempfile::tempdir()?;
+        let config = toml::toml! {
+            [todos]
+            disabled = false
+            threshold = 2
+        };
+        assert_eq!(render(repo_dir.path(), cache_dir.path(), config), None);
+        repo_dir.close()?;
+        cache_dir.close()
+    }
+
+    #[test]
+    fn custom_markers() -> io::Result<()> {
+        let repo_dir = repo_with_file("main.py", "# XXX: slow\n# HACK: retry\n# TODO: docs\n")?;
+        let cache_dir = tempfile::tempdir()?;
+        let config = toml::toml! {
+            [todos]
+            disabled = false
+            markers = ["XXX", "HACK"]
+        };
+        let expected = Some(format!("{} ", Color::Yellow.bold().paint("📝 2")));
+        assert_eq!(render(repo_dir.path(), cache_dir.path(), config), expected);
+        repo_dir.close()?;
+        cache_dir.close()
+    }
+
+    #[test]
+    fn outside_of_repo() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cache_dir = tempfile::tempdir()?;
+        fs::write(dir.path().join("main.rs"), "// TODO: parse arguments\n")?;
+        let config = toml::toml! {
+            [todos]
+            disabled = false
+        };
+        assert_eq!(render(dir.path(), cache_dir.path(), config), None);
+        dir.close()?;
+        cache_dir.close()
+    }
+}
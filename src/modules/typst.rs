@@ -57,7 +57,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
 fn get_typst_config(context: &Context) -> Option<String> {
     context
-        .exec_cmd("typst", &["--version"])?
+        .exec_version_cmd("typst", &["--version"])?
         .stdout
         .trim()
         .strip_prefix("typst ")
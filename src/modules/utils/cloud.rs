@@ -0,0 +1,82 @@
+//! Concerns shared by the modules of cloud providers: aliasing names, like regions and profiles,
+//! and showing how long the credentials of the current session stay valid.
+
+use chrono::DateTime;
+use std::collections::HashMap;
+
+use crate::context::Context;
+
+/// Returns the alias of `name` from an alias table of the module's config, or the name itself
+pub fn alias<'a>(name: &'a str, aliases: &HashMap<String, &'a str>) -> &'a str {
+    aliases.get(name).copied().unwrap_or(name)
+}
+
+/// Parses the time credentials expire at, either in RFC 3339 format or as a Unix timestamp,
+/// into a Unix timestamp
+pub fn parse_expiration(value: &str) -> Option<i64> {
+    let value = value.trim();
+    DateTime::parse_from_rfc3339(value)
+        .map(|expiration| expiration.timestamp())
+        .ok()
+        .or_else(|| value.parse().ok())
+}
+
+/// Renders the time left until the credentials expire, or `expiration_symbol` once they have
+pub fn render_expiration(context: &Context, expires_at: i64, expiration_symbol: &str) -> String {
+    render_seconds_left(
+        context,
+        expires_at - chrono::Local::now().timestamp(),
+        expiration_symbol,
+    )
+}
+
+fn render_seconds_left(context: &Context, seconds_left: i64, expiration_symbol: &str) -> String {
+    if seconds_left > 0 {
+        context.format_duration((seconds_left * 1000) as u128, false)
+    } else {
+        expiration_symbol.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{Shell, Target};
+    use std::path::PathBuf;
+
+    #[test]
+    fn aliases() {
+        let aliases = HashMap::from([("us-east-1".to_string(), "va")]);
+        assert_eq!(alias("us-east-1", &aliases), "va");
+        assert_eq!(alias("eu-west-1", &aliases), "eu-west-1");
+    }
+
+    #[test]
+    fn expiration_formats() {
+        assert_eq!(
+            parse_expiration("2024-01-01T00:00:00Z"),
+            Some(1_704_067_200)
+        );
+        assert_eq!(
+            parse_expiration("2024-01-01T01:00:00+01:00"),
+            Some(1_704_067_200)
+        );
+        assert_eq!(parse_expiration("1704067200"), Some(1_704_067_200));
+        assert_eq!(parse_expiration("tomorrow"), None);
+    }
+
+    #[test]
+    fn expired_credentials() {
+        let context = Context::new_with_shell_and_path(
+            Default::default(),
+            Shell::Unknown,
+            Target::Main,
+            PathBuf::new(),
+            PathBuf::new(),
+            Default::default(),
+        );
+        assert_eq!(render_seconds_left(&context, 3720, "X"), "1h2m0s");
+        assert_eq!(render_seconds_left(&context, 0, "X"), "X");
+        assert_eq!(render_seconds_left(&context, -60, "X"), "X");
+    }
+}
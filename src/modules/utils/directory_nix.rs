@@ -1,11 +1,52 @@
+use nix::errno::Errno;
+use nix::fcntl::AtFlags;
 use nix::sys::stat::Mode;
-use nix::unistd::{Gid, Uid};
+use nix::unistd::{AccessFlags, Gid, Uid};
 use std::fs;
 use std::os::unix::fs::MetadataExt;
 use std::os::unix::fs::PermissionsExt;
 use std::path::Path;
 
-/// Checks if the current user can write to the `folder_path`.
+/// Checks if the current user can write to the `folder_path`, returning why not if they can't.
+///
+/// The check asks the kernel for the effective access of the user, like `access(2)` with the
+/// effective IDs, so ACLs, read-only mounts and security modules such as SELinux are respected
+/// along with the permission bits.
+pub fn write_denied_reason(folder_path: &Path) -> Result<Option<String>, String> {
+    let error = match nix::unistd::faccessat(None, folder_path, AccessFlags::W_OK, effective_ids())
+    {
+        Ok(()) => return Ok(None),
+        Err(error) => error,
+    };
+
+    let reason = match error {
+        Errno::EROFS => "read-only file system".to_string(),
+        Errno::EPERM => "operation not permitted, e.g. by an immutable attribute".to_string(),
+        Errno::EACCES => match mode_denied_reason(folder_path)? {
+            Some(reason) => reason,
+            None => "denied by an ACL or a security policy like SELinux".to_string(),
+        },
+        Errno::ENOENT | Errno::ENOTDIR => {
+            return Err(format!("Unable to access directory: {error}"))
+        }
+        _ => format!("access check failed: {}", error.desc()),
+    };
+    Ok(Some(reason))
+}
+
+#[cfg(not(target_os = "android"))]
+fn effective_ids() -> AtFlags {
+    AtFlags::AT_EACCESS
+}
+
+#[cfg(target_os = "android")]
+fn effective_ids() -> AtFlags {
+    // Android has no `AT_EACCESS`, but apps don't run with differing real and effective IDs
+    AtFlags::empty()
+}
+
+/// Checks whether the permission bits alone keep the current user from writing to the
+/// `folder_path`.
 ///
 /// It extracts Unix access rights from the directory and checks whether
 /// 1) the current user is the owner of the directory and whether it has the write access
@@ -14,24 +55,33 @@ use std::path::Path;
 ///        directory group owner and whether it has write access
 /// 3) 'others' part of the access mask has the write access
 #[allow(clippy::useless_conversion)] // On some platforms it is not u32
-pub fn is_write_allowed(folder_path: &Path) -> Result<bool, String> {
+fn mode_denied_reason(folder_path: &Path) -> Result<Option<String>, String> {
     let meta =
         fs::metadata(folder_path).map_err(|e| format!("Unable to stat() directory: {e:?}"))?;
     let perms = meta.permissions().mode();
 
     let euid = Uid::effective();
     if euid.is_root() {
-        return Ok(true);
+        return Ok(None);
     }
 
-    if meta.uid() == euid.as_raw() {
-        Ok(perms & u32::from(Mode::S_IWUSR.bits()) != 0)
+    let (class, write_bit) = if meta.uid() == euid.as_raw() {
+        ("its owner", Mode::S_IWUSR)
     } else if (meta.gid() == Gid::effective().as_raw())
         || (get_supplementary_groups().contains(&meta.gid()))
     {
-        Ok(perms & u32::from(Mode::S_IWGRP.bits()) != 0)
+        ("its group", Mode::S_IWGRP)
+    } else {
+        ("others", Mode::S_IWOTH)
+    };
+
+    if perms & u32::from(write_bit.bits()) != 0 {
+        Ok(None)
     } else {
-        Ok(perms & u32::from(Mode::S_IWOTH.bits()) != 0)
+        Ok(Some(format!(
+            "mode {:o} denies writing to {class}",
+            perms & 0o7777
+        )))
     }
 }
 
@@ -56,10 +106,20 @@ mod tests {
     #[test]
     #[ignore]
     fn read_only_test() {
-        assert_eq!(is_write_allowed(Path::new("/etc")), Ok(false));
-        assert!(match is_write_allowed(Path::new("/i_dont_exist")) {
+        assert_eq!(
+            write_denied_reason(Path::new("/etc")),
+            Ok(Some("mode 755 denies writing to others".to_string()))
+        );
+        assert!(match write_denied_reason(Path::new("/i_dont_exist")) {
             Ok(_) => false,
-            Err(e) => e.starts_with("Unable to stat() directory"),
+            Err(e) => e.starts_with("Unable to access directory"),
         });
     }
+
+    #[test]
+    fn writable_temp_dir() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        assert_eq!(write_denied_reason(dir.path()), Ok(None));
+        dir.close()
+    }
 }
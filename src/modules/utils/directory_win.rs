@@ -28,12 +28,18 @@ impl Drop for Handle {
     }
 }
 
+/// Checks if the current user can write to the `folder_path`, returning why not if they can't
+pub fn write_denied_reason(folder_path: &Path) -> std::result::Result<Option<String>, String> {
+    Ok((!is_write_allowed(folder_path)?)
+        .then(|| "denied by the access control list of the directory".to_string()))
+}
+
 /// Checks if the current user has write access right to the `folder_path`
 ///
 /// First, the function extracts DACL from the given directory and then calls `AccessCheck` against
 /// the current process access token and directory's security descriptor.
 /// Does not work for network drives and always returns true
-pub fn is_write_allowed(folder_path: &Path) -> std::result::Result<bool, String> {
+fn is_write_allowed(folder_path: &Path) -> std::result::Result<bool, String> {
     let wpath_vec: Vec<u16> = folder_path.as_os_str().encode_wide().chain([0]).collect();
     let wpath = PCWSTR(wpath_vec.as_ptr());
 
@@ -1,3 +1,5 @@
+pub mod cloud;
+
 pub mod directory;
 
 #[cfg(target_os = "windows")]
@@ -8,4 +10,8 @@ pub mod directory_nix;
 
 pub mod path;
 
+pub mod plan;
+
 pub mod truncate;
+
+pub mod version;
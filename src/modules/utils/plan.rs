@@ -0,0 +1,161 @@
+//! Concerns shared by the modules of infrastructure tools: summarizing the changes of a plan the
+//! workflow of the user saved, like `terraform show -json` of a `terraform plan -out` or
+//! `pulumi preview --json`, to show pending changes like `+3 ~1 -0`.
+//!
+//! The tools are never run to make a plan, which takes far too long for a prompt.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// The number of resources a plan adds, changes and destroys
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct PlanSummary {
+    pub add: usize,
+    pub change: usize,
+    pub destroy: usize,
+}
+
+impl PlanSummary {
+    /// Summarizes the JSON of `terraform show -json`, where replaced resources are both added
+    /// and destroyed, like in the output of `terraform plan`
+    pub fn from_terraform_plan(plan: &str) -> Option<Self> {
+        #[derive(Deserialize)]
+        struct Plan {
+            #[serde(default)]
+            resource_changes: Vec<ResourceChange>,
+        }
+        #[derive(Deserialize)]
+        struct ResourceChange {
+            change: Change,
+        }
+        #[derive(Deserialize)]
+        struct Change {
+            actions: Vec<String>,
+        }
+
+        let plan: Plan = serde_json::from_str(plan).ok()?;
+        let mut summary = Self::default();
+        for change in plan.resource_changes {
+            for action in change.change.actions {
+                match action.as_str() {
+                    "create" => summary.add += 1,
+                    "update" => summary.change += 1,
+                    "delete" => summary.destroy += 1,
+                    _ => (),
+                }
+            }
+        }
+        Some(summary)
+    }
+
+    /// Summarizes the `changeSummary` of `pulumi preview --json`, where replaced resources are
+    /// both added and destroyed
+    pub fn from_pulumi_preview(preview: &str) -> Option<Self> {
+        #[derive(Deserialize)]
+        struct Preview {
+            #[serde(rename = "changeSummary", default)]
+            change_summary: HashMap<String, usize>,
+        }
+
+        let preview: Preview = serde_json::from_str(preview).ok()?;
+        let count = |op: &str| preview.change_summary.get(op).copied().unwrap_or(0);
+        Some(Self {
+            add: count("create") + count("replace"),
+            change: count("update"),
+            destroy: count("delete") + count("replace"),
+        })
+    }
+
+    pub fn has_changes(&self) -> bool {
+        self.add + self.change + self.destroy > 0
+    }
+}
+
+impl std::fmt::Display for PlanSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "+{} ~{} -{}", self.add, self.change, self.destroy)
+    }
+}
+
+/// Reads the saved plan at `file`, relative to `dir`, nothing if it isn't configured or missing
+///
+/// A plan is made from the configuration and state in `sources`, so it is outdated once one of
+/// them changed after it was saved, and ignored.
+pub fn read_plan(dir: &Path, file: &str, sources: &[PathBuf]) -> Option<String> {
+    if file.is_empty() {
+        return None;
+    }
+    let plan = dir.join(file);
+    let saved = modified(&plan)?;
+    if sources
+        .iter()
+        .filter_map(|source| modified(source))
+        .any(|changed| changed > saved)
+    {
+        log::debug!(
+            "Ignoring {}, as it is older than its sources",
+            plan.display()
+        );
+        return None;
+    }
+    fs::read_to_string(plan).ok()
+}
+
+fn modified(file: &Path) -> Option<SystemTime> {
+    fs::metadata(file).and_then(|meta| meta.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn terraform_plan() {
+        let plan = r#"{
+            "format_version": "1.2",
+            "resource_changes": [
+                {"address": "aws_instance.web", "change": {"actions": ["create"]}},
+                {"address": "aws_s3_bucket.logs", "change": {"actions": ["update"]}},
+                {"address": "aws_iam_role.ci", "change": {"actions": ["delete", "create"]}},
+                {"address": "aws_vpc.main", "change": {"actions": ["no-op"]}}
+            ]
+        }"#;
+        let summary = PlanSummary::from_terraform_plan(plan).unwrap();
+        assert_eq!(summary.to_string(), "+2 ~1 -1");
+
+        let empty = PlanSummary::from_terraform_plan(r#"{"format_version": "1.2"}"#).unwrap();
+        assert!(!empty.has_changes());
+        assert_eq!(PlanSummary::from_terraform_plan("not json"), None);
+    }
+
+    #[test]
+    fn outdated_plan() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let source = dir.path().join("main.tf");
+        fs::write(dir.path().join("plan.json"), "{}")?;
+        assert_eq!(
+            read_plan(dir.path(), "plan.json", &[source.clone()]),
+            Some("{}".to_string())
+        );
+
+        // Far more than the resolution of modification times
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        fs::write(&source, "")?;
+        assert_eq!(read_plan(dir.path(), "plan.json", &[source]), None);
+        assert_eq!(read_plan(dir.path(), "", &[]), None);
+        dir.close()
+    }
+
+    #[test]
+    fn pulumi_preview() {
+        let preview = r#"{
+            "steps": [],
+            "changeSummary": {"create": 3, "update": 1, "replace": 1, "same": 12}
+        }"#;
+        let summary = PlanSummary::from_pulumi_preview(preview).unwrap();
+        assert_eq!(summary.to_string(), "+4 ~1 -1");
+    }
+}
@@ -1,8 +1,11 @@
 use unicode_segmentation::UnicodeSegmentation;
 
-/// Truncate a string to only have a set number of characters
+use crate::print::UnicodeWidthGraphemes;
+
+/// Truncate a string to fit into a set number of terminal columns
 ///
-/// Will truncate a string to only show the last `length` character in the string.
+/// Will truncate a string to only show the first `length` columns of the string, where wide
+/// characters like CJK take two columns and are never split.
 /// If a length of `0` is provided, the string will not be truncated and the original
 /// will be returned.
 pub fn truncate_text(text: &str, length: usize, truncation_symbol: &str) -> String {
@@ -10,27 +13,14 @@ pub fn truncate_text(text: &str, length: usize, truncation_symbol: &str) -> Stri
         return String::from(text);
     }
 
-    let truncated_graphemes = get_graphemes(text, length);
+    let truncated = text.truncate_to_width(length);
     // The truncation symbol should only be added if we truncated
-    let truncated_and_symbol = if length < graphemes_len(text) {
-        let truncation_symbol = get_graphemes(truncation_symbol, 1);
-        truncated_graphemes + truncation_symbol.as_str()
+    if truncated.len() < text.len() {
+        let truncation_symbol = truncation_symbol.graphemes(true).next().unwrap_or("");
+        format!("{truncated}{truncation_symbol}")
     } else {
-        truncated_graphemes
-    };
-
-    truncated_and_symbol
-}
-
-fn get_graphemes(text: &str, length: usize) -> String {
-    UnicodeSegmentation::graphemes(text, true)
-        .take(length)
-        .collect::<Vec<&str>>()
-        .concat()
-}
-
-fn graphemes_len(text: &str) -> usize {
-    UnicodeSegmentation::graphemes(text, true).count()
+        truncated.to_string()
+    }
 }
 
 #[cfg(test)]
@@ -91,7 +81,12 @@ mod tests {
 
     #[test]
     fn test_japanese_truncation() {
-        test_truncate_length("がんばってね", 4, "がんばっ", "…")
+        test_truncate_length("がんばってね", 4, "がん", "…")
+    }
+
+    #[test]
+    fn test_wide_character_is_not_split() {
+        test_truncate_length("main-東京", 6, "main-", "…")
     }
 
     fn test_truncate_length(
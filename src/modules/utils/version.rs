@@ -0,0 +1,238 @@
+//! Concerns shared by the modules of languages: comparing the installed version with the version
+//! a project declares, like in `.nvmrc` or the `go` directive of `go.mod`, for the `$satisfies`
+//! variable and the `not_capable_style` of the modules.
+
+use semver::{Version, VersionReq};
+
+/// The version of a language a project declares it needs
+#[derive(Debug, PartialEq, Eq)]
+pub enum VersionConstraint {
+    /// The version to use, where `18` or `3.11` match all of their releases, like in `.nvmrc`,
+    /// `.python-version` or `rust-toolchain`
+    Pinned(String),
+    /// The oldest version to use, like the `go` directive of `go.mod`
+    Minimum(String),
+    /// A range of versions like `>=18 <21`, like `engines` in `package.json`
+    Range(String),
+}
+
+impl VersionConstraint {
+    /// A pinned version, unless it names a channel or an alias like `stable`, `lts/*` or `system`,
+    /// which can't be compared
+    pub fn pinned(version: &str) -> Option<Self> {
+        let version = version.trim().trim_start_matches('v');
+        version
+            .starts_with(|c: char| c.is_ascii_digit())
+            .then(|| Self::Pinned(version.to_string()))
+    }
+
+    /// Whether the installed version satisfies the constraint, `None` if either can't be parsed
+    pub fn is_satisfied_by(&self, installed: &str) -> Option<bool> {
+        match self {
+            Self::Pinned(pinned) => {
+                let installed = parse_version(installed)?;
+                let installed = [installed.major, installed.minor, installed.patch];
+                let pinned = numeric_parts(pinned)?;
+                Some(
+                    pinned
+                        .iter()
+                        .zip(installed)
+                        .all(|(pinned, installed)| *pinned == installed),
+                )
+            }
+            Self::Minimum(minimum) => {
+                Some(parse_precise_version(installed)? >= parse_precise_version(minimum)?)
+            }
+            Self::Range(range) => {
+                let installed = parse_version(installed)?;
+                let alternatives: Option<Vec<Requirement>> =
+                    range.split("||").map(parse_requirement).collect();
+                Some(
+                    alternatives?
+                        .iter()
+                        .any(|requirement| requirement.matches(&installed)),
+                )
+            }
+        }
+    }
+}
+
+/// The value of `$satisfies`, nothing without a declared version or an installed version
+pub fn satisfies(installed: Option<&str>, constraint: Option<&VersionConstraint>) -> Option<bool> {
+    constraint?.is_satisfied_by(installed?)
+}
+
+/// Parses versions leniently, like `v18.2.0`, `1.21` as `1.21.0` or `3.13.0rc1` as `3.13.0`
+fn parse_version(version: &str) -> Option<Version> {
+    let parts = numeric_parts(version.trim().trim_start_matches('v'))?;
+    let part = |index: usize| parts.get(index).copied().unwrap_or(0);
+    Some(Version::new(part(0), part(1), part(2)))
+}
+
+/// Parses versions like [`parse_version`], but keeps the pre-release of a semantic version, so
+/// nightlies like `0.12.0-dev.3180+83e578a18` order before their release
+fn parse_precise_version(version: &str) -> Option<Version> {
+    Version::parse(version.trim().trim_start_matches('v'))
+        .ok()
+        .or_else(|| parse_version(version))
+}
+
+/// The numbers of the major, minor and patch parts a version starts with
+fn numeric_parts(version: &str) -> Option<Vec<u64>> {
+    let parts: Vec<u64> = version
+        .split('.')
+        .take(3)
+        .map_while(|part| {
+            let end = part
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(part.len());
+            part[..end].parse().ok()
+        })
+        .collect();
+    (!parts.is_empty()).then_some(parts)
+}
+
+/// A range of versions and the versions it leaves out, like `!= 2.7.1`, which semver can't express
+struct Requirement {
+    range: VersionReq,
+    excluded: Vec<Version>,
+}
+
+impl Requirement {
+    fn matches(&self, version: &Version) -> bool {
+        self.range.matches(version) && !self.excluded.contains(version)
+    }
+}
+
+/// Parses a requirement whose comparators are separated by spaces, as npm writes them, or by
+/// commas, as gemspecs and Terraform write them
+///
+/// A version without an operator is the only one that matches, as for npm, gemspecs and
+/// Terraform.
+fn parse_requirement(requirement: &str) -> Option<Requirement> {
+    let mut comparators: Vec<String> = Vec::new();
+    for token in requirement
+        .split([' ', ','])
+        .filter(|token| !token.is_empty())
+    {
+        // The operator of `>= 18` is a token of its own
+        match comparators.last_mut() {
+            Some(last) if last.chars().all(|c| "<>=~^!".contains(c)) => last.push_str(token),
+            _ => comparators.push(token.to_string()),
+        }
+    }
+
+    let mut range = Vec::new();
+    let mut excluded = Vec::new();
+    for comparator in comparators {
+        if let Some(version) = comparator.strip_prefix("!=") {
+            excluded.push(parse_version(version)?);
+        } else if let Some(version) = comparator.strip_prefix("~>") {
+            range.push(pessimistic_to_range(version)?);
+        } else if comparator.starts_with(|c: char| c.is_ascii_digit()) {
+            range.push(format!("={comparator}"));
+        } else {
+            range.push(comparator);
+        }
+    }
+    let range = if range.is_empty() {
+        VersionReq::STAR
+    } else {
+        VersionReq::parse(&range.join(", ")).ok()?
+    };
+    Some(Requirement { range, excluded })
+}
+
+/// Rewrites the pessimistic operator of RubyGems and Mix, where `~> 2.7` allows `>= 2.7, < 3`
+/// and `~> 2.7.1` allows `>= 2.7.1, < 2.8`
+fn pessimistic_to_range(version: &str) -> Option<String> {
+    let mut parts = numeric_parts(version)?;
+    if parts.len() > 1 {
+        parts.pop();
+    }
+    if let Some(last) = parts.last_mut() {
+        *last += 1;
+    }
+    let upper: Vec<String> = parts.iter().map(u64::to_string).collect();
+    Some(format!(">={version}, <{}", upper.join(".")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pinned_versions() {
+        let pinned = VersionConstraint::pinned("v18\n").unwrap();
+        assert_eq!(pinned.is_satisfied_by("v18.19.0"), Some(true));
+        assert_eq!(pinned.is_satisfied_by("v20.11.1"), Some(false));
+
+        let pinned = VersionConstraint::pinned("3.11.4").unwrap();
+        assert_eq!(pinned.is_satisfied_by("3.11.4"), Some(true));
+        assert_eq!(pinned.is_satisfied_by("3.11.5"), Some(false));
+
+        assert_eq!(VersionConstraint::pinned("lts/*"), None);
+        assert_eq!(VersionConstraint::pinned("stable"), None);
+    }
+
+    #[test]
+    fn minimum_versions() {
+        let minimum = VersionConstraint::Minimum("1.21".to_string());
+        assert_eq!(minimum.is_satisfied_by("1.21.0"), Some(true));
+        assert_eq!(minimum.is_satisfied_by("1.22rc1"), Some(true));
+        assert_eq!(minimum.is_satisfied_by("1.20.14"), Some(false));
+
+        let minimum = VersionConstraint::Minimum("0.12.0".to_string());
+        assert_eq!(
+            minimum.is_satisfied_by("0.12.0-dev.3180+83e578a18"),
+            Some(false)
+        );
+        assert_eq!(
+            minimum.is_satisfied_by("0.13.0-dev.46+3648d7df1"),
+            Some(true)
+        );
+        let minimum = VersionConstraint::Minimum("0.12.0-dev.2000".to_string());
+        assert_eq!(
+            minimum.is_satisfied_by("0.12.0-dev.3180+83e578a18"),
+            Some(true)
+        );
+        assert_eq!(minimum.is_satisfied_by("0.12.0-dev.1900"), Some(false));
+    }
+
+    #[test]
+    fn ranges() {
+        let range = VersionConstraint::Range(">= 18 <21 || ^22".to_string());
+        assert_eq!(range.is_satisfied_by("20.1.0"), Some(true));
+        assert_eq!(range.is_satisfied_by("22.3.0"), Some(true));
+        assert_eq!(range.is_satisfied_by("21.0.0"), Some(false));
+
+        let range = VersionConstraint::Range(">= 2.7.0, < 4".to_string());
+        assert_eq!(range.is_satisfied_by("3.3.0"), Some(true));
+
+        let pessimistic = VersionConstraint::Range("~> 2.7".to_string());
+        assert_eq!(pessimistic.is_satisfied_by("2.9.1"), Some(true));
+        assert_eq!(pessimistic.is_satisfied_by("3.0.0"), Some(false));
+        let pessimistic = VersionConstraint::Range("~> 2.7.1".to_string());
+        assert_eq!(pessimistic.is_satisfied_by("2.7.5"), Some(true));
+        assert_eq!(pessimistic.is_satisfied_by("2.8.0"), Some(false));
+
+        let exact = VersionConstraint::Range("3.1.0".to_string());
+        assert_eq!(exact.is_satisfied_by("3.1.0"), Some(true));
+        assert_eq!(exact.is_satisfied_by("3.2.5"), Some(false));
+
+        let terraform = VersionConstraint::Range("~> 1.5.0".to_string());
+        assert_eq!(terraform.is_satisfied_by("1.5.7"), Some(true));
+        assert_eq!(terraform.is_satisfied_by("1.6.0"), Some(false));
+        let tool = VersionConstraint::Range(">=1.6".to_string());
+        assert_eq!(tool.is_satisfied_by("1.7.0-stable"), Some(true));
+
+        let excluding = VersionConstraint::Range(">= 1.5, != 1.5.7".to_string());
+        assert_eq!(excluding.is_satisfied_by("1.5.6"), Some(true));
+        assert_eq!(excluding.is_satisfied_by("1.5.7-rc1"), Some(false));
+
+        let invalid = VersionConstraint::Range("latest".to_string());
+        assert_eq!(invalid.is_satisfied_by("20.1.0"), None);
+        assert_eq!(satisfies(None, Some(&range)), None);
+        assert_eq!(satisfies(Some("3.3.0"), None), None);
+    }
+}
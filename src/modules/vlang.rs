@@ -30,7 +30,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             })
             .map(|variable| match variable {
                 "version" => context
-                    .exec_cmd("v", &["version"])
+                    .exec_version_cmd("v", &["version"])
                     .map(|output| parse_v_version(&output.stdout))?
                     .map(|output| {
                         VersionFormatter::format_module_version(
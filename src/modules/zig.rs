@@ -1,9 +1,14 @@
+use super::utils::version::{self, VersionConstraint};
 use super::{Context, Module, ModuleConfig};
 
 use crate::configs::zig::ZigConfig;
 use crate::formatter::StringFormatter;
 use crate::formatter::VersionFormatter;
 
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::ops::Deref;
+
 /// Creates a module with the current Zig version
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("zig");
@@ -20,6 +25,24 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
+    let manifest = Lazy::new(|| {
+        context
+            .read_file_from_pwd("build.zig.zon")
+            .map(|zon| parse_manifest(&zon))
+    });
+    let zig_version = Lazy::new(|| {
+        let output = context.exec_version_cmd("zig", &["version"])?.stdout;
+        Some(output.trim().to_string())
+    });
+    let satisfies = Lazy::new(|| {
+        let constraint = manifest
+            .as_ref()?
+            .minimum_zig_version
+            .as_ref()
+            .map(|minimum| VersionConstraint::Minimum(minimum.clone()));
+        version::satisfies(zig_version.as_deref(), constraint.as_ref())
+    });
+
     let parsed = StringFormatter::new(config.format).and_then(|formatter| {
         formatter
             .map_meta(|variable, _| match variable {
@@ -27,19 +50,29 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 _ => None,
             })
             .map_style(|variable| match variable {
-                "style" => Some(Ok(config.style)),
+                "style" => {
+                    if *satisfies == Some(false) {
+                        Some(Ok(config.not_capable_style))
+                    } else {
+                        Some(Ok(config.style))
+                    }
+                }
                 _ => None,
             })
             .map(|variable| match variable {
-                "version" => {
-                    let zig_version = context.exec_cmd("zig", &["version"])?.stdout;
-                    VersionFormatter::format_module_version(
-                        module.get_name(),
-                        zig_version.trim(),
-                        config.version_format,
-                    )
-                    .map(Ok)
-                }
+                "version" => VersionFormatter::format_module_version(
+                    module.get_name(),
+                    zig_version.deref().as_ref()?,
+                    config.version_format,
+                )
+                .map(Ok),
+                "minimum_version" => manifest.as_ref()?.minimum_zig_version.clone().map(Ok),
+                "dependencies" => manifest
+                    .as_ref()?
+                    .dependencies
+                    .filter(|count| *count > 0)
+                    .map(|count| Ok(count.to_string())),
+                "satisfies" => satisfies.map(|satisfies| Ok(satisfies.to_string())),
                 _ => None,
             })
             .parse(None, Some(context))
@@ -56,13 +89,89 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
+/// What the module shows of `build.zig.zon`, the package manifest of Zig
+#[derive(Debug, Default, PartialEq)]
+struct Manifest {
+    minimum_zig_version: Option<String>,
+    /// The number of entries in `.dependencies`, `None` without the field
+    dependencies: Option<usize>,
+}
+
+/// Reads the manifest, which is written in ZON, the object notation of Zig, like
+/// `.{ .name = "app", .minimum_zig_version = "0.13.0", .dependencies = .{ .zap = .{ ... } } }`
+fn parse_manifest(zon: &str) -> Manifest {
+    let minimum_zig_version = Regex::new(r#"\.minimum_zig_version\s*=\s*"([^"]*)""#)
+        .ok()
+        .and_then(|field| Some(field.captures(zon)?[1].to_string()));
+    let dependencies = Regex::new(r"\.dependencies\s*=\s*\.\{")
+        .ok()
+        .and_then(|field| field.find(zon))
+        .map(|field| count_fields(&zon[field.end()..]));
+    Manifest {
+        minimum_zig_version,
+        dependencies,
+    }
+}
+
+/// Counts the fields of the struct literal `zon` starts in, up to its closing brace
+///
+/// Every field is assigned with `=` at the top level of the struct, while strings and comments
+/// may contain anything.
+fn count_fields(zon: &str) -> usize {
+    let mut fields = 0;
+    let mut depth = 0;
+    let mut chars = zon.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => break,
+                        _ => (),
+                    }
+                }
+            }
+            '/' if chars.as_str().starts_with('/') => {
+                chars.find(|c| *c == '\n');
+            }
+            '{' => depth += 1,
+            '}' if depth == 0 => break,
+            '}' => depth -= 1,
+            '=' if depth == 0 => fields += 1,
+            _ => (),
+        }
+    }
+    fields
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::test::ModuleRenderer;
+    use crate::utils::CommandOutput;
     use nu_ansi_term::Color;
-    use std::fs::File;
+    use std::fs::{self, File};
     use std::io;
 
+    const MANIFEST: &str = r#".{
+    .name = "app",
+    .version = "0.1.0",
+    // Switch back to the release once it has the fix
+    .minimum_zig_version = "0.13.0",
+    .dependencies = .{
+        .zap = .{
+            .url = "https://github.com/zigzap/zap/archive/v0.8.0.tar.gz",
+            .hash = "12209936c3333b53b53edcf453b1670babb9ae8c2197b1ca627c01e72670e20c1a21",
+        },
+        .@"known-folders" = .{ .path = "../known-folders" },
+    },
+    .paths = .{""},
+}
+"#;
+
     #[test]
     fn folder_without_zig() -> io::Result<()> {
         let dir = tempfile::tempdir()?;
@@ -73,6 +182,69 @@ mod tests {
         dir.close()
     }
 
+    #[test]
+    fn manifest_requires_newer_zig() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("build.zig"))?.sync_all()?;
+        fs::write(dir.path().join("build.zig.zon"), MANIFEST)?;
+        let actual = ModuleRenderer::new("zig")
+            .path(dir.path())
+            .config(toml::toml! {
+                [zig]
+                format = "via [$symbol($version )(>=$minimum_version )(📦 $dependencies )]($style)"
+            })
+            .collect();
+        let expected = Some(format!(
+            "via {}",
+            Color::Red.bold().paint("↯ v0.6.0 >=0.13.0 📦 2 ")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn nightly_before_minimum_release() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("build.zig"))?.sync_all()?;
+        fs::write(dir.path().join("build.zig.zon"), MANIFEST)?;
+        let actual = ModuleRenderer::new("zig")
+            .path(dir.path())
+            .cmd(
+                "zig version",
+                Some(CommandOutput {
+                    stdout: "0.13.0-dev.351+64ef45eb0\n".to_string(),
+                    stderr: String::new(),
+                }),
+            )
+            .config(toml::toml! {
+                [zig]
+                format = "via [$symbol$satisfies]($style) "
+            })
+            .collect();
+        let expected = Some(format!("via {} ", Color::Red.bold().paint("↯ false")));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        assert_eq!(
+            parse_manifest(MANIFEST),
+            Manifest {
+                minimum_zig_version: Some("0.13.0".to_string()),
+                dependencies: Some(2),
+            }
+        );
+        assert_eq!(
+            parse_manifest(".{ .name = \"app\", .dependencies = .{}, }"),
+            Manifest {
+                minimum_zig_version: None,
+                dependencies: Some(0),
+            }
+        );
+        assert_eq!(parse_manifest(".{ .name = \"app\" }"), Manifest::default());
+    }
+
     #[test]
     fn folder_with_zig_file() -> io::Result<()> {
         let dir = tempfile::tempdir()?;
@@ -0,0 +1,176 @@
+use std::collections::BTreeSet;
+use std::io::{self, BufRead, Write};
+use toml::Value;
+
+use crate::configs::FullConfig;
+use crate::context::{Context, Properties, Target, GLOBAL_VARIABLES};
+use crate::formatter::{StringFormatter, VariableHolder};
+use crate::lint::{module_variables, root_variables};
+use crate::module::ALL_MODULES;
+use crate::print::{get_module, get_prompt};
+
+const HELP: &str = "\
+Type a format string to render it, or one of these commands:
+  :module <name>  render the format of a module, without a name the whole prompt
+  :vars           list the variables of the selected module
+  :quit           leave the playground";
+
+/// Renders format strings typed by the user against the current directory
+///
+/// With a module selected, the format string replaces the format of that module; otherwise it
+/// replaces the format of the whole prompt. Variables that don't exist are completed from the
+/// variables of the selected module. Modules may resolve further variables at runtime, like a
+/// variable per configured marker, so only names that look like a known variable are reported.
+pub fn playground(properties: Properties, module: Option<String>) -> io::Result<()> {
+    let defaults = Value::try_from(FullConfig::default()).unwrap_or(Value::Boolean(false));
+    let mut module = match module {
+        Some(name) if !ALL_MODULES.contains(&name.as_str()) => {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{name} is not a module"),
+            ))
+        }
+        module => module,
+    };
+
+    println!("{HELP}\n");
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        match &module {
+            Some(name) => print!("{name}> "),
+            None => print!("format> "),
+        }
+        io::stdout().flush()?;
+        let Some(line) = lines.next().transpose()? else {
+            println!();
+            return Ok(());
+        };
+
+        let context = Context::new(properties.clone(), Target::Main);
+        let known = match &module {
+            Some(name) => {
+                let configured = context.config.config.clone().map(Value::Table);
+                let mut known = module_variables(&defaults, name);
+                known.extend(configured.map_or_else(BTreeSet::new, |configured| {
+                    module_variables(&configured, name)
+                }));
+                known
+            }
+            None => root_variables(&context),
+        };
+        match line.trim().split_once(' ').unwrap_or((line.trim(), "")) {
+            ("", _) => {}
+            (":quit" | ":q", _) => return Ok(()),
+            (":help", _) => println!("{HELP}"),
+            (":vars", _) => println!("{}", list(known.iter())),
+            (":module", "") => module = None,
+            (":module", name) if ALL_MODULES.contains(&name) => module = Some(name.to_string()),
+            (":module", name) => println!("{name} is not a module"),
+            _ => {
+                println!("{}", render(context, module.as_deref(), &line));
+                for (variable, completions) in completions(&known, &line) {
+                    println!("${variable} could be {}", list(completions.into_iter()));
+                }
+            }
+        }
+    }
+}
+
+/// Renders the format string in place of the configured format of the module or prompt
+fn render(context: Context, module: Option<&str>, format: &str) -> String {
+    let mut config = context.config.config.clone().unwrap_or_default();
+    let format = Value::String(format.to_string());
+    match module {
+        Some(name) => {
+            let mut table = config
+                .get(name)
+                .and_then(Value::as_table)
+                .cloned()
+                .unwrap_or_default();
+            // Show modules that are disabled by default too
+            table.insert("disabled".to_string(), Value::Boolean(false));
+            table.insert("format".to_string(), format);
+            config.insert(name.to_string(), Value::Table(table));
+        }
+        None => {
+            config.insert("add_newline".to_string(), Value::Boolean(false));
+            config.insert("format".to_string(), format);
+        }
+    }
+
+    let context = context.set_config(config);
+    match module {
+        Some(name) => get_module(name, context)
+            .unwrap_or_else(|| format!("({name} is not shown in this directory)")),
+        None => get_prompt(context),
+    }
+}
+
+/// Finds the variables of the format string that don't exist, each with the known variables
+/// it is a prefix of
+///
+/// Variables that aren't the prefix of any known variable are left out, since they may be
+/// resolved by the module at runtime.
+fn completions<'a>(known: &'a BTreeSet<String>, format: &str) -> Vec<(String, Vec<&'a String>)> {
+    let Ok(formatter) = StringFormatter::new(format) else {
+        return Vec::new();
+    };
+    formatter
+        .get_variables()
+        .into_iter()
+        .filter(|variable| {
+            !known.contains(variable) && !GLOBAL_VARIABLES.contains(&variable.as_str())
+        })
+        .map(|variable| {
+            let completions = known
+                .iter()
+                .filter(|candidate| candidate.starts_with(&variable))
+                .collect();
+            (variable, completions)
+        })
+        .filter(|(_, completions)| !completions.is_empty())
+        .collect()
+}
+
+fn list<'a>(variables: impl Iterator<Item = &'a String>) -> String {
+    variables
+        .map(|variable| format!("${variable}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::default_context;
+
+    #[test]
+    fn complete_unknown_variables() {
+        let known: BTreeSet<String> = ["branch", "remote_branch", "symbol"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        let completions = completions(&known, "[$sym $bra $remote_branch $todo]($style)");
+
+        assert_eq!(
+            completions,
+            vec![
+                ("bra".to_string(), vec![&"branch".to_string()]),
+                ("sym".to_string(), vec![&"symbol".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn render_module_format() {
+        let context = default_context().set_config(toml::toml! {
+            [character]
+            format = "ignored"
+            success_symbol = ">"
+        });
+
+        assert_eq!(render(context, Some("character"), "=$symbol="), "=>=");
+    }
+}
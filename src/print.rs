@@ -1,29 +1,59 @@
 use clap::{builder::PossibleValue, ValueEnum};
 use nu_ansi_term::AnsiStrings;
+use once_cell::sync::Lazy;
 use rayon::prelude::*;
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
 use std::fmt::{Debug, Write as FmtWrite};
 use std::io::{self, Write};
 use std::path::PathBuf;
+use std::sync::Mutex;
 use std::time::Duration;
 use terminal_size::terminal_size;
 use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthChar;
 
-use crate::configs::PROMPT_ORDER;
-use crate::context::{Context, Properties, Shell, Target};
+use crate::color_depth;
+use crate::configs::{FullConfig, PROMPT_ORDER};
+use crate::context::{Context, Properties, Shell, Target, GLOBAL_VARIABLES};
 use crate::formatter::{StringFormatter, VariableHolder};
 use crate::module::Module;
 use crate::module::ALL_MODULES;
 use crate::modules;
+use crate::prompt_cache;
 use crate::segment::Segment;
+use crate::session;
 use crate::shadow;
+use crate::stats;
 use crate::utils::wrap_colorseq_for_shell;
 
+/// The module reporting on the problems of all other modules, which has to be computed last
+const REPORTING_MODULE: &str = "errors";
+
 pub struct Grapheme<'a>(pub &'a str);
 
 impl<'a> Grapheme<'a> {
+    /// Returns the number of terminal columns the grapheme occupies
+    ///
+    /// Wide East Asian characters take two columns. Emoji sequences are drawn as one wide
+    /// glyph, even if they are made of several narrow code points like keycaps and flags.
     pub fn width(&self) -> usize {
+        let mut chars = self.0.chars();
+        let Some(first) = chars.next() else {
+            return 0;
+        };
+
+        if self.0.contains('\u{FE0E}') {
+            // Explicitly requested text presentation, e.g. `☁︎`
+            return first.width().unwrap_or(0);
+        }
+        let is_regional_indicator = |c: char| ('\u{1F1E6}'..='\u{1F1FF}').contains(&c);
+        if self.0.contains(['\u{FE0F}', '\u{20E3}'])
+            || (is_regional_indicator(first) && chars.next().map_or(false, is_regional_indicator))
+        {
+            // Emoji presentation, keycaps like `1️⃣` and flags like `🇩🇪`
+            return 2;
+        }
+
         self.0
             .chars()
             .filter_map(UnicodeWidthChar::width)
@@ -34,6 +64,9 @@ impl<'a> Grapheme<'a> {
 
 pub trait UnicodeWidthGraphemes {
     fn width_graphemes(&self) -> usize;
+
+    /// Returns the longest prefix that fits into `width` columns without splitting a grapheme
+    fn truncate_to_width(&self, width: usize) -> &str;
 }
 
 impl<T> UnicodeWidthGraphemes for T
@@ -47,6 +80,18 @@ where
             .map(|g| g.width())
             .sum()
     }
+
+    fn truncate_to_width(&self, width: usize) -> &str {
+        let text = self.as_ref();
+        let mut used = 0;
+        for (idx, g) in text.grapheme_indices(true) {
+            used += Grapheme(g).width();
+            if used > width {
+                return &text[..idx];
+            }
+        }
+        text
+    }
 }
 
 #[test]
@@ -57,14 +102,53 @@ fn test_grapheme_aware_width() {
     assert_eq!(11, "normal text".width_graphemes());
 }
 
+#[test]
+fn test_wide_grapheme_width() {
+    assert_eq!(4, "東京".width_graphemes());
+    assert_eq!(2, "🇩🇪".width_graphemes());
+    assert_eq!(2, "1️⃣".width_graphemes());
+    assert_eq!(2, "☁️".width_graphemes());
+    assert_eq!(1, "☁︎".width_graphemes());
+    assert_eq!(0, "\u{200B}".width_graphemes());
+}
+
+#[test]
+fn test_truncate_to_width() {
+    assert_eq!("src/東", "src/東京".truncate_to_width(7));
+    assert_eq!("src/東", "src/東京".truncate_to_width(6));
+    assert_eq!("🇩🇪", "🇩🇪🇫🇷".truncate_to_width(3));
+    assert_eq!("", "東京".truncate_to_width(1));
+    assert_eq!("normal", "normal".truncate_to_width(10));
+}
+
 pub fn prompt(args: Properties, target: Target) {
     let context = Context::new(args, target);
+    // Cached prompts are never animated, so only a rendered prompt marks the session as waiting
+    let mut pending = Vec::new();
+    let prompt = prompt_cache::get_or_render(&context, || {
+        pending = context.pending_modules();
+        prompt_string(&context)
+    });
+    if context.target == Target::Main {
+        if let Err(error) = session::set_pending(&context, &pending) {
+            log::debug!("Unable to mark the modules waiting for a refresh: {error}");
+        }
+    }
     let stdout = io::stdout();
     let mut handle = stdout.lock();
-    write!(handle, "{}", get_prompt(context)).unwrap();
+    write!(handle, "{prompt}").unwrap();
+    stats::record(&context);
 }
 
 pub fn get_prompt(context: Context) -> String {
+    prompt_string(&context)
+}
+
+fn prompt_string(context: &Context) -> String {
+    if context.target == Target::StatusBar {
+        return status_bar_string(context);
+    }
+
     let config = &context.root_config;
     let mut buf = String::new();
 
@@ -83,40 +167,11 @@ pub fn get_prompt(context: Context) -> String {
         buf.push_str("\x1b[J"); // An ASCII control code to clear screen
     }
 
-    let (formatter, modules) = load_formatter_and_modules(&context);
-
-    let formatter = formatter.map_variables_to_segments(|module| {
-        // Make $all display all modules not explicitly referenced
-        if module == "all" {
-            Some(Ok(all_modules_uniq(&modules)
-                .par_iter()
-                .flat_map(|module| {
-                    handle_module(module, &context, &modules)
-                        .into_iter()
-                        .flat_map(|module| module.segments)
-                        .collect::<Vec<Segment>>()
-                })
-                .collect::<Vec<_>>()))
-        } else if context.is_module_disabled_in_config(module) {
-            None
-        } else {
-            // Get segments from module
-            Some(Ok(handle_module(module, &context, &modules)
-                .into_iter()
-                .flat_map(|module| module.segments)
-                .collect::<Vec<Segment>>()))
-        }
-    });
-
-    // Creates a root module and prints it.
     let mut root_module = Module::new("Starship Root", "The root module", None);
-    root_module.set_segments(
-        formatter
-            .parse(None, Some(&context))
-            .expect("Unexpected error returned in root format variables"),
-    );
+    root_module.set_segments(prompt_segments(context));
 
     let module_strings = root_module.ansi_strings_for_width(Some(context.width));
+    let module_strings = color_depth::degrade_strings(context, module_strings);
     if config.add_newline && context.target != Target::Continuation {
         // continuation prompts normally do not include newlines, but they can
         writeln!(buf).unwrap();
@@ -142,6 +197,254 @@ pub fn get_prompt(context: Context) -> String {
     buf
 }
 
+/// Draws `status_bar_format` on the first row of the terminal and keeps the output below it
+/// from scrolling over the bar
+///
+/// The init scripts print the bar straight to the terminal before each prompt, so it is not
+/// wrapped for the shell and the cursor is put back where the prompt will be drawn. The scrolling region is set
+/// again each time, as programs like `clear` or `reset` may have reset it.
+fn status_bar_string(context: &Context) -> String {
+    let is_dumb = std::env::var_os("TERM").map_or(false, |term| term == "dumb");
+    if context.root_config.status_bar_format.is_empty() || is_dumb {
+        return String::new();
+    }
+
+    let mut root_module = Module::new("Starship Root", "The root module", None);
+    root_module.set_segments(prompt_segments(context));
+    let module_strings = root_module.ansi_strings_for_width(Some(context.width));
+    let module_strings = color_depth::degrade_strings(context, module_strings);
+    let bar = AnsiStrings(&module_strings).to_string().replace('\n', "");
+
+    // Save the cursor, limit scrolling to the rows below the bar (which moves the cursor),
+    // draw the bar on the first row without wrapping and restore the cursor
+    format!("\x1b7\x1b[2r\x1b[1;1H\x1b[2K\x1b[?7l{bar}\x1b[?7h\x1b8")
+}
+
+/// Renders the prompt described by `config` for the given context.
+///
+/// Unlike [`get_prompt`] this performs no shell-specific escaping and returns the styled
+/// segments as-is, so that programs embedding starship can draw them however they like.
+#[cfg(feature = "embed")]
+pub fn render(config: toml::Table, context: Context) -> Vec<Segment> {
+    let context = context.set_config(config);
+    prompt_segments(&context)
+}
+
+/// How a module with a `priority` is shrunk to fit the prompt into `max_prompt_width`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Shrink {
+    /// Only the symbol of the module is shown
+    Collapsed,
+    Hidden,
+}
+
+/// The outputs of the modules of a prompt, so the prompt can be formatted again with some of
+/// them shrunk without computing them again
+#[derive(Default)]
+struct PromptModules {
+    /// The name and segments of the modules each root format variable stands for
+    outputs: Mutex<HashMap<String, Vec<(String, Vec<Segment>)>>>,
+    shrunk: HashMap<String, Shrink>,
+}
+
+/// Computes the segments of the prompt selected by `context.target`
+///
+/// If a line of the prompt is wider than `max_prompt_width` or the terminal, modules with a
+/// `priority` are collapsed to their symbol, lowest priority first, and then hidden, until it
+/// fits.
+fn prompt_segments(context: &Context) -> Vec<Segment> {
+    let mut prompt_modules = PromptModules::default();
+    let mut segments = render_segments(context, &prompt_modules);
+    let Some(max_width) = max_prompt_width(context) else {
+        return segments;
+    };
+
+    let candidates = shrink_candidates(context, &prompt_modules);
+    for shrink in [Shrink::Collapsed, Shrink::Hidden] {
+        for module in &candidates {
+            if widest_line(&segments) <= max_width {
+                return segments;
+            }
+            if shrink == Shrink::Collapsed && collapsed_segments(context, module).is_none() {
+                continue;
+            }
+            log::debug!("Shrinking module {module:?} to fit the prompt: {shrink:?}");
+            prompt_modules.shrunk.insert(module.clone(), shrink);
+            segments = render_segments(context, &prompt_modules);
+        }
+    }
+    segments
+}
+
+fn render_segments(context: &Context, prompt_modules: &PromptModules) -> Vec<Segment> {
+    let (formatter, modules) = load_formatter_and_modules(context);
+    let segments = format_segments(context, formatter, &modules, prompt_modules);
+    match context.target {
+        // The upper lines of a multi-line right format are drawn by the main prompt
+        Target::Right if multiline_right_formatter(context).is_some() => {
+            last_line(&segments).to_vec()
+        }
+        Target::Main => match multiline_right_formatter(context) {
+            Some(formatter) => {
+                let right = format_segments(context, formatter, &modules, prompt_modules);
+                add_right_blocks(segments, &right)
+            }
+            None => segments,
+        },
+        _ => segments,
+    }
+}
+
+/// Computes the modules referenced in a root format string and fills them in
+fn format_segments<'a>(
+    context: &'a Context,
+    formatter: StringFormatter<'a>,
+    modules: &BTreeSet<String>,
+    prompt_modules: &PromptModules,
+) -> Vec<Segment> {
+    let formatter = formatter.map_variables_to_segments(|module| {
+        if module == REPORTING_MODULE {
+            // Computed below, once the problems of all other modules are known
+            None
+        } else if module == "all" {
+            // Make $all display all modules not explicitly referenced
+            Some(Ok(all_modules_uniq(modules)
+                .par_iter()
+                .flat_map(|module| module_segments(module, context, modules, prompt_modules))
+                .collect::<Vec<_>>()))
+        } else if context.is_module_disabled_in_config(module) {
+            // Resolve to nothing so the variable is not filled in as a global variable
+            Some(Ok(Vec::new()))
+        } else {
+            // Get segments from module
+            Some(Ok(module_segments(
+                module,
+                context,
+                modules,
+                prompt_modules,
+            )))
+        }
+    });
+
+    let formatter = formatter.map_variables_to_segments(|module| {
+        (module == REPORTING_MODULE)
+            .then(|| Ok(module_segments(module, context, modules, prompt_modules)))
+    });
+
+    formatter
+        .parse(None, Some(context))
+        .expect("Unexpected error returned in root format variables")
+}
+
+/// Returns the segments of the modules a root format variable stands for, computing them only
+/// the first time the prompt is formatted
+fn module_segments(
+    variable: &str,
+    context: &Context,
+    modules: &BTreeSet<String>,
+    prompt_modules: &PromptModules,
+) -> Vec<Segment> {
+    let computed = prompt_modules
+        .outputs
+        .lock()
+        .ok()
+        .and_then(|outputs| outputs.get(variable).cloned());
+    let outputs = computed.unwrap_or_else(|| {
+        let outputs: Vec<(String, Vec<Segment>)> = handle_module(variable, context, modules)
+            .into_iter()
+            .map(|module| (module.get_name().clone(), module.segments))
+            .collect();
+        if let Ok(mut computed) = prompt_modules.outputs.lock() {
+            computed.insert(variable.to_string(), outputs.clone());
+        }
+        outputs
+    });
+
+    outputs
+        .into_iter()
+        .flat_map(|(name, segments)| match prompt_modules.shrunk.get(&name) {
+            None => segments,
+            Some(Shrink::Collapsed) => collapsed_segments(context, &name).unwrap_or_default(),
+            Some(Shrink::Hidden) => Vec::new(),
+        })
+        .collect()
+}
+
+/// The width the lines of the main prompt have to fit into, if any module can be shrunk
+fn max_prompt_width(context: &Context) -> Option<usize> {
+    if context.target != Target::Main {
+        return None;
+    }
+    let max_width = match (context.root_config.max_prompt_width, context.width) {
+        (0, width) => width,
+        (max_width, 0) => max_width,
+        (max_width, width) => max_width.min(width),
+    };
+    (max_width > 0).then_some(max_width)
+}
+
+/// The modules of the prompt with a `priority`, in the order they are shrunk
+fn shrink_candidates(context: &Context, prompt_modules: &PromptModules) -> Vec<String> {
+    let Ok(outputs) = prompt_modules.outputs.lock() else {
+        return Vec::new();
+    };
+    let mut candidates: Vec<(i64, &String)> = outputs
+        .values()
+        .flatten()
+        .filter(|(_, segments)| !segments.is_empty())
+        .filter_map(|(name, _)| Some((context.module_priority(name)?, name)))
+        .collect();
+    candidates.sort();
+    candidates
+        .into_iter()
+        .map(|(_, name)| name.clone())
+        .collect()
+}
+
+/// The default configuration of all modules, where collapsed modules look up their symbol
+static DEFAULT_CONFIG: Lazy<Option<toml::Value>> =
+    Lazy::new(|| toml::Value::try_from(FullConfig::default()).ok());
+
+/// Renders only the symbol of a module in its style, or `None` for modules without a symbol
+fn collapsed_segments(context: &Context, module: &str) -> Option<Vec<Segment>> {
+    let defaults = DEFAULT_CONFIG.as_ref()?;
+    let option = |key: &str| {
+        let path: Vec<&str> = module.split('.').chain([key]).collect();
+        context
+            .config
+            .get_config(&path)
+            .or_else(|| path.iter().try_fold(defaults, |value, key| value.get(key)))
+            .and_then(toml::Value::as_str)
+            .map(ToString::to_string)
+    };
+    let symbol = option("symbol").filter(|symbol| !symbol.trim().is_empty())?;
+    let style = option("style").unwrap_or_default();
+
+    StringFormatter::new("[$symbol]($style)")
+        .and_then(|formatter| {
+            formatter
+                .map_meta(|var, _| match var {
+                    "symbol" => Some(symbol.as_str()),
+                    _ => None,
+                })
+                .map_style(|variable| match variable {
+                    "style" => Some(Ok(style.as_str())),
+                    _ => None,
+                })
+                .parse(None, Some(context))
+        })
+        .ok()
+}
+
+/// The width of the widest line of the prompt, where fills take up their minimal width
+fn widest_line(segments: &[Segment]) -> usize {
+    segments
+        .split(|segment| matches!(segment, Segment::LineTerm))
+        .map(|line| line.iter().map(Segment::width_graphemes).sum())
+        .max()
+        .unwrap_or(0)
+}
+
 pub fn module(module_name: &str, args: Properties) {
     let context = Context::new(args, Target::Main);
     let module = get_module(module_name, context).unwrap_or_default();
@@ -182,7 +485,7 @@ pub fn timings(args: Properties) {
     let max_name_width = modules.iter().map(|i| i.name_len).max().unwrap_or(0);
     let max_duration_width = modules.iter().map(|i| i.duration_len).max().unwrap_or(0);
 
-    println!("\n Here are the timings of modules in your prompt (>=1ms or output):");
+    println!("\n {}", context.message("timings-heading"));
 
     // for now we do not expect a wrap around at the end... famous last words
     // Overall a line looks like this: " {module name}  -  {duration}  -  "{module value}"".
@@ -210,7 +513,18 @@ pub fn explain(args: Properties) {
 
     static DONT_PRINT: &[&str] = &["line_break"];
 
-    let modules = compute_modules(&context)
+    let computed = compute_modules(&context);
+    let problems: Vec<(String, String)> = computed
+        .iter()
+        .flat_map(|module| {
+            module
+                .problems
+                .iter()
+                .map(|problem| (module.get_name().clone(), problem.clone()))
+        })
+        .collect();
+
+    let modules = computed
         .into_iter()
         .filter(|module| !DONT_PRINT.contains(&module.get_name().as_str()))
         // this contains empty modules which should not print
@@ -238,7 +552,7 @@ pub fn explain(args: Properties) {
         // Add padding length to module length to avoid text overflow. This line also assures desc_width >= 0.
         .map(|width| width - std::cmp::min(width, max_module_width + PADDING_WIDTH));
 
-    println!("\n Here's a breakdown of your prompt:");
+    println!("\n {}", context.message("explain-heading"));
     for info in modules {
         if let Some(desc_width) = desc_width {
             // Custom Textwrapping!
@@ -277,7 +591,7 @@ pub fn explain(args: Properties) {
                         continue;
                     }
 
-                    current_pos = 1;
+                    current_pos = Grapheme(g).width();
                 }
                 print!("{g}");
             }
@@ -291,16 +605,29 @@ pub fn explain(args: Properties) {
             );
         };
     }
+
+    if !problems.is_empty() {
+        println!("\n {}", context.message("problems-heading"));
+        for (name, problem) in problems {
+            println!(
+                " {}  -  {}",
+                name,
+                problem.lines().collect::<Vec<_>>().join(" ")
+            );
+        }
+    }
 }
 
-fn compute_modules<'a>(context: &'a Context) -> Vec<Module<'a>> {
+pub(crate) fn compute_modules<'a>(context: &'a Context) -> Vec<Module<'a>> {
     let mut prompt_order: Vec<Module<'a>> = Vec::new();
 
     let (_formatter, modules) = load_formatter_and_modules(context);
 
     for module in &modules {
-        // Manually add all modules if `$all` is encountered
-        if module == "all" {
+        if module == REPORTING_MODULE {
+            continue;
+        } else if module == "all" {
+            // Manually add all modules if `$all` is encountered
             for module in all_modules_uniq(&modules) {
                 let modules = handle_module(&module, context, &modules);
                 prompt_order.extend(modules);
@@ -311,6 +638,10 @@ fn compute_modules<'a>(context: &'a Context) -> Vec<Module<'a>> {
         }
     }
 
+    if modules.contains(REPORTING_MODULE) {
+        prompt_order.extend(handle_module(REPORTING_MODULE, context, &modules));
+    }
+
     prompt_order
 }
 
@@ -405,6 +736,95 @@ fn all_modules_uniq(module_list: &BTreeSet<String>) -> Vec<String> {
     prompt_order
 }
 
+/// The segments after the last line break
+fn last_line(segments: &[Segment]) -> &[Segment] {
+    segments
+        .rsplit(|segment| matches!(segment, Segment::LineTerm))
+        .next()
+        .unwrap_or_default()
+}
+
+/// The formatter of `right_format` for the main prompt, if it has a line break of its own,
+/// either written out or as `$line_break`
+///
+/// Other right formats are a single line, which is left to the shell, even when modules like
+/// `custom` ones print several lines.
+fn multiline_right_formatter<'a>(context: &'a Context) -> Option<StringFormatter<'a>> {
+    let format = &context.root_config.right_format;
+    let formatter = StringFormatter::new(format)
+        .map(|formatter| prune_formatter(formatter, context))
+        .ok()?;
+    let can_break = format.contains('\n')
+        || formatter
+            .get_variables()
+            .iter()
+            .any(|variable| variable == "line_break");
+    can_break.then_some(formatter)
+}
+
+/// Right-aligns the upper lines of a multi-line `right_format` on the lines of the left prompt
+/// above its last one, whose right side is left to the shell
+///
+/// The blocks are aligned at the bottom, so the block before the last one is drawn on the line
+/// above the input line.
+fn add_right_blocks(left: Vec<Segment>, right: &[Segment]) -> Vec<Segment> {
+    let mut right_blocks: Vec<&[Segment]> = right
+        .split(|segment| matches!(segment, Segment::LineTerm))
+        .collect();
+    // The last line is the right prompt of the shell
+    right_blocks.pop();
+    if right_blocks.is_empty() {
+        return left;
+    }
+
+    let mut lines: Vec<Vec<Segment>> = left
+        .split(|segment| matches!(segment, Segment::LineTerm))
+        .map(<[Segment]>::to_vec)
+        .collect();
+    let last = lines.len() - 1;
+    for (line, block) in lines[..last]
+        .iter_mut()
+        .rev()
+        .zip(right_blocks.into_iter().rev())
+    {
+        if block.iter().all(|segment| segment.value().is_empty()) {
+            continue;
+        }
+        line.push(Segment::fill(None, " "));
+        line.extend_from_slice(block);
+    }
+    lines.join(&Segment::LineTerm)
+}
+
+/// Drops the conditional groups of a root format string whose modules are all known to be
+/// empty, so modules that could never be displayed are not computed
+fn prune_formatter<'a>(formatter: StringFormatter<'a>, context: &Context) -> StringFormatter<'a> {
+    formatter.prune_conditionals(|variable| is_known_empty(variable, context))
+}
+
+/// Returns true if a root format variable resolves to nothing without computing any module:
+/// disabled modules, global variables without a value, and unknown names
+fn is_known_empty(variable: &str, context: &Context) -> bool {
+    if ALL_MODULES.contains(&variable) {
+        return context.is_module_disabled_in_config(variable);
+    }
+    if let Some((parent, child)) = variable.split_once('.') {
+        return matches!(parent, "custom" | "env_var")
+            && context
+                .config
+                .get_config(&[parent, child, "disabled"])
+                .and_then(toml::Value::as_bool)
+                == Some(true);
+    }
+    match variable {
+        "all" | "custom" | "env_var" => false,
+        _ if GLOBAL_VARIABLES.contains(&variable) => context
+            .global_variable(variable)
+            .map_or(true, |value| value.is_empty()),
+        _ => true,
+    }
+}
+
 /// Load the correct formatter for the context (ie left prompt or right prompt)
 /// and the list of all modules used in a format string
 fn load_formatter_and_modules<'a>(context: &'a Context) -> (StringFormatter<'a>, BTreeSet<String>) {
@@ -412,7 +832,7 @@ fn load_formatter_and_modules<'a>(context: &'a Context) -> (StringFormatter<'a>,
 
     if context.target == Target::Continuation {
         let cf = &config.continuation_prompt;
-        let formatter = StringFormatter::new(cf);
+        let formatter = StringFormatter::new(cf).map(|f| prune_formatter(f, context));
         return match formatter {
             Ok(f) => {
                 let modules = f.get_variables().into_iter().collect();
@@ -425,6 +845,21 @@ fn load_formatter_and_modules<'a>(context: &'a Context) -> (StringFormatter<'a>,
         };
     }
 
+    if context.target == Target::StatusBar {
+        let formatter =
+            StringFormatter::new(&config.status_bar_format).map(|f| prune_formatter(f, context));
+        return match formatter {
+            Ok(f) => {
+                let modules = f.get_variables().into_iter().collect();
+                (f, modules)
+            }
+            Err(e) => {
+                log::error!("Error parsing status_bar_format: {e}");
+                (StringFormatter::raw(""), BTreeSet::new())
+            }
+        };
+    }
+
     let (left_format_str, right_format_str): (&str, &str) = match context.target {
         Target::Main | Target::Right => (&config.format, &config.right_format),
         Target::Profile(ref name) => {
@@ -435,11 +870,13 @@ fn load_formatter_and_modules<'a>(context: &'a Context) -> (StringFormatter<'a>,
                 return (StringFormatter::raw(">"), BTreeSet::new());
             }
         }
-        Target::Continuation => unreachable!("Continuation prompt should have been handled above"),
+        Target::Continuation | Target::StatusBar => {
+            unreachable!("Continuation prompt and status bar should have been handled above")
+        }
     };
 
-    let lf = StringFormatter::new(left_format_str);
-    let rf = StringFormatter::new(right_format_str);
+    let lf = StringFormatter::new(left_format_str).map(|f| prune_formatter(f, context));
+    let rf = StringFormatter::new(right_format_str).map(|f| prune_formatter(f, context));
 
     if let Err(ref e) = lf {
         let name = if let Target::Profile(ref profile_name) = context.target {
@@ -463,7 +900,9 @@ fn load_formatter_and_modules<'a>(context: &'a Context) -> (StringFormatter<'a>,
     let main_formatter = match context.target {
         Target::Main | Target::Profile(_) => lf,
         Target::Right => rf,
-        Target::Continuation => unreachable!("Continuation prompt should have been handled above"),
+        Target::Continuation | Target::StatusBar => {
+            unreachable!("Continuation prompt and status bar should have been handled above")
+        }
     };
 
     match main_formatter {
@@ -590,6 +1029,135 @@ mod test {
         dir.close()
     }
 
+    #[test]
+    fn right_format_per_line() {
+        let config = toml::toml! {
+            add_newline = false
+            format = "top\n> "
+            right_format = "info$line_break<"
+        };
+        let mut context = default_context().set_config(config.clone());
+        context.width = 10;
+        assert_eq!(get_prompt(context), "top   info\n> ");
+
+        let mut context = default_context().set_config(config);
+        context.target = Target::Right;
+        assert_eq!(get_prompt(context), "<");
+    }
+
+    #[test]
+    fn right_format_breaks_lines_in_groups() {
+        let config = toml::toml! {
+            add_newline = false
+            format = "top\n> "
+            right_format = "[info\n]()<"
+        };
+        let mut context = default_context().set_config(config.clone());
+        context.width = 10;
+        assert_eq!(get_prompt(context), "top   info\n> ");
+
+        let mut context = default_context().set_config(config);
+        context.target = Target::Right;
+        assert_eq!(get_prompt(context), "<");
+    }
+
+    #[test]
+    fn right_format_keeps_multi_line_module_output() {
+        let config = toml::toml! {
+            add_newline = false
+            format = "top\n> "
+            right_format = "${env_var.NOTES}"
+            [env_var.NOTES]
+            format = "$env_value"
+        };
+        let mut context = default_context().set_config(config.clone());
+        context.env.insert("NOTES", "up\ndown".to_string());
+        context.width = 10;
+        assert_eq!(get_prompt(context), "top\n> ");
+
+        let mut context = default_context().set_config(config);
+        context.env.insert("NOTES", "up\ndown".to_string());
+        context.target = Target::Right;
+        assert_eq!(get_prompt(context), "up\ndown");
+    }
+
+    #[test]
+    fn low_priority_modules_are_shrunk_to_fit() {
+        let render = |width, max_prompt_width: i64| {
+            let mut config = toml::toml! {
+                add_newline = false
+                format = "${env_var.first}${env_var.second}> "
+                [env_var.first]
+                format = "[$symbol$env_value]($style) "
+                symbol = "1 "
+                style = ""
+                priority = 1
+                [env_var.second]
+                format = "[$symbol$env_value]($style) "
+                symbol = "2 "
+                style = ""
+                priority = 2
+            };
+            config.insert("max_prompt_width".to_string(), max_prompt_width.into());
+            let mut context = default_context().set_config(config);
+            context.env.insert("first", "aaaa".to_string());
+            context.env.insert("second", "bbbb".to_string());
+            context.width = width;
+            get_prompt(context)
+        };
+
+        assert_eq!(render(80, 0), "1 aaaa 2 bbbb > ");
+        assert_eq!(render(13, 0), "1 2 bbbb > ");
+        assert_eq!(render(80, 12), "1 2 bbbb > ");
+        assert_eq!(render(8, 0), "1 2 > ");
+        assert_eq!(render(5, 0), "2 > ");
+    }
+
+    #[test]
+    fn right_blocks_align_at_the_bottom() {
+        let segments = |text: &str| Segment::from_text(None, text);
+        let left = segments("one\ntwo\nthree");
+        let right = segments("x\n\n<");
+
+        let actual: String = add_right_blocks(left, &right)
+            .iter()
+            .map(Segment::value)
+            .collect();
+        assert_eq!(actual, "one x\ntwo\nthree");
+    }
+
+    #[test]
+    fn unreachable_groups_are_pruned() {
+        let context = default_context().set_config(toml::toml! {
+            add_newline = false
+            format = "(\\[$hostname$custom.a$unknown\\])($custom.b)$character"
+            [hostname]
+            disabled = true
+            [custom.a]
+            command = "echo a"
+            when = true
+            disabled = true
+            [custom.b]
+            command = "echo b"
+            when = true
+            [character]
+            format = ">"
+        });
+
+        assert!(is_known_empty("hostname", &context));
+        assert!(is_known_empty("custom.a", &context));
+        assert!(is_known_empty("unknown", &context));
+        assert!(!is_known_empty("custom.b", &context));
+        assert!(!is_known_empty("character", &context));
+        assert!(!is_known_empty("all", &context));
+
+        let (_, modules) = load_formatter_and_modules(&context);
+        assert_eq!(
+            modules.into_iter().collect::<Vec<_>>(),
+            vec!["character", "custom.b"]
+        );
+    }
+
     #[test]
     fn custom_prompt() {
         let mut context = default_context().set_config(toml::toml! {
@@ -634,6 +1202,22 @@ mod test {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn status_bar() {
+        let mut context = default_context().set_config(toml::toml! {
+                status_bar_format="k8s"
+        });
+        context.target = Target::StatusBar;
+
+        let expected = String::from("\x1b7\x1b[2r\x1b[1;1H\x1b[2K\x1b[?7lk8s\x1b[?7h\x1b8");
+        let actual = get_prompt(context);
+        assert_eq!(expected, actual);
+
+        let mut context = default_context();
+        context.target = Target::StatusBar;
+        assert_eq!(get_prompt(context), "");
+    }
+
     #[test]
     fn preset_list_returns_one_or_more_items() {
         assert!(preset_list().trim().split('\n').count() > 0);
@@ -660,6 +1244,24 @@ mod test {
         dir.close()
     }
 
+    #[test]
+    #[cfg(feature = "embed")]
+    fn render_returns_unescaped_segments() {
+        let mut context = default_context();
+        context.shell = Shell::Bash;
+
+        let segments = render(
+            toml::toml! {
+                format = "[a](red)\n$character"
+                [character]
+                format = ">"
+            },
+            context,
+        );
+        let actual: String = segments.iter().map(Segment::value).collect();
+        assert_eq!(actual, "a\n>");
+    }
+
     #[test]
     #[cfg(feature = "config-schema")]
     fn print_schema_does_not_panic() {
@@ -0,0 +1,207 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::context::{Context, Target};
+use crate::git_watch;
+use crate::module_cache;
+use crate::session;
+use crate::utils::encode_to_hex;
+
+/// Returns the prompt rendered last in this shell session if nothing it depends on has changed
+/// since, otherwise renders it with `render` and keeps it for the next prompt
+///
+/// This makes pressing enter on an empty line almost free when `prompt_cache` is enabled.
+pub fn get_or_render<F>(context: &Context, render: F) -> String
+where
+    F: FnOnce() -> String,
+{
+    let Some((file, key)) = cache_entry(context) else {
+        return render();
+    };
+    if let Some(prompt) = read(&file, &key) {
+        log::trace!("Using the cached prompt from {}", file.display());
+        return prompt;
+    }
+
+    let prompt = render();
+    if let Err(error) = write(&file, &key, &prompt) {
+        log::debug!("Unable to cache the prompt: {error}");
+    }
+    prompt
+}
+
+/// The file caching the prompt of the session and the key of its current inputs
+fn cache_entry(context: &Context) -> Option<(PathBuf, String)> {
+    if !context.root_config.prompt_cache {
+        return None;
+    }
+    // The clock changes even when nothing else does
+    let time_enabled = context
+        .config
+        .get_config(&["time", "disabled"])
+        .and_then(toml::Value::as_bool)
+        == Some(false);
+    if time_enabled {
+        return None;
+    }
+
+    // Changes to files of the working tree change neither the directory nor the git files, so
+    // the status of the repository is only reused while `starship watch-git` keeps a snapshot
+    let git_metrics_enabled = context
+        .config
+        .get_config(&["git_metrics", "disabled"])
+        .and_then(toml::Value::as_bool)
+        == Some(false);
+    let shows_working_tree =
+        !context.is_module_disabled_in_config("git_status") || git_metrics_enabled;
+    let snapshot = match context.get_repo() {
+        Ok(repo) if shows_working_tree && repo.workdir.is_some() => {
+            Some(git_watch::current_snapshot(&repo.path)?)
+        }
+        _ => None,
+    };
+
+    let target = match &context.target {
+        Target::Main => "main".to_string(),
+        Target::Right => "right".to_string(),
+        Target::Continuation => "continuation".to_string(),
+        Target::StatusBar => "status-bar".to_string(),
+        Target::Profile(name) => format!("profile-{}", encode_to_hex(name.as_bytes())),
+    };
+    let file = session::session_dir(context)?.join(format!("prompt-{target}"));
+    Some((file, key(context, snapshot.as_deref())))
+}
+
+/// Hashes everything the prompt is rendered from: the arguments from the shell, the
+/// directory, the configuration, the environment and the state of the git repository, with
+/// the `snapshot` of its status
+///
+/// The arguments include the duration of the last command, so the prompt is rendered again
+/// after any command ran, while an empty line keeps it.
+fn key(context: &Context, snapshot: Option<&Path>) -> String {
+    let fingerprint = context.fingerprint();
+    // `starship refresh` recreates the directories of the refreshed modules
+    let fingerprint = match module_cache::cache_dir(context) {
+        Some(dir) => fingerprint.modified(&dir),
+        None => fingerprint,
+    };
+    let fingerprint = fingerprint
+        .text(format!("{:?}", context.properties))
+        .cwd()
+        .config()
+        .all_env()
+        // Creating or removing files changes the directory
+        .modified(&context.current_dir)
+        .repo_head();
+    let fingerprint = match snapshot {
+        Some(snapshot) => fingerprint.modified(snapshot),
+        None => fingerprint,
+    };
+    fingerprint.finish()
+}
+
+fn read(file: &Path, key: &str) -> Option<String> {
+    let contents = fs::read_to_string(file).ok()?;
+    let (cached_key, prompt) = contents.split_once('\n')?;
+    (cached_key == key).then(|| prompt.to_string())
+}
+
+fn write(file: &Path, key: &str, prompt: &str) -> io::Result<()> {
+    if let Some(dir) = file.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(file, format!("{key}\n{prompt}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::default_context;
+    use crate::utils::create_command;
+
+    fn context<'a>(cache: &Path, dir: &Path) -> Context<'a> {
+        let mut context = default_context().set_config(toml::toml! {
+            prompt_cache = true
+        });
+        context.current_dir = dir.to_path_buf();
+        context.logical_dir = dir.to_path_buf();
+        context
+            .env
+            .insert("STARSHIP_CACHE", cache.to_string_lossy().to_string());
+        context
+            .env
+            .insert("STARSHIP_SESSION_KEY", "1234567890123456".to_string());
+        context
+    }
+
+    #[test]
+    fn reuses_unchanged_prompt() -> io::Result<()> {
+        let cache = tempfile::tempdir()?;
+        let dir = tempfile::tempdir()?;
+
+        let first = context(cache.path(), dir.path());
+        assert_eq!(get_or_render(&first, || "first".to_string()), "first");
+        let second = context(cache.path(), dir.path());
+        assert_eq!(get_or_render(&second, || "second".to_string()), "first");
+
+        let mut after_command = context(cache.path(), dir.path());
+        after_command.properties.cmd_duration = Some("1500".to_string());
+        assert_eq!(
+            get_or_render(&after_command, || "third".to_string()),
+            "third"
+        );
+
+        cache.close()?;
+        dir.close()
+    }
+
+    #[test]
+    fn renders_git_status_without_watcher() -> io::Result<()> {
+        let cache = tempfile::tempdir()?;
+        let dir = tempfile::tempdir()?;
+        create_command("git")?
+            .args(["init", "--quiet"])
+            .current_dir(dir.path())
+            .output()?;
+
+        let first = context(cache.path(), dir.path());
+        assert_eq!(get_or_render(&first, || "first".to_string()), "first");
+        // A changed file in a directory below wouldn't change anything the key is made from
+        let second = context(cache.path(), dir.path());
+        assert_eq!(get_or_render(&second, || "second".to_string()), "second");
+
+        let without_status = context(cache.path(), dir.path()).set_config(toml::toml! {
+            prompt_cache = true
+            [git_status]
+            disabled = true
+        });
+        assert_eq!(
+            get_or_render(&without_status, || "third".to_string()),
+            "third"
+        );
+        assert_eq!(
+            get_or_render(&without_status, || "fourth".to_string()),
+            "third"
+        );
+
+        cache.close()?;
+        dir.close()
+    }
+
+    #[test]
+    fn renders_without_session() -> io::Result<()> {
+        let cache = tempfile::tempdir()?;
+        let dir = tempfile::tempdir()?;
+
+        let mut first = context(cache.path(), dir.path());
+        first.env.env.remove("STARSHIP_SESSION_KEY");
+        assert_eq!(get_or_render(&first, || "first".to_string()), "first");
+        let mut second = context(cache.path(), dir.path());
+        second.env.env.remove("STARSHIP_SESSION_KEY");
+        assert_eq!(get_or_render(&second, || "second".to_string()), "second");
+
+        cache.close()?;
+        dir.close()
+    }
+}
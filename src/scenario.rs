@@ -0,0 +1,398 @@
+//! Declarative scenarios for rendering a prompt in a reproducible environment.
+//!
+//! A scenario is a TOML file describing the environment variables, a directory tree and the
+//! output of the commands modules run, e.g.
+//!
+//! ```toml
+//! config = "starship.toml"
+//! path = "project"
+//! width = 80
+//!
+//! [env]
+//! USER = "astronaut"
+//!
+//! [files]
+//! "project/Cargo.toml" = '''
+//! [package]
+//! name = "demo"
+//! version = "0.1.0"
+//! '''
+//! "project/target/" = ""
+//!
+//! [commands]
+//! "rustc --version" = "rustc 1.80.0 (051478957 2024-07-21)"
+//! ```
+//!
+//! The prompt is rendered by a separate starship process, so nothing from the environment
+//! of the caller leaks into the result.
+
+use indexmap::IndexMap;
+use serde::Deserialize;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Error, ErrorKind};
+use std::path::{Component, Path, PathBuf};
+use tempfile::TempDir;
+
+use crate::context::Context;
+use crate::utils::{create_command, CommandOutput};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Scenario {
+    /// The starship config to render, relative to the scenario file.
+    /// Uses the config of the caller if unset.
+    pub config: Option<PathBuf>,
+    /// The file the rendered prompt is compared to, relative to the scenario file.
+    /// Defaults to the scenario file with a `.snap` extension.
+    pub snapshot: Option<PathBuf>,
+    /// The working directory inside the directory tree
+    pub path: PathBuf,
+    /// The width of the terminal
+    pub width: Option<usize>,
+    /// The exit code of the last command
+    pub status_code: Option<String>,
+    /// Environment variables, all others are unset
+    pub env: IndexMap<String, String>,
+    /// Files of the directory tree and their contents. Paths ending in `/` are directories.
+    pub files: IndexMap<String, String>,
+    /// Command lines, e.g. `rustc --version`, and what they print.
+    /// Commands without an entry are not found.
+    pub commands: IndexMap<String, String>,
+}
+
+/// A scenario whose directory tree and mocked commands have been set up
+pub struct PreparedScenario {
+    scenario: Scenario,
+    base_dir: PathBuf,
+    root: TempDir,
+}
+
+impl Scenario {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        toml::from_str(&contents).map_err(|error| Error::new(ErrorKind::InvalidData, error))
+    }
+
+    /// Creates the directory tree and the mocked commands in a temporary directory
+    pub fn prepare(self, scenario_path: &Path) -> io::Result<PreparedScenario> {
+        let root = tempfile::tempdir()?;
+        let tree = root.path().join("root");
+        fs::create_dir_all(tree.join(relative_path(&self.path.to_string_lossy())?))?;
+
+        for (path, contents) in &self.files {
+            let target = tree.join(relative_path(path)?);
+            if path.ends_with('/') {
+                fs::create_dir_all(target)?;
+            } else {
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(target, contents)?;
+            }
+        }
+
+        let bin_dir = root.path().join("bin");
+        fs::create_dir_all(&bin_dir)?;
+        write_command_stubs(&bin_dir, &self.commands)?;
+
+        // Starship runs in the tree, so the paths of the scenario must not depend on the
+        // current directory
+        let base_dir =
+            std::env::current_dir()?.join(scenario_path.parent().unwrap_or(Path::new("")));
+        let base_dir = dunce::canonicalize(&base_dir).unwrap_or(base_dir);
+        Ok(PreparedScenario {
+            scenario: self,
+            base_dir,
+            root,
+        })
+    }
+}
+
+impl PreparedScenario {
+    /// The path of the stored snapshot
+    pub fn snapshot_path(&self, scenario_path: &Path) -> PathBuf {
+        match &self.scenario.snapshot {
+            Some(snapshot) => self.base_dir.join(snapshot),
+            None => scenario_path.with_extension("snap"),
+        }
+    }
+
+    /// The path of the starship config the scenario is rendered with
+    fn config_path(&self) -> Option<OsString> {
+        match &self.scenario.config {
+            Some(config) => Some(self.base_dir.join(config).into_os_string()),
+            None => Context::default().get_config_path_os(),
+        }
+    }
+
+    /// Runs starship with the given arguments inside the scenario
+    pub fn run(&self, args: &[&str]) -> io::Result<CommandOutput> {
+        let tree = self.root.path().join("root");
+        let tree = dunce::canonicalize(&tree).unwrap_or(tree);
+        let current_dir = tree.join(&self.scenario.path);
+        let config = self.config_path();
+
+        let mut path = OsString::from(self.root.path().join("bin"));
+        if let Some(scenario_path) = self.scenario.env.get("PATH") {
+            path.push(if cfg!(windows) { ";" } else { ":" });
+            path.push(scenario_path);
+        }
+
+        let mut cmd = create_command(std::env::current_exe()?)?;
+        // The tree is the home directory, unless the scenario sets one
+        cmd.env_clear()
+            .env("HOME", &tree)
+            .envs(&self.scenario.env)
+            .env("PATH", path)
+            .env("STARSHIP_CACHE", self.root.path().join("cache"))
+            .env("STARSHIP_LOG", "error")
+            .current_dir(&current_dir)
+            .args(args)
+            .arg("--path")
+            .arg(&current_dir)
+            .arg("--terminal-width")
+            .arg(self.scenario.width.unwrap_or(80).to_string());
+        if let Some(config) = config {
+            cmd.env("STARSHIP_CONFIG", config);
+        }
+        if let Some(status_code) = &self.scenario.status_code {
+            cmd.arg("--status").arg(status_code);
+        }
+
+        let output = cmd.output()?;
+        let stdout = String::from_utf8_lossy(&output.stdout)
+            // The temporary directory differs between runs
+            .replace(&tree.to_string_lossy().to_string(), "<root>");
+        Ok(CommandOutput {
+            stdout,
+            stderr: String::from_utf8_lossy(&output.stderr).into(),
+        })
+    }
+}
+
+/// Renders the prompt of the scenario at `scenario_path` and compares it with its snapshot.
+///
+/// With `update`, the snapshot is written instead. Returns whether the prompt matched.
+pub fn test_render(scenario_path: &Path, update: bool) -> io::Result<bool> {
+    let scenario = Scenario::load(scenario_path)?.prepare(scenario_path)?;
+    let snapshot_path = scenario.snapshot_path(scenario_path);
+    let actual = scenario.run(&["prompt"])?.stdout;
+
+    if update {
+        fs::write(&snapshot_path, &actual)?;
+        println!("Updated {}", snapshot_path.display());
+        return Ok(true);
+    }
+
+    let expected = match fs::read_to_string(&snapshot_path) {
+        Ok(expected) => expected,
+        Err(error) if error.kind() == ErrorKind::NotFound => {
+            println!(
+                "No snapshot at {}, run with `--update` to create it.",
+                snapshot_path.display()
+            );
+            return Ok(false);
+        }
+        Err(error) => return Err(error),
+    };
+
+    if expected == actual {
+        println!("{}: ok", scenario_path.display());
+        return Ok(true);
+    }
+
+    println!(
+        "{}: the prompt does not match the snapshot",
+        scenario_path.display()
+    );
+    println!("\n Expected:\n {expected:?}\n\n Actual:\n {actual:?}\n");
+    println!(" Rendered:\n{actual}");
+    Ok(false)
+}
+
+/// Paths in the scenario must stay inside its directory tree
+fn relative_path(path: &str) -> io::Result<&Path> {
+    let path = Path::new(path);
+    if path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        Ok(path)
+    } else {
+        Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "{} is not a relative path inside the scenario",
+                path.display()
+            ),
+        ))
+    }
+}
+
+/// Writes an executable for every mocked program, printing the output for its arguments
+fn write_command_stubs(bin_dir: &Path, commands: &IndexMap<String, String>) -> io::Result<()> {
+    let mut programs: IndexMap<&str, Vec<(&str, &str)>> = IndexMap::new();
+    for (command, output) in commands {
+        let (program, args) = command.split_once(' ').unwrap_or((command, ""));
+        programs
+            .entry(program)
+            .or_default()
+            .push((args.trim(), output));
+    }
+
+    for (program, cases) in programs {
+        write_stub(bin_dir, program, &cases)?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn write_stub(bin_dir: &Path, program: &str, cases: &[(&str, &str)]) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let quote = |text: &str| format!("'{}'", text.replace('\'', r"'\''"));
+    let mut script = String::from("#!/bin/sh\ncase \"$*\" in\n");
+    for (args, output) in cases {
+        script.push_str(&format!(
+            "  {}) printf '%s\\n' {} ;;\n",
+            quote(args),
+            quote(output)
+        ));
+    }
+    script.push_str("  *) exit 127 ;;\nesac\n");
+
+    let path = bin_dir.join(relative_path(program)?);
+    fs::write(&path, script)?;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+}
+
+#[cfg(windows)]
+fn write_stub(bin_dir: &Path, program: &str, cases: &[(&str, &str)]) -> io::Result<()> {
+    // Batch files cannot print arbitrary text safely, so the outputs are stored next to them
+    let mut script = String::from("@echo off\r\n");
+    for (idx, (args, output)) in cases.iter().enumerate() {
+        let output_file = bin_dir.join(format!("{program}.{idx}.out"));
+        fs::write(&output_file, format!("{output}\n"))?;
+        script.push_str(&format!(
+            "if \"%*\"==\"{args}\" (type \"{}\" & exit /b 0)\r\n",
+            output_file.display()
+        ));
+    }
+    script.push_str("exit /b 127\r\n");
+
+    let path = bin_dir.join(relative_path(&format!("{program}.cmd"))?);
+    fs::write(path, script)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_scenario() {
+        let scenario: Scenario = toml::from_str(
+            r#"
+            path = "project"
+            status_code = "1"
+
+            [env]
+            USER = "astronaut"
+
+            [files]
+            "project/Cargo.toml" = "[package]"
+            "project/target/" = ""
+
+            [commands]
+            "rustc --version" = "rustc 1.80.0"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(scenario.path, PathBuf::from("project"));
+        assert_eq!(scenario.status_code.as_deref(), Some("1"));
+        assert_eq!(scenario.env["USER"], "astronaut");
+        assert_eq!(scenario.files.len(), 2);
+        assert_eq!(scenario.commands["rustc --version"], "rustc 1.80.0");
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        assert!(toml::from_str::<Scenario>("cwd = 'project'").is_err());
+    }
+
+    #[test]
+    fn paths_stay_inside_the_tree() {
+        assert!(relative_path("project/src/main.rs").is_ok());
+        assert!(relative_path("../outside").is_err());
+        assert!(relative_path("/etc/passwd").is_err());
+    }
+
+    #[test]
+    fn prepare_tree() -> io::Result<()> {
+        let scenario = Scenario {
+            path: PathBuf::from("project"),
+            files: IndexMap::from([
+                ("project/Cargo.toml".to_string(), "[package]".to_string()),
+                ("project/target/".to_string(), String::new()),
+            ]),
+            commands: IndexMap::from([("rustc --version".to_string(), "rustc 1.80.0".to_string())]),
+            ..Default::default()
+        };
+
+        let prepared = scenario.prepare(Path::new("prompt.toml"))?;
+        let tree = prepared.root.path().join("root/project");
+
+        assert_eq!(fs::read_to_string(tree.join("Cargo.toml"))?, "[package]");
+        assert!(tree.join("target").is_dir());
+        let stub = if cfg!(windows) { "rustc.cmd" } else { "rustc" };
+        assert!(prepared.root.path().join("bin").join(stub).is_file());
+        assert_eq!(
+            prepared.snapshot_path(Path::new("prompt.toml")),
+            PathBuf::from("prompt.snap")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn relative_scenario_path() -> io::Result<()> {
+        let dir = tempfile::tempdir_in(std::env::current_dir()?)?;
+        fs::write(dir.path().join("starship.toml"), "format = '$directory'")?;
+        let scenario_path = Path::new(dir.path().file_name().unwrap()).join("prompt.toml");
+        let scenario = Scenario {
+            config: Some(PathBuf::from("starship.toml")),
+            ..Default::default()
+        };
+
+        let prepared = scenario.prepare(&scenario_path)?;
+        let config = PathBuf::from(prepared.config_path().unwrap());
+        assert!(config.is_absolute());
+        assert_eq!(
+            config,
+            dunce::canonicalize(dir.path())?.join("starship.toml")
+        );
+        dir.close()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command_stub_output() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let commands = IndexMap::from([
+            ("node --version".to_string(), "v20.0.0".to_string()),
+            ("node -e 'x'".to_string(), "it's".to_string()),
+        ]);
+        write_command_stubs(dir.path(), &commands)?;
+
+        let run = |args: &[&str]| {
+            create_command(dir.path().join("node"))
+                .and_then(|mut cmd| cmd.args(args).output())
+                .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+        };
+
+        assert_eq!(run(&["--version"])?, "v20.0.0\n");
+        assert_eq!(run(&["-e", "'x'"])?, "it's\n");
+        assert_eq!(run(&["--help"])?, "");
+        dir.close()
+    }
+}
@@ -0,0 +1,341 @@
+//! Renders the prompts of scenarios into images, e.g. for the README of a dotfile repository.
+//!
+//! The escape sequences of the rendered prompt are drawn into an SVG terminal window, which is
+//! converted to PNG with `rsvg-convert` when asked for.
+
+use nu_ansi_term::{Color, Style as AnsiStyle};
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Error, ErrorKind};
+use std::path::Path;
+
+use crate::color_depth::to_rgb;
+use crate::print::UnicodeWidthGraphemes;
+use crate::scenario::Scenario;
+use crate::utils::create_command;
+
+/// The image formats prompts are rendered into
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ImageFormat {
+    Svg,
+    Png,
+}
+
+const FONT_SIZE: f64 = 14.0;
+/// The width of a column in a monospace font of `FONT_SIZE`
+const CELL_WIDTH: f64 = FONT_SIZE * 0.6;
+const LINE_HEIGHT: f64 = FONT_SIZE * 1.4;
+const PADDING: f64 = 12.0;
+const FOREGROUND: (u8, u8, u8) = (229, 229, 229);
+const BACKGROUND: (u8, u8, u8) = (30, 30, 30);
+
+/// A piece of a line drawn in one style
+struct Run {
+    column: usize,
+    style: AnsiStyle,
+    text: String,
+}
+
+/// Renders the prompt of every scenario file in `scenarios` into an image in `out`, named
+/// after the scenario
+pub fn screenshot(scenarios: &Path, out: &Path, format: ImageFormat) -> io::Result<()> {
+    let mut paths: Vec<_> = fs::read_dir(scenarios)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<_>>()?;
+    paths.retain(|path| path.extension().map_or(false, |ext| ext == "toml"));
+    paths.sort();
+    if paths.is_empty() {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            format!("no scenario files in {}", scenarios.display()),
+        ));
+    }
+
+    fs::create_dir_all(out)?;
+    for scenario_path in paths {
+        let scenario = Scenario::load(&scenario_path)?.prepare(&scenario_path)?;
+        let prompt = scenario.run(&["prompt"])?.stdout;
+        let svg = to_svg(&parse_ansi(&prompt));
+
+        let stem = scenario_path.file_stem().unwrap_or_default();
+        let svg_path = out.join(stem).with_extension("svg");
+        fs::write(&svg_path, svg)?;
+        let image_path = match format {
+            ImageFormat::Svg => svg_path,
+            ImageFormat::Png => {
+                let png_path = svg_path.with_extension("png");
+                convert_to_png(&svg_path, &png_path)?;
+                fs::remove_file(&svg_path)?;
+                png_path
+            }
+        };
+        println!("Rendered {}", image_path.display());
+    }
+    Ok(())
+}
+
+fn convert_to_png(svg_path: &Path, png_path: &Path) -> io::Result<()> {
+    let output = create_command("rsvg-convert")
+        .map_err(|error| {
+            Error::new(
+                error.kind(),
+                format!("PNG images need rsvg-convert from librsvg: {error}"),
+            )
+        })?
+        .arg("--zoom=2")
+        .arg("--output")
+        .arg(png_path)
+        .arg(svg_path)
+        .output()?;
+    if !output.status.success() {
+        return Err(Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Splits the rendered prompt into lines of styled runs, following its SGR sequences and
+/// skipping all other escape sequences, like those of hyperlinks
+fn parse_ansi(text: &str) -> Vec<Vec<Run>> {
+    let mut lines: Vec<Vec<Run>> = vec![Vec::new()];
+    let mut style = AnsiStyle::new();
+    let mut column = 0;
+    // The blank line of `add_newline` would only add a margin to the image
+    let mut chars = text.trim_start_matches('\n').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                Some('[') => {
+                    let mut params = String::new();
+                    for c in chars.by_ref() {
+                        if ('\x40'..='\x7e').contains(&c) {
+                            if c == 'm' {
+                                apply_sgr(&mut style, &params);
+                            }
+                            break;
+                        }
+                        params.push(c);
+                    }
+                }
+                // Operating system commands end with BEL or ESC \
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            '\n' => {
+                lines.push(Vec::new());
+                column = 0;
+            }
+            '\r' => {}
+            c => {
+                let line = lines.last_mut().unwrap();
+                match line.last_mut() {
+                    Some(run) if run.style == style => run.text.push(c),
+                    _ => line.push(Run {
+                        column,
+                        style,
+                        text: c.to_string(),
+                    }),
+                }
+                // Wide graphemes are only complete once all their characters are read
+                let run = line.last().unwrap();
+                column = run.column + run.text.width_graphemes();
+            }
+        }
+    }
+    while lines.len() > 1 && lines.last().map_or(false, Vec::is_empty) {
+        lines.pop();
+    }
+    lines
+}
+
+fn apply_sgr(style: &mut AnsiStyle, params: &str) {
+    let mut codes = params
+        .split(';')
+        .map(|code| code.parse::<u8>().unwrap_or(0));
+    while let Some(code) = codes.next() {
+        match code {
+            0 => *style = AnsiStyle::new(),
+            1 => style.is_bold = true,
+            2 => style.is_dimmed = true,
+            3 => style.is_italic = true,
+            4 | 21 => style.is_underline = true,
+            5 => style.is_blink = true,
+            7 => style.is_reverse = true,
+            8 => style.is_hidden = true,
+            9 => style.is_strikethrough = true,
+            22 => {
+                style.is_bold = false;
+                style.is_dimmed = false;
+            }
+            23 => style.is_italic = false,
+            24 => style.is_underline = false,
+            25 => style.is_blink = false,
+            27 => style.is_reverse = false,
+            28 => style.is_hidden = false,
+            29 => style.is_strikethrough = false,
+            30..=37 => style.foreground = Some(Color::Fixed(code - 30)),
+            90..=97 => style.foreground = Some(Color::Fixed(code - 90 + 8)),
+            38 => style.foreground = extended_color(&mut codes),
+            39 => style.foreground = None,
+            40..=47 => style.background = Some(Color::Fixed(code - 40)),
+            100..=107 => style.background = Some(Color::Fixed(code - 100 + 8)),
+            48 => style.background = extended_color(&mut codes),
+            49 => style.background = None,
+            _ => {}
+        }
+    }
+}
+
+/// Reads the rest of a `38;5;<n>` or `38;2;<r>;<g>;<b>` color
+fn extended_color(codes: &mut impl Iterator<Item = u8>) -> Option<Color> {
+    match codes.next()? {
+        5 => Some(Color::Fixed(codes.next()?)),
+        2 => Some(Color::Rgb(codes.next()?, codes.next()?, codes.next()?)),
+        _ => None,
+    }
+}
+
+/// Draws the lines into a terminal window as wide as the longest line
+fn to_svg(lines: &[Vec<Run>]) -> String {
+    let columns = lines
+        .iter()
+        .filter_map(|line| line.last())
+        .map(|run| run.column + run.text.width_graphemes())
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let width = 2.0 * PADDING + columns as f64 * CELL_WIDTH;
+    let height = 2.0 * PADDING + lines.len() as f64 * LINE_HEIGHT;
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width:.1}" height="{height:.1}" font-family="ui-monospace, Menlo, Consolas, monospace" font-size="{FONT_SIZE}">
+<rect width="100%" height="100%" rx="6" fill="{}"/>
+"#,
+        hex(BACKGROUND)
+    );
+    for (index, line) in lines.iter().enumerate() {
+        let top = PADDING + index as f64 * LINE_HEIGHT;
+        for run in line {
+            let x = PADDING + run.column as f64 * CELL_WIDTH;
+            let mut fg = run.style.foreground.map(to_rgb);
+            let mut bg = run.style.background.map(to_rgb);
+            if run.style.is_reverse {
+                (fg, bg) = (
+                    Some(bg.unwrap_or(BACKGROUND)),
+                    Some(fg.unwrap_or(FOREGROUND)),
+                );
+            }
+            if let Some(bg) = bg {
+                let run_width = run.text.width_graphemes() as f64 * CELL_WIDTH;
+                let _ = writeln!(
+                    svg,
+                    r#"<rect x="{x:.1}" y="{top:.1}" width="{run_width:.1}" height="{LINE_HEIGHT:.1}" fill="{}"/>"#,
+                    hex(bg)
+                );
+            }
+            if run.style.is_hidden || run.text.trim().is_empty() {
+                continue;
+            }
+
+            let mut attributes = format!(r#" fill="{}""#, hex(fg.unwrap_or(FOREGROUND)));
+            if run.style.is_bold {
+                attributes.push_str(r#" font-weight="bold""#);
+            }
+            if run.style.is_italic {
+                attributes.push_str(r#" font-style="italic""#);
+            }
+            if run.style.is_dimmed {
+                attributes.push_str(r#" opacity="0.6""#);
+            }
+            let decorations: Vec<&str> = [
+                (run.style.is_underline, "underline"),
+                (run.style.is_strikethrough, "line-through"),
+            ]
+            .iter()
+            .filter(|(is_set, _)| *is_set)
+            .map(|(_, decoration)| *decoration)
+            .collect();
+            if !decorations.is_empty() {
+                let _ = write!(
+                    attributes,
+                    r#" text-decoration="{}""#,
+                    decorations.join(" ")
+                );
+            }
+
+            let baseline = top + LINE_HEIGHT * 0.75;
+            let _ = writeln!(
+                svg,
+                r#"<text x="{x:.1}" y="{baseline:.1}" xml:space="preserve"{attributes}>{}</text>"#,
+                escape(&run.text)
+            );
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_styled_runs() {
+        let prompt = format!(
+            "\n{} on {}\n{}",
+            Color::Cyan.bold().paint("~/starship"),
+            Color::Fixed(208).on(Color::Rgb(1, 2, 3)).paint("main"),
+            Color::Green.paint("❯ ")
+        );
+        let lines = parse_ansi(&prompt);
+        assert_eq!(lines.len(), 2);
+
+        let runs: Vec<(usize, &str)> = lines[0]
+            .iter()
+            .map(|run| (run.column, run.text.as_str()))
+            .collect();
+        assert_eq!(runs, [(0, "~/starship"), (10, " on "), (14, "main")]);
+        assert_eq!(
+            lines[0][0].style,
+            AnsiStyle::new().bold().fg(Color::Fixed(6))
+        );
+        assert_eq!(lines[0][2].style, Color::Fixed(208).on(Color::Rgb(1, 2, 3)));
+        assert_eq!(lines[1][0].style, Color::Fixed(2).normal());
+    }
+
+    #[test]
+    fn skips_hyperlinks() {
+        let prompt = "\x1b]8;;https://starship.rs\x1b\\starship\x1b]8;;\x1b\\ >";
+        let lines = parse_ansi(prompt);
+        assert_eq!(lines[0].len(), 1);
+        assert_eq!(lines[0][0].text, "starship >");
+    }
+
+    #[test]
+    fn draws_backgrounds_and_escapes_text() {
+        let svg = to_svg(&parse_ansi(&format!(
+            "{}",
+            Color::Black.on(Color::Blue).paint("<a&b>")
+        )));
+        assert!(svg.contains(r##"width="42.0" height="19.6" fill="#0000ee""##));
+        assert!(svg.contains(r##"fill="#000000">&lt;a&amp;b&gt;</text>"##));
+    }
+}
@@ -13,14 +13,23 @@ pub struct TextSegment {
 
     /// The string value of the current segment.
     value: String,
+
+    /// An URL the segment links to, rendered as an OSC 8 hyperlink.
+    link: Option<String>,
 }
 
 impl TextSegment {
     // Returns the AnsiString of the segment value
     fn ansi_string(&self, prev: Option<&AnsiStyle>) -> AnsiString {
-        match self.style {
-            Some(style) => style.to_ansi_style(prev).paint(&self.value),
+        let ansi_string = match self.style {
+            Some(style) => style
+                .to_ansi_style(prev)
+                .paint(style.wrap_extra_attributes(&self.value)),
             None => AnsiString::from(&self.value),
+        };
+        match &self.link {
+            Some(link) => ansi_string.hyperlink(link.as_str()),
+            None => ansi_string,
         }
     }
 }
@@ -33,32 +42,83 @@ pub struct FillSegment {
 
     /// The string value of the current segment.
     value: String,
+
+    /// Styles of equally wide sections the fill is split into, e.g. for a gradient.
+    /// If empty, the whole fill uses `style`.
+    sections: Vec<Style>,
 }
 
 impl FillSegment {
     // Returns the AnsiString of the segment value, not including its prefix and suffix
     pub fn ansi_string(&self, width: Option<usize>, prev: Option<&AnsiStyle>) -> AnsiString {
         let s = match width {
-            Some(w) => self
-                .value
-                .graphemes(true)
-                .cycle()
-                .scan(0usize, |len, g| {
-                    *len += Grapheme(g).width();
-                    if *len <= w {
-                        Some(g)
-                    } else {
-                        None
-                    }
-                })
-                .collect::<String>(),
+            Some(w) => {
+                let mut s = self.fill_graphemes(w).map(|(_, g)| g).collect::<String>();
+                s.push_str(&fill_padding(w, s.width_graphemes()));
+                s
+            }
             None => String::from(&self.value),
         };
         match self.style {
-            Some(style) => style.to_ansi_style(prev).paint(s),
+            Some(style) => style
+                .to_ansi_style(prev)
+                .paint(style.wrap_extra_attributes(&s).into_owned()),
             None => AnsiString::from(s),
         }
     }
+
+    /// Returns the AnsiStrings of the segment value, one for each of its styled sections
+    pub fn ansi_strings(&self, width: Option<usize>, prev: Option<&AnsiStyle>) -> Vec<AnsiString> {
+        let w = match width {
+            Some(w) if !self.sections.is_empty() && w > 0 => w,
+            _ => return vec![self.ansi_string(width, prev)],
+        };
+
+        let count = self.sections.len();
+        let mut texts = vec![String::new(); count];
+        let mut used = 0;
+        for (start, g) in self.fill_graphemes(w) {
+            texts[(start * count / w).min(count - 1)].push_str(g);
+            used = start + Grapheme(g).width();
+        }
+        texts[count - 1].push_str(&fill_padding(w, used));
+
+        let mut prev = prev.copied();
+        self.sections
+            .iter()
+            .zip(texts)
+            .filter(|(_, text)| !text.is_empty())
+            .map(|(style, text)| {
+                let ansi_string = style
+                    .to_ansi_style(prev.as_ref())
+                    .paint(style.wrap_extra_attributes(&text).into_owned());
+                prev = Some(*ansi_string.style_ref());
+                ansi_string
+            })
+            .collect()
+    }
+
+    /// Repeats the value until it is `width` columns wide, yielding each grapheme
+    /// together with the column it starts at
+    fn fill_graphemes(&self, width: usize) -> impl Iterator<Item = (usize, &str)> {
+        self.value
+            .graphemes(true)
+            .cycle()
+            .scan(0usize, move |len, g| {
+                let start = *len;
+                *len += Grapheme(g).width();
+                if *len <= width {
+                    Some((start, g))
+                } else {
+                    None
+                }
+            })
+    }
+}
+
+/// Spaces for the columns a fill leaves empty, when a wide grapheme does not fit into the last column
+fn fill_padding(width: usize, used: usize) -> String {
+    " ".repeat(width.saturating_sub(used))
 }
 
 #[cfg(test)]
@@ -83,11 +143,47 @@ mod fill_seg_tests {
             let f = FillSegment {
                 value: String::from(*text),
                 style: Some(style.into()),
+                sections: Vec::new(),
             };
             let actual = f.ansi_string(Some(width), None);
             assert_eq!(style.paint(*expected), actual);
         }
     }
+
+    #[test]
+    fn ansi_string_pads_wide_graphemes() {
+        let style = Color::Blue.bold();
+        let f = FillSegment {
+            value: String::from("🟦"),
+            style: Some(style.into()),
+            sections: Vec::new(),
+        };
+
+        let actual = f.ansi_string(Some(9), None);
+        assert_eq!(style.paint("🟦🟦🟦🟦 "), actual);
+    }
+
+    #[test]
+    fn ansi_strings_sections() {
+        let styles = [
+            Color::Red.normal(),
+            Color::Green.normal(),
+            Color::Blue.normal(),
+        ];
+        let f = FillSegment {
+            value: String::from("-="),
+            style: Some(styles[0].into()),
+            sections: styles.iter().map(|&style| style.into()).collect(),
+        };
+
+        let actual = f.ansi_strings(Some(8), None);
+        let expected = vec![
+            styles[0].paint("-=-"),
+            styles[1].paint("=-="),
+            styles[2].paint("-="),
+        ];
+        assert_eq!(expected, actual);
+    }
 }
 
 /// A segment is a styled text chunk ready for printing.
@@ -104,14 +200,25 @@ impl Segment {
     where
         T: Into<String>,
     {
+        let value = value.into();
+        // Most text is a single line, which keeps its allocation
+        if !value.contains(LINE_TERMINATOR) {
+            return vec![Self::Text(TextSegment {
+                value,
+                style,
+                link: None,
+            })];
+        }
+
         let mut segs: Vec<Self> = Vec::new();
-        value.into().split(LINE_TERMINATOR).for_each(|s| {
+        value.split(LINE_TERMINATOR).for_each(|s| {
             if !segs.is_empty() {
                 segs.push(Self::LineTerm)
             }
             segs.push(Self::Text(TextSegment {
                 value: String::from(s),
                 style,
+                link: None,
             }))
         });
         segs
@@ -125,6 +232,19 @@ impl Segment {
         Self::Fill(FillSegment {
             style,
             value: value.into(),
+            sections: Vec::new(),
+        })
+    }
+
+    /// Creates a new fill segment that is split into equally wide sections with the given styles
+    pub fn fill_sections<T>(sections: Vec<Style>, value: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self::Fill(FillSegment {
+            style: sections.first().copied(),
+            value: value.into(),
+            sections,
         })
     }
 
@@ -136,6 +256,24 @@ impl Segment {
         }
     }
 
+    /// The style the segment was created with, which may still refer to the colors of the
+    /// previous segment
+    pub fn raw_style(&self) -> Option<Style> {
+        match self {
+            Self::Fill(fs) => fs.style,
+            Self::Text(ts) => ts.style,
+            Self::LineTerm => None,
+        }
+    }
+
+    /// The URL a text segment links to
+    pub fn link(&self) -> Option<&str> {
+        match self {
+            Self::Text(ts) => ts.link.as_deref(),
+            Self::Fill(_) | Self::LineTerm => None,
+        }
+    }
+
     pub fn set_style_if_empty(&mut self, style: Option<Style>) {
         match self {
             Self::Fill(fs) => {
@@ -152,6 +290,26 @@ impl Segment {
         }
     }
 
+    /// Turns a text segment into a hyperlink to the given URL
+    pub fn set_link<T>(&mut self, url: T)
+    where
+        T: Into<String>,
+    {
+        if let Self::Text(ts) = self {
+            ts.link = Some(url.into());
+        }
+    }
+
+    /// Replaces the text of a text segment, keeping its style and link
+    pub fn set_value<T>(&mut self, value: T)
+    where
+        T: Into<String>,
+    {
+        if let Self::Text(ts) = self {
+            ts.value = value.into();
+        }
+    }
+
     pub fn value(&self) -> &str {
         match self {
             Self::Fill(fs) => &fs.value,
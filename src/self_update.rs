@@ -0,0 +1,431 @@
+use semver::Version;
+use serde_json::Value;
+use std::fs;
+use std::io::{self, Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use crate::shadow;
+use crate::utils::create_command;
+
+const RELEASES_API: &str = "https://api.github.com/repos/starship/starship/releases";
+/// The minisign public key releases are signed with, pinned when the binary is built, as the
+/// key published next to the releases could be replaced along with them
+const PUBLIC_KEY: Option<&str> = option_env!("STARSHIP_RELEASE_PUBLIC_KEY");
+
+/// The releases `self-update` picks from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Channel {
+    /// The latest stable release
+    Stable,
+    /// The newest release, including pre-releases
+    Nightly,
+}
+
+/// A release of starship on GitHub and the archive built for this platform
+#[derive(Debug, PartialEq, Eq)]
+struct Release {
+    version: Version,
+    archive_url: String,
+    checksum_url: String,
+    signature_url: String,
+}
+
+/// Replaces the running binary with the newest release of the channel
+///
+/// The archive is verified against the SHA-256 checksum published with the release and its
+/// minisign signature made with the pinned `PUBLIC_KEY`. The new binary is staged next to the
+/// current one and renamed over it, so an interrupted update never leaves a partially written
+/// binary behind.
+pub fn self_update(channel: Channel, check_only: bool) -> io::Result<()> {
+    let current_exe = dunce::canonicalize(std::env::current_exe()?)?;
+    if let Some(manager) = package_manager(&current_exe) {
+        return Err(Error::new(
+            ErrorKind::Unsupported,
+            format!("starship was installed by {manager}, update it from there instead"),
+        ));
+    }
+
+    let current = Version::parse(shadow::PKG_VERSION)
+        .map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+    let releases = download_text(RELEASES_API)?;
+    let release = find_release(&releases, channel, &archive_name())?;
+    if release.version <= current {
+        println!("starship {current} is up to date");
+        return Ok(());
+    }
+    if check_only {
+        println!(
+            "starship {} is available (installed: {current})",
+            release.version
+        );
+        return Ok(());
+    }
+
+    let Some(public_key) = PUBLIC_KEY else {
+        return Err(Error::new(
+            ErrorKind::Unsupported,
+            "this build of starship has no public key to verify releases with",
+        ));
+    };
+
+    println!("Updating starship {current} to {}", release.version);
+    let dir = tempfile::tempdir()?;
+    let archive = dir.path().join(archive_name());
+    download_file(&release.archive_url, &archive)?;
+
+    let checksum = download_text(&release.checksum_url)?;
+    let expected = parse_checksum(&checksum)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "invalid checksum file"))?;
+    let actual = sha256(&archive)?;
+    if !actual.eq_ignore_ascii_case(expected) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("checksum mismatch: expected {expected}, got {actual}"),
+        ));
+    }
+    let signature = download_text(&release.signature_url)?;
+    verify_signature(&fs::read(&archive)?, &signature, public_key)?;
+
+    let binary = extract(&archive, dir.path())?;
+    replace_binary(&binary, &current_exe)?;
+    println!("Updated {} to {}", current_exe.display(), release.version);
+    Ok(())
+}
+
+/// The name of the release archive for the platform starship was built for
+fn archive_name() -> String {
+    let extension = if cfg!(windows) { "zip" } else { "tar.gz" };
+    format!("starship-{}.{extension}", shadow::BUILD_TARGET)
+}
+
+/// Detects installations that are managed by a package manager and must not be replaced
+fn package_manager(exe: &Path) -> Option<&'static str> {
+    let path = exe.to_string_lossy();
+    if path.starts_with("/nix/store/") {
+        Some("Nix")
+    } else if path.contains("/Cellar/") {
+        Some("Homebrew")
+    } else if path.contains("/scoop/apps/") || path.contains("\\scoop\\apps\\") {
+        Some("Scoop")
+    } else if path.starts_with("/usr/bin/") {
+        Some("the system package manager")
+    } else {
+        None
+    }
+}
+
+/// Picks the newest release of the channel from the GitHub releases API response
+fn find_release(releases: &str, channel: Channel, archive: &str) -> io::Result<Release> {
+    let releases: Value = serde_json::from_str(releases)
+        .map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+    let release = releases
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|release| {
+            channel == Channel::Nightly || release["prerelease"].as_bool() == Some(false)
+        })
+        .filter(|release| release["draft"].as_bool() != Some(true))
+        .find_map(|release| {
+            let tag = release["tag_name"].as_str()?;
+            let version = Version::parse(tag.trim_start_matches('v')).ok()?;
+            let asset_url = |name: &str| {
+                release["assets"].as_array()?.iter().find_map(|asset| {
+                    (asset["name"].as_str()? == name)
+                        .then(|| asset["browser_download_url"].as_str())
+                        .flatten()
+                        .map(ToString::to_string)
+                })
+            };
+            Some(Release {
+                version,
+                archive_url: asset_url(archive)?,
+                checksum_url: asset_url(&format!("{archive}.sha256"))?,
+                signature_url: asset_url(&format!("{archive}.minisig"))?,
+            })
+        });
+
+    release.ok_or_else(|| {
+        Error::new(
+            ErrorKind::NotFound,
+            format!("no release of starship provides {archive}"),
+        )
+    })
+}
+
+/// Reads the digest from a checksum file, which may be followed by the file name
+fn parse_checksum(contents: &str) -> Option<&str> {
+    contents
+        .split_whitespace()
+        .next()
+        .filter(|digest| digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Checks the minisign signature of the archive, which proves it was built by the holders of
+/// the private key rather than only uploaded with a matching checksum
+fn verify_signature(archive: &[u8], signature: &str, public_key: &str) -> io::Result<()> {
+    let invalid = |error: minisign_verify::Error| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("invalid signature of the release: {error}"),
+        )
+    };
+    let public_key = minisign_verify::PublicKey::from_base64(public_key).map_err(invalid)?;
+    let signature = minisign_verify::Signature::decode(signature).map_err(invalid)?;
+    public_key
+        .verify(archive, &signature, false)
+        .map_err(invalid)
+}
+
+fn download_text(url: &str) -> io::Result<String> {
+    let output = create_command("curl")?
+        .args(["--fail", "--silent", "--show-error", "--location", url])
+        .output()?;
+    if !output.status.success() {
+        return Err(Error::other(format!(
+            "could not download {url}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    String::from_utf8(output.stdout).map_err(|error| Error::new(ErrorKind::InvalidData, error))
+}
+
+fn download_file(url: &str, path: &Path) -> io::Result<()> {
+    let status = create_command("curl")?
+        .args([
+            "--fail",
+            "--silent",
+            "--show-error",
+            "--location",
+            "--output",
+        ])
+        .arg(path)
+        .arg(url)
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(Error::other(format!("could not download {url}")))
+    }
+}
+
+/// Computes the SHA-256 digest of a file with the tools the platform ships
+fn sha256(path: &Path) -> io::Result<String> {
+    let attempts: &[(&str, &[&str])] = if cfg!(windows) {
+        &[("certutil", &["-hashfile"])]
+    } else {
+        &[("sha256sum", &[]), ("shasum", &["-a", "256"])]
+    };
+    for (tool, args) in attempts {
+        let Ok(mut command) = create_command(tool) else {
+            continue;
+        };
+        command.args(*args).arg(path);
+        if cfg!(windows) {
+            command.arg("SHA256");
+        }
+        let output = command.output()?;
+        if !output.status.success() {
+            continue;
+        }
+        // certutil prints a header line before the digest
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let digest = stdout
+            .lines()
+            .filter_map(parse_checksum)
+            .next()
+            .map(ToString::to_string);
+        if let Some(digest) = digest {
+            return Ok(digest);
+        }
+    }
+    Err(Error::new(
+        ErrorKind::NotFound,
+        "no tool to compute SHA-256 checksums found",
+    ))
+}
+
+/// Unpacks the archive and returns the path of the binary inside
+fn extract(archive: &Path, dir: &Path) -> io::Result<PathBuf> {
+    // tar ships with Windows 10 and later, and also unpacks zip archives there
+    let flags = if cfg!(windows) { "-xf" } else { "-xzf" };
+    let status = create_command("tar")?
+        .arg(flags)
+        .arg(archive)
+        .arg("-C")
+        .arg(dir)
+        .status()?;
+    if !status.success() {
+        return Err(Error::other("could not unpack the release archive"));
+    }
+
+    let binary = dir.join(if cfg!(windows) {
+        "starship.exe"
+    } else {
+        "starship"
+    });
+    if binary.is_file() {
+        Ok(binary)
+    } else {
+        Err(Error::new(
+            ErrorKind::NotFound,
+            "the release archive does not contain starship",
+        ))
+    }
+}
+
+/// Moves the new binary into place. The copy is staged in the same directory, where the final
+/// rename cannot cross file systems, and is only moved once it runs.
+fn replace_binary(new: &Path, current: &Path) -> io::Result<()> {
+    let dir = current
+        .parent()
+        .ok_or_else(|| Error::new(ErrorKind::NotFound, "starship is not in a directory"))?;
+    let staged = dir.join(if cfg!(windows) {
+        ".starship.new.exe"
+    } else {
+        ".starship.new"
+    });
+    let replaced = fs::copy(new, &staged)
+        .and_then(|_| make_executable(&staged))
+        .and_then(|()| check_binary(&staged))
+        .and_then(|()| rename_into_place(&staged, current));
+    if replaced.is_err() {
+        let _ = fs::remove_file(&staged);
+    }
+    replaced
+}
+
+#[cfg(unix)]
+fn make_executable(binary: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(binary, fs::Permissions::from_mode(0o755))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_binary: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// Runs the new binary, so a binary that can't run here never replaces one that can
+fn check_binary(binary: &Path) -> io::Result<()> {
+    let output = create_command(binary)?.arg("--version").output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(Error::other("the new binary of starship does not run"))
+    }
+}
+
+/// Renames the staged binary over the current one, which is atomic on Unix
+#[cfg(not(windows))]
+fn rename_into_place(staged: &Path, current: &Path) -> io::Result<()> {
+    fs::rename(staged, current)
+}
+
+/// A running binary can't be replaced on Windows, but it can be renamed. It is moved aside
+/// first, and moved back if the new binary can't take its place.
+#[cfg(windows)]
+fn rename_into_place(staged: &Path, current: &Path) -> io::Result<()> {
+    let old = current.with_file_name("starship.old.exe");
+    // Left behind by the last update, once that binary is no longer running
+    let _ = fs::remove_file(&old);
+    fs::rename(current, &old)?;
+    fs::rename(staged, current).map_err(|error| {
+        if let Err(restore_error) = fs::rename(&old, current) {
+            log::error!(
+                "Unable to restore {} from {}: {restore_error}",
+                current.display(),
+                old.display()
+            );
+        }
+        error
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RELEASES: &str = r#"[
+        {
+            "tag_name": "v1.22.0-rc.1",
+            "prerelease": true,
+            "draft": false,
+            "assets": [
+                {"name": "starship-x86_64-unknown-linux-musl.tar.gz", "browser_download_url": "https://example.com/rc/archive"},
+                {"name": "starship-x86_64-unknown-linux-musl.tar.gz.sha256", "browser_download_url": "https://example.com/rc/sha256"},
+                {"name": "starship-x86_64-unknown-linux-musl.tar.gz.minisig", "browser_download_url": "https://example.com/rc/minisig"}
+            ]
+        },
+        {
+            "tag_name": "v1.21.1",
+            "prerelease": false,
+            "draft": false,
+            "assets": [
+                {"name": "starship-aarch64-apple-darwin.tar.gz", "browser_download_url": "https://example.com/mac/archive"},
+                {"name": "starship-x86_64-unknown-linux-musl.tar.gz", "browser_download_url": "https://example.com/stable/archive"},
+                {"name": "starship-x86_64-unknown-linux-musl.tar.gz.sha256", "browser_download_url": "https://example.com/stable/sha256"},
+                {"name": "starship-x86_64-unknown-linux-musl.tar.gz.minisig", "browser_download_url": "https://example.com/stable/minisig"}
+            ]
+        }
+    ]"#;
+
+    const ARCHIVE: &str = "starship-x86_64-unknown-linux-musl.tar.gz";
+
+    #[test]
+    fn stable_release() {
+        let release = find_release(RELEASES, Channel::Stable, ARCHIVE).unwrap();
+        assert_eq!(
+            release,
+            Release {
+                version: Version::new(1, 21, 1),
+                archive_url: "https://example.com/stable/archive".to_string(),
+                checksum_url: "https://example.com/stable/sha256".to_string(),
+                signature_url: "https://example.com/stable/minisig".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn nightly_release() {
+        let release = find_release(RELEASES, Channel::Nightly, ARCHIVE).unwrap();
+        assert_eq!(release.version, Version::parse("1.22.0-rc.1").unwrap());
+        assert_eq!(release.archive_url, "https://example.com/rc/archive");
+    }
+
+    #[test]
+    fn release_without_checksum() {
+        let release = find_release(
+            RELEASES,
+            Channel::Stable,
+            "starship-aarch64-apple-darwin.tar.gz",
+        );
+        assert!(release.is_err());
+    }
+
+    #[test]
+    fn checksum_files() {
+        let digest = "8a3f6c0f4e3a5e3c1b1f7e6b3f7c9a6e5d4c3b2a1f0e9d8c7b6a5f4e3d2c1b0a";
+        assert_eq!(parse_checksum(digest), Some(digest));
+        assert_eq!(
+            parse_checksum(&format!("{digest}  starship.tar.gz\n")),
+            Some(digest)
+        );
+        assert_eq!(parse_checksum("SHA256 hash of starship.zip:"), None);
+    }
+
+    #[test]
+    fn package_managers() {
+        assert_eq!(
+            package_manager(Path::new("/nix/store/abc-starship-1.21.1/bin/starship")),
+            Some("Nix")
+        );
+        assert_eq!(
+            package_manager(Path::new(
+                "/opt/homebrew/Cellar/starship/1.21.1/bin/starship"
+            )),
+            Some("Homebrew")
+        );
+        assert_eq!(package_manager(Path::new("/usr/local/bin/starship")), None);
+    }
+}
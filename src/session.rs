@@ -0,0 +1,161 @@
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::context::Context;
+
+/// Session directories that were not used for this long belong to shells that exited without
+/// running `starship session end`, e.g. because they were killed, or because they have no hook
+/// for their exit, like cmd, Nushell, Elvish, tcsh and Ion
+const STALE_AFTER: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Generates a random key identifying a shell session
+pub fn new_key() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(16)
+        .map(char::from)
+        .collect()
+}
+
+/// The directory holding the state of the shell session starship runs in, like the last
+/// rendered prompt
+///
+/// Returns `None` outside of a session started by the init scripts.
+pub fn session_dir(context: &Context) -> Option<PathBuf> {
+    let key = sanitize(&context.get_env("STARSHIP_SESSION_KEY")?)?;
+    Some(context.get_cache_dir()?.join("sessions").join(key))
+}
+
+/// Marks the session as waiting for modules that run commands in the background, or clears
+/// the mark once none are left
+///
+/// Shells that can redraw the prompt on their own animate `pending_symbols` while it is set.
+pub fn set_pending(context: &Context, modules: &[String]) -> io::Result<()> {
+    let Some(dir) = session_dir(context) else {
+        return Ok(());
+    };
+    let file = dir.join("pending");
+    if modules.is_empty() {
+        match fs::remove_file(file) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+            _ => Ok(()),
+        }
+    } else {
+        // The directory is removed with stale sessions, even while the shell still runs
+        fs::create_dir_all(dir)?;
+        fs::write(file, modules.join("\n"))
+    }
+}
+
+/// Session keys become directory names, so only letters and digits are kept
+fn sanitize(key: &str) -> Option<String> {
+    let key: String = key.chars().filter(char::is_ascii_alphanumeric).collect();
+    (!key.is_empty()).then_some(key)
+}
+
+/// Creates the state directory of a new session and returns its key
+///
+/// The directories of sessions that ended without cleaning up are removed at the same time.
+pub fn start(context: &Context, key: Option<String>) -> io::Result<String> {
+    let key = key.as_deref().and_then(sanitize).unwrap_or_else(new_key);
+    let Some(cache_dir) = context.get_cache_dir() else {
+        return Ok(key);
+    };
+    let sessions = cache_dir.join("sessions");
+    fs::create_dir_all(sessions.join(&key))?;
+    remove_stale(&sessions, SystemTime::now())?;
+    Ok(key)
+}
+
+/// Removes the state directory of the current session
+pub fn end(context: &Context) -> io::Result<()> {
+    match session_dir(context) {
+        Some(dir) if dir.exists() => fs::remove_dir_all(dir),
+        _ => Ok(()),
+    }
+}
+
+/// Removes the session directories that were not used since `STALE_AFTER`
+fn remove_stale(sessions: &Path, now: SystemTime) -> io::Result<()> {
+    for entry in fs::read_dir(sessions)? {
+        let dir = entry?.path();
+        if !dir.is_dir() {
+            continue;
+        }
+        let is_stale = last_used(&dir)
+            .and_then(|used| now.duration_since(used).ok())
+            .map_or(false, |age| age > STALE_AFTER);
+        if is_stale {
+            log::debug!("Removing stale session {}", dir.display());
+            fs::remove_dir_all(dir)?;
+        }
+    }
+    Ok(())
+}
+
+/// The time the session directory or any file in it was last modified
+fn last_used(dir: &Path) -> Option<SystemTime> {
+    let modified = |path: &Path| fs::metadata(path).and_then(|metadata| metadata.modified());
+    let files = fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter_map(|entry| modified(&entry.path()).ok());
+    files.chain(modified(dir).ok()).max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::default_context;
+
+    #[test]
+    fn session_lifecycle() -> io::Result<()> {
+        let cache = tempfile::tempdir()?;
+        let mut context = default_context();
+        context
+            .env
+            .insert("STARSHIP_CACHE", cache.path().to_string_lossy().to_string());
+
+        let key = start(&context, Some("0123-4567".to_string()))?;
+        assert_eq!(key, "01234567");
+        let dir = cache.path().join("sessions").join(&key);
+        assert!(dir.is_dir());
+
+        context.env.insert("STARSHIP_SESSION_KEY", key);
+        assert_eq!(session_dir(&context), Some(dir.clone()));
+        set_pending(&context, &["repo_remote".to_string()])?;
+        assert!(dir.join("pending").exists());
+        set_pending(&context, &[])?;
+        assert!(!dir.join("pending").exists());
+        end(&context)?;
+        assert!(!dir.exists());
+        cache.close()
+    }
+
+    #[test]
+    fn stale_sessions_are_removed() -> io::Result<()> {
+        let sessions = tempfile::tempdir()?;
+        let dir = sessions.path().join("abc");
+        fs::create_dir(&dir)?;
+        fs::write(dir.join("prompt-main"), "")?;
+
+        remove_stale(sessions.path(), SystemTime::now())?;
+        assert!(dir.exists());
+
+        let later = SystemTime::now() + STALE_AFTER + Duration::from_secs(60);
+        remove_stale(sessions.path(), later)?;
+        assert!(!dir.exists());
+        sessions.close()
+    }
+
+    #[test]
+    fn keys_are_sanitized() {
+        assert_eq!(new_key().len(), 16);
+        assert_eq!(sanitize("../.."), None);
+        assert_eq!(sanitize("abc/12"), Some("abc12".to_string()));
+    }
+}
@@ -0,0 +1,245 @@
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::context::{Context, Target};
+use crate::logger::get_log_dir;
+
+/// Once the history grows past this size, the older half is dropped
+const MAX_HISTORY_BYTES: u64 = 2 * 1024 * 1024;
+
+/// A rendered prompt, as recorded in the history when `record_stats` is enabled
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct StatsEntry {
+    /// When the prompt was rendered, in seconds since the unix epoch
+    pub time: i64,
+    pub dir: String,
+    /// The exit code of the command before the prompt
+    pub status: Option<String>,
+    /// How long each module took to compute, in milliseconds
+    pub modules: IndexMap<String, f64>,
+}
+
+fn history_path() -> PathBuf {
+    get_log_dir().join("stats.jsonl")
+}
+
+/// Appends the prompt that was just rendered to the local history
+///
+/// Nothing is recorded unless `record_stats` is enabled, and nothing leaves the machine.
+pub fn record(context: &Context) {
+    if !context.root_config.record_stats || context.target != Target::Main {
+        return;
+    }
+
+    let entry = StatsEntry {
+        time: Utc::now().timestamp(),
+        dir: context.logical_dir.to_string_lossy().into_owned(),
+        status: context.properties.status_code.clone(),
+        modules: context
+            .module_durations()
+            .into_iter()
+            .map(|(name, duration)| (name, duration.as_secs_f64() * 1000.0))
+            .collect(),
+    };
+    if let Err(error) = append(&history_path(), &entry) {
+        log::debug!("Unable to record prompt statistics: {error}");
+    }
+}
+
+fn append(path: &Path, entry: &StatsEntry) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let line = serde_json::to_string(entry)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{line}")?;
+
+    // Keep the history bounded like a ring buffer, without reading it on every prompt
+    if file.metadata()?.len() > MAX_HISTORY_BYTES {
+        let contents = fs::read_to_string(path)?;
+        let lines: Vec<&str> = contents.lines().collect();
+        let kept = lines[lines.len() / 2..].join("\n");
+        fs::write(path, kept + "\n")?;
+    }
+    Ok(())
+}
+
+fn load(path: &Path) -> io::Result<Vec<StatsEntry>> {
+    let contents = fs::read_to_string(path)?;
+    Ok(contents
+        .lines()
+        // Skip lines cut off by concurrent writes
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Prints a report of the prompts recorded in the last `days`
+pub fn stats(days: i64) -> io::Result<()> {
+    let path = history_path();
+    let entries = match load(&path) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(error) => return Err(error),
+    };
+    let since = Utc::now() - ChronoDuration::days(days);
+    let entries: Vec<&StatsEntry> = entries
+        .iter()
+        .filter(|entry| entry.time >= since.timestamp())
+        .collect();
+
+    if entries.is_empty() {
+        println!("No prompts recorded in the last {days} days.");
+        println!("Set `record_stats = true` in your configuration to record them locally.");
+        return Ok(());
+    }
+
+    println!(
+        "\n Rendered {} prompts in the last {days} days\n",
+        entries.len()
+    );
+
+    println!(" Slowest modules (average)");
+    for (name, average) in slowest_modules(&entries).into_iter().take(10) {
+        println!("   {name:<20} {average:>9.2}ms");
+    }
+
+    println!("\n Most common directories");
+    for (dir, count) in common_directories(&entries).into_iter().take(10) {
+        println!("   {count:>6}  {dir}");
+    }
+
+    println!("\n Failed commands per day");
+    for (day, rate) in failure_rates(&entries) {
+        println!("   {day}  {:>5.1}%", rate * 100.0);
+    }
+    println!();
+    Ok(())
+}
+
+/// Average duration of each module, slowest first
+fn slowest_modules(entries: &[&StatsEntry]) -> Vec<(String, f64)> {
+    let mut totals: IndexMap<&str, (f64, usize)> = IndexMap::new();
+    for entry in entries {
+        for (name, duration) in &entry.modules {
+            let total = totals.entry(name).or_default();
+            total.0 += duration;
+            total.1 += 1;
+        }
+    }
+    let mut averages: Vec<(String, f64)> = totals
+        .into_iter()
+        .map(|(name, (total, count))| (name.to_string(), total / count as f64))
+        .collect();
+    averages.sort_by(|a, b| b.1.total_cmp(&a.1));
+    averages
+}
+
+/// How often a prompt was rendered in each directory, most common first
+fn common_directories(entries: &[&StatsEntry]) -> Vec<(String, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for entry in entries {
+        *counts.entry(&entry.dir).or_default() += 1;
+    }
+    let mut counts: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|(dir, count)| (dir.to_string(), count))
+        .collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+/// The share of prompts after a failed command for each day, oldest first
+fn failure_rates(entries: &[&StatsEntry]) -> Vec<(String, f64)> {
+    let mut days: IndexMap<String, (usize, usize)> = IndexMap::new();
+    for entry in entries {
+        let Some(time) = DateTime::from_timestamp(entry.time, 0) else {
+            continue;
+        };
+        let day = days.entry(time.format("%Y-%m-%d").to_string()).or_default();
+        day.1 += 1;
+        if !matches!(entry.status.as_deref(), None | Some("0")) {
+            day.0 += 1;
+        }
+    }
+    days.sort_keys();
+    days.into_iter()
+        .map(|(day, (failed, total))| (day, failed as f64 / total as f64))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(time: i64, dir: &str, status: &str, modules: &[(&str, f64)]) -> StatsEntry {
+        StatsEntry {
+            time,
+            dir: dir.to_string(),
+            status: Some(status.to_string()),
+            modules: modules
+                .iter()
+                .map(|(name, duration)| (name.to_string(), *duration))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn report() {
+        // 2025-10-12 and 2025-10-13, in UTC
+        let entries = [
+            entry(
+                1_760_270_400,
+                "~/src/app",
+                "0",
+                &[("git_status", 30.0), ("rust", 5.0)],
+            ),
+            entry(1_760_274_000, "~/src/app", "1", &[("git_status", 50.0)]),
+            entry(1_760_360_400, "~", "0", &[("rust", 7.0)]),
+        ];
+        let entries: Vec<&StatsEntry> = entries.iter().collect();
+
+        assert_eq!(
+            slowest_modules(&entries),
+            vec![("git_status".to_string(), 40.0), ("rust".to_string(), 6.0)]
+        );
+        assert_eq!(
+            common_directories(&entries),
+            vec![("~/src/app".to_string(), 2), ("~".to_string(), 1)]
+        );
+        assert_eq!(
+            failure_rates(&entries),
+            vec![
+                ("2025-10-12".to_string(), 0.5),
+                ("2025-10-13".to_string(), 0.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn history_is_bounded() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("stats.jsonl");
+        let modules: Vec<(String, f64)> = (0..100)
+            .map(|i| (format!("custom.module{i}"), 1.5))
+            .collect();
+        let entry = StatsEntry {
+            time: 0,
+            dir: "/".to_string(),
+            status: None,
+            modules: modules.into_iter().collect(),
+        };
+
+        for _ in 0..1000 {
+            append(&path, &entry)?;
+        }
+
+        assert!(fs::metadata(&path)?.len() <= MAX_HISTORY_BYTES);
+        assert_eq!(load(&path)?.first(), Some(&entry));
+        dir.close()
+    }
+}
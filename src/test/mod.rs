@@ -1,3 +1,21 @@
+//! Helpers for rendering modules against a mocked [`Context`].
+//!
+//! These are the same utilities starship uses for its own module tests. They are available to
+//! downstream crates through the `test-utils` feature, e.g.
+//!
+//! ```ignore
+//! use starship::test::ModuleRenderer;
+//!
+//! let actual = ModuleRenderer::new("shlvl")
+//!     .env("SHLVL", "3")
+//!     .config(toml::toml! {
+//!         [shlvl]
+//!         disabled = false
+//!         threshold = 0
+//!     })
+//!     .collect();
+//! ```
+
 use crate::context::{Context, Shell, Target};
 use crate::logger::StarshipLogger;
 use crate::{
@@ -35,7 +53,11 @@ fn init_logger() {
     log::set_boxed_logger(Box::new(logger)).unwrap();
 }
 
+/// Creates a context for the current directory, without any configuration loaded from disk
 pub fn default_context() -> Context<'static> {
+    // Start logger, modules report their problems through it
+    LOGGER.call_once(init_logger);
+
     let mut context = Context::new_with_shell_and_path(
         Default::default(),
         Shell::Unknown,
@@ -48,6 +70,29 @@ pub fn default_context() -> Context<'static> {
     context
 }
 
+/// Mocks the processes starship was started from, its parent first
+pub fn mock_parent_processes(context: &mut Context, names: &[&str]) {
+    // Each process is the parent of the one before
+    let mut pid = std::process::id();
+    let stdout = std::iter::once("starship")
+        .chain(names.iter().copied())
+        .enumerate()
+        .map(|(index, name)| {
+            let parent = 1000 + index as u32;
+            let line = format!("{pid:>7} {parent:>7} {name}\n");
+            pid = parent;
+            line
+        })
+        .collect();
+    context.cmd.insert(
+        "ps -A -o pid=,ppid=,comm=",
+        Some(CommandOutput {
+            stdout,
+            stderr: String::new(),
+        }),
+    );
+}
+
 /// Render a specific starship module by name
 pub struct ModuleRenderer<'a> {
     name: &'a str,
@@ -65,6 +110,7 @@ impl<'a> ModuleRenderer<'a> {
         Self { name, context }
     }
 
+    /// Sets the directory the module is rendered in
     pub fn path<T>(mut self, path: T) -> Self
     where
         T: Into<PathBuf>,
@@ -76,10 +122,12 @@ impl<'a> ModuleRenderer<'a> {
         self
     }
 
+    /// Returns the mocked root directory that absolute paths are resolved against
     pub fn root_path(&self) -> &Path {
         self.context.root_dir.path()
     }
 
+    /// Sets the logical directory, e.g. the path as the shell reports it
     pub fn logical_path<T>(mut self, path: T) -> Self
     where
         T: Into<PathBuf>,
@@ -106,21 +154,41 @@ impl<'a> ModuleRenderer<'a> {
         self
     }
 
+    /// Creates a file at the absolute `path` below the mocked root directory
+    pub fn file<P: AsRef<Path>, S: AsRef<str>>(self, path: P, contents: S) -> Self {
+        let path = crate::utils::context_path(&self.context, path.as_ref());
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("failed to create mocked directory");
+        }
+        fs::write(&path, contents.as_ref()).expect("failed to write mocked file");
+        self
+    }
+
+    /// Sets the shell the prompt is rendered for
     pub fn shell(mut self, shell: Shell) -> Self {
         self.context.shell = shell;
         self
     }
 
+    /// Sets the processes starship was started from, its parent first
+    pub fn parent_processes(mut self, names: &[&str]) -> Self {
+        mock_parent_processes(&mut self.context, names);
+        self
+    }
+
+    /// Sets the number of running jobs
     pub fn jobs(mut self, jobs: i64) -> Self {
         self.context.properties.jobs = jobs;
         self
     }
 
+    /// Sets the duration of the last command, in milliseconds
     pub fn cmd_duration(mut self, duration: u64) -> Self {
         self.context.properties.cmd_duration = Some(duration.to_string());
         self
     }
 
+    /// Sets the active keymap
     pub fn keymap<T>(mut self, keymap: T) -> Self
     where
         T: Into<String>,
@@ -129,16 +197,25 @@ impl<'a> ModuleRenderer<'a> {
         self
     }
 
+    /// Sets the frame of the pending animation
+    pub fn pending_frame(mut self, frame: usize) -> Self {
+        self.context.properties.pending_frame = Some(frame);
+        self
+    }
+
+    /// Sets the exit status of the last command
     pub fn status(mut self, status: i64) -> Self {
         self.context.properties.status_code = Some(status.to_string());
         self
     }
 
+    /// Sets the terminal width
     pub fn width(mut self, width: usize) -> Self {
         self.context.width = width;
         self
     }
 
+    /// Replaces the battery info provider
     #[cfg(feature = "battery")]
     pub fn battery_info_provider(
         mut self,
@@ -148,6 +225,7 @@ impl<'a> ModuleRenderer<'a> {
         self
     }
 
+    /// Sets the exit statuses of the last pipeline
     pub fn pipestatus(mut self, status: &[i64]) -> Self {
         self.context.properties.pipestatus = Some(
             status
@@ -175,6 +253,7 @@ impl<'a> From<ModuleRenderer<'a>> for Context<'a> {
     }
 }
 
+/// Version control systems that fixture repositories can be created for
 #[derive(Clone, Copy)]
 pub enum FixtureProvider {
     Fossil,
@@ -184,6 +263,7 @@ pub enum FixtureProvider {
     Pijul,
 }
 
+/// Creates a temporary repository for the given version control system
 pub fn fixture_repo(provider: FixtureProvider) -> io::Result<TempDir> {
     match provider {
         FixtureProvider::Fossil => {
@@ -273,3 +353,17 @@ pub fn fixture_repo(provider: FixtureProvider) -> io::Result<TempDir> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_is_created_below_mocked_root() -> io::Result<()> {
+        let renderer = ModuleRenderer::new("aws").file("/home/user/.aws/config", "[default]");
+
+        let actual = fs::read_to_string(renderer.root_path().join("home/user/.aws/config"))?;
+        assert_eq!(actual, "[default]");
+        Ok(())
+    }
+}
@@ -0,0 +1,266 @@
+//! Theme bundles hold the presentation keys of a configuration, like format strings, palettes and
+//! symbols, so prompts can be shared without behavioral settings like timeouts or commands.
+//!
+//! A bundle is a TOML file shaped like a configuration, starting with a `[theme]` table:
+//!
+//! ```toml
+//! [theme]
+//! name = "ocean"
+//! version = 1
+//!
+//! format = "$directory$character"
+//!
+//! [directory]
+//! style = "bold cyan"
+//! ```
+
+use std::fs;
+use std::path::Path;
+use toml_edit::{DocumentMut, Item, TableLike};
+
+use crate::configure;
+use crate::context::Context;
+
+/// The version of the bundle format written by `export`, newer bundles are not imported
+const BUNDLE_VERSION: i64 = 1;
+
+/// Keys that only change how the prompt looks
+const PRESENTATION_KEYS: &[&str] = &[
+    "add_newline",
+    "charset",
+    "format",
+    "palette",
+    "palettes",
+    "profiles",
+    "style",
+    "substitutions",
+    "symbol",
+    "symbols",
+    // Symbols of `directory` and `git_status` without a `_symbol` suffix
+    "ahead",
+    "behind",
+    "conflicted",
+    "deleted",
+    "diverged",
+    "modified",
+    "read_only",
+    "renamed",
+    "staged",
+    "stashed",
+    "typechanged",
+    "untracked",
+    "up_to_date",
+];
+
+const PRESENTATION_SUFFIXES: &[&str] = &["_format", "_msg", "_style", "_symbol", "_symbols"];
+
+/// Returns the theme bundle of the current configuration
+pub fn export(context: &Context, name: Option<&str>) -> String {
+    let mut header = toml::Table::new();
+    if let Some(name) = name {
+        header.insert("name".to_string(), name.into());
+    }
+    header.insert("version".to_string(), BUNDLE_VERSION.into());
+
+    let mut bundle = toml::Table::new();
+    bundle.insert("theme".to_string(), header.into());
+    bundle.extend(presentation_keys(&configure::get_configuration(context), 0));
+    toml::to_string_pretty(&bundle).unwrap_or_default()
+}
+
+/// Writes the presentation keys of a theme bundle into the configuration file, keeping all
+/// other keys as they are
+pub fn import(context: &Context, file: &Path) -> Result<(), String> {
+    let bundle = fs::read_to_string(file).map_err(|error| error.to_string())?;
+    let mut doc = configure::get_configuration_edit(context);
+    import_into(&mut doc, &bundle)?;
+    configure::write_configuration(context, &doc);
+    Ok(())
+}
+
+fn import_into(doc: &mut DocumentMut, bundle: &str) -> Result<(), String> {
+    let mut bundle: toml::Table = toml::from_str(bundle).map_err(|error| error.to_string())?;
+    let version = bundle
+        .remove("theme")
+        .and_then(|theme| theme.get("version").and_then(toml::Value::as_integer))
+        .ok_or("The file is not a theme bundle, it has no `[theme]` table with a version")?;
+    if version > BUNDLE_VERSION {
+        return Err(format!(
+            "The theme bundle has version {version}, update starship to import it"
+        ));
+    }
+
+    let theme = presentation_keys(&bundle, 0);
+    for key in ignored_keys(&bundle, &theme, "") {
+        log::warn!("Ignoring `{key}` of the theme bundle, it does not change how the prompt looks");
+    }
+    merge(doc.as_table_mut(), &theme);
+    Ok(())
+}
+
+fn is_presentation_key(key: &str) -> bool {
+    PRESENTATION_KEYS.contains(&key)
+        || key.starts_with("style_")
+        || PRESENTATION_SUFFIXES
+            .iter()
+            .any(|suffix| key.ends_with(suffix))
+}
+
+/// Keeps the presentation keys of a configuration table, looking into the tables of modules
+/// and of custom modules
+pub(crate) fn presentation_keys(table: &toml::Table, depth: usize) -> toml::Table {
+    table
+        .iter()
+        .filter_map(|(key, value)| {
+            if is_presentation_key(key) {
+                return Some((key.clone(), value.clone()));
+            }
+            match value {
+                toml::Value::Table(table) if depth < 2 => {
+                    let kept = presentation_keys(table, depth + 1);
+                    (!kept.is_empty()).then(|| (key.clone(), kept.into()))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// The dotted paths of the keys of a bundle that are not part of its theme
+fn ignored_keys(bundle: &toml::Table, theme: &toml::Table, prefix: &str) -> Vec<String> {
+    bundle
+        .iter()
+        .flat_map(|(key, value)| {
+            let path = format!("{prefix}{key}");
+            match (value, theme.get(key)) {
+                (_, Some(kept)) if kept == value => Vec::new(),
+                (toml::Value::Table(table), Some(toml::Value::Table(kept))) => {
+                    ignored_keys(table, kept, &format!("{path}."))
+                }
+                _ => vec![path],
+            }
+        })
+        .collect()
+}
+
+/// Sets the keys of the theme in the configuration, merging tables that exist in both
+fn merge(target: &mut dyn TableLike, theme: &toml::Table) {
+    for (key, value) in theme {
+        if let (Some(table), toml::Value::Table(theme)) =
+            (target.get_mut(key).and_then(Item::as_table_like_mut), value)
+        {
+            merge(table, theme);
+        } else {
+            target.insert(key, to_item(value));
+        }
+    }
+}
+
+fn to_item(value: &toml::Value) -> Item {
+    match value {
+        toml::Value::Table(table) => {
+            let mut item = toml_edit::Table::new();
+            // Tables holding only other tables get no header of their own
+            item.set_implicit(true);
+            for (key, value) in table {
+                item.insert(key, to_item(value));
+            }
+            Item::Table(item)
+        }
+        value => match value.to_string().parse::<toml_edit::Value>() {
+            Ok(mut value) => {
+                value.decor_mut().clear();
+                Item::Value(value)
+            }
+            Err(_) => Item::None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_presentation_keys() {
+        let config = toml::toml! {
+            format = "$directory$character"
+            command_timeout = 1000
+            palette = "ocean"
+            [palettes.ocean]
+            blue = "#0077be"
+            [directory]
+            style = "bold cyan"
+            truncation_length = 2
+            read_only = " ro"
+            [username]
+            style_root = "red"
+            show_always = true
+            [custom.docker]
+            command = "docker context show"
+            format = "[$output]($style)"
+        };
+
+        let expected = toml::toml! {
+            format = "$directory$character"
+            palette = "ocean"
+            [palettes.ocean]
+            blue = "#0077be"
+            [directory]
+            style = "bold cyan"
+            read_only = " ro"
+            [username]
+            style_root = "red"
+            [custom.docker]
+            format = "[$output]($style)"
+        };
+        assert_eq!(presentation_keys(&config, 0), expected);
+    }
+
+    #[test]
+    fn import_keeps_behavioral_keys() {
+        let mut doc: DocumentMut = r#"# My prompt
+command_timeout = 1000
+
+[directory]
+style = "blue"
+truncation_length = 2
+"#
+        .parse()
+        .unwrap();
+        let bundle = r#"
+[theme]
+name = "ocean"
+version = 1
+
+[directory]
+style = "bold cyan"
+truncation_length = 8
+
+[git_branch]
+symbol = "> "
+"#;
+
+        import_into(&mut doc, bundle).unwrap();
+        assert_eq!(
+            doc.to_string(),
+            r#"# My prompt
+command_timeout = 1000
+
+[directory]
+style = "bold cyan"
+truncation_length = 2
+
+[git_branch]
+symbol = "> "
+"#
+        );
+    }
+
+    #[test]
+    fn import_rejects_other_files() {
+        let mut doc = DocumentMut::new();
+        assert!(import_into(&mut doc, "format = \"$all\"").is_err());
+        assert!(import_into(&mut doc, "[theme]\nversion = 2").is_err());
+    }
+}
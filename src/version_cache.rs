@@ -0,0 +1,356 @@
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::context::Context;
+use crate::utils::CommandOutput;
+
+/// Files of projects that make a tool switch versions, like the `toolchain` of `go.mod` with
+/// `GOTOOLCHAIN=auto`, the proxies of rustup or the shims of Volta
+const PROJECT_VERSION_FILES: &[&str] = &[
+    "go.mod",
+    "go.work",
+    "rust-toolchain",
+    "rust-toolchain.toml",
+    "package.json",
+    ".nvmrc",
+    ".node-version",
+    ".tool-versions",
+    ".python-version",
+    ".ruby-version",
+    ".java-version",
+    "global.json",
+];
+
+/// Files outside of projects that pin the default version of a tool, each in the directory of
+/// an environment variable or else in a directory of the home directory
+const GLOBAL_VERSION_FILES: &[(&str, &str, &str)] = &[
+    ("VOLTA_HOME", ".volta", "tools/user/platform.json"),
+    ("RUSTUP_HOME", ".rustup", "settings.toml"),
+    ("PYENV_ROOT", ".pyenv", "version"),
+    ("RBENV_ROOT", ".rbenv", "version"),
+    ("NVM_DIR", ".nvm", "alias/default"),
+];
+
+/// Environment variables that make a tool switch versions
+const VERSION_ENV_VARS: &[&str] = &["GOTOOLCHAIN", "RUSTUP_TOOLCHAIN", "VOLTA_HOME"];
+
+/// Cached outputs that were not written for this long are removed, so entries of old binaries
+/// and projects don't pile up
+const MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Returns the file caching the output of running `binary` with `args`
+///
+/// The name is derived from the path and modification time of the binary, so updating the tool
+/// starts a new entry. As tools like `go` or the proxies of rustup answer differently per
+/// project, the name also depends on the current directory, the version files of the projects
+/// it is in and the global version files of version managers. Returns `None` for binaries whose
+/// output does not only depend on the binary itself.
+pub fn cache_file(context: &Context, binary: &Path, args: &[String]) -> Option<PathBuf> {
+    // Shims of version managers like asdf, pyenv or rbenv pick the version from the directory
+    if binary
+        .components()
+        .any(|component| component == Component::Normal("shims".as_ref()))
+    {
+        return None;
+    }
+    fs::metadata(binary).ok()?;
+
+    let fingerprint = VERSION_ENV_VARS.iter().fold(
+        context.fingerprint().modified(binary).cwd(),
+        |fingerprint, name| fingerprint.env(name),
+    );
+    let fingerprint = context
+        .current_dir
+        .ancestors()
+        .flat_map(|dir| PROJECT_VERSION_FILES.iter().map(move |file| dir.join(file)))
+        .filter(|file| file.exists())
+        .fold(fingerprint, |fingerprint, file| fingerprint.modified(&file));
+    let fingerprint = global_version_files(context)
+        .iter()
+        .fold(fingerprint, |fingerprint, file| fingerprint.modified(file));
+    let key = args
+        .iter()
+        .fold(fingerprint, |fingerprint, arg| fingerprint.text(arg))
+        .finish();
+    Some(context.get_cache_dir()?.join("versions").join(key))
+}
+
+/// Lists the global version files, like the default Node.js of Volta or the global
+/// `.tool-versions` of asdf
+fn global_version_files(context: &Context) -> Vec<PathBuf> {
+    let home = context.get_home();
+    let mut files: Vec<PathBuf> = home
+        .iter()
+        .map(|home| home.join(".tool-versions"))
+        .collect();
+    files.extend(
+        GLOBAL_VERSION_FILES
+            .iter()
+            .filter_map(|(root_var, default_root, file)| {
+                let root = match context.get_env(root_var) {
+                    Some(root) => PathBuf::from(root),
+                    None => home.as_ref()?.join(default_root),
+                };
+                Some(root.join(file))
+            }),
+    );
+    files
+}
+
+/// Returns the file caching the output of running `cmd` with `args`, which is checked against
+/// the files it watches instead of the binary, see [`write_watching`]
+///
+/// The binary isn't looked up in `PATH`, so the name only depends on `PATH` itself.
+pub fn watching_file(context: &Context, cmd: &str, args: &[String]) -> Option<PathBuf> {
+    let key = args
+        .iter()
+        .fold(
+            context.fingerprint().env("PATH").text(cmd),
+            |fingerprint, arg| fingerprint.text(arg),
+        )
+        .finish();
+    Some(context.get_cache_dir()?.join("versions").join(key))
+}
+
+pub fn read(file: &Path) -> Option<CommandOutput> {
+    parse_output(&read_json(file)?)
+}
+
+pub fn write(file: &Path, output: &CommandOutput) -> io::Result<()> {
+    write_json(
+        file,
+        serde_json::json!({
+            "stdout": output.stdout,
+            "stderr": output.stderr,
+        }),
+    )
+}
+
+/// Reads an output cached by [`write_watching`], while none of the files it watches changed
+pub fn read_watching(context: &Context, file: &Path) -> Option<CommandOutput> {
+    let cached = read_json(file)?;
+    let watched = cached
+        .get("watched")?
+        .as_array()?
+        .iter()
+        .map(|path| path.as_str().map(PathBuf::from))
+        .collect::<Option<Vec<_>>>()?;
+    if cached.get("modified")?.as_str()? != modified(context, &watched) {
+        return None;
+    }
+    parse_output(&cached)
+}
+
+/// Caches an output until one of the `watched` files or directories changes
+pub fn write_watching(
+    context: &Context,
+    file: &Path,
+    output: &CommandOutput,
+    watched: &[PathBuf],
+) -> io::Result<()> {
+    write_json(
+        file,
+        serde_json::json!({
+            "stdout": output.stdout,
+            "stderr": output.stderr,
+            "watched": watched,
+            "modified": modified(context, watched),
+        }),
+    )
+}
+
+fn modified(context: &Context, watched: &[PathBuf]) -> String {
+    watched
+        .iter()
+        .fold(context.fingerprint(), |fingerprint, path| {
+            fingerprint.modified(path)
+        })
+        .finish()
+}
+
+fn read_json(file: &Path) -> Option<serde_json::Value> {
+    let contents = fs::read_to_string(file).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn parse_output(cached: &serde_json::Value) -> Option<CommandOutput> {
+    Some(CommandOutput {
+        stdout: cached.get("stdout")?.as_str()?.to_string(),
+        stderr: cached.get("stderr")?.as_str()?.to_string(),
+    })
+}
+
+fn write_json(file: &Path, cached: serde_json::Value) -> io::Result<()> {
+    if let Some(dir) = file.parent() {
+        fs::create_dir_all(dir)?;
+        if let Err(error) = remove_stale(dir, SystemTime::now()) {
+            log::debug!(
+                "Unable to remove stale versions in {}: {error}",
+                dir.display()
+            );
+        }
+    }
+    fs::write(file, cached.to_string())
+}
+
+/// Removes the cached outputs that were not written since `MAX_AGE`
+///
+/// Reading an entry doesn't keep it, an evicted entry is written again on the next miss.
+fn remove_stale(dir: &Path, now: SystemTime) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let is_stale = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .map_or(false, |age| age > MAX_AGE);
+        if is_stale {
+            fs::remove_file(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::default_context;
+    use std::fs::File;
+
+    fn context(cache_dir: &Path) -> Context<'static> {
+        let mut context = default_context();
+        context
+            .env
+            .insert("STARSHIP_CACHE", cache_dir.to_string_lossy().to_string());
+        context
+    }
+
+    #[test]
+    fn roundtrip() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let binary = dir.path().join("node");
+        File::create(&binary)?.sync_all()?;
+        let args = vec!["--version".to_string()];
+
+        let file = cache_file(&context(dir.path()), &binary, &args).unwrap();
+        assert!(read(&file).is_none());
+
+        let output = CommandOutput {
+            stdout: "v20.11.0\n".to_string(),
+            stderr: String::new(),
+        };
+        write(&file, &output)?;
+        let cached = read(&file).unwrap();
+        assert_eq!(cached.stdout, output.stdout);
+        assert_eq!(cached.stderr, output.stderr);
+        dir.close()
+    }
+
+    #[test]
+    fn watched_files() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let context = context(dir.path());
+        let sdk = dir.path().join("sdk");
+        fs::create_dir(&sdk)?;
+        let args = vec!["--list-sdks".to_string()];
+
+        let file = watching_file(&context, "dotnet", &args).unwrap();
+        assert!(read_watching(&context, &file).is_none());
+
+        let output = CommandOutput {
+            stdout: format!("8.0.301 [{}]\n", sdk.display()),
+            stderr: String::new(),
+        };
+        write_watching(&context, &file, &output, &[sdk.clone()])?;
+        let cached = read_watching(&context, &file).unwrap();
+        assert_eq!(cached.stdout, output.stdout);
+
+        fs::remove_dir(&sdk)?;
+        assert!(read_watching(&context, &file).is_none());
+        dir.close()
+    }
+
+    #[test]
+    fn keys() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let binary = dir.path().join("java");
+        File::create(&binary)?.sync_all()?;
+        let context = context(dir.path());
+        let key = |args: &[&str]| {
+            let args: Vec<String> = args.iter().map(ToString::to_string).collect();
+            cache_file(&context, &binary, &args)
+        };
+
+        assert_eq!(key(&["-version"]), key(&["-version"]));
+        assert_ne!(key(&["-version"]), key(&["-Xinternalversion"]));
+        assert_ne!(key(&["a", "b"]), key(&["ab"]));
+        assert_eq!(cache_file(&context, &dir.path().join("missing"), &[]), None);
+
+        // Tools like `go` switch versions with the version files of the project
+        let mut project_context = self::context(dir.path());
+        project_context.current_dir = dir.path().to_path_buf();
+        project_context.logical_dir = dir.path().to_path_buf();
+        let in_project = cache_file(&project_context, &binary, &[]);
+        assert_ne!(cache_file(&context, &binary, &[]), in_project);
+        fs::write(
+            dir.path().join("go.mod"),
+            "module example.com/app\n\ngo 1.22\n",
+        )?;
+        assert_ne!(cache_file(&project_context, &binary, &[]), in_project);
+        project_context
+            .env
+            .insert("GOTOOLCHAIN", "go1.23.0".to_string());
+        assert_ne!(cache_file(&project_context, &binary, &[]), in_project);
+
+        let shim = dir.path().join("shims").join("python");
+        fs::create_dir(dir.path().join("shims"))?;
+        File::create(&shim)?.sync_all()?;
+        assert_eq!(cache_file(&context, &shim, &[]), None);
+        dir.close()
+    }
+
+    #[test]
+    fn global_version_files_change_keys() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let binary = dir.path().join("node");
+        File::create(&binary)?.sync_all()?;
+        let volta_home = dir.path().join("volta");
+        let mut context = context(dir.path());
+        context
+            .env
+            .insert("VOLTA_HOME", volta_home.to_string_lossy().to_string());
+
+        let before = cache_file(&context, &binary, &[]);
+        fs::create_dir_all(volta_home.join("tools").join("user"))?;
+        fs::write(
+            volta_home.join("tools").join("user").join("platform.json"),
+            r#"{"node":{"runtime":"20.11.0"}}"#,
+        )?;
+        assert_ne!(cache_file(&context, &binary, &[]), before);
+        dir.close()
+    }
+
+    #[test]
+    fn stale_entries_are_removed() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("versions").join("entry");
+        write(
+            &file,
+            &CommandOutput {
+                stdout: "1.0.0\n".to_string(),
+                stderr: String::new(),
+            },
+        )?;
+
+        remove_stale(file.parent().unwrap(), SystemTime::now())?;
+        assert!(file.exists());
+
+        let later = SystemTime::now() + MAX_AGE + Duration::from_secs(60);
+        remove_stale(file.parent().unwrap(), later)?;
+        assert!(!file.exists());
+        dir.close()
+    }
+}